@@ -38,57 +38,77 @@ describe("Lightning routes tests", () => {
     );
 
     it(
-        "create-herc20-ethereum-erc20-halight-lightning-bitcoin-returns-route-not-supported",
+        "create-herc20-ethereum-erc20-halight-lightning-bitcoin-returns-bad-request",
         twoActorTest(async ({ alice, bob }) => {
             const bodies = (await SwapFactory.newSwap(alice, bob, true))
                 .herc20EthereumErc20HalightLightningBitcoin;
-            await expect(
-                alice.cnd.createHerc20EthereumErc20HalightLightningBitcoin(
-                    bodies.alice
-                )
-            ).rejects.toThrow("Route not yet supported.");
-            await expect(
-                bob.cnd.createHerc20EthereumErc20HalightLightningBitcoin(
-                    bodies.bob
-                )
-            ).rejects.toThrow("Route not yet supported.");
+
+            const aliceResponse = alice.cnd.createHerc20EthereumErc20HalightLightningBitcoin(
+                bodies.alice
+            );
+            const bobResponse = bob.cnd.createHerc20EthereumErc20HalightLightningBitcoin(
+                bodies.bob
+            );
+
+            const expectedProblem = {
+                status: 400,
+                title: "lightning is not configured.",
+                detail:
+                    "lightning ledger is not properly configured, swap involving this ledger are not available.",
+            };
+
+            await expect(aliceResponse).rejects.toMatchObject(expectedProblem);
+            await expect(bobResponse).rejects.toMatchObject(expectedProblem);
         })
     );
 
     it(
-        "create-halight-lightning-bitcoin-han-ethereum-ether-returns-route-not-supported",
+        "create-halight-lightning-bitcoin-han-ethereum-ether-returns-bad-request",
         twoActorTest(async ({ alice, bob }) => {
             const bodies = (await SwapFactory.newSwap(alice, bob, true))
                 .halightLightningBitcoinHanEthereumEther;
-            await expect(
-                alice.cnd.createHalightLightningBitcoinHanEthereumEther(
-                    bodies.alice
-                )
-            ).rejects.toThrow("Route not yet supported.");
-            await expect(
-                bob.cnd.createHalightLightningBitcoinHanEthereumEther(
-                    bodies.bob
-                )
-            ).rejects.toThrow("Route not yet supported.");
+
+            const aliceResponse = alice.cnd.createHalightLightningBitcoinHanEthereumEther(
+                bodies.alice
+            );
+            const bobResponse = bob.cnd.createHalightLightningBitcoinHanEthereumEther(
+                bodies.bob
+            );
+
+            const expectedProblem = {
+                status: 400,
+                title: "lightning is not configured.",
+                detail:
+                    "lightning ledger is not properly configured, swap involving this ledger are not available.",
+            };
+
+            await expect(aliceResponse).rejects.toMatchObject(expectedProblem);
+            await expect(bobResponse).rejects.toMatchObject(expectedProblem);
         })
     );
 
     it(
-        "create-halight-lightning-bitcoin-herc20-ethereum-erc20-returns-route-not-supported",
+        "create-halight-lightning-bitcoin-herc20-ethereum-erc20-returns-bad-request",
         twoActorTest(async ({ alice, bob }) => {
             const bodies = (await SwapFactory.newSwap(alice, bob, true))
                 .halightLightningBitcoinHerc20EthereumErc20;
 
-            await expect(
-                alice.cnd.createHalightLightningBitcoinHerc20EthereumErc20(
-                    bodies.alice
-                )
-            ).rejects.toThrow("Route not yet supported.");
-            await expect(
-                bob.cnd.createHalightLightningBitcoinHerc20EthereumErc20(
-                    bodies.bob
-                )
-            ).rejects.toThrow("Route not yet supported.");
+            const aliceResponse = alice.cnd.createHalightLightningBitcoinHerc20EthereumErc20(
+                bodies.alice
+            );
+            const bobResponse = bob.cnd.createHalightLightningBitcoinHerc20EthereumErc20(
+                bodies.bob
+            );
+
+            const expectedProblem = {
+                status: 400,
+                title: "lightning is not configured.",
+                detail:
+                    "lightning ledger is not properly configured, swap involving this ledger are not available.",
+            };
+
+            await expect(aliceResponse).rejects.toMatchObject(expectedProblem);
+            await expect(bobResponse).rejects.toMatchObject(expectedProblem);
         })
     );
 
@@ -0,0 +1,6 @@
+-- This file should undo anything in `up.sql`
+
+DROP TABLE halights;
+DROP TABLE hans;
+DROP TABLE finalized_swaps;
+DROP TABLE swaps;
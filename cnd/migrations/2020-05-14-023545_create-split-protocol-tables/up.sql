@@ -0,0 +1,45 @@
+-- Your SQL goes here
+
+CREATE TABLE swaps
+(
+    id INTEGER            NOT NULL PRIMARY KEY,
+    local_swap_id UNIQUE  NOT NULL,
+    role                  NOT NULL,
+    counterparty_peer_id  NOT NULL
+);
+
+CREATE TABLE finalized_swaps
+(
+    id INTEGER             NOT NULL PRIMARY KEY,
+    swap_id INTEGER UNIQUE NOT NULL,
+    shared_swap_id UNIQUE  NOT NULL,
+    secret_hash            NOT NULL,
+    at DATETIME            NOT NULL,
+    FOREIGN KEY(swap_id)   REFERENCES swaps(id)
+);
+
+CREATE TABLE hans
+(
+    id INTEGER             NOT NULL PRIMARY KEY,
+    swap_id INTEGER UNIQUE NOT NULL,
+    amount                 NOT NULL,
+    chain_id               NOT NULL,
+    expiry                 NOT NULL,
+    redeem_identity,
+    refund_identity,
+    ledger                 NOT NULL,
+    FOREIGN KEY(swap_id)   REFERENCES swaps(id)
+);
+
+CREATE TABLE halights
+(
+    id INTEGER             NOT NULL PRIMARY KEY,
+    swap_id INTEGER UNIQUE NOT NULL,
+    amount                 NOT NULL,
+    network                NOT NULL,
+    cltv_expiry            NOT NULL,
+    redeem_identity,
+    refund_identity,
+    ledger                 NOT NULL,
+    FOREIGN KEY(swap_id)   REFERENCES swaps(id)
+);
@@ -0,0 +1,15 @@
+-- Your SQL goes here
+
+CREATE TABLE herc20s
+(
+    id INTEGER             NOT NULL PRIMARY KEY,
+    swap_id INTEGER UNIQUE NOT NULL,
+    amount                 NOT NULL,
+    chain_id               NOT NULL,
+    expiry                 NOT NULL,
+    token_contract         NOT NULL,
+    redeem_identity,
+    refund_identity,
+    ledger                 NOT NULL,
+    FOREIGN KEY(swap_id)   REFERENCES swaps(id)
+);
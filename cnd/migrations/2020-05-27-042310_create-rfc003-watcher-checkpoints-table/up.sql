@@ -0,0 +1,13 @@
+-- Your SQL goes here
+
+CREATE TABLE rfc003_watcher_checkpoints
+(
+    id INTEGER             NOT NULL PRIMARY KEY,
+    swap_id                NOT NULL,
+    ledger                 NOT NULL,
+    block_hash,
+    block_timestamp DATETIME,
+    deployed,
+    funded,
+    UNIQUE(swap_id, ledger)
+);
@@ -0,0 +1,10 @@
+-- Your SQL goes here
+
+CREATE TABLE autopilot_actions
+(
+    id INTEGER               NOT NULL PRIMARY KEY,
+    swap_id                  NOT NULL,
+    action                   NOT NULL,
+    executed_at DATETIME     NOT NULL,
+    UNIQUE(swap_id, action)
+);
@@ -0,0 +1,17 @@
+-- This file should undo anything in `up.sql`
+
+CREATE TABLE rfc003_swaps_without_status
+(
+    id INTEGER     	NOT NULL PRIMARY KEY,
+    swap_id UNIQUE 	NOT NULL,
+    role 		NOT NULL,
+    counterparty 	NOT NULL
+);
+
+INSERT INTO rfc003_swaps_without_status
+SELECT id, swap_id, role, counterparty
+FROM rfc003_swaps;
+
+DROP TABLE rfc003_swaps;
+
+ALTER TABLE rfc003_swaps_without_status RENAME TO rfc003_swaps;
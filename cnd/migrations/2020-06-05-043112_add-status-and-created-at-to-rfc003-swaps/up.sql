@@ -0,0 +1,14 @@
+-- Your SQL goes here
+
+ALTER TABLE rfc003_swaps ADD COLUMN status NOT NULL DEFAULT 'in_progress';
+
+-- SQLite does not allow a non-constant default when adding a column, the
+-- creation time of existing swaps is approximated below.
+ALTER TABLE rfc003_swaps ADD COLUMN created_at DATETIME NOT NULL DEFAULT '1970-01-01 00:00:00';
+
+UPDATE rfc003_swaps
+SET created_at = COALESCE(
+    (SELECT at FROM rfc003_bitcoin_ethereum_accept_messages WHERE swap_id = rfc003_swaps.swap_id),
+    (SELECT at FROM rfc003_ethereum_bitcoin_accept_messages WHERE swap_id = rfc003_swaps.swap_id),
+    CURRENT_TIMESTAMP
+);
@@ -0,0 +1,17 @@
+-- This file should undo anything in `up.sql`
+
+CREATE TABLE swaps_without_status
+(
+    id INTEGER            NOT NULL PRIMARY KEY,
+    local_swap_id UNIQUE  NOT NULL,
+    role                  NOT NULL,
+    counterparty_peer_id  NOT NULL
+);
+
+INSERT INTO swaps_without_status
+SELECT id, local_swap_id, role, counterparty_peer_id
+FROM swaps;
+
+DROP TABLE swaps;
+
+ALTER TABLE swaps_without_status RENAME TO swaps;
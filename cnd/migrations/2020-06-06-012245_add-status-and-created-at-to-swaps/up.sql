@@ -0,0 +1,13 @@
+-- Your SQL goes here
+
+ALTER TABLE swaps ADD COLUMN status NOT NULL DEFAULT 'in_progress';
+
+-- SQLite does not allow a non-constant default when adding a column, the
+-- creation time of existing swaps is approximated below.
+ALTER TABLE swaps ADD COLUMN created_at DATETIME NOT NULL DEFAULT '1970-01-01 00:00:00';
+
+UPDATE swaps
+SET created_at = COALESCE(
+    (SELECT at FROM finalized_swaps WHERE swap_id = swaps.id),
+    CURRENT_TIMESTAMP
+);
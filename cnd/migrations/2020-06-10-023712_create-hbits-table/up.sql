@@ -0,0 +1,14 @@
+-- Your SQL goes here
+
+CREATE TABLE hbits
+(
+    id INTEGER             NOT NULL PRIMARY KEY,
+    swap_id INTEGER UNIQUE NOT NULL,
+    amount                 NOT NULL,
+    network                NOT NULL,
+    expiry                 NOT NULL,
+    redeem_identity,
+    refund_identity,
+    ledger                 NOT NULL,
+    FOREIGN KEY(swap_id)   REFERENCES swaps(id)
+);
@@ -0,0 +1,22 @@
+-- This file should undo anything in `up.sql`
+
+CREATE TABLE rfc003_watcher_checkpoints_without_block_height
+(
+    id INTEGER             NOT NULL PRIMARY KEY,
+    swap_id                NOT NULL,
+    ledger                 NOT NULL,
+    block_hash,
+    block_timestamp DATETIME,
+    deployed,
+    funded,
+    start_height,
+    UNIQUE(swap_id, ledger)
+);
+
+INSERT INTO rfc003_watcher_checkpoints_without_block_height
+SELECT id, swap_id, ledger, block_hash, block_timestamp, deployed, funded, start_height
+FROM rfc003_watcher_checkpoints;
+
+DROP TABLE rfc003_watcher_checkpoints;
+
+ALTER TABLE rfc003_watcher_checkpoints_without_block_height RENAME TO rfc003_watcher_checkpoints;
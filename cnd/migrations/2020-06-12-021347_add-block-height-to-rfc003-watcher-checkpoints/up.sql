@@ -0,0 +1,3 @@
+-- Your SQL goes here
+
+ALTER TABLE rfc003_watcher_checkpoints ADD COLUMN block_height;
@@ -0,0 +1,10 @@
+-- Your SQL goes here
+
+CREATE TABLE swap_watcher_checkpoints
+(
+    id INTEGER             NOT NULL PRIMARY KEY,
+    swap_id                NOT NULL,
+    ledger                 NOT NULL,
+    start_height,
+    UNIQUE(swap_id, ledger)
+);
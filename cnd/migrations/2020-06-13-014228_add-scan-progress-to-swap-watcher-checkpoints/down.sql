@@ -0,0 +1,18 @@
+-- This file should undo anything in `up.sql`
+
+CREATE TABLE swap_watcher_checkpoints_without_scan_progress
+(
+    id INTEGER             NOT NULL PRIMARY KEY,
+    swap_id                NOT NULL,
+    ledger                 NOT NULL,
+    start_height,
+    UNIQUE(swap_id, ledger)
+);
+
+INSERT INTO swap_watcher_checkpoints_without_scan_progress
+SELECT id, swap_id, ledger, start_height
+FROM swap_watcher_checkpoints;
+
+DROP TABLE swap_watcher_checkpoints;
+
+ALTER TABLE swap_watcher_checkpoints_without_scan_progress RENAME TO swap_watcher_checkpoints;
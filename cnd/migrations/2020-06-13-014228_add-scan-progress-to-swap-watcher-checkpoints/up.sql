@@ -0,0 +1,7 @@
+-- Your SQL goes here
+
+ALTER TABLE swap_watcher_checkpoints ADD COLUMN block_hash;
+ALTER TABLE swap_watcher_checkpoints ADD COLUMN block_height;
+ALTER TABLE swap_watcher_checkpoints ADD COLUMN block_timestamp DATETIME;
+ALTER TABLE swap_watcher_checkpoints ADD COLUMN deployed;
+ALTER TABLE swap_watcher_checkpoints ADD COLUMN funded;
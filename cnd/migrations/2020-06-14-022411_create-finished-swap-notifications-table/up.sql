@@ -0,0 +1,7 @@
+-- Your SQL goes here
+
+CREATE TABLE finished_swap_notifications
+(
+    id INTEGER                   NOT NULL PRIMARY KEY,
+    swap_id                      NOT NULL UNIQUE
+);
@@ -0,0 +1,12 @@
+-- This file should undo anything in `up.sql`
+
+CREATE TABLE finished_swap_notifications
+(
+    id INTEGER                   NOT NULL PRIMARY KEY,
+    swap_id                      NOT NULL UNIQUE
+);
+
+INSERT INTO finished_swap_notifications (swap_id)
+SELECT swap_id FROM queued_webhook_notifications WHERE event = 'swap_finished';
+
+DROP TABLE queued_webhook_notifications;
@@ -0,0 +1,14 @@
+-- Your SQL goes here
+
+CREATE TABLE queued_webhook_notifications
+(
+    id INTEGER                   NOT NULL PRIMARY KEY,
+    swap_id                      NOT NULL,
+    event                        NOT NULL,
+    UNIQUE (swap_id, event)
+);
+
+INSERT INTO queued_webhook_notifications (swap_id, event)
+SELECT swap_id, 'swap_finished' FROM finished_swap_notifications;
+
+DROP TABLE finished_swap_notifications;
@@ -1,4 +1,5 @@
 use bitcoin::{util::amount::Denomination, Amount};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
 use std::fmt;
 
 #[derive(Debug, Clone, Copy, PartialOrd, Ord, PartialEq, Eq, Hash)]
@@ -24,6 +25,26 @@ impl From<Bitcoin> for Amount {
     }
 }
 
+impl Serialize for Bitcoin {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serializer.serialize_u64(self.as_sat())
+    }
+}
+
+impl<'de> Deserialize<'de> for Bitcoin {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let sat = u64::deserialize(deserializer)?;
+
+        Ok(Bitcoin::from_sat(sat))
+    }
+}
+
 impl fmt::Display for Bitcoin {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> Result<(), fmt::Error> {
         let bitcoin = self.0.to_string_in(Denomination::Bitcoin);
@@ -0,0 +1,290 @@
+//! Lets cnd execute the actions of swaps itself.
+//!
+//! Once enabled, the autopilot periodically looks at the actions that are
+//! available for each swap and executes `deploy`, `fund`, `redeem` and
+//! `refund` using hot wallets cnd controls. Actions on ledgers for which no
+//! wallet is configured are left to the user.
+mod bitcoin;
+mod ethereum;
+mod lnd;
+mod rfc003;
+mod split_protocols;
+
+pub use self::{bitcoin::BitcoindWallet, ethereum::EthereumWallet, lnd::LndWallet};
+
+use crate::{
+    config::Settings,
+    db::{AutopilotActions, Sqlite},
+    swap_protocols::{rfc003::actions::ActionKind, Facade, Rfc003Facade},
+};
+use async_trait::async_trait;
+use chrono::Utc;
+use std::{convert::Infallible, time::Duration};
+use uuid::Uuid;
+
+/// How often we look for actions to execute.
+const POLL_INTERVAL: Duration = Duration::from_secs(15);
+
+/// Redeem and refund transactions may be dropped, e.g. because their fee was
+/// too low. If the action is still available after this many minutes, we
+/// execute it again.
+///
+/// Deploy and fund actions are never executed twice, the second transaction
+/// would lock up more of the user's funds.
+const RETRY_INTERVAL_MINUTES: i64 = 30;
+
+/// Failures of the autopilot for which we know that no transaction was
+/// broadcast, the action can safely be attempted again.
+#[derive(Debug, Clone, Copy, thiserror::Error)]
+pub enum Error {
+    #[error("no {0} wallet is configured")]
+    NoWallet(&'static str),
+    #[error("action cannot be executed yet")]
+    TooEarly,
+}
+
+/// Executes an action using the wallets of the autopilot.
+#[async_trait]
+pub trait Execute<A> {
+    async fn execute(&self, action: A) -> anyhow::Result<()>;
+}
+
+#[derive(Debug)]
+pub struct Autopilot {
+    bitcoin: Option<BitcoindWallet>,
+    ethereum: Option<EthereumWallet>,
+    lightning: Option<LndWallet>,
+    db: Sqlite,
+}
+
+impl Autopilot {
+    /// Returns `None` if the autopilot is disabled.
+    pub fn new(settings: &Settings, db: Sqlite) -> anyhow::Result<Option<Self>> {
+        let autopilot = match &settings.autopilot {
+            Some(autopilot) => autopilot,
+            None => return Ok(None),
+        };
+
+        let bitcoin = autopilot
+            .bitcoin
+            .as_ref()
+            .map(|bitcoin| BitcoindWallet::new(&settings.bitcoin.bitcoind, bitcoin))
+            .transpose()?;
+        let ethereum = autopilot
+            .ethereum
+            .as_ref()
+            .map(|ethereum| EthereumWallet::new(&settings.ethereum.parity, ethereum))
+            .transpose()?;
+        let lightning = autopilot
+            .lightning
+            .as_ref()
+            .map(|lightning| LndWallet::new(&settings.lightning.lnd, lightning))
+            .transpose()?;
+
+        Ok(Some(Self {
+            bitcoin,
+            ethereum,
+            lightning,
+            db,
+        }))
+    }
+
+    /// Executes the action unless it was attempted before.
+    ///
+    /// The attempt is recorded before executing the action. If the action
+    /// fails with an `Error`, nothing was broadcast and the attempt is undone
+    /// so that it is executed the next time we look at the swap. Any other
+    /// failure may have happened after broadcasting, the action is treated as
+    /// attempted.
+    async fn act<A>(&self, swap_id: Uuid, kind: ActionKind, action: A) -> anyhow::Result<()>
+    where
+        Self: Execute<A>,
+    {
+        let now = Utc::now().naive_local();
+        let retry_after = match kind {
+            ActionKind::Redeem | ActionKind::Refund => {
+                Some(chrono::Duration::minutes(RETRY_INTERVAL_MINUTES))
+            }
+            _ => None,
+        };
+
+        let claim = match self
+            .db
+            .claim_action(swap_id, kind, now, retry_after)
+            .await?
+        {
+            Some(claim) => claim,
+            None => return Ok(()),
+        };
+
+        match self.execute(action).await {
+            Ok(()) => tracing::info!("autopilot executed {} action of swap {}", kind, swap_id),
+            Err(e) => match e.downcast_ref::<Error>() {
+                Some(error) => {
+                    tracing::debug!(
+                        "autopilot did not execute {} action of swap {}: {}",
+                        kind,
+                        swap_id,
+                        error
+                    );
+                    self.db.release_action(claim).await?;
+                }
+                None => tracing::warn!(
+                    "autopilot failed to execute {} action of swap {}, not attempting it again before the retry interval passed: {:?}",
+                    kind,
+                    swap_id,
+                    e
+                ),
+            },
+        }
+
+        Ok(())
+    }
+}
+
+/// Some protocol combinations don't have all actions.
+#[async_trait]
+impl Execute<Infallible> for Autopilot {
+    async fn execute(&self, action: Infallible) -> anyhow::Result<()> {
+        match action {}
+    }
+}
+
+/// Looks for actions to execute until cnd is shut down.
+pub async fn run(autopilot: Autopilot, rfc003_facade: Rfc003Facade, facade: Facade) {
+    tracing::info!("autopilot is enabled");
+
+    loop {
+        if let Err(e) = rfc003::act_on_swaps(&autopilot, &rfc003_facade).await {
+            tracing::warn!("autopilot failed to look at rfc003 swaps: {:?}", e);
+        }
+        if let Err(e) = split_protocols::act_on_swaps(&autopilot, &facade).await {
+            tracing::warn!("autopilot failed to look at swaps: {:?}", e);
+        }
+
+        tokio::time::delay_for(POLL_INTERVAL).await;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::swap_protocols::LocalSwapId;
+    use std::{
+        path::Path,
+        sync::{
+            atomic::{AtomicUsize, Ordering},
+            Arc,
+        },
+    };
+
+    /// Broadcasts the transaction of the action but fails before learning
+    /// whether it was accepted, e.g. because the connection was closed.
+    struct FailsAfterBroadcast(Arc<AtomicUsize>);
+
+    /// Fails before anything is broadcast.
+    struct FailsBeforeBroadcast(Arc<AtomicUsize>);
+
+    #[async_trait]
+    impl Execute<FailsAfterBroadcast> for Autopilot {
+        async fn execute(&self, action: FailsAfterBroadcast) -> anyhow::Result<()> {
+            action.0.fetch_add(1, Ordering::SeqCst);
+
+            Err(anyhow::anyhow!(
+                "connection closed before receiving a response"
+            ))
+        }
+    }
+
+    #[async_trait]
+    impl Execute<FailsBeforeBroadcast> for Autopilot {
+        async fn execute(&self, action: FailsBeforeBroadcast) -> anyhow::Result<()> {
+            action.0.fetch_add(1, Ordering::SeqCst);
+
+            Err(Error::TooEarly.into())
+        }
+    }
+
+    fn autopilot() -> Autopilot {
+        Autopilot {
+            bitcoin: None,
+            ethereum: None,
+            lightning: None,
+            db: Sqlite::new(&Path::new(":memory:")).unwrap(),
+        }
+    }
+
+    #[tokio::test]
+    async fn does_not_fund_again_after_a_failure_after_broadcasting() {
+        let autopilot = autopilot();
+        let swap_id = LocalSwapId::default().into();
+        let broadcasts = Arc::new(AtomicUsize::new(0));
+
+        for _ in 0..2 {
+            autopilot
+                .act(
+                    swap_id,
+                    ActionKind::Fund,
+                    FailsAfterBroadcast(Arc::clone(&broadcasts)),
+                )
+                .await
+                .unwrap();
+        }
+
+        assert_eq!(broadcasts.load(Ordering::SeqCst), 1);
+        assert!(autopilot
+            .db
+            .load_attempted_at(swap_id, ActionKind::Fund)
+            .await
+            .unwrap()
+            .is_some());
+    }
+
+    #[tokio::test]
+    async fn redeem_is_not_attempted_again_within_the_retry_interval() {
+        let autopilot = autopilot();
+        let swap_id = LocalSwapId::default().into();
+        let broadcasts = Arc::new(AtomicUsize::new(0));
+
+        for _ in 0..2 {
+            autopilot
+                .act(
+                    swap_id,
+                    ActionKind::Redeem,
+                    FailsAfterBroadcast(Arc::clone(&broadcasts)),
+                )
+                .await
+                .unwrap();
+        }
+
+        assert_eq!(broadcasts.load(Ordering::SeqCst), 1);
+    }
+
+    #[tokio::test]
+    async fn fund_is_attempted_again_if_nothing_was_broadcast() {
+        let autopilot = autopilot();
+        let swap_id = LocalSwapId::default().into();
+        let attempts = Arc::new(AtomicUsize::new(0));
+
+        for _ in 0..2 {
+            autopilot
+                .act(
+                    swap_id,
+                    ActionKind::Fund,
+                    FailsBeforeBroadcast(Arc::clone(&attempts)),
+                )
+                .await
+                .unwrap();
+        }
+
+        assert_eq!(attempts.load(Ordering::SeqCst), 2);
+        assert_eq!(
+            autopilot
+                .db
+                .load_attempted_at(swap_id, ActionKind::Fund)
+                .await
+                .unwrap(),
+            None
+        );
+    }
+}
@@ -0,0 +1,106 @@
+use crate::{
+    autopilot::{Autopilot, Error, Execute},
+    btsieve::bitcoin::rpc_client,
+    config::{AutopilotBitcoin, Bitcoind},
+    jsonrpc,
+    swap_protocols::actions::bitcoin::{SendToAddress, SpendOutput},
+};
+use anyhow::Context;
+use async_trait::async_trait;
+use bitcoin::{util::amount::Denomination, Address, Amount, Transaction, Txid};
+
+/// A wallet of the bitcoind node cnd is connected to.
+#[derive(Clone, Debug)]
+pub struct BitcoindWallet {
+    client: jsonrpc::Client,
+    fee_per_wu: usize,
+}
+
+impl BitcoindWallet {
+    pub fn new(bitcoind: &Bitcoind, config: &AutopilotBitcoin) -> anyhow::Result<Self> {
+        let rpc_auth = bitcoind
+            .rpc_auth
+            .as_ref()
+            .context("the bitcoin autopilot requires bitcoind rpc credentials")?;
+        let wallet_url = match &config.wallet {
+            Some(wallet) => bitcoind.node_url.join(&format!("wallet/{}", wallet))?,
+            None => bitcoind.node_url.clone(),
+        };
+
+        Ok(Self {
+            client: rpc_client(wallet_url, rpc_auth)?,
+            fee_per_wu: config.fee_per_wu,
+        })
+    }
+
+    async fn send_to_address(&self, to: &Address, amount: Amount) -> anyhow::Result<Txid> {
+        let txid = self
+            .client
+            .send(jsonrpc::Request::new("sendtoaddress", vec![
+                jsonrpc::serialize(to)?,
+                jsonrpc::serialize(amount.to_string_in(Denomination::Bitcoin))?,
+            ]))
+            .await?;
+
+        Ok(txid)
+    }
+
+    async fn new_address(&self) -> anyhow::Result<Address> {
+        let address = self
+            .client
+            .send::<Vec<()>, _>(jsonrpc::Request::new("getnewaddress", vec![]))
+            .await?;
+
+        Ok(address)
+    }
+
+    async fn send_raw_transaction(&self, transaction: &Transaction) -> anyhow::Result<Txid> {
+        let result = self
+            .client
+            .send(jsonrpc::Request::new("sendrawtransaction", vec![
+                jsonrpc::serialize(bitcoin::consensus::encode::serialize_hex(transaction))?,
+            ]))
+            .await;
+
+        match result {
+            Ok(txid) => Ok(txid),
+            // bitcoind refuses transactions whose lock time has not passed yet.
+            Err(jsonrpc::Error::JsonRpc { message, .. }) if message.contains("non-final") => {
+                Err(Error::TooEarly.into())
+            }
+            Err(e) => Err(e.into()),
+        }
+    }
+}
+
+#[async_trait]
+impl Execute<SendToAddress> for Autopilot {
+    async fn execute(&self, action: SendToAddress) -> anyhow::Result<()> {
+        let wallet = self.bitcoin.as_ref().ok_or(Error::NoWallet("bitcoin"))?;
+
+        let txid = wallet
+            .send_to_address(&action.to, action.amount.into())
+            .await?;
+        tracing::debug!("autopilot sent bitcoin to {} in {}", action.to, txid);
+
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl Execute<SpendOutput> for Autopilot {
+    async fn execute(&self, action: SpendOutput) -> anyhow::Result<()> {
+        let wallet = self.bitcoin.as_ref().ok_or(Error::NoWallet("bitcoin"))?;
+
+        let address = wallet.new_address().await?;
+        let transaction = action
+            .spend_to(address)
+            .sign_with_rate(&*crate::SECP, wallet.fee_per_wu)
+            .map_err(|e| anyhow::anyhow!("failed to sign bitcoin transaction: {:?}", e))?;
+
+        let txid = wallet.send_raw_transaction(&transaction).await?;
+        tracing::debug!("autopilot spent htlc output in {}", txid);
+
+        Ok(())
+    }
+}
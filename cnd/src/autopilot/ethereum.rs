@@ -0,0 +1,323 @@
+use crate::{
+    autopilot::{Autopilot, Error, Execute},
+    config::{AutopilotEthereum, Parity},
+    ethereum::{Address, Hash, U256},
+    jsonrpc,
+    swap_protocols::actions::ethereum::{CallContract, DeployContract},
+    timestamp::Timestamp,
+};
+use anyhow::Context;
+use async_trait::async_trait;
+use secp256k1::{Message, PublicKey, SecretKey};
+use std::convert::TryFrom;
+use tiny_keccak::{Hasher, Keccak};
+use tokio::sync::Mutex;
+
+/// An account whose private key cnd has access to.
+///
+/// Transactions are signed locally and submitted through the Ethereum node cnd
+/// is connected to.
+#[derive(derivative::Derivative)]
+#[derivative(Debug)]
+pub struct EthereumWallet {
+    client: jsonrpc::Client,
+    #[derivative(Debug = "ignore")]
+    secret_key: SecretKey,
+    address: Address,
+    gas_price: U256,
+    /// Held while a transaction is being sent so that no two transactions are
+    /// assigned the same nonce.
+    nonce_lock: Mutex<()>,
+}
+
+impl EthereumWallet {
+    pub fn new(parity: &Parity, config: &AutopilotEthereum) -> anyhow::Result<Self> {
+        let private_key = std::fs::read_to_string(&config.private_key_file).with_context(|| {
+            format!(
+                "failed to read private key file {}",
+                config.private_key_file.display()
+            )
+        })?;
+        let private_key = private_key.trim();
+        let private_key = private_key.trim_start_matches("0x");
+        let secret_key = SecretKey::from_slice(&hex::decode(private_key)?)
+            .context("private key file does not contain a valid private key")?;
+
+        Ok(Self {
+            client: jsonrpc::Client::new(parity.node_url.clone()),
+            secret_key,
+            address: address_of(&secret_key),
+            gas_price: U256::from(config.gas_price),
+            nonce_lock: Mutex::new(()),
+        })
+    }
+
+    async fn send_transaction(
+        &self,
+        to: Option<Address>,
+        value: U256,
+        data: Vec<u8>,
+        gas_limit: u64,
+        chain_id: u32,
+    ) -> anyhow::Result<Hash> {
+        let _guard = self.nonce_lock.lock().await;
+
+        let nonce: U256 = self
+            .client
+            .send(jsonrpc::Request::new("eth_getTransactionCount", vec![
+                jsonrpc::serialize(self.address)?,
+                jsonrpc::serialize("pending")?,
+            ]))
+            .await?;
+
+        let transaction = UnsignedTransaction {
+            nonce,
+            gas_price: self.gas_price,
+            gas_limit: U256::from(gas_limit),
+            to,
+            value,
+            data,
+            chain_id,
+        };
+        let signed_transaction = transaction.sign(&self.secret_key);
+
+        let transaction_hash = self
+            .client
+            .send(jsonrpc::Request::new("eth_sendRawTransaction", vec![
+                jsonrpc::serialize(format!("0x{}", hex::encode(signed_transaction)))?,
+            ]))
+            .await?;
+
+        Ok(transaction_hash)
+    }
+
+    async fn latest_block_timestamp(&self) -> anyhow::Result<Timestamp> {
+        #[derive(serde::Deserialize)]
+        struct Header {
+            timestamp: U256,
+        }
+
+        let header: Header = self
+            .client
+            .send(jsonrpc::Request::new("eth_getBlockByNumber", vec![
+                jsonrpc::serialize("latest")?,
+                jsonrpc::serialize(false)?,
+            ]))
+            .await?;
+
+        Ok(Timestamp::from(header.timestamp.low_u32()))
+    }
+}
+
+#[async_trait]
+impl Execute<DeployContract> for Autopilot {
+    async fn execute(&self, action: DeployContract) -> anyhow::Result<()> {
+        let wallet = self.ethereum.as_ref().ok_or(Error::NoWallet("ethereum"))?;
+
+        let transaction_hash = wallet
+            .send_transaction(
+                None,
+                action.amount.to_u256(),
+                action.data.0,
+                action.gas_limit,
+                u32::from(action.chain_id),
+            )
+            .await?;
+        tracing::debug!("autopilot deployed contract in {:x}", transaction_hash);
+
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl Execute<CallContract> for Autopilot {
+    async fn execute(&self, action: CallContract) -> anyhow::Result<()> {
+        let wallet = self.ethereum.as_ref().ok_or(Error::NoWallet("ethereum"))?;
+
+        // Calling the contract too early makes the transaction fail, the gas
+        // would be lost.
+        if let Some(min_block_timestamp) = action.min_block_timestamp {
+            if wallet.latest_block_timestamp().await? < min_block_timestamp {
+                return Err(Error::TooEarly.into());
+            }
+        }
+
+        let transaction_hash = wallet
+            .send_transaction(
+                Some(action.to),
+                U256::zero(),
+                action.data.map(|data| data.0).unwrap_or_default(),
+                action.gas_limit,
+                u32::from(action.chain_id),
+            )
+            .await?;
+        tracing::debug!("autopilot called contract in {:x}", transaction_hash);
+
+        Ok(())
+    }
+}
+
+/// A transaction as defined in EIP-155.
+#[derive(Clone, Debug, PartialEq)]
+struct UnsignedTransaction {
+    nonce: U256,
+    gas_price: U256,
+    gas_limit: U256,
+    /// `None` for transactions that deploy a contract.
+    to: Option<Address>,
+    value: U256,
+    data: Vec<u8>,
+    chain_id: u32,
+}
+
+impl UnsignedTransaction {
+    /// Returns the RLP encoded signed transaction.
+    fn sign(&self, secret_key: &SecretKey) -> Vec<u8> {
+        let hash = keccak256(&self.rlp(
+            &rlp::encode_u64(u64::from(self.chain_id)),
+            &rlp::encode_bytes(&[]),
+            &rlp::encode_bytes(&[]),
+        ));
+        let message = Message::from_slice(&hash).expect("keccak256 hash is 32 bytes");
+
+        let (recovery_id, signature) = crate::SECP
+            .sign_recoverable(&message, secret_key)
+            .serialize_compact();
+        let recovery_id =
+            u64::try_from(recovery_id.to_i32()).expect("recovery id is between 0 and 3");
+        let v = u64::from(self.chain_id) * 2 + 35 + recovery_id;
+
+        self.rlp(
+            &rlp::encode_u64(v),
+            &rlp::encode_uint(&signature[..32]),
+            &rlp::encode_uint(&signature[32..]),
+        )
+    }
+
+    fn rlp(&self, v: &[u8], r: &[u8], s: &[u8]) -> Vec<u8> {
+        let to = match self.to {
+            Some(to) => rlp::encode_bytes(&<[u8; 20]>::from(to)),
+            None => rlp::encode_bytes(&[]),
+        };
+
+        rlp::encode_list(&[
+            &rlp::encode_u256(self.nonce),
+            &rlp::encode_u256(self.gas_price),
+            &rlp::encode_u256(self.gas_limit),
+            &to,
+            &rlp::encode_u256(self.value),
+            &rlp::encode_bytes(&self.data),
+            v,
+            r,
+            s,
+        ])
+    }
+}
+
+fn address_of(secret_key: &SecretKey) -> Address {
+    let public_key = PublicKey::from_secret_key(&*crate::SECP, secret_key);
+    let hash = keccak256(&public_key.serialize_uncompressed()[1..]);
+
+    Address::from_slice(&hash[12..])
+}
+
+fn keccak256(data: &[u8]) -> [u8; 32] {
+    let mut keccak = Keccak::v256();
+    keccak.update(data);
+
+    let mut hash = [0u8; 32];
+    keccak.finalize(&mut hash);
+
+    hash
+}
+
+/// The subset of the recursive length prefix encoding needed for transactions.
+mod rlp {
+    use crate::ethereum::U256;
+    use std::convert::TryFrom;
+
+    pub fn encode_bytes(bytes: &[u8]) -> Vec<u8> {
+        match bytes {
+            [byte] if *byte < 0x80 => vec![*byte],
+            _ => [length_prefix(0x80, bytes.len()), bytes.to_vec()].concat(),
+        }
+    }
+
+    /// Integers are encoded as big endian bytes without leading zeros.
+    pub fn encode_uint(big_endian: &[u8]) -> Vec<u8> {
+        let first_non_zero = big_endian
+            .iter()
+            .position(|byte| *byte != 0)
+            .unwrap_or_else(|| big_endian.len());
+
+        encode_bytes(&big_endian[first_non_zero..])
+    }
+
+    pub fn encode_u64(value: u64) -> Vec<u8> {
+        encode_uint(&value.to_be_bytes())
+    }
+
+    pub fn encode_u256(value: U256) -> Vec<u8> {
+        let mut big_endian = [0u8; 32];
+        value.to_big_endian(&mut big_endian);
+
+        encode_uint(&big_endian)
+    }
+
+    pub fn encode_list(items: &[&[u8]]) -> Vec<u8> {
+        let payload = items.concat();
+
+        [length_prefix(0xc0, payload.len()), payload].concat()
+    }
+
+    fn length_prefix(offset: u8, length: usize) -> Vec<u8> {
+        if length <= 55 {
+            return vec![offset + u8::try_from(length).expect("length is at most 55")];
+        }
+
+        let length = u64::try_from(length)
+            .expect("length fits into 64 bits")
+            .to_be_bytes();
+        let length = &length[length.iter().position(|byte| *byte != 0).unwrap_or(7)..];
+        let length_of_length = u8::try_from(length.len()).expect("length is at most 8 bytes");
+
+        [vec![offset + 55 + length_of_length], length.to_vec()].concat()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn signs_transaction_according_to_eip155() {
+        // Example taken from https://eips.ethereum.org/EIPS/eip-155
+        let transaction = UnsignedTransaction {
+            nonce: U256::from(9),
+            gas_price: U256::from(20_000_000_000u64),
+            gas_limit: U256::from(21000),
+            to: Some(Address::from([0x35; 20])),
+            value: U256::from(1_000_000_000_000_000_000u64),
+            data: vec![],
+            chain_id: 1,
+        };
+        let secret_key = SecretKey::from_slice(&[0x46; 32]).unwrap();
+
+        let signed_transaction = transaction.sign(&secret_key);
+
+        assert_eq!(
+            hex::encode(signed_transaction),
+            "f86c098504a817c800825208943535353535353535353535353535353535353535880de0b6b3a76400008025a028ef61340bd939bc2195fe537567866003e1a15d3c71ff63e1590620aa636276a067cbe9d8997f761aecb703304b3800ccf555c9f3dc64214b297fb1966a3b6d83"
+        );
+    }
+
+    #[test]
+    fn encodes_long_strings_with_length_of_length() {
+        let bytes = [0x61; 56];
+
+        let encoded = rlp::encode_bytes(&bytes);
+
+        assert_eq!(&encoded[..2], &[0xb8, 56]);
+        assert_eq!(&encoded[2..], &bytes[..]);
+    }
+}
@@ -0,0 +1,160 @@
+use crate::{
+    autopilot::{Autopilot, Error, Execute},
+    config::{AutopilotLightning, Lnd},
+    swap_protocols::{
+        actions::lnd::{SendPayment, SettleInvoice},
+        halight::LndConnectorParams,
+    },
+};
+use async_trait::async_trait;
+use reqwest::Url;
+use serde::Serialize;
+
+/// The lnd node cnd is connected to, authenticated with its admin macaroon.
+#[derive(Clone, Debug)]
+pub struct LndWallet {
+    client: reqwest::Client,
+    lnd_url: Url,
+}
+
+impl LndWallet {
+    pub fn new(lnd: &Lnd, config: &AutopilotLightning) -> anyhow::Result<Self> {
+        let params = LndConnectorParams::new(
+            lnd.rest_api_url.clone(),
+            100,
+            lnd.cert_path.clone(),
+            config.admin_macaroon_path.clone(),
+        )?;
+
+        Ok(Self {
+            client: params.client()?,
+            lnd_url: params.url().clone(),
+        })
+    }
+}
+
+/// Body of lnd's `POST /v1/channels/transactions`.
+#[derive(Debug, Serialize)]
+struct SendPaymentRequest {
+    /// Base64 encoded public key of the recipient.
+    dest: String,
+    amt: String,
+    /// Base64 encoded hash the payment is locked with.
+    payment_hash: String,
+    final_cltv_delta: u32,
+}
+
+impl From<&SendPayment> for SendPaymentRequest {
+    fn from(action: &SendPayment) -> Self {
+        Self {
+            dest: base64::encode(action.to_public_key.to_bytes()),
+            amt: action.amount.as_sat().to_string(),
+            payment_hash: base64::encode(action.secret_hash.as_raw()),
+            final_cltv_delta: action.final_cltv_delta,
+        }
+    }
+}
+
+/// Body of lnd's `POST /v2/invoices/settle`.
+#[derive(Debug, Serialize)]
+struct SettleInvoiceRequest {
+    /// Base64 encoded preimage of the invoice's payment hash.
+    preimage: String,
+}
+
+impl From<&SettleInvoice> for SettleInvoiceRequest {
+    fn from(action: &SettleInvoice) -> Self {
+        Self {
+            preimage: base64::encode(action.secret.as_raw_secret()),
+        }
+    }
+}
+
+#[async_trait]
+impl Execute<SendPayment> for Autopilot {
+    async fn execute(&self, action: SendPayment) -> anyhow::Result<()> {
+        let wallet = self
+            .lightning
+            .as_ref()
+            .ok_or(Error::NoWallet("lightning"))?;
+
+        let request = wallet
+            .client
+            .post(wallet.lnd_url.join("/v1/channels/transactions")?)
+            .json(&SendPaymentRequest::from(&action));
+
+        // Paying a hold invoice only returns once the recipient settled or
+        // cancelled it, we learn about either through the halight watcher.
+        let secret_hash = action.secret_hash;
+        tokio::spawn(async move {
+            match request
+                .send()
+                .await
+                .and_then(|response| response.error_for_status())
+            {
+                Ok(_) => tracing::debug!("autopilot payment for {} completed", secret_hash),
+                Err(e) => tracing::warn!("autopilot payment for {} failed: {:?}", secret_hash, e),
+            }
+        });
+
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl Execute<SettleInvoice> for Autopilot {
+    async fn execute(&self, action: SettleInvoice) -> anyhow::Result<()> {
+        let wallet = self
+            .lightning
+            .as_ref()
+            .ok_or(Error::NoWallet("lightning"))?;
+
+        wallet
+            .client
+            .post(wallet.lnd_url.join("/v2/invoices/settle")?)
+            .json(&SettleInvoiceRequest::from(&action))
+            .send()
+            .await?
+            .error_for_status()?;
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        asset, identity,
+        swap_protocols::{actions::lnd::Chain, rfc003::SecretHash},
+    };
+
+    #[test]
+    fn serializes_send_payment_request_like_lnd_expects() {
+        let public_key: identity::Lightning =
+            "0279be667ef9dcbbac55a06295ce870b07029bfcdb2dce28d959f2815b16f81798"
+                .parse()
+                .unwrap();
+        let action = SendPayment {
+            to_public_key: public_key,
+            amount: asset::Bitcoin::from_sat(10_000),
+            secret_hash: SecretHash::from([1u8; 32]),
+            final_cltv_delta: 144,
+            chain: Chain::Bitcoin,
+            network: bitcoin::Network::Regtest,
+            self_public_key: public_key,
+        };
+
+        let json = serde_json::to_value(SendPaymentRequest::from(&action)).unwrap();
+
+        assert_eq!(
+            json,
+            serde_json::json!({
+                "dest": base64::encode(public_key.to_bytes()),
+                "amt": "10000",
+                "payment_hash": "AQEBAQEBAQEBAQEBAQEBAQEBAQEBAQEBAQEBAQEBAQE=",
+                "final_cltv_delta": 144,
+            })
+        );
+    }
+}
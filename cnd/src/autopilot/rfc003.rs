@@ -0,0 +1,82 @@
+use crate::{
+    autopilot::Autopilot,
+    db::{self, DetermineTypes},
+    seed::Rfc003DeriveSwapSeed,
+    swap_protocols::{
+        actions::Actions,
+        rfc003::{
+            actions::{Action, ActionKind},
+            state::Get,
+            LedgerState, SwapCommunication, SwapId,
+        },
+        Rfc003Facade,
+    },
+};
+
+pub async fn act_on_swaps(autopilot: &Autopilot, facade: &Rfc003Facade) -> anyhow::Result<()> {
+    for swap in db::Retrieve::all(facade).await? {
+        if let Err(e) = act_on_swap(autopilot, facade, swap.swap_id).await {
+            tracing::warn!("autopilot failed to act on swap {}: {:?}", swap.swap_id, e);
+        }
+    }
+
+    Ok(())
+}
+
+/// Executes the actions that are currently available for the swap.
+///
+/// Accepting and declining is always left to the user.
+#[allow(clippy::cognitive_complexity)]
+async fn act_on_swap(
+    autopilot: &Autopilot,
+    dependencies: &Rfc003Facade,
+    swap_id: SwapId,
+) -> anyhow::Result<()> {
+    let types = dependencies.determine_types(&swap_id).await?;
+
+    with_swap_types!(types, {
+        let swap_communication: Option<SwapCommunication<AL, BL, AA, BA, AI, BI>> =
+            dependencies.get(&swap_id).await?;
+        let alpha_ledger_state: Option<LedgerState<AA, AH, AT>> =
+            dependencies.alpha_ledger_states.get(&swap_id).await?;
+        let beta_ledger_state: Option<LedgerState<BA, BH, BT>> =
+            dependencies.beta_ledger_states.get(&swap_id).await?;
+
+        // Swaps that are still being set up don't have all of their states yet.
+        let (swap_communication, alpha_ledger_state, beta_ledger_state) =
+            match (swap_communication, alpha_ledger_state, beta_ledger_state) {
+                (Some(swap_communication), Some(alpha_ledger_state), Some(beta_ledger_state)) => {
+                    (swap_communication, alpha_ledger_state, beta_ledger_state)
+                }
+                _ => return Ok(()),
+            };
+        let secret_source = dependencies.rfc003_derive_swap_seed(swap_id);
+
+        let state = RoleState::new(
+            swap_communication,
+            alpha_ledger_state,
+            beta_ledger_state,
+            secret_source,
+        );
+
+        for action in state.actions() {
+            let swap_id = swap_id.into();
+
+            match action {
+                Action::Accept(_) | Action::Decline(_) => {}
+                Action::Deploy(deploy) => {
+                    autopilot.act(swap_id, ActionKind::Deploy, deploy).await?
+                }
+                Action::Fund(fund) => autopilot.act(swap_id, ActionKind::Fund, fund).await?,
+                Action::Redeem(redeem) => {
+                    autopilot.act(swap_id, ActionKind::Redeem, redeem).await?
+                }
+                Action::Refund(refund) => {
+                    autopilot.act(swap_id, ActionKind::Refund, refund).await?
+                }
+            }
+        }
+
+        Ok(())
+    })
+}
@@ -0,0 +1,214 @@
+use crate::{
+    autopilot::Autopilot,
+    http_api::routes::{load_swap_state, SwapState},
+    swap_protocols::{
+        rfc003::actions::ActionKind, DeployAction, Facade, FundAction, LocalSwapId, RedeemAction,
+        RefundAction,
+    },
+};
+
+pub async fn act_on_swaps(autopilot: &Autopilot, facade: &Facade) -> anyhow::Result<()> {
+    for swap_id in facade.db.all_local_swap_ids().await? {
+        if let Err(e) = act_on_swap(autopilot, facade, swap_id).await {
+            tracing::warn!("autopilot failed to act on swap {}: {:?}", swap_id, e);
+        }
+    }
+
+    Ok(())
+}
+
+/// Executes the actions that are currently available for the swap.
+///
+/// Adding the hold invoice (`init`) is left to the user.
+async fn act_on_swap(
+    autopilot: &Autopilot,
+    facade: &Facade,
+    swap_id: LocalSwapId,
+) -> anyhow::Result<()> {
+    let state = match load_swap_state(facade, swap_id).await? {
+        Some(state) => state,
+        None => return Ok(()),
+    };
+    let swap_id = swap_id.into();
+
+    match state {
+        SwapState::AliceHanEthereumHalightBitcoin(state) => {
+            if let Some(action) = state.fund_action() {
+                autopilot.act(swap_id, ActionKind::Fund, action).await?;
+            }
+            if let Some(action) = state.redeem_action() {
+                autopilot.act(swap_id, ActionKind::Redeem, action).await?;
+            }
+            if let Some(action) = state.refund_action() {
+                autopilot.act(swap_id, ActionKind::Refund, action).await?;
+            }
+        }
+        SwapState::BobHanEthereumHalightBitcoin(state) => {
+            if let Some(action) = state.fund_action() {
+                autopilot.act(swap_id, ActionKind::Fund, action).await?;
+            }
+            if let Some(action) = state.redeem_action() {
+                autopilot.act(swap_id, ActionKind::Redeem, action).await?;
+            }
+        }
+        SwapState::AliceHerc20HalightBitcoin(state) => {
+            if let Some(action) = state.deploy_action() {
+                autopilot.act(swap_id, ActionKind::Deploy, action).await?;
+            }
+            if let Some(action) = state.fund_action() {
+                autopilot.act(swap_id, ActionKind::Fund, action).await?;
+            }
+            if let Some(action) = state.redeem_action() {
+                autopilot.act(swap_id, ActionKind::Redeem, action).await?;
+            }
+            if let Some(action) = state.refund_action() {
+                autopilot.act(swap_id, ActionKind::Refund, action).await?;
+            }
+        }
+        SwapState::BobHerc20HalightBitcoin(state) => {
+            if let Some(action) = state.fund_action() {
+                autopilot.act(swap_id, ActionKind::Fund, action).await?;
+            }
+            if let Some(action) = state.redeem_action() {
+                autopilot.act(swap_id, ActionKind::Redeem, action).await?;
+            }
+        }
+        SwapState::AliceHalightBitcoinHanEthereum(state) => {
+            if let Some(action) = state.fund_action() {
+                autopilot.act(swap_id, ActionKind::Fund, action).await?;
+            }
+            if let Some(action) = state.redeem_action() {
+                autopilot.act(swap_id, ActionKind::Redeem, action).await?;
+            }
+        }
+        SwapState::BobHalightBitcoinHanEthereum(state) => {
+            if let Some(action) = state.fund_action() {
+                autopilot.act(swap_id, ActionKind::Fund, action).await?;
+            }
+            if let Some(action) = state.redeem_action() {
+                autopilot.act(swap_id, ActionKind::Redeem, action).await?;
+            }
+            if let Some(action) = state.refund_action() {
+                autopilot.act(swap_id, ActionKind::Refund, action).await?;
+            }
+        }
+        SwapState::AliceHalightBitcoinHerc20(state) => {
+            if let Some(action) = state.fund_action() {
+                autopilot.act(swap_id, ActionKind::Fund, action).await?;
+            }
+            if let Some(action) = state.redeem_action() {
+                autopilot.act(swap_id, ActionKind::Redeem, action).await?;
+            }
+        }
+        SwapState::BobHalightBitcoinHerc20(state) => {
+            if let Some(action) = state.deploy_action() {
+                autopilot.act(swap_id, ActionKind::Deploy, action).await?;
+            }
+            if let Some(action) = state.fund_action() {
+                autopilot.act(swap_id, ActionKind::Fund, action).await?;
+            }
+            if let Some(action) = state.redeem_action() {
+                autopilot.act(swap_id, ActionKind::Redeem, action).await?;
+            }
+            if let Some(action) = state.refund_action() {
+                autopilot.act(swap_id, ActionKind::Refund, action).await?;
+            }
+        }
+        SwapState::AliceHanEthereumHbitBitcoin(state) => {
+            if let Some(action) = state.fund_action() {
+                autopilot.act(swap_id, ActionKind::Fund, action).await?;
+            }
+            if let Some(action) = state.redeem_action() {
+                autopilot.act(swap_id, ActionKind::Redeem, action).await?;
+            }
+            if let Some(action) = state.refund_action() {
+                autopilot.act(swap_id, ActionKind::Refund, action).await?;
+            }
+        }
+        SwapState::BobHanEthereumHbitBitcoin(state) => {
+            if let Some(action) = state.fund_action() {
+                autopilot.act(swap_id, ActionKind::Fund, action).await?;
+            }
+            if let Some(action) = state.redeem_action() {
+                autopilot.act(swap_id, ActionKind::Redeem, action).await?;
+            }
+            if let Some(action) = state.refund_action() {
+                autopilot.act(swap_id, ActionKind::Refund, action).await?;
+            }
+        }
+        SwapState::AliceHerc20HbitBitcoin(state) => {
+            if let Some(action) = state.deploy_action() {
+                autopilot.act(swap_id, ActionKind::Deploy, action).await?;
+            }
+            if let Some(action) = state.fund_action() {
+                autopilot.act(swap_id, ActionKind::Fund, action).await?;
+            }
+            if let Some(action) = state.redeem_action() {
+                autopilot.act(swap_id, ActionKind::Redeem, action).await?;
+            }
+            if let Some(action) = state.refund_action() {
+                autopilot.act(swap_id, ActionKind::Refund, action).await?;
+            }
+        }
+        SwapState::BobHerc20HbitBitcoin(state) => {
+            if let Some(action) = state.fund_action() {
+                autopilot.act(swap_id, ActionKind::Fund, action).await?;
+            }
+            if let Some(action) = state.redeem_action() {
+                autopilot.act(swap_id, ActionKind::Redeem, action).await?;
+            }
+            if let Some(action) = state.refund_action() {
+                autopilot.act(swap_id, ActionKind::Refund, action).await?;
+            }
+        }
+        SwapState::AliceHbitBitcoinHanEthereum(state) => {
+            if let Some(action) = state.fund_action() {
+                autopilot.act(swap_id, ActionKind::Fund, action).await?;
+            }
+            if let Some(action) = state.redeem_action() {
+                autopilot.act(swap_id, ActionKind::Redeem, action).await?;
+            }
+            if let Some(action) = state.refund_action() {
+                autopilot.act(swap_id, ActionKind::Refund, action).await?;
+            }
+        }
+        SwapState::BobHbitBitcoinHanEthereum(state) => {
+            if let Some(action) = state.fund_action() {
+                autopilot.act(swap_id, ActionKind::Fund, action).await?;
+            }
+            if let Some(action) = state.redeem_action() {
+                autopilot.act(swap_id, ActionKind::Redeem, action).await?;
+            }
+            if let Some(action) = state.refund_action() {
+                autopilot.act(swap_id, ActionKind::Refund, action).await?;
+            }
+        }
+        SwapState::AliceHbitBitcoinHerc20(state) => {
+            if let Some(action) = state.fund_action() {
+                autopilot.act(swap_id, ActionKind::Fund, action).await?;
+            }
+            if let Some(action) = state.redeem_action() {
+                autopilot.act(swap_id, ActionKind::Redeem, action).await?;
+            }
+            if let Some(action) = state.refund_action() {
+                autopilot.act(swap_id, ActionKind::Refund, action).await?;
+            }
+        }
+        SwapState::BobHbitBitcoinHerc20(state) => {
+            if let Some(action) = state.deploy_action() {
+                autopilot.act(swap_id, ActionKind::Deploy, action).await?;
+            }
+            if let Some(action) = state.fund_action() {
+                autopilot.act(swap_id, ActionKind::Fund, action).await?;
+            }
+            if let Some(action) = state.redeem_action() {
+                autopilot.act(swap_id, ActionKind::Redeem, action).await?;
+            }
+            if let Some(action) = state.refund_action() {
+                autopilot.act(swap_id, ActionKind::Refund, action).await?;
+            }
+        }
+    }
+
+    Ok(())
+}
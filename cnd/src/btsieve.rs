@@ -2,19 +2,42 @@
 #![forbid(unsafe_code)]
 
 pub mod bitcoin;
+mod checkpoint;
 pub mod ethereum;
 
-use crate::Never;
+pub use self::checkpoint::{BlockTimestamp, Checkpoint, ScanProgress, StartOfScan, StartOfSwap};
+
 use async_trait::async_trait;
 use chrono::NaiveDateTime;
-use genawaiter::sync::Co;
-use std::{collections::HashSet, hash::Hash};
+use derivative::Derivative;
+use futures::future::{self, Either};
+use std::{
+    collections::HashMap,
+    fmt::LowerHex,
+    future::Future,
+    hash::Hash,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc,
+    },
+    time::Duration,
+};
+use tokio::sync::{mpsc, watch};
 
 #[async_trait]
 pub trait LatestBlock: Send + Sync + 'static {
     type Block;
 
     async fn latest_block(&self) -> anyhow::Result<Self::Block>;
+
+    /// Resolves once it is worth asking for the latest block again.
+    ///
+    /// Connectors that get notified about new blocks can resolve as soon as
+    /// one arrives, everybody else simply polls.
+    async fn wait_for_new_block(&self) {
+        // The duration of this timeout could/should depend on the network
+        tokio::time::delay_for(Duration::from_secs(1)).await;
+    }
 }
 
 #[async_trait]
@@ -25,11 +48,32 @@ pub trait BlockByHash: Send + Sync + 'static {
     async fn block_by_hash(&self, block_hash: Self::BlockHash) -> anyhow::Result<Self::Block>;
 }
 
+/// Abstracts over the ability of subscribing to the blocks of a ledger.
+pub trait SubscribeLatestBlocks {
+    type Block;
+
+    fn subscribe_latest_blocks(&self) -> LatestBlocks<Self::Block>;
+
+    /// Registers a watcher that is handed every block since the start of the
+    /// scan that passes the given sieve.
+    fn subscribe_relevant_blocks(
+        &self,
+        start_of_scan: StartOfScan,
+        sieve: Sieve<Self::Block>,
+    ) -> RelevantBlocks<Self::Block>;
+}
+
 /// Checks if a given block predates a certain timestamp.
 pub trait Predates {
     fn predates(&self, timestamp: NaiveDateTime) -> bool;
 }
 
+/// Abstracts over the ability of getting the height of a block.
+pub trait BlockHeight {
+    /// Returns `None` if the height cannot be told from the block alone.
+    fn block_height(&self) -> Option<u32>;
+}
+
 /// Abstracts over the ability of getting the hash of the current block.
 pub trait BlockHash {
     type BlockHash;
@@ -44,114 +88,726 @@ pub trait PreviousBlockHash {
     fn previous_block_hash(&self) -> Self::BlockHash;
 }
 
-/// This function uses the `connector` to find blocks relevant to a swap.  To do
-/// this we must get the latest block, for each latest block we receive we must
-/// ensure that we saw its parent i.e., that we did not miss any blocks between
-/// this latest block and the previous latest block we received.  Finally, we
-/// must also get each block back until the time that the swap started i.e.,
-/// look into the past (in case any action occurred on chain while we were not
-/// watching).
+/// Follows the parents of the given block through the blocks we have seen until
+/// we reach the common ancestor with the current chain.
 ///
-/// It yields those blocks as part of the process.
-pub async fn find_relevant_blocks<C, B, H>(
-    connector: &C,
-    co: Co<B>,
-    start_of_swap: NaiveDateTime,
-) -> anyhow::Result<Never>
+/// Returns the hashes of all blocks on the way, these are no longer part of the
+/// chain.
+fn orphaned_blocks<H>(
+    seen_blocks: &HashMap<H, H>,
+    latest_block_hash: H,
+    common_ancestor: Option<H>,
+) -> Vec<H>
 where
-    C: LatestBlock<Block = B> + BlockByHash<Block = B, BlockHash = H>,
-    B: Predates + BlockHash<BlockHash = H> + PreviousBlockHash<BlockHash = H> + Clone,
     H: Eq + Hash + Copy,
 {
-    let block = connector.latest_block().await?;
+    let mut orphaned_blocks = Vec::new();
+    let mut current_blockhash = latest_block_hash;
+
+    while Some(current_blockhash) != common_ancestor {
+        match seen_blocks.get(&current_blockhash) {
+            Some(previous_blockhash) => {
+                orphaned_blocks.push(current_blockhash);
+                current_blockhash = *previous_blockhash;
+            }
+            None => break,
+        }
+    }
+
+    orphaned_blocks
+}
+
+/// Follows the blocks of a ledger with a single task and shares them with all
+/// subscribers.
+///
+/// The first subscription spawns a task that polls the connector for the
+/// latest block and hands it out to all subscribers of the latest blocks. For
+/// each new latest block, the task walks back to the blocks it has seen before,
+/// fetching every block once and matching it against the sieves of all
+/// registered watchers in the same pass. If the connector fails, the task stops
+/// and the next subscription spawns a new one.
+#[derive(Debug)]
+pub struct BlockStream<B> {
+    inner: Arc<std::sync::Mutex<Option<Subscriptions<B>>>>,
+    min_confirmations: u32,
+}
+
+#[derive(Debug)]
+struct Subscriptions<B> {
+    latest_blocks: watch::Receiver<Option<B>>,
+    watchers: mpsc::UnboundedSender<Watcher<B>>,
+    running: Arc<AtomicBool>,
+}
+
+/// Tells if a block contains anything a watcher is looking for.
+pub type Sieve<B> = Box<dyn Fn(&B) -> bool + Send + Sync>;
+
+#[derive(Derivative)]
+#[derivative(Debug)]
+struct Watcher<B> {
+    start_of_scan: StartOfScan,
+    #[derivative(Debug = "ignore")]
+    sieve: Sieve<B>,
+    sender: mpsc::UnboundedSender<Scanned<B>>,
+    /// Whether the watcher was handed all relevant blocks up to the latest
+    /// block we have seen.
+    caught_up: bool,
+}
+
+#[derive(Debug)]
+enum Scanned<B> {
+    /// A block that passed the sieve of the watcher.
+    Block(B),
+    /// All blocks up to and including the one of the checkpoint were scanned.
+    UpTo(Checkpoint),
+}
+
+impl<B> BlockStream<B> {
+    /// Creates a block stream for a ledger on which transactions are only
+    /// considered final once their block has `min_confirmations`
+    /// confirmations.
+    pub fn new(min_confirmations: u32) -> Self {
+        Self {
+            inner: Arc::new(std::sync::Mutex::new(None)),
+            min_confirmations,
+        }
+    }
+}
+
+impl<B> Clone for BlockStream<B> {
+    fn clone(&self) -> Self {
+        Self {
+            inner: Arc::clone(&self.inner),
+            min_confirmations: self.min_confirmations,
+        }
+    }
+}
+
+impl<B> Default for BlockStream<B> {
+    fn default() -> Self {
+        Self::new(1)
+    }
+}
+
+impl<B, H> BlockStream<B>
+where
+    B: Predates
+        + BlockHeight
+        + BlockTimestamp
+        + BlockHash<BlockHash = H>
+        + PreviousBlockHash<BlockHash = H>
+        + Clone
+        + Send
+        + Sync
+        + 'static,
+    H: Eq + Hash + Copy + LowerHex + Send + Sync + 'static,
+{
+    pub fn subscribe<C>(&self, connector: &C) -> LatestBlocks<B>
+    where
+        C: LatestBlock<Block = B> + BlockByHash<Block = B, BlockHash = H> + Clone,
+    {
+        self.with_running_task(connector, |subscriptions| LatestBlocks {
+            receiver: subscriptions.latest_blocks.clone(),
+            min_confirmations: self.min_confirmations,
+        })
+    }
+
+    /// Registers a watcher that is handed every block since the start of the
+    /// scan that passes the given sieve.
+    pub fn watch<C>(
+        &self,
+        connector: &C,
+        start_of_scan: StartOfScan,
+        sieve: Sieve<B>,
+    ) -> RelevantBlocks<B>
+    where
+        C: LatestBlock<Block = B> + BlockByHash<Block = B, BlockHash = H> + Clone,
+    {
+        let (sender, receiver) = mpsc::unbounded_channel();
+        let watcher = Watcher {
+            start_of_scan: start_of_scan.clone(),
+            sieve,
+            sender,
+            caught_up: false,
+        };
+
+        self.with_running_task(connector, |subscriptions| {
+            // Should the task stop before it picks up the watcher, the watcher is dropped
+            // and the subscriber learns about it from the closed channel.
+            let _ = subscriptions.watchers.send(watcher);
+        });
+
+        RelevantBlocks {
+            receiver,
+            start_of_scan,
+        }
+    }
+
+    fn with_running_task<C, R>(&self, connector: &C, f: impl FnOnce(&Subscriptions<B>) -> R) -> R
+    where
+        C: LatestBlock<Block = B> + BlockByHash<Block = B, BlockHash = H> + Clone,
+    {
+        let mut guard = self.inner.lock().expect("mutex is not poisoned");
 
-    // Look back in time until we get a block that predates start_of_swap.
-    let mut seen_blocks =
-        walk_back_until(predates_start_of_swap(start_of_swap), block, connector, &co).await?;
+        match &*guard {
+            Some(subscriptions) if subscriptions.running.load(Ordering::SeqCst) => f(subscriptions),
+            _ => {
+                let (sender, latest_blocks) = watch::channel(None);
+                let (watchers, registrations) = mpsc::unbounded_channel();
+                let running = Arc::new(AtomicBool::new(true));
+
+                tokio::spawn(follow_blocks(
+                    connector.clone(),
+                    sender,
+                    registrations,
+                    Arc::clone(&running),
+                ));
+
+                let subscriptions = Subscriptions {
+                    latest_blocks,
+                    watchers,
+                    running,
+                };
+                let result = f(&subscriptions);
+                *guard = Some(subscriptions);
+
+                result
+            }
+        }
+    }
+}
+
+async fn follow_blocks<C, B, H>(
+    connector: C,
+    sender: watch::Sender<Option<B>>,
+    mut registrations: mpsc::UnboundedReceiver<Watcher<B>>,
+    running: Arc<AtomicBool>,
+) where
+    C: LatestBlock<Block = B> + BlockByHash<Block = B, BlockHash = H>,
+    B: Predates
+        + BlockHeight
+        + BlockTimestamp
+        + BlockHash<BlockHash = H>
+        + PreviousBlockHash<BlockHash = H>
+        + Clone,
+    H: Eq + Hash + Copy + LowerHex,
+{
+    let mut watchers = Vec::new();
+
+    if let Err(e) = scan_blocks(&connector, &sender, &mut registrations, &mut watchers).await {
+        tracing::warn!("failed to follow blocks: {:?}", e);
+    }
+
+    running.store(false, Ordering::SeqCst);
+}
+
+async fn scan_blocks<C, B, H>(
+    connector: &C,
+    sender: &watch::Sender<Option<B>>,
+    registrations: &mut mpsc::UnboundedReceiver<Watcher<B>>,
+    watchers: &mut Vec<Watcher<B>>,
+) -> anyhow::Result<()>
+where
+    C: LatestBlock<Block = B> + BlockByHash<Block = B, BlockHash = H>,
+    B: Predates
+        + BlockHeight
+        + BlockTimestamp
+        + BlockHash<BlockHash = H>
+        + PreviousBlockHash<BlockHash = H>
+        + Clone,
+    H: Eq + Hash + Copy + LowerHex,
+{
+    let mut seen_blocks = HashMap::new();
+    let mut latest_block_hash = None;
 
-    // Look forward in time, but keep going back for missed blocks
     loop {
         let block = connector.latest_block().await?;
+        let block_hash = block.block_hash();
 
-        let missed_blocks = walk_back_until(
-            seen_block_or_predates_start_of_swap(&seen_blocks, start_of_swap),
-            block,
-            connector,
-            &co,
-        )
-        .await?;
+        while let Ok(watcher) = registrations.try_recv() {
+            watchers.push(watcher);
+        }
 
-        seen_blocks.extend(missed_blocks);
+        let is_new_block = latest_block_hash != Some(block_hash);
+        let has_new_watchers = watchers.iter().any(|watcher| !watcher.caught_up);
 
-        // The duration of this timeout could/should depend on the network
-        tokio::time::delay_for(std::time::Duration::from_secs(1)).await;
+        if is_new_block || has_new_watchers {
+            let (visited_blocks, common_ancestor) =
+                walk_back(connector, block.clone(), &seen_blocks, watchers).await?;
+
+            // If we did not walk back to the previous latest block, the blocks
+            // between it and the first block we had already seen were orphaned.
+            if let (true, Some(previous_latest_block_hash)) = (is_new_block, latest_block_hash) {
+                let orphaned_blocks =
+                    orphaned_blocks(&seen_blocks, previous_latest_block_hash, common_ancestor);
+
+                if !orphaned_blocks.is_empty() {
+                    tracing::warn!(
+                        "chain reorganisation detected, {} block(s) were orphaned",
+                        orphaned_blocks.len()
+                    );
+
+                    // Forget about orphaned blocks so that we hand them out again
+                    // should they become part of the chain again.
+                    for orphaned_block in orphaned_blocks {
+                        seen_blocks.remove(&orphaned_block);
+                    }
+                }
+            }
+
+            seen_blocks.extend(visited_blocks);
+            seen_blocks = recent_blocks(&seen_blocks, block_hash, FINALITY_CONFIRMATIONS);
+
+            let checkpoint = Checkpoint::of_block(&block);
+            watchers.retain(|watcher| {
+                watcher
+                    .sender
+                    .send(Scanned::UpTo(checkpoint.clone()))
+                    .is_ok()
+            });
+            for watcher in watchers.iter_mut() {
+                watcher.caught_up = true;
+            }
+        }
+
+        if is_new_block {
+            latest_block_hash = Some(block_hash);
+
+            if sender.broadcast(Some(block)).is_err() {
+                return Ok(());
+            }
+        }
+
+        connector.wait_for_new_block().await;
     }
 }
 
-/// Walks the blockchain backwards from the given hash until the predicate given
-/// in `stop_condition` returns `true`.
+/// Walks back from the latest block until every watcher was handed the blocks
+/// it is interested in and we came across a block we have seen before.
 ///
-/// This function yields all blocks as part of its process.
-/// This function returns the block-hashes of all visited blocks.
-async fn walk_back_until<C, P, B, H>(
-    should_stop_here: P,
-    starting_block: B,
+/// Every block is matched against the sieves of all watchers that still need
+/// it. Returns the hashes of all visited blocks, mapped to the hashes of their
+/// parents, together with the first block we had seen before.
+async fn walk_back<C, B, H>(
     connector: &C,
-    co: &Co<B>,
-) -> anyhow::Result<HashSet<H>>
+    latest_block: B,
+    seen_blocks: &HashMap<H, H>,
+    watchers: &[Watcher<B>],
+) -> anyhow::Result<(HashMap<H, H>, Option<H>)>
 where
     C: BlockByHash<Block = B, BlockHash = H>,
-    P: Fn(&B) -> bool,
-    B: BlockHash<BlockHash = H> + PreviousBlockHash<BlockHash = H>,
+    B: Predates + BlockHeight + BlockHash<BlockHash = H> + PreviousBlockHash<BlockHash = H> + Clone,
+    H: Eq + Hash + Copy + LowerHex,
+{
+    let mut visited_blocks = HashMap::new();
+    let mut common_ancestor = None;
+    let mut done = vec![false; watchers.len()];
+    let mut current_block = latest_block;
+
+    loop {
+        let block_hash = current_block.block_hash();
+        let previous_block_hash = current_block.previous_block_hash();
+
+        if common_ancestor.is_none() && seen_blocks.contains_key(&block_hash) {
+            common_ancestor = Some(block_hash);
+        }
+
+        for (watcher, done) in watchers.iter().zip(done.iter_mut()) {
+            // Watchers that caught up were handed all blocks we have seen already.
+            if *done || (watcher.caught_up && common_ancestor.is_some()) {
+                *done = true;
+                continue;
+            }
+
+            if (watcher.sieve)(&current_block) {
+                let _ = watcher.sender.send(Scanned::Block(current_block.clone()));
+            }
+            *done = watcher.start_of_scan.is_reached_by(&current_block);
+        }
+
+        visited_blocks.insert(block_hash, previous_block_hash);
+
+        if common_ancestor.is_none() && seen_blocks.contains_key(&previous_block_hash) {
+            common_ancestor = Some(previous_block_hash);
+        }
+
+        // We only remember that many blocks, there is no point in looking further.
+        let gave_up_on_common_ancestor = visited_blocks.len() >= FINALITY_CONFIRMATIONS as usize;
+        let found_seen_blocks =
+            seen_blocks.is_empty() || common_ancestor.is_some() || gave_up_on_common_ancestor;
+        let watchers_done = watchers
+            .iter()
+            .zip(&done)
+            .all(|(watcher, done)| *done || (watcher.caught_up && common_ancestor.is_some()));
+
+        if found_seen_blocks && watchers_done {
+            return Ok((visited_blocks, common_ancestor));
+        }
+
+        current_block = connector.block_by_hash(previous_block_hash).await?;
+    }
+}
+
+/// Keeps the most recent blocks of the chain ending in the latest block, these
+/// are enough to tell whether the next latest block builds on top of it.
+fn recent_blocks<H>(seen_blocks: &HashMap<H, H>, latest_block_hash: H, depth: u32) -> HashMap<H, H>
+where
     H: Eq + Hash + Copy,
 {
-    let mut seen_blocks = HashSet::new();
+    let mut recent_blocks = HashMap::new();
+    let mut current_blockhash = latest_block_hash;
 
-    let mut current_blockhash = starting_block.block_hash();
-    let mut current_block = starting_block;
+    for _ in 0..depth {
+        match seen_blocks.get(&current_blockhash) {
+            Some(previous_blockhash) => {
+                recent_blocks.insert(current_blockhash, *previous_blockhash);
+                current_blockhash = *previous_blockhash;
+            }
+            None => break,
+        }
+    }
 
-    loop {
-        seen_blocks.insert(current_blockhash);
+    recent_blocks
+}
 
-        // we have to compute these variables before we consume the block with
-        // `co.yield_`
-        current_blockhash = current_block.previous_block_hash();
-        let should_stop_here = should_stop_here(&current_block);
+/// The blocks that passed the sieve of a single watcher, in the order they were
+/// scanned.
+#[derive(Debug)]
+pub struct RelevantBlocks<B> {
+    receiver: mpsc::UnboundedReceiver<Scanned<B>>,
+    start_of_scan: StartOfScan,
+}
 
-        // we have to yield the block before exiting
-        co.yield_(current_block).await;
+impl<B> RelevantBlocks<B> {
+    /// Returns the next block that passed the sieve.
+    ///
+    /// The progress of the scan is reported to the start of scan once all
+    /// blocks before the checkpoint were returned.
+    pub async fn next(&mut self) -> anyhow::Result<B> {
+        loop {
+            match self.receiver.recv().await {
+                Some(Scanned::Block(block)) => return Ok(block),
+                Some(Scanned::UpTo(checkpoint)) => self.start_of_scan.report(checkpoint),
+                None => return Err(anyhow::Error::from(BlockStreamClosed)),
+            }
+        }
+    }
+}
 
-        if should_stop_here {
-            return Ok(seen_blocks);
+/// A subscription to the latest blocks of a ledger.
+///
+/// Only the most recent block is kept for each subscriber, blocks that were
+/// superseded before the subscriber got to them have to be fetched by hash.
+#[derive(Debug)]
+pub struct LatestBlocks<B> {
+    receiver: watch::Receiver<Option<B>>,
+    min_confirmations: u32,
+}
+
+impl<B> LatestBlocks<B>
+where
+    B: Clone,
+{
+    pub async fn next(&mut self) -> anyhow::Result<B> {
+        loop {
+            match self.receiver.recv().await {
+                Some(Some(block)) => return Ok(block),
+                Some(None) => continue,
+                None => return Err(anyhow::Error::from(BlockStreamClosed)),
+            }
         }
+    }
 
-        current_block = connector.block_by_hash(current_blockhash).await?
+    /// The number of confirmations a block needs on this ledger before we act
+    /// upon the transactions it contains.
+    pub fn min_confirmations(&self) -> u32 {
+        self.min_confirmations
     }
 }
 
-/// Constructs a predicate that returns `true` if the given block predates the
-/// start_of_swap timestamp.
-fn predates_start_of_swap<B>(start_of_swap: NaiveDateTime) -> impl Fn(&B) -> bool
+#[derive(Clone, Copy, Debug, thiserror::Error)]
+#[error("block stream closed because a block could not be fetched")]
+pub struct BlockStreamClosed;
+
+/// Number of confirmations after which we consider a block final i.e., we no
+/// longer expect it to be orphaned by a chain reorganisation.
+const FINALITY_CONFIRMATIONS: u32 = 100;
+
+/// Outcome of waiting for a block to be confirmed.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum BlockStatus {
+    /// The block has the number of confirmations we waited for.
+    Confirmed,
+    /// The block was orphaned by a chain reorganisation before it had the
+    /// number of confirmations we waited for.
+    Reverted,
+}
+
+/// Abstracts over the ability of waiting until a transaction we found on a
+/// ledger is final or no longer part of the chain.
+#[async_trait]
+pub trait WaitForFinality<T>: Send + Sync + 'static {
+    async fn wait_for_finality(&self, transaction: &T) -> anyhow::Result<BlockStatus>;
+}
+
+/// Abstracts over the ability of telling how many confirmations a transaction
+/// we found on a ledger has.
+#[async_trait]
+pub trait Confirmations<T>: Send + Sync + 'static {
+    /// Returns `None` if the transaction is not included in a block yet.
+    async fn confirmations(&self, transaction: &T) -> anyhow::Result<Option<u32>>;
+}
+
+/// Returns the height of the first block that needs to be looked at for a swap
+/// that starts now, `margin` blocks below the latest block of the ledger.
+///
+/// Returns `None` if the height of the latest block cannot be told.
+pub async fn start_height<C, B>(connector: &C, margin: u32) -> anyhow::Result<Option<u32>>
+where
+    C: LatestBlock<Block = B>,
+    B: BlockHeight,
+{
+    let latest_block = connector.latest_block().await?;
+
+    Ok(latest_block
+        .block_height()
+        .map(|height| height.saturating_sub(margin)))
+}
+
+/// Waits for the given event unless the given transaction is reverted first,
+/// in which case `None` is returned.
+///
+/// This allows protocols to roll back to the previous state if the
+/// transaction that led to the current state is orphaned by a chain
+/// reorganisation while they are waiting for the next event.
+pub async fn unless_reverted<C, T, F, R>(
+    connector: &C,
+    transaction: &T,
+    event: F,
+) -> anyhow::Result<Option<R>>
 where
-    B: Predates,
+    C: WaitForFinality<T>,
+    F: Future<Output = anyhow::Result<R>>,
 {
-    move |block| block.predates(start_of_swap)
+    let reverted = async {
+        match connector.wait_for_finality(transaction).await? {
+            BlockStatus::Reverted => Ok(()),
+            BlockStatus::Confirmed => future::pending::<anyhow::Result<()>>().await,
+        }
+    };
+
+    futures::pin_mut!(event);
+    futures::pin_mut!(reverted);
+
+    match future::try_select(event, reverted).await {
+        Ok(Either::Left((value, _))) => Ok(Some(value)),
+        Ok(Either::Right(((), _))) => Ok(None),
+        Err(either) => {
+            let (error, _other_future) = either.factor_first();
+            Err(error)
+        }
+    }
 }
 
-/// Constructs a predicate that returns `true` if we have seen the given block
-/// or the block predates the start_of_swap timestamp.
-fn seen_block_or_predates_start_of_swap<'sb, B, H>(
-    seen_blocks: &'sb HashSet<H>,
-    start_of_swap: NaiveDateTime,
-) -> impl Fn(&B) -> bool + 'sb
+/// Waits until the block with the given hash has the number of confirmations
+/// that are required on this ledger, the block itself being the first one.
+///
+/// We only look back as far as the required number of confirmations from the
+/// latest block. Not finding the block within that range means it is buried
+/// deep enough, unless we came across its parent, in which case the block is
+/// no longer part of the chain.
+pub async fn wait_for_confirmations<C, B, H>(
+    connector: &C,
+    block_hash: H,
+    previous_block_hash: H,
+) -> anyhow::Result<BlockStatus>
 where
-    B: Predates + BlockHash<BlockHash = H>,
-    H: Eq + Hash,
+    C: SubscribeLatestBlocks<Block = B> + BlockByHash<Block = B, BlockHash = H>,
+    B: BlockHash<BlockHash = H> + PreviousBlockHash<BlockHash = H> + Clone,
+    H: Eq + Copy,
 {
-    move |block: &B| {
-        let have_seen_block = seen_blocks.contains(&block.block_hash());
-        let predates_start_of_swap = predates_start_of_swap(start_of_swap)(block);
+    let latest_blocks = connector.subscribe_latest_blocks();
+    let min_confirmations = latest_blocks.min_confirmations();
 
-        have_seen_block || predates_start_of_swap
+    if min_confirmations <= 1 {
+        return Ok(BlockStatus::Confirmed);
     }
+
+    wait_until_confirmed(
+        connector,
+        latest_blocks,
+        block_hash,
+        previous_block_hash,
+        min_confirmations,
+    )
+    .await
+}
+
+/// Waits until the block containing a transaction is final or no longer part
+/// of the chain.
+///
+/// We look for the block by walking back from the latest block. If it is not
+/// within the last `FINALITY_CONFIRMATIONS` blocks, it is final already.
+pub async fn wait_for_block_finality<C, B, H, F>(
+    connector: &C,
+    contains_transaction: F,
+) -> anyhow::Result<BlockStatus>
+where
+    C: SubscribeLatestBlocks<Block = B> + BlockByHash<Block = B, BlockHash = H>,
+    B: BlockHash<BlockHash = H> + PreviousBlockHash<BlockHash = H> + Clone,
+    H: Eq + Copy,
+    F: Fn(&B) -> bool,
+{
+    let mut latest_blocks = connector.subscribe_latest_blocks();
+    let mut current_block = latest_blocks.next().await?;
+
+    for _ in 0..FINALITY_CONFIRMATIONS {
+        if contains_transaction(&current_block) {
+            return wait_until_confirmed(
+                connector,
+                latest_blocks,
+                current_block.block_hash(),
+                current_block.previous_block_hash(),
+                FINALITY_CONFIRMATIONS,
+            )
+            .await;
+        }
+
+        current_block = match connector
+            .block_by_hash(current_block.previous_block_hash())
+            .await
+        {
+            Ok(block) => block,
+            Err(e) => {
+                // Most likely we walked past the genesis block.
+                tracing::debug!("stopped looking for block of transaction: {:?}", e);
+                break;
+            }
+        };
+    }
+
+    Ok(BlockStatus::Confirmed)
+}
+
+/// Counts the confirmations of the block containing a transaction, the block
+/// itself being the first one.
+///
+/// We walk back from the latest block handed out by the block stream of the
+/// ledger, but only as far as `FINALITY_CONFIRMATIONS` blocks. Returns `None`
+/// if the transaction is not within that range, either because it is not
+/// included in a block yet or because it is buried deeper.
+pub async fn count_block_confirmations<C, B, H, F>(
+    connector: &C,
+    contains_transaction: F,
+) -> anyhow::Result<Option<u32>>
+where
+    C: SubscribeLatestBlocks<Block = B> + BlockByHash<Block = B, BlockHash = H>,
+    B: PreviousBlockHash<BlockHash = H> + Clone,
+    F: Fn(&B) -> bool,
+{
+    let mut current_block = connector.subscribe_latest_blocks().next().await?;
+
+    for confirmations in 1..FINALITY_CONFIRMATIONS {
+        if contains_transaction(&current_block) {
+            return Ok(Some(confirmations));
+        }
+
+        current_block = match connector
+            .block_by_hash(current_block.previous_block_hash())
+            .await
+        {
+            Ok(block) => block,
+            Err(e) => {
+                // Most likely we walked past the genesis block.
+                tracing::debug!("stopped counting confirmations of transaction: {:?}", e);
+                return Ok(None);
+            }
+        };
+    }
+
+    if contains_transaction(&current_block) {
+        return Ok(Some(FINALITY_CONFIRMATIONS));
+    }
+
+    Ok(None)
+}
+
+async fn wait_until_confirmed<C, B, H>(
+    connector: &C,
+    mut latest_blocks: LatestBlocks<B>,
+    block_hash: H,
+    previous_block_hash: H,
+    min_confirmations: u32,
+) -> anyhow::Result<BlockStatus>
+where
+    C: BlockByHash<Block = B, BlockHash = H>,
+    B: BlockHash<BlockHash = H> + PreviousBlockHash<BlockHash = H> + Clone,
+    H: Eq + Copy,
+{
+    loop {
+        let latest_block = latest_blocks.next().await?;
+
+        match count_confirmations(
+            connector,
+            latest_block,
+            block_hash,
+            previous_block_hash,
+            min_confirmations,
+        )
+        .await?
+        {
+            Some(confirmations) if confirmations >= min_confirmations => {
+                return Ok(BlockStatus::Confirmed)
+            }
+            Some(confirmations) => tracing::debug!(
+                "block has {} of {} required confirmations",
+                confirmations,
+                min_confirmations
+            ),
+            None => {
+                tracing::warn!("block is no longer part of the chain");
+                return Ok(BlockStatus::Reverted);
+            }
+        }
+    }
+}
+
+/// Counts the confirmations of the block with the given hash by walking back
+/// from the latest block, stops counting at `max`.
+///
+/// Returns `None` if the block is not an ancestor of the latest block.
+async fn count_confirmations<C, B, H>(
+    connector: &C,
+    latest_block: B,
+    block_hash: H,
+    previous_block_hash: H,
+    max: u32,
+) -> anyhow::Result<Option<u32>>
+where
+    C: BlockByHash<Block = B, BlockHash = H>,
+    B: BlockHash<BlockHash = H> + PreviousBlockHash<BlockHash = H>,
+    H: Eq,
+{
+    let mut current_block = latest_block;
+
+    for confirmations in 1..max {
+        let current_blockhash = current_block.block_hash();
+
+        if current_blockhash == block_hash {
+            return Ok(Some(confirmations));
+        }
+        if current_blockhash == previous_block_hash {
+            return Ok(None);
+        }
+
+        current_block = connector
+            .block_by_hash(current_block.previous_block_hash())
+            .await?;
+    }
+
+    if current_block.block_hash() == previous_block_hash {
+        return Ok(None);
+    }
+
+    Ok(Some(max))
 }
@@ -1,23 +1,32 @@
 mod bitcoind_connector;
+mod bitcoind_rpc_connector;
 mod cache;
+mod connector;
+mod esplora_connector;
 
 pub use self::{
-    bitcoind_connector::{BitcoindConnector, ChainInfo},
+    bitcoind_connector::{BitcoindRestConnector, ChainInfo},
+    bitcoind_rpc_connector::{rpc_client, BitcoindRpcConnector},
     cache::Cache,
+    connector::BitcoinConnector,
+    esplora_connector::EsploraConnector,
 };
 use crate::{
     btsieve::{
-        find_relevant_blocks, BlockByHash, BlockHash, LatestBlock, Predates, PreviousBlockHash,
+        count_block_confirmations, wait_for_block_finality, wait_for_confirmations, BlockByHash,
+        BlockHash, BlockHeight, BlockStatus, BlockTimestamp, Predates, PreviousBlockHash,
+        StartOfScan, SubscribeLatestBlocks,
     },
     identity,
 };
+use async_trait::async_trait;
 use bitcoin::{
     consensus::{encode::deserialize, Decodable},
-    BitcoinHash, OutPoint,
+    BitcoinHash, OutPoint, Txid,
 };
 use chrono::NaiveDateTime;
-use genawaiter::{sync::Gen, GeneratorState};
 use reqwest::{Client, Url};
+use std::{collections::HashSet, sync::Arc, time::Duration};
 
 type Hash = bitcoin::BlockHash;
 type Block = bitcoin::Block;
@@ -38,84 +47,199 @@ impl PreviousBlockHash for Block {
     }
 }
 
+/// Abstracts over the ability to look at transactions that have not been
+/// included in a block yet.
+#[async_trait]
+pub trait Mempool: Send + Sync + 'static {
+    async fn mempool_txids(&self) -> anyhow::Result<Vec<Txid>>;
+    async fn transaction_by_id(&self, txid: Txid) -> anyhow::Result<bitcoin::Transaction>;
+}
+
 pub async fn watch_for_spent_outpoint<C>(
     blockchain_connector: &C,
-    start_of_swap: NaiveDateTime,
+    start_of_scan: impl Into<StartOfScan>,
     from_outpoint: OutPoint,
     identity: identity::Bitcoin,
 ) -> anyhow::Result<(bitcoin::Transaction, bitcoin::TxIn)>
 where
-    C: LatestBlock<Block = Block> + BlockByHash<Block = Block, BlockHash = Hash>,
+    C: SubscribeLatestBlocks<Block = Block> + BlockByHash<Block = Block, BlockHash = Hash>,
 {
-    let (transaction, txin) = watch(blockchain_connector, start_of_swap, |transaction| {
-        transaction
-            .input
-            .iter()
-            .filter(|txin| txin.previous_output == from_outpoint)
-            .find(|txin| txin.witness.contains(&identity.to_bytes()))
-            .cloned()
-    })
+    let (transaction, txin) = watch(
+        blockchain_connector,
+        start_of_scan.into(),
+        move |transaction| spending_txin(transaction, from_outpoint, &identity),
+    )
     .await?;
 
     Ok((transaction, txin))
 }
 
+/// Looks for a transaction spending the given outpoint in the mempool.
+///
+/// The returned transaction is unconfirmed and may never make it into a block,
+/// callers must still wait for the confirmed transaction.
+pub async fn watch_for_spent_outpoint_in_mempool<C>(
+    mempool: &C,
+    from_outpoint: OutPoint,
+    identity: identity::Bitcoin,
+) -> anyhow::Result<(bitcoin::Transaction, bitcoin::TxIn)>
+where
+    C: Mempool,
+{
+    let mut seen_txids = HashSet::new();
+
+    loop {
+        for txid in mempool.mempool_txids().await? {
+            if !seen_txids.insert(txid) {
+                continue;
+            }
+
+            let transaction = match mempool.transaction_by_id(txid).await {
+                Ok(transaction) => transaction,
+                Err(e) => {
+                    // The transaction may have been mined or evicted in the meantime.
+                    tracing::debug!("could not fetch mempool transaction {}: {:?}", txid, e);
+                    continue;
+                }
+            };
+
+            if let Some(txin) = spending_txin(&transaction, from_outpoint, &identity) {
+                tracing::trace!("mempool transaction matched {:x}", txid);
+                return Ok((transaction, txin));
+            }
+        }
+
+        tokio::time::delay_for(Duration::from_secs(1)).await;
+    }
+}
+
+fn spending_txin(
+    transaction: &bitcoin::Transaction,
+    from_outpoint: OutPoint,
+    identity: &identity::Bitcoin,
+) -> Option<bitcoin::TxIn> {
+    transaction
+        .input
+        .iter()
+        .filter(|txin| txin.previous_output == from_outpoint)
+        .find(|txin| txin.witness.contains(&identity.to_bytes()))
+        .cloned()
+}
+
 pub async fn watch_for_created_outpoint<C>(
     blockchain_connector: &C,
-    start_of_swap: NaiveDateTime,
+    start_of_scan: impl Into<StartOfScan>,
     compute_address: bitcoin::Address,
 ) -> anyhow::Result<(bitcoin::Transaction, bitcoin::OutPoint)>
 where
-    C: LatestBlock<Block = Block> + BlockByHash<Block = Block, BlockHash = Hash>,
+    C: SubscribeLatestBlocks<Block = Block> + BlockByHash<Block = Block, BlockHash = Hash>,
 {
-    let (transaction, out_point) = watch(blockchain_connector, start_of_swap, |transaction| {
-        let txid = transaction.txid();
-        transaction
-            .output
-            .iter()
-            .enumerate()
-            .map(|(index, txout)| {
-                // Casting a usize to u32 can lead to truncation on 64bit platforms
-                // However, bitcoin limits the number of inputs to u32 anyway, so this
-                // is not a problem for us.
-                #[allow(clippy::cast_possible_truncation)]
-                (index as u32, txout)
-            })
-            .find(|(_, txout)| txout.script_pubkey == compute_address.script_pubkey())
-            .map(|(vout, _txout)| OutPoint { txid, vout })
-    })
+    let script_pubkey = compute_address.script_pubkey();
+
+    let (transaction, out_point) = watch(
+        blockchain_connector,
+        start_of_scan.into(),
+        move |transaction| {
+            let txid = transaction.txid();
+            transaction
+                .output
+                .iter()
+                .enumerate()
+                .map(|(index, txout)| {
+                    // Casting a usize to u32 can lead to truncation on 64bit platforms
+                    // However, bitcoin limits the number of inputs to u32 anyway, so this
+                    // is not a problem for us.
+                    #[allow(clippy::cast_possible_truncation)]
+                    (index as u32, txout)
+                })
+                .find(|(_, txout)| txout.script_pubkey == script_pubkey)
+                .map(|(vout, _txout)| OutPoint { txid, vout })
+        },
+    )
     .await?;
 
     Ok((transaction, out_point))
 }
 
+/// Waits until the block containing the given transaction is final or no
+/// longer part of the chain.
+pub async fn wait_for_finality<C>(
+    blockchain_connector: &C,
+    transaction: &bitcoin::Transaction,
+) -> anyhow::Result<BlockStatus>
+where
+    C: SubscribeLatestBlocks<Block = Block> + BlockByHash<Block = Block, BlockHash = Hash>,
+{
+    let txid = transaction.txid();
+
+    wait_for_block_finality(blockchain_connector, |block: &Block| {
+        block
+            .txdata
+            .iter()
+            .any(|transaction| transaction.txid() == txid)
+    })
+    .await
+}
+
+/// Returns the number of confirmations of the given transaction, `None` if it
+/// is not within the most recent blocks.
+pub async fn confirmations<C>(
+    blockchain_connector: &C,
+    transaction: &bitcoin::Transaction,
+) -> anyhow::Result<Option<u32>>
+where
+    C: SubscribeLatestBlocks<Block = Block> + BlockByHash<Block = Block, BlockHash = Hash>,
+{
+    let txid = transaction.txid();
+
+    count_block_confirmations(blockchain_connector, |block: &Block| {
+        block
+            .txdata
+            .iter()
+            .any(|transaction| transaction.txid() == txid)
+    })
+    .await
+}
+
 async fn watch<C, S, M>(
     connector: &C,
-    start_of_swap: NaiveDateTime,
+    start_of_scan: StartOfScan,
     sieve: S,
 ) -> anyhow::Result<(bitcoin::Transaction, M)>
 where
-    C: LatestBlock<Block = Block> + BlockByHash<Block = Block, BlockHash = Hash>,
-    S: Fn(&bitcoin::Transaction) -> Option<M>,
+    C: SubscribeLatestBlocks<Block = Block> + BlockByHash<Block = Block, BlockHash = Hash>,
+    S: Fn(&bitcoin::Transaction) -> Option<M> + Send + Sync + 'static,
 {
-    let mut block_generator =
-        Gen::new({ |co| async { find_relevant_blocks(connector, co, start_of_swap).await } });
+    let sieve = Arc::new(sieve);
+    let mut relevant_blocks = connector.subscribe_relevant_blocks(
+        start_of_scan,
+        Box::new({
+            let sieve = Arc::clone(&sieve);
+            move |block: &Block| {
+                block
+                    .txdata
+                    .iter()
+                    .any(|transaction| sieve(transaction).is_some())
+            }
+        }),
+    );
 
     loop {
-        match block_generator.async_resume().await {
-            GeneratorState::Yielded(block) => {
-                for transaction in block.txdata.into_iter() {
-                    if let Some(result) = sieve(&transaction) {
-                        tracing::trace!("transaction matched {:x}", transaction.txid());
-                        return Ok((transaction, result));
-                    }
+        let block = relevant_blocks.next().await?;
+        let block_hash = block.bitcoin_hash();
+        let previous_block_hash = block.previous_block_hash();
+
+        for transaction in block.txdata.into_iter() {
+            if let Some(result) = sieve(&transaction) {
+                tracing::trace!("transaction matched {:x}", transaction.txid());
+
+                match wait_for_confirmations(connector, block_hash, previous_block_hash).await? {
+                    BlockStatus::Confirmed => return Ok((transaction, result)),
+                    // The block got orphaned, keep looking for the transaction in the blocks
+                    // that replace it.
+                    BlockStatus::Reverted => break,
                 }
             }
-            GeneratorState::Complete(Err(e)) => return Err(e),
-            // By matching against the never type explicitly, we assert that the `Ok` value of the
-            // result is actually the never type and has not been changed since this line was
-            // written. The never type can never be constructed, so we can never reach this line.
-            GeneratorState::Complete(Ok(never)) => match never {},
         }
     }
 }
@@ -129,6 +253,46 @@ impl Predates for Block {
     }
 }
 
+/// Since BIP34, the first push of the coinbase script is the height of the
+/// block.
+impl BlockHeight for Block {
+    fn block_height(&self) -> Option<u32> {
+        let coinbase = self.txdata.first()?;
+        let script_sig = &coinbase.input.first()?.script_sig;
+
+        coinbase_height(script_sig.as_bytes())
+    }
+}
+
+/// Decodes the height pushed at the start of a coinbase script as a script
+/// number.
+fn coinbase_height(script_sig: &[u8]) -> Option<u32> {
+    const OP_1: u8 = 0x51;
+    const OP_16: u8 = 0x60;
+
+    let (&opcode, rest) = script_sig.split_first()?;
+
+    match opcode {
+        OP_1..=OP_16 => Some(u32::from(opcode - OP_1 + 1)),
+        length @ 1..=4 => {
+            let bytes = rest.get(..usize::from(length))?;
+            let height = bytes
+                .iter()
+                .rev()
+                .fold(0u32, |height, byte| (height << 8) | u32::from(*byte));
+
+            Some(height)
+        }
+        _ => None,
+    }
+}
+
+impl BlockTimestamp for Block {
+    fn block_timestamp(&self) -> NaiveDateTime {
+        NaiveDateTime::from_timestamp(self.header.time as i64, 0)
+    }
+}
+
 pub async fn bitcoin_http_request_for_hex_encoded_object<T>(
     request_url: Url,
     client: &Client,
@@ -179,6 +343,15 @@ mod tests {
         assert_that(&bytes).is_ok();
     }
 
+    #[test]
+    fn coinbase_height_is_decoded_from_script_number() {
+        assert_eq!(coinbase_height(&[0x01, 0x65, 0x01, 0x01]), Some(101));
+        assert_eq!(coinbase_height(&[0x03, 0xfc, 0x7a, 0x03]), Some(228_092));
+        assert_eq!(coinbase_height(&[0x55]), Some(5));
+        assert_eq!(coinbase_height(&[0x03, 0xfc]), None);
+        assert_eq!(coinbase_height(&[]), None);
+    }
+
     #[test]
     fn can_decode_block_from_bitcoind_http_interface() {
         // the line break here is on purpose, as it is returned like that from bitcoind
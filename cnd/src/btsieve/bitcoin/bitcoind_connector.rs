@@ -1,31 +1,45 @@
 use crate::{
-    btsieve::{bitcoin::bitcoin_http_request_for_hex_encoded_object, BlockByHash, LatestBlock},
-    config::validation::FetchNetworkId,
+    btsieve::{
+        bitcoin::{bitcoin_http_request_for_hex_encoded_object, Mempool},
+        BlockByHash, LatestBlock,
+    },
+    config::validation::{FetchNetworkId, FetchSyncState},
 };
 use async_trait::async_trait;
-use bitcoin::{BlockHash, Network};
+use bitcoin::{BlockHash, Network, Txid};
 use reqwest::{Client, Url};
-use serde::{de, export::fmt, Deserialize, Deserializer};
+use serde::{
+    de::{self, IgnoredAny},
+    export::fmt,
+    Deserialize, Deserializer,
+};
+use std::collections::HashMap;
 
 #[derive(Copy, Clone, Debug, Deserialize)]
 pub struct ChainInfo {
     bestblockhash: BlockHash,
     #[serde(deserialize_with = "deserialize_bitcoind_values")]
     pub chain: Network,
+    #[serde(default)]
+    pub initialblockdownload: bool,
 }
 
-#[derive(Debug)]
-pub struct BitcoindConnector {
+#[derive(Clone, Debug)]
+pub struct BitcoindRestConnector {
     chaininfo_url: Url,
     raw_block_by_hash_url: Url,
+    mempool_contents_url: Url,
+    raw_transaction_by_id_url: Url,
     client: Client,
 }
 
-impl BitcoindConnector {
+impl BitcoindRestConnector {
     pub fn new(base_url: Url, _network: Network) -> anyhow::Result<Self> {
         Ok(Self {
             chaininfo_url: base_url.join("rest/chaininfo.json")?,
             raw_block_by_hash_url: base_url.join("rest/block/")?,
+            mempool_contents_url: base_url.join("rest/mempool/contents.json")?,
+            raw_transaction_by_id_url: base_url.join("rest/tx/")?,
             client: Client::new(),
         })
     }
@@ -35,10 +49,16 @@ impl BitcoindConnector {
             .join(&format!("{}.hex", block_hash))
             .expect("building url should work")
     }
+
+    fn raw_transaction_by_id_url(&self, txid: &Txid) -> Url {
+        self.raw_transaction_by_id_url
+            .join(&format!("{}.hex", txid))
+            .expect("building url should work")
+    }
 }
 
 #[async_trait]
-impl LatestBlock for BitcoindConnector {
+impl LatestBlock for BitcoindRestConnector {
     type Block = bitcoin::Block;
 
     async fn latest_block(&self) -> anyhow::Result<Self::Block> {
@@ -59,7 +79,7 @@ impl LatestBlock for BitcoindConnector {
 }
 
 #[async_trait]
-impl BlockByHash for BitcoindConnector {
+impl BlockByHash for BitcoindRestConnector {
     type Block = bitcoin::Block;
     type BlockHash = bitcoin::BlockHash;
 
@@ -79,8 +99,34 @@ impl BlockByHash for BitcoindConnector {
 }
 
 #[async_trait]
-impl FetchNetworkId<Network> for BitcoindConnector {
-    async fn network_id(&self) -> anyhow::Result<Network> {
+impl Mempool for BitcoindRestConnector {
+    async fn mempool_txids(&self) -> anyhow::Result<Vec<Txid>> {
+        let mempool_contents_url = self.mempool_contents_url.clone();
+
+        // The entries are keyed by txid, we are not interested in their details.
+        let contents = self
+            .client
+            .get(mempool_contents_url)
+            .send()
+            .await?
+            .json::<HashMap<Txid, IgnoredAny>>()
+            .await?;
+
+        Ok(contents.into_iter().map(|(txid, _)| txid).collect())
+    }
+
+    async fn transaction_by_id(&self, txid: Txid) -> anyhow::Result<bitcoin::Transaction> {
+        let url = self.raw_transaction_by_id_url(&txid);
+        let transaction =
+            bitcoin_http_request_for_hex_encoded_object::<bitcoin::Transaction>(url, &self.client)
+                .await?;
+
+        Ok(transaction)
+    }
+}
+
+impl BitcoindRestConnector {
+    async fn chain_info(&self) -> anyhow::Result<ChainInfo> {
         let client = self.client.clone();
         let chaininfo_url = self.chaininfo_url.clone();
 
@@ -93,7 +139,21 @@ impl FetchNetworkId<Network> for BitcoindConnector {
 
         tracing::debug!("Fetched chain info: {:?} from bitcoind", chain_info);
 
-        Ok(chain_info.chain)
+        Ok(chain_info)
+    }
+}
+
+#[async_trait]
+impl FetchNetworkId<Network> for BitcoindRestConnector {
+    async fn network_id(&self) -> anyhow::Result<Network> {
+        Ok(self.chain_info().await?.chain)
+    }
+}
+
+#[async_trait]
+impl FetchSyncState for BitcoindRestConnector {
+    async fn is_syncing(&self) -> anyhow::Result<bool> {
+        Ok(self.chain_info().await?.initialblockdownload)
     }
 }
 
@@ -143,7 +203,7 @@ mod tests {
     #[test]
     fn constructor_does_not_fail_for_base_urls() {
         for base_url in base_urls() {
-            let result = BitcoindConnector::new(base_url, Network::Regtest);
+            let result = BitcoindRestConnector::new(base_url, Network::Regtest);
 
             assert!(result.is_ok());
         }
@@ -155,7 +215,7 @@ mod tests {
     fn build_sub_url_should_never_fail() {
         fn prop(hash: Quickcheck<BlockHash>) -> bool {
             for base_url in base_urls() {
-                let connector = BitcoindConnector::new(base_url, Network::Regtest).unwrap();
+                let connector = BitcoindRestConnector::new(base_url, Network::Regtest).unwrap();
 
                 connector.raw_block_by_hash_url(&hash);
             }
@@ -169,7 +229,7 @@ mod tests {
     #[test]
     fn given_different_base_urls_correct_sub_urls_are_built() {
         for base_url in base_urls() {
-            let connector = BitcoindConnector::new(base_url, Network::Regtest).unwrap();
+            let connector = BitcoindRestConnector::new(base_url, Network::Regtest).unwrap();
 
             let chaininfo_url = connector.chaininfo_url.clone();
             assert_eq!(
@@ -183,6 +243,19 @@ mod tests {
                     .unwrap();
             let raw_block_by_hash_url = connector.raw_block_by_hash_url(&block_id.into());
             assert_eq!(raw_block_by_hash_url, Url::parse("http://localhost:8080/rest/block/2a593b84b1943521be01f97a59fc7feba30e7e8527fb2ba20b0158ca09016d02.hex").unwrap());
+
+            let mempool_contents_url = connector.mempool_contents_url.clone();
+            assert_eq!(
+                mempool_contents_url,
+                Url::parse("http://localhost:8080/rest/mempool/contents.json").unwrap()
+            );
+
+            let txid: sha256d::Hash =
+                "0a7e8e7b6b0cd43d1e4ad6d9f3e1e1e0b1f8d7c1f0f6b3b0a9f0e8e7e6d5c4b3"
+                    .parse()
+                    .unwrap();
+            let raw_transaction_by_id_url = connector.raw_transaction_by_id_url(&txid.into());
+            assert_eq!(raw_transaction_by_id_url, Url::parse("http://localhost:8080/rest/tx/0a7e8e7b6b0cd43d1e4ad6d9f3e1e1e0b1f8d7c1f0f6b3b0a9f0e8e7e6d5c4b3.hex").unwrap());
         }
     }
 
@@ -212,4 +285,34 @@ mod tests {
         let info = serde_json::from_str::<ChainInfo>(chain_info).unwrap();
         assert_eq!(info.chain, Network::Regtest);
     }
+
+    #[test]
+    fn deserializes_initial_block_download() {
+        let chain_info = r#"{
+    "chain": "main",
+    "bestblockhash": "00000000000000c473d592c8637824b8362d522af18bfb1d0e92107b96ecdc5c",
+    "initialblockdownload": true
+  }
+  "#;
+        let info = serde_json::from_str::<ChainInfo>(chain_info).unwrap();
+        assert!(info.initialblockdownload);
+    }
+
+    #[test]
+    fn can_deserialize_txids_from_mempool_contents() {
+        let contents = r#"{
+    "0a7e8e7b6b0cd43d1e4ad6d9f3e1e1e0b1f8d7c1f0f6b3b0a9f0e8e7e6d5c4b3": {
+      "fees": { "base": 0.00000141 },
+      "vsize": 141,
+      "depends": []
+    }
+  }
+  "#;
+        let txids = serde_json::from_str::<HashMap<Txid, IgnoredAny>>(contents).unwrap();
+
+        let expected: Txid = "0a7e8e7b6b0cd43d1e4ad6d9f3e1e1e0b1f8d7c1f0f6b3b0a9f0e8e7e6d5c4b3"
+            .parse()
+            .unwrap();
+        assert!(txids.contains_key(&expected));
+    }
 }
@@ -0,0 +1,175 @@
+use crate::{
+    btsieve::{
+        bitcoin::{decode_response, ChainInfo, Mempool},
+        BlockByHash, LatestBlock,
+    },
+    config::{
+        validation::{FetchNetworkId, FetchSyncState},
+        BitcoindRpcAuth,
+    },
+    jsonrpc,
+};
+use anyhow::Context;
+use async_trait::async_trait;
+use bitcoin::{BlockHash, Network, Txid};
+
+/// Talks to bitcoind through its authenticated JSON-RPC interface.
+#[derive(Clone, Debug)]
+pub struct BitcoindRpcConnector {
+    client: jsonrpc::Client,
+}
+
+impl BitcoindRpcConnector {
+    pub fn new(node_url: reqwest::Url, auth: &BitcoindRpcAuth) -> anyhow::Result<Self> {
+        Ok(Self {
+            client: rpc_client(node_url, auth)?,
+        })
+    }
+}
+
+/// Builds a JSON-RPC client that authenticates against bitcoind.
+pub fn rpc_client(
+    node_url: reqwest::Url,
+    auth: &BitcoindRpcAuth,
+) -> anyhow::Result<jsonrpc::Client> {
+    let (username, password) = match auth {
+        BitcoindRpcAuth::Cookie { cookie_file } => {
+            let cookie = std::fs::read_to_string(cookie_file)
+                .with_context(|| format!("failed to read cookie file {}", cookie_file.display()))?;
+
+            parse_cookie(&cookie)?
+        }
+        BitcoindRpcAuth::UserPassword { user, password } => {
+            (user.clone(), password.expose().to_owned())
+        }
+    };
+
+    Ok(jsonrpc::Client::with_basic_auth(
+        node_url, username, password,
+    ))
+}
+
+/// The cookie file contains a single line of the form `username:password`.
+fn parse_cookie(cookie: &str) -> anyhow::Result<(String, String)> {
+    let mut parts = cookie.trim().splitn(2, ':');
+
+    match (parts.next(), parts.next()) {
+        (Some(username), Some(password)) => Ok((username.to_owned(), password.to_owned())),
+        _ => Err(anyhow::anyhow!(
+            "cookie file is not of the form username:password"
+        )),
+    }
+}
+
+#[async_trait]
+impl LatestBlock for BitcoindRpcConnector {
+    type Block = bitcoin::Block;
+
+    async fn latest_block(&self) -> anyhow::Result<Self::Block> {
+        let block_hash: BlockHash = self
+            .client
+            .send::<Vec<()>, _>(jsonrpc::Request::new("getbestblockhash", vec![]))
+            .await?;
+
+        let block = self.block_by_hash(block_hash).await?;
+
+        Ok(block)
+    }
+}
+
+#[async_trait]
+impl BlockByHash for BitcoindRpcConnector {
+    type Block = bitcoin::Block;
+    type BlockHash = bitcoin::BlockHash;
+
+    async fn block_by_hash(&self, block_hash: Self::BlockHash) -> anyhow::Result<Self::Block> {
+        // Verbosity 0 returns the block as a hex-encoded string.
+        let block_hex: String = self
+            .client
+            .send(jsonrpc::Request::new("getblock", vec![
+                jsonrpc::serialize(&block_hash)?,
+                jsonrpc::serialize(0)?,
+            ]))
+            .await?;
+        let block = decode_response::<Self::Block>(block_hex)?;
+
+        tracing::debug!(
+            "Fetched block {} with {} transactions from bitcoind",
+            block_hash,
+            block.txdata.len()
+        );
+
+        Ok(block)
+    }
+}
+
+#[async_trait]
+impl Mempool for BitcoindRpcConnector {
+    async fn mempool_txids(&self) -> anyhow::Result<Vec<Txid>> {
+        let txids = self
+            .client
+            .send::<Vec<()>, _>(jsonrpc::Request::new("getrawmempool", vec![]))
+            .await?;
+
+        Ok(txids)
+    }
+
+    async fn transaction_by_id(&self, txid: Txid) -> anyhow::Result<bitcoin::Transaction> {
+        let transaction_hex: String = self
+            .client
+            .send(jsonrpc::Request::new("getrawtransaction", vec![
+                jsonrpc::serialize(&txid)?,
+            ]))
+            .await?;
+        let transaction = decode_response::<bitcoin::Transaction>(transaction_hex)?;
+
+        Ok(transaction)
+    }
+}
+
+impl BitcoindRpcConnector {
+    async fn chain_info(&self) -> anyhow::Result<ChainInfo> {
+        let chain_info: ChainInfo = self
+            .client
+            .send::<Vec<()>, _>(jsonrpc::Request::new("getblockchaininfo", vec![]))
+            .await?;
+
+        tracing::debug!("Fetched chain info: {:?} from bitcoind", chain_info);
+
+        Ok(chain_info)
+    }
+}
+
+#[async_trait]
+impl FetchNetworkId<Network> for BitcoindRpcConnector {
+    async fn network_id(&self) -> anyhow::Result<Network> {
+        Ok(self.chain_info().await?.chain)
+    }
+}
+
+#[async_trait]
+impl FetchSyncState for BitcoindRpcConnector {
+    async fn is_syncing(&self) -> anyhow::Result<bool> {
+        Ok(self.chain_info().await?.initialblockdownload)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_cookie_file_contents() {
+        let (username, password) = parse_cookie("__cookie__:a9f4c0d2e8b1\n").unwrap();
+
+        assert_eq!(username, "__cookie__");
+        assert_eq!(password, "a9f4c0d2e8b1");
+    }
+
+    #[test]
+    fn fails_to_parse_cookie_without_separator() {
+        let result = parse_cookie("__cookie__");
+
+        assert!(result.is_err());
+    }
+}
@@ -1,6 +1,13 @@
-use crate::btsieve::{BlockByHash, LatestBlock};
+use crate::{
+    btsieve::{
+        bitcoin::{confirmations, wait_for_finality, Mempool},
+        BlockByHash, BlockStatus, BlockStream, Confirmations, LatestBlock, LatestBlocks,
+        RelevantBlocks, Sieve, StartOfScan, SubscribeLatestBlocks, WaitForFinality,
+    },
+    metrics,
+};
 use async_trait::async_trait;
-use bitcoin::{util::hash::BitcoinHash, Block, BlockHash as Hash, BlockHash};
+use bitcoin::{util::hash::BitcoinHash, Block, BlockHash as Hash, BlockHash, Transaction, Txid};
 use derivative::Derivative;
 use lru::LruCache;
 use std::sync::Arc;
@@ -12,14 +19,17 @@ pub struct Cache<C> {
     pub connector: C,
     #[derivative(Debug = "ignore")]
     pub block_cache: Arc<Mutex<LruCache<BlockHash, Block>>>,
+    #[derivative(Debug = "ignore")]
+    block_stream: BlockStream<Block>,
 }
 
 impl<C> Cache<C> {
-    pub fn new(connector: C, capacity: usize) -> Cache<C> {
+    pub fn new(connector: C, capacity: usize, min_confirmations: u32) -> Cache<C> {
         let block_cache = Arc::new(Mutex::new(LruCache::new(capacity)));
         Cache {
             connector,
             block_cache,
+            block_stream: BlockStream::new(min_confirmations),
         }
     }
 }
@@ -33,6 +43,7 @@ where
 
     async fn latest_block(&self) -> anyhow::Result<Self::Block> {
         let block = self.connector.latest_block().await?;
+        metrics::record_block_fetched("bitcoin");
 
         let block_hash = block.bitcoin_hash();
         let mut guard = self.block_cache.lock().await;
@@ -44,6 +55,25 @@ where
     }
 }
 
+impl<C> SubscribeLatestBlocks for Cache<C>
+where
+    C: LatestBlock<Block = Block> + BlockByHash<Block = Block, BlockHash = Hash> + Clone,
+{
+    type Block = Block;
+
+    fn subscribe_latest_blocks(&self) -> LatestBlocks<Self::Block> {
+        self.block_stream.subscribe(self)
+    }
+
+    fn subscribe_relevant_blocks(
+        &self,
+        start_of_scan: StartOfScan,
+        sieve: Sieve<Self::Block>,
+    ) -> RelevantBlocks<Self::Block> {
+        self.block_stream.watch(self, start_of_scan, sieve)
+    }
+}
+
 #[async_trait]
 impl<C> BlockByHash for Cache<C>
 where
@@ -55,11 +85,14 @@ where
     async fn block_by_hash(&self, block_hash: Self::BlockHash) -> anyhow::Result<Self::Block> {
         if let Some(block) = self.block_cache.lock().await.get(&block_hash) {
             tracing::trace!("Found block in cache: {:x}", block_hash);
+            metrics::record_cache_hit("bitcoin", "block");
             return Ok(block.clone());
         }
+        metrics::record_cache_miss("bitcoin", "block");
 
         let block = self.connector.block_by_hash(block_hash.clone()).await?;
         tracing::trace!("Fetched block from connector: {:x}", block_hash);
+        metrics::record_block_fetched("bitcoin");
 
         // We dropped the lock so at this stage the block may have been inserted by
         // another thread, no worries, inserting the same block twice does not hurt.
@@ -69,3 +102,37 @@ where
         Ok(block)
     }
 }
+
+#[async_trait]
+impl<C> WaitForFinality<Transaction> for Cache<C>
+where
+    C: LatestBlock<Block = Block> + BlockByHash<Block = Block, BlockHash = Hash> + Clone,
+{
+    async fn wait_for_finality(&self, transaction: &Transaction) -> anyhow::Result<BlockStatus> {
+        wait_for_finality(self, transaction).await
+    }
+}
+
+#[async_trait]
+impl<C> Confirmations<Transaction> for Cache<C>
+where
+    C: LatestBlock<Block = Block> + BlockByHash<Block = Block, BlockHash = Hash> + Clone,
+{
+    async fn confirmations(&self, transaction: &Transaction) -> anyhow::Result<Option<u32>> {
+        confirmations(self, transaction).await
+    }
+}
+
+#[async_trait]
+impl<C> Mempool for Cache<C>
+where
+    C: Mempool,
+{
+    async fn mempool_txids(&self) -> anyhow::Result<Vec<Txid>> {
+        self.connector.mempool_txids().await
+    }
+
+    async fn transaction_by_id(&self, txid: Txid) -> anyhow::Result<Transaction> {
+        self.connector.transaction_by_id(txid).await
+    }
+}
@@ -0,0 +1,139 @@
+use crate::{
+    btsieve::{
+        bitcoin::{BitcoindRestConnector, BitcoindRpcConnector, EsploraConnector, Mempool},
+        BlockByHash, LatestBlock,
+    },
+    config::{
+        self,
+        validation::{FetchNetworkId, FetchSyncState},
+    },
+    metrics,
+};
+use async_trait::async_trait;
+use bitcoin::{Network, Txid};
+
+/// Connects to the Bitcoin blockchain through the source selected in the
+/// configuration: bitcoind's REST or JSON-RPC interface, or an Esplora
+/// instance.
+#[derive(Clone, Debug)]
+pub enum BitcoinConnector {
+    BitcoindRest(BitcoindRestConnector),
+    BitcoindRpc(BitcoindRpcConnector),
+    Esplora(EsploraConnector),
+}
+
+impl BitcoinConnector {
+    pub fn new(bitcoin: &config::Bitcoin) -> anyhow::Result<Self> {
+        if let Some(esplora) = &bitcoin.esplora {
+            return Ok(BitcoinConnector::Esplora(EsploraConnector::new(
+                esplora.url.clone(),
+            )));
+        }
+
+        let bitcoind = &bitcoin.bitcoind;
+        let connector = match &bitcoind.rpc_auth {
+            Some(auth) => BitcoinConnector::BitcoindRpc(BitcoindRpcConnector::new(
+                bitcoind.node_url.clone(),
+                auth,
+            )?),
+            None => BitcoinConnector::BitcoindRest(BitcoindRestConnector::new(
+                bitcoind.node_url.clone(),
+                bitcoin.network,
+            )?),
+        };
+
+        Ok(connector)
+    }
+}
+
+#[async_trait]
+impl LatestBlock for BitcoinConnector {
+    type Block = bitcoin::Block;
+
+    async fn latest_block(&self) -> anyhow::Result<Self::Block> {
+        metrics::observe_node_rpc("bitcoin", "latest_block", async {
+            match self {
+                BitcoinConnector::BitcoindRest(connector) => connector.latest_block().await,
+                BitcoinConnector::BitcoindRpc(connector) => connector.latest_block().await,
+                BitcoinConnector::Esplora(connector) => connector.latest_block().await,
+            }
+        })
+        .await
+    }
+}
+
+#[async_trait]
+impl BlockByHash for BitcoinConnector {
+    type Block = bitcoin::Block;
+    type BlockHash = bitcoin::BlockHash;
+
+    async fn block_by_hash(&self, block_hash: Self::BlockHash) -> anyhow::Result<Self::Block> {
+        metrics::observe_node_rpc("bitcoin", "block_by_hash", async {
+            match self {
+                BitcoinConnector::BitcoindRest(connector) => {
+                    connector.block_by_hash(block_hash).await
+                }
+                BitcoinConnector::BitcoindRpc(connector) => {
+                    connector.block_by_hash(block_hash).await
+                }
+                BitcoinConnector::Esplora(connector) => connector.block_by_hash(block_hash).await,
+            }
+        })
+        .await
+    }
+}
+
+#[async_trait]
+impl Mempool for BitcoinConnector {
+    async fn mempool_txids(&self) -> anyhow::Result<Vec<Txid>> {
+        metrics::observe_node_rpc("bitcoin", "mempool_txids", async {
+            match self {
+                BitcoinConnector::BitcoindRest(connector) => connector.mempool_txids().await,
+                BitcoinConnector::BitcoindRpc(connector) => connector.mempool_txids().await,
+                BitcoinConnector::Esplora(connector) => connector.mempool_txids().await,
+            }
+        })
+        .await
+    }
+
+    async fn transaction_by_id(&self, txid: Txid) -> anyhow::Result<bitcoin::Transaction> {
+        metrics::observe_node_rpc("bitcoin", "transaction_by_id", async {
+            match self {
+                BitcoinConnector::BitcoindRest(connector) => {
+                    connector.transaction_by_id(txid).await
+                }
+                BitcoinConnector::BitcoindRpc(connector) => connector.transaction_by_id(txid).await,
+                BitcoinConnector::Esplora(connector) => connector.transaction_by_id(txid).await,
+            }
+        })
+        .await
+    }
+}
+
+#[async_trait]
+impl FetchNetworkId<Network> for BitcoinConnector {
+    async fn network_id(&self) -> anyhow::Result<Network> {
+        metrics::observe_node_rpc("bitcoin", "network_id", async {
+            match self {
+                BitcoinConnector::BitcoindRest(connector) => connector.network_id().await,
+                BitcoinConnector::BitcoindRpc(connector) => connector.network_id().await,
+                BitcoinConnector::Esplora(connector) => connector.network_id().await,
+            }
+        })
+        .await
+    }
+}
+
+#[async_trait]
+impl FetchSyncState for BitcoinConnector {
+    async fn is_syncing(&self) -> anyhow::Result<bool> {
+        metrics::observe_node_rpc("bitcoin", "is_syncing", async {
+            match self {
+                BitcoinConnector::BitcoindRest(connector) => connector.is_syncing().await,
+                BitcoinConnector::BitcoindRpc(connector) => connector.is_syncing().await,
+                BitcoinConnector::Esplora(connector) => connector.is_syncing().await,
+            }
+        })
+        .await
+    }
+}
@@ -0,0 +1,207 @@
+use crate::{
+    btsieve::{bitcoin::Mempool, BlockByHash, LatestBlock},
+    config::validation::{FetchNetworkId, FetchSyncState},
+};
+use async_trait::async_trait;
+use bitcoin::{
+    blockdata::constants::genesis_block, consensus::encode::deserialize, BitcoinHash, BlockHash,
+    Network, Txid,
+};
+use reqwest::{Client, Url};
+
+/// Fetches blocks from an instance of the Esplora HTTP API, as run by
+/// blockstream.info.
+#[derive(Clone, Debug)]
+pub struct EsploraConnector {
+    base_url: Url,
+    client: Client,
+}
+
+impl EsploraConnector {
+    pub fn new(mut base_url: Url) -> Self {
+        // Esplora is commonly served under a path like `/api`, without the trailing
+        // slash all our relative urls would replace the last segment of that path.
+        if !base_url.path().ends_with('/') {
+            let path = format!("{}/", base_url.path());
+            base_url.set_path(&path);
+        }
+
+        Self {
+            base_url,
+            client: Client::new(),
+        }
+    }
+
+    fn tip_hash_url(&self) -> Url {
+        self.base_url
+            .join("blocks/tip/hash")
+            .expect("building url should work")
+    }
+
+    fn raw_block_by_hash_url(&self, block_hash: &BlockHash) -> Url {
+        self.base_url
+            .join(&format!("block/{}/raw", block_hash))
+            .expect("building url should work")
+    }
+
+    fn block_hash_by_height_url(&self, height: u32) -> Url {
+        self.base_url
+            .join(&format!("block-height/{}", height))
+            .expect("building url should work")
+    }
+
+    fn mempool_txids_url(&self) -> Url {
+        self.base_url
+            .join("mempool/txids")
+            .expect("building url should work")
+    }
+
+    fn raw_transaction_by_id_url(&self, txid: &Txid) -> Url {
+        self.base_url
+            .join(&format!("tx/{}/raw", txid))
+            .expect("building url should work")
+    }
+
+    async fn block_hash(&self, url: Url) -> anyhow::Result<BlockHash> {
+        let text = self
+            .client
+            .get(url)
+            .send()
+            .await?
+            .error_for_status()?
+            .text()
+            .await?;
+        let block_hash = text.trim().parse()?;
+
+        Ok(block_hash)
+    }
+
+    async fn raw_object<T>(&self, url: Url) -> anyhow::Result<T>
+    where
+        T: bitcoin::consensus::Decodable,
+    {
+        let bytes = self
+            .client
+            .get(url)
+            .send()
+            .await?
+            .error_for_status()?
+            .bytes()
+            .await?;
+        let object = deserialize(&bytes)?;
+
+        Ok(object)
+    }
+}
+
+#[async_trait]
+impl LatestBlock for EsploraConnector {
+    type Block = bitcoin::Block;
+
+    async fn latest_block(&self) -> anyhow::Result<Self::Block> {
+        let block_hash = self.block_hash(self.tip_hash_url()).await?;
+        let block = self.block_by_hash(block_hash).await?;
+
+        Ok(block)
+    }
+}
+
+#[async_trait]
+impl BlockByHash for EsploraConnector {
+    type Block = bitcoin::Block;
+    type BlockHash = bitcoin::BlockHash;
+
+    async fn block_by_hash(&self, block_hash: Self::BlockHash) -> anyhow::Result<Self::Block> {
+        let block: Self::Block = self
+            .raw_object(self.raw_block_by_hash_url(&block_hash))
+            .await?;
+
+        tracing::debug!(
+            "Fetched block {} with {} transactions from esplora",
+            block_hash,
+            block.txdata.len()
+        );
+
+        Ok(block)
+    }
+}
+
+#[async_trait]
+impl Mempool for EsploraConnector {
+    async fn mempool_txids(&self) -> anyhow::Result<Vec<Txid>> {
+        let txids = self
+            .client
+            .get(self.mempool_txids_url())
+            .send()
+            .await?
+            .error_for_status()?
+            .json::<Vec<Txid>>()
+            .await?;
+
+        Ok(txids)
+    }
+
+    async fn transaction_by_id(&self, txid: Txid) -> anyhow::Result<bitcoin::Transaction> {
+        self.raw_object(self.raw_transaction_by_id_url(&txid)).await
+    }
+}
+
+/// Esplora does not tell us which network it serves, hence we compare its
+/// genesis block against the well-known ones.
+#[async_trait]
+impl FetchNetworkId<Network> for EsploraConnector {
+    async fn network_id(&self) -> anyhow::Result<Network> {
+        let genesis_hash = self.block_hash(self.block_hash_by_height_url(0)).await?;
+
+        let network = vec![Network::Bitcoin, Network::Testnet, Network::Regtest]
+            .into_iter()
+            .find(|network| genesis_block(*network).bitcoin_hash() == genesis_hash)
+            .ok_or_else(|| anyhow::anyhow!("unknown genesis block {}", genesis_hash))?;
+
+        tracing::debug!("Fetched network: {:?} from esplora", network);
+
+        Ok(network)
+    }
+}
+
+/// Esplora does not tell whether its node is still syncing, it only serves the
+/// blocks it has indexed.
+#[async_trait]
+impl FetchSyncState for EsploraConnector {
+    async fn is_syncing(&self) -> anyhow::Result<bool> {
+        Ok(false)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn given_different_base_urls_correct_sub_urls_are_built() {
+        let base_urls: Vec<Url> = vec![
+            "http://localhost:3000/api".parse().unwrap(),
+            "http://localhost:3000/api/".parse().unwrap(),
+        ];
+
+        for base_url in base_urls {
+            let connector = EsploraConnector::new(base_url);
+
+            assert_eq!(
+                connector.tip_hash_url(),
+                Url::parse("http://localhost:3000/api/blocks/tip/hash").unwrap()
+            );
+
+            let block_hash: BlockHash =
+                "2a593b84b1943521be01f97a59fc7feba30e7e8527fb2ba20b0158ca09016d02"
+                    .parse()
+                    .unwrap();
+            assert_eq!(connector.raw_block_by_hash_url(&block_hash), Url::parse("http://localhost:3000/api/block/2a593b84b1943521be01f97a59fc7feba30e7e8527fb2ba20b0158ca09016d02/raw").unwrap());
+
+            assert_eq!(
+                connector.block_hash_by_height_url(0),
+                Url::parse("http://localhost:3000/api/block-height/0").unwrap()
+            );
+        }
+    }
+}
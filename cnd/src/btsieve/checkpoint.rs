@@ -0,0 +1,480 @@
+use crate::btsieve::{BlockHash, BlockHeight, Predates};
+use chrono::{Duration, NaiveDateTime};
+use futures::future;
+use std::{
+    fmt::LowerHex,
+    sync::{Arc, Mutex},
+};
+use tokio::sync::watch;
+
+/// Block timestamps are not strictly increasing, a block mined after the
+/// checkpoint may claim to be up to this much older than the checkpoint.
+const TIMESTAMP_TOLERANCE_HOURS: i64 = 2;
+
+/// Abstracts over the ability of getting the time a block was mined at.
+pub trait BlockTimestamp {
+    fn block_timestamp(&self) -> NaiveDateTime;
+}
+
+/// A block up to which a ledger was scanned: none of the events we were
+/// waiting for happened in this block or any block before it.
+///
+/// Like for the start of a swap, the height is preferred over the timestamp,
+/// the latter is only used if either block doesn't tell its height.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Checkpoint {
+    pub block_hash: String,
+    pub height: Option<u32>,
+    pub timestamp: NaiveDateTime,
+}
+
+impl Checkpoint {
+    pub fn of_block<B, H>(block: &B) -> Self
+    where
+        B: BlockHash<BlockHash = H> + BlockHeight + BlockTimestamp,
+        H: LowerHex,
+    {
+        Self {
+            block_hash: format!("{:x}", block.block_hash()),
+            height: block.block_height(),
+            timestamp: block.block_timestamp(),
+        }
+    }
+
+    fn is_older_than(&self, other: &Checkpoint) -> bool {
+        match (self.height, other.height) {
+            (Some(height), Some(other_height)) => height < other_height,
+            _ => self.timestamp < other.timestamp,
+        }
+    }
+}
+
+/// Where a swap starts on a ledger.
+///
+/// Block timestamps may be off by hours, hence we prefer the height of the
+/// first block that has to be looked at. The timestamp is only used for blocks
+/// that don't tell their height and for swaps for which no height was recorded.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct StartOfSwap {
+    pub timestamp: NaiveDateTime,
+    pub height: Option<u32>,
+}
+
+impl StartOfSwap {
+    /// Returns `true` if the given block was mined before the swap started.
+    pub fn is_after<B>(&self, block: &B) -> bool
+    where
+        B: Predates + BlockHeight,
+    {
+        match (self.height, block.block_height()) {
+            (Some(start_height), Some(height)) => height < start_height,
+            _ => block.predates(self.timestamp),
+        }
+    }
+}
+
+impl From<NaiveDateTime> for StartOfSwap {
+    fn from(timestamp: NaiveDateTime) -> Self {
+        Self {
+            timestamp,
+            height: None,
+        }
+    }
+}
+
+/// Where a watcher starts looking for blocks.
+///
+/// Without a checkpoint, all blocks since the start of the swap are looked at.
+/// Otherwise, we only go back as far as the checkpoint.
+#[derive(Clone, Debug)]
+pub struct StartOfScan {
+    start_of_swap: StartOfSwap,
+    checkpoint: Option<Checkpoint>,
+    progress: Option<ProgressSlot>,
+}
+
+impl StartOfScan {
+    pub fn new(start_of_swap: StartOfSwap, checkpoint: Option<Checkpoint>) -> Self {
+        Self {
+            start_of_swap,
+            checkpoint,
+            progress: None,
+        }
+    }
+
+    /// Returns `true` if there is no need to look at blocks before the given
+    /// one.
+    pub fn is_reached_by<B, H>(&self, block: &B) -> bool
+    where
+        B: Predates + BlockHeight + BlockHash<BlockHash = H>,
+        H: LowerHex,
+    {
+        if self.start_of_swap.is_after(block) {
+            return true;
+        }
+
+        let checkpoint = match &self.checkpoint {
+            Some(checkpoint) => checkpoint,
+            None => return false,
+        };
+
+        if format!("{:x}", block.block_hash()) == checkpoint.block_hash {
+            return true;
+        }
+
+        // A block at the height of the checkpoint with a different hash replaced
+        // the checkpoint in a chain reorganisation, it has not been looked at.
+        match (checkpoint.height, block.block_height()) {
+            (Some(checkpoint_height), Some(height)) => height < checkpoint_height,
+            _ => block.predates(checkpoint.timestamp - Duration::hours(TIMESTAMP_TOLERANCE_HOURS)),
+        }
+    }
+
+    /// Returns the height of the first block that has to be looked at, `None`
+    /// if neither the start of the swap nor the checkpoint tell a height.
+    ///
+    /// The block at the height of the checkpoint is looked at again, it may
+    /// have been replaced in a chain reorganisation.
+    pub fn first_block_height(&self) -> Option<u32> {
+        let checkpoint_height = self
+            .checkpoint
+            .as_ref()
+            .and_then(|checkpoint| checkpoint.height);
+
+        match (self.start_of_swap.height, checkpoint_height) {
+            (Some(start_height), Some(checkpoint_height)) => {
+                Some(start_height.max(checkpoint_height))
+            }
+            (start_height, checkpoint_height) => checkpoint_height.or(start_height),
+        }
+    }
+
+    /// Records that all blocks up to and including the one of the checkpoint
+    /// have been looked at.
+    pub fn report(&self, checkpoint: Checkpoint) {
+        if let Some(progress) = &self.progress {
+            progress.report(checkpoint)
+        }
+    }
+}
+
+impl From<StartOfSwap> for StartOfScan {
+    fn from(start_of_swap: StartOfSwap) -> Self {
+        Self::new(start_of_swap, None)
+    }
+}
+
+impl From<NaiveDateTime> for StartOfScan {
+    fn from(start_of_swap: NaiveDateTime) -> Self {
+        Self::new(start_of_swap.into(), None)
+    }
+}
+
+/// Combines the progress of several watchers into a single checkpoint.
+///
+/// The checkpoint only advances once every watcher started through this has
+/// reported, it is the oldest block any of them reported.
+#[derive(Debug)]
+pub struct ScanProgress {
+    reports: Arc<Mutex<Reports>>,
+    receiver: watch::Receiver<Option<Checkpoint>>,
+}
+
+#[derive(Debug)]
+struct Reports {
+    checkpoints: Vec<Option<Checkpoint>>,
+    combined: Option<Checkpoint>,
+    sender: watch::Sender<Option<Checkpoint>>,
+}
+
+#[derive(Clone, Debug)]
+struct ProgressSlot {
+    reports: Arc<Mutex<Reports>>,
+    index: usize,
+}
+
+impl ScanProgress {
+    pub fn new() -> Self {
+        let (sender, receiver) = watch::channel(None);
+
+        Self {
+            reports: Arc::new(Mutex::new(Reports {
+                checkpoints: Vec::new(),
+                combined: None,
+                sender,
+            })),
+            receiver,
+        }
+    }
+
+    /// Returns where another watcher starts looking for blocks, the progress
+    /// of that watcher is taken into account from now on.
+    pub fn start_of_scan(
+        &self,
+        start_of_swap: StartOfSwap,
+        checkpoint: Option<Checkpoint>,
+    ) -> StartOfScan {
+        let mut reports = self.reports.lock().expect("mutex is not poisoned");
+        reports.checkpoints.push(None);
+
+        StartOfScan {
+            start_of_swap,
+            checkpoint,
+            progress: Some(ProgressSlot {
+                reports: Arc::clone(&self.reports),
+                index: reports.checkpoints.len() - 1,
+            }),
+        }
+    }
+
+    /// Waits until the checkpoint advances.
+    pub async fn next_checkpoint(&mut self) -> Checkpoint {
+        while let Some(checkpoint) = self.receiver.recv().await {
+            if let Some(checkpoint) = checkpoint {
+                return checkpoint;
+            }
+        }
+
+        // We hold on to the sender ourselves, hence this is unreachable.
+        future::pending().await
+    }
+}
+
+impl Default for ScanProgress {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Reports {
+    /// The oldest checkpoint reported, `None` until every watcher reported.
+    fn oldest(&self) -> Option<Checkpoint> {
+        let mut oldest: Option<&Checkpoint> = None;
+
+        for checkpoint in &self.checkpoints {
+            let checkpoint = checkpoint.as_ref()?;
+
+            if oldest.map_or(true, |oldest| checkpoint.is_older_than(oldest)) {
+                oldest = Some(checkpoint);
+            }
+        }
+
+        oldest.cloned()
+    }
+}
+
+impl ProgressSlot {
+    fn report(&self, checkpoint: Checkpoint) {
+        let mut reports = self.reports.lock().expect("mutex is not poisoned");
+        reports.checkpoints[self.index] = Some(checkpoint);
+
+        let oldest = match reports.oldest() {
+            Some(oldest) => oldest,
+            None => return,
+        };
+
+        if reports.combined.as_ref() != Some(&oldest) {
+            reports.combined = Some(oldest.clone());
+            let _ = reports.sender.broadcast(Some(oldest));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn checkpoint(block_hash: &str, timestamp: i64) -> Checkpoint {
+        Checkpoint {
+            block_hash: block_hash.to_owned(),
+            height: None,
+            timestamp: NaiveDateTime::from_timestamp(timestamp, 0),
+        }
+    }
+
+    struct FakeBlock {
+        hash: u32,
+        height: Option<u32>,
+        timestamp: i64,
+    }
+
+    impl BlockHash for FakeBlock {
+        type BlockHash = u32;
+
+        fn block_hash(&self) -> u32 {
+            self.hash
+        }
+    }
+
+    impl Predates for FakeBlock {
+        fn predates(&self, timestamp: NaiveDateTime) -> bool {
+            self.timestamp < timestamp.timestamp()
+        }
+    }
+
+    impl BlockHeight for FakeBlock {
+        fn block_height(&self) -> Option<u32> {
+            self.height
+        }
+    }
+
+    #[test]
+    fn start_of_swap_prefers_height_over_timestamp() {
+        let start_of_swap = StartOfSwap {
+            timestamp: NaiveDateTime::from_timestamp(1000, 0),
+            height: Some(100),
+        };
+
+        // Claims to be older than the swap but is above the start height.
+        let late_block_with_early_timestamp = FakeBlock {
+            hash: 1,
+            height: Some(100),
+            timestamp: 900,
+        };
+        let early_block = FakeBlock {
+            hash: 2,
+            height: Some(99),
+            timestamp: 1100,
+        };
+        let block_without_height = FakeBlock {
+            hash: 3,
+            height: None,
+            timestamp: 900,
+        };
+
+        assert!(!start_of_swap.is_after(&late_block_with_early_timestamp));
+        assert!(start_of_swap.is_after(&early_block));
+        assert!(start_of_swap.is_after(&block_without_height));
+    }
+
+    #[test]
+    fn start_of_scan_compares_heights_with_the_checkpoint() {
+        let start_of_swap = StartOfSwap {
+            timestamp: NaiveDateTime::from_timestamp(0, 0),
+            height: Some(1),
+        };
+        let start_of_scan = StartOfScan::new(
+            start_of_swap,
+            Some(Checkpoint {
+                block_hash: "a".to_owned(),
+                height: Some(100),
+                timestamp: NaiveDateTime::from_timestamp(10_000, 0),
+            }),
+        );
+
+        let checkpoint_block = FakeBlock {
+            hash: 0xa,
+            height: Some(100),
+            timestamp: 10_000,
+        };
+        // Replaced the checkpoint in a chain reorganisation.
+        let block_at_checkpoint_height = FakeBlock {
+            hash: 0xb,
+            height: Some(100),
+            timestamp: 10_000,
+        };
+        // Claims to be hours older than the checkpoint but is above it.
+        let later_block_with_early_timestamp = FakeBlock {
+            hash: 0xc,
+            height: Some(101),
+            timestamp: 0,
+        };
+        let block_below_checkpoint = FakeBlock {
+            hash: 0xd,
+            height: Some(99),
+            timestamp: 20_000,
+        };
+
+        assert!(start_of_scan.is_reached_by(&checkpoint_block));
+        assert!(!start_of_scan.is_reached_by(&block_at_checkpoint_height));
+        assert!(!start_of_scan.is_reached_by(&later_block_with_early_timestamp));
+        assert!(start_of_scan.is_reached_by(&block_below_checkpoint));
+    }
+
+    #[test]
+    fn first_block_height_is_the_higher_of_start_and_checkpoint() {
+        let start_of_swap = StartOfSwap {
+            timestamp: NaiveDateTime::from_timestamp(0, 0),
+            height: Some(50),
+        };
+        let checkpoint_with_height = Checkpoint {
+            height: Some(100),
+            ..checkpoint("a", 10_000)
+        };
+
+        assert_eq!(
+            StartOfScan::new(start_of_swap, Some(checkpoint_with_height.clone()))
+                .first_block_height(),
+            Some(100)
+        );
+        assert_eq!(
+            StartOfScan::new(start_of_swap, Some(checkpoint("a", 10_000))).first_block_height(),
+            Some(50)
+        );
+        assert_eq!(
+            StartOfScan::new(
+                StartOfSwap::from(NaiveDateTime::from_timestamp(0, 0)),
+                Some(checkpoint_with_height)
+            )
+            .first_block_height(),
+            Some(100)
+        );
+        assert_eq!(
+            StartOfScan::from(NaiveDateTime::from_timestamp(0, 0)).first_block_height(),
+            None
+        );
+    }
+
+    #[tokio::test]
+    async fn checkpoint_is_the_oldest_block_reported_by_all_watchers() {
+        let start_of_swap = StartOfSwap::from(NaiveDateTime::from_timestamp(0, 0));
+        let mut progress = ScanProgress::new();
+        let first = progress.start_of_scan(start_of_swap, None);
+        let second = progress.start_of_scan(start_of_swap, None);
+
+        first.report(checkpoint("b", 20));
+        second.report(checkpoint("a", 10));
+
+        assert_eq!(progress.next_checkpoint().await, checkpoint("a", 10));
+
+        second.report(checkpoint("c", 30));
+
+        assert_eq!(progress.next_checkpoint().await, checkpoint("b", 20));
+    }
+
+    #[tokio::test]
+    async fn checkpoint_prefers_heights_over_timestamps() {
+        let start_of_swap = StartOfSwap::from(NaiveDateTime::from_timestamp(0, 0));
+        let mut progress = ScanProgress::new();
+        let first = progress.start_of_scan(start_of_swap, None);
+        let second = progress.start_of_scan(start_of_swap, None);
+
+        let lower_block_with_later_timestamp = Checkpoint {
+            height: Some(10),
+            ..checkpoint("a", 20)
+        };
+        first.report(lower_block_with_later_timestamp.clone());
+        second.report(Checkpoint {
+            height: Some(11),
+            ..checkpoint("b", 10)
+        });
+
+        assert_eq!(
+            progress.next_checkpoint().await,
+            lower_block_with_later_timestamp
+        );
+    }
+
+    #[test]
+    fn checkpoint_does_not_advance_before_every_watcher_reported() {
+        let start_of_swap = StartOfSwap::from(NaiveDateTime::from_timestamp(0, 0));
+        let progress = ScanProgress::new();
+        let first = progress.start_of_scan(start_of_swap, None);
+        let _second = progress.start_of_scan(start_of_swap, None);
+
+        first.report(checkpoint("a", 10));
+
+        let reports = progress.reports.lock().unwrap();
+        assert_eq!(reports.combined, None);
+    }
+}
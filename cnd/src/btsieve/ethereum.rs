@@ -1,22 +1,41 @@
 mod cache;
+mod new_heads;
 mod web3_connector;
 
 pub use self::{cache::Cache, web3_connector::Web3Connector};
 use crate::{
     btsieve::{
-        find_relevant_blocks, BlockByHash, BlockHash, LatestBlock, Predates, PreviousBlockHash,
+        wait_for_block_finality, wait_for_confirmations, BlockByHash, BlockHash, BlockHeight,
+        BlockStatus, BlockTimestamp, Checkpoint, Predates, PreviousBlockHash, StartOfScan,
+        SubscribeLatestBlocks,
     },
-    ethereum::{Address, Block, Bytes, Hash, Input, Log, Transaction, TransactionReceipt, U256},
+    ethereum::{Address, Block, Bytes, Hash, Log, Transaction, TransactionReceipt, U256},
 };
 use async_trait::async_trait;
 use chrono::NaiveDateTime;
-use genawaiter::{sync::Gen, GeneratorState};
+use std::{cmp, sync::Arc};
+
+/// Number of blocks below the latest one whose logs are queried again when
+/// watching for an event, a chain reorganisation may have replaced them.
+const REORG_MARGIN: u32 = 12;
 
 #[async_trait]
 pub trait ReceiptByHash: Send + Sync + 'static {
     async fn receipt_by_hash(&self, transaction_hash: Hash) -> anyhow::Result<TransactionReceipt>;
 }
 
+/// Abstracts over the ability to query the logs matching an event within a
+/// range of blocks, as done by `eth_getLogs`.
+#[async_trait]
+pub trait GetLogs: Send + Sync + 'static {
+    async fn get_logs(
+        &self,
+        event: &Event,
+        from_block: U256,
+        to_block: U256,
+    ) -> anyhow::Result<Vec<Log>>;
+}
+
 impl BlockHash for Block {
     type BlockHash = Hash;
 
@@ -36,19 +55,23 @@ impl PreviousBlockHash for Block {
 
 pub async fn watch_for_contract_creation<C>(
     blockchain_connector: &C,
-    start_of_swap: NaiveDateTime,
+    start_of_scan: impl Into<StartOfScan>,
     bytecode: &Bytes,
 ) -> anyhow::Result<(Transaction, Address)>
 where
-    C: LatestBlock<Block = Block> + BlockByHash<Block = Block, BlockHash = Hash> + ReceiptByHash,
+    C: SubscribeLatestBlocks<Block = Block>
+        + BlockByHash<Block = Block, BlockHash = Hash>
+        + ReceiptByHash,
 {
+    let bytecode = bytecode.clone();
+
     let (transaction, receipt) =
-        matching_transaction_and_receipt(blockchain_connector, start_of_swap, |transaction| {
+        matching_transaction_and_receipt(blockchain_connector, start_of_scan, move |transaction| {
             // transaction.to address is None if, and only if, the transaction
             // creates a contract.
 
             let is_contract_creation = transaction.to.is_none();
-            let is_expected_contract = &transaction.input == bytecode;
+            let is_expected_contract = transaction.input == bytecode;
 
             if !is_contract_creation {
                 tracing::trace!("rejected because transaction doesn't create a contract");
@@ -81,20 +104,153 @@ where
     }
 }
 
+/// Watches for a log matching the given event by querying the logs of all
+/// blocks since the start of the scan instead of walking the blocks and
+/// fetching every receipt.
+///
+/// Each new block only extends the range by the blocks that were not queried
+/// yet and the last `REORG_MARGIN` ones. We only walk back from the latest
+/// block to find the start of the scan if no block height is known for it.
 pub async fn watch_for_event<C>(
-    blockchain_connector: &C,
-    start_of_swap: NaiveDateTime,
+    connector: &C,
+    start_of_scan: impl Into<StartOfScan>,
     event: Event,
 ) -> anyhow::Result<(Transaction, Log)>
 where
-    C: LatestBlock<Block = Block> + BlockByHash<Block = Block, BlockHash = Hash> + ReceiptByHash,
+    C: SubscribeLatestBlocks<Block = Block>
+        + BlockByHash<Block = Block, BlockHash = Hash>
+        + GetLogs,
+{
+    let start_of_scan = start_of_scan.into();
+    let mut latest_blocks = connector.subscribe_latest_blocks();
+    let mut latest_block = latest_blocks.next().await?;
+
+    let mut from_block = match start_of_scan.first_block_height() {
+        Some(height) => U256::from(height),
+        None => first_block_number_since(connector, latest_block.clone(), &start_of_scan).await?,
+    };
+
+    loop {
+        let to_block = block_number(&latest_block)?;
+
+        // The swap might have started after the latest block.
+        if from_block <= to_block {
+            let logs = connector.get_logs(&event, from_block, to_block).await?;
+            let mut reverted = false;
+
+            for log in logs
+                .into_iter()
+                .filter(|log| log_matches_event(&event, log))
+            {
+                // Pending logs are neither in a block nor do they have a transaction yet.
+                let (block_hash, transaction_hash) = match (log.block_hash, log.transaction_hash) {
+                    (Some(block_hash), Some(transaction_hash)) => (block_hash, transaction_hash),
+                    _ => continue,
+                };
+
+                let block = connector.block_by_hash(block_hash).await?;
+                let transaction = match block
+                    .transactions
+                    .iter()
+                    .find(|transaction| transaction.hash == transaction_hash)
+                {
+                    Some(transaction) => transaction.clone(),
+                    None => {
+                        tracing::warn!("log references a transaction that is not in its block");
+                        continue;
+                    }
+                };
+                tracing::info!("transaction {:x} matched", transaction_hash);
+
+                match wait_for_confirmations(connector, block_hash, block.parent_hash).await? {
+                    BlockStatus::Confirmed => return Ok((transaction, log)),
+                    // The block got orphaned, query the logs again once the chain moved on.
+                    BlockStatus::Reverted => {
+                        reverted = true;
+                        break;
+                    }
+                }
+            }
+
+            if !reverted {
+                start_of_scan.report(Checkpoint::of_block(&latest_block));
+
+                let next_block = to_block + 1;
+                from_block = cmp::max(
+                    from_block,
+                    next_block.saturating_sub(U256::from(REORG_MARGIN)),
+                );
+            }
+        }
+
+        latest_block = latest_blocks.next().await?;
+    }
+}
+
+/// Walks back from the given block to find the number of the first block after
+/// the start of the scan.
+async fn first_block_number_since<C>(
+    connector: &C,
+    latest_block: Block,
+    start_of_scan: &StartOfScan,
+) -> anyhow::Result<U256>
+where
+    C: BlockByHash<Block = Block, BlockHash = Hash>,
+{
+    let mut block = latest_block;
+
+    while !start_of_scan.is_reached_by(&block) {
+        let number = block_number(&block)?;
+        if number.is_zero() {
+            return Ok(number);
+        }
+
+        block = connector.block_by_hash(block.parent_hash).await?;
+    }
+
+    Ok(block_number(&block)? + 1)
+}
+
+fn block_number(block: &Block) -> anyhow::Result<U256> {
+    block
+        .number
+        .ok_or_else(|| anyhow::anyhow!("block without number"))
+}
+
+/// Returns the number of confirmations of the given transaction, `None` if it
+/// is not yet included in a block.
+pub async fn confirmations<C>(connector: &C, transaction: Hash) -> anyhow::Result<Option<u32>>
+where
+    C: SubscribeLatestBlocks<Block = Block> + ReceiptByHash,
+{
+    let receipt = fetch_receipt(connector, transaction).await?;
+    let latest_block = connector.subscribe_latest_blocks().next().await?;
+
+    let confirmations = match (receipt.block_number, latest_block.number) {
+        (Some(included), Some(latest)) if latest >= included => latest - included + 1,
+        _ => return Ok(None),
+    };
+
+    Ok(Some(confirmations.low_u32()))
+}
+
+/// Waits until the block containing the given transaction is final or no
+/// longer part of the chain.
+pub async fn wait_for_finality<C>(
+    blockchain_connector: &C,
+    transaction: &Transaction,
+) -> anyhow::Result<BlockStatus>
+where
+    C: SubscribeLatestBlocks<Block = Block> + BlockByHash<Block = Block, BlockHash = Hash>,
 {
-    matching_transaction_and_log(
-        blockchain_connector,
-        start_of_swap,
-        event.topics.clone(),
-        |receipt| find_log_for_event_in_receipt(&event, receipt),
-    )
+    let hash = transaction.hash;
+
+    wait_for_block_finality(blockchain_connector, |block: &Block| {
+        block
+            .transactions
+            .iter()
+            .any(|transaction| transaction.hash == hash)
+    })
     .await
 }
 
@@ -110,154 +266,81 @@ where
     Ok(receipt)
 }
 
-fn find_log_for_event_in_receipt(event: &Event, receipt: TransactionReceipt) -> Option<Log> {
-    match event {
-        Event { topics, .. } if topics.is_empty() => None,
-        Event { address, topics } => receipt.logs.into_iter().find(|log| {
-            if address != &log.address {
-                return false;
-            }
-
-            if log.topics.len() != topics.len() {
-                return false;
-            }
+fn log_matches_event(event: &Event, log: &Log) -> bool {
+    let Event { address, topics } = event;
 
-            log.topics.iter().enumerate().all(|(index, tx_topic)| {
-                let topic = &topics[index];
-                topic.as_ref().map_or(true, |topic| tx_topic == &topic.0)
-            })
-        }),
+    if topics.is_empty() || address != &log.address || log.topics.len() != topics.len() {
+        return false;
     }
+
+    log.topics.iter().enumerate().all(|(index, tx_topic)| {
+        let topic = &topics[index];
+        topic.as_ref().map_or(true, |topic| tx_topic == &topic.0)
+    })
 }
 
 pub async fn matching_transaction_and_receipt<C, F>(
     connector: &C,
-    start_of_swap: NaiveDateTime,
+    start_of_scan: impl Into<StartOfScan>,
     matcher: F,
 ) -> anyhow::Result<(Transaction, TransactionReceipt)>
 where
-    C: LatestBlock<Block = Block> + BlockByHash<Block = Block, BlockHash = Hash> + ReceiptByHash,
-    F: Fn(&Transaction) -> bool,
+    C: SubscribeLatestBlocks<Block = Block>
+        + BlockByHash<Block = Block, BlockHash = Hash>
+        + ReceiptByHash,
+    F: Fn(&Transaction) -> bool + Send + Sync + 'static,
 {
-    let mut block_generator =
-        Gen::new({ |co| async { find_relevant_blocks(connector, co, start_of_swap).await } });
-
-    loop {
-        match block_generator.async_resume().await {
-            GeneratorState::Yielded(block) => {
-                let block_hash = block
-                    .hash
-                    .ok_or_else(|| anyhow::anyhow!("block without hash"))?;
-
-                let span =
-                    tracing::trace_span!("new_block", blockhash = format_args!("{:x}", block_hash));
-                let _enter = span.enter();
-
-                tracing::trace!("checking {} transactions", block.transactions.len());
-
-                for transaction in block.transactions.into_iter() {
-                    let tx_hash = transaction.hash;
-                    let span = tracing::trace_span!(
-                        "matching_transaction",
-                        txhash = format_args!("{:x}", tx_hash)
-                    );
-                    let _enter = span.enter();
-
-                    if matcher(&transaction) {
-                        let receipt = fetch_receipt(connector, tx_hash).await?;
-                        if !receipt.is_status_ok() {
-                            // This can be caused by a failed attempt to complete an action,
-                            // for example, sending a transaction with low gas.
-                            tracing::warn!("transaction matched but status was NOT OK");
-                            continue;
-                        }
-                        tracing::info!("transaction matched");
-                        return Ok((transaction, receipt));
-                    }
-                }
+    let matcher = Arc::new(matcher);
+    let mut relevant_blocks = connector.subscribe_relevant_blocks(
+        start_of_scan.into(),
+        Box::new({
+            let matcher = Arc::clone(&matcher);
+            move |block: &Block| {
+                block
+                    .transactions
+                    .iter()
+                    .any(|transaction| matcher(transaction))
             }
-            GeneratorState::Complete(Err(e)) => return Err(e),
-            // By matching against the never type explicitly, we assert that the `Ok` value of the
-            // result is actually the never type and has not been changed since this line was
-            // written. The never type can never be constructed, so we can never reach this line.
-            GeneratorState::Complete(Ok(never)) => match never {},
-        }
-    }
-}
-
-async fn matching_transaction_and_log<C, F>(
-    connector: &C,
-    start_of_swap: NaiveDateTime,
-    topics: Vec<Option<Topic>>,
-    matcher: F,
-) -> anyhow::Result<(Transaction, Log)>
-where
-    C: LatestBlock<Block = Block> + BlockByHash<Block = Block, BlockHash = Hash> + ReceiptByHash,
-    F: Fn(TransactionReceipt) -> Option<Log>,
-{
-    let mut block_generator =
-        Gen::new({ |co| async { find_relevant_blocks(connector, co, start_of_swap).await } });
+        }),
+    );
 
     loop {
-        match block_generator.async_resume().await {
-            GeneratorState::Yielded(block) => {
-                let block_hash = block
-                    .hash
-                    .ok_or_else(|| anyhow::anyhow!("block without hash"))?;
-
-                let span =
-                    tracing::trace_span!("new_block", blockhash = format_args!("{:x}", block_hash));
-                let _enter = span.enter();
-
-                let maybe_contains_transaction = topics.iter().all(|topic| {
-                    topic.as_ref().map_or(true, |topic| {
-                        block
-                            .logs_bloom
-                            .contains_input(Input::Raw(&topic.0.as_bytes()))
-                    })
-                });
-                if !maybe_contains_transaction {
-                    tracing::trace!(
-                    "bloom filter indicates that this block will not contain an instance of the event"
-
-                    );
+        let block = relevant_blocks.next().await?;
+        let block_hash = block
+            .hash
+            .ok_or_else(|| anyhow::anyhow!("block without hash"))?;
+        let previous_block_hash = block.previous_block_hash();
+
+        let span = tracing::trace_span!("new_block", blockhash = format_args!("{:x}", block_hash));
+        let _enter = span.enter();
+
+        tracing::trace!("checking {} transactions", block.transactions.len());
+
+        for transaction in block.transactions.into_iter() {
+            let tx_hash = transaction.hash;
+            let span = tracing::trace_span!(
+                "matching_transaction",
+                txhash = format_args!("{:x}", tx_hash)
+            );
+            let _enter = span.enter();
+
+            if matcher(&transaction) {
+                let receipt = fetch_receipt(connector, tx_hash).await?;
+                if !receipt.is_status_ok() {
+                    // This can be caused by a failed attempt to complete an action,
+                    // for example, sending a transaction with low gas.
+                    tracing::warn!("transaction matched but status was NOT OK");
                     continue;
-                } else {
-                    tracing::trace!(
-                        "bloom filter indicates that this block might contain an instance of the event"
-                    );
                 }
+                tracing::info!("transaction matched");
 
-                tracing::trace!("checking {} transactions", block.transactions.len());
-
-                for transaction in block.transactions.into_iter() {
-                    let tx_hash = transaction.hash;
-
-                    let span = tracing::trace_span!(
-                        "matching_transaction",
-                        txhash = format_args!("{:x}", tx_hash)
-                    );
-                    let _enter = span.enter();
-
-                    let receipt = fetch_receipt(connector, tx_hash).await?;
-                    let status_is_ok = receipt.is_status_ok();
-                    if let Some(log) = matcher(receipt) {
-                        if !status_is_ok {
-                            // This can be caused by a failed attempt to complete an action,
-                            // for example, sending a transaction with low gas.
-                            tracing::warn!("transaction matched but status was NOT OK");
-                            continue;
-                        }
-                        tracing::info!("transaction matched");
-                        return Ok((transaction, log));
-                    }
+                match wait_for_confirmations(connector, block_hash, previous_block_hash).await? {
+                    BlockStatus::Confirmed => return Ok((transaction, receipt)),
+                    // The block got orphaned, keep looking for the transaction in the blocks
+                    // that replace it.
+                    BlockStatus::Reverted => break,
                 }
             }
-            GeneratorState::Complete(Err(e)) => return Err(e),
-            // By matching against the never type explicitly, we assert that the `Ok` value of the
-            // result is actually the never type and has not been changed since this line was
-            // written. The never type can never be constructed, so we can never reach this line.
-            GeneratorState::Complete(Ok(never)) => match never {},
         }
     }
 }
@@ -270,6 +353,18 @@ impl Predates for Block {
     }
 }
 
+impl BlockHeight for Block {
+    fn block_height(&self) -> Option<u32> {
+        self.number.map(|number| number.low_u32())
+    }
+}
+
+impl BlockTimestamp for Block {
+    fn block_timestamp(&self) -> NaiveDateTime {
+        NaiveDateTime::from_timestamp(self.timestamp.low_u64() as i64, 0)
+    }
+}
+
 #[derive(Clone, Copy, Default, Eq, PartialEq, serde::Serialize, serdebug::SerDebug)]
 #[serde(transparent)]
 pub struct Topic(pub Hash);
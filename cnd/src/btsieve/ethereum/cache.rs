@@ -1,9 +1,11 @@
 use crate::{
     btsieve::{
-        ethereum::{self, Hash, ReceiptByHash},
-        BlockByHash, LatestBlock,
+        ethereum::{self, Event, GetLogs, Hash, ReceiptByHash},
+        BlockByHash, BlockStatus, BlockStream, Confirmations, LatestBlock, LatestBlocks,
+        RelevantBlocks, Sieve, StartOfScan, SubscribeLatestBlocks, WaitForFinality,
     },
-    ethereum::TransactionReceipt,
+    ethereum::{Log, Transaction, TransactionReceipt, U256},
+    metrics,
 };
 use async_trait::async_trait;
 use derivative::Derivative;
@@ -23,6 +25,8 @@ pub struct Cache<C> {
     pub block_cache: Arc<Mutex<LruCache<Hash, Block>>>,
     #[derivative(Debug = "ignore")]
     pub receipt_cache: Arc<Mutex<LruCache<Hash, TransactionReceipt>>>,
+    #[derivative(Debug = "ignore")]
+    block_stream: BlockStream<Block>,
 }
 
 impl<C> Cache<C> {
@@ -30,6 +34,7 @@ impl<C> Cache<C> {
         connector: C,
         block_cache_capacity: usize,
         receipt_cache_capacity: usize,
+        min_confirmations: u32,
     ) -> Cache<C> {
         let block_cache = Arc::new(Mutex::new(LruCache::new(block_cache_capacity)));
         let receipt_cache = Arc::new(Mutex::new(LruCache::new(receipt_cache_capacity)));
@@ -37,6 +42,7 @@ impl<C> Cache<C> {
             connector,
             block_cache,
             receipt_cache,
+            block_stream: BlockStream::new(min_confirmations),
         }
     }
 }
@@ -50,6 +56,7 @@ where
 
     async fn latest_block(&self) -> anyhow::Result<Self::Block> {
         let block = self.connector.latest_block().await?;
+        metrics::record_block_fetched("ethereum");
 
         let block_hash = block.hash.expect("no blocks without hash");
         let mut guard = self.block_cache.lock().await;
@@ -59,6 +66,29 @@ where
 
         Ok(block)
     }
+
+    async fn wait_for_new_block(&self) {
+        self.connector.wait_for_new_block().await
+    }
+}
+
+impl<C> SubscribeLatestBlocks for Cache<C>
+where
+    C: LatestBlock<Block = Block> + BlockByHash<Block = Block, BlockHash = Hash> + Clone,
+{
+    type Block = Block;
+
+    fn subscribe_latest_blocks(&self) -> LatestBlocks<Self::Block> {
+        self.block_stream.subscribe(self)
+    }
+
+    fn subscribe_relevant_blocks(
+        &self,
+        start_of_scan: StartOfScan,
+        sieve: Sieve<Self::Block>,
+    ) -> RelevantBlocks<Self::Block> {
+        self.block_stream.watch(self, start_of_scan, sieve)
+    }
 }
 
 #[async_trait]
@@ -72,11 +102,14 @@ where
     async fn block_by_hash(&self, block_hash: Self::BlockHash) -> anyhow::Result<Self::Block> {
         if let Some(block) = self.block_cache.lock().await.get(&block_hash) {
             tracing::trace!("Found block in cache: {:x}", block_hash);
+            metrics::record_cache_hit("ethereum", "block");
             return Ok(block.clone());
         }
+        metrics::record_cache_miss("ethereum", "block");
 
         let block = self.connector.block_by_hash(block_hash.clone()).await?;
         tracing::trace!("Fetched block from connector: {:x}", block_hash);
+        metrics::record_block_fetched("ethereum");
 
         // We dropped the lock so at this stage the block may have been inserted by
         // another thread, no worries, inserting the same block twice does not hurt.
@@ -95,8 +128,10 @@ where
     async fn receipt_by_hash(&self, transaction_hash: Hash) -> anyhow::Result<TransactionReceipt> {
         if let Some(receipt) = self.receipt_cache.lock().await.get(&transaction_hash) {
             tracing::trace!("Found receipt in cache: {:x}", transaction_hash);
+            metrics::record_cache_hit("ethereum", "receipt");
             return Ok(receipt.clone());
         }
+        metrics::record_cache_miss("ethereum", "receipt");
 
         let receipt = self
             .connector
@@ -113,3 +148,43 @@ where
         Ok(receipt)
     }
 }
+
+#[async_trait]
+impl<C> GetLogs for Cache<C>
+where
+    C: GetLogs,
+{
+    async fn get_logs(
+        &self,
+        event: &Event,
+        from_block: U256,
+        to_block: U256,
+    ) -> anyhow::Result<Vec<Log>> {
+        // Logs of the latest blocks change with every new block, there is nothing to
+        // cache.
+        self.connector.get_logs(event, from_block, to_block).await
+    }
+}
+
+#[async_trait]
+impl<C> WaitForFinality<Transaction> for Cache<C>
+where
+    C: LatestBlock<Block = Block> + BlockByHash<Block = Block, BlockHash = Hash> + Clone,
+{
+    async fn wait_for_finality(&self, transaction: &Transaction) -> anyhow::Result<BlockStatus> {
+        ethereum::wait_for_finality(self, transaction).await
+    }
+}
+
+#[async_trait]
+impl<C> Confirmations<Transaction> for Cache<C>
+where
+    C: LatestBlock<Block = Block>
+        + BlockByHash<Block = Block, BlockHash = Hash>
+        + ReceiptByHash
+        + Clone,
+{
+    async fn confirmations(&self, transaction: &Transaction) -> anyhow::Result<Option<u32>> {
+        ethereum::confirmations(self, transaction.hash).await
+    }
+}
@@ -0,0 +1,121 @@
+use crate::{ethereum::Hash, jsonrpc};
+use futures::{SinkExt, StreamExt};
+use reqwest::Url;
+use std::time::Duration;
+use tokio::sync::watch;
+use tokio_tungstenite::tungstenite::Message;
+
+/// How long to wait before re-establishing a dropped subscription.
+const RECONNECT_DELAY: Duration = Duration::from_secs(5);
+
+#[derive(Debug, thiserror::Error)]
+#[error("nobody is interested in new blocks anymore")]
+struct NoReceivers;
+
+/// Keeps a `newHeads` subscription open on the given websocket and broadcasts
+/// the hash of every block the node announces.
+///
+/// Whenever the connection drops, we reconnect after a short delay. Blocks
+/// announced in the meantime are not lost: btsieve walks back from the next
+/// block we hear about until it reaches a block it has already seen.
+pub async fn follow_new_heads(ws_url: Url, sender: watch::Sender<Option<Hash>>) {
+    loop {
+        match subscribe_new_heads(&ws_url, &sender).await {
+            Ok(()) => tracing::warn!("newHeads subscription on {} was closed", ws_url),
+            Err(e) if e.is::<NoReceivers>() => return,
+            Err(e) => tracing::warn!("newHeads subscription on {} failed: {:?}", ws_url, e),
+        }
+
+        tokio::time::delay_for(RECONNECT_DELAY).await;
+    }
+}
+
+async fn subscribe_new_heads(
+    ws_url: &Url,
+    sender: &watch::Sender<Option<Hash>>,
+) -> anyhow::Result<()> {
+    let (mut stream, _) = tokio_tungstenite::connect_async(ws_url.as_str()).await?;
+
+    let request = jsonrpc::Request::new("eth_subscribe", vec!["newHeads"]);
+    stream
+        .send(Message::Text(serde_json::to_string(&request)?))
+        .await?;
+
+    tracing::info!("subscribed to new blocks on {}", ws_url);
+
+    while let Some(message) = stream.next().await {
+        let text = match message? {
+            Message::Text(text) => text,
+            Message::Close(_) => break,
+            _ => continue,
+        };
+
+        // The first message is the response carrying the subscription id, it
+        // doesn't match the shape of a notification and is skipped.
+        let notification = match serde_json::from_str::<Notification>(&text) {
+            Ok(notification) => notification,
+            Err(_) => continue,
+        };
+
+        let block_hash = notification.params.result.hash;
+        tracing::trace!("new block announced over websocket: {:x}", block_hash);
+
+        sender
+            .broadcast(Some(block_hash))
+            .map_err(|_| NoReceivers)?;
+    }
+
+    Ok(())
+}
+
+#[derive(Debug, serde::Deserialize)]
+struct Notification {
+    params: NotificationParams,
+}
+
+#[derive(Debug, serde::Deserialize)]
+struct NotificationParams {
+    result: Header,
+}
+
+#[derive(Debug, serde::Deserialize)]
+struct Header {
+    hash: Hash,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn deserializes_new_heads_notification() {
+        let text = r#"{
+            "jsonrpc": "2.0",
+            "method": "eth_subscription",
+            "params": {
+                "subscription": "0x9ce59a13059e417087c02d3236a0b1cc",
+                "result": {
+                    "hash": "0x7c47c3d6ab6e2f1a87b2c98f1ee5dbb6e1ad8e0ddf4a2d8f3f4c1b1c3a6ab9e2",
+                    "number": "0x1b4",
+                    "parentHash": "0x1e77d8f1267348b516ebc4f4da1e2aa59f85f0cbd853949500ffac8bfc38ba14"
+                }
+            }
+        }"#;
+
+        let notification = serde_json::from_str::<Notification>(text).unwrap();
+
+        assert_eq!(
+            notification.params.result.hash,
+            "7c47c3d6ab6e2f1a87b2c98f1ee5dbb6e1ad8e0ddf4a2d8f3f4c1b1c3a6ab9e2"
+                .parse()
+                .unwrap()
+        );
+    }
+
+    #[test]
+    fn subscription_response_is_not_a_notification() {
+        let text = r#"{"jsonrpc":"2.0","id":"1","result":"0x9ce59a13059e417087c02d3236a0b1cc"}"#;
+
+        assert!(serde_json::from_str::<Notification>(text).is_err());
+    }
+}
@@ -1,23 +1,71 @@
 use crate::{
-    btsieve::{ethereum::ReceiptByHash, BlockByHash, LatestBlock},
-    config::validation::FetchNetworkId,
-    ethereum::{Hash, TransactionReceipt},
-    jsonrpc,
+    btsieve::{
+        ethereum::{new_heads::follow_new_heads, Event, GetLogs, ReceiptByHash},
+        BlockByHash, LatestBlock,
+    },
+    config::validation::{FetchNetworkId, FetchSyncState},
+    ethereum::{Hash, Log, TransactionReceipt, U256},
+    jsonrpc, metrics,
     swap_protocols::ledger::ethereum::ChainId,
 };
 use async_trait::async_trait;
+use futures::Future;
+use serde::{de::DeserializeOwned, Serialize};
+use std::{sync::Arc, time::Duration};
+use tokio::sync::{watch, Mutex};
 
-#[derive(Debug)]
+/// How often we ask for the latest block if we don't get told about new ones.
+const POLL_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Upper bound for waiting on a block announcement, so we keep making progress
+/// while the websocket is down.
+const NEW_HEADS_TIMEOUT: Duration = Duration::from_secs(15);
+
+#[derive(Clone, Debug)]
 pub struct Web3Connector {
     client: jsonrpc::Client,
+    new_heads: Option<Arc<Mutex<watch::Receiver<Option<Hash>>>>>,
 }
 
 impl Web3Connector {
     pub fn new(node_url: reqwest::Url) -> Self {
         Self {
             client: jsonrpc::Client::new(node_url),
+            new_heads: None,
         }
     }
+
+    /// Creates a connector that learns about new blocks through an
+    /// `eth_subscribe("newHeads")` subscription instead of polling.
+    ///
+    /// The returned future maintains the subscription and has to be spawned
+    /// by the caller.
+    pub fn with_websocket(
+        node_url: reqwest::Url,
+        ws_url: reqwest::Url,
+    ) -> (Self, impl Future<Output = ()>) {
+        let (sender, receiver) = watch::channel(None);
+
+        let connector = Self {
+            client: jsonrpc::Client::new(node_url),
+            new_heads: Some(Arc::new(Mutex::new(receiver))),
+        };
+
+        (connector, follow_new_heads(ws_url, sender))
+    }
+
+    async fn send<Req, Res>(&self, method: &str, params: Req) -> Result<Res, jsonrpc::Error>
+    where
+        Req: Serialize,
+        Res: DeserializeOwned,
+    {
+        metrics::observe_node_rpc(
+            "ethereum",
+            method,
+            self.client.send(jsonrpc::Request::new(method, params)),
+        )
+        .await
+    }
 }
 
 #[async_trait]
@@ -26,11 +74,10 @@ impl LatestBlock for Web3Connector {
 
     async fn latest_block(&self) -> anyhow::Result<Self::Block> {
         let block: Self::Block = self
-            .client
-            .send(jsonrpc::Request::new("eth_getBlockByNumber", vec![
+            .send("eth_getBlockByNumber", vec![
                 jsonrpc::serialize("latest")?,
                 jsonrpc::serialize(true)?,
-            ]))
+            ])
             .await?;
 
         tracing::trace!(
@@ -40,6 +87,19 @@ impl LatestBlock for Web3Connector {
 
         Ok(block)
     }
+
+    async fn wait_for_new_block(&self) {
+        let new_heads = match &self.new_heads {
+            Some(new_heads) => new_heads,
+            None => return tokio::time::delay_for(POLL_INTERVAL).await,
+        };
+
+        let mut new_heads = new_heads.lock().await;
+        if let Ok(None) = tokio::time::timeout(NEW_HEADS_TIMEOUT, new_heads.recv()).await {
+            // The subscription is gone for good, fall back to polling.
+            tokio::time::delay_for(POLL_INTERVAL).await;
+        }
+    }
 }
 
 #[async_trait]
@@ -49,11 +109,10 @@ impl BlockByHash for Web3Connector {
 
     async fn block_by_hash(&self, block_hash: Self::BlockHash) -> anyhow::Result<Self::Block> {
         let block = self
-            .client
-            .send(jsonrpc::Request::new("eth_getBlockByHash", vec![
+            .send("eth_getBlockByHash", vec![
                 jsonrpc::serialize(&block_hash)?,
                 jsonrpc::serialize(true)?,
-            ]))
+            ])
             .await?;
 
         tracing::trace!("Fetched block from web3: {:x}", block_hash);
@@ -66,10 +125,9 @@ impl BlockByHash for Web3Connector {
 impl ReceiptByHash for Web3Connector {
     async fn receipt_by_hash(&self, transaction_hash: Hash) -> anyhow::Result<TransactionReceipt> {
         let receipt = self
-            .client
-            .send(jsonrpc::Request::new("eth_getTransactionReceipt", vec![
-                jsonrpc::serialize(transaction_hash)?,
-            ]))
+            .send("eth_getTransactionReceipt", vec![jsonrpc::serialize(
+                transaction_hash,
+            )?])
             .await?;
 
         tracing::trace!("Fetched receipt from web3: {:x}", transaction_hash);
@@ -78,16 +136,106 @@ impl ReceiptByHash for Web3Connector {
     }
 }
 
+#[derive(Debug, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+struct LogFilter<'a> {
+    from_block: U256,
+    to_block: U256,
+    #[serde(flatten)]
+    event: &'a Event,
+}
+
+#[async_trait]
+impl GetLogs for Web3Connector {
+    async fn get_logs(
+        &self,
+        event: &Event,
+        from_block: U256,
+        to_block: U256,
+    ) -> anyhow::Result<Vec<Log>> {
+        let filter = LogFilter {
+            from_block,
+            to_block,
+            event,
+        };
+
+        let logs: Vec<Log> = self
+            .send("eth_getLogs", vec![jsonrpc::serialize(&filter)?])
+            .await?;
+
+        tracing::trace!(
+            "Fetched {} logs of {:x} from web3 for blocks {} to {}",
+            logs.len(),
+            event.address,
+            from_block,
+            to_block
+        );
+
+        Ok(logs)
+    }
+}
+
 #[async_trait]
 impl FetchNetworkId<ChainId> for Web3Connector {
     async fn network_id(&self) -> anyhow::Result<ChainId> {
-        let chain_id: String = self
-            .client
-            .send::<Vec<()>, String>(jsonrpc::Request::new("net_version", vec![]))
-            .await?;
+        let chain_id: String = self.send::<Vec<()>, String>("net_version", vec![]).await?;
 
         tracing::debug!("Fetched net_version from web3: {:?}", chain_id);
 
         Ok(ChainId::from(chain_id.parse::<u32>()?))
     }
 }
+
+#[async_trait]
+impl FetchSyncState for Web3Connector {
+    async fn is_syncing(&self) -> anyhow::Result<bool> {
+        // `false` if the node is in sync, an object describing the progress
+        // otherwise.
+        let syncing: serde_json::Value = self.send::<Vec<()>, _>("eth_syncing", vec![]).await?;
+
+        tracing::debug!("Fetched eth_syncing from web3: {:?}", syncing);
+
+        Ok(syncing != serde_json::Value::Bool(false))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::btsieve::ethereum::Topic;
+
+    #[test]
+    fn log_filter_serializes_to_eth_get_logs_params() {
+        let event = Event {
+            address: "e46fb33e4db653de84cb0e0e8b810a6c4cd39d59".parse().unwrap(),
+            topics: vec![
+                Some(Topic(
+                    "ddf252ad1be2c89b69c2b068fc378daa952ba7f163c4a11628f55a4df523b3ef"
+                        .parse()
+                        .unwrap(),
+                )),
+                None,
+            ],
+        };
+        let filter = LogFilter {
+            from_block: U256::from(16),
+            to_block: U256::from(255),
+            event: &event,
+        };
+
+        let serialized = serde_json::to_value(&filter).unwrap();
+
+        assert_eq!(
+            serialized,
+            serde_json::json!({
+                "fromBlock": "0x10",
+                "toBlock": "0xff",
+                "address": "0xe46fb33e4db653de84cb0e0e8b810a6c4cd39d59",
+                "topics": [
+                    "0xddf252ad1be2c89b69c2b068fc378daa952ba7f163c4a11628f55a4df523b3ef",
+                    null
+                ]
+            })
+        );
+    }
+}
@@ -6,11 +6,37 @@ pub mod validation;
 use crate::swap_protocols::ledger::ethereum;
 use libp2p::Multiaddr;
 use reqwest::Url;
-use serde::{Deserialize, Serialize};
-use std::path::PathBuf;
+use serde::{Deserialize, Serialize, Serializer};
+use std::{fmt, path::PathBuf};
 
 pub use self::{file::File, settings::Settings};
 
+/// By default, we act upon a transaction as soon as it is included in a block.
+pub const DEFAULT_MIN_CONFIRMATIONS: u32 = 1;
+
+/// Number of blocks below the best block at the time a swap was accepted from
+/// which on we look for HTLC events, in case the node was lagging behind.
+pub const DEFAULT_BITCOIN_START_HEIGHT_MARGIN: u32 = 6;
+pub const DEFAULT_ETHEREUM_START_HEIGHT_MARGIN: u32 = 20;
+
+/// Fee rate in satoshi per weight unit the autopilot pays for spending HTLCs.
+pub const DEFAULT_AUTOPILOT_FEE_PER_WU: usize = 10;
+
+/// Gas price in wei the autopilot pays for Ethereum transactions.
+pub const DEFAULT_AUTOPILOT_GAS_PRICE: u64 = 20_000_000_000;
+
+/// Minutes before the expiry of an HTLC we still have to redeem at which the
+/// expiry monitor raises an alert, each one more urgent than the last.
+pub const DEFAULT_EXPIRY_WARNINGS_MINUTES: [u32; 3] = [360, 60, 15];
+
+/// Certificate and key of the HTTP API in the data directory, if TLS is
+/// enabled but no paths are configured.
+pub const DEFAULT_TLS_CERT_FILE: &str = "tls.cert";
+pub const DEFAULT_TLS_KEY_FILE: &str = "tls.key";
+
+/// Port the Prometheus metrics are served on if none is configured.
+pub const DEFAULT_METRICS_PORT: u16 = 9090;
+
 lazy_static::lazy_static! {
     pub static ref LND_URL: Url = Url::parse("https://localhost:8080").expect("static string to be a valid url");
 }
@@ -30,11 +56,41 @@ pub struct Bitcoin {
     #[serde(with = "crate::config::serde_bitcoin_network")]
     pub network: bitcoin::Network,
     pub bitcoind: Bitcoind,
+    /// If present, blocks are fetched from this Esplora instance instead of
+    /// `bitcoind`.
+    pub esplora: Option<Esplora>,
+    /// Number of confirmations a block needs before we act upon the
+    /// transactions it contains.
+    pub min_confirmations: u32,
+    /// Number of blocks below the best block at the time a swap was accepted
+    /// that are also looked at for HTLC events.
+    pub start_height_margin: u32,
 }
 
 #[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
 pub struct Bitcoind {
     pub node_url: Url,
+    /// Credentials for the JSON-RPC interface of bitcoind. If present, we talk
+    /// JSON-RPC to `node_url` instead of relying on the REST interface.
+    pub rpc_auth: Option<BitcoindRpcAuth>,
+}
+
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct Esplora {
+    pub url: Url,
+}
+
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+#[serde(untagged)]
+pub enum BitcoindRpcAuth {
+    /// The `.cookie` file bitcoind writes to its data directory on startup.
+    Cookie {
+        cookie_file: PathBuf,
+    },
+    UserPassword {
+        user: String,
+        password: Redacted,
+    },
 }
 
 impl Default for Bitcoin {
@@ -44,7 +100,11 @@ impl Default for Bitcoin {
             bitcoind: Bitcoind {
                 node_url: Url::parse("http://localhost:18443")
                     .expect("static string to be a valid url"),
+                rpc_auth: None,
             },
+            esplora: None,
+            min_confirmations: DEFAULT_MIN_CONFIRMATIONS,
+            start_height_margin: DEFAULT_BITCOIN_START_HEIGHT_MARGIN,
         }
     }
 }
@@ -54,6 +114,9 @@ impl From<Bitcoin> for file::Bitcoin {
         file::Bitcoin {
             network: bitcoin.network,
             bitcoind: Some(bitcoin.bitcoind),
+            esplora: bitcoin.esplora,
+            min_confirmations: Some(bitcoin.min_confirmations),
+            start_height_margin: Some(bitcoin.start_height_margin),
         }
     }
 }
@@ -62,6 +125,12 @@ impl From<Bitcoin> for file::Bitcoin {
 pub struct Ethereum {
     pub chain_id: ethereum::ChainId,
     pub parity: Parity,
+    /// Number of confirmations a block needs before we act upon the
+    /// transactions it contains.
+    pub min_confirmations: u32,
+    /// Number of blocks below the best block at the time a swap was accepted
+    /// that are also looked at for HTLC events.
+    pub start_height_margin: u32,
 }
 
 impl From<Ethereum> for file::Ethereum {
@@ -69,6 +138,8 @@ impl From<Ethereum> for file::Ethereum {
         file::Ethereum {
             chain_id: ethereum.chain_id,
             parity: Some(ethereum.parity),
+            min_confirmations: Some(ethereum.min_confirmations),
+            start_height_margin: Some(ethereum.start_height_margin),
         }
     }
 }
@@ -80,7 +151,10 @@ impl Default for Ethereum {
             parity: Parity {
                 node_url: Url::parse("http://localhost:8545")
                     .expect("static string to be a valid url"),
+                ws_url: None,
             },
+            min_confirmations: DEFAULT_MIN_CONFIRMATIONS,
+            start_height_margin: DEFAULT_ETHEREUM_START_HEIGHT_MARGIN,
         }
     }
 }
@@ -88,6 +162,10 @@ impl Default for Ethereum {
 #[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
 pub struct Parity {
     pub node_url: Url,
+    /// WebSocket endpoint of the node. If present, we are notified about new
+    /// blocks through an `eth_subscribe("newHeads")` subscription instead of
+    /// polling for them.
+    pub ws_url: Option<Url>,
 }
 
 #[derive(Clone, Debug, PartialEq, Serialize)]
@@ -142,6 +220,121 @@ impl Lnd {
     }
 }
 
+/// Lets cnd execute the actions of swaps itself, using hot wallets it
+/// controls.
+///
+/// Actions on ledgers without a wallet are left to the user.
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct Autopilot {
+    pub bitcoin: Option<AutopilotBitcoin>,
+    pub ethereum: Option<AutopilotEthereum>,
+    pub lightning: Option<AutopilotLightning>,
+}
+
+impl From<Autopilot> for file::Autopilot {
+    fn from(autopilot: Autopilot) -> Self {
+        file::Autopilot {
+            bitcoin: autopilot.bitcoin.map(|bitcoin| file::AutopilotBitcoin {
+                wallet: bitcoin.wallet,
+                fee_per_wu: Some(bitcoin.fee_per_wu),
+            }),
+            ethereum: autopilot.ethereum.map(|ethereum| file::AutopilotEthereum {
+                private_key_file: ethereum.private_key_file,
+                gas_price: Some(ethereum.gas_price),
+            }),
+            lightning: autopilot
+                .lightning
+                .map(|lightning| file::AutopilotLightning {
+                    admin_macaroon_path: Some(lightning.admin_macaroon_path),
+                }),
+        }
+    }
+}
+
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct AutopilotBitcoin {
+    /// Name of the bitcoind wallet to use, the default wallet if absent.
+    pub wallet: Option<String>,
+    pub fee_per_wu: usize,
+}
+
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct AutopilotEthereum {
+    /// File containing the hex encoded private key transactions are signed
+    /// with.
+    pub private_key_file: PathBuf,
+    pub gas_price: u64,
+}
+
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct AutopilotLightning {
+    pub admin_macaroon_path: PathBuf,
+}
+
+/// Watches the expiries of the HTLCs of all active swaps.
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct ExpiryMonitor {
+    /// Minutes before expiry at which we warn about an HTLC we haven't
+    /// redeemed yet.
+    pub warn_before_expiry_minutes: Vec<u32>,
+}
+
+impl Default for ExpiryMonitor {
+    fn default() -> Self {
+        Self {
+            warn_before_expiry_minutes: DEFAULT_EXPIRY_WARNINGS_MINUTES.to_vec(),
+        }
+    }
+}
+
+impl From<ExpiryMonitor> for file::ExpiryMonitor {
+    fn from(expiry_monitor: ExpiryMonitor) -> Self {
+        file::ExpiryMonitor {
+            warn_before_expiry_minutes: Some(expiry_monitor.warn_before_expiry_minutes),
+        }
+    }
+}
+
+/// An HTTP endpoint that is notified about swap events.
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct Webhook {
+    pub url: Url,
+    /// Key used to sign the notifications with HMAC-SHA256.
+    pub secret: Redacted,
+}
+
+/// A secret read from the config file, it is neither logged nor dumped.
+#[derive(Clone, PartialEq, Deserialize)]
+#[serde(transparent)]
+pub struct Redacted(String);
+
+impl Redacted {
+    pub fn expose(&self) -> &str {
+        &self.0
+    }
+}
+
+impl From<&str> for Redacted {
+    fn from(secret: &str) -> Self {
+        Redacted(secret.to_owned())
+    }
+}
+
+impl fmt::Debug for Redacted {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "[*****]")
+    }
+}
+
+impl Serialize for Redacted {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serializer.serialize_str("*****")
+    }
+}
+
 fn default_lnd_dir() -> PathBuf {
     crate::lnd_dir().expect("no home directory")
 }
@@ -151,6 +344,14 @@ fn default_lnd_cert_path(lnd_dir: PathBuf) -> PathBuf {
 }
 
 fn default_lnd_readonly_macaroon_path(lnd_dir: PathBuf, network: bitcoin::Network) -> PathBuf {
+    lnd_network_dir(lnd_dir, network).join("readonly.macaroon")
+}
+
+fn default_lnd_admin_macaroon_path(lnd_dir: PathBuf, network: bitcoin::Network) -> PathBuf {
+    lnd_network_dir(lnd_dir, network).join("admin.macaroon")
+}
+
+fn lnd_network_dir(lnd_dir: PathBuf, network: bitcoin::Network) -> PathBuf {
     let network_dir = match network {
         bitcoin::Network::Bitcoin => "mainnet",
         bitcoin::Network::Testnet => "testnet",
@@ -161,7 +362,6 @@ fn default_lnd_readonly_macaroon_path(lnd_dir: PathBuf, network: bitcoin::Networ
         .join("chain")
         .join("bitcoin")
         .join(network_dir)
-        .join("readonly.macaroon")
 }
 
 #[cfg(test)]
@@ -1,5 +1,5 @@
 use crate::{
-    config::{Bitcoind, Data, Network, Parity},
+    config::{Bitcoind, Data, Esplora, Network, Parity, Webhook},
     swap_protocols::ledger::ethereum,
 };
 use config as config_rs;
@@ -25,6 +25,10 @@ pub struct File {
     pub bitcoin: Option<Bitcoin>,
     pub ethereum: Option<Ethereum>,
     pub lightning: Option<Lightning>,
+    pub autopilot: Option<Autopilot>,
+    pub expiry_monitor: Option<ExpiryMonitor>,
+    pub webhooks: Option<Vec<Webhook>>,
+    pub metrics: Option<Metrics>,
 }
 
 #[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
@@ -32,12 +36,17 @@ pub struct Bitcoin {
     #[serde(with = "crate::config::serde_bitcoin_network")]
     pub network: bitcoin::Network,
     pub bitcoind: Option<Bitcoind>,
+    pub esplora: Option<Esplora>,
+    pub min_confirmations: Option<u32>,
+    pub start_height_margin: Option<u32>,
 }
 
 #[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
 pub struct Ethereum {
     pub chain_id: ethereum::ChainId,
     pub parity: Option<Parity>,
+    pub min_confirmations: Option<u32>,
+    pub start_height_margin: Option<u32>,
 }
 
 #[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
@@ -52,6 +61,35 @@ pub struct Lnd {
     pub dir: PathBuf,
 }
 
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct Autopilot {
+    pub bitcoin: Option<AutopilotBitcoin>,
+    pub ethereum: Option<AutopilotEthereum>,
+    pub lightning: Option<AutopilotLightning>,
+}
+
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct AutopilotBitcoin {
+    pub wallet: Option<String>,
+    pub fee_per_wu: Option<usize>,
+}
+
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct AutopilotEthereum {
+    pub private_key_file: PathBuf,
+    pub gas_price: Option<u64>,
+}
+
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct AutopilotLightning {
+    pub admin_macaroon_path: Option<PathBuf>,
+}
+
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct ExpiryMonitor {
+    pub warn_before_expiry_minutes: Option<Vec<u32>>,
+}
+
 impl File {
     pub fn default() -> Self {
         File {
@@ -62,6 +100,10 @@ impl File {
             bitcoin: Option::None,
             ethereum: Option::None,
             lightning: Option::None,
+            autopilot: Option::None,
+            expiry_monitor: Option::None,
+            webhooks: Option::None,
+            metrics: Option::None,
         }
     }
 
@@ -118,8 +160,11 @@ impl From<Level> for LevelFilter {
 
 #[derive(Clone, Debug, serde::Serialize, serde::Deserialize, PartialEq)]
 pub struct HttpApi {
-    pub socket: SocketAddr,
+    pub socket: Option<SocketAddr>,
+    pub unix_socket: Option<PathBuf>,
     pub cors: Option<Cors>,
+    pub auth: Option<Auth>,
+    pub tls: Option<Tls>,
 }
 
 #[derive(Clone, Debug, serde::Serialize, serde::Deserialize, PartialEq)]
@@ -135,6 +180,23 @@ pub enum AllowedOrigins {
     Some(Vec<String>),
 }
 
+#[derive(Clone, Copy, Debug, serde::Serialize, serde::Deserialize, PartialEq)]
+pub struct Auth {
+    pub enabled: bool,
+}
+
+#[derive(Clone, Debug, Default, serde::Serialize, serde::Deserialize, PartialEq)]
+pub struct Tls {
+    pub cert_path: Option<PathBuf>,
+    pub key_path: Option<PathBuf>,
+    pub client_ca_path: Option<PathBuf>,
+}
+
+#[derive(Clone, Copy, Debug, Default, serde::Serialize, serde::Deserialize, PartialEq)]
+pub struct Metrics {
+    pub socket: Option<SocketAddr>,
+}
+
 #[derive(Clone, Copy, Debug, serde::Serialize, serde::Deserialize, PartialEq)]
 #[serde(rename_all = "lowercase")]
 pub enum All {
@@ -151,7 +213,7 @@ pub enum None {
 mod tests {
     use super::*;
     use crate::{
-        config::{Bitcoind, Parity, Settings},
+        config::{Bitcoind, BitcoindRpcAuth, Esplora, Parity, Redacted, Settings},
         swap_protocols::ledger::ethereum,
     };
     use reqwest::Url;
@@ -212,10 +274,18 @@ listen = ["/ip4/0.0.0.0/tcp/9939"]
 
 [http_api]
 socket = "127.0.0.1:8000"
+unix_socket = "/tmp/comit/cnd.sock"
 
 [http_api.cors]
 allowed_origins = "all"
 
+[http_api.auth]
+enabled = true
+
+[http_api.tls]
+cert_path = "/tmp/comit/tls.cert"
+key_path = "/tmp/comit/tls.key"
+
 [data]
 dir = "/tmp/comit/"
 
@@ -224,12 +294,16 @@ level = "Debug"
 
 [bitcoin]
 network = "regtest"
+min_confirmations = 3
+start_height_margin = 10
 
 [bitcoin.bitcoind]
 node_url = "http://localhost:18443/"
 
 [ethereum]
 chain_id = 1337
+min_confirmations = 12
+start_height_margin = 40
 
 [ethereum.parity]
 node_url = "http://localhost:8545/"
@@ -240,16 +314,45 @@ network = "regtest"
 [lightning.lnd]
 rest_api_url = "https://localhost:8080"
 dir = "/foo/bar"
+
+[autopilot.bitcoin]
+wallet = "cnd"
+fee_per_wu = 20
+
+[autopilot.ethereum]
+private_key_file = "/foo/ethereum.key"
+
+[autopilot.lightning]
+
+[expiry_monitor]
+warn_before_expiry_minutes = [120, 30]
+
+[[webhooks]]
+url = "http://localhost:3000/cnd-events"
+secret = "s3cr3t"
+
+[metrics]
+socket = "127.0.0.1:9090"
 "#;
         let file = File {
             network: Some(Network {
                 listen: vec!["/ip4/0.0.0.0/tcp/9939".parse().unwrap()],
             }),
             http_api: Some(HttpApi {
-                socket: SocketAddr::new(IpAddr::V4(Ipv4Addr::new(127, 0, 0, 1)), 8000),
+                socket: Some(SocketAddr::new(
+                    IpAddr::V4(Ipv4Addr::new(127, 0, 0, 1)),
+                    8000,
+                )),
+                unix_socket: Some(PathBuf::from("/tmp/comit/cnd.sock")),
                 cors: Some(Cors {
                     allowed_origins: AllowedOrigins::All(All::All),
                 }),
+                auth: Some(Auth { enabled: true }),
+                tls: Some(Tls {
+                    cert_path: Some(PathBuf::from("/tmp/comit/tls.cert")),
+                    key_path: Some(PathBuf::from("/tmp/comit/tls.key")),
+                    client_ca_path: None,
+                }),
             }),
             data: Some(Data {
                 dir: PathBuf::from("/tmp/comit/"),
@@ -261,13 +364,20 @@ dir = "/foo/bar"
                 network: bitcoin::Network::Regtest,
                 bitcoind: Some(Bitcoind {
                     node_url: "http://localhost:18443".parse().unwrap(),
+                    rpc_auth: None,
                 }),
+                esplora: None,
+                min_confirmations: Some(3),
+                start_height_margin: Some(10),
             }),
             ethereum: Some(Ethereum {
                 chain_id: ethereum::ChainId::regtest(),
                 parity: Some(Parity {
                     node_url: "http://localhost:8545".parse().unwrap(),
+                    ws_url: None,
                 }),
+                min_confirmations: Some(12),
+                start_height_margin: Some(40),
             }),
             lightning: Some(Lightning {
                 network: bitcoin::Network::Regtest,
@@ -276,6 +386,32 @@ dir = "/foo/bar"
                     dir: PathBuf::from("/foo/bar"),
                 }),
             }),
+            autopilot: Some(Autopilot {
+                bitcoin: Some(AutopilotBitcoin {
+                    wallet: Some(String::from("cnd")),
+                    fee_per_wu: Some(20),
+                }),
+                ethereum: Some(AutopilotEthereum {
+                    private_key_file: PathBuf::from("/foo/ethereum.key"),
+                    gas_price: None,
+                }),
+                lightning: Some(AutopilotLightning {
+                    admin_macaroon_path: None,
+                }),
+            }),
+            expiry_monitor: Some(ExpiryMonitor {
+                warn_before_expiry_minutes: Some(vec![120, 30]),
+            }),
+            webhooks: Some(vec![Webhook {
+                url: "http://localhost:3000/cnd-events".parse().unwrap(),
+                secret: Redacted::from("s3cr3t"),
+            }]),
+            metrics: Some(Metrics {
+                socket: Some(SocketAddr::new(
+                    IpAddr::V4(Ipv4Addr::new(127, 0, 0, 1)),
+                    9090,
+                )),
+            }),
         };
 
         let config = toml::from_str::<File>(contents);
@@ -299,6 +435,36 @@ dir = "/foo/bar"
         assert_eq!(file, file_with_effective_settings)
     }
 
+    #[test]
+    fn secrets_are_redacted_when_serialized() {
+        let file = File {
+            bitcoin: Some(Bitcoin {
+                network: bitcoin::Network::Regtest,
+                bitcoind: Some(Bitcoind {
+                    node_url: Url::parse("http://example.com:8332").unwrap(),
+                    rpc_auth: Some(BitcoindRpcAuth::UserPassword {
+                        user: "bitcoin".to_owned(),
+                        password: Redacted::from("hunter2"),
+                    }),
+                }),
+                esplora: None,
+                min_confirmations: None,
+                start_height_margin: None,
+            }),
+            webhooks: Some(vec![Webhook {
+                url: "http://localhost:3000/cnd-events".parse().unwrap(),
+                secret: Redacted::from("s3cr3t"),
+            }]),
+            ..File::default()
+        };
+
+        let serialized = toml::to_string(&file).unwrap();
+        let debug = format!("{:?}", file);
+
+        assert!(!serialized.contains("hunter2") && !serialized.contains("s3cr3t"));
+        assert!(!debug.contains("hunter2") && !debug.contains("s3cr3t"));
+    }
+
     #[test]
     fn bitcoin_deserializes_correctly() {
         let file_contents = vec![
@@ -317,6 +483,11 @@ dir = "/foo/bar"
             [bitcoind]
             node_url = "http://example.com:18443"
             "#,
+            r#"
+            network = "mainnet"
+            [esplora]
+            url = "https://blockstream.info/api/"
+            "#,
         ];
 
         let expected = vec![
@@ -324,19 +495,40 @@ dir = "/foo/bar"
                 network: bitcoin::Network::Bitcoin,
                 bitcoind: Some(Bitcoind {
                     node_url: Url::parse("http://example.com:8332").unwrap(),
+                    rpc_auth: None,
                 }),
+                esplora: None,
+                min_confirmations: None,
+                start_height_margin: None,
             },
             Bitcoin {
                 network: bitcoin::Network::Testnet,
                 bitcoind: Some(Bitcoind {
                     node_url: Url::parse("http://example.com:18332").unwrap(),
+                    rpc_auth: None,
                 }),
+                esplora: None,
+                min_confirmations: None,
+                start_height_margin: None,
             },
             Bitcoin {
                 network: bitcoin::Network::Regtest,
                 bitcoind: Some(Bitcoind {
                     node_url: Url::parse("http://example.com:18443").unwrap(),
+                    rpc_auth: None,
+                }),
+                esplora: None,
+                min_confirmations: None,
+                start_height_margin: None,
+            },
+            Bitcoin {
+                network: bitcoin::Network::Bitcoin,
+                bitcoind: None,
+                esplora: Some(Esplora {
+                    url: Url::parse("https://blockstream.info/api/").unwrap(),
                 }),
+                min_confirmations: None,
+                start_height_margin: None,
             },
         ];
 
@@ -349,6 +541,47 @@ dir = "/foo/bar"
         assert_eq!(actual, expected);
     }
 
+    #[test]
+    fn bitcoind_rpc_auth_deserializes_correctly() {
+        let file_contents = vec![
+            r#"
+            node_url = "http://example.com:8332"
+            [rpc_auth]
+            cookie_file = "/home/bitcoin/.bitcoin/.cookie"
+            "#,
+            r#"
+            node_url = "http://example.com:8332"
+            [rpc_auth]
+            user = "bitcoin"
+            password = "hunter2"
+            "#,
+        ];
+
+        let expected = vec![
+            Bitcoind {
+                node_url: Url::parse("http://example.com:8332").unwrap(),
+                rpc_auth: Some(BitcoindRpcAuth::Cookie {
+                    cookie_file: PathBuf::from("/home/bitcoin/.bitcoin/.cookie"),
+                }),
+            },
+            Bitcoind {
+                node_url: Url::parse("http://example.com:8332").unwrap(),
+                rpc_auth: Some(BitcoindRpcAuth::UserPassword {
+                    user: "bitcoin".to_owned(),
+                    password: Redacted::from("hunter2"),
+                }),
+            },
+        ];
+
+        let actual = file_contents
+            .into_iter()
+            .map(toml::from_str)
+            .collect::<Result<Vec<Bitcoind>, toml::de::Error>>()
+            .unwrap();
+
+        assert_eq!(actual, expected);
+    }
+
     #[test]
     fn ethereum_deserializes_correctly() {
         let file_contents = vec![
@@ -366,6 +599,7 @@ dir = "/foo/bar"
             chain_id = 1
             [parity]
             node_url = "http://example.com:8545"
+            ws_url = "ws://example.com:8546"
             "#,
         ];
 
@@ -374,19 +608,28 @@ dir = "/foo/bar"
                 chain_id: ethereum::ChainId::regtest(),
                 parity: Some(Parity {
                     node_url: Url::parse("http://example.com:8545").unwrap(),
+                    ws_url: None,
                 }),
+                min_confirmations: None,
+                start_height_margin: None,
             },
             Ethereum {
                 chain_id: ethereum::ChainId::ropsten(),
                 parity: Some(Parity {
                     node_url: Url::parse("http://example.com:8545").unwrap(),
+                    ws_url: None,
                 }),
+                min_confirmations: None,
+                start_height_margin: None,
             },
             Ethereum {
                 chain_id: ethereum::ChainId::mainnet(),
                 parity: Some(Parity {
                     node_url: Url::parse("http://example.com:8545").unwrap(),
+                    ws_url: Some(Url::parse("ws://example.com:8546").unwrap()),
                 }),
+                min_confirmations: None,
+                start_height_margin: None,
             },
         ];
 
@@ -1,11 +1,18 @@
 use crate::config::{
-    default_lnd_cert_path, default_lnd_readonly_macaroon_path, file, Bitcoin, Bitcoind, Data,
-    Ethereum, File, Lightning, Lnd, Network, Parity,
+    default_lnd_admin_macaroon_path, default_lnd_cert_path, default_lnd_readonly_macaroon_path,
+    file, Autopilot, AutopilotBitcoin, AutopilotEthereum, AutopilotLightning, Bitcoin, Bitcoind,
+    Data, Ethereum, ExpiryMonitor, File, Lightning, Lnd, Network, Parity, Webhook,
+    DEFAULT_AUTOPILOT_FEE_PER_WU, DEFAULT_AUTOPILOT_GAS_PRICE, DEFAULT_BITCOIN_START_HEIGHT_MARGIN,
+    DEFAULT_ETHEREUM_START_HEIGHT_MARGIN, DEFAULT_EXPIRY_WARNINGS_MINUTES, DEFAULT_METRICS_PORT,
+    DEFAULT_MIN_CONFIRMATIONS, DEFAULT_TLS_CERT_FILE, DEFAULT_TLS_KEY_FILE,
 };
 use anyhow::Context;
 use log::LevelFilter;
 use reqwest::Url;
-use std::net::{IpAddr, Ipv4Addr, SocketAddr};
+use std::{
+    net::{IpAddr, Ipv4Addr, SocketAddr},
+    path::PathBuf,
+};
 
 /// This structs represents the settings as they are used through out the code.
 ///
@@ -22,29 +29,101 @@ pub struct Settings {
     pub bitcoin: Bitcoin,
     pub ethereum: Ethereum,
     pub lightning: Lightning,
+    /// Disabled if `None`.
+    pub autopilot: Option<Autopilot>,
+    pub expiry_monitor: ExpiryMonitor,
+    /// No notifications are sent if empty.
+    pub webhooks: Vec<Webhook>,
+    /// Prometheus metrics are not served if `None`.
+    pub metrics: Option<Metrics>,
+}
+
+fn derive_http_api(http_api: Option<file::HttpApi>, data: &Data) -> HttpApi {
+    match http_api {
+        None => HttpApi::default(),
+        Some(file::HttpApi {
+            socket,
+            unix_socket,
+            cors,
+            auth,
+            tls,
+        }) => {
+            // Only listen on the Unix socket if no TCP socket is configured.
+            let socket = match (socket, &unix_socket) {
+                (None, None) => HttpApi::default().socket,
+                (socket, _) => socket,
+            };
+
+            let cors = cors
+                .map(|cors| {
+                    let allowed_origins = match cors.allowed_origins {
+                        file::AllowedOrigins::All(_) => AllowedOrigins::All,
+                        file::AllowedOrigins::None(_) => AllowedOrigins::None,
+                        file::AllowedOrigins::Some(origins) => AllowedOrigins::Some(origins),
+                    };
+
+                    Cors { allowed_origins }
+                })
+                .unwrap_or_default();
+
+            let auth = auth
+                .map(|auth| Auth {
+                    enabled: auth.enabled,
+                })
+                .unwrap_or_default();
+
+            let tls = tls.map(|tls| Tls {
+                cert_path: tls
+                    .cert_path
+                    .unwrap_or_else(|| data.dir.join(DEFAULT_TLS_CERT_FILE)),
+                key_path: tls
+                    .key_path
+                    .unwrap_or_else(|| data.dir.join(DEFAULT_TLS_KEY_FILE)),
+                client_ca_path: tls.client_ca_path,
+            });
+
+            HttpApi {
+                socket,
+                unix_socket,
+                cors,
+                auth,
+                tls,
+            }
+        }
+    }
 }
 
 fn derive_url_bitcoin(bitcoin: Option<file::Bitcoin>) -> Bitcoin {
     match bitcoin {
         None => Bitcoin::default(),
         Some(bitcoin) => {
-            let node_url = match bitcoin.bitcoind {
-                Some(bitcoind) => bitcoind.node_url,
-                None => match bitcoin.network {
-                    bitcoin::Network::Bitcoin => "http://localhost:8332"
-                        .parse()
-                        .expect("to be valid static string"),
-                    bitcoin::Network::Testnet => "http://localhost:18332"
-                        .parse()
-                        .expect("to be valid static string"),
-                    bitcoin::Network::Regtest => "http://localhost:18443"
-                        .parse()
-                        .expect("to be valid static string"),
+            let bitcoind = match bitcoin.bitcoind {
+                Some(bitcoind) => bitcoind,
+                None => Bitcoind {
+                    node_url: match bitcoin.network {
+                        bitcoin::Network::Bitcoin => "http://localhost:8332"
+                            .parse()
+                            .expect("to be valid static string"),
+                        bitcoin::Network::Testnet => "http://localhost:18332"
+                            .parse()
+                            .expect("to be valid static string"),
+                        bitcoin::Network::Regtest => "http://localhost:18443"
+                            .parse()
+                            .expect("to be valid static string"),
+                    },
+                    rpc_auth: None,
                 },
             };
             Bitcoin {
                 network: bitcoin.network,
-                bitcoind: Bitcoind { node_url },
+                bitcoind,
+                esplora: bitcoin.esplora,
+                min_confirmations: bitcoin
+                    .min_confirmations
+                    .unwrap_or(DEFAULT_MIN_CONFIRMATIONS),
+                start_height_margin: bitcoin
+                    .start_height_margin
+                    .unwrap_or(DEFAULT_BITCOIN_START_HEIGHT_MARGIN),
             }
         }
     }
@@ -54,23 +133,85 @@ fn derive_url_ethereum(ethereum: Option<file::Ethereum>) -> Ethereum {
     match ethereum {
         None => Ethereum::default(),
         Some(ethereum) => {
-            let node_url = match ethereum.parity {
-                None => {
+            let parity = match ethereum.parity {
+                None => Parity {
                     // default is always localhost:8545
-                    "http://localhost:8545"
+                    node_url: "http://localhost:8545"
                         .parse()
-                        .expect("to be valid static string")
-                }
-                Some(parity) => parity.node_url,
+                        .expect("to be valid static string"),
+                    ws_url: None,
+                },
+                Some(parity) => parity,
             };
             Ethereum {
                 chain_id: ethereum.chain_id,
-                parity: Parity { node_url },
+                parity,
+                min_confirmations: ethereum
+                    .min_confirmations
+                    .unwrap_or(DEFAULT_MIN_CONFIRMATIONS),
+                start_height_margin: ethereum
+                    .start_height_margin
+                    .unwrap_or(DEFAULT_ETHEREUM_START_HEIGHT_MARGIN),
             }
         }
     }
 }
 
+fn derive_autopilot(
+    autopilot: Option<file::Autopilot>,
+    bitcoin: &Bitcoin,
+    lightning: &Lightning,
+) -> anyhow::Result<Option<Autopilot>> {
+    let autopilot = match autopilot {
+        None => return Ok(None),
+        Some(autopilot) => autopilot,
+    };
+
+    if autopilot.bitcoin.is_some() && bitcoin.bitcoind.rpc_auth.is_none() {
+        anyhow::bail!(
+            "the autopilot needs rpc_auth in [bitcoin.bitcoind] to use the bitcoind wallet"
+        )
+    }
+
+    Ok(Some(Autopilot {
+        bitcoin: autopilot.bitcoin.map(|bitcoin| AutopilotBitcoin {
+            wallet: bitcoin.wallet,
+            fee_per_wu: bitcoin.fee_per_wu.unwrap_or(DEFAULT_AUTOPILOT_FEE_PER_WU),
+        }),
+        ethereum: autopilot.ethereum.map(|ethereum| AutopilotEthereum {
+            private_key_file: ethereum.private_key_file,
+            gas_price: ethereum.gas_price.unwrap_or(DEFAULT_AUTOPILOT_GAS_PRICE),
+        }),
+        lightning: autopilot
+            .lightning
+            .map(|autopilot_lightning| AutopilotLightning {
+                admin_macaroon_path: autopilot_lightning.admin_macaroon_path.unwrap_or_else(|| {
+                    default_lnd_admin_macaroon_path(lightning.lnd.dir.clone(), lightning.network)
+                }),
+            }),
+    }))
+}
+
+fn derive_expiry_monitor(expiry_monitor: Option<file::ExpiryMonitor>) -> ExpiryMonitor {
+    match expiry_monitor {
+        None => ExpiryMonitor::default(),
+        Some(file::ExpiryMonitor {
+            warn_before_expiry_minutes,
+        }) => ExpiryMonitor {
+            warn_before_expiry_minutes: warn_before_expiry_minutes
+                .unwrap_or_else(|| DEFAULT_EXPIRY_WARNINGS_MINUTES.to_vec()),
+        },
+    }
+}
+
+fn derive_metrics(metrics: Option<file::Metrics>) -> Option<Metrics> {
+    metrics.map(|file::Metrics { socket }| Metrics {
+        socket: socket.unwrap_or_else(|| {
+            SocketAddr::new(IpAddr::V4(Ipv4Addr::LOCALHOST), DEFAULT_METRICS_PORT)
+        }),
+    })
+}
+
 fn check_url_lnd(lnd_url: Url) -> anyhow::Result<Url> {
     if lnd_url.scheme() == "https" {
         Ok(lnd_url)
@@ -83,18 +224,30 @@ impl From<Settings> for File {
     fn from(settings: Settings) -> Self {
         let Settings {
             network,
-            http_api: HttpApi { socket, cors },
+            http_api:
+                HttpApi {
+                    socket,
+                    unix_socket,
+                    cors,
+                    auth,
+                    tls,
+                },
             data,
             logging: Logging { level },
             bitcoin,
             ethereum,
             lightning,
+            autopilot,
+            expiry_monitor,
+            webhooks,
+            metrics,
         } = settings;
 
         File {
             network: Some(network),
             http_api: Some(file::HttpApi {
                 socket,
+                unix_socket,
                 cors: Some(file::Cors {
                     allowed_origins: match cors.allowed_origins {
                         AllowedOrigins::All => file::AllowedOrigins::All(file::All::All),
@@ -102,6 +255,14 @@ impl From<Settings> for File {
                         AllowedOrigins::Some(origins) => file::AllowedOrigins::Some(origins),
                     },
                 }),
+                auth: Some(file::Auth {
+                    enabled: auth.enabled,
+                }),
+                tls: tls.map(|tls| file::Tls {
+                    cert_path: Some(tls.cert_path),
+                    key_path: Some(tls.key_path),
+                    client_ca_path: tls.client_ca_path,
+                }),
             }),
             data: Some(data),
             logging: Some(file::Logging {
@@ -110,21 +271,41 @@ impl From<Settings> for File {
             bitcoin: Some(bitcoin.into()),
             ethereum: Some(ethereum.into()),
             lightning: Some(lightning.into()),
+            autopilot: autopilot.map(Into::into),
+            expiry_monitor: Some(expiry_monitor.into()),
+            webhooks: if webhooks.is_empty() {
+                None
+            } else {
+                Some(webhooks)
+            },
+            metrics: metrics.map(|metrics| file::Metrics {
+                socket: Some(metrics.socket),
+            }),
         }
     }
 }
 
 #[derive(Clone, Debug, PartialEq)]
 pub struct HttpApi {
-    pub socket: SocketAddr,
+    /// Not listening on TCP if `None`.
+    pub socket: Option<SocketAddr>,
+    /// Also serve the API on this Unix domain socket, without TLS. Access is
+    /// restricted to the owner and the group of the socket file.
+    pub unix_socket: Option<PathBuf>,
     pub cors: Cors,
+    pub auth: Auth,
+    /// Plain HTTP if `None`.
+    pub tls: Option<Tls>,
 }
 
 impl Default for HttpApi {
     fn default() -> Self {
         Self {
-            socket: SocketAddr::new(IpAddr::V4(Ipv4Addr::UNSPECIFIED), 8000),
+            socket: Some(SocketAddr::new(IpAddr::V4(Ipv4Addr::UNSPECIFIED), 8000)),
+            unix_socket: None,
             cors: Cors::default(),
+            auth: Auth::default(),
+            tls: None,
         }
     }
 }
@@ -149,6 +330,29 @@ pub enum AllowedOrigins {
     Some(Vec<String>),
 }
 
+/// Bearer token authentication of the HTTP API, the tokens are generated into
+/// the data directory.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct Auth {
+    pub enabled: bool,
+}
+
+/// Serve the HTTP API over HTTPS. A self-signed certificate is generated if
+/// neither the certificate nor the key exist.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Tls {
+    pub cert_path: PathBuf,
+    pub key_path: PathBuf,
+    /// Only accept clients with a certificate signed by one of these CAs.
+    pub client_ca_path: Option<PathBuf>,
+}
+
+/// Serve Prometheus metrics at `/metrics` on this socket.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Metrics {
+    pub socket: SocketAddr,
+}
+
 #[derive(Clone, Copy, Debug, PartialEq, derivative::Derivative)]
 #[derivative(Default)]
 pub struct Logging {
@@ -166,8 +370,41 @@ impl Settings {
             bitcoin,
             ethereum,
             lightning,
+            autopilot,
+            expiry_monitor,
+            webhooks,
+            metrics,
         } = config_file;
 
+        let bitcoin = derive_url_bitcoin(bitcoin);
+        let lightning = match lightning {
+            None => Lightning::default(),
+            Some(lightning) => Lightning {
+                network: lightning.network,
+                lnd: match lightning.lnd {
+                    None => Lnd::default(),
+                    Some(lnd) => Lnd {
+                        rest_api_url: check_url_lnd(lnd.rest_api_url)?,
+                        dir: lnd.dir.clone(),
+                        cert_path: default_lnd_cert_path(lnd.dir.clone()),
+                        readonly_macaroon_path: default_lnd_readonly_macaroon_path(
+                            lnd.dir,
+                            lightning.network,
+                        ),
+                    },
+                },
+            },
+        };
+        let autopilot = derive_autopilot(autopilot, &bitcoin, &lightning)?;
+        let data = {
+            let default_data_dir =
+                crate::data_dir().context("unable to determine default data path")?;
+            data.unwrap_or(Data {
+                dir: default_data_dir,
+            })
+        };
+        let http_api = derive_http_api(http_api, &data);
+
         Ok(Self {
             network: network.unwrap_or_else(|| {
                 let default_socket = "/ip4/0.0.0.0/tcp/9939"
@@ -178,33 +415,8 @@ impl Settings {
                     listen: vec![default_socket],
                 }
             }),
-            http_api: http_api
-                .map(|file::HttpApi { socket, cors }| {
-                    let cors = cors
-                        .map(|cors| {
-                            let allowed_origins = match cors.allowed_origins {
-                                file::AllowedOrigins::All(_) => AllowedOrigins::All,
-                                file::AllowedOrigins::None(_) => AllowedOrigins::None,
-                                file::AllowedOrigins::Some(origins) => {
-                                    AllowedOrigins::Some(origins)
-                                }
-                            };
-
-                            Cors { allowed_origins }
-                        })
-                        .unwrap_or_default();
-
-                    HttpApi { socket, cors }
-                })
-                .unwrap_or_default(),
-            data: {
-                let default_data_dir =
-                    crate::data_dir().context("unable to determine default data path")?;
-                data.unwrap_or(Data {
-                    dir: default_data_dir,
-                })
-            },
-
+            http_api,
+            data,
             logging: {
                 match logging {
                     None => Logging::default(),
@@ -216,26 +428,13 @@ impl Settings {
                     },
                 }
             },
-            bitcoin: derive_url_bitcoin(bitcoin),
+            bitcoin,
             ethereum: derive_url_ethereum(ethereum),
-            lightning: match lightning {
-                None => Lightning::default(),
-                Some(lightning) => Lightning {
-                    network: lightning.network,
-                    lnd: match lightning.lnd {
-                        None => Lnd::default(),
-                        Some(lnd) => Lnd {
-                            rest_api_url: check_url_lnd(lnd.rest_api_url)?,
-                            dir: lnd.dir.clone(),
-                            cert_path: default_lnd_cert_path(lnd.dir.clone()),
-                            readonly_macaroon_path: default_lnd_readonly_macaroon_path(
-                                lnd.dir,
-                                lightning.network,
-                            ),
-                        },
-                    },
-                },
-            },
+            lightning,
+            autopilot,
+            expiry_monitor: derive_expiry_monitor(expiry_monitor),
+            webhooks: webhooks.unwrap_or_default(),
+            metrics: derive_metrics(metrics),
         })
     }
 }
@@ -244,9 +443,12 @@ impl Settings {
 mod tests {
 
     use super::*;
-    use crate::{config::file, swap_protocols::ledger::ethereum};
+    use crate::{
+        config::{file, BitcoindRpcAuth, Redacted},
+        swap_protocols::ledger::ethereum,
+    };
     use spectral::prelude::*;
-    use std::net::IpAddr;
+    use std::{net::IpAddr, path::PathBuf};
 
     #[test]
     fn logging_section_defaults_to_info() {
@@ -269,8 +471,11 @@ mod tests {
     fn cors_section_defaults_to_no_allowed_foreign_origins() {
         let config_file = File {
             http_api: Some(file::HttpApi {
-                socket: SocketAddr::new(IpAddr::V4(Ipv4Addr::UNSPECIFIED), 8000),
+                socket: Some(SocketAddr::new(IpAddr::V4(Ipv4Addr::UNSPECIFIED), 8000)),
+                unix_socket: None,
                 cors: None,
+                auth: None,
+                tls: None,
             }),
             ..File::default()
         };
@@ -298,13 +503,65 @@ mod tests {
             .is_ok()
             .map(|settings| &settings.http_api)
             .is_equal_to(HttpApi {
-                socket: SocketAddr::new(IpAddr::V4(Ipv4Addr::UNSPECIFIED), 8000),
+                socket: Some(SocketAddr::new(IpAddr::V4(Ipv4Addr::UNSPECIFIED), 8000)),
+                unix_socket: None,
                 cors: Cors {
                     allowed_origins: AllowedOrigins::None,
                 },
+                auth: Auth { enabled: false },
+                tls: None,
             })
     }
 
+    #[test]
+    fn unix_socket_replaces_default_tcp_socket() {
+        let config_file = File {
+            http_api: Some(file::HttpApi {
+                socket: None,
+                unix_socket: Some(PathBuf::from("/tmp/comit/cnd.sock")),
+                cors: None,
+                auth: None,
+                tls: None,
+            }),
+            ..File::default()
+        };
+
+        let settings = Settings::from_config_file_and_defaults(config_file);
+
+        assert_that(&settings)
+            .is_ok()
+            .map(|settings| &settings.http_api.socket)
+            .is_none()
+    }
+
+    #[test]
+    fn tls_files_default_to_data_dir() {
+        let config_file = File {
+            http_api: Some(file::HttpApi {
+                socket: Some(SocketAddr::new(IpAddr::V4(Ipv4Addr::UNSPECIFIED), 8000)),
+                unix_socket: None,
+                cors: None,
+                auth: None,
+                tls: Some(file::Tls::default()),
+            }),
+            data: Some(Data {
+                dir: PathBuf::from("/tmp/comit/"),
+            }),
+            ..File::default()
+        };
+
+        let settings = Settings::from_config_file_and_defaults(config_file);
+
+        assert_that(&settings)
+            .is_ok()
+            .map(|settings| &settings.http_api.tls)
+            .is_equal_to(Some(Tls {
+                cert_path: PathBuf::from("/tmp/comit/tls.cert"),
+                key_path: PathBuf::from("/tmp/comit/tls.key"),
+                client_ca_path: None,
+            }))
+    }
+
     #[test]
     fn network_section_defaults() {
         let config_file = File {
@@ -335,7 +592,11 @@ mod tests {
                 network: bitcoin::Network::Regtest,
                 bitcoind: Bitcoind {
                     node_url: "http://localhost:18443".parse().unwrap(),
+                    rpc_auth: None,
                 },
+                esplora: None,
+                min_confirmations: 1,
+                start_height_margin: 6,
             })
     }
 
@@ -352,6 +613,9 @@ mod tests {
                 bitcoin: Some(file::Bitcoin {
                     network,
                     bitcoind: None,
+                    esplora: None,
+                    min_confirmations: None,
+                    start_height_margin: None,
                 }),
                 ..File::default()
             };
@@ -365,11 +629,65 @@ mod tests {
                     network,
                     bitcoind: Bitcoind {
                         node_url: url.parse().unwrap(),
+                        rpc_auth: None,
                     },
+                    esplora: None,
+                    min_confirmations: 1,
+                    start_height_margin: 6,
                 })
         }
     }
 
+    #[test]
+    fn min_confirmations_are_taken_from_config_file() {
+        let config_file = File {
+            bitcoin: Some(file::Bitcoin {
+                network: bitcoin::Network::Bitcoin,
+                bitcoind: None,
+                esplora: None,
+                min_confirmations: Some(3),
+                start_height_margin: None,
+            }),
+            ethereum: Some(file::Ethereum {
+                chain_id: ethereum::ChainId::mainnet(),
+                parity: None,
+                min_confirmations: Some(12),
+                start_height_margin: None,
+            }),
+            ..File::default()
+        };
+
+        let settings = Settings::from_config_file_and_defaults(config_file).unwrap();
+
+        assert_that(&settings.bitcoin.min_confirmations).is_equal_to(3);
+        assert_that(&settings.ethereum.min_confirmations).is_equal_to(12);
+    }
+
+    #[test]
+    fn start_height_margins_are_taken_from_config_file() {
+        let config_file = File {
+            bitcoin: Some(file::Bitcoin {
+                network: bitcoin::Network::Bitcoin,
+                bitcoind: None,
+                esplora: None,
+                min_confirmations: None,
+                start_height_margin: Some(10),
+            }),
+            ethereum: Some(file::Ethereum {
+                chain_id: ethereum::ChainId::mainnet(),
+                parity: None,
+                min_confirmations: None,
+                start_height_margin: Some(40),
+            }),
+            ..File::default()
+        };
+
+        let settings = Settings::from_config_file_and_defaults(config_file).unwrap();
+
+        assert_that(&settings.bitcoin.start_height_margin).is_equal_to(10);
+        assert_that(&settings.ethereum.start_height_margin).is_equal_to(40);
+    }
+
     #[test]
     fn ethereum_defaults() {
         let config_file = File { ..File::default() };
@@ -383,7 +701,10 @@ mod tests {
                 chain_id: ethereum::ChainId::regtest(),
                 parity: Parity {
                     node_url: "http://localhost:8545".parse().unwrap(),
+                    ws_url: None,
                 },
+                min_confirmations: 1,
+                start_height_margin: 20,
             })
     }
 
@@ -399,6 +720,8 @@ mod tests {
             let ethereum = Some(file::Ethereum {
                 chain_id,
                 parity: None,
+                min_confirmations: None,
+                start_height_margin: None,
             });
             let config_file = File {
                 ethereum,
@@ -414,7 +737,10 @@ mod tests {
                     chain_id,
                     parity: Parity {
                         node_url: url.parse().unwrap(),
+                        ws_url: None,
                     },
+                    min_confirmations: 1,
+                    start_height_margin: 20,
                 })
         }
     }
@@ -472,4 +798,154 @@ mod tests {
 
         assert_that(&settings).is_err();
     }
+
+    #[test]
+    fn autopilot_is_disabled_by_default() {
+        let settings = Settings::from_config_file_and_defaults(File::default());
+
+        assert_that(&settings)
+            .is_ok()
+            .map(|settings| &settings.autopilot)
+            .is_none()
+    }
+
+    #[test]
+    fn autopilot_section_defaults() {
+        let config_file = File {
+            bitcoin: Some(file::Bitcoin {
+                network: bitcoin::Network::Regtest,
+                bitcoind: Some(Bitcoind {
+                    node_url: "http://localhost:18443".parse().unwrap(),
+                    rpc_auth: Some(BitcoindRpcAuth::UserPassword {
+                        user: "user".to_owned(),
+                        password: Redacted::from("password"),
+                    }),
+                }),
+                esplora: None,
+                min_confirmations: None,
+                start_height_margin: None,
+            }),
+            lightning: Some(file::Lightning {
+                network: bitcoin::Network::Regtest,
+                lnd: Some(file::Lnd {
+                    rest_api_url: "https://localhost:8080".parse().unwrap(),
+                    dir: PathBuf::from("/foo/lnd"),
+                }),
+            }),
+            autopilot: Some(file::Autopilot {
+                bitcoin: Some(file::AutopilotBitcoin {
+                    wallet: None,
+                    fee_per_wu: None,
+                }),
+                ethereum: Some(file::AutopilotEthereum {
+                    private_key_file: PathBuf::from("/foo/ethereum.key"),
+                    gas_price: None,
+                }),
+                lightning: Some(file::AutopilotLightning {
+                    admin_macaroon_path: None,
+                }),
+            }),
+            ..File::default()
+        };
+
+        let settings = Settings::from_config_file_and_defaults(config_file);
+
+        assert_that(&settings)
+            .is_ok()
+            .map(|settings| &settings.autopilot)
+            .is_equal_to(Some(Autopilot {
+                bitcoin: Some(AutopilotBitcoin {
+                    wallet: None,
+                    fee_per_wu: DEFAULT_AUTOPILOT_FEE_PER_WU,
+                }),
+                ethereum: Some(AutopilotEthereum {
+                    private_key_file: PathBuf::from("/foo/ethereum.key"),
+                    gas_price: DEFAULT_AUTOPILOT_GAS_PRICE,
+                }),
+                lightning: Some(AutopilotLightning {
+                    admin_macaroon_path: PathBuf::from(
+                        "/foo/lnd/data/chain/bitcoin/regtest/admin.macaroon",
+                    ),
+                }),
+            }))
+    }
+
+    #[test]
+    fn error_on_bitcoin_autopilot_without_rpc_auth() {
+        let config_file = File {
+            autopilot: Some(file::Autopilot {
+                bitcoin: Some(file::AutopilotBitcoin {
+                    wallet: None,
+                    fee_per_wu: None,
+                }),
+                ethereum: None,
+                lightning: None,
+            }),
+            ..File::default()
+        };
+
+        let settings = Settings::from_config_file_and_defaults(config_file);
+
+        assert_that(&settings).is_err();
+    }
+
+    #[test]
+    fn expiry_monitor_section_defaults() {
+        let config_file = File {
+            expiry_monitor: Some(file::ExpiryMonitor {
+                warn_before_expiry_minutes: None,
+            }),
+            ..File::default()
+        };
+
+        let settings = Settings::from_config_file_and_defaults(config_file);
+
+        assert_that(&settings)
+            .is_ok()
+            .map(|settings| &settings.expiry_monitor)
+            .is_equal_to(ExpiryMonitor {
+                warn_before_expiry_minutes: vec![360, 60, 15],
+            })
+    }
+
+    #[test]
+    fn webhooks_default_to_none() {
+        let config_file = File::default();
+
+        let settings = Settings::from_config_file_and_defaults(config_file);
+
+        assert_that(&settings)
+            .is_ok()
+            .map(|settings| &settings.webhooks)
+            .is_empty()
+    }
+
+    #[test]
+    fn metrics_are_disabled_by_default() {
+        let config_file = File::default();
+
+        let settings = Settings::from_config_file_and_defaults(config_file);
+
+        assert_that(&settings)
+            .is_ok()
+            .map(|settings| &settings.metrics)
+            .is_none()
+    }
+
+    #[test]
+    fn metrics_socket_defaults_to_localhost() {
+        let config_file = File {
+            metrics: Some(file::Metrics { socket: None }),
+            ..File::default()
+        };
+
+        let settings = Settings::from_config_file_and_defaults(config_file);
+
+        assert_that(&settings)
+            .is_ok()
+            .map(|settings| &settings.metrics)
+            .is_equal_to(Some(Metrics {
+                socket: "127.0.0.1:9090".parse().unwrap(),
+            }))
+    }
 }
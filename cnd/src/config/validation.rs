@@ -30,3 +30,9 @@ where
 pub trait FetchNetworkId<S>: Send + Sync + 'static {
     async fn network_id(&self) -> anyhow::Result<S>;
 }
+
+/// Whether a node is still catching up with the rest of its network.
+#[async_trait]
+pub trait FetchSyncState: Send + Sync + 'static {
+    async fn is_syncing(&self) -> anyhow::Result<bool>;
+}
@@ -1,8 +1,12 @@
+mod autopilot_actions;
+mod checkpoints;
 #[cfg(test)]
 mod integration_tests;
 mod load_swaps;
 mod save;
 mod schema;
+mod swap_listing;
+mod webhook_deliveries;
 mod wrapper_types;
 #[macro_use]
 mod swap;
@@ -13,16 +17,31 @@ pub mod with_swap_types;
 embed_migrations!("./migrations");
 
 pub use self::{
-    load_swaps::{AcceptedSwap, LoadAcceptedSwap},
+    autopilot_actions::{AutopilotActions, Claim},
+    checkpoints::{
+        SwapStartHeights, SwapWatcherCheckpoint, SwapWatcherCheckpoints, WatcherCheckpoint,
+        WatcherCheckpoints,
+    },
+    load_swaps::{AcceptedSwap, LoadAcceptedSwap, LoadCreatedSwaps, LoadedSwap},
     save::*,
     swap::*,
+    swap_listing::{
+        Cursor, InvalidCursor, ListedSwap, Order, Page, SwapFilter, SwapKind, SwapPage, SwapStatus,
+    },
     swap_types::*,
+    webhook_deliveries::{WebhookDeliveries, WebhookDelivery},
+    wrapper_types::Ledger,
 };
 
 use crate::{
     db::wrapper_types::custom_sql_types::Text,
-    swap_protocols::{rfc003::SwapId, LocalSwapId, Role},
+    identity,
+    swap_protocols::{
+        rfc003::{SecretHash, SwapId},
+        LocalSwapId, Role, SharedSwapId,
+    },
 };
+use chrono::NaiveDateTime;
 use diesel::{self, prelude::*, sqlite::SqliteConnection};
 use libp2p::PeerId;
 use std::{
@@ -105,6 +124,19 @@ impl Sqlite {
 
         Ok(*record.role)
     }
+
+    /// Returns the ids of all swaps that use the split protocols.
+    pub async fn all_local_swap_ids(&self) -> anyhow::Result<Vec<LocalSwapId>> {
+        use self::schema::swaps;
+
+        let swap_ids: Vec<Text<LocalSwapId>> = self
+            .do_in_transaction(|connection| {
+                swaps::table.select(swaps::local_swap_id).load(connection)
+            })
+            .await?;
+
+        Ok(swap_ids.into_iter().map(|swap_id| *swap_id).collect())
+    }
 }
 
 // Construct an absolute path to the database file using 'dir' as the base.
@@ -138,7 +170,7 @@ pub enum Error {
 /// 'create' a swap is defined as the process of initiating a swap within `cnd`.
 /// The data required to do so is assumed to have been negotiated between the
 /// two parties prior to each creating the swap.
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq)]
 pub struct CreatedSwap<A, B> {
     /// Node specific swap identifier.
     pub swap_id: LocalSwapId,
@@ -150,6 +182,49 @@ pub struct CreatedSwap<A, B> {
     pub peer: PeerId,
     /// Role of the node in this swap, Alice or Bob.
     pub role: Role,
+    /// Point in time the swap was created.
+    pub created_at: NaiveDateTime,
+}
+
+/// Data learned from the counterparty while finalizing a swap.
+///
+/// 'finalize' a swap is defined as the process of executing the communication
+/// protocols with the counterparty of a created swap. Once finalized, the swap
+/// is ready to be executed on the ledgers.
+#[derive(Debug, Clone, PartialEq)]
+pub struct FinalizedSwapData {
+    /// Node specific swap identifier.
+    pub swap_id: LocalSwapId,
+    /// Swap identifier shared with the counterparty.
+    pub shared_swap_id: SharedSwapId,
+    /// The secret hash used for the HTLCs on both ledgers.
+    pub secret_hash: SecretHash,
+    /// Ethereum identity of the swap counterparty.
+    pub counterparty_ethereum_identity: identity::Ethereum,
+    /// Lightning identity of the swap counterparty.
+    pub counterparty_lightning_identity: identity::Lightning,
+    /// Point in time from which on the ledgers are watched for events.
+    pub start_of_swap: NaiveDateTime,
+}
+
+/// Data learned from the counterparty while finalizing a swap that uses hbit.
+///
+/// Same as [`FinalizedSwapData`](struct.FinalizedSwapData.html) except that the
+/// counterparty identity on the Bitcoin side is an hbit identity.
+#[derive(Debug, Clone, PartialEq)]
+pub struct FinalizedHbitSwapData {
+    /// Node specific swap identifier.
+    pub swap_id: LocalSwapId,
+    /// Swap identifier shared with the counterparty.
+    pub shared_swap_id: SharedSwapId,
+    /// The secret hash used for the HTLCs on both ledgers.
+    pub secret_hash: SecretHash,
+    /// Ethereum identity of the swap counterparty.
+    pub counterparty_ethereum_identity: identity::Ethereum,
+    /// Bitcoin identity of the swap counterparty.
+    pub counterparty_bitcoin_identity: identity::Bitcoin,
+    /// Point in time from which on the ledgers are watched for events.
+    pub start_of_swap: NaiveDateTime,
 }
 
 #[cfg(test)]
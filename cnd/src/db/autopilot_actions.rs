@@ -0,0 +1,142 @@
+use crate::{
+    db::{schema::autopilot_actions, wrapper_types::custom_sql_types::Text, Sqlite},
+    swap_protocols::rfc003::actions::ActionKind,
+};
+use async_trait::async_trait;
+use chrono::{Duration, NaiveDateTime};
+use diesel::{ExpressionMethods, OptionalExtension, QueryDsl, RunQueryDsl};
+use uuid::Uuid;
+
+/// Remember which actions the autopilot attempted.
+///
+/// Swaps are identified by their UUID, regardless of whether they are rfc003
+/// or split protocol swaps. An attempt is recorded before the transaction of
+/// the action is broadcast, a crash while broadcasting must not lead to the
+/// action being executed twice.
+#[async_trait]
+pub trait AutopilotActions: Send + Sync + 'static {
+    /// Returns when the action was last attempted, `None` if it never was.
+    async fn load_attempted_at(
+        &self,
+        swap_id: Uuid,
+        action: ActionKind,
+    ) -> anyhow::Result<Option<NaiveDateTime>>;
+    /// Records an attempt of the action unless it was attempted before.
+    ///
+    /// A previous attempt is only superseded if `retry_after` is given and
+    /// has passed since. Returns `None` if the action must not be attempted.
+    async fn claim_action(
+        &self,
+        swap_id: Uuid,
+        action: ActionKind,
+        attempted_at: NaiveDateTime,
+        retry_after: Option<Duration>,
+    ) -> anyhow::Result<Option<Claim>>;
+    /// Undoes a claim whose action was not broadcast.
+    async fn release_action(&self, claim: Claim) -> anyhow::Result<()>;
+}
+
+/// An attempt of an action recorded by `claim_action`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Claim {
+    pub swap_id: Uuid,
+    pub action: ActionKind,
+    /// The attempt superseded by this one, if any.
+    pub previous_attempt: Option<NaiveDateTime>,
+}
+
+#[async_trait]
+impl AutopilotActions for Sqlite {
+    async fn load_attempted_at(
+        &self,
+        swap_id: Uuid,
+        action: ActionKind,
+    ) -> anyhow::Result<Option<NaiveDateTime>> {
+        let attempted_at = self
+            .do_in_transaction(|connection| {
+                autopilot_actions::table
+                    .filter(autopilot_actions::swap_id.eq(Text(swap_id)))
+                    .filter(autopilot_actions::action.eq(Text(action)))
+                    .select(autopilot_actions::executed_at)
+                    .first(connection)
+                    .optional()
+            })
+            .await?;
+
+        Ok(attempted_at)
+    }
+
+    async fn claim_action(
+        &self,
+        swap_id: Uuid,
+        action: ActionKind,
+        attempted_at: NaiveDateTime,
+        retry_after: Option<Duration>,
+    ) -> anyhow::Result<Option<Claim>> {
+        let insertable = InsertableAutopilotAction {
+            swap_id: Text(swap_id),
+            action: Text(action),
+            executed_at: attempted_at,
+        };
+
+        let claim = self
+            .do_in_transaction(|connection| {
+                let previous_attempt: Option<NaiveDateTime> = autopilot_actions::table
+                    .filter(autopilot_actions::swap_id.eq(insertable.swap_id))
+                    .filter(autopilot_actions::action.eq(insertable.action))
+                    .select(autopilot_actions::executed_at)
+                    .first(connection)
+                    .optional()?;
+
+                let may_attempt = match (previous_attempt, retry_after) {
+                    (None, _) => true,
+                    (Some(previous_attempt), Some(retry_after)) => {
+                        attempted_at.signed_duration_since(previous_attempt) >= retry_after
+                    }
+                    (Some(_), None) => false,
+                };
+                if !may_attempt {
+                    return Ok(None);
+                }
+
+                diesel::replace_into(autopilot_actions::table)
+                    .values(&insertable)
+                    .execute(connection)?;
+
+                Ok::<_, diesel::result::Error>(Some(Claim {
+                    swap_id,
+                    action,
+                    previous_attempt,
+                }))
+            })
+            .await?;
+
+        Ok(claim)
+    }
+
+    async fn release_action(&self, claim: Claim) -> anyhow::Result<()> {
+        self.do_in_transaction(|connection| {
+            let actions = autopilot_actions::table
+                .filter(autopilot_actions::swap_id.eq(Text(claim.swap_id)))
+                .filter(autopilot_actions::action.eq(Text(claim.action)));
+
+            match claim.previous_attempt {
+                Some(previous_attempt) => diesel::update(actions)
+                    .set(autopilot_actions::executed_at.eq(previous_attempt))
+                    .execute(connection),
+                None => diesel::delete(actions).execute(connection),
+            }
+        })
+        .await?;
+
+        Ok(())
+    }
+}
+
+#[derive(Insertable, Debug, Clone, Copy)]
+#[table_name = "autopilot_actions"]
+struct InsertableAutopilotAction {
+    swap_id: Text<Uuid>,
+    action: Text<ActionKind>,
+    executed_at: NaiveDateTime,
+}
@@ -0,0 +1,450 @@
+use crate::{
+    btsieve::Checkpoint,
+    db::{
+        schema::{rfc003_watcher_checkpoints as checkpoints, swap_watcher_checkpoints},
+        wrapper_types::{
+            custom_sql_types::{Text, U32},
+            Ledger,
+        },
+        Sqlite,
+    },
+    swap_protocols::{
+        rfc003::{
+            events::{Deployed, Funded},
+            SwapId,
+        },
+        LocalSwapId,
+    },
+};
+use async_trait::async_trait;
+use chrono::NaiveDateTime;
+use diesel::{ExpressionMethods, OptionalExtension, QueryDsl, RunQueryDsl};
+use serde::{de::DeserializeOwned, Serialize};
+
+/// How far the watcher of a swap got on one of its ledgers.
+///
+/// Events that were already observed don't need to be looked for again and the
+/// scan for the remaining ones only needs to go back as far as `scanned`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct WatcherCheckpoint<A, H, T> {
+    /// Height of the first block that is looked at, recorded when the swap was
+    /// accepted.
+    pub start_height: Option<u32>,
+    pub deployed: Option<Deployed<H, T>>,
+    pub funded: Option<Funded<A, T>>,
+    pub scanned: Option<Checkpoint>,
+}
+
+impl<A, H, T> Default for WatcherCheckpoint<A, H, T> {
+    fn default() -> Self {
+        Self {
+            start_height: None,
+            deployed: None,
+            funded: None,
+            scanned: None,
+        }
+    }
+}
+
+/// Load and save the progress of the watchers of a swap.
+#[async_trait]
+pub trait WatcherCheckpoints<A, H, T>: Send + Sync + 'static {
+    /// Returns an empty checkpoint if nothing was saved for this swap yet.
+    async fn load_watcher_checkpoint(
+        &self,
+        swap_id: &SwapId,
+        ledger: Ledger,
+    ) -> anyhow::Result<WatcherCheckpoint<A, H, T>>;
+    async fn save_watcher_checkpoint(
+        &self,
+        swap_id: &SwapId,
+        ledger: Ledger,
+        checkpoint: WatcherCheckpoint<A, H, T>,
+    ) -> anyhow::Result<()>;
+}
+
+#[async_trait]
+impl<A, H, T> WatcherCheckpoints<A, H, T> for Sqlite
+where
+    A: Serialize + DeserializeOwned + Send + 'static,
+    H: Serialize + DeserializeOwned + Send + 'static,
+    T: Serialize + DeserializeOwned + Send + 'static,
+{
+    async fn load_watcher_checkpoint(
+        &self,
+        swap_id: &SwapId,
+        ledger: Ledger,
+    ) -> anyhow::Result<WatcherCheckpoint<A, H, T>> {
+        let record: Option<QueryableCheckpoint> = self
+            .do_in_transaction(|connection| {
+                checkpoints::table
+                    .filter(checkpoints::swap_id.eq(Text(swap_id)))
+                    .filter(checkpoints::ledger.eq(Text(ledger)))
+                    .select((
+                        checkpoints::block_hash,
+                        checkpoints::block_height,
+                        checkpoints::block_timestamp,
+                        checkpoints::deployed,
+                        checkpoints::funded,
+                        checkpoints::start_height,
+                    ))
+                    .first(connection)
+                    .optional()
+            })
+            .await?;
+
+        let record = match record {
+            Some(record) => record,
+            None => return Ok(WatcherCheckpoint::default()),
+        };
+
+        let scanned = match (record.block_hash, record.block_timestamp) {
+            (Some(block_hash), Some(timestamp)) => Some(Checkpoint {
+                block_hash,
+                height: record.block_height.map(|height| height.0),
+                timestamp,
+            }),
+            _ => None,
+        };
+
+        Ok(WatcherCheckpoint {
+            start_height: record.start_height.map(|start_height| start_height.0),
+            deployed: record
+                .deployed
+                .map(|deployed| serde_json::from_str(&deployed))
+                .transpose()?,
+            funded: record
+                .funded
+                .map(|funded| serde_json::from_str(&funded))
+                .transpose()?,
+            scanned,
+        })
+    }
+
+    async fn save_watcher_checkpoint(
+        &self,
+        swap_id: &SwapId,
+        ledger: Ledger,
+        checkpoint: WatcherCheckpoint<A, H, T>,
+    ) -> anyhow::Result<()> {
+        let insertable = InsertableCheckpoint {
+            swap_id: Text(*swap_id),
+            ledger: Text(ledger),
+            block_hash: checkpoint
+                .scanned
+                .as_ref()
+                .map(|scanned| scanned.block_hash.clone()),
+            block_height: checkpoint
+                .scanned
+                .as_ref()
+                .and_then(|scanned| scanned.height)
+                .map(U32),
+            block_timestamp: checkpoint.scanned.as_ref().map(|scanned| scanned.timestamp),
+            deployed: checkpoint
+                .deployed
+                .as_ref()
+                .map(serde_json::to_string)
+                .transpose()?,
+            funded: checkpoint
+                .funded
+                .as_ref()
+                .map(serde_json::to_string)
+                .transpose()?,
+            start_height: checkpoint.start_height.map(U32),
+        };
+
+        self.do_in_transaction(|connection| {
+            diesel::replace_into(checkpoints::table)
+                .values(&insertable)
+                .execute(connection)
+        })
+        .await?;
+
+        Ok(())
+    }
+}
+
+#[derive(Queryable, Debug, Clone, PartialEq)]
+struct QueryableCheckpoint {
+    block_hash: Option<String>,
+    block_height: Option<U32>,
+    block_timestamp: Option<NaiveDateTime>,
+    deployed: Option<String>,
+    funded: Option<String>,
+    start_height: Option<U32>,
+}
+
+#[derive(Insertable, Debug, Clone)]
+#[table_name = "checkpoints"]
+struct InsertableCheckpoint {
+    swap_id: Text<SwapId>,
+    ledger: Text<Ledger>,
+    block_hash: Option<String>,
+    block_height: Option<U32>,
+    block_timestamp: Option<NaiveDateTime>,
+    deployed: Option<String>,
+    funded: Option<String>,
+    start_height: Option<U32>,
+}
+
+/// How far the watcher of a swap that uses the split protocols got on one of
+/// its ledgers, see `WatcherCheckpoint`.
+///
+/// The start height is not part of it, it is recorded separately when the swap
+/// is created. Protocols without a deployed event, like hbit, use `()` for `D`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct SwapWatcherCheckpoint<D, F> {
+    pub deployed: Option<D>,
+    pub funded: Option<F>,
+    pub scanned: Option<Checkpoint>,
+}
+
+impl<D, F> Default for SwapWatcherCheckpoint<D, F> {
+    fn default() -> Self {
+        Self {
+            deployed: None,
+            funded: None,
+            scanned: None,
+        }
+    }
+}
+
+/// Load and save the progress of the watchers of a swap that uses the split
+/// protocols.
+#[async_trait]
+pub trait SwapWatcherCheckpoints<D, F>: Send + Sync + 'static {
+    /// Returns an empty checkpoint if nothing was saved for this swap yet.
+    async fn load_swap_watcher_checkpoint(
+        &self,
+        swap_id: &LocalSwapId,
+        ledger: Ledger,
+    ) -> anyhow::Result<SwapWatcherCheckpoint<D, F>>;
+    async fn save_swap_watcher_checkpoint(
+        &self,
+        swap_id: &LocalSwapId,
+        ledger: Ledger,
+        checkpoint: SwapWatcherCheckpoint<D, F>,
+    ) -> anyhow::Result<()>;
+}
+
+#[async_trait]
+impl<D, F> SwapWatcherCheckpoints<D, F> for Sqlite
+where
+    D: Serialize + DeserializeOwned + Send + 'static,
+    F: Serialize + DeserializeOwned + Send + 'static,
+{
+    async fn load_swap_watcher_checkpoint(
+        &self,
+        swap_id: &LocalSwapId,
+        ledger: Ledger,
+    ) -> anyhow::Result<SwapWatcherCheckpoint<D, F>> {
+        let record: Option<QueryableSwapCheckpoint> = self
+            .do_in_transaction(|connection| {
+                swap_watcher_checkpoints::table
+                    .filter(swap_watcher_checkpoints::swap_id.eq(Text(swap_id)))
+                    .filter(swap_watcher_checkpoints::ledger.eq(Text(ledger)))
+                    .select((
+                        swap_watcher_checkpoints::block_hash,
+                        swap_watcher_checkpoints::block_height,
+                        swap_watcher_checkpoints::block_timestamp,
+                        swap_watcher_checkpoints::deployed,
+                        swap_watcher_checkpoints::funded,
+                    ))
+                    .first(connection)
+                    .optional()
+            })
+            .await?;
+
+        let record = match record {
+            Some(record) => record,
+            None => return Ok(SwapWatcherCheckpoint::default()),
+        };
+
+        let scanned = match (record.block_hash, record.block_timestamp) {
+            (Some(block_hash), Some(timestamp)) => Some(Checkpoint {
+                block_hash,
+                height: record.block_height.map(|height| height.0),
+                timestamp,
+            }),
+            _ => None,
+        };
+
+        Ok(SwapWatcherCheckpoint {
+            deployed: record
+                .deployed
+                .map(|deployed| serde_json::from_str(&deployed))
+                .transpose()?,
+            funded: record
+                .funded
+                .map(|funded| serde_json::from_str(&funded))
+                .transpose()?,
+            scanned,
+        })
+    }
+
+    /// Leaves the start height of the swap untouched.
+    async fn save_swap_watcher_checkpoint(
+        &self,
+        swap_id: &LocalSwapId,
+        ledger: Ledger,
+        checkpoint: SwapWatcherCheckpoint<D, F>,
+    ) -> anyhow::Result<()> {
+        let insertable = InsertableSwapCheckpoint {
+            swap_id: Text(*swap_id),
+            ledger: Text(ledger),
+            start_height: None,
+            block_hash: checkpoint
+                .scanned
+                .as_ref()
+                .map(|scanned| scanned.block_hash.clone()),
+            block_height: checkpoint
+                .scanned
+                .as_ref()
+                .and_then(|scanned| scanned.height)
+                .map(U32),
+            block_timestamp: checkpoint.scanned.as_ref().map(|scanned| scanned.timestamp),
+            deployed: checkpoint
+                .deployed
+                .as_ref()
+                .map(serde_json::to_string)
+                .transpose()?,
+            funded: checkpoint
+                .funded
+                .as_ref()
+                .map(serde_json::to_string)
+                .transpose()?,
+        };
+
+        self.do_in_transaction(|connection| {
+            let updated = diesel::update(
+                swap_watcher_checkpoints::table
+                    .filter(swap_watcher_checkpoints::swap_id.eq(insertable.swap_id))
+                    .filter(swap_watcher_checkpoints::ledger.eq(insertable.ledger)),
+            )
+            .set((
+                swap_watcher_checkpoints::block_hash.eq(insertable.block_hash.clone()),
+                swap_watcher_checkpoints::block_height.eq(insertable.block_height),
+                swap_watcher_checkpoints::block_timestamp.eq(insertable.block_timestamp),
+                swap_watcher_checkpoints::deployed.eq(insertable.deployed.clone()),
+                swap_watcher_checkpoints::funded.eq(insertable.funded.clone()),
+            ))
+            .execute(connection)?;
+
+            if updated == 0 {
+                diesel::insert_into(swap_watcher_checkpoints::table)
+                    .values(&insertable)
+                    .execute(connection)?;
+            }
+
+            Ok::<_, diesel::result::Error>(())
+        })
+        .await?;
+
+        Ok(())
+    }
+}
+
+/// Load and save the height from which on a ledger of a swap that uses the
+/// split protocols is watched.
+///
+/// The height is recorded when the swap is created, `None` is saved if the
+/// latest block could not be fetched at that time.
+#[async_trait]
+pub trait SwapStartHeights: Send + Sync + 'static {
+    /// Returns `None` if nothing was saved for this swap.
+    async fn load_start_height(
+        &self,
+        swap_id: &LocalSwapId,
+        ledger: Ledger,
+    ) -> anyhow::Result<Option<u32>>;
+    async fn save_start_height(
+        &self,
+        swap_id: &LocalSwapId,
+        ledger: Ledger,
+        start_height: Option<u32>,
+    ) -> anyhow::Result<()>;
+}
+
+#[async_trait]
+impl SwapStartHeights for Sqlite {
+    async fn load_start_height(
+        &self,
+        swap_id: &LocalSwapId,
+        ledger: Ledger,
+    ) -> anyhow::Result<Option<u32>> {
+        let start_height: Option<Option<U32>> = self
+            .do_in_transaction(|connection| {
+                swap_watcher_checkpoints::table
+                    .filter(swap_watcher_checkpoints::swap_id.eq(Text(swap_id)))
+                    .filter(swap_watcher_checkpoints::ledger.eq(Text(ledger)))
+                    .select(swap_watcher_checkpoints::start_height)
+                    .first(connection)
+                    .optional()
+            })
+            .await?;
+
+        Ok(start_height.flatten().map(|start_height| start_height.0))
+    }
+
+    /// Leaves the progress of the watcher untouched.
+    async fn save_start_height(
+        &self,
+        swap_id: &LocalSwapId,
+        ledger: Ledger,
+        start_height: Option<u32>,
+    ) -> anyhow::Result<()> {
+        let insertable = InsertableSwapCheckpoint {
+            swap_id: Text(*swap_id),
+            ledger: Text(ledger),
+            start_height: start_height.map(U32),
+            block_hash: None,
+            block_height: None,
+            block_timestamp: None,
+            deployed: None,
+            funded: None,
+        };
+
+        self.do_in_transaction(|connection| {
+            let updated = diesel::update(
+                swap_watcher_checkpoints::table
+                    .filter(swap_watcher_checkpoints::swap_id.eq(insertable.swap_id))
+                    .filter(swap_watcher_checkpoints::ledger.eq(insertable.ledger)),
+            )
+            .set(swap_watcher_checkpoints::start_height.eq(insertable.start_height))
+            .execute(connection)?;
+
+            if updated == 0 {
+                diesel::insert_into(swap_watcher_checkpoints::table)
+                    .values(&insertable)
+                    .execute(connection)?;
+            }
+
+            Ok::<_, diesel::result::Error>(())
+        })
+        .await?;
+
+        Ok(())
+    }
+}
+
+#[derive(Queryable, Debug, Clone, PartialEq)]
+struct QueryableSwapCheckpoint {
+    block_hash: Option<String>,
+    block_height: Option<U32>,
+    block_timestamp: Option<NaiveDateTime>,
+    deployed: Option<String>,
+    funded: Option<String>,
+}
+
+#[derive(Insertable, Debug, Clone)]
+#[table_name = "swap_watcher_checkpoints"]
+struct InsertableSwapCheckpoint {
+    swap_id: Text<LocalSwapId>,
+    ledger: Text<Ledger>,
+    start_height: Option<U32>,
+    block_hash: Option<String>,
+    block_height: Option<U32>,
+    block_timestamp: Option<NaiveDateTime>,
+    deployed: Option<String>,
+    funded: Option<String>,
+}
@@ -1,17 +1,31 @@
 use crate::{
-    asset::{Bitcoin as BitcoinAsset, Erc20, Ether},
+    asset::{ethereum::FromWei, Bitcoin as BitcoinAsset, Erc20, Ether},
+    btsieve::Checkpoint,
     db::{
         load_swaps::LoadAcceptedSwap,
         swap_types::{DetermineTypes, SwapTypes},
-        AssetKind, BitcoinLedgerKind, LedgerKind, Retrieve, Save, Sqlite, Swap,
+        AssetKind, AutopilotActions, BitcoinLedgerKind, CreatedSwap, FinalizedHbitSwapData,
+        FinalizedSwapData, Ledger, LedgerKind, ListedSwap, LoadCreatedSwaps, Order, Page, Retrieve,
+        Save, Sqlite, Swap, SwapFilter, SwapKind, SwapStartHeights, SwapStatus,
+        SwapWatcherCheckpoint, SwapWatcherCheckpoints, WatcherCheckpoint, WatcherCheckpoints,
+        WebhookDeliveries,
     },
-    identity,
+    htlc_location, identity,
     quickcheck::Quickcheck,
     swap_protocols::{
+        halight, han, hbit, herc20,
         ledger::Ethereum,
-        rfc003::{Accept, Request},
+        rfc003::{
+            actions::ActionKind,
+            events::{Deployed, Funded},
+            Accept, Request, SecretHash, SwapId,
+        },
+        LocalSwapId, Role, SharedSwapId,
     },
+    transaction,
 };
+use chrono::{NaiveDateTime, Utc};
+use libp2p::PeerId;
 use std::path::Path;
 
 use crate::swap_protocols::ledger::bitcoin::{Mainnet, Regtest, Testnet};
@@ -189,3 +203,1013 @@ db_roundtrip_test!(
         }
     }
 );
+
+#[derive(Clone, Debug)]
+struct EthereumLightningIdentities {
+    ethereum: Quickcheck<identity::Ethereum>,
+    lightning: Quickcheck<identity::Lightning>,
+    counterparty_ethereum: Quickcheck<identity::Ethereum>,
+    counterparty_lightning: Quickcheck<identity::Lightning>,
+}
+
+impl quickcheck::Arbitrary for EthereumLightningIdentities {
+    fn arbitrary<G: quickcheck::Gen>(g: &mut G) -> Self {
+        EthereumLightningIdentities {
+            ethereum: quickcheck::Arbitrary::arbitrary(g),
+            lightning: quickcheck::Arbitrary::arbitrary(g),
+            counterparty_ethereum: quickcheck::Arbitrary::arbitrary(g),
+            counterparty_lightning: quickcheck::Arbitrary::arbitrary(g),
+        }
+    }
+}
+
+#[test]
+fn roundtrip_test_han_halight_created_and_finalized_swap() {
+    fn prop(
+        role: Quickcheck<Role>,
+        peer: Quickcheck<PeerId>,
+        ether: Quickcheck<Ether>,
+        bitcoin: Quickcheck<BitcoinAsset>,
+        identities: EthereumLightningIdentities,
+        secret_hash: Quickcheck<SecretHash>,
+        expiries: (u32, u32),
+        start_of_swap: u32,
+    ) -> anyhow::Result<bool> {
+        let db = Sqlite::new(&Path::new(":memory:"))?;
+
+        let swap_id = LocalSwapId::default();
+        let created = CreatedSwap {
+            swap_id,
+            alpha: han::CreatedSwap {
+                amount: ether.0,
+                identity: *identities.ethereum,
+                chain_id: 1337,
+                absolute_expiry: expiries.0,
+            },
+            beta: halight::CreatedSwap {
+                amount: *bitcoin,
+                identity: *identities.lightning,
+                network: String::from("regtest"),
+                cltv_expiry: expiries.1,
+            },
+            peer: peer.0,
+            role: *role,
+            created_at: NaiveDateTime::from_timestamp(i64::from(start_of_swap), 0),
+        };
+        let finalized = FinalizedSwapData {
+            swap_id,
+            shared_swap_id: SharedSwapId::default(),
+            secret_hash: *secret_hash,
+            counterparty_ethereum_identity: *identities.counterparty_ethereum,
+            counterparty_lightning_identity: *identities.counterparty_lightning,
+            start_of_swap: NaiveDateTime::from_timestamp(i64::from(start_of_swap), 0),
+        };
+
+        let (loaded_after_create, loaded_after_finalize) = tokio::runtime::Runtime::new()?
+            .block_on(async {
+                db.save(created.clone()).await?;
+                let loaded_after_create =
+                    LoadCreatedSwaps::<han::CreatedSwap, halight::CreatedSwap>::load_created_swaps(
+                        &db,
+                    )
+                    .await?;
+
+                db.save(finalized.clone()).await?;
+                let loaded_after_finalize = LoadCreatedSwaps::<
+                    han::CreatedSwap,
+                    halight::CreatedSwap,
+                >::load_created_swaps(&db)
+                .await?;
+
+                anyhow::Result::<_>::Ok((loaded_after_create, loaded_after_finalize))
+            })?;
+
+        Ok(loaded_after_create == vec![(created.clone(), None)]
+            && loaded_after_finalize == vec![(created, Some(finalized))])
+    }
+
+    quickcheck::quickcheck(
+        prop as fn(
+            Quickcheck<Role>,
+            Quickcheck<PeerId>,
+            Quickcheck<Ether>,
+            Quickcheck<BitcoinAsset>,
+            EthereumLightningIdentities,
+            Quickcheck<SecretHash>,
+            (u32, u32),
+            u32,
+        ) -> anyhow::Result<bool>,
+    );
+}
+
+#[test]
+fn roundtrip_test_herc20_halight_created_and_finalized_swap() {
+    fn prop(
+        role: Quickcheck<Role>,
+        peer: Quickcheck<PeerId>,
+        erc20: Quickcheck<Erc20>,
+        bitcoin: Quickcheck<BitcoinAsset>,
+        identities: EthereumLightningIdentities,
+        secret_hash: Quickcheck<SecretHash>,
+        expiries: (u32, u32),
+        start_of_swap: u32,
+    ) -> anyhow::Result<bool> {
+        let db = Sqlite::new(&Path::new(":memory:"))?;
+
+        let swap_id = LocalSwapId::default();
+        let created = CreatedSwap {
+            swap_id,
+            alpha: herc20::CreatedSwap {
+                amount: erc20.0.quantity,
+                identity: *identities.ethereum,
+                chain_id: 1337,
+                contract_address: erc20.0.token_contract,
+                absolute_expiry: expiries.0,
+            },
+            beta: halight::CreatedSwap {
+                amount: *bitcoin,
+                identity: *identities.lightning,
+                network: String::from("regtest"),
+                cltv_expiry: expiries.1,
+            },
+            peer: peer.0,
+            role: *role,
+            created_at: NaiveDateTime::from_timestamp(i64::from(start_of_swap), 0),
+        };
+        let finalized = FinalizedSwapData {
+            swap_id,
+            shared_swap_id: SharedSwapId::default(),
+            secret_hash: *secret_hash,
+            counterparty_ethereum_identity: *identities.counterparty_ethereum,
+            counterparty_lightning_identity: *identities.counterparty_lightning,
+            start_of_swap: NaiveDateTime::from_timestamp(i64::from(start_of_swap), 0),
+        };
+
+        let (loaded_after_create, loaded_after_finalize, loaded_han_swaps) =
+            tokio::runtime::Runtime::new()?.block_on(async {
+                db.save(created.clone()).await?;
+                let loaded_after_create = LoadCreatedSwaps::<
+                    herc20::CreatedSwap,
+                    halight::CreatedSwap,
+                >::load_created_swaps(&db)
+                .await?;
+
+                db.save(finalized.clone()).await?;
+                let loaded_after_finalize = LoadCreatedSwaps::<
+                    herc20::CreatedSwap,
+                    halight::CreatedSwap,
+                >::load_created_swaps(&db)
+                .await?;
+                let loaded_han_swaps =
+                    LoadCreatedSwaps::<han::CreatedSwap, halight::CreatedSwap>::load_created_swaps(
+                        &db,
+                    )
+                    .await?;
+
+                anyhow::Result::<_>::Ok((
+                    loaded_after_create,
+                    loaded_after_finalize,
+                    loaded_han_swaps,
+                ))
+            })?;
+
+        Ok(loaded_after_create == vec![(created.clone(), None)]
+            && loaded_after_finalize == vec![(created, Some(finalized))]
+            && loaded_han_swaps.is_empty())
+    }
+
+    quickcheck::quickcheck(
+        prop as fn(
+            Quickcheck<Role>,
+            Quickcheck<PeerId>,
+            Quickcheck<Erc20>,
+            Quickcheck<BitcoinAsset>,
+            EthereumLightningIdentities,
+            Quickcheck<SecretHash>,
+            (u32, u32),
+            u32,
+        ) -> anyhow::Result<bool>,
+    );
+}
+
+#[test]
+fn roundtrip_test_halight_han_created_and_finalized_swap() {
+    fn prop(
+        role: Quickcheck<Role>,
+        peer: Quickcheck<PeerId>,
+        bitcoin: Quickcheck<BitcoinAsset>,
+        ether: Quickcheck<Ether>,
+        identities: EthereumLightningIdentities,
+        secret_hash: Quickcheck<SecretHash>,
+        expiries: (u32, u32),
+        start_of_swap: u32,
+    ) -> anyhow::Result<bool> {
+        let db = Sqlite::new(&Path::new(":memory:"))?;
+
+        let swap_id = LocalSwapId::default();
+        let created = CreatedSwap {
+            swap_id,
+            alpha: halight::CreatedSwap {
+                amount: *bitcoin,
+                identity: *identities.lightning,
+                network: String::from("regtest"),
+                cltv_expiry: expiries.0,
+            },
+            beta: han::CreatedSwap {
+                amount: ether.0,
+                identity: *identities.ethereum,
+                chain_id: 1337,
+                absolute_expiry: expiries.1,
+            },
+            peer: peer.0,
+            role: *role,
+            created_at: NaiveDateTime::from_timestamp(i64::from(start_of_swap), 0),
+        };
+        let finalized = FinalizedSwapData {
+            swap_id,
+            shared_swap_id: SharedSwapId::default(),
+            secret_hash: *secret_hash,
+            counterparty_ethereum_identity: *identities.counterparty_ethereum,
+            counterparty_lightning_identity: *identities.counterparty_lightning,
+            start_of_swap: NaiveDateTime::from_timestamp(i64::from(start_of_swap), 0),
+        };
+
+        let (loaded_after_create, loaded_after_finalize, loaded_han_halight_swaps) =
+            tokio::runtime::Runtime::new()?.block_on(async {
+                db.save(created.clone()).await?;
+                let loaded_after_create =
+                    LoadCreatedSwaps::<halight::CreatedSwap, han::CreatedSwap>::load_created_swaps(
+                        &db,
+                    )
+                    .await?;
+
+                db.save(finalized.clone()).await?;
+                let loaded_after_finalize = LoadCreatedSwaps::<
+                    halight::CreatedSwap,
+                    han::CreatedSwap,
+                >::load_created_swaps(&db)
+                .await?;
+                let loaded_han_halight_swaps = LoadCreatedSwaps::<
+                    han::CreatedSwap,
+                    halight::CreatedSwap,
+                >::load_created_swaps(&db)
+                .await?;
+
+                anyhow::Result::<_>::Ok((
+                    loaded_after_create,
+                    loaded_after_finalize,
+                    loaded_han_halight_swaps,
+                ))
+            })?;
+
+        Ok(loaded_after_create == vec![(created.clone(), None)]
+            && loaded_after_finalize == vec![(created, Some(finalized))]
+            && loaded_han_halight_swaps.is_empty())
+    }
+
+    quickcheck::quickcheck(
+        prop as fn(
+            Quickcheck<Role>,
+            Quickcheck<PeerId>,
+            Quickcheck<BitcoinAsset>,
+            Quickcheck<Ether>,
+            EthereumLightningIdentities,
+            Quickcheck<SecretHash>,
+            (u32, u32),
+            u32,
+        ) -> anyhow::Result<bool>,
+    );
+}
+
+#[test]
+fn roundtrip_test_halight_herc20_created_and_finalized_swap() {
+    fn prop(
+        role: Quickcheck<Role>,
+        peer: Quickcheck<PeerId>,
+        bitcoin: Quickcheck<BitcoinAsset>,
+        erc20: Quickcheck<Erc20>,
+        identities: EthereumLightningIdentities,
+        secret_hash: Quickcheck<SecretHash>,
+        expiries: (u32, u32),
+        start_of_swap: u32,
+    ) -> anyhow::Result<bool> {
+        let db = Sqlite::new(&Path::new(":memory:"))?;
+
+        let swap_id = LocalSwapId::default();
+        let created = CreatedSwap {
+            swap_id,
+            alpha: halight::CreatedSwap {
+                amount: *bitcoin,
+                identity: *identities.lightning,
+                network: String::from("regtest"),
+                cltv_expiry: expiries.0,
+            },
+            beta: herc20::CreatedSwap {
+                amount: erc20.0.quantity,
+                identity: *identities.ethereum,
+                chain_id: 1337,
+                contract_address: erc20.0.token_contract,
+                absolute_expiry: expiries.1,
+            },
+            peer: peer.0,
+            role: *role,
+            created_at: NaiveDateTime::from_timestamp(i64::from(start_of_swap), 0),
+        };
+        let finalized = FinalizedSwapData {
+            swap_id,
+            shared_swap_id: SharedSwapId::default(),
+            secret_hash: *secret_hash,
+            counterparty_ethereum_identity: *identities.counterparty_ethereum,
+            counterparty_lightning_identity: *identities.counterparty_lightning,
+            start_of_swap: NaiveDateTime::from_timestamp(i64::from(start_of_swap), 0),
+        };
+
+        let (loaded_after_create, loaded_after_finalize, loaded_herc20_halight_swaps) =
+            tokio::runtime::Runtime::new()?.block_on(async {
+                db.save(created.clone()).await?;
+                let loaded_after_create = LoadCreatedSwaps::<
+                    halight::CreatedSwap,
+                    herc20::CreatedSwap,
+                >::load_created_swaps(&db)
+                .await?;
+
+                db.save(finalized.clone()).await?;
+                let loaded_after_finalize = LoadCreatedSwaps::<
+                    halight::CreatedSwap,
+                    herc20::CreatedSwap,
+                >::load_created_swaps(&db)
+                .await?;
+                let loaded_herc20_halight_swaps = LoadCreatedSwaps::<
+                    herc20::CreatedSwap,
+                    halight::CreatedSwap,
+                >::load_created_swaps(&db)
+                .await?;
+
+                anyhow::Result::<_>::Ok((
+                    loaded_after_create,
+                    loaded_after_finalize,
+                    loaded_herc20_halight_swaps,
+                ))
+            })?;
+
+        Ok(loaded_after_create == vec![(created.clone(), None)]
+            && loaded_after_finalize == vec![(created, Some(finalized))]
+            && loaded_herc20_halight_swaps.is_empty())
+    }
+
+    quickcheck::quickcheck(
+        prop as fn(
+            Quickcheck<Role>,
+            Quickcheck<PeerId>,
+            Quickcheck<BitcoinAsset>,
+            Quickcheck<Erc20>,
+            EthereumLightningIdentities,
+            Quickcheck<SecretHash>,
+            (u32, u32),
+            u32,
+        ) -> anyhow::Result<bool>,
+    );
+}
+
+#[derive(Clone, Debug)]
+struct EthereumBitcoinIdentities {
+    ethereum: Quickcheck<identity::Ethereum>,
+    counterparty_ethereum: Quickcheck<identity::Ethereum>,
+    counterparty_bitcoin: Quickcheck<identity::Bitcoin>,
+}
+
+impl quickcheck::Arbitrary for EthereumBitcoinIdentities {
+    fn arbitrary<G: quickcheck::Gen>(g: &mut G) -> Self {
+        EthereumBitcoinIdentities {
+            ethereum: quickcheck::Arbitrary::arbitrary(g),
+            counterparty_ethereum: quickcheck::Arbitrary::arbitrary(g),
+            counterparty_bitcoin: quickcheck::Arbitrary::arbitrary(g),
+        }
+    }
+}
+
+#[test]
+fn roundtrip_test_hbit_han_created_and_finalized_swap() {
+    fn prop(
+        role: Quickcheck<Role>,
+        peer: Quickcheck<PeerId>,
+        bitcoin: Quickcheck<BitcoinAsset>,
+        ether: Quickcheck<Ether>,
+        identities: EthereumBitcoinIdentities,
+        secret_hash: Quickcheck<SecretHash>,
+        expiries: (u32, u32),
+        start_of_swap: u32,
+    ) -> anyhow::Result<bool> {
+        let db = Sqlite::new(&Path::new(":memory:"))?;
+
+        let swap_id = LocalSwapId::default();
+        let created = CreatedSwap {
+            swap_id,
+            alpha: hbit::CreatedSwap {
+                amount: *bitcoin,
+                network: ::bitcoin::Network::Regtest,
+                absolute_expiry: expiries.0,
+            },
+            beta: han::CreatedSwap {
+                amount: ether.0,
+                identity: *identities.ethereum,
+                chain_id: 1337,
+                absolute_expiry: expiries.1,
+            },
+            peer: peer.0,
+            role: *role,
+            created_at: NaiveDateTime::from_timestamp(i64::from(start_of_swap), 0),
+        };
+        let finalized = FinalizedHbitSwapData {
+            swap_id,
+            shared_swap_id: SharedSwapId::default(),
+            secret_hash: *secret_hash,
+            counterparty_ethereum_identity: *identities.counterparty_ethereum,
+            counterparty_bitcoin_identity: *identities.counterparty_bitcoin,
+            start_of_swap: NaiveDateTime::from_timestamp(i64::from(start_of_swap), 0),
+        };
+
+        let (loaded_after_create, loaded_after_finalize, loaded_halight_swaps) =
+            tokio::runtime::Runtime::new()?.block_on(async {
+                db.save(created.clone()).await?;
+                let loaded_after_create =
+                    LoadCreatedSwaps::<hbit::CreatedSwap, han::CreatedSwap>::load_created_swaps(
+                        &db,
+                    )
+                    .await?;
+
+                db.save(finalized.clone()).await?;
+                let loaded_after_finalize =
+                    LoadCreatedSwaps::<hbit::CreatedSwap, han::CreatedSwap>::load_created_swaps(
+                        &db,
+                    )
+                    .await?;
+                let loaded_halight_swaps =
+                    LoadCreatedSwaps::<halight::CreatedSwap, han::CreatedSwap>::load_created_swaps(
+                        &db,
+                    )
+                    .await?;
+
+                anyhow::Result::<_>::Ok((
+                    loaded_after_create,
+                    loaded_after_finalize,
+                    loaded_halight_swaps,
+                ))
+            })?;
+
+        Ok(loaded_after_create == vec![(created.clone(), None)]
+            && loaded_after_finalize == vec![(created, Some(finalized))]
+            && loaded_halight_swaps.is_empty())
+    }
+
+    quickcheck::quickcheck(
+        prop as fn(
+            Quickcheck<Role>,
+            Quickcheck<PeerId>,
+            Quickcheck<BitcoinAsset>,
+            Quickcheck<Ether>,
+            EthereumBitcoinIdentities,
+            Quickcheck<SecretHash>,
+            (u32, u32),
+            u32,
+        ) -> anyhow::Result<bool>,
+    );
+}
+
+#[test]
+fn roundtrip_test_watcher_checkpoint() -> anyhow::Result<()> {
+    let db = Sqlite::new(&Path::new(":memory:"))?;
+    let swap_id = SwapId::default();
+
+    let deployed = WatcherCheckpoint::<Ether, htlc_location::Ethereum, transaction::Ethereum> {
+        start_height: Some(9_876_543),
+        deployed: Some(Deployed {
+            location: htlc_location::Ethereum::from([1u8; 20]),
+            transaction: transaction::Ethereum::default(),
+        }),
+        funded: None,
+        scanned: Some(Checkpoint {
+            block_hash: "f7a4b2c0c1bd9a3e2b0f9e1f5a8d7c6b5a4938271605f4e3d2c1b0a998877665"
+                .to_owned(),
+            height: Some(9_876_600),
+            timestamp: NaiveDateTime::from_timestamp(1_590_000_000, 0),
+        }),
+    };
+    let funded = WatcherCheckpoint {
+        funded: Some(Funded::Correctly {
+            asset: Ether::from_wei(1_000u32),
+            transaction: transaction::Ethereum::default(),
+        }),
+        ..deployed.clone()
+    };
+
+    let (loaded_before_save, loaded_after_save, loaded_after_overwrite, loaded_other_ledger) =
+        tokio::runtime::Runtime::new()?.block_on(async {
+            let loaded_before_save = db.load_watcher_checkpoint(&swap_id, Ledger::Alpha).await?;
+
+            db.save_watcher_checkpoint(&swap_id, Ledger::Alpha, deployed.clone())
+                .await?;
+            let loaded_after_save = db.load_watcher_checkpoint(&swap_id, Ledger::Alpha).await?;
+
+            db.save_watcher_checkpoint(&swap_id, Ledger::Alpha, funded.clone())
+                .await?;
+            let loaded_after_overwrite =
+                db.load_watcher_checkpoint(&swap_id, Ledger::Alpha).await?;
+            let loaded_other_ledger = db.load_watcher_checkpoint(&swap_id, Ledger::Beta).await?;
+
+            anyhow::Result::<_>::Ok((
+                loaded_before_save,
+                loaded_after_save,
+                loaded_after_overwrite,
+                loaded_other_ledger,
+            ))
+        })?;
+
+    assert_eq!(loaded_before_save, WatcherCheckpoint::default());
+    assert_eq!(loaded_after_save, deployed);
+    assert_eq!(loaded_after_overwrite, funded);
+    assert_eq!(loaded_other_ledger, WatcherCheckpoint::default());
+
+    Ok(())
+}
+
+#[test]
+fn roundtrip_test_swap_start_heights() -> anyhow::Result<()> {
+    let db = Sqlite::new(&Path::new(":memory:"))?;
+    let swap_id = LocalSwapId::default();
+
+    let (loaded_before_save, loaded_after_save, loaded_other_ledger, loaded_unknown_height) =
+        tokio::runtime::Runtime::new()?.block_on(async {
+            let loaded_before_save = db.load_start_height(&swap_id, Ledger::Alpha).await?;
+
+            db.save_start_height(&swap_id, Ledger::Alpha, Some(1_234_567))
+                .await?;
+            db.save_start_height(&swap_id, Ledger::Beta, Some(654_321))
+                .await?;
+            let loaded_after_save = db.load_start_height(&swap_id, Ledger::Alpha).await?;
+            let loaded_other_ledger = db.load_start_height(&swap_id, Ledger::Beta).await?;
+
+            db.save_start_height(&swap_id, Ledger::Beta, None).await?;
+            let loaded_unknown_height = db.load_start_height(&swap_id, Ledger::Beta).await?;
+
+            anyhow::Result::<_>::Ok((
+                loaded_before_save,
+                loaded_after_save,
+                loaded_other_ledger,
+                loaded_unknown_height,
+            ))
+        })?;
+
+    assert_eq!(loaded_before_save, None);
+    assert_eq!(loaded_after_save, Some(1_234_567));
+    assert_eq!(loaded_other_ledger, Some(654_321));
+    assert_eq!(loaded_unknown_height, None);
+
+    Ok(())
+}
+
+#[test]
+fn roundtrip_test_swap_watcher_checkpoint() -> anyhow::Result<()> {
+    let db = Sqlite::new(&Path::new(":memory:"))?;
+    let swap_id = LocalSwapId::default();
+
+    let deployed = SwapWatcherCheckpoint::<
+        Deployed<htlc_location::Ethereum, transaction::Ethereum>,
+        Funded<Ether, transaction::Ethereum>,
+    > {
+        deployed: Some(Deployed {
+            location: htlc_location::Ethereum::from([1u8; 20]),
+            transaction: transaction::Ethereum::default(),
+        }),
+        funded: None,
+        scanned: Some(Checkpoint {
+            block_hash: "f7a4b2c0c1bd9a3e2b0f9e1f5a8d7c6b5a4938271605f4e3d2c1b0a998877665"
+                .to_owned(),
+            height: Some(9_876_600),
+            timestamp: NaiveDateTime::from_timestamp(1_590_000_000, 0),
+        }),
+    };
+    let funded = SwapWatcherCheckpoint {
+        funded: Some(Funded::Correctly {
+            asset: Ether::from_wei(1_000u32),
+            transaction: transaction::Ethereum::default(),
+        }),
+        ..deployed.clone()
+    };
+
+    let (
+        loaded_before_save,
+        loaded_after_save,
+        loaded_after_overwrite,
+        loaded_other_ledger,
+        loaded_start_height,
+    ) = tokio::runtime::Runtime::new()?.block_on(async {
+        let loaded_before_save = db
+            .load_swap_watcher_checkpoint(&swap_id, Ledger::Alpha)
+            .await?;
+
+        db.save_start_height(&swap_id, Ledger::Alpha, Some(9_876_543))
+            .await?;
+        db.save_swap_watcher_checkpoint(&swap_id, Ledger::Alpha, deployed.clone())
+            .await?;
+        let loaded_after_save = db
+            .load_swap_watcher_checkpoint(&swap_id, Ledger::Alpha)
+            .await?;
+
+        db.save_swap_watcher_checkpoint(&swap_id, Ledger::Alpha, funded.clone())
+            .await?;
+        db.save_start_height(&swap_id, Ledger::Alpha, Some(9_876_543))
+            .await?;
+        let loaded_after_overwrite = db
+            .load_swap_watcher_checkpoint(&swap_id, Ledger::Alpha)
+            .await?;
+        let loaded_other_ledger = db
+            .load_swap_watcher_checkpoint(&swap_id, Ledger::Beta)
+            .await?;
+        let loaded_start_height = db.load_start_height(&swap_id, Ledger::Alpha).await?;
+
+        anyhow::Result::<_>::Ok((
+            loaded_before_save,
+            loaded_after_save,
+            loaded_after_overwrite,
+            loaded_other_ledger,
+            loaded_start_height,
+        ))
+    })?;
+
+    assert_eq!(loaded_before_save, SwapWatcherCheckpoint::default());
+    assert_eq!(loaded_after_save, deployed);
+    assert_eq!(loaded_after_overwrite, funded);
+    assert_eq!(loaded_other_ledger, SwapWatcherCheckpoint::default());
+    assert_eq!(loaded_start_height, Some(9_876_543));
+
+    Ok(())
+}
+
+#[test]
+fn roundtrip_test_autopilot_actions() -> anyhow::Result<()> {
+    let db = Sqlite::new(&Path::new(":memory:"))?;
+    let swap_id = LocalSwapId::default().into();
+    let first_attempt = NaiveDateTime::from_timestamp(1_590_000_000, 0);
+    let too_early_retry = NaiveDateTime::from_timestamp(1_590_000_600, 0);
+    let second_attempt = NaiveDateTime::from_timestamp(1_590_001_800, 0);
+    let retry_after = chrono::Duration::minutes(30);
+
+    let (
+        loaded_before_claim,
+        first_claim,
+        too_early_claim,
+        second_claim,
+        loaded_after_claims,
+        claim_without_retry,
+        loaded_after_release,
+    ) = tokio::runtime::Runtime::new()?.block_on(async {
+        let loaded_before_claim = db.load_attempted_at(swap_id, ActionKind::Redeem).await?;
+
+        let first_claim = db
+            .claim_action(
+                swap_id,
+                ActionKind::Redeem,
+                first_attempt,
+                Some(retry_after),
+            )
+            .await?;
+        let too_early_claim = db
+            .claim_action(
+                swap_id,
+                ActionKind::Redeem,
+                too_early_retry,
+                Some(retry_after),
+            )
+            .await?;
+        let second_claim = db
+            .claim_action(
+                swap_id,
+                ActionKind::Redeem,
+                second_attempt,
+                Some(retry_after),
+            )
+            .await?;
+        let loaded_after_claims = db.load_attempted_at(swap_id, ActionKind::Redeem).await?;
+
+        let fund_claim = db
+            .claim_action(swap_id, ActionKind::Fund, first_attempt, None)
+            .await?;
+        let claim_without_retry = db
+            .claim_action(swap_id, ActionKind::Fund, second_attempt, None)
+            .await?;
+
+        if let Some(claim) = second_claim {
+            db.release_action(claim).await?;
+        }
+        if let Some(claim) = fund_claim {
+            db.release_action(claim).await?;
+        }
+        let loaded_after_release = (
+            db.load_attempted_at(swap_id, ActionKind::Redeem).await?,
+            db.load_attempted_at(swap_id, ActionKind::Fund).await?,
+        );
+
+        anyhow::Result::<_>::Ok((
+            loaded_before_claim,
+            first_claim,
+            too_early_claim,
+            second_claim,
+            loaded_after_claims,
+            claim_without_retry,
+            loaded_after_release,
+        ))
+    })?;
+
+    assert_eq!(loaded_before_claim, None);
+    assert_eq!(first_claim.map(|claim| claim.previous_attempt), Some(None));
+    assert_eq!(too_early_claim, None);
+    assert_eq!(
+        second_claim.map(|claim| claim.previous_attempt),
+        Some(Some(first_attempt))
+    );
+    assert_eq!(loaded_after_claims, Some(second_attempt));
+    assert_eq!(claim_without_retry, None);
+    assert_eq!(loaded_after_release, (Some(first_attempt), None));
+
+    Ok(())
+}
+
+#[test]
+fn roundtrip_test_webhook_deliveries() -> anyhow::Result<()> {
+    let db = Sqlite::new(&Path::new(":memory:"))?;
+    let url: reqwest::Url = "http://localhost:3000/webhook".parse()?;
+    let first_attempt = NaiveDateTime::from_timestamp(1_590_000_000, 0);
+    let retry = NaiveDateTime::from_timestamp(1_590_000_060, 0);
+
+    let (due_at_first_attempt, due_before_retry, due_at_retry, due_after_removal) =
+        tokio::runtime::Runtime::new()?.block_on(async {
+            db.enqueue_webhook_delivery(url.clone(), String::from("{}"), first_attempt)
+                .await?;
+            let due_at_first_attempt = db.due_webhook_deliveries(first_attempt).await?;
+            let id = due_at_first_attempt[0].id;
+
+            db.reschedule_webhook_delivery(id, 1, retry).await?;
+            let due_before_retry = db.due_webhook_deliveries(first_attempt).await?;
+            let due_at_retry = db.due_webhook_deliveries(retry).await?;
+
+            db.remove_webhook_delivery(id).await?;
+            let due_after_removal = db.due_webhook_deliveries(retry).await?;
+
+            anyhow::Result::<_>::Ok((
+                due_at_first_attempt,
+                due_before_retry,
+                due_at_retry,
+                due_after_removal,
+            ))
+        })?;
+
+    assert_eq!(due_at_first_attempt.len(), 1);
+    assert_eq!(*due_at_first_attempt[0].url, url);
+    assert_eq!(due_at_first_attempt[0].payload, "{}");
+    assert_eq!(due_at_first_attempt[0].attempts, 0);
+    assert!(due_before_retry.is_empty());
+    assert_eq!(due_at_retry.len(), 1);
+    assert_eq!(due_at_retry[0].attempts, 1);
+    assert!(due_after_removal.is_empty());
+
+    Ok(())
+}
+
+#[test]
+fn swap_event_deliveries_are_only_queued_once() -> anyhow::Result<()> {
+    let db = Sqlite::new(&Path::new(":memory:"))?;
+    let urls: Vec<reqwest::Url> = vec![
+        "http://localhost:3000/webhook".parse()?,
+        "http://localhost:4000/webhook".parse()?,
+    ];
+    let swap_id = LocalSwapId::default().into();
+    let first_event = NaiveDateTime::from_timestamp(1_590_000_000, 0);
+    let replayed_event = NaiveDateTime::from_timestamp(1_590_000_060, 0);
+    let enqueue = |event: &str, at| {
+        db.enqueue_swap_event_deliveries(
+            swap_id,
+            String::from(event),
+            urls.clone(),
+            String::from("{}"),
+            at,
+        )
+    };
+
+    let (queued_first, queued_other_event, queued_again, due) = tokio::runtime::Runtime::new()?
+        .block_on(async {
+            let queued_first = enqueue("swap_finished", first_event).await?;
+            let queued_other_event = enqueue("beta Funded 1", first_event).await?;
+            let queued_again = enqueue("swap_finished", replayed_event).await?;
+            let due = db.due_webhook_deliveries(replayed_event).await?;
+
+            anyhow::Result::<_>::Ok((queued_first, queued_other_event, queued_again, due))
+        })?;
+
+    assert!(queued_first);
+    assert!(queued_other_event);
+    assert!(!queued_again);
+    assert_eq!(due.len(), 2 * urls.len());
+
+    Ok(())
+}
+
+#[test]
+fn list_swaps_filters_and_paginates() -> anyhow::Result<()> {
+    let db = Sqlite::new(&Path::new(":memory:"))?;
+    let alice_swaps = vec![
+        Swap::new(SwapId::default(), Role::Alice, PeerId::random()),
+        Swap::new(SwapId::default(), Role::Alice, PeerId::random()),
+    ];
+    let bob_swap = Swap::new(SwapId::default(), Role::Bob, PeerId::random());
+    let split_protocol_swap = CreatedSwap {
+        swap_id: LocalSwapId::default(),
+        alpha: han::CreatedSwap {
+            amount: Ether::from_wei(1_000u32),
+            identity: identity::Ethereum::random(),
+            chain_id: 1337,
+            absolute_expiry: 100,
+        },
+        beta: halight::CreatedSwap {
+            amount: BitcoinAsset::from_sat(1_000),
+            identity: identity::Lightning::random(),
+            network: String::from("regtest"),
+            cltv_expiry: 50,
+        },
+        peer: PeerId::random(),
+        role: Role::Alice,
+        created_at: Utc::now().naive_utc(),
+    };
+    let alice_filter = SwapFilter {
+        role: Some(Role::Alice),
+        ..SwapFilter::default()
+    };
+
+    let (first_page, second_page, third_page, newest_first, swapped, han_halight) =
+        tokio::runtime::Runtime::new()?.block_on(async {
+            db.save(alice_swaps[0].clone()).await?;
+            // The swaps are listed in the order they were created in.
+            db.save(CreatedSwap {
+                created_at: Utc::now().naive_utc(),
+                ..split_protocol_swap.clone()
+            })
+            .await?;
+            db.save(bob_swap.clone()).await?;
+            db.save(alice_swaps[1].clone()).await?;
+            db.update_swap_status(&bob_swap.swap_id, SwapStatus::Swapped)
+                .await?;
+            db.update_local_swap_status(split_protocol_swap.swap_id, SwapStatus::Swapped)
+                .await?;
+
+            let page = Page {
+                limit: Some(1),
+                ..Page::default()
+            };
+            let first_page = db.list_swaps(&alice_filter, page).await?;
+            let second_page = db
+                .list_swaps(&alice_filter, Page {
+                    cursor: first_page.next_cursor,
+                    ..page
+                })
+                .await?;
+            let third_page = db
+                .list_swaps(&alice_filter, Page {
+                    cursor: second_page.next_cursor,
+                    ..page
+                })
+                .await?;
+            let newest_first = db
+                .list_swaps(&SwapFilter::default(), Page {
+                    order: Order::Desc,
+                    ..Page::default()
+                })
+                .await?;
+            let swapped = db
+                .list_swaps(
+                    &SwapFilter {
+                        status: Some(SwapStatus::Swapped),
+                        ..SwapFilter::default()
+                    },
+                    Page::default(),
+                )
+                .await?;
+            let han_halight = db
+                .list_swaps(
+                    &SwapFilter {
+                        kinds: Some(vec![SwapKind::HanHalight, SwapKind::HalightHan]),
+                        ..SwapFilter::default()
+                    },
+                    Page::default(),
+                )
+                .await?;
+
+            anyhow::Result::<_>::Ok((
+                first_page,
+                second_page,
+                third_page,
+                newest_first,
+                swapped,
+                han_halight,
+            ))
+        })?;
+
+    let split_protocol_swap =
+        ListedSwap::SplitProtocol(split_protocol_swap.swap_id, SwapKind::HanHalight);
+    assert_eq!(first_page.swaps, vec![ListedSwap::Rfc003(
+        alice_swaps[0].clone()
+    )]);
+    assert!(first_page.next_cursor.is_some());
+    assert_eq!(second_page.swaps, vec![split_protocol_swap.clone()]);
+    assert_eq!(third_page.swaps, vec![ListedSwap::Rfc003(
+        alice_swaps[1].clone()
+    )]);
+    assert_eq!(third_page.next_cursor, None);
+    assert_eq!(newest_first.swaps, vec![
+        ListedSwap::Rfc003(alice_swaps[1].clone()),
+        ListedSwap::Rfc003(bob_swap.clone()),
+        split_protocol_swap.clone(),
+        ListedSwap::Rfc003(alice_swaps[0].clone()),
+    ]);
+    assert_eq!(newest_first.next_cursor, None);
+    assert_eq!(swapped.swaps, vec![
+        split_protocol_swap.clone(),
+        ListedSwap::Rfc003(bob_swap)
+    ]);
+    assert_eq!(han_halight.swaps, vec![split_protocol_swap]);
+
+    Ok(())
+}
+
+#[test]
+fn list_and_count_swaps_tell_hbit_and_halight_swaps_apart() -> anyhow::Result<()> {
+    let db = Sqlite::new(&Path::new(":memory:"))?;
+    let han = han::CreatedSwap {
+        amount: Ether::from_wei(1_000u32),
+        identity: identity::Ethereum::random(),
+        chain_id: 1337,
+        absolute_expiry: 100,
+    };
+    let halight_swap = CreatedSwap {
+        swap_id: LocalSwapId::default(),
+        alpha: han.clone(),
+        beta: halight::CreatedSwap {
+            amount: BitcoinAsset::from_sat(1_000),
+            identity: identity::Lightning::random(),
+            network: String::from("regtest"),
+            cltv_expiry: 50,
+        },
+        peer: PeerId::random(),
+        role: Role::Alice,
+        created_at: Utc::now().naive_utc(),
+    };
+    let hbit_swap = CreatedSwap {
+        swap_id: LocalSwapId::default(),
+        alpha: han,
+        beta: hbit::CreatedSwap {
+            amount: BitcoinAsset::from_sat(1_000),
+            network: ::bitcoin::Network::Regtest,
+            absolute_expiry: 50,
+        },
+        peer: PeerId::random(),
+        role: Role::Alice,
+        created_at: Utc::now().naive_utc(),
+    };
+    let only = |kind| SwapFilter {
+        kinds: Some(vec![kind]),
+        ..SwapFilter::default()
+    };
+
+    let (han_halight, han_hbit, counts) = tokio::runtime::Runtime::new()?.block_on(async {
+        db.save(halight_swap.clone()).await?;
+        db.save(hbit_swap.clone()).await?;
+
+        let han_halight = db
+            .list_swaps(&only(SwapKind::HanHalight), Page::default())
+            .await?;
+        let han_hbit = db
+            .list_swaps(&only(SwapKind::HanHbit), Page::default())
+            .await?;
+        let counts = db.count_swaps().await?;
+
+        anyhow::Result::<_>::Ok((han_halight, han_hbit, counts))
+    })?;
+
+    assert_eq!(han_halight.swaps, vec![ListedSwap::SplitProtocol(
+        halight_swap.swap_id,
+        SwapKind::HanHalight
+    )]);
+    assert_eq!(han_hbit.swaps, vec![ListedSwap::SplitProtocol(
+        hbit_swap.swap_id,
+        SwapKind::HanHbit
+    )]);
+    assert_eq!(
+        counts.get(&(SwapKind::HanHalight, SwapStatus::InProgress)),
+        Some(&1)
+    );
+    assert_eq!(
+        counts.get(&(SwapKind::HanHbit, SwapStatus::InProgress)),
+        Some(&1)
+    );
+
+    Ok(())
+}
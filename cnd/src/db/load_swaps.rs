@@ -4,31 +4,34 @@ use crate::{
         schema,
         wrapper_types::{
             custom_sql_types::{Text, U32},
-            BitcoinNetwork, Erc20Amount, Ether, EthereumAddress, Satoshis,
+            BitcoinNetwork, Erc20Amount, Ether, EthereumAddress, Ledger, Satoshis,
         },
-        Sqlite,
+        CreatedSwap, FinalizedHbitSwapData, FinalizedSwapData, Sqlite,
     },
     identity,
     swap_protocols::{
+        halight, han, hbit, herc20,
         ledger::{bitcoin, Ethereum},
         rfc003::{
             messages::{Accept, Request},
             SecretHash, SwapId,
         },
-        HashFunction,
+        HashFunction, LocalSwapId, Role, SharedSwapId,
     },
 };
+use anyhow::Context;
 use async_trait::async_trait;
 use chrono::NaiveDateTime;
-use diesel::{self, prelude::*, RunQueryDsl};
+use diesel::{self, prelude::*, RunQueryDsl, SqliteConnection};
 use impl_template::impl_template;
+use libp2p::PeerId;
 use schema::{
-    rfc003_bitcoin_ethereum_accept_messages,
+    finalized_swaps, halights, hans, hbits, herc20s, rfc003_bitcoin_ethereum_accept_messages,
     rfc003_bitcoin_ethereum_bitcoin_erc20_request_messages,
     rfc003_bitcoin_ethereum_bitcoin_ether_request_messages,
     rfc003_ethereum_bitcoin_accept_messages,
     rfc003_ethereum_bitcoin_erc20_bitcoin_request_messages,
-    rfc003_ethereum_bitcoin_ether_bitcoin_request_messages,
+    rfc003_ethereum_bitcoin_ether_bitcoin_request_messages, swaps,
 };
 
 pub type AcceptedSwap<AL, BL, AA, BA, AI, BI> = (
@@ -565,3 +568,942 @@ impl
         Ok(record.into())
     }
 }
+
+/// A created swap together with the data learned while finalizing it.
+///
+/// The finalized data is `None` if cnd was stopped before the communication
+/// protocols with the counterparty finished.
+pub type LoadedSwap<A, B, F = FinalizedSwapData> = (CreatedSwap<A, B>, Option<F>);
+
+#[async_trait]
+pub trait LoadCreatedSwaps<A, B> {
+    /// The data learned while finalizing a swap of this kind.
+    type Finalized;
+
+    async fn load_created_swaps(&self) -> anyhow::Result<Vec<LoadedSwap<A, B, Self::Finalized>>>;
+}
+
+/// The columns `QueryableCreatedSwap` is loaded from.
+const CREATED_SWAP_COLUMNS: (
+    swaps::id,
+    swaps::local_swap_id,
+    swaps::role,
+    swaps::counterparty_peer_id,
+    swaps::created_at,
+) = (
+    swaps::id,
+    swaps::local_swap_id,
+    swaps::role,
+    swaps::counterparty_peer_id,
+    swaps::created_at,
+);
+
+#[derive(Queryable, Debug, Clone, PartialEq)]
+struct QueryableCreatedSwap {
+    id: i32,
+    local_swap_id: Text<LocalSwapId>,
+    role: Text<Role>,
+    counterparty_peer_id: Text<PeerId>,
+    created_at: NaiveDateTime,
+}
+
+#[derive(Queryable, Debug, Clone, PartialEq)]
+struct QueryableHan {
+    amount: Text<Ether>,
+    chain_id: U32,
+    expiry: U32,
+    redeem_identity: Option<Text<EthereumAddress>>,
+    refund_identity: Option<Text<EthereumAddress>>,
+}
+
+#[derive(Queryable, Debug, Clone, PartialEq)]
+struct QueryableHalight {
+    amount: Text<Satoshis>,
+    network: String,
+    cltv_expiry: U32,
+    redeem_identity: Option<Text<::bitcoin::PublicKey>>,
+    refund_identity: Option<Text<::bitcoin::PublicKey>>,
+}
+
+#[derive(Queryable, Debug, Clone, PartialEq)]
+struct QueryableHbit {
+    amount: Text<Satoshis>,
+    network: Text<BitcoinNetwork>,
+    expiry: U32,
+    redeem_identity: Option<Text<::bitcoin::PublicKey>>,
+    refund_identity: Option<Text<::bitcoin::PublicKey>>,
+}
+
+#[derive(Queryable, Debug, Clone, PartialEq)]
+struct QueryableFinalizedSwap {
+    shared_swap_id: Text<SharedSwapId>,
+    secret_hash: Text<SecretHash>,
+    at: NaiveDateTime,
+}
+
+#[derive(Queryable, Debug, Clone, PartialEq)]
+struct QueryableHerc20 {
+    amount: Text<Erc20Amount>,
+    chain_id: U32,
+    expiry: U32,
+    token_contract: Text<EthereumAddress>,
+    redeem_identity: Option<Text<EthereumAddress>>,
+    refund_identity: Option<Text<EthereumAddress>>,
+}
+
+type HanHalightRecord = (
+    QueryableCreatedSwap,
+    QueryableHan,
+    QueryableHalight,
+    Option<QueryableFinalizedSwap>,
+);
+
+type Herc20HalightRecord = (
+    QueryableCreatedSwap,
+    QueryableHerc20,
+    QueryableHalight,
+    Option<QueryableFinalizedSwap>,
+);
+
+type HalightHanRecord = (
+    QueryableCreatedSwap,
+    QueryableHalight,
+    QueryableHan,
+    Option<QueryableFinalizedSwap>,
+);
+
+type HalightHerc20Record = (
+    QueryableCreatedSwap,
+    QueryableHalight,
+    QueryableHerc20,
+    Option<QueryableFinalizedSwap>,
+);
+
+type HanHbitRecord = (
+    QueryableCreatedSwap,
+    QueryableHan,
+    QueryableHbit,
+    Option<QueryableFinalizedSwap>,
+);
+
+type Herc20HbitRecord = (
+    QueryableCreatedSwap,
+    QueryableHerc20,
+    QueryableHbit,
+    Option<QueryableFinalizedSwap>,
+);
+
+type HbitHanRecord = (
+    QueryableCreatedSwap,
+    QueryableHbit,
+    QueryableHan,
+    Option<QueryableFinalizedSwap>,
+);
+
+type HbitHerc20Record = (
+    QueryableCreatedSwap,
+    QueryableHbit,
+    QueryableHerc20,
+    Option<QueryableFinalizedSwap>,
+);
+
+#[async_trait]
+impl LoadCreatedSwaps<han::CreatedSwap, halight::CreatedSwap> for Sqlite {
+    type Finalized = FinalizedSwapData;
+
+    async fn load_created_swaps(
+        &self,
+    ) -> anyhow::Result<Vec<LoadedSwap<han::CreatedSwap, halight::CreatedSwap>>> {
+        let records: Vec<HanHalightRecord> = self
+            .do_in_transaction(|connection| {
+                let mut records = Vec::new();
+
+                for swap in swaps::table
+                    .select(CREATED_SWAP_COLUMNS)
+                    .load::<QueryableCreatedSwap>(connection)?
+                {
+                    let han = load_han(connection, swap.id, Ledger::Alpha)?;
+                    let halight = load_halight(connection, swap.id, Ledger::Beta)?;
+
+                    // This swap uses a different combination of protocols.
+                    let (han, halight) = match (han, halight) {
+                        (Some(han), Some(halight)) => (han, halight),
+                        _ => continue,
+                    };
+
+                    let finalized = load_finalized_swap(connection, swap.id)?;
+
+                    records.push((swap, han, halight, finalized));
+                }
+
+                Ok::<_, diesel::result::Error>(records)
+            })
+            .await?;
+
+        records.into_iter().map(into_han_halight_swap).collect()
+    }
+}
+
+#[async_trait]
+impl LoadCreatedSwaps<herc20::CreatedSwap, halight::CreatedSwap> for Sqlite {
+    type Finalized = FinalizedSwapData;
+
+    async fn load_created_swaps(
+        &self,
+    ) -> anyhow::Result<Vec<LoadedSwap<herc20::CreatedSwap, halight::CreatedSwap>>> {
+        let records: Vec<Herc20HalightRecord> = self
+            .do_in_transaction(|connection| {
+                let mut records = Vec::new();
+
+                for swap in swaps::table
+                    .select(CREATED_SWAP_COLUMNS)
+                    .load::<QueryableCreatedSwap>(connection)?
+                {
+                    let herc20 = load_herc20(connection, swap.id, Ledger::Alpha)?;
+                    let halight = load_halight(connection, swap.id, Ledger::Beta)?;
+
+                    // This swap uses a different combination of protocols.
+                    let (herc20, halight) = match (herc20, halight) {
+                        (Some(herc20), Some(halight)) => (herc20, halight),
+                        _ => continue,
+                    };
+
+                    let finalized = load_finalized_swap(connection, swap.id)?;
+
+                    records.push((swap, herc20, halight, finalized));
+                }
+
+                Ok::<_, diesel::result::Error>(records)
+            })
+            .await?;
+
+        records.into_iter().map(into_herc20_halight_swap).collect()
+    }
+}
+
+#[async_trait]
+impl LoadCreatedSwaps<halight::CreatedSwap, han::CreatedSwap> for Sqlite {
+    type Finalized = FinalizedSwapData;
+
+    async fn load_created_swaps(
+        &self,
+    ) -> anyhow::Result<Vec<LoadedSwap<halight::CreatedSwap, han::CreatedSwap>>> {
+        let records: Vec<HalightHanRecord> = self
+            .do_in_transaction(|connection| {
+                let mut records = Vec::new();
+
+                for swap in swaps::table
+                    .select(CREATED_SWAP_COLUMNS)
+                    .load::<QueryableCreatedSwap>(connection)?
+                {
+                    let halight = load_halight(connection, swap.id, Ledger::Alpha)?;
+                    let han = load_han(connection, swap.id, Ledger::Beta)?;
+
+                    // This swap uses a different combination of protocols.
+                    let (halight, han) = match (halight, han) {
+                        (Some(halight), Some(han)) => (halight, han),
+                        _ => continue,
+                    };
+
+                    let finalized = load_finalized_swap(connection, swap.id)?;
+
+                    records.push((swap, halight, han, finalized));
+                }
+
+                Ok::<_, diesel::result::Error>(records)
+            })
+            .await?;
+
+        records.into_iter().map(into_halight_han_swap).collect()
+    }
+}
+
+#[async_trait]
+impl LoadCreatedSwaps<halight::CreatedSwap, herc20::CreatedSwap> for Sqlite {
+    type Finalized = FinalizedSwapData;
+
+    async fn load_created_swaps(
+        &self,
+    ) -> anyhow::Result<Vec<LoadedSwap<halight::CreatedSwap, herc20::CreatedSwap>>> {
+        let records: Vec<HalightHerc20Record> = self
+            .do_in_transaction(|connection| {
+                let mut records = Vec::new();
+
+                for swap in swaps::table
+                    .select(CREATED_SWAP_COLUMNS)
+                    .load::<QueryableCreatedSwap>(connection)?
+                {
+                    let halight = load_halight(connection, swap.id, Ledger::Alpha)?;
+                    let herc20 = load_herc20(connection, swap.id, Ledger::Beta)?;
+
+                    // This swap uses a different combination of protocols.
+                    let (halight, herc20) = match (halight, herc20) {
+                        (Some(halight), Some(herc20)) => (halight, herc20),
+                        _ => continue,
+                    };
+
+                    let finalized = load_finalized_swap(connection, swap.id)?;
+
+                    records.push((swap, halight, herc20, finalized));
+                }
+
+                Ok::<_, diesel::result::Error>(records)
+            })
+            .await?;
+
+        records.into_iter().map(into_halight_herc20_swap).collect()
+    }
+}
+
+#[async_trait]
+impl LoadCreatedSwaps<han::CreatedSwap, hbit::CreatedSwap> for Sqlite {
+    type Finalized = FinalizedHbitSwapData;
+
+    async fn load_created_swaps(
+        &self,
+    ) -> anyhow::Result<Vec<LoadedSwap<han::CreatedSwap, hbit::CreatedSwap, FinalizedHbitSwapData>>>
+    {
+        let records: Vec<HanHbitRecord> = self
+            .do_in_transaction(|connection| {
+                let mut records = Vec::new();
+
+                for swap in swaps::table
+                    .select(CREATED_SWAP_COLUMNS)
+                    .load::<QueryableCreatedSwap>(connection)?
+                {
+                    let han = load_han(connection, swap.id, Ledger::Alpha)?;
+                    let hbit = load_hbit(connection, swap.id, Ledger::Beta)?;
+
+                    // This swap uses a different combination of protocols.
+                    let (han, hbit) = match (han, hbit) {
+                        (Some(han), Some(hbit)) => (han, hbit),
+                        _ => continue,
+                    };
+
+                    let finalized = load_finalized_swap(connection, swap.id)?;
+
+                    records.push((swap, han, hbit, finalized));
+                }
+
+                Ok::<_, diesel::result::Error>(records)
+            })
+            .await?;
+
+        records.into_iter().map(into_han_hbit_swap).collect()
+    }
+}
+
+#[async_trait]
+impl LoadCreatedSwaps<herc20::CreatedSwap, hbit::CreatedSwap> for Sqlite {
+    type Finalized = FinalizedHbitSwapData;
+
+    async fn load_created_swaps(
+        &self,
+    ) -> anyhow::Result<
+        Vec<LoadedSwap<herc20::CreatedSwap, hbit::CreatedSwap, FinalizedHbitSwapData>>,
+    > {
+        let records: Vec<Herc20HbitRecord> = self
+            .do_in_transaction(|connection| {
+                let mut records = Vec::new();
+
+                for swap in swaps::table
+                    .select(CREATED_SWAP_COLUMNS)
+                    .load::<QueryableCreatedSwap>(connection)?
+                {
+                    let herc20 = load_herc20(connection, swap.id, Ledger::Alpha)?;
+                    let hbit = load_hbit(connection, swap.id, Ledger::Beta)?;
+
+                    // This swap uses a different combination of protocols.
+                    let (herc20, hbit) = match (herc20, hbit) {
+                        (Some(herc20), Some(hbit)) => (herc20, hbit),
+                        _ => continue,
+                    };
+
+                    let finalized = load_finalized_swap(connection, swap.id)?;
+
+                    records.push((swap, herc20, hbit, finalized));
+                }
+
+                Ok::<_, diesel::result::Error>(records)
+            })
+            .await?;
+
+        records.into_iter().map(into_herc20_hbit_swap).collect()
+    }
+}
+
+#[async_trait]
+impl LoadCreatedSwaps<hbit::CreatedSwap, han::CreatedSwap> for Sqlite {
+    type Finalized = FinalizedHbitSwapData;
+
+    async fn load_created_swaps(
+        &self,
+    ) -> anyhow::Result<Vec<LoadedSwap<hbit::CreatedSwap, han::CreatedSwap, FinalizedHbitSwapData>>>
+    {
+        let records: Vec<HbitHanRecord> = self
+            .do_in_transaction(|connection| {
+                let mut records = Vec::new();
+
+                for swap in swaps::table
+                    .select(CREATED_SWAP_COLUMNS)
+                    .load::<QueryableCreatedSwap>(connection)?
+                {
+                    let hbit = load_hbit(connection, swap.id, Ledger::Alpha)?;
+                    let han = load_han(connection, swap.id, Ledger::Beta)?;
+
+                    // This swap uses a different combination of protocols.
+                    let (hbit, han) = match (hbit, han) {
+                        (Some(hbit), Some(han)) => (hbit, han),
+                        _ => continue,
+                    };
+
+                    let finalized = load_finalized_swap(connection, swap.id)?;
+
+                    records.push((swap, hbit, han, finalized));
+                }
+
+                Ok::<_, diesel::result::Error>(records)
+            })
+            .await?;
+
+        records.into_iter().map(into_hbit_han_swap).collect()
+    }
+}
+
+#[async_trait]
+impl LoadCreatedSwaps<hbit::CreatedSwap, herc20::CreatedSwap> for Sqlite {
+    type Finalized = FinalizedHbitSwapData;
+
+    async fn load_created_swaps(
+        &self,
+    ) -> anyhow::Result<
+        Vec<LoadedSwap<hbit::CreatedSwap, herc20::CreatedSwap, FinalizedHbitSwapData>>,
+    > {
+        let records: Vec<HbitHerc20Record> = self
+            .do_in_transaction(|connection| {
+                let mut records = Vec::new();
+
+                for swap in swaps::table
+                    .select(CREATED_SWAP_COLUMNS)
+                    .load::<QueryableCreatedSwap>(connection)?
+                {
+                    let hbit = load_hbit(connection, swap.id, Ledger::Alpha)?;
+                    let herc20 = load_herc20(connection, swap.id, Ledger::Beta)?;
+
+                    // This swap uses a different combination of protocols.
+                    let (hbit, herc20) = match (hbit, herc20) {
+                        (Some(hbit), Some(herc20)) => (hbit, herc20),
+                        _ => continue,
+                    };
+
+                    let finalized = load_finalized_swap(connection, swap.id)?;
+
+                    records.push((swap, hbit, herc20, finalized));
+                }
+
+                Ok::<_, diesel::result::Error>(records)
+            })
+            .await?;
+
+        records.into_iter().map(into_hbit_herc20_swap).collect()
+    }
+}
+
+fn load_han(
+    connection: &SqliteConnection,
+    swap_id: i32,
+    ledger: Ledger,
+) -> diesel::QueryResult<Option<QueryableHan>> {
+    hans::table
+        .filter(hans::swap_id.eq(swap_id))
+        .filter(hans::ledger.eq(Text(ledger)))
+        .select((
+            hans::amount,
+            hans::chain_id,
+            hans::expiry,
+            hans::redeem_identity,
+            hans::refund_identity,
+        ))
+        .first::<QueryableHan>(connection)
+        .optional()
+}
+
+fn load_herc20(
+    connection: &SqliteConnection,
+    swap_id: i32,
+    ledger: Ledger,
+) -> diesel::QueryResult<Option<QueryableHerc20>> {
+    herc20s::table
+        .filter(herc20s::swap_id.eq(swap_id))
+        .filter(herc20s::ledger.eq(Text(ledger)))
+        .select((
+            herc20s::amount,
+            herc20s::chain_id,
+            herc20s::expiry,
+            herc20s::token_contract,
+            herc20s::redeem_identity,
+            herc20s::refund_identity,
+        ))
+        .first::<QueryableHerc20>(connection)
+        .optional()
+}
+
+fn load_halight(
+    connection: &SqliteConnection,
+    swap_id: i32,
+    ledger: Ledger,
+) -> diesel::QueryResult<Option<QueryableHalight>> {
+    halights::table
+        .filter(halights::swap_id.eq(swap_id))
+        .filter(halights::ledger.eq(Text(ledger)))
+        .select((
+            halights::amount,
+            halights::network,
+            halights::cltv_expiry,
+            halights::redeem_identity,
+            halights::refund_identity,
+        ))
+        .first::<QueryableHalight>(connection)
+        .optional()
+}
+
+fn load_hbit(
+    connection: &SqliteConnection,
+    swap_id: i32,
+    ledger: Ledger,
+) -> diesel::QueryResult<Option<QueryableHbit>> {
+    hbits::table
+        .filter(hbits::swap_id.eq(swap_id))
+        .filter(hbits::ledger.eq(Text(ledger)))
+        .select((
+            hbits::amount,
+            hbits::network,
+            hbits::expiry,
+            hbits::redeem_identity,
+            hbits::refund_identity,
+        ))
+        .first::<QueryableHbit>(connection)
+        .optional()
+}
+
+fn load_finalized_swap(
+    connection: &SqliteConnection,
+    swap_id: i32,
+) -> diesel::QueryResult<Option<QueryableFinalizedSwap>> {
+    finalized_swaps::table
+        .filter(finalized_swaps::swap_id.eq(swap_id))
+        .select((
+            finalized_swaps::shared_swap_id,
+            finalized_swaps::secret_hash,
+            finalized_swaps::at,
+        ))
+        .first::<QueryableFinalizedSwap>(connection)
+        .optional()
+}
+
+fn into_han_halight_swap(
+    (swap, han, halight, finalized): HanHalightRecord,
+) -> anyhow::Result<LoadedSwap<han::CreatedSwap, halight::CreatedSwap>> {
+    let swap_id = *swap.local_swap_id;
+    let role = *swap.role;
+
+    let identities = Identities::new(
+        role,
+        han.redeem_identity,
+        han.refund_identity,
+        halight.redeem_identity,
+        halight.refund_identity,
+    );
+
+    let created = CreatedSwap {
+        swap_id,
+        alpha: into_han(swap_id, han, identities.alpha)?,
+        beta: into_halight(swap_id, halight, identities.beta)?,
+        peer: swap.counterparty_peer_id.0,
+        role,
+        created_at: swap.created_at,
+    };
+
+    let finalized = into_finalized_swap_data(
+        swap_id,
+        finalized,
+        identities.counterparty_alpha,
+        identities.counterparty_beta,
+    );
+
+    Ok((created, finalized))
+}
+
+fn into_herc20_halight_swap(
+    (swap, herc20, halight, finalized): Herc20HalightRecord,
+) -> anyhow::Result<LoadedSwap<herc20::CreatedSwap, halight::CreatedSwap>> {
+    let swap_id = *swap.local_swap_id;
+    let role = *swap.role;
+
+    let identities = Identities::new(
+        role,
+        herc20.redeem_identity,
+        herc20.refund_identity,
+        halight.redeem_identity,
+        halight.refund_identity,
+    );
+
+    let created = CreatedSwap {
+        swap_id,
+        alpha: into_herc20(swap_id, herc20, identities.alpha)?,
+        beta: into_halight(swap_id, halight, identities.beta)?,
+        peer: swap.counterparty_peer_id.0,
+        role,
+        created_at: swap.created_at,
+    };
+
+    let finalized = into_finalized_swap_data(
+        swap_id,
+        finalized,
+        identities.counterparty_alpha,
+        identities.counterparty_beta,
+    );
+
+    Ok((created, finalized))
+}
+
+fn into_halight_han_swap(
+    (swap, halight, han, finalized): HalightHanRecord,
+) -> anyhow::Result<LoadedSwap<halight::CreatedSwap, han::CreatedSwap>> {
+    let swap_id = *swap.local_swap_id;
+    let role = *swap.role;
+
+    let identities = Identities::new(
+        role,
+        halight.redeem_identity,
+        halight.refund_identity,
+        han.redeem_identity,
+        han.refund_identity,
+    );
+
+    let created = CreatedSwap {
+        swap_id,
+        alpha: into_halight(swap_id, halight, identities.alpha)?,
+        beta: into_han(swap_id, han, identities.beta)?,
+        peer: swap.counterparty_peer_id.0,
+        role,
+        created_at: swap.created_at,
+    };
+
+    let finalized = into_finalized_swap_data(
+        swap_id,
+        finalized,
+        identities.counterparty_beta,
+        identities.counterparty_alpha,
+    );
+
+    Ok((created, finalized))
+}
+
+fn into_halight_herc20_swap(
+    (swap, halight, herc20, finalized): HalightHerc20Record,
+) -> anyhow::Result<LoadedSwap<halight::CreatedSwap, herc20::CreatedSwap>> {
+    let swap_id = *swap.local_swap_id;
+    let role = *swap.role;
+
+    let identities = Identities::new(
+        role,
+        halight.redeem_identity,
+        halight.refund_identity,
+        herc20.redeem_identity,
+        herc20.refund_identity,
+    );
+
+    let created = CreatedSwap {
+        swap_id,
+        alpha: into_halight(swap_id, halight, identities.alpha)?,
+        beta: into_herc20(swap_id, herc20, identities.beta)?,
+        peer: swap.counterparty_peer_id.0,
+        role,
+        created_at: swap.created_at,
+    };
+
+    let finalized = into_finalized_swap_data(
+        swap_id,
+        finalized,
+        identities.counterparty_beta,
+        identities.counterparty_alpha,
+    );
+
+    Ok((created, finalized))
+}
+
+fn into_han_hbit_swap(
+    (swap, han, hbit, finalized): HanHbitRecord,
+) -> anyhow::Result<LoadedSwap<han::CreatedSwap, hbit::CreatedSwap, FinalizedHbitSwapData>> {
+    let swap_id = *swap.local_swap_id;
+    let role = *swap.role;
+
+    let identities = Identities::new(
+        role,
+        han.redeem_identity,
+        han.refund_identity,
+        hbit.redeem_identity,
+        hbit.refund_identity,
+    );
+
+    let created = CreatedSwap {
+        swap_id,
+        alpha: into_han(swap_id, han, identities.alpha)?,
+        beta: into_hbit(hbit),
+        peer: swap.counterparty_peer_id.0,
+        role,
+        created_at: swap.created_at,
+    };
+
+    let finalized = into_finalized_hbit_swap_data(
+        swap_id,
+        finalized,
+        identities.counterparty_alpha,
+        identities.counterparty_beta,
+    );
+
+    Ok((created, finalized))
+}
+
+fn into_herc20_hbit_swap(
+    (swap, herc20, hbit, finalized): Herc20HbitRecord,
+) -> anyhow::Result<LoadedSwap<herc20::CreatedSwap, hbit::CreatedSwap, FinalizedHbitSwapData>> {
+    let swap_id = *swap.local_swap_id;
+    let role = *swap.role;
+
+    let identities = Identities::new(
+        role,
+        herc20.redeem_identity,
+        herc20.refund_identity,
+        hbit.redeem_identity,
+        hbit.refund_identity,
+    );
+
+    let created = CreatedSwap {
+        swap_id,
+        alpha: into_herc20(swap_id, herc20, identities.alpha)?,
+        beta: into_hbit(hbit),
+        peer: swap.counterparty_peer_id.0,
+        role,
+        created_at: swap.created_at,
+    };
+
+    let finalized = into_finalized_hbit_swap_data(
+        swap_id,
+        finalized,
+        identities.counterparty_alpha,
+        identities.counterparty_beta,
+    );
+
+    Ok((created, finalized))
+}
+
+fn into_hbit_han_swap(
+    (swap, hbit, han, finalized): HbitHanRecord,
+) -> anyhow::Result<LoadedSwap<hbit::CreatedSwap, han::CreatedSwap, FinalizedHbitSwapData>> {
+    let swap_id = *swap.local_swap_id;
+    let role = *swap.role;
+
+    let identities = Identities::new(
+        role,
+        hbit.redeem_identity,
+        hbit.refund_identity,
+        han.redeem_identity,
+        han.refund_identity,
+    );
+
+    let created = CreatedSwap {
+        swap_id,
+        alpha: into_hbit(hbit),
+        beta: into_han(swap_id, han, identities.beta)?,
+        peer: swap.counterparty_peer_id.0,
+        role,
+        created_at: swap.created_at,
+    };
+
+    let finalized = into_finalized_hbit_swap_data(
+        swap_id,
+        finalized,
+        identities.counterparty_beta,
+        identities.counterparty_alpha,
+    );
+
+    Ok((created, finalized))
+}
+
+fn into_hbit_herc20_swap(
+    (swap, hbit, herc20, finalized): HbitHerc20Record,
+) -> anyhow::Result<LoadedSwap<hbit::CreatedSwap, herc20::CreatedSwap, FinalizedHbitSwapData>> {
+    let swap_id = *swap.local_swap_id;
+    let role = *swap.role;
+
+    let identities = Identities::new(
+        role,
+        hbit.redeem_identity,
+        hbit.refund_identity,
+        herc20.redeem_identity,
+        herc20.refund_identity,
+    );
+
+    let created = CreatedSwap {
+        swap_id,
+        alpha: into_hbit(hbit),
+        beta: into_herc20(swap_id, herc20, identities.beta)?,
+        peer: swap.counterparty_peer_id.0,
+        role,
+        created_at: swap.created_at,
+    };
+
+    let finalized = into_finalized_hbit_swap_data(
+        swap_id,
+        finalized,
+        identities.counterparty_beta,
+        identities.counterparty_alpha,
+    );
+
+    Ok((created, finalized))
+}
+
+fn into_han(
+    swap_id: LocalSwapId,
+    han: QueryableHan,
+    identity: Option<Text<EthereumAddress>>,
+) -> anyhow::Result<han::CreatedSwap> {
+    Ok(han::CreatedSwap {
+        amount: han.amount.0.into(),
+        identity: identity
+            .map(|identity| identity.0.into())
+            .with_context(|| format!("ethereum identity of swap {} not found", swap_id))?,
+        chain_id: han.chain_id.into(),
+        absolute_expiry: han.expiry.into(),
+    })
+}
+
+fn into_herc20(
+    swap_id: LocalSwapId,
+    herc20: QueryableHerc20,
+    identity: Option<Text<EthereumAddress>>,
+) -> anyhow::Result<herc20::CreatedSwap> {
+    Ok(herc20::CreatedSwap {
+        amount: herc20.amount.0.into(),
+        identity: identity
+            .map(|identity| identity.0.into())
+            .with_context(|| format!("ethereum identity of swap {} not found", swap_id))?,
+        chain_id: herc20.chain_id.into(),
+        contract_address: herc20.token_contract.0.into(),
+        absolute_expiry: herc20.expiry.into(),
+    })
+}
+
+fn into_halight(
+    swap_id: LocalSwapId,
+    halight: QueryableHalight,
+    identity: Option<Text<::bitcoin::PublicKey>>,
+) -> anyhow::Result<halight::CreatedSwap> {
+    Ok(halight::CreatedSwap {
+        amount: halight.amount.0.into(),
+        identity: identity
+            .map(|identity| identity.0.into())
+            .with_context(|| format!("lightning identity of swap {} not found", swap_id))?,
+        network: halight.network,
+        cltv_expiry: halight.cltv_expiry.into(),
+    })
+}
+
+/// Our own hbit identity is not stored, it is derived from the seed when the
+/// swap is resumed.
+fn into_hbit(hbit: QueryableHbit) -> hbit::CreatedSwap {
+    hbit::CreatedSwap {
+        amount: hbit.amount.0.into(),
+        network: hbit.network.0.into(),
+        absolute_expiry: hbit.expiry.into(),
+    }
+}
+
+/// Our own and the counterparty's identities on the alpha and beta ledger of a
+/// swap.
+#[derive(Debug)]
+struct Identities<A, B> {
+    alpha: Option<A>,
+    counterparty_alpha: Option<A>,
+    beta: Option<B>,
+    counterparty_beta: Option<B>,
+}
+
+impl<A, B> Identities<A, B> {
+    fn new(
+        role: Role,
+        alpha_redeem_identity: Option<A>,
+        alpha_refund_identity: Option<A>,
+        beta_redeem_identity: Option<B>,
+        beta_refund_identity: Option<B>,
+    ) -> Self {
+        // Alice refunds on the alpha ledger and redeems on the beta ledger, Bob does
+        // it the other way around.
+        match role {
+            Role::Alice => Identities {
+                alpha: alpha_refund_identity,
+                counterparty_alpha: alpha_redeem_identity,
+                beta: beta_redeem_identity,
+                counterparty_beta: beta_refund_identity,
+            },
+            Role::Bob => Identities {
+                alpha: alpha_redeem_identity,
+                counterparty_alpha: alpha_refund_identity,
+                beta: beta_refund_identity,
+                counterparty_beta: beta_redeem_identity,
+            },
+        }
+    }
+}
+
+fn into_finalized_swap_data(
+    swap_id: LocalSwapId,
+    finalized: Option<QueryableFinalizedSwap>,
+    counterparty_ethereum_identity: Option<Text<EthereumAddress>>,
+    counterparty_lightning_identity: Option<Text<::bitcoin::PublicKey>>,
+) -> Option<FinalizedSwapData> {
+    match (
+        finalized,
+        counterparty_ethereum_identity,
+        counterparty_lightning_identity,
+    ) {
+        (Some(finalized), Some(ethereum_identity), Some(lightning_identity)) => {
+            Some(FinalizedSwapData {
+                swap_id,
+                shared_swap_id: *finalized.shared_swap_id,
+                secret_hash: *finalized.secret_hash,
+                counterparty_ethereum_identity: ethereum_identity.0.into(),
+                counterparty_lightning_identity: lightning_identity.0.into(),
+                start_of_swap: finalized.at,
+            })
+        }
+        _ => None,
+    }
+}
+
+fn into_finalized_hbit_swap_data(
+    swap_id: LocalSwapId,
+    finalized: Option<QueryableFinalizedSwap>,
+    counterparty_ethereum_identity: Option<Text<EthereumAddress>>,
+    counterparty_bitcoin_identity: Option<Text<::bitcoin::PublicKey>>,
+) -> Option<FinalizedHbitSwapData> {
+    match (
+        finalized,
+        counterparty_ethereum_identity,
+        counterparty_bitcoin_identity,
+    ) {
+        (Some(finalized), Some(ethereum_identity), Some(bitcoin_identity)) => {
+            Some(FinalizedHbitSwapData {
+                swap_id,
+                shared_swap_id: *finalized.shared_swap_id,
+                secret_hash: *finalized.secret_hash,
+                counterparty_ethereum_identity: ethereum_identity.0.into(),
+                counterparty_bitcoin_identity: bitcoin_identity.0.into(),
+                start_of_swap: finalized.at,
+            })
+        }
+        _ => None,
+    }
+}
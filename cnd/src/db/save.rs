@@ -4,20 +4,21 @@ use crate::{
         schema::{self, *},
         wrapper_types::{
             custom_sql_types::{Text, U32},
-            BitcoinNetwork, Erc20Amount, Ether, EthereumAddress, Satoshis,
+            BitcoinNetwork, Erc20Amount, Ether, EthereumAddress, Ledger, Satoshis,
         },
-        CreatedSwap, Sqlite, Swap,
+        CreatedSwap, FinalizedHbitSwapData, FinalizedSwapData, Sqlite, Swap,
     },
     identity,
     swap_protocols::{
-        halight, han,
+        halight, han, hbit, herc20,
         ledger::{self, Ethereum},
         rfc003::{Accept, Decline, Request, SecretHash, SwapId},
-        HashFunction, Role,
+        HashFunction, LocalSwapId, Role, SharedSwapId,
     },
 };
 use async_trait::async_trait;
-use diesel::RunQueryDsl;
+use chrono::{NaiveDateTime, Utc};
+use diesel::{ExpressionMethods, QueryDsl, RunQueryDsl, SqliteConnection};
 use impl_template::impl_template;
 use libp2p::{self, PeerId};
 
@@ -49,6 +50,7 @@ struct InsertableSwap {
     pub swap_id: Text<SwapId>,
     pub role: Text<Role>,
     pub counterparty: Text<PeerId>,
+    pub created_at: NaiveDateTime,
 }
 
 impl From<Swap> for InsertableSwap {
@@ -57,6 +59,7 @@ impl From<Swap> for InsertableSwap {
             swap_id: Text(swap.swap_id),
             role: Text(swap.role),
             counterparty: Text(swap.counterparty),
+            created_at: Utc::now().naive_utc(),
         }
     }
 }
@@ -507,12 +510,773 @@ impl Save<Decline> for Sqlite {
     }
 }
 
+#[derive(Insertable, Debug, Clone)]
+#[table_name = "swaps"]
+struct InsertableCreatedSwap {
+    local_swap_id: Text<LocalSwapId>,
+    role: Text<Role>,
+    counterparty_peer_id: Text<PeerId>,
+    created_at: NaiveDateTime,
+}
+
+#[derive(Insertable, Debug, Clone)]
+#[table_name = "hans"]
+struct InsertableHan {
+    swap_id: i32,
+    amount: Text<Ether>,
+    chain_id: U32,
+    expiry: U32,
+    redeem_identity: Option<Text<EthereumAddress>>,
+    refund_identity: Option<Text<EthereumAddress>>,
+    ledger: Text<Ledger>,
+}
+
+#[derive(Insertable, Debug, Clone)]
+#[table_name = "halights"]
+struct InsertableHalight {
+    swap_id: i32,
+    amount: Text<Satoshis>,
+    network: String,
+    cltv_expiry: U32,
+    redeem_identity: Option<Text<::bitcoin::PublicKey>>,
+    refund_identity: Option<Text<::bitcoin::PublicKey>>,
+    ledger: Text<Ledger>,
+}
+
+#[derive(Insertable, Debug, Clone)]
+#[table_name = "herc20s"]
+struct InsertableHerc20 {
+    swap_id: i32,
+    amount: Text<Erc20Amount>,
+    chain_id: U32,
+    expiry: U32,
+    token_contract: Text<EthereumAddress>,
+    redeem_identity: Option<Text<EthereumAddress>>,
+    refund_identity: Option<Text<EthereumAddress>>,
+    ledger: Text<Ledger>,
+}
+
+#[derive(Insertable, Debug, Clone)]
+#[table_name = "hbits"]
+struct InsertableHbit {
+    swap_id: i32,
+    amount: Text<Satoshis>,
+    network: Text<BitcoinNetwork>,
+    expiry: U32,
+    redeem_identity: Option<Text<::bitcoin::PublicKey>>,
+    refund_identity: Option<Text<::bitcoin::PublicKey>>,
+    ledger: Text<Ledger>,
+}
+
+impl InsertableHbit {
+    /// Our own identity is derived from the seed once the swap is finalized,
+    /// hence only the counterparty identity is ever stored.
+    fn new(swap_id: i32, created: hbit::CreatedSwap, ledger: Ledger) -> Self {
+        InsertableHbit {
+            swap_id,
+            amount: Text(created.amount.into()),
+            network: Text(created.network.into()),
+            expiry: U32(created.absolute_expiry),
+            redeem_identity: None,
+            refund_identity: None,
+            ledger: Text(ledger),
+        }
+    }
+}
+
 #[async_trait]
 impl Save<CreatedSwap<han::CreatedSwap, halight::CreatedSwap>> for Sqlite {
     async fn save(
         &self,
-        _: CreatedSwap<han::CreatedSwap, halight::CreatedSwap>,
+        created: CreatedSwap<han::CreatedSwap, halight::CreatedSwap>,
+    ) -> anyhow::Result<()> {
+        let CreatedSwap {
+            swap_id: local_swap_id,
+            alpha,
+            beta,
+            peer,
+            role,
+            created_at,
+        } = created;
+
+        let insertable_swap = InsertableCreatedSwap {
+            local_swap_id: Text(local_swap_id),
+            role: Text(role),
+            counterparty_peer_id: Text(peer),
+            created_at,
+        };
+
+        let ethereum_identity = Text(EthereumAddress::from(alpha.identity));
+        let lightning_identity = Text(::bitcoin::PublicKey::from(beta.identity));
+        let (
+            han_redeem_identity,
+            han_refund_identity,
+            halight_redeem_identity,
+            halight_refund_identity,
+        ) = own_identities(role, ethereum_identity, lightning_identity);
+
+        self.do_in_transaction(|connection| {
+            let swap_id = insert_created_swap(connection, &insertable_swap)?;
+
+            let insertable_han = InsertableHan {
+                swap_id,
+                amount: Text(alpha.amount.clone().into()),
+                chain_id: U32(alpha.chain_id),
+                expiry: U32(alpha.absolute_expiry),
+                redeem_identity: han_redeem_identity.clone(),
+                refund_identity: han_refund_identity.clone(),
+                ledger: Text(Ledger::Alpha),
+            };
+
+            diesel::insert_into(hans::table)
+                .values(&insertable_han)
+                .execute(connection)?;
+
+            let insertable_halight = InsertableHalight {
+                swap_id,
+                amount: Text(beta.amount.into()),
+                network: beta.network.clone(),
+                cltv_expiry: U32(beta.cltv_expiry),
+                redeem_identity: halight_redeem_identity.clone(),
+                refund_identity: halight_refund_identity.clone(),
+                ledger: Text(Ledger::Beta),
+            };
+
+            diesel::insert_into(halights::table)
+                .values(&insertable_halight)
+                .execute(connection)
+        })
+        .await?;
+
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl Save<CreatedSwap<herc20::CreatedSwap, halight::CreatedSwap>> for Sqlite {
+    async fn save(
+        &self,
+        created: CreatedSwap<herc20::CreatedSwap, halight::CreatedSwap>,
+    ) -> anyhow::Result<()> {
+        let CreatedSwap {
+            swap_id: local_swap_id,
+            alpha,
+            beta,
+            peer,
+            role,
+            created_at,
+        } = created;
+
+        let insertable_swap = InsertableCreatedSwap {
+            local_swap_id: Text(local_swap_id),
+            role: Text(role),
+            counterparty_peer_id: Text(peer),
+            created_at,
+        };
+
+        let ethereum_identity = Text(EthereumAddress::from(alpha.identity));
+        let lightning_identity = Text(::bitcoin::PublicKey::from(beta.identity));
+        let (
+            herc20_redeem_identity,
+            herc20_refund_identity,
+            halight_redeem_identity,
+            halight_refund_identity,
+        ) = own_identities(role, ethereum_identity, lightning_identity);
+
+        self.do_in_transaction(|connection| {
+            let swap_id = insert_created_swap(connection, &insertable_swap)?;
+
+            let insertable_herc20 = InsertableHerc20 {
+                swap_id,
+                amount: Text(alpha.amount.clone().into()),
+                chain_id: U32(alpha.chain_id),
+                expiry: U32(alpha.absolute_expiry),
+                token_contract: Text(alpha.contract_address.into()),
+                redeem_identity: herc20_redeem_identity.clone(),
+                refund_identity: herc20_refund_identity.clone(),
+                ledger: Text(Ledger::Alpha),
+            };
+
+            diesel::insert_into(herc20s::table)
+                .values(&insertable_herc20)
+                .execute(connection)?;
+
+            let insertable_halight = InsertableHalight {
+                swap_id,
+                amount: Text(beta.amount.into()),
+                network: beta.network.clone(),
+                cltv_expiry: U32(beta.cltv_expiry),
+                redeem_identity: halight_redeem_identity.clone(),
+                refund_identity: halight_refund_identity.clone(),
+                ledger: Text(Ledger::Beta),
+            };
+
+            diesel::insert_into(halights::table)
+                .values(&insertable_halight)
+                .execute(connection)
+        })
+        .await?;
+
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl Save<CreatedSwap<halight::CreatedSwap, han::CreatedSwap>> for Sqlite {
+    async fn save(
+        &self,
+        created: CreatedSwap<halight::CreatedSwap, han::CreatedSwap>,
+    ) -> anyhow::Result<()> {
+        let CreatedSwap {
+            swap_id: local_swap_id,
+            alpha,
+            beta,
+            peer,
+            role,
+            created_at,
+        } = created;
+
+        let insertable_swap = InsertableCreatedSwap {
+            local_swap_id: Text(local_swap_id),
+            role: Text(role),
+            counterparty_peer_id: Text(peer),
+            created_at,
+        };
+
+        let lightning_identity = Text(::bitcoin::PublicKey::from(alpha.identity));
+        let ethereum_identity = Text(EthereumAddress::from(beta.identity));
+        let (
+            halight_redeem_identity,
+            halight_refund_identity,
+            han_redeem_identity,
+            han_refund_identity,
+        ) = own_identities(role, lightning_identity, ethereum_identity);
+
+        self.do_in_transaction(|connection| {
+            let swap_id = insert_created_swap(connection, &insertable_swap)?;
+
+            let insertable_halight = InsertableHalight {
+                swap_id,
+                amount: Text(alpha.amount.into()),
+                network: alpha.network.clone(),
+                cltv_expiry: U32(alpha.cltv_expiry),
+                redeem_identity: halight_redeem_identity.clone(),
+                refund_identity: halight_refund_identity.clone(),
+                ledger: Text(Ledger::Alpha),
+            };
+
+            diesel::insert_into(halights::table)
+                .values(&insertable_halight)
+                .execute(connection)?;
+
+            let insertable_han = InsertableHan {
+                swap_id,
+                amount: Text(beta.amount.clone().into()),
+                chain_id: U32(beta.chain_id),
+                expiry: U32(beta.absolute_expiry),
+                redeem_identity: han_redeem_identity.clone(),
+                refund_identity: han_refund_identity.clone(),
+                ledger: Text(Ledger::Beta),
+            };
+
+            diesel::insert_into(hans::table)
+                .values(&insertable_han)
+                .execute(connection)
+        })
+        .await?;
+
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl Save<CreatedSwap<halight::CreatedSwap, herc20::CreatedSwap>> for Sqlite {
+    async fn save(
+        &self,
+        created: CreatedSwap<halight::CreatedSwap, herc20::CreatedSwap>,
+    ) -> anyhow::Result<()> {
+        let CreatedSwap {
+            swap_id: local_swap_id,
+            alpha,
+            beta,
+            peer,
+            role,
+            created_at,
+        } = created;
+
+        let insertable_swap = InsertableCreatedSwap {
+            local_swap_id: Text(local_swap_id),
+            role: Text(role),
+            counterparty_peer_id: Text(peer),
+            created_at,
+        };
+
+        let lightning_identity = Text(::bitcoin::PublicKey::from(alpha.identity));
+        let ethereum_identity = Text(EthereumAddress::from(beta.identity));
+        let (
+            halight_redeem_identity,
+            halight_refund_identity,
+            herc20_redeem_identity,
+            herc20_refund_identity,
+        ) = own_identities(role, lightning_identity, ethereum_identity);
+
+        self.do_in_transaction(|connection| {
+            let swap_id = insert_created_swap(connection, &insertable_swap)?;
+
+            let insertable_halight = InsertableHalight {
+                swap_id,
+                amount: Text(alpha.amount.into()),
+                network: alpha.network.clone(),
+                cltv_expiry: U32(alpha.cltv_expiry),
+                redeem_identity: halight_redeem_identity.clone(),
+                refund_identity: halight_refund_identity.clone(),
+                ledger: Text(Ledger::Alpha),
+            };
+
+            diesel::insert_into(halights::table)
+                .values(&insertable_halight)
+                .execute(connection)?;
+
+            let insertable_herc20 = InsertableHerc20 {
+                swap_id,
+                amount: Text(beta.amount.clone().into()),
+                chain_id: U32(beta.chain_id),
+                expiry: U32(beta.absolute_expiry),
+                token_contract: Text(beta.contract_address.into()),
+                redeem_identity: herc20_redeem_identity.clone(),
+                refund_identity: herc20_refund_identity.clone(),
+                ledger: Text(Ledger::Beta),
+            };
+
+            diesel::insert_into(herc20s::table)
+                .values(&insertable_herc20)
+                .execute(connection)
+        })
+        .await?;
+
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl Save<CreatedSwap<han::CreatedSwap, hbit::CreatedSwap>> for Sqlite {
+    async fn save(
+        &self,
+        created: CreatedSwap<han::CreatedSwap, hbit::CreatedSwap>,
+    ) -> anyhow::Result<()> {
+        let CreatedSwap {
+            swap_id: local_swap_id,
+            alpha,
+            beta,
+            peer,
+            role,
+            created_at,
+        } = created;
+
+        let insertable_swap = InsertableCreatedSwap {
+            local_swap_id: Text(local_swap_id),
+            role: Text(role),
+            counterparty_peer_id: Text(peer),
+            created_at,
+        };
+
+        let ethereum_identity = Text(EthereumAddress::from(alpha.identity));
+        let (han_redeem_identity, han_refund_identity, ..) =
+            own_identities(role, ethereum_identity, ());
+
+        self.do_in_transaction(|connection| {
+            let swap_id = insert_created_swap(connection, &insertable_swap)?;
+
+            let insertable_han = InsertableHan {
+                swap_id,
+                amount: Text(alpha.amount.clone().into()),
+                chain_id: U32(alpha.chain_id),
+                expiry: U32(alpha.absolute_expiry),
+                redeem_identity: han_redeem_identity.clone(),
+                refund_identity: han_refund_identity.clone(),
+                ledger: Text(Ledger::Alpha),
+            };
+
+            diesel::insert_into(hans::table)
+                .values(&insertable_han)
+                .execute(connection)?;
+
+            diesel::insert_into(hbits::table)
+                .values(&InsertableHbit::new(swap_id, beta.clone(), Ledger::Beta))
+                .execute(connection)
+        })
+        .await?;
+
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl Save<CreatedSwap<herc20::CreatedSwap, hbit::CreatedSwap>> for Sqlite {
+    async fn save(
+        &self,
+        created: CreatedSwap<herc20::CreatedSwap, hbit::CreatedSwap>,
+    ) -> anyhow::Result<()> {
+        let CreatedSwap {
+            swap_id: local_swap_id,
+            alpha,
+            beta,
+            peer,
+            role,
+            created_at,
+        } = created;
+
+        let insertable_swap = InsertableCreatedSwap {
+            local_swap_id: Text(local_swap_id),
+            role: Text(role),
+            counterparty_peer_id: Text(peer),
+            created_at,
+        };
+
+        let ethereum_identity = Text(EthereumAddress::from(alpha.identity));
+        let (herc20_redeem_identity, herc20_refund_identity, ..) =
+            own_identities(role, ethereum_identity, ());
+
+        self.do_in_transaction(|connection| {
+            let swap_id = insert_created_swap(connection, &insertable_swap)?;
+
+            let insertable_herc20 = InsertableHerc20 {
+                swap_id,
+                amount: Text(alpha.amount.clone().into()),
+                chain_id: U32(alpha.chain_id),
+                expiry: U32(alpha.absolute_expiry),
+                token_contract: Text(alpha.contract_address.into()),
+                redeem_identity: herc20_redeem_identity.clone(),
+                refund_identity: herc20_refund_identity.clone(),
+                ledger: Text(Ledger::Alpha),
+            };
+
+            diesel::insert_into(herc20s::table)
+                .values(&insertable_herc20)
+                .execute(connection)?;
+
+            diesel::insert_into(hbits::table)
+                .values(&InsertableHbit::new(swap_id, beta.clone(), Ledger::Beta))
+                .execute(connection)
+        })
+        .await?;
+
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl Save<CreatedSwap<hbit::CreatedSwap, han::CreatedSwap>> for Sqlite {
+    async fn save(
+        &self,
+        created: CreatedSwap<hbit::CreatedSwap, han::CreatedSwap>,
     ) -> anyhow::Result<()> {
-        unimplemented!()
+        let CreatedSwap {
+            swap_id: local_swap_id,
+            alpha,
+            beta,
+            peer,
+            role,
+            created_at,
+        } = created;
+
+        let insertable_swap = InsertableCreatedSwap {
+            local_swap_id: Text(local_swap_id),
+            role: Text(role),
+            counterparty_peer_id: Text(peer),
+            created_at,
+        };
+
+        let ethereum_identity = Text(EthereumAddress::from(beta.identity));
+        let (_, _, han_redeem_identity, han_refund_identity) =
+            own_identities(role, (), ethereum_identity);
+
+        self.do_in_transaction(|connection| {
+            let swap_id = insert_created_swap(connection, &insertable_swap)?;
+
+            diesel::insert_into(hbits::table)
+                .values(&InsertableHbit::new(swap_id, alpha.clone(), Ledger::Alpha))
+                .execute(connection)?;
+
+            let insertable_han = InsertableHan {
+                swap_id,
+                amount: Text(beta.amount.clone().into()),
+                chain_id: U32(beta.chain_id),
+                expiry: U32(beta.absolute_expiry),
+                redeem_identity: han_redeem_identity.clone(),
+                refund_identity: han_refund_identity.clone(),
+                ledger: Text(Ledger::Beta),
+            };
+
+            diesel::insert_into(hans::table)
+                .values(&insertable_han)
+                .execute(connection)
+        })
+        .await?;
+
+        Ok(())
     }
 }
+
+#[async_trait]
+impl Save<CreatedSwap<hbit::CreatedSwap, herc20::CreatedSwap>> for Sqlite {
+    async fn save(
+        &self,
+        created: CreatedSwap<hbit::CreatedSwap, herc20::CreatedSwap>,
+    ) -> anyhow::Result<()> {
+        let CreatedSwap {
+            swap_id: local_swap_id,
+            alpha,
+            beta,
+            peer,
+            role,
+            created_at,
+        } = created;
+
+        let insertable_swap = InsertableCreatedSwap {
+            local_swap_id: Text(local_swap_id),
+            role: Text(role),
+            counterparty_peer_id: Text(peer),
+            created_at,
+        };
+
+        let ethereum_identity = Text(EthereumAddress::from(beta.identity));
+        let (_, _, herc20_redeem_identity, herc20_refund_identity) =
+            own_identities(role, (), ethereum_identity);
+
+        self.do_in_transaction(|connection| {
+            let swap_id = insert_created_swap(connection, &insertable_swap)?;
+
+            diesel::insert_into(hbits::table)
+                .values(&InsertableHbit::new(swap_id, alpha.clone(), Ledger::Alpha))
+                .execute(connection)?;
+
+            let insertable_herc20 = InsertableHerc20 {
+                swap_id,
+                amount: Text(beta.amount.clone().into()),
+                chain_id: U32(beta.chain_id),
+                expiry: U32(beta.absolute_expiry),
+                token_contract: Text(beta.contract_address.into()),
+                redeem_identity: herc20_redeem_identity.clone(),
+                refund_identity: herc20_refund_identity.clone(),
+                ledger: Text(Ledger::Beta),
+            };
+
+            diesel::insert_into(herc20s::table)
+                .values(&insertable_herc20)
+                .execute(connection)
+        })
+        .await?;
+
+        Ok(())
+    }
+}
+
+/// Inserts the swap and returns the id of the newly created row.
+fn insert_created_swap(
+    connection: &SqliteConnection,
+    insertable: &InsertableCreatedSwap,
+) -> diesel::QueryResult<i32> {
+    diesel::insert_into(swaps::table)
+        .values(insertable)
+        .execute(connection)?;
+
+    swaps::table
+        .filter(swaps::local_swap_id.eq(insertable.local_swap_id))
+        .select(swaps::id)
+        .first::<i32>(connection)
+}
+
+/// Returns the alpha redeem, alpha refund, beta redeem and beta refund
+/// identity, in that order.
+///
+/// Alice refunds on the alpha ledger and redeems on the beta ledger, Bob does
+/// it the other way around. We only know our own identities before the swap is
+/// finalized.
+#[allow(clippy::type_complexity)]
+fn own_identities<A, B>(
+    role: Role,
+    alpha_identity: A,
+    beta_identity: B,
+) -> (Option<A>, Option<A>, Option<B>, Option<B>) {
+    match role {
+        Role::Alice => (None, Some(alpha_identity), Some(beta_identity), None),
+        Role::Bob => (Some(alpha_identity), None, None, Some(beta_identity)),
+    }
+}
+
+#[derive(Insertable, Debug, Clone)]
+#[table_name = "finalized_swaps"]
+struct InsertableFinalizedSwap {
+    swap_id: i32,
+    shared_swap_id: Text<SharedSwapId>,
+    secret_hash: Text<SecretHash>,
+    at: NaiveDateTime,
+}
+
+#[async_trait]
+impl Save<FinalizedSwapData> for Sqlite {
+    async fn save(&self, finalized: FinalizedSwapData) -> anyhow::Result<()> {
+        let FinalizedSwapData {
+            swap_id: local_swap_id,
+            shared_swap_id,
+            secret_hash,
+            counterparty_ethereum_identity,
+            counterparty_lightning_identity,
+            start_of_swap,
+        } = finalized;
+
+        let lightning_identity = Some(Text(::bitcoin::PublicKey::from(
+            counterparty_lightning_identity,
+        )));
+
+        self.do_in_transaction(|connection| {
+            let (swap_id, redeem_ledger, refund_ledger) = insert_finalized_swap(
+                connection,
+                local_swap_id,
+                shared_swap_id,
+                secret_hash,
+                counterparty_ethereum_identity,
+                start_of_swap,
+            )?;
+
+            diesel::update(
+                halights::table
+                    .filter(halights::swap_id.eq(swap_id))
+                    .filter(halights::ledger.eq(redeem_ledger)),
+            )
+            .set(halights::redeem_identity.eq(lightning_identity.clone()))
+            .execute(connection)?;
+            diesel::update(
+                halights::table
+                    .filter(halights::swap_id.eq(swap_id))
+                    .filter(halights::ledger.eq(refund_ledger)),
+            )
+            .set(halights::refund_identity.eq(lightning_identity.clone()))
+            .execute(connection)
+        })
+        .await?;
+
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl Save<FinalizedHbitSwapData> for Sqlite {
+    async fn save(&self, finalized: FinalizedHbitSwapData) -> anyhow::Result<()> {
+        let FinalizedHbitSwapData {
+            swap_id: local_swap_id,
+            shared_swap_id,
+            secret_hash,
+            counterparty_ethereum_identity,
+            counterparty_bitcoin_identity,
+            start_of_swap,
+        } = finalized;
+
+        let bitcoin_identity = Some(Text(::bitcoin::PublicKey::from(
+            counterparty_bitcoin_identity,
+        )));
+
+        self.do_in_transaction(|connection| {
+            let (swap_id, redeem_ledger, refund_ledger) = insert_finalized_swap(
+                connection,
+                local_swap_id,
+                shared_swap_id,
+                secret_hash,
+                counterparty_ethereum_identity,
+                start_of_swap,
+            )?;
+
+            diesel::update(
+                hbits::table
+                    .filter(hbits::swap_id.eq(swap_id))
+                    .filter(hbits::ledger.eq(redeem_ledger)),
+            )
+            .set(hbits::redeem_identity.eq(bitcoin_identity.clone()))
+            .execute(connection)?;
+            diesel::update(
+                hbits::table
+                    .filter(hbits::swap_id.eq(swap_id))
+                    .filter(hbits::ledger.eq(refund_ledger)),
+            )
+            .set(hbits::refund_identity.eq(bitcoin_identity.clone()))
+            .execute(connection)
+        })
+        .await?;
+
+        Ok(())
+    }
+}
+
+/// Records that the swap was finalized and stores the Ethereum identity of the
+/// counterparty.
+///
+/// Returns the id of the swap row as well as the ledgers on which the
+/// counterparty redeems and refunds, in that order, so the caller can store
+/// the counterparty identity of the other protocol.
+fn insert_finalized_swap(
+    connection: &SqliteConnection,
+    local_swap_id: LocalSwapId,
+    shared_swap_id: SharedSwapId,
+    secret_hash: SecretHash,
+    counterparty_ethereum_identity: identity::Ethereum,
+    start_of_swap: NaiveDateTime,
+) -> diesel::QueryResult<(i32, Text<Ledger>, Text<Ledger>)> {
+    let ethereum_identity = Some(Text(EthereumAddress::from(counterparty_ethereum_identity)));
+
+    let (swap_id, role) = swaps::table
+        .filter(swaps::local_swap_id.eq(Text(local_swap_id)))
+        .select((swaps::id, swaps::role))
+        .first::<(i32, Text<Role>)>(connection)?;
+
+    let insertable = InsertableFinalizedSwap {
+        swap_id,
+        shared_swap_id: Text(shared_swap_id),
+        secret_hash: Text(secret_hash),
+        at: start_of_swap,
+    };
+
+    diesel::insert_into(finalized_swaps::table)
+        .values(&insertable)
+        .execute(connection)?;
+
+    // The counterparty redeems on the ledger we fund and refunds on the other one.
+    // A swap only uses one of the two Ethereum protocols, updating the rows of the
+    // protocol that is not used is a no-op.
+    let (redeem_ledger, refund_ledger) = match *role {
+        Role::Alice => (Text(Ledger::Alpha), Text(Ledger::Beta)),
+        Role::Bob => (Text(Ledger::Beta), Text(Ledger::Alpha)),
+    };
+
+    diesel::update(
+        hans::table
+            .filter(hans::swap_id.eq(swap_id))
+            .filter(hans::ledger.eq(redeem_ledger)),
+    )
+    .set(hans::redeem_identity.eq(ethereum_identity.clone()))
+    .execute(connection)?;
+    diesel::update(
+        hans::table
+            .filter(hans::swap_id.eq(swap_id))
+            .filter(hans::ledger.eq(refund_ledger)),
+    )
+    .set(hans::refund_identity.eq(ethereum_identity.clone()))
+    .execute(connection)?;
+
+    diesel::update(
+        herc20s::table
+            .filter(herc20s::swap_id.eq(swap_id))
+            .filter(herc20s::ledger.eq(redeem_ledger)),
+    )
+    .set(herc20s::redeem_identity.eq(ethereum_identity.clone()))
+    .execute(connection)?;
+    diesel::update(
+        herc20s::table
+            .filter(herc20s::swap_id.eq(swap_id))
+            .filter(herc20s::ledger.eq(refund_ledger)),
+    )
+    .set(herc20s::refund_identity.eq(ethereum_identity))
+    .execute(connection)?;
+
+    Ok((swap_id, redeem_ledger, refund_ledger))
+}
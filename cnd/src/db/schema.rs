@@ -102,5 +102,136 @@ table! {
        swap_id -> Text,
        role -> Text,
        counterparty -> Text,
+       status -> Text,
+       created_at -> Timestamp,
+   }
+}
+
+table! {
+   swaps {
+       id -> Integer,
+       local_swap_id -> Text,
+       role -> Text,
+       counterparty_peer_id -> Text,
+       status -> Text,
+       created_at -> Timestamp,
+   }
+}
+
+table! {
+   finalized_swaps {
+       id -> Integer,
+       swap_id -> Integer,
+       shared_swap_id -> Text,
+       secret_hash -> Text,
+       at -> Timestamp,
+   }
+}
+
+table! {
+   hans {
+       id -> Integer,
+       swap_id -> Integer,
+       amount -> Text,
+       chain_id -> BigInt,
+       expiry -> BigInt,
+       redeem_identity -> Nullable<Text>,
+       refund_identity -> Nullable<Text>,
+       ledger -> Text,
+   }
+}
+
+table! {
+   halights {
+       id -> Integer,
+       swap_id -> Integer,
+       amount -> Text,
+       network -> Text,
+       cltv_expiry -> BigInt,
+       redeem_identity -> Nullable<Text>,
+       refund_identity -> Nullable<Text>,
+       ledger -> Text,
+   }
+}
+
+table! {
+   herc20s {
+       id -> Integer,
+       swap_id -> Integer,
+       amount -> Text,
+       chain_id -> BigInt,
+       expiry -> BigInt,
+       token_contract -> Text,
+       redeem_identity -> Nullable<Text>,
+       refund_identity -> Nullable<Text>,
+       ledger -> Text,
+   }
+}
+
+table! {
+   hbits {
+       id -> Integer,
+       swap_id -> Integer,
+       amount -> Text,
+       network -> Text,
+       expiry -> BigInt,
+       redeem_identity -> Nullable<Text>,
+       refund_identity -> Nullable<Text>,
+       ledger -> Text,
+   }
+}
+
+table! {
+   rfc003_watcher_checkpoints {
+       id -> Integer,
+       swap_id -> Text,
+       ledger -> Text,
+       block_hash -> Nullable<Text>,
+       block_timestamp -> Nullable<Timestamp>,
+       deployed -> Nullable<Text>,
+       funded -> Nullable<Text>,
+       start_height -> Nullable<BigInt>,
+       block_height -> Nullable<BigInt>,
+   }
+}
+
+table! {
+   swap_watcher_checkpoints {
+       id -> Integer,
+       swap_id -> Text,
+       ledger -> Text,
+       start_height -> Nullable<BigInt>,
+       block_hash -> Nullable<Text>,
+       block_height -> Nullable<BigInt>,
+       block_timestamp -> Nullable<Timestamp>,
+       deployed -> Nullable<Text>,
+       funded -> Nullable<Text>,
+   }
+}
+
+table! {
+   autopilot_actions {
+       id -> Integer,
+       swap_id -> Text,
+       action -> Text,
+       executed_at -> Timestamp,
+   }
+}
+
+table! {
+   webhook_deliveries {
+       id -> Integer,
+       url -> Text,
+       payload -> Text,
+       attempts -> Integer,
+       next_attempt_at -> Timestamp,
+   }
+}
+
+table! {
+   queued_webhook_notifications {
+       id -> Integer,
+       swap_id -> Text,
+       event -> Text,
    }
 }
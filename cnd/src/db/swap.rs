@@ -7,6 +7,19 @@ use async_trait::async_trait;
 use diesel::RunQueryDsl;
 use libp2p::{self, PeerId};
 
+/// The columns `QueryableSwap` is loaded from.
+pub(super) const SWAP_COLUMNS: (
+    schema::rfc003_swaps::id,
+    schema::rfc003_swaps::swap_id,
+    schema::rfc003_swaps::role,
+    schema::rfc003_swaps::counterparty,
+) = (
+    schema::rfc003_swaps::id,
+    schema::rfc003_swaps::swap_id,
+    schema::rfc003_swaps::role,
+    schema::rfc003_swaps::counterparty,
+);
+
 /// Retrieve swaps from database.
 #[async_trait]
 #[ambassador::delegatable_trait]
@@ -42,6 +55,7 @@ impl Retrieve for Sqlite {
                 let key = Text(key);
 
                 rfc003_swaps
+                    .select(SWAP_COLUMNS)
                     .filter(swap_id.eq(key))
                     .first(&*connection)
                     .optional()
@@ -56,7 +70,7 @@ impl Retrieve for Sqlite {
         use self::schema::rfc003_swaps::dsl::*;
 
         let records: Vec<QueryableSwap> = self
-            .do_in_transaction(|connection| rfc003_swaps.load(&*connection))
+            .do_in_transaction(|connection| rfc003_swaps.select(SWAP_COLUMNS).load(&*connection))
             .await?;
 
         Ok(records.into_iter().map(|q| q.into()).collect())
@@ -64,7 +78,7 @@ impl Retrieve for Sqlite {
 }
 
 #[derive(Queryable, Debug, Clone, PartialEq)]
-struct QueryableSwap {
+pub(super) struct QueryableSwap {
     pub id: i32,
     pub swap_id: Text<SwapId>,
     pub role: Text<Role>,
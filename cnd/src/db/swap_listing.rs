@@ -0,0 +1,646 @@
+use crate::{
+    db::{
+        schema::{
+            self, halights, hans, hbits, herc20s,
+            rfc003_bitcoin_ethereum_bitcoin_erc20_request_messages,
+            rfc003_bitcoin_ethereum_bitcoin_ether_request_messages,
+            rfc003_ethereum_bitcoin_erc20_bitcoin_request_messages,
+            rfc003_ethereum_bitcoin_ether_bitcoin_request_messages, rfc003_swaps, swaps,
+        },
+        swap::{QueryableSwap, SWAP_COLUMNS},
+        wrapper_types::{custom_sql_types::Text, Ledger},
+        Sqlite, Swap,
+    },
+    swap_protocols::{rfc003::SwapId, LocalSwapId, Role},
+};
+use chrono::NaiveDateTime;
+use diesel::{
+    sqlite::Sqlite as Backend, BoolExpressionMethods, ExpressionMethods, OptionalExtension,
+    QueryDsl, QueryResult, RunQueryDsl, SqliteConnection,
+};
+use libp2p::PeerId;
+use serde::{Deserialize, Serialize};
+use std::{cmp::Ordering, collections::HashMap, convert::TryFrom, fmt, str::FromStr};
+use strum_macros::{Display, EnumString};
+
+/// The status of a swap, kept up to date by `swap_status::track`.
+#[derive(Clone, Copy, Debug, Display, EnumString, PartialEq, Eq, Hash, Deserialize, Serialize)]
+#[strum(serialize_all = "snake_case")]
+#[serde(rename_all = "snake_case")]
+pub enum SwapStatus {
+    InProgress,
+    Swapped,
+    NotSwapped,
+    Failed,
+}
+
+/// The combinations of protocols and assets of the swaps we store.
+#[derive(Clone, Copy, Debug, Display, PartialEq, Eq, Hash)]
+#[strum(serialize_all = "snake_case")]
+pub enum SwapKind {
+    Rfc003BitcoinEther,
+    Rfc003EtherBitcoin,
+    Rfc003BitcoinErc20,
+    Rfc003Erc20Bitcoin,
+    HanHalight,
+    Herc20Halight,
+    HalightHan,
+    HalightHerc20,
+    HanHbit,
+    Herc20Hbit,
+    HbitHan,
+    HbitHerc20,
+}
+
+impl SwapKind {
+    /// The protocols used on the alpha and the beta ledger, as named by the
+    /// HTTP API.
+    pub fn protocol(self) -> &'static str {
+        match self {
+            SwapKind::Rfc003BitcoinEther
+            | SwapKind::Rfc003EtherBitcoin
+            | SwapKind::Rfc003BitcoinErc20
+            | SwapKind::Rfc003Erc20Bitcoin => "rfc003",
+            SwapKind::HanHalight => "han-halight",
+            SwapKind::Herc20Halight => "herc20-halight",
+            SwapKind::HalightHan => "halight-han",
+            SwapKind::HalightHerc20 => "halight-herc20",
+            SwapKind::HanHbit => "han-hbit",
+            SwapKind::Herc20Hbit => "herc20-hbit",
+            SwapKind::HbitHan => "hbit-han",
+            SwapKind::HbitHerc20 => "hbit-herc20",
+        }
+    }
+}
+
+// The kind of a swap is determined by the tables its protocols are stored in.
+diesel::allow_tables_to_appear_in_same_query!(swaps, hans, herc20s, halights, hbits);
+diesel::allow_tables_to_appear_in_same_query!(
+    rfc003_swaps,
+    rfc003_bitcoin_ethereum_bitcoin_ether_request_messages,
+    rfc003_ethereum_bitcoin_ether_bitcoin_request_messages,
+    rfc003_bitcoin_ethereum_bitcoin_erc20_request_messages,
+    rfc003_ethereum_bitcoin_erc20_bitcoin_request_messages
+);
+
+/// Restricts the swaps returned by `Sqlite::list_swaps`.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct SwapFilter {
+    pub role: Option<Role>,
+    pub status: Option<SwapStatus>,
+    pub counterparty: Option<PeerId>,
+    /// Only swaps of one of these kinds.
+    pub kinds: Option<Vec<SwapKind>>,
+    pub created_after: Option<NaiveDateTime>,
+    pub created_before: Option<NaiveDateTime>,
+}
+
+impl SwapFilter {
+    fn includes(&self, kind: SwapKind) -> bool {
+        self.kinds
+            .as_ref()
+            .map_or(true, |kinds| kinds.contains(&kind))
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Order {
+    Asc,
+    Desc,
+}
+
+impl Default for Order {
+    fn default() -> Self {
+        Order::Asc
+    }
+}
+
+/// Which part of the swaps to return, in order of creation.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct Page {
+    /// All swaps if `None`.
+    pub limit: Option<u32>,
+    /// Only swaps after the one this cursor was returned for.
+    pub cursor: Option<Cursor>,
+    pub order: Order,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct SwapPage {
+    pub swaps: Vec<ListedSwap>,
+    /// Cursor of the next page, `None` if this is the last one.
+    pub next_cursor: Option<Cursor>,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum ListedSwap {
+    Rfc003(Swap),
+    /// Split protocol swaps are listed with the kind their protocols tell,
+    /// whether or not the swap was finalized yet.
+    SplitProtocol(LocalSwapId, SwapKind),
+}
+
+/// The table a swap is stored in, rfc003 swaps come first if two swaps were
+/// created at the same time.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+enum Table {
+    Rfc003Swaps,
+    Swaps,
+}
+
+/// Position of a swap in the listing.
+///
+/// Swaps are ordered by creation time, ties are broken by the table and the
+/// row id of the swap.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub struct Cursor {
+    created_at: NaiveDateTime,
+    table: Table,
+    id: i32,
+}
+
+impl fmt::Display for Cursor {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let table = match self.table {
+            Table::Rfc003Swaps => 0,
+            Table::Swaps => 1,
+        };
+
+        write!(
+            f,
+            "{}.{:09}-{}-{}",
+            self.created_at.timestamp(),
+            self.created_at.timestamp_subsec_nanos(),
+            table,
+            self.id
+        )
+    }
+}
+
+#[derive(Clone, Copy, Debug, thiserror::Error)]
+#[error("invalid cursor")]
+pub struct InvalidCursor;
+
+impl FromStr for Cursor {
+    type Err = InvalidCursor;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let parts = s.split(|c| c == '.' || c == '-').collect::<Vec<_>>();
+
+        match parts.as_slice() {
+            [secs, nanos, table, id] => {
+                let secs = secs.parse().map_err(|_| InvalidCursor)?;
+                let nanos = nanos.parse().map_err(|_| InvalidCursor)?;
+                let created_at =
+                    NaiveDateTime::from_timestamp_opt(secs, nanos).ok_or(InvalidCursor)?;
+                let table = match *table {
+                    "0" => Table::Rfc003Swaps,
+                    "1" => Table::Swaps,
+                    _ => return Err(InvalidCursor),
+                };
+                let id = id.parse().map_err(|_| InvalidCursor)?;
+
+                Ok(Cursor {
+                    created_at,
+                    table,
+                    id,
+                })
+            }
+            _ => Err(InvalidCursor),
+        }
+    }
+}
+
+/// Restricts `$query` on `$table` to the rows after the cursor of `$page`,
+/// orders them and applies the limit.
+macro_rules! paginate {
+    ($query:ident, $table:ident, $this_table:expr, $page:expr, $limit:expr) => {
+        match ($page.order, $page.cursor) {
+            (_, None) => {}
+            (Order::Asc, Some(cursor)) => {
+                $query = match $this_table.cmp(&cursor.table) {
+                    Ordering::Greater => $query.filter($table::created_at.ge(cursor.created_at)),
+                    Ordering::Less => $query.filter($table::created_at.gt(cursor.created_at)),
+                    Ordering::Equal => $query.filter(
+                        $table::created_at
+                            .gt(cursor.created_at)
+                            .or($table::created_at
+                                .eq(cursor.created_at)
+                                .and($table::id.gt(cursor.id))),
+                    ),
+                };
+            }
+            (Order::Desc, Some(cursor)) => {
+                $query = match $this_table.cmp(&cursor.table) {
+                    Ordering::Greater => $query.filter($table::created_at.lt(cursor.created_at)),
+                    Ordering::Less => $query.filter($table::created_at.le(cursor.created_at)),
+                    Ordering::Equal => $query.filter(
+                        $table::created_at
+                            .lt(cursor.created_at)
+                            .or($table::created_at
+                                .eq(cursor.created_at)
+                                .and($table::id.lt(cursor.id))),
+                    ),
+                };
+            }
+        }
+
+        $query = match $page.order {
+            Order::Asc => $query.order(($table::created_at.asc(), $table::id.asc())),
+            Order::Desc => $query.order(($table::created_at.desc(), $table::id.desc())),
+        };
+
+        if let Some(limit) = $limit {
+            $query = $query.limit(limit);
+        }
+    };
+}
+
+impl Sqlite {
+    /// Lists the swaps of all protocols that match the filter.
+    pub async fn list_swaps(&self, filter: &SwapFilter, page: Page) -> anyhow::Result<SwapPage> {
+        // Fetch one more than requested to know whether there is a next page.
+        let limit = page.limit.map(|limit| i64::from(limit) + 1);
+
+        let mut swaps = self
+            .do_in_transaction(|connection| {
+                let mut swaps = load_rfc003_swaps(connection, filter, page, limit)?;
+                swaps.append(&mut load_split_protocol_swaps(
+                    connection, filter, page, limit,
+                )?);
+
+                QueryResult::Ok(swaps)
+            })
+            .await?;
+
+        swaps.sort_by(|(left, _), (right, _)| match page.order {
+            Order::Asc => left.cmp(right),
+            Order::Desc => right.cmp(left),
+        });
+
+        let next_cursor = match page.limit {
+            Some(limit) => {
+                let limit = usize::try_from(limit)?;
+                if swaps.len() > limit {
+                    swaps.truncate(limit);
+                    swaps.last().map(|(cursor, _)| *cursor)
+                } else {
+                    None
+                }
+            }
+            None => None,
+        };
+
+        Ok(SwapPage {
+            swaps: swaps.into_iter().map(|(_, swap)| swap).collect(),
+            next_cursor,
+        })
+    }
+
+    /// Counts the swaps of every kind by their status.
+    pub async fn count_swaps(&self) -> anyhow::Result<HashMap<(SwapKind, SwapStatus), usize>> {
+        let counts = self
+            .do_in_transaction(|connection| {
+                let mut counts = HashMap::new();
+
+                macro_rules! count_rfc003 {
+                    ($kind:ident, $table:ident) => {
+                        let statuses: Vec<Text<SwapStatus>> = rfc003_swaps::table
+                            .filter(
+                                rfc003_swaps::swap_id
+                                    .eq_any(schema::$table::table.select(schema::$table::swap_id)),
+                            )
+                            .select(rfc003_swaps::status)
+                            .load(connection)?;
+                        for status in statuses {
+                            *counts.entry((SwapKind::$kind, *status)).or_insert(0) += 1;
+                        }
+                    };
+                }
+                count_rfc003!(
+                    Rfc003BitcoinEther,
+                    rfc003_bitcoin_ethereum_bitcoin_ether_request_messages
+                );
+                count_rfc003!(
+                    Rfc003EtherBitcoin,
+                    rfc003_ethereum_bitcoin_ether_bitcoin_request_messages
+                );
+                count_rfc003!(
+                    Rfc003BitcoinErc20,
+                    rfc003_bitcoin_ethereum_bitcoin_erc20_request_messages
+                );
+                count_rfc003!(
+                    Rfc003Erc20Bitcoin,
+                    rfc003_ethereum_bitcoin_erc20_bitcoin_request_messages
+                );
+
+                macro_rules! count_split_protocol {
+                    ($kind:ident, $table:ident, $ledger:ident, $bitcoin_table:ident) => {
+                        let statuses: Vec<Text<SwapStatus>> = swaps::table
+                            .filter(
+                                swaps::id.eq_any(
+                                    schema::$table::table
+                                        .filter(schema::$table::ledger.eq(Text(Ledger::$ledger)))
+                                        .filter(
+                                            schema::$table::swap_id.eq_any(
+                                                schema::$bitcoin_table::table
+                                                    .select(schema::$bitcoin_table::swap_id),
+                                            ),
+                                        )
+                                        .select(schema::$table::swap_id),
+                                ),
+                            )
+                            .select(swaps::status)
+                            .load(connection)?;
+                        for status in statuses {
+                            *counts.entry((SwapKind::$kind, *status)).or_insert(0) += 1;
+                        }
+                    };
+                }
+                count_split_protocol!(HanHalight, hans, Alpha, halights);
+                count_split_protocol!(Herc20Halight, herc20s, Alpha, halights);
+                count_split_protocol!(HalightHan, hans, Beta, halights);
+                count_split_protocol!(HalightHerc20, herc20s, Beta, halights);
+                count_split_protocol!(HanHbit, hans, Alpha, hbits);
+                count_split_protocol!(Herc20Hbit, herc20s, Alpha, hbits);
+                count_split_protocol!(HbitHan, hans, Beta, hbits);
+                count_split_protocol!(HbitHerc20, herc20s, Beta, hbits);
+
+                QueryResult::Ok(counts)
+            })
+            .await?;
+
+        Ok(counts)
+    }
+
+    pub async fn update_swap_status(
+        &self,
+        swap_id: &SwapId,
+        status: SwapStatus,
+    ) -> anyhow::Result<()> {
+        self.do_in_transaction(|connection| {
+            diesel::update(rfc003_swaps::table.filter(rfc003_swaps::swap_id.eq(Text(swap_id))))
+                .set(rfc003_swaps::status.eq(Text(status)))
+                .execute(connection)
+        })
+        .await?;
+
+        Ok(())
+    }
+
+    pub async fn update_local_swap_status(
+        &self,
+        swap_id: LocalSwapId,
+        status: SwapStatus,
+    ) -> anyhow::Result<()> {
+        self.do_in_transaction(|connection| {
+            diesel::update(swaps::table.filter(swaps::local_swap_id.eq(Text(swap_id))))
+                .set(swaps::status.eq(Text(status)))
+                .execute(connection)
+        })
+        .await?;
+
+        Ok(())
+    }
+}
+
+fn load_rfc003_swaps(
+    connection: &SqliteConnection,
+    filter: &SwapFilter,
+    page: Page,
+    limit: Option<i64>,
+) -> QueryResult<Vec<(Cursor, ListedSwap)>> {
+    let mut query = rfc003_swaps::table
+        .select((SWAP_COLUMNS, rfc003_swaps::created_at))
+        .into_boxed::<Backend>();
+
+    if let Some(role) = filter.role {
+        query = query.filter(rfc003_swaps::role.eq(Text(role)));
+    }
+    if let Some(status) = filter.status {
+        query = query.filter(rfc003_swaps::status.eq(Text(status)));
+    }
+    if let Some(counterparty) = &filter.counterparty {
+        query = query.filter(rfc003_swaps::counterparty.eq(Text(counterparty.clone())));
+    }
+    if let Some(created_after) = filter.created_after {
+        query = query.filter(rfc003_swaps::created_at.ge(created_after));
+    }
+    if let Some(created_before) = filter.created_before {
+        query = query.filter(rfc003_swaps::created_at.lt(created_before));
+    }
+
+    // Every rfc003 swap has its request in exactly one of the request tables,
+    // we exclude the tables of the kinds we are not interested in.
+    let mut included = 0;
+    macro_rules! exclude_unless_included {
+        ($kind:ident, $table:ident) => {
+            if filter.includes(SwapKind::$kind) {
+                included += 1;
+            } else {
+                query = query.filter(
+                    rfc003_swaps::swap_id
+                        .ne_all(schema::$table::table.select(schema::$table::swap_id)),
+                );
+            }
+        };
+    }
+    exclude_unless_included!(
+        Rfc003BitcoinEther,
+        rfc003_bitcoin_ethereum_bitcoin_ether_request_messages
+    );
+    exclude_unless_included!(
+        Rfc003EtherBitcoin,
+        rfc003_ethereum_bitcoin_ether_bitcoin_request_messages
+    );
+    exclude_unless_included!(
+        Rfc003BitcoinErc20,
+        rfc003_bitcoin_ethereum_bitcoin_erc20_request_messages
+    );
+    exclude_unless_included!(
+        Rfc003Erc20Bitcoin,
+        rfc003_ethereum_bitcoin_erc20_bitcoin_request_messages
+    );
+    if included == 0 {
+        return Ok(Vec::new());
+    }
+
+    paginate!(query, rfc003_swaps, Table::Rfc003Swaps, page, limit);
+
+    let records: Vec<(QueryableSwap, NaiveDateTime)> = query.load(connection)?;
+
+    Ok(records
+        .into_iter()
+        .map(|(swap, created_at)| {
+            let cursor = Cursor {
+                created_at,
+                table: Table::Rfc003Swaps,
+                id: swap.id,
+            };
+
+            (cursor, ListedSwap::Rfc003(Swap::from(swap)))
+        })
+        .collect())
+}
+
+fn load_split_protocol_swaps(
+    connection: &SqliteConnection,
+    filter: &SwapFilter,
+    page: Page,
+    limit: Option<i64>,
+) -> QueryResult<Vec<(Cursor, ListedSwap)>> {
+    let mut query = swaps::table
+        .select((swaps::id, swaps::local_swap_id, swaps::created_at))
+        .into_boxed::<Backend>();
+
+    if let Some(role) = filter.role {
+        query = query.filter(swaps::role.eq(Text(role)));
+    }
+    if let Some(status) = filter.status {
+        query = query.filter(swaps::status.eq(Text(status)));
+    }
+    if let Some(counterparty) = &filter.counterparty {
+        query = query.filter(swaps::counterparty_peer_id.eq(Text(counterparty.clone())));
+    }
+    if let Some(created_after) = filter.created_after {
+        query = query.filter(swaps::created_at.ge(created_after));
+    }
+    if let Some(created_before) = filter.created_before {
+        query = query.filter(swaps::created_at.lt(created_before));
+    }
+
+    // The protocol on the Ethereum side, the ledger it is used on and the
+    // protocol on the Bitcoin side determine the kind of a split protocol swap.
+    let mut included = 0;
+    macro_rules! exclude_unless_included {
+        ($kind:ident, $table:ident, $ledger:ident, $bitcoin_table:ident) => {
+            if filter.includes(SwapKind::$kind) {
+                included += 1;
+            } else {
+                query = query.filter(
+                    swaps::id.ne_all(
+                        schema::$table::table
+                            .filter(schema::$table::ledger.eq(Text(Ledger::$ledger)))
+                            .filter(
+                                schema::$table::swap_id.eq_any(
+                                    schema::$bitcoin_table::table
+                                        .select(schema::$bitcoin_table::swap_id),
+                                ),
+                            )
+                            .select(schema::$table::swap_id),
+                    ),
+                );
+            }
+        };
+    }
+    exclude_unless_included!(HanHalight, hans, Alpha, halights);
+    exclude_unless_included!(Herc20Halight, herc20s, Alpha, halights);
+    exclude_unless_included!(HalightHan, hans, Beta, halights);
+    exclude_unless_included!(HalightHerc20, herc20s, Beta, halights);
+    exclude_unless_included!(HanHbit, hans, Alpha, hbits);
+    exclude_unless_included!(Herc20Hbit, herc20s, Alpha, hbits);
+    exclude_unless_included!(HbitHan, hans, Beta, hbits);
+    exclude_unless_included!(HbitHerc20, herc20s, Beta, hbits);
+    if included == 0 {
+        return Ok(Vec::new());
+    }
+
+    paginate!(query, swaps, Table::Swaps, page, limit);
+
+    let records: Vec<(i32, Text<LocalSwapId>, NaiveDateTime)> = query.load(connection)?;
+
+    records
+        .into_iter()
+        .map(|(id, local_swap_id, created_at)| {
+            let cursor = Cursor {
+                created_at,
+                table: Table::Swaps,
+                id,
+            };
+            let kind = split_protocol_kind(connection, id)?;
+
+            Ok((cursor, ListedSwap::SplitProtocol(*local_swap_id, kind)))
+        })
+        .collect()
+}
+
+/// Tells the kind of a split protocol swap from the tables its protocols are
+/// stored in.
+fn split_protocol_kind(connection: &SqliteConnection, id: i32) -> QueryResult<SwapKind> {
+    let han: Option<Text<Ledger>> = hans::table
+        .filter(hans::swap_id.eq(id))
+        .select(hans::ledger)
+        .first(connection)
+        .optional()?;
+    let herc20: Option<Text<Ledger>> = herc20s::table
+        .filter(herc20s::swap_id.eq(id))
+        .select(herc20s::ledger)
+        .first(connection)
+        .optional()?;
+    let halight = halights::table
+        .filter(halights::swap_id.eq(id))
+        .select(halights::id)
+        .first::<i32>(connection)
+        .optional()?
+        .is_some();
+    let hbit = hbits::table
+        .filter(hbits::swap_id.eq(id))
+        .select(hbits::id)
+        .first::<i32>(connection)
+        .optional()?
+        .is_some();
+
+    let kind = match (
+        han.map(|ledger| *ledger),
+        herc20.map(|ledger| *ledger),
+        halight,
+        hbit,
+    ) {
+        (Some(Ledger::Alpha), None, true, false) => SwapKind::HanHalight,
+        (None, Some(Ledger::Alpha), true, false) => SwapKind::Herc20Halight,
+        (Some(Ledger::Beta), None, true, false) => SwapKind::HalightHan,
+        (None, Some(Ledger::Beta), true, false) => SwapKind::HalightHerc20,
+        (Some(Ledger::Alpha), None, false, true) => SwapKind::HanHbit,
+        (None, Some(Ledger::Alpha), false, true) => SwapKind::Herc20Hbit,
+        (Some(Ledger::Beta), None, false, true) => SwapKind::HbitHan,
+        (None, Some(Ledger::Beta), false, true) => SwapKind::HbitHerc20,
+        _ => return Err(diesel::result::Error::NotFound),
+    };
+
+    Ok(kind)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn cursor_roundtrips_through_its_string_representation() {
+        let cursor = Cursor {
+            created_at: NaiveDateTime::from_timestamp(1_591_000_000, 123_456_789),
+            table: Table::Swaps,
+            id: 42,
+        };
+
+        let parsed = cursor.to_string().parse::<Cursor>().unwrap();
+
+        assert_eq!(parsed, cursor);
+    }
+
+    #[test]
+    fn cursors_are_ordered_by_creation_time_table_and_id() {
+        let earlier = NaiveDateTime::from_timestamp(1_591_000_000, 0);
+        let later = NaiveDateTime::from_timestamp(1_591_000_001, 0);
+
+        let cursor = |created_at, table, id| Cursor {
+            created_at,
+            table,
+            id,
+        };
+
+        assert!(cursor(earlier, Table::Swaps, 9) < cursor(later, Table::Rfc003Swaps, 1));
+        assert!(cursor(earlier, Table::Rfc003Swaps, 9) < cursor(earlier, Table::Swaps, 1));
+        assert!(cursor(earlier, Table::Swaps, 1) < cursor(earlier, Table::Swaps, 2));
+    }
+}
@@ -0,0 +1,181 @@
+use crate::db::{
+    schema::{queued_webhook_notifications, webhook_deliveries},
+    wrapper_types::custom_sql_types::Text,
+    Sqlite,
+};
+use async_trait::async_trait;
+use chrono::NaiveDateTime;
+use diesel::{ExpressionMethods, QueryDsl, RunQueryDsl};
+use reqwest::Url;
+use uuid::Uuid;
+
+/// A webhook notification that still has to be delivered.
+#[derive(Queryable, Debug, Clone, PartialEq)]
+pub struct WebhookDelivery {
+    pub id: i32,
+    pub url: Text<Url>,
+    /// The JSON body of the notification.
+    pub payload: String,
+    /// Number of failed attempts so far.
+    pub attempts: i32,
+    pub next_attempt_at: NaiveDateTime,
+}
+
+/// Persists the queue of webhook notifications so deliveries are retried
+/// across restarts.
+#[async_trait]
+pub trait WebhookDeliveries: Send + Sync + 'static {
+    async fn enqueue_webhook_delivery(
+        &self,
+        url: Url,
+        payload: String,
+        next_attempt_at: NaiveDateTime,
+    ) -> anyhow::Result<()>;
+    /// Queues the notification about an event of a swap for each of the given
+    /// webhooks.
+    ///
+    /// The events of a swap are seen again when cnd restarts, the notification
+    /// is only queued the first time. Returns `false` if it was queued before.
+    async fn enqueue_swap_event_deliveries(
+        &self,
+        swap_id: Uuid,
+        event: String,
+        urls: Vec<Url>,
+        payload: String,
+        next_attempt_at: NaiveDateTime,
+    ) -> anyhow::Result<bool>;
+    /// Returns the deliveries whose next attempt is due at `now`.
+    async fn due_webhook_deliveries(
+        &self,
+        now: NaiveDateTime,
+    ) -> anyhow::Result<Vec<WebhookDelivery>>;
+    async fn reschedule_webhook_delivery(
+        &self,
+        id: i32,
+        attempts: i32,
+        next_attempt_at: NaiveDateTime,
+    ) -> anyhow::Result<()>;
+    async fn remove_webhook_delivery(&self, id: i32) -> anyhow::Result<()>;
+}
+
+#[async_trait]
+impl WebhookDeliveries for Sqlite {
+    async fn enqueue_webhook_delivery(
+        &self,
+        url: Url,
+        payload: String,
+        next_attempt_at: NaiveDateTime,
+    ) -> anyhow::Result<()> {
+        let insertable = InsertableWebhookDelivery {
+            url: Text(url),
+            payload,
+            attempts: 0,
+            next_attempt_at,
+        };
+
+        self.do_in_transaction(|connection| {
+            diesel::insert_into(webhook_deliveries::table)
+                .values(&insertable)
+                .execute(connection)
+        })
+        .await?;
+
+        Ok(())
+    }
+
+    async fn enqueue_swap_event_deliveries(
+        &self,
+        swap_id: Uuid,
+        event: String,
+        urls: Vec<Url>,
+        payload: String,
+        next_attempt_at: NaiveDateTime,
+    ) -> anyhow::Result<bool> {
+        let insertables: Vec<InsertableWebhookDelivery> = urls
+            .into_iter()
+            .map(|url| InsertableWebhookDelivery {
+                url: Text(url),
+                payload: payload.clone(),
+                attempts: 0,
+                next_attempt_at,
+            })
+            .collect();
+
+        let queued = self
+            .do_in_transaction(|connection| {
+                let inserted = diesel::insert_or_ignore_into(queued_webhook_notifications::table)
+                    .values((
+                        queued_webhook_notifications::swap_id.eq(Text(swap_id)),
+                        queued_webhook_notifications::event.eq(&event),
+                    ))
+                    .execute(connection)?;
+                if inserted == 0 {
+                    return Ok(false);
+                }
+
+                for insertable in &insertables {
+                    diesel::insert_into(webhook_deliveries::table)
+                        .values(insertable)
+                        .execute(connection)?;
+                }
+
+                Ok::<_, diesel::result::Error>(true)
+            })
+            .await?;
+
+        Ok(queued)
+    }
+
+    async fn due_webhook_deliveries(
+        &self,
+        now: NaiveDateTime,
+    ) -> anyhow::Result<Vec<WebhookDelivery>> {
+        let deliveries = self
+            .do_in_transaction(|connection| {
+                webhook_deliveries::table
+                    .filter(webhook_deliveries::next_attempt_at.le(now))
+                    .order(webhook_deliveries::id.asc())
+                    .load(connection)
+            })
+            .await?;
+
+        Ok(deliveries)
+    }
+
+    async fn reschedule_webhook_delivery(
+        &self,
+        id: i32,
+        attempts: i32,
+        next_attempt_at: NaiveDateTime,
+    ) -> anyhow::Result<()> {
+        self.do_in_transaction(|connection| {
+            diesel::update(webhook_deliveries::table.find(id))
+                .set((
+                    webhook_deliveries::attempts.eq(attempts),
+                    webhook_deliveries::next_attempt_at.eq(next_attempt_at),
+                ))
+                .execute(connection)
+        })
+        .await?;
+
+        Ok(())
+    }
+
+    async fn remove_webhook_delivery(&self, id: i32) -> anyhow::Result<()> {
+        self.do_in_transaction(|connection| {
+            diesel::delete(webhook_deliveries::table.find(id)).execute(connection)
+        })
+        .await?;
+
+        Ok(())
+    }
+}
+
+#[derive(Insertable, Debug, Clone)]
+#[table_name = "webhook_deliveries"]
+struct InsertableWebhookDelivery {
+    url: Text<Url>,
+    payload: String,
+    attempts: i32,
+    next_attempt_at: NaiveDateTime,
+}
@@ -167,6 +167,38 @@ impl fmt::Display for BitcoinNetwork {
     }
 }
 
+/// A wrapper type for the position of a ledger within a swap.
+///
+/// Split protocols are stored per ledger, this records whether a protocol was
+/// used on the alpha or the beta ledger of a swap.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Ledger {
+    Alpha,
+    Beta,
+}
+
+impl FromStr for Ledger {
+    type Err = UnknownVariant;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "Alpha" => Ok(Self::Alpha),
+            "Beta" => Ok(Self::Beta),
+            _ => Err(UnknownVariant),
+        }
+    }
+}
+
+impl fmt::Display for Ledger {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let s = match self {
+            Self::Alpha => "Alpha",
+            Self::Beta => "Beta",
+        };
+        write!(f, "{}", s)
+    }
+}
+
 macro_rules! impl_from_for_bitcoinnetwork {
     ($ledger:ident) => {
         impl From<$ledger> for BitcoinNetwork {
@@ -192,3 +224,23 @@ impl From<BitcoinNetwork> for LedgerKind {
         }
     }
 }
+
+impl From<::bitcoin::Network> for BitcoinNetwork {
+    fn from(network: ::bitcoin::Network) -> Self {
+        match network {
+            ::bitcoin::Network::Bitcoin => BitcoinNetwork::Mainnet,
+            ::bitcoin::Network::Testnet => BitcoinNetwork::Testnet,
+            ::bitcoin::Network::Regtest => BitcoinNetwork::Regtest,
+        }
+    }
+}
+
+impl From<BitcoinNetwork> for ::bitcoin::Network {
+    fn from(network: BitcoinNetwork) -> Self {
+        match network {
+            BitcoinNetwork::Mainnet => ::bitcoin::Network::Bitcoin,
+            BitcoinNetwork::Testnet => ::bitcoin::Network::Testnet,
+            BitcoinNetwork::Regtest => ::bitcoin::Network::Regtest,
+        }
+    }
+}
@@ -148,6 +148,10 @@ impl Display for Hash {
 /// "Receipt" of an executed transaction: details of its execution.
 #[derive(Debug, Default, Clone, PartialEq, Deserialize)]
 pub struct TransactionReceipt {
+    /// Number of the block this transaction was included in, `None` if
+    /// pending.
+    #[serde(rename = "blockNumber")]
+    pub block_number: Option<U256>,
     /// Contract address created, or `None` if not a deployment.
     #[serde(rename = "contractAddress")]
     pub contract_address: Option<Address>,
@@ -165,7 +169,7 @@ impl TransactionReceipt {
 }
 
 /// Description of a Transaction, pending or in the chain.
-#[derive(Debug, Default, Clone, PartialEq, Deserialize)]
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
 pub struct Transaction {
     /// Hash
     pub hash: Hash,
@@ -186,6 +190,12 @@ pub struct Log {
     pub topics: Vec<Hash>,
     /// Data
     pub data: Bytes,
+    /// Hash of the block this log was emitted in, `None` if pending.
+    #[serde(rename = "blockHash")]
+    pub block_hash: Option<Hash>,
+    /// Hash of the transaction that emitted this log, `None` if pending.
+    #[serde(rename = "transactionHash")]
+    pub transaction_hash: Option<Hash>,
 }
 
 /// The block returned from RPC calls.
@@ -195,6 +205,8 @@ pub struct Log {
 pub struct Block {
     /// Hash of the block
     pub hash: Option<Hash>,
+    /// Number of the block, `None` if pending
+    pub number: Option<U256>,
     /// Hash of the parent
     #[serde(rename = "parentHash")]
     pub parent_hash: Hash,
@@ -0,0 +1,475 @@
+//! Keeps an eye on the expiries of the HTLCs of all active swaps.
+//!
+//! The expiry monitor periodically compares the expiry of every locked HTLC
+//! with the time of the latest block of its ledger, or with its height for
+//! lightning HTLCs which expire at a block height. It raises an alert when
+//! an HTLC we still have to redeem is about to expire and when an HTLC we
+//! funded can be refunded. Alerts are logged once when they are raised and
+//! are available through the HTTP API for as long as they apply.
+mod rfc003;
+mod split_protocols;
+
+use crate::{
+    btsieve::{BlockHeight, BlockTimestamp, LatestBlock},
+    config,
+    db::{self, LedgerKind},
+    swap_protocols::{Facade, Rfc003Facade, Role},
+    timestamp::Timestamp,
+};
+use serde::Serialize;
+use std::{collections::HashMap, convert::TryFrom, fmt, time::Duration};
+use tokio::sync::Mutex;
+use uuid::Uuid;
+
+/// How often we compare the expiries of all HTLCs with the ledger time.
+const POLL_INTERVAL: Duration = Duration::from_secs(60);
+
+/// The expected number of seconds between two bitcoin blocks, used to tell how
+/// much time is left until an HTLC expires at a block height.
+const BITCOIN_BLOCK_INTERVAL_SECS: u32 = 600;
+
+#[derive(Clone, Copy, Debug, PartialEq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Ledger {
+    Alpha,
+    Beta,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Serialize)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum AlertKind {
+    /// An HTLC we have to redeem expires soon.
+    RedeemExpiresSoon,
+    /// An HTLC we had to redeem expired, the counterparty can now refund it.
+    RedeemExpired,
+    /// An HTLC we funded expired and can be refunded.
+    Refundable,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Serialize)]
+pub struct Alert {
+    pub kind: AlertKind,
+    pub ledger: Ledger,
+    #[serde(flatten)]
+    pub expiry: Expiry,
+    /// The warning threshold that was crossed, only present for
+    /// `REDEEM_EXPIRES_SOON`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub threshold_minutes: Option<u32>,
+}
+
+impl fmt::Display for Alert {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let ledger = match self.ledger {
+            Ledger::Alpha => "alpha",
+            Ledger::Beta => "beta",
+        };
+
+        match (self.kind, self.threshold_minutes) {
+            (AlertKind::RedeemExpiresSoon, Some(minutes)) => write!(
+                f,
+                "{} HTLC expires in less than {} minutes and has not been redeemed",
+                ledger, minutes
+            ),
+            (AlertKind::RedeemExpiresSoon, None) => {
+                write!(f, "{} HTLC expires soon and has not been redeemed", ledger)
+            }
+            (AlertKind::RedeemExpired, _) => {
+                write!(f, "{} HTLC expired before it was redeemed", ledger)
+            }
+            (AlertKind::Refundable, _) => write!(f, "{} HTLC expired and can be refunded", ledger),
+        }
+    }
+}
+
+/// When an HTLC expires, serialized as `expiry` or `expiry_height`.
+#[derive(Clone, Copy, Debug, PartialEq, Serialize)]
+pub enum Expiry {
+    /// The block time at which an on-chain HTLC expires.
+    #[serde(rename = "expiry")]
+    Timestamp(Timestamp),
+    /// The bitcoin block height at which a lightning HTLC expires.
+    #[serde(rename = "expiry_height")]
+    BlockHeight(u32),
+}
+
+impl From<Timestamp> for Expiry {
+    fn from(timestamp: Timestamp) -> Self {
+        Expiry::Timestamp(timestamp)
+    }
+}
+
+/// The alerts that currently apply, per swap.
+#[derive(Default, Debug)]
+pub struct ExpiryAlerts(Mutex<HashMap<Uuid, Vec<Alert>>>);
+
+impl ExpiryAlerts {
+    pub async fn get(&self, swap_id: Uuid) -> Vec<Alert> {
+        self.0
+            .lock()
+            .await
+            .get(&swap_id)
+            .cloned()
+            .unwrap_or_default()
+    }
+
+    pub async fn all(&self) -> HashMap<Uuid, Vec<Alert>> {
+        self.0.lock().await.clone()
+    }
+
+    /// Replaces the alerts of a swap, returning the ones that were not raised
+    /// before.
+    async fn update(&self, swap_id: Uuid, alerts: Vec<Alert>) -> Vec<Alert> {
+        let mut all = self.0.lock().await;
+
+        let previous = if alerts.is_empty() {
+            all.remove(&swap_id)
+        } else {
+            all.insert(swap_id, alerts.clone())
+        }
+        .unwrap_or_default();
+
+        alerts
+            .into_iter()
+            .filter(|alert| !previous.contains(alert))
+            .collect()
+    }
+}
+
+/// The blockchain whose latest block applies to an HTLC.
+///
+/// Lightning HTLCs expire according to the Bitcoin block height.
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum Chain {
+    Bitcoin,
+    Ethereum,
+}
+
+impl From<LedgerKind> for Chain {
+    fn from(ledger: LedgerKind) -> Self {
+        match ledger {
+            LedgerKind::Bitcoin(_) => Chain::Bitcoin,
+            LedgerKind::Ethereum => Chain::Ethereum,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum Duty {
+    Redeem,
+    Refund,
+}
+
+/// Alice redeems on beta and refunds on alpha, Bob does the opposite.
+fn duty(role: Role, ledger: Ledger) -> Duty {
+    match (role, ledger) {
+        (Role::Alice, Ledger::Alpha) | (Role::Bob, Ledger::Beta) => Duty::Refund,
+        (Role::Alice, Ledger::Beta) | (Role::Bob, Ledger::Alpha) => Duty::Redeem,
+    }
+}
+
+/// What we need to know about an HTLC to tell whether it deserves an alert.
+#[derive(Clone, Copy, Debug)]
+struct Htlc {
+    ledger: Ledger,
+    chain: Chain,
+    expiry: Expiry,
+    /// Funded and neither redeemed nor refunded yet.
+    locked: bool,
+    duty: Duty,
+}
+
+impl Htlc {
+    fn new(
+        ledger: Ledger,
+        chain: Chain,
+        expiry: impl Into<Expiry>,
+        locked: bool,
+        role: Role,
+    ) -> Self {
+        Self {
+            ledger,
+            chain,
+            expiry: expiry.into(),
+            locked,
+            duty: duty(role, ledger),
+        }
+    }
+
+    fn alert(&self, tip: Tip, warn_before_expiry_minutes: &[u32]) -> Option<Alert> {
+        if !self.locked {
+            return None;
+        }
+
+        // Zero once the HTLC expired
+        let seconds_left = match self.expiry {
+            Expiry::Timestamp(expiry) => u32::from(expiry).saturating_sub(u32::from(tip.time)),
+            Expiry::BlockHeight(expiry) => expiry
+                .saturating_sub(tip.height?)
+                .saturating_mul(BITCOIN_BLOCK_INTERVAL_SECS),
+        };
+
+        let alert = |kind, threshold_minutes| Alert {
+            kind,
+            ledger: self.ledger,
+            expiry: self.expiry,
+            threshold_minutes,
+        };
+
+        if seconds_left == 0 {
+            return match self.duty {
+                Duty::Redeem => Some(alert(AlertKind::RedeemExpired, None)),
+                Duty::Refund => Some(alert(AlertKind::Refundable, None)),
+            };
+        }
+
+        if self.duty == Duty::Refund {
+            return None;
+        }
+
+        // The most urgent threshold we crossed
+        warn_before_expiry_minutes
+            .iter()
+            .copied()
+            .filter(|minutes| seconds_left <= minutes.saturating_mul(60))
+            .min()
+            .map(|minutes| alert(AlertKind::RedeemExpiresSoon, Some(minutes)))
+    }
+}
+
+/// The time and, if the block tells it, the height of the latest block of a
+/// ledger.
+#[derive(Clone, Copy, Debug)]
+struct Tip {
+    time: Timestamp,
+    height: Option<u32>,
+}
+
+/// The latest block of each ledger, `None` if we failed to fetch it.
+#[derive(Clone, Copy, Debug)]
+struct Clocks {
+    bitcoin: Option<Tip>,
+    ethereum: Option<Tip>,
+}
+
+impl Clocks {
+    async fn read(facade: &Rfc003Facade) -> Self {
+        Self {
+            bitcoin: latest_block(facade.bitcoin_connector.as_ref())
+                .await
+                .map_err(|e| tracing::warn!("failed to get latest bitcoin block: {:?}", e))
+                .ok(),
+            ethereum: latest_block(facade.ethereum_connector.as_ref())
+                .await
+                .map_err(|e| tracing::warn!("failed to get latest ethereum block: {:?}", e))
+                .ok(),
+        }
+    }
+
+    fn tip(&self, chain: Chain) -> Option<Tip> {
+        match chain {
+            Chain::Bitcoin => self.bitcoin,
+            Chain::Ethereum => self.ethereum,
+        }
+    }
+}
+
+async fn latest_block<C>(connector: &C) -> anyhow::Result<Tip>
+where
+    C: LatestBlock,
+    C::Block: BlockHeight + BlockTimestamp,
+{
+    let block = connector.latest_block().await?;
+    let timestamp = u32::try_from(block.block_timestamp().timestamp())?;
+
+    Ok(Tip {
+        time: Timestamp::from(timestamp),
+        height: block.block_height(),
+    })
+}
+
+/// Periodically checks the HTLCs of all swaps, forever.
+pub async fn run(settings: config::ExpiryMonitor, rfc003_facade: Rfc003Facade, facade: Facade) {
+    let warn_before_expiry_minutes = settings.warn_before_expiry_minutes;
+
+    loop {
+        let clocks = Clocks::read(&rfc003_facade).await;
+
+        match db::Retrieve::all(&rfc003_facade).await {
+            Ok(swaps) => {
+                for swap in swaps {
+                    let htlcs = rfc003::htlcs(&rfc003_facade, swap.swap_id).await;
+                    check_swap(
+                        &rfc003_facade.expiry_alerts,
+                        swap.swap_id.into(),
+                        htlcs,
+                        clocks,
+                        &warn_before_expiry_minutes,
+                    )
+                    .await;
+                }
+            }
+            Err(e) => tracing::warn!("expiry monitor failed to load rfc003 swaps: {:?}", e),
+        }
+
+        match facade.db.all_local_swap_ids().await {
+            Ok(swap_ids) => {
+                for swap_id in swap_ids {
+                    let htlcs = split_protocols::htlcs(&facade, swap_id).await;
+                    check_swap(
+                        &facade.expiry_alerts,
+                        swap_id.into(),
+                        htlcs,
+                        clocks,
+                        &warn_before_expiry_minutes,
+                    )
+                    .await;
+                }
+            }
+            Err(e) => tracing::warn!("expiry monitor failed to load swaps: {:?}", e),
+        }
+
+        tokio::time::delay_for(POLL_INTERVAL).await;
+    }
+}
+
+async fn check_swap(
+    expiry_alerts: &ExpiryAlerts,
+    swap_id: Uuid,
+    htlcs: anyhow::Result<Vec<Htlc>>,
+    clocks: Clocks,
+    warn_before_expiry_minutes: &[u32],
+) {
+    let htlcs = match htlcs {
+        Ok(htlcs) => htlcs,
+        Err(e) => {
+            tracing::warn!("expiry monitor failed to check swap {}: {:?}", swap_id, e);
+            return;
+        }
+    };
+
+    let alerts = htlcs
+        .iter()
+        .filter_map(|htlc| {
+            let tip = clocks.tip(htlc.chain)?;
+            htlc.alert(tip, warn_before_expiry_minutes)
+        })
+        .collect();
+
+    for alert in expiry_alerts.update(swap_id, alerts).await {
+        tracing::warn!("swap {}: {}", swap_id, alert);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const THRESHOLDS: [u32; 3] = [360, 60, 15];
+
+    fn htlc(ledger: Ledger, locked: bool, role: Role) -> Htlc {
+        Htlc::new(
+            ledger,
+            Chain::Bitcoin,
+            Timestamp::from(100_000),
+            locked,
+            role,
+        )
+    }
+
+    fn at(time: u32) -> Tip {
+        Tip {
+            time: Timestamp::from(time),
+            height: None,
+        }
+    }
+
+    fn minutes_before_expiry(minutes: u32) -> Tip {
+        at(100_000 - minutes * 60)
+    }
+
+    #[test]
+    fn no_alert_for_htlc_that_is_not_locked() {
+        let htlc = htlc(Ledger::Beta, false, Role::Alice);
+
+        assert_eq!(htlc.alert(minutes_before_expiry(1), &THRESHOLDS), None);
+        assert_eq!(htlc.alert(at(200_000), &THRESHOLDS), None);
+    }
+
+    #[test]
+    fn no_alert_before_first_threshold() {
+        let htlc = htlc(Ledger::Beta, true, Role::Alice);
+
+        assert_eq!(htlc.alert(minutes_before_expiry(361), &THRESHOLDS), None);
+    }
+
+    #[test]
+    fn redeem_alert_escalates_with_thresholds() {
+        let htlc = htlc(Ledger::Alpha, true, Role::Bob);
+
+        let threshold_at = |minutes| {
+            htlc.alert(minutes_before_expiry(minutes), &THRESHOLDS)
+                .and_then(|alert| alert.threshold_minutes)
+        };
+
+        assert_eq!(threshold_at(360), Some(360));
+        assert_eq!(threshold_at(61), Some(360));
+        assert_eq!(threshold_at(60), Some(60));
+        assert_eq!(threshold_at(1), Some(15));
+    }
+
+    #[test]
+    fn expired_redeem_htlc_raises_redeem_expired() {
+        let htlc = htlc(Ledger::Beta, true, Role::Alice);
+
+        assert_eq!(
+            htlc.alert(at(100_000), &THRESHOLDS),
+            Some(Alert {
+                kind: AlertKind::RedeemExpired,
+                ledger: Ledger::Beta,
+                expiry: Expiry::Timestamp(Timestamp::from(100_000)),
+                threshold_minutes: None,
+            })
+        );
+    }
+
+    #[test]
+    fn refund_htlc_only_alerts_once_expired() {
+        let htlc = htlc(Ledger::Alpha, true, Role::Alice);
+
+        assert_eq!(htlc.alert(minutes_before_expiry(1), &THRESHOLDS), None);
+        assert_eq!(
+            htlc.alert(at(100_001), &THRESHOLDS),
+            Some(Alert {
+                kind: AlertKind::Refundable,
+                ledger: Ledger::Alpha,
+                expiry: Expiry::Timestamp(Timestamp::from(100_000)),
+                threshold_minutes: None,
+            })
+        );
+    }
+
+    #[tokio::test]
+    async fn update_only_returns_newly_raised_alerts() {
+        let expiry_alerts = ExpiryAlerts::default();
+        let swap_id = Uuid::new_v4();
+        let htlc = htlc(Ledger::Beta, true, Role::Alice);
+        let alert_at = |minutes| {
+            htlc.alert(minutes_before_expiry(minutes), &THRESHOLDS)
+                .unwrap()
+        };
+
+        let raised = expiry_alerts.update(swap_id, vec![alert_at(100)]).await;
+        assert_eq!(raised, vec![alert_at(100)]);
+
+        let raised = expiry_alerts.update(swap_id, vec![alert_at(90)]).await;
+        assert_eq!(raised, vec![]);
+
+        let raised = expiry_alerts.update(swap_id, vec![alert_at(30)]).await;
+        assert_eq!(raised, vec![alert_at(30)]);
+
+        let raised = expiry_alerts.update(swap_id, vec![]).await;
+        assert_eq!(raised, vec![]);
+        assert_eq!(expiry_alerts.get(swap_id).await, vec![]);
+    }
+}
@@ -0,0 +1,65 @@
+use crate::{
+    db::DetermineTypes,
+    expiry_monitor::{Chain, Htlc, Ledger},
+    swap_protocols::{
+        rfc003::{ledger_state::HtlcState, state::Get, LedgerState, SwapCommunication, SwapId},
+        Rfc003Facade,
+    },
+};
+
+/// The HTLCs of an accepted swap whose ledger state is known.
+#[allow(clippy::cognitive_complexity)]
+pub async fn htlcs(dependencies: &Rfc003Facade, swap_id: SwapId) -> anyhow::Result<Vec<Htlc>> {
+    let types = dependencies.determine_types(&swap_id).await?;
+    let role = types.role;
+    let alpha_chain = Chain::from(types.alpha_ledger);
+    let beta_chain = Chain::from(types.beta_ledger);
+
+    with_swap_types!(types, {
+        let swap_communication: Option<SwapCommunication<AL, BL, AA, BA, AI, BI>> =
+            dependencies.get(&swap_id).await?;
+        let request = match swap_communication {
+            Some(SwapCommunication::Accepted { request, .. }) => request,
+            _ => return Ok(Vec::new()),
+        };
+
+        let alpha_ledger_state: Option<LedgerState<AA, AH, AT>> =
+            dependencies.alpha_ledger_states.get(&swap_id).await?;
+        let beta_ledger_state: Option<LedgerState<BA, BH, BT>> =
+            dependencies.beta_ledger_states.get(&swap_id).await?;
+
+        let mut htlcs = Vec::new();
+        if let Some(state) = alpha_ledger_state {
+            htlcs.push(Htlc::new(
+                Ledger::Alpha,
+                alpha_chain,
+                request.alpha_expiry,
+                is_locked(HtlcState::from(state)),
+                role,
+            ));
+        }
+        if let Some(state) = beta_ledger_state {
+            htlcs.push(Htlc::new(
+                Ledger::Beta,
+                beta_chain,
+                request.beta_expiry,
+                is_locked(HtlcState::from(state)),
+                role,
+            ));
+        }
+
+        Ok(htlcs)
+    })
+}
+
+/// Once a redeem transaction has been seen, the HTLC is as good as resolved.
+pub(super) fn is_locked(state: HtlcState) -> bool {
+    match state {
+        HtlcState::Funded | HtlcState::IncorrectlyFunded => true,
+        HtlcState::NotDeployed
+        | HtlcState::Deployed
+        | HtlcState::RedeemPending
+        | HtlcState::Redeemed
+        | HtlcState::Refunded => false,
+    }
+}
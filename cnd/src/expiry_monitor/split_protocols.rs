@@ -0,0 +1,373 @@
+use crate::{
+    expiry_monitor::{duty, rfc003::is_locked, Chain, Duty, Expiry, Htlc, Ledger},
+    http_api::routes::{load_swap_state, SwapState},
+    swap_protocols::{
+        halight, hbit, herc20, rfc003::ledger_state::HtlcState, Facade, LocalSwapId, Role,
+    },
+    timestamp::Timestamp,
+};
+
+/// The HTLCs of a finalized swap.
+pub async fn htlcs(facade: &Facade, swap_id: LocalSwapId) -> anyhow::Result<Vec<Htlc>> {
+    let state = match load_swap_state(facade, swap_id).await? {
+        Some(state) => state,
+        None => return Ok(Vec::new()),
+    };
+
+    let htlcs = match state {
+        SwapState::AliceHanEthereumHalightBitcoin(state) => vec![
+            han(
+                Ledger::Alpha,
+                state.finalized_swap.ethereum_expiry,
+                HtlcState::from(state.alpha_ledger_state),
+                Role::Alice,
+            ),
+            halight(
+                Ledger::Beta,
+                state.finalized_swap.lightning_expiry,
+                &state.beta_ledger_state,
+                Role::Alice,
+            ),
+        ],
+        SwapState::BobHanEthereumHalightBitcoin(state) => vec![
+            han(
+                Ledger::Alpha,
+                state.finalized_swap.ethereum_expiry,
+                HtlcState::from(state.alpha_ledger_state),
+                Role::Bob,
+            ),
+            halight(
+                Ledger::Beta,
+                state.finalized_swap.lightning_expiry,
+                &state.beta_ledger_state,
+                Role::Bob,
+            ),
+        ],
+        SwapState::AliceHerc20HalightBitcoin(state) => vec![
+            herc20(
+                Ledger::Alpha,
+                state.finalized_swap.ethereum_expiry,
+                &state.alpha_ledger_state,
+                Role::Alice,
+            ),
+            halight(
+                Ledger::Beta,
+                state.finalized_swap.lightning_expiry,
+                &state.beta_ledger_state,
+                Role::Alice,
+            ),
+        ],
+        SwapState::BobHerc20HalightBitcoin(state) => vec![
+            herc20(
+                Ledger::Alpha,
+                state.finalized_swap.ethereum_expiry,
+                &state.alpha_ledger_state,
+                Role::Bob,
+            ),
+            halight(
+                Ledger::Beta,
+                state.finalized_swap.lightning_expiry,
+                &state.beta_ledger_state,
+                Role::Bob,
+            ),
+        ],
+        SwapState::AliceHalightBitcoinHanEthereum(state) => vec![
+            halight(
+                Ledger::Alpha,
+                state.finalized_swap.lightning_expiry,
+                &state.alpha_ledger_state,
+                Role::Alice,
+            ),
+            han(
+                Ledger::Beta,
+                state.finalized_swap.ethereum_expiry,
+                HtlcState::from(state.beta_ledger_state),
+                Role::Alice,
+            ),
+        ],
+        SwapState::BobHalightBitcoinHanEthereum(state) => vec![
+            halight(
+                Ledger::Alpha,
+                state.finalized_swap.lightning_expiry,
+                &state.alpha_ledger_state,
+                Role::Bob,
+            ),
+            han(
+                Ledger::Beta,
+                state.finalized_swap.ethereum_expiry,
+                HtlcState::from(state.beta_ledger_state),
+                Role::Bob,
+            ),
+        ],
+        SwapState::AliceHalightBitcoinHerc20(state) => vec![
+            halight(
+                Ledger::Alpha,
+                state.finalized_swap.lightning_expiry,
+                &state.alpha_ledger_state,
+                Role::Alice,
+            ),
+            herc20(
+                Ledger::Beta,
+                state.finalized_swap.ethereum_expiry,
+                &state.beta_ledger_state,
+                Role::Alice,
+            ),
+        ],
+        SwapState::BobHalightBitcoinHerc20(state) => vec![
+            halight(
+                Ledger::Alpha,
+                state.finalized_swap.lightning_expiry,
+                &state.alpha_ledger_state,
+                Role::Bob,
+            ),
+            herc20(
+                Ledger::Beta,
+                state.finalized_swap.ethereum_expiry,
+                &state.beta_ledger_state,
+                Role::Bob,
+            ),
+        ],
+        SwapState::AliceHanEthereumHbitBitcoin(state) => vec![
+            han(
+                Ledger::Alpha,
+                state.finalized_swap.ethereum_expiry,
+                HtlcState::from(state.alpha_ledger_state),
+                Role::Alice,
+            ),
+            hbit(
+                Ledger::Beta,
+                state.finalized_swap.bitcoin_expiry,
+                &state.beta_ledger_state,
+                Role::Alice,
+            ),
+        ],
+        SwapState::BobHanEthereumHbitBitcoin(state) => vec![
+            han(
+                Ledger::Alpha,
+                state.finalized_swap.ethereum_expiry,
+                HtlcState::from(state.alpha_ledger_state),
+                Role::Bob,
+            ),
+            hbit(
+                Ledger::Beta,
+                state.finalized_swap.bitcoin_expiry,
+                &state.beta_ledger_state,
+                Role::Bob,
+            ),
+        ],
+        SwapState::AliceHerc20HbitBitcoin(state) => vec![
+            herc20(
+                Ledger::Alpha,
+                state.finalized_swap.ethereum_expiry,
+                &state.alpha_ledger_state,
+                Role::Alice,
+            ),
+            hbit(
+                Ledger::Beta,
+                state.finalized_swap.bitcoin_expiry,
+                &state.beta_ledger_state,
+                Role::Alice,
+            ),
+        ],
+        SwapState::BobHerc20HbitBitcoin(state) => vec![
+            herc20(
+                Ledger::Alpha,
+                state.finalized_swap.ethereum_expiry,
+                &state.alpha_ledger_state,
+                Role::Bob,
+            ),
+            hbit(
+                Ledger::Beta,
+                state.finalized_swap.bitcoin_expiry,
+                &state.beta_ledger_state,
+                Role::Bob,
+            ),
+        ],
+        SwapState::AliceHbitBitcoinHanEthereum(state) => vec![
+            hbit(
+                Ledger::Alpha,
+                state.finalized_swap.bitcoin_expiry,
+                &state.alpha_ledger_state,
+                Role::Alice,
+            ),
+            han(
+                Ledger::Beta,
+                state.finalized_swap.ethereum_expiry,
+                HtlcState::from(state.beta_ledger_state),
+                Role::Alice,
+            ),
+        ],
+        SwapState::BobHbitBitcoinHanEthereum(state) => vec![
+            hbit(
+                Ledger::Alpha,
+                state.finalized_swap.bitcoin_expiry,
+                &state.alpha_ledger_state,
+                Role::Bob,
+            ),
+            han(
+                Ledger::Beta,
+                state.finalized_swap.ethereum_expiry,
+                HtlcState::from(state.beta_ledger_state),
+                Role::Bob,
+            ),
+        ],
+        SwapState::AliceHbitBitcoinHerc20(state) => vec![
+            hbit(
+                Ledger::Alpha,
+                state.finalized_swap.bitcoin_expiry,
+                &state.alpha_ledger_state,
+                Role::Alice,
+            ),
+            herc20(
+                Ledger::Beta,
+                state.finalized_swap.ethereum_expiry,
+                &state.beta_ledger_state,
+                Role::Alice,
+            ),
+        ],
+        SwapState::BobHbitBitcoinHerc20(state) => vec![
+            hbit(
+                Ledger::Alpha,
+                state.finalized_swap.bitcoin_expiry,
+                &state.alpha_ledger_state,
+                Role::Bob,
+            ),
+            herc20(
+                Ledger::Beta,
+                state.finalized_swap.ethereum_expiry,
+                &state.beta_ledger_state,
+                Role::Bob,
+            ),
+        ],
+    };
+
+    Ok(htlcs.into_iter().flatten().collect())
+}
+
+fn han(ledger: Ledger, expiry: Timestamp, state: HtlcState, role: Role) -> Option<Htlc> {
+    Some(Htlc::new(
+        ledger,
+        Chain::Ethereum,
+        expiry,
+        is_locked(state),
+        role,
+    ))
+}
+
+fn herc20(ledger: Ledger, expiry: Timestamp, state: &herc20::State, role: Role) -> Option<Htlc> {
+    let locked = match state {
+        herc20::State::Funded { .. } | herc20::State::IncorrectlyFunded { .. } => true,
+        herc20::State::None
+        | herc20::State::Deployed { .. }
+        | herc20::State::Redeemed { .. }
+        | herc20::State::Refunded { .. } => false,
+    };
+
+    Some(Htlc::new(ledger, Chain::Ethereum, expiry, locked, role))
+}
+
+fn hbit(ledger: Ledger, expiry: Timestamp, state: &hbit::State, role: Role) -> Option<Htlc> {
+    let locked = match state {
+        hbit::State::Funded { .. } | hbit::State::IncorrectlyFunded { .. } => true,
+        hbit::State::None | hbit::State::Redeemed { .. } | hbit::State::Refunded { .. } => false,
+    };
+
+    Some(Htlc::new(ledger, Chain::Bitcoin, expiry, locked, role))
+}
+
+/// lnd cancels expired payments by itself, we only watch the invoices we have
+/// to settle.
+///
+/// The cltv expiry of an invoice is a delta, the HTLC paying it expires that
+/// many blocks after it was accepted.
+fn halight(
+    ledger: Ledger,
+    cltv_expiry: Timestamp,
+    state: &halight::State,
+    role: Role,
+) -> Option<Htlc> {
+    if duty(role, ledger) == Duty::Refund {
+        return None;
+    }
+
+    let accept_height = match state {
+        halight::State::Accepted(accepted) => accepted.accept_height?,
+        halight::State::None
+        | halight::State::Opened(_)
+        | halight::State::Settled(_)
+        | halight::State::Cancelled(_) => return None,
+    };
+    let expiry_height = accept_height.saturating_add(u32::from(cltv_expiry));
+
+    Some(Htlc::new(
+        ledger,
+        Chain::Bitcoin,
+        Expiry::BlockHeight(expiry_height),
+        true,
+        role,
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::expiry_monitor::{Alert, AlertKind, Tip};
+
+    const THRESHOLDS: [u32; 3] = [360, 60, 15];
+
+    fn accepted_at(height: u32) -> halight::State {
+        halight::State::Accepted(halight::Accepted {
+            accept_height: Some(height),
+        })
+    }
+
+    fn tip_at(height: u32) -> Tip {
+        Tip {
+            time: Timestamp::from(1_592_000_000),
+            height: Some(height),
+        }
+    }
+
+    #[test]
+    fn accepted_invoice_expires_cltv_delta_blocks_after_it_was_accepted() {
+        let htlc = halight(
+            Ledger::Beta,
+            Timestamp::from(350),
+            &accepted_at(650_000),
+            Role::Alice,
+        )
+        .unwrap();
+
+        assert_eq!(htlc.alert(tip_at(650_001), &THRESHOLDS), None);
+        assert_eq!(
+            htlc.alert(tip_at(650_314), &THRESHOLDS),
+            Some(Alert {
+                kind: AlertKind::RedeemExpiresSoon,
+                ledger: Ledger::Beta,
+                expiry: Expiry::BlockHeight(650_350),
+                threshold_minutes: Some(360),
+            })
+        );
+        assert_eq!(
+            htlc.alert(tip_at(650_350), &THRESHOLDS),
+            Some(Alert {
+                kind: AlertKind::RedeemExpired,
+                ledger: Ledger::Beta,
+                expiry: Expiry::BlockHeight(650_350),
+                threshold_minutes: None,
+            })
+        );
+    }
+
+    #[test]
+    fn invoice_we_pay_is_not_watched() {
+        let htlc = halight(
+            Ledger::Alpha,
+            Timestamp::from(350),
+            &accepted_at(650_000),
+            Role::Alice,
+        );
+
+        assert!(htlc.is_none());
+    }
+}
@@ -0,0 +1,330 @@
+//! Keeps an eye on the connections cnd depends on.
+//!
+//! At startup we only warn if a blockchain node is unreachable or connected to
+//! the wrong network, and halight is silently disabled if the lnd
+//! configuration cannot be read. The health checks periodically repeat the
+//! network checks, ask the nodes whether they are still syncing and whether
+//! we are listening for COMIT peers. The latest report is available through
+//! the HTTP API so that orchestrators only send traffic to a ready node.
+use crate::{
+    config::{
+        validation::{self, validate_blockchain_config, FetchNetworkId, FetchSyncState},
+        Settings,
+    },
+    network::ListenAddresses,
+    swap_protocols::{halight::LndConnectorParams, Rfc003Facade},
+    timestamp::Timestamp,
+};
+use serde::{Deserialize, Serialize};
+use std::{fmt::Debug, sync::Arc, time::Duration};
+use tokio::sync::Mutex;
+
+/// How often we check all connections.
+const POLL_INTERVAL: Duration = Duration::from_secs(30);
+
+#[derive(Clone, Copy, Debug, PartialEq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Status {
+    /// Not checked yet.
+    Unknown,
+    /// Reachable, on the configured network and in sync.
+    Ok,
+    /// Reachable and on the configured network, but still catching up.
+    Syncing,
+    WrongNetwork,
+    Unreachable,
+    /// Not used by cnd because its configuration could not be read.
+    Disabled,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct Check {
+    pub status: Status,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub detail: Option<String>,
+}
+
+impl Check {
+    fn new(status: Status, detail: impl ToString) -> Self {
+        Self {
+            status,
+            detail: Some(detail.to_string()),
+        }
+    }
+
+    fn status(status: Status) -> Self {
+        Self {
+            status,
+            detail: None,
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct Report {
+    /// Whether we can do swaps: the blockchain nodes are in sync, lnd is in
+    /// sync unless halight is disabled and we are listening for peers.
+    pub ready: bool,
+    pub bitcoin: Check,
+    pub ethereum: Check,
+    pub lightning: Check,
+    pub network: Check,
+    /// `None` until the checks ran for the first time.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub checked_at: Option<Timestamp>,
+}
+
+impl Default for Report {
+    fn default() -> Self {
+        Self::new(
+            Check::status(Status::Unknown),
+            Check::status(Status::Unknown),
+            Check::status(Status::Unknown),
+            Check::status(Status::Unknown),
+            None,
+        )
+    }
+}
+
+impl Report {
+    fn new(
+        bitcoin: Check,
+        ethereum: Check,
+        lightning: Check,
+        network: Check,
+        checked_at: Option<Timestamp>,
+    ) -> Self {
+        let ready = bitcoin.status == Status::Ok
+            && ethereum.status == Status::Ok
+            && (lightning.status == Status::Ok || lightning.status == Status::Disabled)
+            && network.status == Status::Ok;
+
+        Self {
+            ready,
+            bitcoin,
+            ethereum,
+            lightning,
+            network,
+            checked_at,
+        }
+    }
+}
+
+/// The report of the latest health checks.
+#[derive(Default, Debug)]
+pub struct Health(Mutex<Report>);
+
+impl Health {
+    pub async fn report(&self) -> Report {
+        self.0.lock().await.clone()
+    }
+
+    /// Replaces the report, logging the checks that started to fail.
+    async fn update(&self, report: Report) {
+        let mut current = self.0.lock().await;
+
+        for (name, previous, check) in &[
+            ("bitcoin", &current.bitcoin, &report.bitcoin),
+            ("ethereum", &current.ethereum, &report.ethereum),
+            ("lightning", &current.lightning, &report.lightning),
+            ("network", &current.network, &report.network),
+        ] {
+            if check.status != Status::Ok && check.status != previous.status {
+                tracing::warn!(
+                    "{} health check: {:?} {}",
+                    name,
+                    check.status,
+                    check.detail.as_deref().unwrap_or_default()
+                );
+            }
+        }
+
+        *current = report;
+    }
+}
+
+/// Periodically checks all connections, forever.
+pub async fn run(
+    health: Arc<Health>,
+    settings: Settings,
+    facade: Rfc003Facade,
+    lnd_connector_params: Option<LndConnectorParams>,
+) {
+    loop {
+        let bitcoin = check_ledger(
+            &facade.bitcoin_connector.connector,
+            settings.bitcoin.network,
+        )
+        .await;
+        let ethereum = check_ledger(
+            &facade.ethereum_connector.connector,
+            settings.ethereum.chain_id,
+        )
+        .await;
+        let lightning = check_lnd(lnd_connector_params.as_ref(), settings.lightning.network).await;
+        let network = check_network(&facade).await;
+
+        health
+            .update(Report::new(
+                bitcoin,
+                ethereum,
+                lightning,
+                network,
+                Some(Timestamp::now()),
+            ))
+            .await;
+
+        tokio::time::delay_for(POLL_INTERVAL).await;
+    }
+}
+
+async fn check_ledger<C, N>(connector: &C, network: N) -> Check
+where
+    C: FetchNetworkId<N> + FetchSyncState,
+    N: PartialEq + Debug + Send + Sync + 'static,
+{
+    if let Err(e) = validate_blockchain_config(connector, network).await {
+        return match e.downcast_ref::<validation::Error<N>>() {
+            Some(wrong_network) => Check::new(Status::WrongNetwork, wrong_network),
+            None => Check::new(Status::Unreachable, e),
+        };
+    }
+
+    match connector.is_syncing().await {
+        Ok(false) => Check::status(Status::Ok),
+        Ok(true) => Check::status(Status::Syncing),
+        Err(e) => Check::new(Status::Unreachable, e),
+    }
+}
+
+/// Response of lnd's `GET /v1/getinfo`, lnd omits fields that are `false`.
+#[derive(Debug, Deserialize)]
+struct LndInfo {
+    #[serde(default)]
+    synced_to_chain: bool,
+    #[serde(default)]
+    chains: Vec<LndChain>,
+}
+
+#[derive(Debug, Deserialize)]
+struct LndChain {
+    chain: String,
+    network: String,
+}
+
+async fn lnd_info(params: &LndConnectorParams) -> anyhow::Result<LndInfo> {
+    let info = params
+        .client()?
+        .get(params.url().join("/v1/getinfo")?)
+        .send()
+        .await?
+        .error_for_status()?
+        .json::<LndInfo>()
+        .await?;
+
+    Ok(info)
+}
+
+async fn check_lnd(params: Option<&LndConnectorParams>, network: bitcoin::Network) -> Check {
+    let params = match params {
+        Some(params) => params,
+        None => {
+            return Check::new(
+                Status::Disabled,
+                "failed to read the lnd certificate or macaroon at startup",
+            )
+        }
+    };
+
+    let info = match lnd_info(params).await {
+        Ok(info) => info,
+        Err(e) => return Check::new(Status::Unreachable, e),
+    };
+
+    let network = match network {
+        bitcoin::Network::Bitcoin => "mainnet",
+        bitcoin::Network::Testnet => "testnet",
+        bitcoin::Network::Regtest => "regtest",
+    };
+    if !info
+        .chains
+        .iter()
+        .any(|chain| chain.chain == "bitcoin" && chain.network == network)
+    {
+        return Check::new(
+            Status::WrongNetwork,
+            format!("lnd is not connected to bitcoin {}", network),
+        );
+    }
+
+    if info.synced_to_chain {
+        Check::status(Status::Ok)
+    } else {
+        Check::status(Status::Syncing)
+    }
+}
+
+async fn check_network<L>(swarm: &L) -> Check
+where
+    L: ListenAddresses,
+{
+    if swarm.listen_addresses().await.is_empty() {
+        Check::new(Status::Unreachable, "not listening on any address")
+    } else {
+        Check::status(Status::Ok)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn ready_if_all_checks_pass_and_lnd_is_disabled() {
+        let ok = || Check::status(Status::Ok);
+
+        let report = Report::new(
+            ok(),
+            ok(),
+            Check::status(Status::Disabled),
+            ok(),
+            Some(Timestamp::now()),
+        );
+
+        assert!(report.ready);
+    }
+
+    #[test]
+    fn not_ready_if_a_node_is_syncing() {
+        let ok = || Check::status(Status::Ok);
+
+        let report = Report::new(
+            Check::status(Status::Syncing),
+            ok(),
+            ok(),
+            ok(),
+            Some(Timestamp::now()),
+        );
+
+        assert!(!report.ready);
+    }
+
+    #[test]
+    fn not_ready_before_the_first_check() {
+        assert!(!Report::default().ready);
+    }
+
+    #[test]
+    fn deserializes_lnd_info() {
+        let info = r#"{
+            "identity_pubkey": "02a4a3ba6e5a8a1d1e0f4a5c0a6b3c1e5f9b8c2d1a0e9f8b7c6d5e4f3a2b1c0d9e",
+            "synced_to_chain": true,
+            "chains": [{ "chain": "bitcoin", "network": "regtest" }]
+        }"#;
+
+        let info = serde_json::from_str::<LndInfo>(info).unwrap();
+
+        assert!(info.synced_to_chain);
+        assert_eq!(info.chains[0].network, "regtest");
+    }
+}
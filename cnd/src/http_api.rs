@@ -3,8 +3,10 @@ pub mod routes;
 #[macro_use]
 pub mod impl_serialize_http;
 pub mod action;
+pub mod auth;
 mod problem;
 mod swap_resource;
+pub mod tls;
 
 pub use self::{
     problem::*,
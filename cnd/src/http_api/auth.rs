@@ -0,0 +1,275 @@
+//! Optional bearer token authentication of the HTTP API.
+//!
+//! Two tokens are generated into the data directory on the first start: one
+//! that can only read and one that can also create swaps and execute actions.
+//! Clients present a token in the `Authorization: Bearer <token>` header.
+use http_api_problem::HttpApiProblem;
+use rand::RngCore;
+use std::{
+    fs::{self, OpenOptions},
+    io::Write,
+    path::{Path, PathBuf},
+    sync::Arc,
+};
+use warp::{filters::BoxedFilter, http::StatusCode, Filter, Rejection};
+
+const TOKEN_LENGTH: usize = 32;
+
+/// What a client is allowed to do, every scope includes the ones before it.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Scope {
+    /// Read swaps, peers, alerts and events.
+    Read,
+    /// Additionally create swaps and execute their actions.
+    Write,
+}
+
+impl Scope {
+    fn file_name(self) -> &'static str {
+        match self {
+            Scope::Read => "read.token",
+            Scope::Write => "write.token",
+        }
+    }
+}
+
+#[derive(Clone, PartialEq)]
+pub struct Tokens {
+    read: String,
+    write: String,
+}
+
+impl std::fmt::Debug for Tokens {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Tokens([*****])")
+    }
+}
+
+impl Tokens {
+    /// Reads the tokens from `<dir>/api_tokens`, generating the ones that do
+    /// not exist yet.
+    pub fn from_dir_or_generate<R>(dir: &Path, mut rng: R) -> anyhow::Result<Self>
+    where
+        R: RngCore,
+    {
+        let dir = tokens_dir(dir);
+        fs::create_dir_all(&dir)?;
+
+        Ok(Tokens {
+            read: read_or_generate(&dir, Scope::Read, &mut rng)?,
+            write: read_or_generate(&dir, Scope::Write, &mut rng)?,
+        })
+    }
+
+    fn scope_of(&self, token: &str) -> Option<Scope> {
+        if constant_time_eq(token.as_bytes(), self.write.as_bytes()) {
+            Some(Scope::Write)
+        } else if constant_time_eq(token.as_bytes(), self.read.as_bytes()) {
+            Some(Scope::Read)
+        } else {
+            None
+        }
+    }
+}
+
+fn tokens_dir(data_dir: &Path) -> PathBuf {
+    data_dir.join("api_tokens")
+}
+
+fn read_or_generate<R>(dir: &Path, scope: Scope, rng: &mut R) -> anyhow::Result<String>
+where
+    R: RngCore,
+{
+    let path = dir.join(scope.file_name());
+
+    if path.exists() {
+        let token = fs::read_to_string(&path)?.trim().to_owned();
+        anyhow::ensure!(!token.is_empty(), "token file {} is empty", path.display());
+
+        return Ok(token);
+    }
+
+    let mut bytes = [0u8; TOKEN_LENGTH];
+    rng.try_fill_bytes(&mut bytes)?;
+    let token = hex::encode(bytes);
+
+    let mut options = OpenOptions::new();
+    options.write(true).create_new(true);
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::OpenOptionsExt;
+        options.mode(0o600);
+    }
+    let mut file = options.open(&path)?;
+    file.write_all(token.as_bytes())?;
+
+    tracing::info!(
+        "No {:?} API token found, created one at: {}",
+        scope,
+        path.display()
+    );
+
+    Ok(token)
+}
+
+/// Compares two byte strings without leaking the position of the first
+/// difference through the time it takes.
+fn constant_time_eq(left: &[u8], right: &[u8]) -> bool {
+    if left.len() != right.len() {
+        return false;
+    }
+
+    left.iter()
+        .zip(right.iter())
+        .fold(0u8, |acc, (left, right)| acc | (left ^ right))
+        == 0
+}
+
+/// Rejects requests that do not carry a token of at least the `required`
+/// scope, lets all requests pass if authentication is disabled.
+pub fn authorize(tokens: Option<Arc<Tokens>>, required: Scope) -> BoxedFilter<()> {
+    warp::header::optional::<String>("authorization")
+        .and_then(move |header: Option<String>| {
+            let tokens = tokens.clone();
+
+            async move { check(tokens.as_deref(), header.as_deref(), required) }
+        })
+        .untuple_one()
+        .boxed()
+}
+
+fn check(tokens: Option<&Tokens>, header: Option<&str>, required: Scope) -> Result<(), Rejection> {
+    let tokens = match tokens {
+        Some(tokens) => tokens,
+        None => return Ok(()),
+    };
+
+    let token = header.and_then(|header| {
+        let mut parts = header.splitn(2, ' ');
+        match (parts.next(), parts.next()) {
+            (Some("Bearer"), Some(token)) => Some(token.trim()),
+            _ => None,
+        }
+    });
+
+    match token.and_then(|token| tokens.scope_of(token)) {
+        Some(scope) if scope >= required => Ok(()),
+        Some(_) => Err(forbidden()),
+        None => Err(unauthorized()),
+    }
+}
+
+fn unauthorized() -> Rejection {
+    warp::reject::custom(
+        HttpApiProblem::new("Missing or invalid API token.")
+            .set_status(StatusCode::UNAUTHORIZED)
+            .set_detail("Provide an API token in the `Authorization: Bearer <token>` header."),
+    )
+}
+
+fn forbidden() -> Rejection {
+    warp::reject::custom(
+        HttpApiProblem::new("Insufficient scope.")
+            .set_status(StatusCode::FORBIDDEN)
+            .set_detail("This request requires the write API token."),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::rngs::OsRng;
+
+    fn tokens() -> Arc<Tokens> {
+        Arc::new(Tokens {
+            read: String::from("read"),
+            write: String::from("write"),
+        })
+    }
+
+    async fn status(tokens: Option<Arc<Tokens>>, required: Scope, header: Option<&str>) -> u16 {
+        let filter = authorize(tokens, required)
+            .map(warp::reply)
+            .recover(crate::http_api::unpack_problem);
+
+        let mut request = warp::test::request();
+        if let Some(header) = header {
+            request = request.header("authorization", header);
+        }
+
+        request.reply(&filter).await.status().as_u16()
+    }
+
+    #[test]
+    fn tokens_are_generated_once_and_read_back() {
+        let dir = tempfile::tempdir().unwrap();
+
+        let generated = Tokens::from_dir_or_generate(dir.path(), OsRng).unwrap();
+        let read_back = Tokens::from_dir_or_generate(dir.path(), OsRng).unwrap();
+
+        assert_eq!(generated, read_back);
+        assert_ne!(generated.read, generated.write);
+        assert_eq!(generated.read.len(), 2 * TOKEN_LENGTH);
+    }
+
+    #[tokio::test]
+    async fn requests_pass_if_authentication_is_disabled() {
+        assert_eq!(status(None, Scope::Write, None).await, 200);
+    }
+
+    #[tokio::test]
+    async fn requests_without_valid_token_are_unauthorized() {
+        assert_eq!(status(Some(tokens()), Scope::Read, None).await, 401);
+        assert_eq!(
+            status(Some(tokens()), Scope::Read, Some("Bearer wrong")).await,
+            401
+        );
+        assert_eq!(status(Some(tokens()), Scope::Read, Some("read")).await, 401);
+    }
+
+    #[tokio::test]
+    async fn read_token_cannot_write() {
+        assert_eq!(
+            status(Some(tokens()), Scope::Read, Some("Bearer read")).await,
+            200
+        );
+        assert_eq!(
+            status(Some(tokens()), Scope::Write, Some("Bearer read")).await,
+            403
+        );
+    }
+
+    #[tokio::test]
+    async fn write_token_can_read_and_write() {
+        assert_eq!(
+            status(Some(tokens()), Scope::Read, Some("Bearer write")).await,
+            200
+        );
+        assert_eq!(
+            status(Some(tokens()), Scope::Write, Some("Bearer write")).await,
+            200
+        );
+    }
+
+    /// Mirrors the routes of `route_factory`, which authorize before parsing
+    /// the body.
+    #[tokio::test]
+    async fn unauthorized_requests_are_rejected_before_parsing_their_body() {
+        let filter = warp::post()
+            .and(authorize(Some(tokens()), Scope::Write))
+            .and(warp::body::json::<serde_json::Value>())
+            .map(|_| warp::reply())
+            .recover(crate::http_api::unpack_problem);
+
+        let status = warp::test::request()
+            .method("POST")
+            .header("content-type", "application/json")
+            .body("not json")
+            .reply(&filter)
+            .await
+            .status()
+            .as_u16();
+
+        assert_eq!(status, 401);
+    }
+}
@@ -1,9 +1,14 @@
 use crate::{
     config::settings::AllowedOrigins,
-    http_api,
+    health::Health,
+    http_api::{
+        self,
+        auth::{self, Scope, Tokens},
+    },
     network::LocalPeerId,
     swap_protocols::{self, rfc003::SwapId, Facade, LocalSwapId, Rfc003Facade},
 };
+use std::sync::Arc;
 use warp::{self, filters::BoxedFilter, Filter, Reply};
 
 pub const RFC003: &str = "rfc003";
@@ -24,6 +29,8 @@ pub fn create(
     rfc003_facade: Rfc003Facade,
     facade: Facade,
     allowed_origins: &AllowedOrigins,
+    tokens: Option<Arc<Tokens>>,
+    health: Arc<Health>,
 ) -> BoxedFilter<(impl Reply,)> {
     let peer_id = rfc003_facade.local_peer_id();
     let swaps = warp::path(http_api::PATH);
@@ -32,10 +39,16 @@ pub fn create(
     let empty_json_body = warp::any().map(|| serde_json::json!({}));
     let rfc003_facade = warp::any().map(move || rfc003_facade.clone());
     let facade = warp::any().map(move || facade.clone());
+    let health = warp::any().map(move || health.clone());
+    // Authorization is checked right after matching the path, before query
+    // strings or bodies are parsed, so that unauthorized requests are rejected
+    // regardless of what they carry.
+    let read = auth::authorize(tokens.clone(), Scope::Read);
+    let write = auth::authorize(tokens, Scope::Write);
 
     let cors = warp::cors()
         .allow_methods(vec!["GET", "POST"])
-        .allow_header("content-type");
+        .allow_headers(vec!["content-type", "authorization"]);
     let cors = match allowed_origins {
         AllowedOrigins::None => cors.allow_origins(Vec::<&str>::new()),
         AllowedOrigins::All => cors.allow_any_origin(),
@@ -47,8 +60,9 @@ pub fn create(
     let preflight_cors_route = warp::options().map(warp::reply);
 
     let rfc003_post_swap = rfc003
-        .and(warp::path::end())
         .and(warp::post())
+        .and(warp::path::end())
+        .and(write.clone())
         .and(rfc003_facade.clone())
         .and(warp::body::json())
         .and_then(http_api::routes::rfc003::post_swap);
@@ -58,13 +72,45 @@ pub fn create(
         .and(rfc003_facade.clone())
         .and(warp::path::param())
         .and(warp::path::end())
+        .and(read.clone())
         .and_then(http_api::routes::rfc003::get_swap);
 
     let get_swaps = swaps
         .and(warp::get())
         .and(warp::path::end())
+        .and(read.clone())
+        .and(warp::query::<http_api::routes::swaps::GetSwapsQuery>())
+        .and(rfc003_facade.clone())
+        .and(facade.clone())
+        .and_then(http_api::routes::swaps::get_swaps);
+
+    let get_events = warp::get()
+        .and(warp::path("events"))
+        .and(warp::path::end())
+        .and(read.clone())
+        .and(rfc003_facade.clone())
+        .and(facade.clone())
+        .and_then(http_api::routes::events::get_events);
+
+    let get_rfc003_swap_events = rfc003
+        .and(warp::get())
+        .and(warp::path::param::<SwapId>())
+        .and(warp::path("events"))
+        .and(warp::path::end())
+        .and(read.clone())
+        .and(rfc003_facade.clone())
+        .and(facade.clone())
+        .and_then(http_api::routes::events::get_rfc003_swap_events);
+
+    let get_swap_events = swaps
+        .and(warp::get())
+        .and(warp::path::param::<LocalSwapId>())
+        .and(warp::path("events"))
+        .and(warp::path::end())
+        .and(read.clone())
         .and(rfc003_facade.clone())
-        .and_then(http_api::routes::rfc003::get_swaps);
+        .and(facade.clone())
+        .and_then(http_api::routes::events::get_swap_events);
 
     let rfc003_action = warp::method()
         .and(rfc003)
@@ -73,6 +119,7 @@ pub fn create(
             swap_protocols::rfc003::actions::ActionKind,
         >())
         .and(warp::path::end())
+        .and(write.clone())
         .and(warp::query::<http_api::action::ActionExecutionParameters>())
         .and(rfc003_facade.clone())
         .and(warp::body::json().or(empty_json_body).unify())
@@ -81,11 +128,28 @@ pub fn create(
     let get_peers = warp::get()
         .and(warp::path("peers"))
         .and(warp::path::end())
+        .and(read.clone())
         .and(rfc003_facade.clone())
         .and_then(http_api::routes::peers::get_peers);
 
+    let get_alerts = warp::get()
+        .and(warp::path("alerts"))
+        .and(warp::path::end())
+        .and(read.clone())
+        .and(facade.clone())
+        .and_then(http_api::routes::alerts::get_alerts);
+
+    // Not authenticated, orchestrators poll it to decide whether to send
+    // traffic to us.
+    let get_health = warp::get()
+        .and(warp::path("health"))
+        .and(warp::path::end())
+        .and(health)
+        .and_then(http_api::routes::health::get_health);
+
     let get_info_siren = warp::get()
         .and(warp::path::end())
+        .and(read.clone())
         .and(warp::header::exact("accept", "application/vnd.siren+json"))
         .and(peer_id.clone())
         .and(rfc003_facade.clone())
@@ -93,6 +157,7 @@ pub fn create(
 
     let get_info = warp::get()
         .and(warp::path::end())
+        .and(read.clone())
         .and(peer_id)
         .and(rfc003_facade)
         .and_then(http_api::routes::index::get_info);
@@ -102,6 +167,7 @@ pub fn create(
             "swaps" / "han" / "ethereum" / "ether" / "halight" / "lightning" / "bitcoin"
         ))
         .and(warp::path::end())
+        .and(write.clone())
         .and(warp::body::json())
         .and(facade.clone())
         .and_then(http_api::routes::index::post_han_ethereum_halight_bitcoin);
@@ -111,6 +177,7 @@ pub fn create(
             "swaps" / "herc20" / "ethereum" / "erc20" / "halight" / "lightning" / "bitcoin"
         ))
         .and(warp::path::end())
+        .and(write.clone())
         .and(warp::body::json())
         .and(facade.clone())
         .and_then(http_api::routes::index::post_herc20_halight_bitcoin);
@@ -120,6 +187,7 @@ pub fn create(
             "swaps" / "halight" / "lightning" / "bitcoin" / "han" / "ethereum" / "ether"
         ))
         .and(warp::path::end())
+        .and(write.clone())
         .and(warp::body::json())
         .and(facade.clone())
         .and_then(http_api::routes::index::post_halight_bitcoin_han_ether);
@@ -129,14 +197,56 @@ pub fn create(
             "swaps" / "halight" / "lightning" / "bitcoin" / "herc20" / "ethereum" / "erc20"
         ))
         .and(warp::path::end())
+        .and(write.clone())
         .and(warp::body::json())
         .and(facade.clone())
         .and_then(http_api::routes::index::post_halight_bitcoin_herc20);
 
+    let han_ethereum_hbit_bitcoin = warp::post()
+        .and(warp::path!(
+            "swaps" / "han" / "ethereum" / "ether" / "hbit" / "bitcoin" / "bitcoin"
+        ))
+        .and(warp::path::end())
+        .and(write.clone())
+        .and(warp::body::json())
+        .and(facade.clone())
+        .and_then(http_api::routes::index::post_han_ethereum_hbit_bitcoin);
+
+    let herc20_hbit_bitcoin = warp::post()
+        .and(warp::path!(
+            "swaps" / "herc20" / "ethereum" / "erc20" / "hbit" / "bitcoin" / "bitcoin"
+        ))
+        .and(warp::path::end())
+        .and(write.clone())
+        .and(warp::body::json())
+        .and(facade.clone())
+        .and_then(http_api::routes::index::post_herc20_hbit_bitcoin);
+
+    let hbit_bitcoin_han_ether = warp::post()
+        .and(warp::path!(
+            "swaps" / "hbit" / "bitcoin" / "bitcoin" / "han" / "ethereum" / "ether"
+        ))
+        .and(warp::path::end())
+        .and(write.clone())
+        .and(warp::body::json())
+        .and(facade.clone())
+        .and_then(http_api::routes::index::post_hbit_bitcoin_han_ether);
+
+    let hbit_bitcoin_herc20 = warp::post()
+        .and(warp::path!(
+            "swaps" / "hbit" / "bitcoin" / "bitcoin" / "herc20" / "ethereum" / "erc20"
+        ))
+        .and(warp::path::end())
+        .and(write.clone())
+        .and(warp::body::json())
+        .and(facade.clone())
+        .and_then(http_api::routes::index::post_hbit_bitcoin_herc20);
+
     let get_halight_swap = swaps
         .and(warp::get())
         .and(warp::path::param())
         .and(warp::path::end())
+        .and(read)
         .and(facade.clone())
         .and_then(http_api::routes::get_halight_swap);
 
@@ -145,14 +255,25 @@ pub fn create(
         .and(warp::path::param::<LocalSwapId>())
         .and(warp::path("init"))
         .and(warp::path::end())
+        .and(write.clone())
         .and(facade.clone())
         .and_then(http_api::routes::action_init);
 
+    let lightning_action_deploy = swaps
+        .and(warp::get())
+        .and(warp::path::param::<LocalSwapId>())
+        .and(warp::path("deploy"))
+        .and(warp::path::end())
+        .and(write.clone())
+        .and(facade.clone())
+        .and_then(http_api::routes::action_deploy);
+
     let lightning_action_fund = swaps
         .and(warp::get())
         .and(warp::path::param::<LocalSwapId>())
         .and(warp::path("fund"))
         .and(warp::path::end())
+        .and(write.clone())
         .and(facade.clone())
         .and_then(http_api::routes::action_fund);
 
@@ -161,6 +282,8 @@ pub fn create(
         .and(warp::path::param::<LocalSwapId>())
         .and(warp::path("redeem"))
         .and(warp::path::end())
+        .and(write.clone())
+        .and(warp::query::<http_api::action::ActionExecutionParameters>())
         .and(facade.clone())
         .and_then(http_api::routes::action_redeem);
 
@@ -169,23 +292,35 @@ pub fn create(
         .and(warp::path::param::<LocalSwapId>())
         .and(warp::path("refund"))
         .and(warp::path::end())
+        .and(write)
+        .and(warp::query::<http_api::action::ActionExecutionParameters>())
         .and(facade)
         .and_then(http_api::routes::action_refund);
 
     preflight_cors_route
         .or(rfc003_get_swap)
         .or(rfc003_post_swap)
+        .or(get_events)
+        .or(get_rfc003_swap_events)
+        .or(get_swap_events)
         .or(rfc003_action)
         .or(get_swaps)
         .or(get_peers)
+        .or(get_alerts)
+        .or(get_health)
         .or(get_info_siren)
         .or(get_info)
         .or(han_ethereum_halight_bitcoin)
         .or(herc20_halight_bitcoin)
         .or(halight_bitcoin_han_ether)
         .or(halight_bitcoin_herc20)
+        .or(han_ethereum_hbit_bitcoin)
+        .or(herc20_hbit_bitcoin)
+        .or(hbit_bitcoin_han_ether)
+        .or(hbit_bitcoin_herc20)
         .or(get_halight_swap)
         .or(lightning_action_init)
+        .or(lightning_action_deploy)
         .or(lightning_action_fund)
         .or(lightning_action_redeem)
         .or(lightning_action_refund)
@@ -1,27 +1,46 @@
+pub mod alerts;
+pub mod events;
+pub mod health;
 pub mod index;
 pub mod peers;
 pub mod rfc003;
+pub mod swaps;
 
 use crate::{
-    asset,
+    asset, btsieve, db,
     ethereum::Bytes,
+    expiry_monitor::Alert,
     htlc_location,
-    http_api::{action::ActionResponseBody, problem, route_factory, Http},
+    http_api::{
+        action::{
+            ActionExecutionParameters, ActionResponseBody, IntoResponsePayload, ListRequiredFields,
+        },
+        problem, route_factory, Http,
+    },
+    identity,
     network::comit_ln,
     swap_protocols::{
         actions::{
+            bitcoin::{SendToAddress, SpendOutput},
             ethereum,
             lnd::{self, Chain},
         },
         halight::{self, Settled},
-        ledger::ethereum::ChainId,
-        rfc003::{ledger_state::HtlcState, LedgerState},
+        hbit, herc20,
+        ledger::{ethereum::ChainId, Ethereum},
+        rfc003::{
+            actions::erc20, create_swap::HtlcParams, ledger_state::HtlcState, LedgerState, Secret,
+        },
         state::Get,
-        Facade, FundAction, InitAction, LocalSwapId, RedeemAction, RefundAction, Role,
+        AlphaLedger, DeployAction, Facade, FundAction, InitAction, LocalSwapId, RedeemAction,
+        RefundAction, Role,
     },
+    timestamp::Timestamp,
     transaction,
 };
-use blockchain_contracts::ethereum::rfc003::ether_htlc::EtherHtlc;
+use blockchain_contracts::{
+    bitcoin::witness::PrimedInput, ethereum::rfc003::ether_htlc::EtherHtlc,
+};
 use http_api_problem::HttpApiProblem;
 use serde::Serialize;
 use std::collections::HashMap;
@@ -47,80 +66,499 @@ pub async fn handle_get_halight_swap(
     facade: Facade,
     swap_id: LocalSwapId,
 ) -> anyhow::Result<siren::Entity> {
-    let alpha_ledger_state: Option<
-        LedgerState<asset::Ether, htlc_location::Ethereum, transaction::Ethereum>,
-    > = facade.alpha_ledger_states.get(&swap_id).await?;
-
-    let beta_ledger_state = facade.beta_ledger_states.get(&swap_id).await?;
+    let state = match load_swap_state(&facade, swap_id).await? {
+        Some(state) => state,
+        None => {
+            let empty_swap = siren::Entity::default().with_class_member("swaps");
 
-    let finalized_swap = facade.get_finalized_swap(swap_id).await;
-
-    let (alpha_ledger_state, beta_ledger_state, finalized_swap) =
-        match (alpha_ledger_state, beta_ledger_state, finalized_swap) {
-            (Some(alpha_ledger_state), Some(beta_ledger_state), Some(finalized_swap)) => {
-                (alpha_ledger_state, beta_ledger_state, finalized_swap)
-            }
-            _ => {
-                let empty_swap = siren::Entity::default().with_class_member("swaps");
+            tracing::debug!("returning empty siren document because states are not yet completed");
 
-                tracing::debug!(
-                    "returning empty siren document because states are not yet completed"
-                );
+            return Ok(empty_swap);
+        }
+    };
 
-                return Ok(empty_swap);
-            }
-        };
+    let protocol = state.protocol();
 
-    match finalized_swap.role {
-        Role::Alice => {
-            let state = AliceHanEthereumHalightBitcoinState {
-                alpha_ledger_state,
-                beta_ledger_state,
-                finalized_swap,
-            };
+    make_swap_state_entity(&facade, swap_id, protocol, state).await
+}
 
+/// Builds the siren entity of a finalized split protocol swap.
+pub(crate) async fn make_swap_state_entity(
+    facade: &Facade,
+    swap_id: LocalSwapId,
+    protocol: &'static str,
+    state: SwapState,
+) -> anyhow::Result<siren::Entity> {
+    match state {
+        SwapState::AliceHanEthereumHalightBitcoin(state) => {
             let maybe_action_names = vec![
                 state.init_action().map(|_| "init"),
                 state.fund_action().map(|_| "fund"),
                 state.redeem_action().map(|_| "redeem"),
                 state.refund_action().map(|_| "refund"),
             ];
-            make_swap_entity(swap_id, state, maybe_action_names)
+            make_swap_entity(facade, swap_id, protocol, state, maybe_action_names).await
         }
-        Role::Bob => {
-            let state = BobHanEthereumHalightBitcoinState {
-                alpha_ledger_state,
-                beta_ledger_state,
-                finalized_swap,
-            };
-
+        SwapState::BobHanEthereumHalightBitcoin(state) => {
             // Bob cannot init and refund in this swap combination
             let maybe_action_names = vec![
                 state.fund_action().map(|_| "fund"),
                 state.redeem_action().map(|_| "redeem"),
             ];
-            make_swap_entity(swap_id, state, maybe_action_names)
+            make_swap_entity(facade, swap_id, protocol, state, maybe_action_names).await
+        }
+        SwapState::AliceHerc20HalightBitcoin(state) => {
+            let maybe_action_names = vec![
+                state.init_action().map(|_| "init"),
+                state.deploy_action().map(|_| "deploy"),
+                state.fund_action().map(|_| "fund"),
+                state.redeem_action().map(|_| "redeem"),
+                state.refund_action().map(|_| "refund"),
+            ];
+            make_swap_entity(facade, swap_id, protocol, state, maybe_action_names).await
+        }
+        SwapState::BobHerc20HalightBitcoin(state) => {
+            // Bob cannot init, deploy and refund in this swap combination
+            let maybe_action_names = vec![
+                state.fund_action().map(|_| "fund"),
+                state.redeem_action().map(|_| "redeem"),
+            ];
+            make_swap_entity(facade, swap_id, protocol, state, maybe_action_names).await
+        }
+        SwapState::AliceHalightBitcoinHanEthereum(state) => {
+            // Alice cannot init and refund in this swap combination
+            let maybe_action_names = vec![
+                state.fund_action().map(|_| "fund"),
+                state.redeem_action().map(|_| "redeem"),
+            ];
+            make_swap_entity(facade, swap_id, protocol, state, maybe_action_names).await
+        }
+        SwapState::BobHalightBitcoinHanEthereum(state) => {
+            let maybe_action_names = vec![
+                state.init_action().map(|_| "init"),
+                state.fund_action().map(|_| "fund"),
+                state.redeem_action().map(|_| "redeem"),
+                state.refund_action().map(|_| "refund"),
+            ];
+            make_swap_entity(facade, swap_id, protocol, state, maybe_action_names).await
+        }
+        SwapState::AliceHalightBitcoinHerc20(state) => {
+            // Alice cannot init, deploy and refund in this swap combination
+            let maybe_action_names = vec![
+                state.fund_action().map(|_| "fund"),
+                state.redeem_action().map(|_| "redeem"),
+            ];
+            make_swap_entity(facade, swap_id, protocol, state, maybe_action_names).await
+        }
+        SwapState::BobHalightBitcoinHerc20(state) => {
+            let maybe_action_names = vec![
+                state.init_action().map(|_| "init"),
+                state.deploy_action().map(|_| "deploy"),
+                state.fund_action().map(|_| "fund"),
+                state.redeem_action().map(|_| "redeem"),
+                state.refund_action().map(|_| "refund"),
+            ];
+            make_swap_entity(facade, swap_id, protocol, state, maybe_action_names).await
+        }
+        SwapState::AliceHanEthereumHbitBitcoin(state) => {
+            let maybe_action_names = vec![
+                state.fund_action().map(|_| "fund"),
+                state.redeem_action().map(|_| "redeem"),
+                state.refund_action().map(|_| "refund"),
+            ];
+            make_swap_entity(facade, swap_id, protocol, state, maybe_action_names)
+                .await
+                .map(|entity| with_spend_output_fields(entity, "redeem"))
+        }
+        SwapState::BobHanEthereumHbitBitcoin(state) => {
+            let maybe_action_names = vec![
+                state.fund_action().map(|_| "fund"),
+                state.redeem_action().map(|_| "redeem"),
+                state.refund_action().map(|_| "refund"),
+            ];
+            make_swap_entity(facade, swap_id, protocol, state, maybe_action_names)
+                .await
+                .map(|entity| with_spend_output_fields(entity, "refund"))
+        }
+        SwapState::AliceHerc20HbitBitcoin(state) => {
+            let maybe_action_names = vec![
+                state.deploy_action().map(|_| "deploy"),
+                state.fund_action().map(|_| "fund"),
+                state.redeem_action().map(|_| "redeem"),
+                state.refund_action().map(|_| "refund"),
+            ];
+            make_swap_entity(facade, swap_id, protocol, state, maybe_action_names)
+                .await
+                .map(|entity| with_spend_output_fields(entity, "redeem"))
+        }
+        SwapState::BobHerc20HbitBitcoin(state) => {
+            let maybe_action_names = vec![
+                state.fund_action().map(|_| "fund"),
+                state.redeem_action().map(|_| "redeem"),
+                state.refund_action().map(|_| "refund"),
+            ];
+            make_swap_entity(facade, swap_id, protocol, state, maybe_action_names)
+                .await
+                .map(|entity| with_spend_output_fields(entity, "refund"))
+        }
+        SwapState::AliceHbitBitcoinHanEthereum(state) => {
+            let maybe_action_names = vec![
+                state.fund_action().map(|_| "fund"),
+                state.redeem_action().map(|_| "redeem"),
+                state.refund_action().map(|_| "refund"),
+            ];
+            make_swap_entity(facade, swap_id, protocol, state, maybe_action_names)
+                .await
+                .map(|entity| with_spend_output_fields(entity, "refund"))
+        }
+        SwapState::BobHbitBitcoinHanEthereum(state) => {
+            let maybe_action_names = vec![
+                state.fund_action().map(|_| "fund"),
+                state.redeem_action().map(|_| "redeem"),
+                state.refund_action().map(|_| "refund"),
+            ];
+            make_swap_entity(facade, swap_id, protocol, state, maybe_action_names)
+                .await
+                .map(|entity| with_spend_output_fields(entity, "redeem"))
+        }
+        SwapState::AliceHbitBitcoinHerc20(state) => {
+            let maybe_action_names = vec![
+                state.fund_action().map(|_| "fund"),
+                state.redeem_action().map(|_| "redeem"),
+                state.refund_action().map(|_| "refund"),
+            ];
+            make_swap_entity(facade, swap_id, protocol, state, maybe_action_names)
+                .await
+                .map(|entity| with_spend_output_fields(entity, "refund"))
+        }
+        SwapState::BobHbitBitcoinHerc20(state) => {
+            let maybe_action_names = vec![
+                state.deploy_action().map(|_| "deploy"),
+                state.fund_action().map(|_| "fund"),
+                state.redeem_action().map(|_| "redeem"),
+                state.refund_action().map(|_| "refund"),
+            ];
+            make_swap_entity(facade, swap_id, protocol, state, maybe_action_names)
+                .await
+                .map(|entity| with_spend_output_fields(entity, "redeem"))
+        }
+    }
+}
+
+/// The Bitcoin HTLC of hbit swaps is spent with a transaction cnd signs, the
+/// client has to tell us where to send the bitcoin to and the fee to pay.
+fn with_spend_output_fields(mut entity: siren::Entity, action_name: &str) -> siren::Entity {
+    for action in entity
+        .actions
+        .iter_mut()
+        .filter(|action| action.name == action_name)
+    {
+        action.fields = SpendOutput::list_required_fields();
+    }
+
+    entity
+}
+
+/// The state of a finalized swap from the point of view of our role.
+#[derive(Debug)]
+pub(crate) enum SwapState {
+    AliceHanEthereumHalightBitcoin(AliceHanEthereumHalightBitcoinState),
+    BobHanEthereumHalightBitcoin(BobHanEthereumHalightBitcoinState),
+    AliceHerc20HalightBitcoin(AliceHerc20HalightBitcoinState),
+    BobHerc20HalightBitcoin(BobHerc20HalightBitcoinState),
+    AliceHalightBitcoinHanEthereum(AliceHalightBitcoinHanEthereumState),
+    BobHalightBitcoinHanEthereum(BobHalightBitcoinHanEthereumState),
+    AliceHalightBitcoinHerc20(AliceHalightBitcoinHerc20State),
+    BobHalightBitcoinHerc20(BobHalightBitcoinHerc20State),
+    AliceHanEthereumHbitBitcoin(AliceHanEthereumHbitBitcoinState),
+    BobHanEthereumHbitBitcoin(BobHanEthereumHbitBitcoinState),
+    AliceHerc20HbitBitcoin(AliceHerc20HbitBitcoinState),
+    BobHerc20HbitBitcoin(BobHerc20HbitBitcoinState),
+    AliceHbitBitcoinHanEthereum(AliceHbitBitcoinHanEthereumState),
+    BobHbitBitcoinHanEthereum(BobHbitBitcoinHanEthereumState),
+    AliceHbitBitcoinHerc20(AliceHbitBitcoinHerc20State),
+    BobHbitBitcoinHerc20(BobHbitBitcoinHerc20State),
+}
+
+impl SwapState {
+    /// The protocols used on the alpha and the beta ledger.
+    pub(crate) fn protocol(&self) -> &'static str {
+        match self {
+            SwapState::AliceHanEthereumHalightBitcoin(_)
+            | SwapState::BobHanEthereumHalightBitcoin(_) => "han-halight",
+            SwapState::AliceHerc20HalightBitcoin(_) | SwapState::BobHerc20HalightBitcoin(_) => {
+                "herc20-halight"
+            }
+            SwapState::AliceHalightBitcoinHanEthereum(_)
+            | SwapState::BobHalightBitcoinHanEthereum(_) => "halight-han",
+            SwapState::AliceHalightBitcoinHerc20(_) | SwapState::BobHalightBitcoinHerc20(_) => {
+                "halight-herc20"
+            }
+            SwapState::AliceHanEthereumHbitBitcoin(_) | SwapState::BobHanEthereumHbitBitcoin(_) => {
+                "han-hbit"
+            }
+            SwapState::AliceHerc20HbitBitcoin(_) | SwapState::BobHerc20HbitBitcoin(_) => {
+                "herc20-hbit"
+            }
+            SwapState::AliceHbitBitcoinHanEthereum(_) | SwapState::BobHbitBitcoinHanEthereum(_) => {
+                "hbit-han"
+            }
+            SwapState::AliceHbitBitcoinHerc20(_) | SwapState::BobHbitBitcoinHerc20(_) => {
+                "hbit-herc20"
+            }
         }
     }
+
+    pub(crate) fn status(&self) -> db::SwapStatus {
+        let status = match self {
+            SwapState::AliceHanEthereumHalightBitcoin(state) => state.get_swap_status(),
+            SwapState::BobHanEthereumHalightBitcoin(state) => state.get_swap_status(),
+            SwapState::AliceHerc20HalightBitcoin(state) => state.get_swap_status(),
+            SwapState::BobHerc20HalightBitcoin(state) => state.get_swap_status(),
+            SwapState::AliceHalightBitcoinHanEthereum(state) => state.get_swap_status(),
+            SwapState::BobHalightBitcoinHanEthereum(state) => state.get_swap_status(),
+            SwapState::AliceHalightBitcoinHerc20(state) => state.get_swap_status(),
+            SwapState::BobHalightBitcoinHerc20(state) => state.get_swap_status(),
+            SwapState::AliceHanEthereumHbitBitcoin(state) => state.get_swap_status(),
+            SwapState::BobHanEthereumHbitBitcoin(state) => state.get_swap_status(),
+            SwapState::AliceHerc20HbitBitcoin(state) => state.get_swap_status(),
+            SwapState::BobHerc20HbitBitcoin(state) => state.get_swap_status(),
+            SwapState::AliceHbitBitcoinHanEthereum(state) => state.get_swap_status(),
+            SwapState::BobHbitBitcoinHanEthereum(state) => state.get_swap_status(),
+            SwapState::AliceHbitBitcoinHerc20(state) => state.get_swap_status(),
+            SwapState::BobHbitBitcoinHerc20(state) => state.get_swap_status(),
+        };
+
+        db::SwapStatus::from(status)
+    }
+}
+
+/// Loads the state of the swap, returns `None` if the swap has not been
+/// finalized yet or the ledger states are not yet available.
+pub(crate) async fn load_swap_state(
+    facade: &Facade,
+    swap_id: LocalSwapId,
+) -> anyhow::Result<Option<SwapState>> {
+    if let Some(finalized_swap) = facade.get_finalized_swap(swap_id).await {
+        let halight_state = match facade.halight_states.get(&swap_id).await? {
+            Some(halight_state) => halight_state,
+            None => return Ok(None),
+        };
+        let han_state: Option<
+            LedgerState<asset::Ether, htlc_location::Ethereum, transaction::Ethereum>,
+        > = facade.han_states.get(&swap_id).await?;
+
+        let han_state = match han_state {
+            Some(han_state) => han_state,
+            None => return Ok(None),
+        };
+
+        let state = match (finalized_swap.role, finalized_swap.alpha_ledger) {
+            (Role::Alice, AlphaLedger::Ethereum) => {
+                SwapState::AliceHanEthereumHalightBitcoin(AliceHanEthereumHalightBitcoinState {
+                    alpha_ledger_state: han_state,
+                    beta_ledger_state: halight_state,
+                    finalized_swap,
+                })
+            }
+            (Role::Bob, AlphaLedger::Ethereum) => {
+                SwapState::BobHanEthereumHalightBitcoin(BobHanEthereumHalightBitcoinState {
+                    alpha_ledger_state: han_state,
+                    beta_ledger_state: halight_state,
+                    finalized_swap,
+                })
+            }
+            (Role::Alice, AlphaLedger::Lightning) => {
+                SwapState::AliceHalightBitcoinHanEthereum(AliceHalightBitcoinHanEthereumState {
+                    alpha_ledger_state: halight_state,
+                    beta_ledger_state: han_state,
+                    finalized_swap,
+                })
+            }
+            (Role::Bob, AlphaLedger::Lightning) => {
+                SwapState::BobHalightBitcoinHanEthereum(BobHalightBitcoinHanEthereumState {
+                    alpha_ledger_state: halight_state,
+                    beta_ledger_state: han_state,
+                    finalized_swap,
+                })
+            }
+            // Swaps with Bitcoin as alpha ledger use hbit, they are never
+            // finalized as halight swaps.
+            (_, AlphaLedger::Bitcoin) => return Ok(None),
+        };
+
+        return Ok(Some(state));
+    }
+
+    if let Some(finalized_swap) = facade.get_finalized_herc20_swap(swap_id).await {
+        let halight_state = match facade.halight_states.get(&swap_id).await? {
+            Some(halight_state) => halight_state,
+            None => return Ok(None),
+        };
+        let herc20_state = match facade.herc20_states.get(&swap_id).await? {
+            Some(herc20_state) => herc20_state,
+            None => return Ok(None),
+        };
+
+        let state = match (finalized_swap.role, finalized_swap.alpha_ledger) {
+            (Role::Alice, AlphaLedger::Ethereum) => {
+                SwapState::AliceHerc20HalightBitcoin(AliceHerc20HalightBitcoinState {
+                    alpha_ledger_state: herc20_state,
+                    beta_ledger_state: halight_state,
+                    finalized_swap,
+                })
+            }
+            (Role::Bob, AlphaLedger::Ethereum) => {
+                SwapState::BobHerc20HalightBitcoin(BobHerc20HalightBitcoinState {
+                    alpha_ledger_state: herc20_state,
+                    beta_ledger_state: halight_state,
+                    finalized_swap,
+                })
+            }
+            (Role::Alice, AlphaLedger::Lightning) => {
+                SwapState::AliceHalightBitcoinHerc20(AliceHalightBitcoinHerc20State {
+                    alpha_ledger_state: halight_state,
+                    beta_ledger_state: herc20_state,
+                    finalized_swap,
+                })
+            }
+            (Role::Bob, AlphaLedger::Lightning) => {
+                SwapState::BobHalightBitcoinHerc20(BobHalightBitcoinHerc20State {
+                    alpha_ledger_state: halight_state,
+                    beta_ledger_state: herc20_state,
+                    finalized_swap,
+                })
+            }
+            // Swaps with Bitcoin as alpha ledger use hbit, they are never
+            // finalized as halight swaps.
+            (_, AlphaLedger::Bitcoin) => return Ok(None),
+        };
+
+        return Ok(Some(state));
+    }
+
+    if let Some(finalized_swap) = facade.get_finalized_han_hbit_swap(swap_id).await {
+        let hbit_state = match facade.hbit_states.get(&swap_id).await? {
+            Some(hbit_state) => hbit_state,
+            None => return Ok(None),
+        };
+        let han_state: Option<
+            LedgerState<asset::Ether, htlc_location::Ethereum, transaction::Ethereum>,
+        > = facade.han_states.get(&swap_id).await?;
+
+        let han_state = match han_state {
+            Some(han_state) => han_state,
+            None => return Ok(None),
+        };
+
+        let state = match (finalized_swap.role, finalized_swap.alpha_ledger) {
+            (Role::Alice, AlphaLedger::Ethereum) => {
+                SwapState::AliceHanEthereumHbitBitcoin(AliceHanEthereumHbitBitcoinState {
+                    alpha_ledger_state: han_state,
+                    beta_ledger_state: hbit_state,
+                    finalized_swap,
+                })
+            }
+            (Role::Bob, AlphaLedger::Ethereum) => {
+                SwapState::BobHanEthereumHbitBitcoin(BobHanEthereumHbitBitcoinState {
+                    alpha_ledger_state: han_state,
+                    beta_ledger_state: hbit_state,
+                    finalized_swap,
+                })
+            }
+            (Role::Alice, AlphaLedger::Bitcoin) => {
+                SwapState::AliceHbitBitcoinHanEthereum(AliceHbitBitcoinHanEthereumState {
+                    alpha_ledger_state: hbit_state,
+                    beta_ledger_state: han_state,
+                    finalized_swap,
+                })
+            }
+            (Role::Bob, AlphaLedger::Bitcoin) => {
+                SwapState::BobHbitBitcoinHanEthereum(BobHbitBitcoinHanEthereumState {
+                    alpha_ledger_state: hbit_state,
+                    beta_ledger_state: han_state,
+                    finalized_swap,
+                })
+            }
+            // Swaps with Lightning as alpha ledger use halight, they are never
+            // finalized as hbit swaps.
+            (_, AlphaLedger::Lightning) => return Ok(None),
+        };
+
+        return Ok(Some(state));
+    }
+
+    if let Some(finalized_swap) = facade.get_finalized_herc20_hbit_swap(swap_id).await {
+        let hbit_state = match facade.hbit_states.get(&swap_id).await? {
+            Some(hbit_state) => hbit_state,
+            None => return Ok(None),
+        };
+        let herc20_state = match facade.herc20_states.get(&swap_id).await? {
+            Some(herc20_state) => herc20_state,
+            None => return Ok(None),
+        };
+
+        let state = match (finalized_swap.role, finalized_swap.alpha_ledger) {
+            (Role::Alice, AlphaLedger::Ethereum) => {
+                SwapState::AliceHerc20HbitBitcoin(AliceHerc20HbitBitcoinState {
+                    alpha_ledger_state: herc20_state,
+                    beta_ledger_state: hbit_state,
+                    finalized_swap,
+                })
+            }
+            (Role::Bob, AlphaLedger::Ethereum) => {
+                SwapState::BobHerc20HbitBitcoin(BobHerc20HbitBitcoinState {
+                    alpha_ledger_state: herc20_state,
+                    beta_ledger_state: hbit_state,
+                    finalized_swap,
+                })
+            }
+            (Role::Alice, AlphaLedger::Bitcoin) => {
+                SwapState::AliceHbitBitcoinHerc20(AliceHbitBitcoinHerc20State {
+                    alpha_ledger_state: hbit_state,
+                    beta_ledger_state: herc20_state,
+                    finalized_swap,
+                })
+            }
+            (Role::Bob, AlphaLedger::Bitcoin) => {
+                SwapState::BobHbitBitcoinHerc20(BobHbitBitcoinHerc20State {
+                    alpha_ledger_state: hbit_state,
+                    beta_ledger_state: herc20_state,
+                    finalized_swap,
+                })
+            }
+            // Swaps with Lightning as alpha ledger use halight, they are never
+            // finalized as hbit swaps.
+            (_, AlphaLedger::Lightning) => return Ok(None),
+        };
+
+        return Ok(Some(state));
+    }
+
+    Ok(None)
 }
 
-fn make_swap_entity<S>(
+async fn make_swap_entity<S>(
+    facade: &Facade,
     swap_id: LocalSwapId,
+    protocol: &'static str,
     state: S,
     maybe_action_names: Vec<Option<&str>>,
 ) -> anyhow::Result<siren::Entity>
 where
     S: GetSwapStatus
         + GetRole
-        + QuantityWei
-        + QuantitySatoshi
+        + GetAlphaParams
+        + GetBetaParams
         + GetAlphaTransaction
         + GetBetaTransaction,
 {
     let role = state.get_role();
     let swap = SwapResource {
+        protocol,
         status: state.get_swap_status(),
         role: Http(role),
+        alerts: facade.expiry_alerts.get(swap_id.into()).await,
     };
 
     let mut entity = siren::Entity::default()
@@ -135,10 +573,7 @@ where
             route_factory::swap_path(swap_id),
         ));
 
-    let alpha_params = HanEthereum {
-        protocol: "han-ethereum".to_string(),
-        quantity: state.quantity_wei(),
-    };
+    let alpha_params = state.get_alpha_params();
     let alpha_params_sub = siren::SubEntity::from_entity(
         siren::Entity::default()
             .with_class_member("parameters")
@@ -151,10 +586,7 @@ where
     );
     entity.push_sub_entity(alpha_params_sub);
 
-    let beta_params = HalightBitcoin {
-        protocol: "halight-bitcoin".to_string(),
-        quantity: state.quantity_satoshi(),
-    };
+    let beta_params = state.get_beta_params();
     let beta_params_sub = siren::SubEntity::from_entity(
         siren::Entity::default()
             .with_class_member("parameters")
@@ -167,7 +599,7 @@ where
     );
     entity.push_sub_entity(beta_params_sub);
 
-    let alpha_tx = state.get_alpha_transaction();
+    let alpha_tx = with_confirmations(facade, state.get_alpha_transaction()).await;
     let alpha_state_sub = siren::SubEntity::from_entity(
         siren::Entity::default()
             .with_class_member("state")
@@ -180,7 +612,7 @@ where
     );
     entity.push_sub_entity(alpha_state_sub);
 
-    let beta_tx = state.get_beta_transaction();
+    let beta_tx = with_confirmations(facade, state.get_beta_transaction()).await;
     let beta_state_sub = siren::SubEntity::from_entity(
         siren::Entity::default()
             .with_class_member("state")
@@ -202,6 +634,36 @@ where
         }))
 }
 
+/// Looks up the confirmations of the most recent transaction if it happened on
+/// Ethereum or the Bitcoin blockchain.
+async fn with_confirmations(facade: &Facade, mut transaction: Transaction) -> Transaction {
+    if let Some(hash) = transaction.latest_ethereum_transaction {
+        transaction.confirmations =
+            btsieve::ethereum::confirmations(facade.ethereum_connector.as_ref(), hash)
+                .await
+                .unwrap_or_else(|e| {
+                    tracing::warn!("failed to look up confirmations of {:x}: {:?}", hash, e);
+                    None
+                });
+    }
+
+    if let Some(bitcoin_transaction) = &transaction.latest_bitcoin_transaction {
+        transaction.confirmations =
+            btsieve::bitcoin::confirmations(facade.bitcoin_connector.as_ref(), bitcoin_transaction)
+                .await
+                .unwrap_or_else(|e| {
+                    tracing::warn!(
+                        "failed to look up confirmations of {}: {:?}",
+                        bitcoin_transaction.txid(),
+                        e
+                    );
+                    None
+                });
+    }
+
+    transaction
+}
+
 fn make_siren_action(swap_id: LocalSwapId, action_name: &str) -> siren::Action {
     siren::Action {
         name: action_name.to_owned(),
@@ -223,10 +685,23 @@ enum SwapStatus {
     NotSwapped,
 }
 
+impl From<SwapStatus> for db::SwapStatus {
+    fn from(status: SwapStatus) -> Self {
+        match status {
+            SwapStatus::Created | SwapStatus::InProgress => db::SwapStatus::InProgress,
+            SwapStatus::Swapped => db::SwapStatus::Swapped,
+            SwapStatus::NotSwapped => db::SwapStatus::NotSwapped,
+        }
+    }
+}
+
 #[derive(Debug, Serialize)]
 struct SwapResource {
+    pub protocol: &'static str,
     pub status: SwapStatus,
     pub role: Http<Role>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub alerts: Vec<Alert>,
 }
 
 trait GetSwapStatus {
@@ -245,21 +720,25 @@ trait GetRole {
     fn get_role(&self) -> Role;
 }
 
-/// Return the ether swap quantity in wei.
-trait QuantityWei {
-    fn quantity_wei(&self) -> String;
+/// Return the parameters of the protocol used on the alpha ledger.
+trait GetAlphaParams {
+    type Output: Serialize;
+
+    fn get_alpha_params(&self) -> Self::Output;
 }
 
-/// Return the bitcoin swap quantity in satoshi.
-trait QuantitySatoshi {
-    fn quantity_satoshi(&self) -> String;
+/// Return the parameters of the protocol used on the beta ledger.
+trait GetBetaParams {
+    type Output: Serialize;
+
+    fn get_beta_params(&self) -> Self::Output;
 }
 
 fn han_eth_halight_swap_status(
     ethereum_status: HtlcState,
-    beta_ledger_state: &halight::State,
+    halight_state: &halight::State,
 ) -> SwapStatus {
-    match (ethereum_status, beta_ledger_state) {
+    match (ethereum_status, halight_state) {
         (HtlcState::NotDeployed, halight::State::None) => SwapStatus::Created,
         (HtlcState::Redeemed, halight::State::Settled(_)) => SwapStatus::Swapped,
         (HtlcState::IncorrectlyFunded, _) => SwapStatus::NotSwapped,
@@ -269,12 +748,50 @@ fn han_eth_halight_swap_status(
     }
 }
 
+fn herc20_halight_swap_status(
+    herc20_state: &herc20::State,
+    halight_state: &halight::State,
+) -> SwapStatus {
+    match (herc20_state, halight_state) {
+        (herc20::State::None, halight::State::None) => SwapStatus::Created,
+        (herc20::State::Redeemed { .. }, halight::State::Settled(_)) => SwapStatus::Swapped,
+        (herc20::State::IncorrectlyFunded { .. }, _) => SwapStatus::NotSwapped,
+        (herc20::State::Refunded { .. }, _) => SwapStatus::NotSwapped,
+        (_, halight::State::Cancelled(_)) => SwapStatus::NotSwapped,
+        _ => SwapStatus::InProgress,
+    }
+}
+
+fn han_eth_hbit_swap_status(ethereum_status: HtlcState, hbit_state: &hbit::State) -> SwapStatus {
+    match (ethereum_status, hbit_state) {
+        (HtlcState::NotDeployed, hbit::State::None) => SwapStatus::Created,
+        (HtlcState::Redeemed, hbit::State::Redeemed { .. }) => SwapStatus::Swapped,
+        (HtlcState::IncorrectlyFunded, _) => SwapStatus::NotSwapped,
+        (HtlcState::Refunded, _) => SwapStatus::NotSwapped,
+        (_, hbit::State::IncorrectlyFunded { .. }) => SwapStatus::NotSwapped,
+        (_, hbit::State::Refunded { .. }) => SwapStatus::NotSwapped,
+        _ => SwapStatus::InProgress,
+    }
+}
+
+fn herc20_hbit_swap_status(herc20_state: &herc20::State, hbit_state: &hbit::State) -> SwapStatus {
+    match (herc20_state, hbit_state) {
+        (herc20::State::None, hbit::State::None) => SwapStatus::Created,
+        (herc20::State::Redeemed { .. }, hbit::State::Redeemed { .. }) => SwapStatus::Swapped,
+        (herc20::State::IncorrectlyFunded { .. }, _) => SwapStatus::NotSwapped,
+        (herc20::State::Refunded { .. }, _) => SwapStatus::NotSwapped,
+        (_, hbit::State::IncorrectlyFunded { .. }) => SwapStatus::NotSwapped,
+        (_, hbit::State::Refunded { .. }) => SwapStatus::NotSwapped,
+        _ => SwapStatus::InProgress,
+    }
+}
+
 #[derive(Debug)]
 pub struct AliceHanEthereumHalightBitcoinState {
     pub alpha_ledger_state:
         LedgerState<asset::Ether, htlc_location::Ethereum, transaction::Ethereum>,
     pub beta_ledger_state: halight::State,
-    pub finalized_swap: comit_ln::FinalizedSwap,
+    pub finalized_swap: comit_ln::FinalizedSwap<asset::Ether>,
 }
 
 impl GetSwapStatus for AliceHanEthereumHalightBitcoinState {
@@ -302,15 +819,19 @@ impl GetRole for AliceHanEthereumHalightBitcoinState {
     }
 }
 
-impl QuantityWei for AliceHanEthereumHalightBitcoinState {
-    fn quantity_wei(&self) -> String {
-        self.finalized_swap.alpha_asset.to_wei_dec()
+impl GetAlphaParams for AliceHanEthereumHalightBitcoinState {
+    type Output = HanEthereum;
+
+    fn get_alpha_params(&self) -> Self::Output {
+        HanEthereum::from(&self.finalized_swap)
     }
 }
 
-impl QuantitySatoshi for AliceHanEthereumHalightBitcoinState {
-    fn quantity_satoshi(&self) -> String {
-        self.finalized_swap.beta_asset.as_sat().to_string()
+impl GetBetaParams for AliceHanEthereumHalightBitcoinState {
+    type Output = HalightBitcoin;
+
+    fn get_beta_params(&self) -> Self::Output {
+        HalightBitcoin::from(&self.finalized_swap)
     }
 }
 
@@ -319,7 +840,7 @@ pub struct BobHanEthereumHalightBitcoinState {
     pub alpha_ledger_state:
         LedgerState<asset::Ether, htlc_location::Ethereum, transaction::Ethereum>,
     pub beta_ledger_state: halight::State,
-    pub finalized_swap: comit_ln::FinalizedSwap,
+    pub finalized_swap: comit_ln::FinalizedSwap<asset::Ether>,
 }
 
 impl GetSwapStatus for BobHanEthereumHalightBitcoinState {
@@ -347,228 +868,1842 @@ impl GetRole for BobHanEthereumHalightBitcoinState {
     }
 }
 
-impl QuantityWei for BobHanEthereumHalightBitcoinState {
-    fn quantity_wei(&self) -> String {
-        self.finalized_swap.alpha_asset.to_wei_dec()
+impl GetAlphaParams for BobHanEthereumHalightBitcoinState {
+    type Output = HanEthereum;
+
+    fn get_alpha_params(&self) -> Self::Output {
+        HanEthereum::from(&self.finalized_swap)
     }
 }
 
-impl QuantitySatoshi for BobHanEthereumHalightBitcoinState {
-    fn quantity_satoshi(&self) -> String {
-        self.finalized_swap.beta_asset.as_sat().to_string()
+impl GetBetaParams for BobHanEthereumHalightBitcoinState {
+    type Output = HalightBitcoin;
+
+    fn get_beta_params(&self) -> Self::Output {
+        HalightBitcoin::from(&self.finalized_swap)
     }
 }
 
-#[derive(Debug, Serialize)]
-struct HanEthereum {
-    pub protocol: String,
-    pub quantity: String, // In Wei.
+#[derive(Debug)]
+pub struct AliceHerc20HalightBitcoinState {
+    pub alpha_ledger_state: herc20::State,
+    pub beta_ledger_state: halight::State,
+    pub finalized_swap: comit_ln::FinalizedSwap<asset::Erc20>,
 }
 
-#[derive(Debug, Serialize)]
-struct HalightBitcoin {
-    pub protocol: String,
-    pub quantity: String, // In Satoshi.
+impl GetSwapStatus for AliceHerc20HalightBitcoinState {
+    fn get_swap_status(&self) -> SwapStatus {
+        herc20_halight_swap_status(&self.alpha_ledger_state, &self.beta_ledger_state)
+    }
 }
 
-#[derive(Debug, Serialize)]
-struct Transaction {
-    /// Keys are on of: "init", "deploy", "fund", "redeem", "refund".
-    /// Values are transactions.
-    transactions: HashMap<String, String>,
-    status: EscrowStatus,
+impl GetAlphaTransaction for AliceHerc20HalightBitcoinState {
+    fn get_alpha_transaction(&self) -> Transaction {
+        Transaction::from(self.alpha_ledger_state.clone())
+    }
 }
 
-#[derive(Debug, Clone, Copy, Serialize, PartialEq)]
-#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
-enum EscrowStatus {
-    None,
-    Initialized,
-    Deployed,
-    Funded,
-    Redeemed,
-    Refunded,
-    IncorrectlyFunded,
+impl GetBetaTransaction for AliceHerc20HalightBitcoinState {
+    fn get_beta_transaction(&self) -> Transaction {
+        Transaction::from(self.beta_ledger_state)
+    }
 }
 
-impl From<LedgerState<asset::Ether, htlc_location::Ethereum, transaction::Ethereum>>
-    for Transaction
+impl GetRole for AliceHerc20HalightBitcoinState {
+    fn get_role(&self) -> Role {
+        Role::Alice
+    }
+}
+
+impl GetAlphaParams for AliceHerc20HalightBitcoinState {
+    type Output = Herc20Ethereum;
+
+    fn get_alpha_params(&self) -> Self::Output {
+        Herc20Ethereum::from(&self.finalized_swap)
+    }
+}
+
+impl GetBetaParams for AliceHerc20HalightBitcoinState {
+    type Output = HalightBitcoin;
+
+    fn get_beta_params(&self) -> Self::Output {
+        HalightBitcoin::from(&self.finalized_swap)
+    }
+}
+
+#[derive(Debug)]
+pub struct BobHerc20HalightBitcoinState {
+    pub alpha_ledger_state: herc20::State,
+    pub beta_ledger_state: halight::State,
+    pub finalized_swap: comit_ln::FinalizedSwap<asset::Erc20>,
+}
+
+impl GetSwapStatus for BobHerc20HalightBitcoinState {
+    fn get_swap_status(&self) -> SwapStatus {
+        herc20_halight_swap_status(&self.alpha_ledger_state, &self.beta_ledger_state)
+    }
+}
+
+impl GetAlphaTransaction for BobHerc20HalightBitcoinState {
+    fn get_alpha_transaction(&self) -> Transaction {
+        Transaction::from(self.alpha_ledger_state.clone())
+    }
+}
+
+impl GetBetaTransaction for BobHerc20HalightBitcoinState {
+    fn get_beta_transaction(&self) -> Transaction {
+        Transaction::from(self.beta_ledger_state)
+    }
+}
+
+impl GetRole for BobHerc20HalightBitcoinState {
+    fn get_role(&self) -> Role {
+        Role::Bob
+    }
+}
+
+impl GetAlphaParams for BobHerc20HalightBitcoinState {
+    type Output = Herc20Ethereum;
+
+    fn get_alpha_params(&self) -> Self::Output {
+        Herc20Ethereum::from(&self.finalized_swap)
+    }
+}
+
+impl GetBetaParams for BobHerc20HalightBitcoinState {
+    type Output = HalightBitcoin;
+
+    fn get_beta_params(&self) -> Self::Output {
+        HalightBitcoin::from(&self.finalized_swap)
+    }
+}
+
+#[derive(Debug)]
+pub struct AliceHalightBitcoinHanEthereumState {
+    pub alpha_ledger_state: halight::State,
+    pub beta_ledger_state:
+        LedgerState<asset::Ether, htlc_location::Ethereum, transaction::Ethereum>,
+    pub finalized_swap: comit_ln::FinalizedSwap<asset::Ether>,
+}
+
+impl GetSwapStatus for AliceHalightBitcoinHanEthereumState {
+    fn get_swap_status(&self) -> SwapStatus {
+        let ethereum_status = HtlcState::from(self.beta_ledger_state.clone());
+        han_eth_halight_swap_status(ethereum_status, &self.alpha_ledger_state)
+    }
+}
+
+impl GetAlphaTransaction for AliceHalightBitcoinHanEthereumState {
+    fn get_alpha_transaction(&self) -> Transaction {
+        Transaction::from(self.alpha_ledger_state)
+    }
+}
+
+impl GetBetaTransaction for AliceHalightBitcoinHanEthereumState {
+    fn get_beta_transaction(&self) -> Transaction {
+        Transaction::from(self.beta_ledger_state.clone())
+    }
+}
+
+impl GetRole for AliceHalightBitcoinHanEthereumState {
+    fn get_role(&self) -> Role {
+        Role::Alice
+    }
+}
+
+impl GetAlphaParams for AliceHalightBitcoinHanEthereumState {
+    type Output = HalightBitcoin;
+
+    fn get_alpha_params(&self) -> Self::Output {
+        HalightBitcoin::from(&self.finalized_swap)
+    }
+}
+
+impl GetBetaParams for AliceHalightBitcoinHanEthereumState {
+    type Output = HanEthereum;
+
+    fn get_beta_params(&self) -> Self::Output {
+        HanEthereum::from(&self.finalized_swap)
+    }
+}
+
+#[derive(Debug)]
+pub struct BobHalightBitcoinHanEthereumState {
+    pub alpha_ledger_state: halight::State,
+    pub beta_ledger_state:
+        LedgerState<asset::Ether, htlc_location::Ethereum, transaction::Ethereum>,
+    pub finalized_swap: comit_ln::FinalizedSwap<asset::Ether>,
+}
+
+impl GetSwapStatus for BobHalightBitcoinHanEthereumState {
+    fn get_swap_status(&self) -> SwapStatus {
+        let ethereum_status = HtlcState::from(self.beta_ledger_state.clone());
+        han_eth_halight_swap_status(ethereum_status, &self.alpha_ledger_state)
+    }
+}
+
+impl GetAlphaTransaction for BobHalightBitcoinHanEthereumState {
+    fn get_alpha_transaction(&self) -> Transaction {
+        Transaction::from(self.alpha_ledger_state)
+    }
+}
+
+impl GetBetaTransaction for BobHalightBitcoinHanEthereumState {
+    fn get_beta_transaction(&self) -> Transaction {
+        Transaction::from(self.beta_ledger_state.clone())
+    }
+}
+
+impl GetRole for BobHalightBitcoinHanEthereumState {
+    fn get_role(&self) -> Role {
+        Role::Bob
+    }
+}
+
+impl GetAlphaParams for BobHalightBitcoinHanEthereumState {
+    type Output = HalightBitcoin;
+
+    fn get_alpha_params(&self) -> Self::Output {
+        HalightBitcoin::from(&self.finalized_swap)
+    }
+}
+
+impl GetBetaParams for BobHalightBitcoinHanEthereumState {
+    type Output = HanEthereum;
+
+    fn get_beta_params(&self) -> Self::Output {
+        HanEthereum::from(&self.finalized_swap)
+    }
+}
+
+#[derive(Debug)]
+pub struct AliceHalightBitcoinHerc20State {
+    pub alpha_ledger_state: halight::State,
+    pub beta_ledger_state: herc20::State,
+    pub finalized_swap: comit_ln::FinalizedSwap<asset::Erc20>,
+}
+
+impl GetSwapStatus for AliceHalightBitcoinHerc20State {
+    fn get_swap_status(&self) -> SwapStatus {
+        herc20_halight_swap_status(&self.beta_ledger_state, &self.alpha_ledger_state)
+    }
+}
+
+impl GetAlphaTransaction for AliceHalightBitcoinHerc20State {
+    fn get_alpha_transaction(&self) -> Transaction {
+        Transaction::from(self.alpha_ledger_state)
+    }
+}
+
+impl GetBetaTransaction for AliceHalightBitcoinHerc20State {
+    fn get_beta_transaction(&self) -> Transaction {
+        Transaction::from(self.beta_ledger_state.clone())
+    }
+}
+
+impl GetRole for AliceHalightBitcoinHerc20State {
+    fn get_role(&self) -> Role {
+        Role::Alice
+    }
+}
+
+impl GetAlphaParams for AliceHalightBitcoinHerc20State {
+    type Output = HalightBitcoin;
+
+    fn get_alpha_params(&self) -> Self::Output {
+        HalightBitcoin::from(&self.finalized_swap)
+    }
+}
+
+impl GetBetaParams for AliceHalightBitcoinHerc20State {
+    type Output = Herc20Ethereum;
+
+    fn get_beta_params(&self) -> Self::Output {
+        Herc20Ethereum::from(&self.finalized_swap)
+    }
+}
+
+#[derive(Debug)]
+pub struct BobHalightBitcoinHerc20State {
+    pub alpha_ledger_state: halight::State,
+    pub beta_ledger_state: herc20::State,
+    pub finalized_swap: comit_ln::FinalizedSwap<asset::Erc20>,
+}
+
+impl GetSwapStatus for BobHalightBitcoinHerc20State {
+    fn get_swap_status(&self) -> SwapStatus {
+        herc20_halight_swap_status(&self.beta_ledger_state, &self.alpha_ledger_state)
+    }
+}
+
+impl GetAlphaTransaction for BobHalightBitcoinHerc20State {
+    fn get_alpha_transaction(&self) -> Transaction {
+        Transaction::from(self.alpha_ledger_state)
+    }
+}
+
+impl GetBetaTransaction for BobHalightBitcoinHerc20State {
+    fn get_beta_transaction(&self) -> Transaction {
+        Transaction::from(self.beta_ledger_state.clone())
+    }
+}
+
+impl GetRole for BobHalightBitcoinHerc20State {
+    fn get_role(&self) -> Role {
+        Role::Bob
+    }
+}
+
+impl GetAlphaParams for BobHalightBitcoinHerc20State {
+    type Output = HalightBitcoin;
+
+    fn get_alpha_params(&self) -> Self::Output {
+        HalightBitcoin::from(&self.finalized_swap)
+    }
+}
+
+impl GetBetaParams for BobHalightBitcoinHerc20State {
+    type Output = Herc20Ethereum;
+
+    fn get_beta_params(&self) -> Self::Output {
+        Herc20Ethereum::from(&self.finalized_swap)
+    }
+}
+
+#[derive(Debug)]
+pub struct AliceHanEthereumHbitBitcoinState {
+    pub alpha_ledger_state:
+        LedgerState<asset::Ether, htlc_location::Ethereum, transaction::Ethereum>,
+    pub beta_ledger_state: hbit::State,
+    pub finalized_swap: comit_ln::FinalizedHbitSwap<asset::Ether>,
+}
+
+impl GetSwapStatus for AliceHanEthereumHbitBitcoinState {
+    fn get_swap_status(&self) -> SwapStatus {
+        let ethereum_status = HtlcState::from(self.alpha_ledger_state.clone());
+        han_eth_hbit_swap_status(ethereum_status, &self.beta_ledger_state)
+    }
+}
+
+impl GetAlphaTransaction for AliceHanEthereumHbitBitcoinState {
+    fn get_alpha_transaction(&self) -> Transaction {
+        Transaction::from(self.alpha_ledger_state.clone())
+    }
+}
+
+impl GetBetaTransaction for AliceHanEthereumHbitBitcoinState {
+    fn get_beta_transaction(&self) -> Transaction {
+        Transaction::from(self.beta_ledger_state.clone())
+    }
+}
+
+impl GetRole for AliceHanEthereumHbitBitcoinState {
+    fn get_role(&self) -> Role {
+        Role::Alice
+    }
+}
+
+impl GetAlphaParams for AliceHanEthereumHbitBitcoinState {
+    type Output = HanEthereum;
+
+    fn get_alpha_params(&self) -> Self::Output {
+        HanEthereum::from(&self.finalized_swap)
+    }
+}
+
+impl GetBetaParams for AliceHanEthereumHbitBitcoinState {
+    type Output = HbitBitcoin;
+
+    fn get_beta_params(&self) -> Self::Output {
+        HbitBitcoin::from(&self.finalized_swap)
+    }
+}
+
+#[derive(Debug)]
+pub struct BobHanEthereumHbitBitcoinState {
+    pub alpha_ledger_state:
+        LedgerState<asset::Ether, htlc_location::Ethereum, transaction::Ethereum>,
+    pub beta_ledger_state: hbit::State,
+    pub finalized_swap: comit_ln::FinalizedHbitSwap<asset::Ether>,
+}
+
+impl GetSwapStatus for BobHanEthereumHbitBitcoinState {
+    fn get_swap_status(&self) -> SwapStatus {
+        let ethereum_status = HtlcState::from(self.alpha_ledger_state.clone());
+        han_eth_hbit_swap_status(ethereum_status, &self.beta_ledger_state)
+    }
+}
+
+impl GetAlphaTransaction for BobHanEthereumHbitBitcoinState {
+    fn get_alpha_transaction(&self) -> Transaction {
+        Transaction::from(self.alpha_ledger_state.clone())
+    }
+}
+
+impl GetBetaTransaction for BobHanEthereumHbitBitcoinState {
+    fn get_beta_transaction(&self) -> Transaction {
+        Transaction::from(self.beta_ledger_state.clone())
+    }
+}
+
+impl GetRole for BobHanEthereumHbitBitcoinState {
+    fn get_role(&self) -> Role {
+        Role::Bob
+    }
+}
+
+impl GetAlphaParams for BobHanEthereumHbitBitcoinState {
+    type Output = HanEthereum;
+
+    fn get_alpha_params(&self) -> Self::Output {
+        HanEthereum::from(&self.finalized_swap)
+    }
+}
+
+impl GetBetaParams for BobHanEthereumHbitBitcoinState {
+    type Output = HbitBitcoin;
+
+    fn get_beta_params(&self) -> Self::Output {
+        HbitBitcoin::from(&self.finalized_swap)
+    }
+}
+
+#[derive(Debug)]
+pub struct AliceHerc20HbitBitcoinState {
+    pub alpha_ledger_state: herc20::State,
+    pub beta_ledger_state: hbit::State,
+    pub finalized_swap: comit_ln::FinalizedHbitSwap<asset::Erc20>,
+}
+
+impl GetSwapStatus for AliceHerc20HbitBitcoinState {
+    fn get_swap_status(&self) -> SwapStatus {
+        herc20_hbit_swap_status(&self.alpha_ledger_state, &self.beta_ledger_state)
+    }
+}
+
+impl GetAlphaTransaction for AliceHerc20HbitBitcoinState {
+    fn get_alpha_transaction(&self) -> Transaction {
+        Transaction::from(self.alpha_ledger_state.clone())
+    }
+}
+
+impl GetBetaTransaction for AliceHerc20HbitBitcoinState {
+    fn get_beta_transaction(&self) -> Transaction {
+        Transaction::from(self.beta_ledger_state.clone())
+    }
+}
+
+impl GetRole for AliceHerc20HbitBitcoinState {
+    fn get_role(&self) -> Role {
+        Role::Alice
+    }
+}
+
+impl GetAlphaParams for AliceHerc20HbitBitcoinState {
+    type Output = Herc20Ethereum;
+
+    fn get_alpha_params(&self) -> Self::Output {
+        Herc20Ethereum::from(&self.finalized_swap)
+    }
+}
+
+impl GetBetaParams for AliceHerc20HbitBitcoinState {
+    type Output = HbitBitcoin;
+
+    fn get_beta_params(&self) -> Self::Output {
+        HbitBitcoin::from(&self.finalized_swap)
+    }
+}
+
+#[derive(Debug)]
+pub struct BobHerc20HbitBitcoinState {
+    pub alpha_ledger_state: herc20::State,
+    pub beta_ledger_state: hbit::State,
+    pub finalized_swap: comit_ln::FinalizedHbitSwap<asset::Erc20>,
+}
+
+impl GetSwapStatus for BobHerc20HbitBitcoinState {
+    fn get_swap_status(&self) -> SwapStatus {
+        herc20_hbit_swap_status(&self.alpha_ledger_state, &self.beta_ledger_state)
+    }
+}
+
+impl GetAlphaTransaction for BobHerc20HbitBitcoinState {
+    fn get_alpha_transaction(&self) -> Transaction {
+        Transaction::from(self.alpha_ledger_state.clone())
+    }
+}
+
+impl GetBetaTransaction for BobHerc20HbitBitcoinState {
+    fn get_beta_transaction(&self) -> Transaction {
+        Transaction::from(self.beta_ledger_state.clone())
+    }
+}
+
+impl GetRole for BobHerc20HbitBitcoinState {
+    fn get_role(&self) -> Role {
+        Role::Bob
+    }
+}
+
+impl GetAlphaParams for BobHerc20HbitBitcoinState {
+    type Output = Herc20Ethereum;
+
+    fn get_alpha_params(&self) -> Self::Output {
+        Herc20Ethereum::from(&self.finalized_swap)
+    }
+}
+
+impl GetBetaParams for BobHerc20HbitBitcoinState {
+    type Output = HbitBitcoin;
+
+    fn get_beta_params(&self) -> Self::Output {
+        HbitBitcoin::from(&self.finalized_swap)
+    }
+}
+
+#[derive(Debug)]
+pub struct AliceHbitBitcoinHanEthereumState {
+    pub alpha_ledger_state: hbit::State,
+    pub beta_ledger_state:
+        LedgerState<asset::Ether, htlc_location::Ethereum, transaction::Ethereum>,
+    pub finalized_swap: comit_ln::FinalizedHbitSwap<asset::Ether>,
+}
+
+impl GetSwapStatus for AliceHbitBitcoinHanEthereumState {
+    fn get_swap_status(&self) -> SwapStatus {
+        let ethereum_status = HtlcState::from(self.beta_ledger_state.clone());
+        han_eth_hbit_swap_status(ethereum_status, &self.alpha_ledger_state)
+    }
+}
+
+impl GetAlphaTransaction for AliceHbitBitcoinHanEthereumState {
+    fn get_alpha_transaction(&self) -> Transaction {
+        Transaction::from(self.alpha_ledger_state.clone())
+    }
+}
+
+impl GetBetaTransaction for AliceHbitBitcoinHanEthereumState {
+    fn get_beta_transaction(&self) -> Transaction {
+        Transaction::from(self.beta_ledger_state.clone())
+    }
+}
+
+impl GetRole for AliceHbitBitcoinHanEthereumState {
+    fn get_role(&self) -> Role {
+        Role::Alice
+    }
+}
+
+impl GetAlphaParams for AliceHbitBitcoinHanEthereumState {
+    type Output = HbitBitcoin;
+
+    fn get_alpha_params(&self) -> Self::Output {
+        HbitBitcoin::from(&self.finalized_swap)
+    }
+}
+
+impl GetBetaParams for AliceHbitBitcoinHanEthereumState {
+    type Output = HanEthereum;
+
+    fn get_beta_params(&self) -> Self::Output {
+        HanEthereum::from(&self.finalized_swap)
+    }
+}
+
+#[derive(Debug)]
+pub struct BobHbitBitcoinHanEthereumState {
+    pub alpha_ledger_state: hbit::State,
+    pub beta_ledger_state:
+        LedgerState<asset::Ether, htlc_location::Ethereum, transaction::Ethereum>,
+    pub finalized_swap: comit_ln::FinalizedHbitSwap<asset::Ether>,
+}
+
+impl GetSwapStatus for BobHbitBitcoinHanEthereumState {
+    fn get_swap_status(&self) -> SwapStatus {
+        let ethereum_status = HtlcState::from(self.beta_ledger_state.clone());
+        han_eth_hbit_swap_status(ethereum_status, &self.alpha_ledger_state)
+    }
+}
+
+impl GetAlphaTransaction for BobHbitBitcoinHanEthereumState {
+    fn get_alpha_transaction(&self) -> Transaction {
+        Transaction::from(self.alpha_ledger_state.clone())
+    }
+}
+
+impl GetBetaTransaction for BobHbitBitcoinHanEthereumState {
+    fn get_beta_transaction(&self) -> Transaction {
+        Transaction::from(self.beta_ledger_state.clone())
+    }
+}
+
+impl GetRole for BobHbitBitcoinHanEthereumState {
+    fn get_role(&self) -> Role {
+        Role::Bob
+    }
+}
+
+impl GetAlphaParams for BobHbitBitcoinHanEthereumState {
+    type Output = HbitBitcoin;
+
+    fn get_alpha_params(&self) -> Self::Output {
+        HbitBitcoin::from(&self.finalized_swap)
+    }
+}
+
+impl GetBetaParams for BobHbitBitcoinHanEthereumState {
+    type Output = HanEthereum;
+
+    fn get_beta_params(&self) -> Self::Output {
+        HanEthereum::from(&self.finalized_swap)
+    }
+}
+
+#[derive(Debug)]
+pub struct AliceHbitBitcoinHerc20State {
+    pub alpha_ledger_state: hbit::State,
+    pub beta_ledger_state: herc20::State,
+    pub finalized_swap: comit_ln::FinalizedHbitSwap<asset::Erc20>,
+}
+
+impl GetSwapStatus for AliceHbitBitcoinHerc20State {
+    fn get_swap_status(&self) -> SwapStatus {
+        herc20_hbit_swap_status(&self.beta_ledger_state, &self.alpha_ledger_state)
+    }
+}
+
+impl GetAlphaTransaction for AliceHbitBitcoinHerc20State {
+    fn get_alpha_transaction(&self) -> Transaction {
+        Transaction::from(self.alpha_ledger_state.clone())
+    }
+}
+
+impl GetBetaTransaction for AliceHbitBitcoinHerc20State {
+    fn get_beta_transaction(&self) -> Transaction {
+        Transaction::from(self.beta_ledger_state.clone())
+    }
+}
+
+impl GetRole for AliceHbitBitcoinHerc20State {
+    fn get_role(&self) -> Role {
+        Role::Alice
+    }
+}
+
+impl GetAlphaParams for AliceHbitBitcoinHerc20State {
+    type Output = HbitBitcoin;
+
+    fn get_alpha_params(&self) -> Self::Output {
+        HbitBitcoin::from(&self.finalized_swap)
+    }
+}
+
+impl GetBetaParams for AliceHbitBitcoinHerc20State {
+    type Output = Herc20Ethereum;
+
+    fn get_beta_params(&self) -> Self::Output {
+        Herc20Ethereum::from(&self.finalized_swap)
+    }
+}
+
+#[derive(Debug)]
+pub struct BobHbitBitcoinHerc20State {
+    pub alpha_ledger_state: hbit::State,
+    pub beta_ledger_state: herc20::State,
+    pub finalized_swap: comit_ln::FinalizedHbitSwap<asset::Erc20>,
+}
+
+impl GetSwapStatus for BobHbitBitcoinHerc20State {
+    fn get_swap_status(&self) -> SwapStatus {
+        herc20_hbit_swap_status(&self.beta_ledger_state, &self.alpha_ledger_state)
+    }
+}
+
+impl GetAlphaTransaction for BobHbitBitcoinHerc20State {
+    fn get_alpha_transaction(&self) -> Transaction {
+        Transaction::from(self.alpha_ledger_state.clone())
+    }
+}
+
+impl GetBetaTransaction for BobHbitBitcoinHerc20State {
+    fn get_beta_transaction(&self) -> Transaction {
+        Transaction::from(self.beta_ledger_state.clone())
+    }
+}
+
+impl GetRole for BobHbitBitcoinHerc20State {
+    fn get_role(&self) -> Role {
+        Role::Bob
+    }
+}
+
+impl GetAlphaParams for BobHbitBitcoinHerc20State {
+    type Output = HbitBitcoin;
+
+    fn get_alpha_params(&self) -> Self::Output {
+        HbitBitcoin::from(&self.finalized_swap)
+    }
+}
+
+impl GetBetaParams for BobHbitBitcoinHerc20State {
+    type Output = Herc20Ethereum;
+
+    fn get_beta_params(&self) -> Self::Output {
+        Herc20Ethereum::from(&self.finalized_swap)
+    }
+}
+
+#[derive(Debug, Serialize)]
+struct HanEthereum {
+    pub protocol: String,
+    pub quantity: String, // In Wei.
+}
+
+impl From<&comit_ln::FinalizedSwap<asset::Ether>> for HanEthereum {
+    fn from(finalized_swap: &comit_ln::FinalizedSwap<asset::Ether>) -> Self {
+        HanEthereum {
+            protocol: "han-ethereum".to_string(),
+            quantity: finalized_swap.ethereum_asset.to_wei_dec(),
+        }
+    }
+}
+
+impl From<&comit_ln::FinalizedHbitSwap<asset::Ether>> for HanEthereum {
+    fn from(finalized_swap: &comit_ln::FinalizedHbitSwap<asset::Ether>) -> Self {
+        HanEthereum {
+            protocol: "han-ethereum".to_string(),
+            quantity: finalized_swap.ethereum_asset.to_wei_dec(),
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+struct Herc20Ethereum {
+    pub protocol: String,
+    pub quantity: String, // In Wei.
+    pub token_contract: identity::Ethereum,
+}
+
+impl From<&comit_ln::FinalizedSwap<asset::Erc20>> for Herc20Ethereum {
+    fn from(finalized_swap: &comit_ln::FinalizedSwap<asset::Erc20>) -> Self {
+        Herc20Ethereum {
+            protocol: "herc20-ethereum".to_string(),
+            quantity: finalized_swap.ethereum_asset.quantity.to_wei_dec(),
+            token_contract: finalized_swap.ethereum_asset.token_contract,
+        }
+    }
+}
+
+impl From<&comit_ln::FinalizedHbitSwap<asset::Erc20>> for Herc20Ethereum {
+    fn from(finalized_swap: &comit_ln::FinalizedHbitSwap<asset::Erc20>) -> Self {
+        Herc20Ethereum {
+            protocol: "herc20-ethereum".to_string(),
+            quantity: finalized_swap.ethereum_asset.quantity.to_wei_dec(),
+            token_contract: finalized_swap.ethereum_asset.token_contract,
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+struct HalightBitcoin {
+    pub protocol: String,
+    pub quantity: String, // In Satoshi.
+}
+
+impl<A> From<&comit_ln::FinalizedSwap<A>> for HalightBitcoin {
+    fn from(finalized_swap: &comit_ln::FinalizedSwap<A>) -> Self {
+        HalightBitcoin {
+            protocol: "halight-bitcoin".to_string(),
+            quantity: finalized_swap.lightning_asset.as_sat().to_string(),
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+struct HbitBitcoin {
+    pub protocol: String,
+    pub quantity: String, // In Satoshi.
+}
+
+impl<A> From<&comit_ln::FinalizedHbitSwap<A>> for HbitBitcoin {
+    fn from(finalized_swap: &comit_ln::FinalizedHbitSwap<A>) -> Self {
+        HbitBitcoin {
+            protocol: "hbit-bitcoin".to_string(),
+            quantity: finalized_swap.bitcoin_asset.as_sat().to_string(),
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+struct Transaction {
+    /// Keys are on of: "init", "deploy", "fund", "redeem", "refund".
+    /// Values are transactions.
+    transactions: HashMap<String, String>,
+    status: EscrowStatus,
+    /// Confirmations of the most recent transaction, only present for
+    /// ledgers with blocks.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    confirmations: Option<u32>,
+    #[serde(skip)]
+    latest_ethereum_transaction: Option<crate::ethereum::Hash>,
+    #[serde(skip)]
+    latest_bitcoin_transaction: Option<transaction::Bitcoin>,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, PartialEq)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+enum EscrowStatus {
+    None,
+    Initialized,
+    Deployed,
+    Funded,
+    Redeemed,
+    Refunded,
+    IncorrectlyFunded,
+}
+
+impl From<LedgerState<asset::Ether, htlc_location::Ethereum, transaction::Ethereum>>
+    for Transaction
 {
     fn from(
         state: LedgerState<asset::Ether, htlc_location::Ethereum, transaction::Ethereum>,
     ) -> Self {
-        match state {
-            LedgerState::NotDeployed => Transaction {
-                transactions: HashMap::new(),
-                status: EscrowStatus::None,
-            },
+        let mut transactions = HashMap::new();
+
+        let (status, latest_ethereum_transaction) = match state {
+            LedgerState::NotDeployed => (EscrowStatus::None, None),
             LedgerState::Deployed {
                 deploy_transaction, ..
             } => {
-                let mut transactions = HashMap::new();
                 transactions.insert("deploy".to_string(), deploy_transaction.hash.to_string());
+                (EscrowStatus::Deployed, Some(deploy_transaction.hash))
+            }
+            LedgerState::Funded {
+                deploy_transaction,
+                fund_transaction,
+                ..
+            } => {
+                transactions.insert("deploy".to_string(), deploy_transaction.hash.to_string());
+                transactions.insert("fund".to_string(), fund_transaction.hash.to_string());
+                (EscrowStatus::Funded, Some(fund_transaction.hash))
+            }
+            LedgerState::IncorrectlyFunded {
+                deploy_transaction,
+                fund_transaction,
+                ..
+            } => {
+                transactions.insert("deploy".to_string(), deploy_transaction.hash.to_string());
+                transactions.insert("fund".to_string(), fund_transaction.hash.to_string());
+                (EscrowStatus::IncorrectlyFunded, Some(fund_transaction.hash))
+            }
+            // The escrow stays funded until the redeem transaction is confirmed.
+            LedgerState::RedeemPending {
+                deploy_transaction,
+                fund_transaction,
+                redeem_transaction,
+                ..
+            } => {
+                transactions.insert("deploy".to_string(), deploy_transaction.hash.to_string());
+                transactions.insert("fund".to_string(), fund_transaction.hash.to_string());
+                transactions.insert("redeem".to_string(), redeem_transaction.hash.to_string());
+                (EscrowStatus::Funded, Some(redeem_transaction.hash))
+            }
+            LedgerState::Redeemed {
+                deploy_transaction,
+                fund_transaction,
+                redeem_transaction,
+                ..
+            } => {
+                transactions.insert("deploy".to_string(), deploy_transaction.hash.to_string());
+                transactions.insert("fund".to_string(), fund_transaction.hash.to_string());
+                transactions.insert("redeem".to_string(), redeem_transaction.hash.to_string());
+                (EscrowStatus::Redeemed, Some(redeem_transaction.hash))
+            }
+            LedgerState::Refunded {
+                deploy_transaction,
+                fund_transaction,
+                refund_transaction,
+                ..
+            } => {
+                transactions.insert("deploy".to_string(), deploy_transaction.hash.to_string());
+                transactions.insert("fund".to_string(), fund_transaction.hash.to_string());
+                transactions.insert("refund".to_string(), refund_transaction.hash.to_string());
+                (EscrowStatus::Refunded, Some(refund_transaction.hash))
+            }
+        };
+
+        Transaction {
+            transactions,
+            status,
+            confirmations: None,
+            latest_ethereum_transaction,
+            latest_bitcoin_transaction: None,
+        }
+    }
+}
+
+impl From<halight::State> for Transaction {
+    fn from(state: halight::State) -> Self {
+        let status = match state {
+            halight::State::None => EscrowStatus::None,
+            halight::State::Opened(_) => EscrowStatus::Initialized,
+            halight::State::Accepted(_) => EscrowStatus::Funded,
+            halight::State::Settled(_) => EscrowStatus::Redeemed,
+            halight::State::Cancelled(_) => EscrowStatus::Refunded,
+        };
+
+        Transaction {
+            transactions: HashMap::new(),
+            status,
+            confirmations: None,
+            latest_ethereum_transaction: None,
+            latest_bitcoin_transaction: None,
+        }
+    }
+}
+
+impl From<herc20::State> for Transaction {
+    fn from(state: herc20::State) -> Self {
+        let mut transactions = HashMap::new();
+
+        let (status, latest_ethereum_transaction) = match state {
+            herc20::State::None => (EscrowStatus::None, None),
+            herc20::State::Deployed {
+                deploy_transaction, ..
+            } => {
+                transactions.insert("deploy".to_string(), deploy_transaction.hash.to_string());
+                (EscrowStatus::Deployed, Some(deploy_transaction.hash))
+            }
+            herc20::State::Funded {
+                deploy_transaction,
+                fund_transaction,
+                ..
+            } => {
+                transactions.insert("deploy".to_string(), deploy_transaction.hash.to_string());
+                transactions.insert("fund".to_string(), fund_transaction.hash.to_string());
+                (EscrowStatus::Funded, Some(fund_transaction.hash))
+            }
+            herc20::State::IncorrectlyFunded {
+                deploy_transaction,
+                fund_transaction,
+                ..
+            } => {
+                transactions.insert("deploy".to_string(), deploy_transaction.hash.to_string());
+                transactions.insert("fund".to_string(), fund_transaction.hash.to_string());
+                (EscrowStatus::IncorrectlyFunded, Some(fund_transaction.hash))
+            }
+            herc20::State::Redeemed {
+                deploy_transaction,
+                fund_transaction,
+                redeem_transaction,
+                ..
+            } => {
+                transactions.insert("deploy".to_string(), deploy_transaction.hash.to_string());
+                transactions.insert("fund".to_string(), fund_transaction.hash.to_string());
+                transactions.insert("redeem".to_string(), redeem_transaction.hash.to_string());
+                (EscrowStatus::Redeemed, Some(redeem_transaction.hash))
+            }
+            herc20::State::Refunded {
+                deploy_transaction,
+                fund_transaction,
+                refund_transaction,
+                ..
+            } => {
+                transactions.insert("deploy".to_string(), deploy_transaction.hash.to_string());
+                transactions.insert("fund".to_string(), fund_transaction.hash.to_string());
+                transactions.insert("refund".to_string(), refund_transaction.hash.to_string());
+                (EscrowStatus::Refunded, Some(refund_transaction.hash))
+            }
+        };
+
+        Transaction {
+            transactions,
+            status,
+            confirmations: None,
+            latest_ethereum_transaction,
+            latest_bitcoin_transaction: None,
+        }
+    }
+}
+
+impl From<hbit::State> for Transaction {
+    fn from(state: hbit::State) -> Self {
+        let mut transactions = HashMap::new();
+
+        let (status, latest_bitcoin_transaction) = match state {
+            hbit::State::None => (EscrowStatus::None, None),
+            hbit::State::Funded {
+                fund_transaction, ..
+            } => {
+                transactions.insert("fund".to_string(), fund_transaction.txid().to_string());
+                (EscrowStatus::Funded, Some(fund_transaction))
+            }
+            hbit::State::IncorrectlyFunded {
+                fund_transaction, ..
+            } => {
+                transactions.insert("fund".to_string(), fund_transaction.txid().to_string());
+                (EscrowStatus::IncorrectlyFunded, Some(fund_transaction))
+            }
+            hbit::State::Redeemed {
+                fund_transaction,
+                redeem_transaction,
+                ..
+            } => {
+                transactions.insert("fund".to_string(), fund_transaction.txid().to_string());
+                transactions.insert("redeem".to_string(), redeem_transaction.txid().to_string());
+                (EscrowStatus::Redeemed, Some(redeem_transaction))
+            }
+            hbit::State::Refunded {
+                fund_transaction,
+                refund_transaction,
+                ..
+            } => {
+                transactions.insert("fund".to_string(), fund_transaction.txid().to_string());
+                transactions.insert("refund".to_string(), refund_transaction.txid().to_string());
+                (EscrowStatus::Refunded, Some(refund_transaction))
+            }
+        };
+
+        Transaction {
+            transactions,
+            status,
+            confirmations: None,
+            latest_ethereum_transaction: None,
+            latest_bitcoin_transaction,
+        }
+    }
+}
+
+/// The party receiving the Lightning payment adds a hold invoice for the
+/// secret hash generated by Alice.
+fn add_hold_invoice<A>(finalized_swap: &comit_ln::FinalizedSwap<A>) -> lnd::AddHoldInvoice {
+    lnd::AddHoldInvoice {
+        amount: finalized_swap.lightning_asset,
+        secret_hash: finalized_swap.secret_hash,
+        expiry: 3600,
+        cltv_expiry: finalized_swap.lightning_expiry.into(),
+        chain: Chain::Bitcoin,
+        network: bitcoin::Network::Regtest,
+        self_public_key: finalized_swap.lightning_redeem_identity,
+    }
+}
+
+/// The party receiving the Lightning payment settles the invoice by revealing
+/// the secret.
+fn settle_invoice<A>(
+    finalized_swap: &comit_ln::FinalizedSwap<A>,
+    secret: Secret,
+) -> lnd::SettleInvoice {
+    lnd::SettleInvoice {
+        secret,
+        chain: Chain::Bitcoin,
+        network: bitcoin::Network::Regtest,
+        self_public_key: finalized_swap.lightning_redeem_identity,
+    }
+}
+
+/// The party paying on Lightning pays the hold invoice of the counterparty.
+fn send_payment<A>(finalized_swap: &comit_ln::FinalizedSwap<A>) -> lnd::SendPayment {
+    lnd::SendPayment {
+        to_public_key: finalized_swap.lightning_redeem_identity,
+        amount: finalized_swap.lightning_asset,
+        secret_hash: finalized_swap.secret_hash,
+        final_cltv_delta: finalized_swap.lightning_expiry.into(),
+        chain: Chain::Bitcoin,
+        network: bitcoin::Network::Regtest,
+        self_public_key: finalized_swap.lightning_refund_identity,
+    }
+}
+
+/// Deploys the han HTLC, this also funds it.
+fn han_deploy_contract(
+    htlc_params: HtlcParams<Ethereum, asset::Ether, identity::Ethereum>,
+) -> ethereum::DeployContract {
+    let amount = htlc_params.asset.clone();
+    let eth_htlc = EtherHtlc::from(htlc_params);
+    let data = eth_htlc.into();
+    let gas_limit = EtherHtlc::deploy_tx_gas_limit();
+    let chain_id = ChainId::regtest();
+
+    ethereum::DeployContract {
+        data,
+        amount,
+        gas_limit,
+        chain_id,
+    }
+}
+
+/// Redeems the han HTLC by revealing the secret.
+fn han_redeem_contract(
+    htlc_location: htlc_location::Ethereum,
+    secret: Secret,
+) -> ethereum::CallContract {
+    let to = htlc_location;
+    let data = Some(Bytes::from(secret.into_raw_secret().to_vec()));
+    let gas_limit = EtherHtlc::redeem_tx_gas_limit();
+    let chain_id: ChainId = ChainId::regtest();
+    let min_block_timestamp = None;
+
+    ethereum::CallContract {
+        to,
+        data,
+        gas_limit,
+        chain_id,
+        min_block_timestamp,
+    }
+}
+
+/// Refunds the han HTLC, this is only possible after the expiry.
+fn han_refund_contract(
+    expiry: Timestamp,
+    htlc_location: htlc_location::Ethereum,
+) -> ethereum::CallContract {
+    let to = htlc_location;
+    let data = None;
+    let gas_limit = EtherHtlc::refund_tx_gas_limit();
+    let chain_id = ChainId::regtest();
+    let min_block_timestamp = Some(expiry);
+
+    ethereum::CallContract {
+        to,
+        data,
+        gas_limit,
+        chain_id,
+        min_block_timestamp,
+    }
+}
+
+/// Funds the hbit HTLC by sending bitcoin to its address.
+fn hbit_fund<A>(finalized_swap: &comit_ln::FinalizedHbitSwap<A>) -> SendToAddress {
+    let network = finalized_swap.bitcoin_network;
+    let to = finalized_swap.bitcoin_htlc().compute_address(network);
+    let amount = finalized_swap.bitcoin_asset;
+
+    SendToAddress {
+        to,
+        amount,
+        network,
+    }
+}
+
+/// Redeems the hbit HTLC by revealing the secret, the transaction is signed
+/// with our transient key.
+fn hbit_redeem<A>(
+    finalized_swap: &comit_ln::FinalizedHbitSwap<A>,
+    htlc_location: htlc_location::Bitcoin,
+    asset: asset::Bitcoin,
+    secret: Secret,
+) -> SpendOutput {
+    let htlc = finalized_swap.bitcoin_htlc();
+
+    SpendOutput {
+        output: PrimedInput::new(
+            htlc_location,
+            asset.into(),
+            htlc.unlock_with_secret(
+                &*crate::SECP,
+                finalized_swap.bitcoin_transient_key,
+                secret.into_raw_secret(),
+            ),
+        ),
+        network: finalized_swap.bitcoin_network,
+    }
+}
+
+/// Refunds the hbit HTLC, this is only possible after the expiry.
+fn hbit_refund<A>(
+    finalized_swap: &comit_ln::FinalizedHbitSwap<A>,
+    htlc_location: htlc_location::Bitcoin,
+    asset: asset::Bitcoin,
+) -> SpendOutput {
+    let htlc = finalized_swap.bitcoin_htlc();
+
+    SpendOutput {
+        output: PrimedInput::new(
+            htlc_location,
+            asset.into(),
+            htlc.unlock_after_timeout(&*crate::SECP, finalized_swap.bitcoin_transient_key),
+        ),
+        network: finalized_swap.bitcoin_network,
+    }
+}
+
+impl InitAction for AliceHanEthereumHalightBitcoinState {
+    type Output = lnd::AddHoldInvoice;
+
+    fn init_action(&self) -> Option<Self::Output> {
+        match self.beta_ledger_state {
+            halight::State::None => Some(add_hold_invoice(&self.finalized_swap)),
+            _ => None,
+        }
+    }
+}
+
+impl FundAction for AliceHanEthereumHalightBitcoinState {
+    type Output = ethereum::DeployContract;
+
+    fn fund_action(&self) -> Option<Self::Output> {
+        match self.beta_ledger_state {
+            halight::State::Opened(_) => {
+                Some(han_deploy_contract(self.finalized_swap.htlc_params()))
+            }
+            _ => None,
+        }
+    }
+}
+
+impl RedeemAction for AliceHanEthereumHalightBitcoinState {
+    type Output = lnd::SettleInvoice;
+
+    fn redeem_action(&self) -> Option<Self::Output> {
+        match self.beta_ledger_state {
+            halight::State::Accepted(_) => self
+                .finalized_swap
+                .secret
+                .map(|secret| settle_invoice(&self.finalized_swap, secret)),
+            _ => None,
+        }
+    }
+}
+
+impl RefundAction for AliceHanEthereumHalightBitcoinState {
+    type Output = ethereum::CallContract;
+
+    fn refund_action(&self) -> Option<Self::Output> {
+        match (&self.alpha_ledger_state, &self.beta_ledger_state) {
+            (LedgerState::Funded { htlc_location, .. }, halight::State::Accepted(_)) => Some(
+                han_refund_contract(self.finalized_swap.ethereum_expiry, *htlc_location),
+            ),
+            _ => None,
+        }
+    }
+}
+
+impl FundAction for BobHanEthereumHalightBitcoinState {
+    type Output = lnd::SendPayment;
+
+    fn fund_action(&self) -> Option<Self::Output> {
+        match (&self.alpha_ledger_state, &self.beta_ledger_state) {
+            (LedgerState::Funded { .. }, halight::State::Opened(_)) => {
+                Some(send_payment(&self.finalized_swap))
+            }
+            _ => None,
+        }
+    }
+}
+
+impl RedeemAction for BobHanEthereumHalightBitcoinState {
+    type Output = ethereum::CallContract;
+
+    fn redeem_action(&self) -> Option<Self::Output> {
+        match (&self.alpha_ledger_state, &self.beta_ledger_state) {
+            (
+                LedgerState::Funded { htlc_location, .. },
+                halight::State::Settled(Settled { secret }),
+            ) => Some(han_redeem_contract(*htlc_location, *secret)),
+            _ => None,
+        }
+    }
+}
+
+impl InitAction for AliceHerc20HalightBitcoinState {
+    type Output = lnd::AddHoldInvoice;
+
+    fn init_action(&self) -> Option<Self::Output> {
+        match self.beta_ledger_state {
+            halight::State::None => Some(add_hold_invoice(&self.finalized_swap)),
+            _ => None,
+        }
+    }
+}
+
+impl DeployAction for AliceHerc20HalightBitcoinState {
+    type Output = ethereum::DeployContract;
+
+    fn deploy_action(&self) -> Option<Self::Output> {
+        match (&self.alpha_ledger_state, &self.beta_ledger_state) {
+            (herc20::State::None, halight::State::Opened(_)) => {
+                Some(erc20::deploy_action(self.finalized_swap.htlc_params()))
+            }
+            _ => None,
+        }
+    }
+}
+
+impl FundAction for AliceHerc20HalightBitcoinState {
+    type Output = ethereum::CallContract;
+
+    fn fund_action(&self) -> Option<Self::Output> {
+        match (&self.alpha_ledger_state, &self.beta_ledger_state) {
+            (herc20::State::Deployed { htlc_location, .. }, halight::State::Opened(_)) => {
+                Some(erc20::fund_action(
+                    self.finalized_swap.htlc_params(),
+                    self.finalized_swap.ethereum_asset.token_contract,
+                    *htlc_location,
+                ))
+            }
+            _ => None,
+        }
+    }
+}
+
+impl RedeemAction for AliceHerc20HalightBitcoinState {
+    type Output = lnd::SettleInvoice;
+
+    fn redeem_action(&self) -> Option<Self::Output> {
+        match self.beta_ledger_state {
+            halight::State::Accepted(_) => self
+                .finalized_swap
+                .secret
+                .map(|secret| settle_invoice(&self.finalized_swap, secret)),
+            _ => None,
+        }
+    }
+}
+
+impl RefundAction for AliceHerc20HalightBitcoinState {
+    type Output = ethereum::CallContract;
+
+    fn refund_action(&self) -> Option<Self::Output> {
+        match (&self.alpha_ledger_state, &self.beta_ledger_state) {
+            (herc20::State::Funded { htlc_location, .. }, halight::State::Accepted(_)) => {
+                Some(erc20::refund_action(
+                    self.finalized_swap.ethereum_ledger.chain_id,
+                    self.finalized_swap.ethereum_expiry,
+                    *htlc_location,
+                ))
+            }
+            _ => None,
+        }
+    }
+}
+
+impl FundAction for BobHerc20HalightBitcoinState {
+    type Output = lnd::SendPayment;
+
+    fn fund_action(&self) -> Option<Self::Output> {
+        match (&self.alpha_ledger_state, &self.beta_ledger_state) {
+            (herc20::State::Funded { .. }, halight::State::Opened(_)) => {
+                Some(send_payment(&self.finalized_swap))
+            }
+            _ => None,
+        }
+    }
+}
+
+impl RedeemAction for BobHerc20HalightBitcoinState {
+    type Output = ethereum::CallContract;
+
+    fn redeem_action(&self) -> Option<Self::Output> {
+        match (&self.alpha_ledger_state, &self.beta_ledger_state) {
+            (
+                herc20::State::Funded { htlc_location, .. },
+                halight::State::Settled(Settled { secret }),
+            ) => Some(erc20::redeem_action(
+                *htlc_location,
+                *secret,
+                self.finalized_swap.ethereum_ledger.chain_id,
+            )),
+            _ => None,
+        }
+    }
+}
+
+impl FundAction for AliceHalightBitcoinHanEthereumState {
+    type Output = lnd::SendPayment;
+
+    fn fund_action(&self) -> Option<Self::Output> {
+        match self.alpha_ledger_state {
+            halight::State::Opened(_) => Some(send_payment(&self.finalized_swap)),
+            _ => None,
+        }
+    }
+}
+
+impl RedeemAction for AliceHalightBitcoinHanEthereumState {
+    type Output = ethereum::CallContract;
+
+    fn redeem_action(&self) -> Option<Self::Output> {
+        match (&self.alpha_ledger_state, &self.beta_ledger_state) {
+            (halight::State::Accepted(_), LedgerState::Funded { htlc_location, .. }) => self
+                .finalized_swap
+                .secret
+                .map(|secret| han_redeem_contract(*htlc_location, secret)),
+            _ => None,
+        }
+    }
+}
+
+impl InitAction for BobHalightBitcoinHanEthereumState {
+    type Output = lnd::AddHoldInvoice;
+
+    fn init_action(&self) -> Option<Self::Output> {
+        match self.alpha_ledger_state {
+            halight::State::None => Some(add_hold_invoice(&self.finalized_swap)),
+            _ => None,
+        }
+    }
+}
+
+impl FundAction for BobHalightBitcoinHanEthereumState {
+    type Output = ethereum::DeployContract;
+
+    fn fund_action(&self) -> Option<Self::Output> {
+        match (&self.alpha_ledger_state, &self.beta_ledger_state) {
+            (halight::State::Accepted(_), LedgerState::NotDeployed) => {
+                Some(han_deploy_contract(self.finalized_swap.htlc_params()))
+            }
+            _ => None,
+        }
+    }
+}
+
+impl RedeemAction for BobHalightBitcoinHanEthereumState {
+    type Output = lnd::SettleInvoice;
+
+    fn redeem_action(&self) -> Option<Self::Output> {
+        match (&self.alpha_ledger_state, &self.beta_ledger_state) {
+            (halight::State::Accepted(_), LedgerState::Redeemed { secret, .. }) => {
+                Some(settle_invoice(&self.finalized_swap, *secret))
+            }
+            _ => None,
+        }
+    }
+}
+
+impl RefundAction for BobHalightBitcoinHanEthereumState {
+    type Output = ethereum::CallContract;
+
+    fn refund_action(&self) -> Option<Self::Output> {
+        match (&self.alpha_ledger_state, &self.beta_ledger_state) {
+            (halight::State::Accepted(_), LedgerState::Funded { htlc_location, .. }) => Some(
+                han_refund_contract(self.finalized_swap.ethereum_expiry, *htlc_location),
+            ),
+            _ => None,
+        }
+    }
+}
+
+impl FundAction for AliceHalightBitcoinHerc20State {
+    type Output = lnd::SendPayment;
+
+    fn fund_action(&self) -> Option<Self::Output> {
+        match self.alpha_ledger_state {
+            halight::State::Opened(_) => Some(send_payment(&self.finalized_swap)),
+            _ => None,
+        }
+    }
+}
+
+impl RedeemAction for AliceHalightBitcoinHerc20State {
+    type Output = ethereum::CallContract;
+
+    fn redeem_action(&self) -> Option<Self::Output> {
+        match (&self.alpha_ledger_state, &self.beta_ledger_state) {
+            (halight::State::Accepted(_), herc20::State::Funded { htlc_location, .. }) => {
+                self.finalized_swap.secret.map(|secret| {
+                    erc20::redeem_action(
+                        *htlc_location,
+                        secret,
+                        self.finalized_swap.ethereum_ledger.chain_id,
+                    )
+                })
+            }
+            _ => None,
+        }
+    }
+}
+
+impl InitAction for BobHalightBitcoinHerc20State {
+    type Output = lnd::AddHoldInvoice;
+
+    fn init_action(&self) -> Option<Self::Output> {
+        match self.alpha_ledger_state {
+            halight::State::None => Some(add_hold_invoice(&self.finalized_swap)),
+            _ => None,
+        }
+    }
+}
+
+impl DeployAction for BobHalightBitcoinHerc20State {
+    type Output = ethereum::DeployContract;
+
+    fn deploy_action(&self) -> Option<Self::Output> {
+        match (&self.alpha_ledger_state, &self.beta_ledger_state) {
+            (halight::State::Accepted(_), herc20::State::None) => {
+                Some(erc20::deploy_action(self.finalized_swap.htlc_params()))
+            }
+            _ => None,
+        }
+    }
+}
+
+impl FundAction for BobHalightBitcoinHerc20State {
+    type Output = ethereum::CallContract;
+
+    fn fund_action(&self) -> Option<Self::Output> {
+        match (&self.alpha_ledger_state, &self.beta_ledger_state) {
+            (halight::State::Accepted(_), herc20::State::Deployed { htlc_location, .. }) => {
+                Some(erc20::fund_action(
+                    self.finalized_swap.htlc_params(),
+                    self.finalized_swap.ethereum_asset.token_contract,
+                    *htlc_location,
+                ))
+            }
+            _ => None,
+        }
+    }
+}
+
+impl RedeemAction for BobHalightBitcoinHerc20State {
+    type Output = lnd::SettleInvoice;
+
+    fn redeem_action(&self) -> Option<Self::Output> {
+        match (&self.alpha_ledger_state, &self.beta_ledger_state) {
+            (halight::State::Accepted(_), herc20::State::Redeemed { secret, .. }) => {
+                Some(settle_invoice(&self.finalized_swap, *secret))
+            }
+            _ => None,
+        }
+    }
+}
+
+impl RefundAction for BobHalightBitcoinHerc20State {
+    type Output = ethereum::CallContract;
+
+    fn refund_action(&self) -> Option<Self::Output> {
+        match (&self.alpha_ledger_state, &self.beta_ledger_state) {
+            (halight::State::Accepted(_), herc20::State::Funded { htlc_location, .. }) => {
+                Some(erc20::refund_action(
+                    self.finalized_swap.ethereum_ledger.chain_id,
+                    self.finalized_swap.ethereum_expiry,
+                    *htlc_location,
+                ))
+            }
+            _ => None,
+        }
+    }
+}
+
+impl FundAction for AliceHanEthereumHbitBitcoinState {
+    type Output = ethereum::DeployContract;
+
+    fn fund_action(&self) -> Option<Self::Output> {
+        match self.alpha_ledger_state {
+            LedgerState::NotDeployed => {
+                Some(han_deploy_contract(self.finalized_swap.htlc_params()))
+            }
+            _ => None,
+        }
+    }
+}
+
+impl RedeemAction for AliceHanEthereumHbitBitcoinState {
+    type Output = SpendOutput;
+
+    fn redeem_action(&self) -> Option<Self::Output> {
+        match self.beta_ledger_state {
+            hbit::State::Funded {
+                htlc_location,
+                asset,
+                ..
+            } => self
+                .finalized_swap
+                .secret
+                .map(|secret| hbit_redeem(&self.finalized_swap, htlc_location, asset, secret)),
+            _ => None,
+        }
+    }
+}
+
+impl RefundAction for AliceHanEthereumHbitBitcoinState {
+    type Output = ethereum::CallContract;
+
+    fn refund_action(&self) -> Option<Self::Output> {
+        match self.alpha_ledger_state {
+            LedgerState::Funded { htlc_location, .. } => Some(han_refund_contract(
+                self.finalized_swap.ethereum_expiry,
+                htlc_location,
+            )),
+            _ => None,
+        }
+    }
+}
+
+impl FundAction for BobHanEthereumHbitBitcoinState {
+    type Output = SendToAddress;
+
+    fn fund_action(&self) -> Option<Self::Output> {
+        match (&self.alpha_ledger_state, &self.beta_ledger_state) {
+            (LedgerState::Funded { .. }, hbit::State::None) => {
+                Some(hbit_fund(&self.finalized_swap))
+            }
+            _ => None,
+        }
+    }
+}
+
+impl RedeemAction for BobHanEthereumHbitBitcoinState {
+    type Output = ethereum::CallContract;
+
+    fn redeem_action(&self) -> Option<Self::Output> {
+        match (&self.alpha_ledger_state, &self.beta_ledger_state) {
+            (LedgerState::Funded { htlc_location, .. }, hbit::State::Redeemed { secret, .. }) => {
+                Some(han_redeem_contract(*htlc_location, *secret))
+            }
+            _ => None,
+        }
+    }
+}
+
+impl RefundAction for BobHanEthereumHbitBitcoinState {
+    type Output = SpendOutput;
+
+    fn refund_action(&self) -> Option<Self::Output> {
+        match self.beta_ledger_state {
+            hbit::State::Funded {
+                htlc_location,
+                asset,
+                ..
+            }
+            | hbit::State::IncorrectlyFunded {
+                htlc_location,
+                asset,
+                ..
+            } => Some(hbit_refund(&self.finalized_swap, htlc_location, asset)),
+            _ => None,
+        }
+    }
+}
+
+impl DeployAction for AliceHerc20HbitBitcoinState {
+    type Output = ethereum::DeployContract;
+
+    fn deploy_action(&self) -> Option<Self::Output> {
+        match self.alpha_ledger_state {
+            herc20::State::None => Some(erc20::deploy_action(self.finalized_swap.htlc_params())),
+            _ => None,
+        }
+    }
+}
+
+impl FundAction for AliceHerc20HbitBitcoinState {
+    type Output = ethereum::CallContract;
+
+    fn fund_action(&self) -> Option<Self::Output> {
+        match self.alpha_ledger_state {
+            herc20::State::Deployed { htlc_location, .. } => Some(erc20::fund_action(
+                self.finalized_swap.htlc_params(),
+                self.finalized_swap.ethereum_asset.token_contract,
+                htlc_location,
+            )),
+            _ => None,
+        }
+    }
+}
+
+impl RedeemAction for AliceHerc20HbitBitcoinState {
+    type Output = SpendOutput;
+
+    fn redeem_action(&self) -> Option<Self::Output> {
+        match self.beta_ledger_state {
+            hbit::State::Funded {
+                htlc_location,
+                asset,
+                ..
+            } => self
+                .finalized_swap
+                .secret
+                .map(|secret| hbit_redeem(&self.finalized_swap, htlc_location, asset, secret)),
+            _ => None,
+        }
+    }
+}
+
+impl RefundAction for AliceHerc20HbitBitcoinState {
+    type Output = ethereum::CallContract;
+
+    fn refund_action(&self) -> Option<Self::Output> {
+        match self.alpha_ledger_state {
+            herc20::State::Funded { htlc_location, .. } => Some(erc20::refund_action(
+                self.finalized_swap.ethereum_ledger.chain_id,
+                self.finalized_swap.ethereum_expiry,
+                htlc_location,
+            )),
+            _ => None,
+        }
+    }
+}
 
-                Transaction {
-                    transactions,
-                    status: EscrowStatus::Deployed,
-                }
-            }
-            LedgerState::Funded {
-                deploy_transaction,
-                fund_transaction,
-                ..
-            } => {
-                let mut transactions = HashMap::new();
-                transactions.insert("deploy".to_string(), deploy_transaction.hash.to_string());
-                transactions.insert("fund".to_string(), fund_transaction.hash.to_string());
-                Transaction {
-                    transactions,
-                    status: EscrowStatus::Funded,
-                }
+impl FundAction for BobHerc20HbitBitcoinState {
+    type Output = SendToAddress;
+
+    fn fund_action(&self) -> Option<Self::Output> {
+        match (&self.alpha_ledger_state, &self.beta_ledger_state) {
+            (herc20::State::Funded { .. }, hbit::State::None) => {
+                Some(hbit_fund(&self.finalized_swap))
             }
-            LedgerState::IncorrectlyFunded {
-                deploy_transaction,
-                fund_transaction,
-                ..
-            } => {
-                let mut transactions = HashMap::new();
-                transactions.insert("deploy".to_string(), deploy_transaction.hash.to_string());
-                transactions.insert("fund".to_string(), fund_transaction.hash.to_string());
-                Transaction {
-                    transactions,
-                    status: EscrowStatus::IncorrectlyFunded,
-                }
+            _ => None,
+        }
+    }
+}
+
+impl RedeemAction for BobHerc20HbitBitcoinState {
+    type Output = ethereum::CallContract;
+
+    fn redeem_action(&self) -> Option<Self::Output> {
+        match (&self.alpha_ledger_state, &self.beta_ledger_state) {
+            (herc20::State::Funded { htlc_location, .. }, hbit::State::Redeemed { secret, .. }) => {
+                Some(erc20::redeem_action(
+                    *htlc_location,
+                    *secret,
+                    self.finalized_swap.ethereum_ledger.chain_id,
+                ))
             }
-            LedgerState::Redeemed {
-                deploy_transaction,
-                fund_transaction,
-                redeem_transaction,
+            _ => None,
+        }
+    }
+}
+
+impl RefundAction for BobHerc20HbitBitcoinState {
+    type Output = SpendOutput;
+
+    fn refund_action(&self) -> Option<Self::Output> {
+        match self.beta_ledger_state {
+            hbit::State::Funded {
+                htlc_location,
+                asset,
                 ..
-            } => {
-                let mut transactions = HashMap::new();
-                transactions.insert("deploy".to_string(), deploy_transaction.hash.to_string());
-                transactions.insert("fund".to_string(), fund_transaction.hash.to_string());
-                transactions.insert("redeem".to_string(), redeem_transaction.hash.to_string());
-                Transaction {
-                    transactions,
-                    status: EscrowStatus::Redeemed,
-                }
             }
-            LedgerState::Refunded {
-                deploy_transaction,
-                fund_transaction,
-                refund_transaction,
+            | hbit::State::IncorrectlyFunded {
+                htlc_location,
+                asset,
                 ..
-            } => {
-                let mut transactions = HashMap::new();
-                transactions.insert("deploy".to_string(), deploy_transaction.hash.to_string());
-                transactions.insert("fund".to_string(), fund_transaction.hash.to_string());
-                transactions.insert("refund".to_string(), refund_transaction.hash.to_string());
-                Transaction {
-                    transactions,
-                    status: EscrowStatus::Refunded,
-                }
-            }
+            } => Some(hbit_refund(&self.finalized_swap, htlc_location, asset)),
+            _ => None,
         }
     }
 }
 
-impl From<halight::State> for Transaction {
-    fn from(state: halight::State) -> Self {
-        match state {
-            halight::State::None => Transaction {
-                transactions: HashMap::new(),
-                status: EscrowStatus::None,
-            },
-            halight::State::Opened(_) => Transaction {
-                transactions: HashMap::new(),
-                status: EscrowStatus::Initialized,
-            },
-            halight::State::Accepted(_) => Transaction {
-                transactions: HashMap::new(),
-                status: EscrowStatus::Funded,
-            },
-            halight::State::Settled(_) => Transaction {
-                transactions: HashMap::new(),
-                status: EscrowStatus::Redeemed,
-            },
-            halight::State::Cancelled(_) => Transaction {
-                transactions: HashMap::new(),
-                status: EscrowStatus::Refunded,
-            },
+impl FundAction for AliceHbitBitcoinHanEthereumState {
+    type Output = SendToAddress;
+
+    fn fund_action(&self) -> Option<Self::Output> {
+        match self.alpha_ledger_state {
+            hbit::State::None => Some(hbit_fund(&self.finalized_swap)),
+            _ => None,
         }
     }
 }
 
-impl InitAction for AliceHanEthereumHalightBitcoinState {
-    type Output = lnd::AddHoldInvoice;
+impl RedeemAction for AliceHbitBitcoinHanEthereumState {
+    type Output = ethereum::CallContract;
 
-    fn init_action(&self) -> Option<Self::Output> {
+    fn redeem_action(&self) -> Option<Self::Output> {
         match self.beta_ledger_state {
-            halight::State::None => {
-                let amount = self.finalized_swap.beta_asset;
-                let secret_hash = self.finalized_swap.secret_hash;
-                let expiry = 3600;
-                let cltv_expiry = self.finalized_swap.beta_expiry.into();
-                let chain = Chain::Bitcoin;
-                let network = bitcoin::Network::Regtest;
-                let self_public_key = self.finalized_swap.beta_ledger_redeem_identity;
-
-                Some(lnd::AddHoldInvoice {
-                    amount,
-                    secret_hash,
-                    expiry,
-                    cltv_expiry,
-                    chain,
-                    network,
-                    self_public_key,
-                })
+            LedgerState::Funded { htlc_location, .. } => self
+                .finalized_swap
+                .secret
+                .map(|secret| han_redeem_contract(htlc_location, secret)),
+            _ => None,
+        }
+    }
+}
+
+impl RefundAction for AliceHbitBitcoinHanEthereumState {
+    type Output = SpendOutput;
+
+    fn refund_action(&self) -> Option<Self::Output> {
+        match self.alpha_ledger_state {
+            hbit::State::Funded {
+                htlc_location,
+                asset,
+                ..
             }
+            | hbit::State::IncorrectlyFunded {
+                htlc_location,
+                asset,
+                ..
+            } => Some(hbit_refund(&self.finalized_swap, htlc_location, asset)),
             _ => None,
         }
     }
 }
 
-impl FundAction for AliceHanEthereumHalightBitcoinState {
+impl FundAction for BobHbitBitcoinHanEthereumState {
     type Output = ethereum::DeployContract;
 
     fn fund_action(&self) -> Option<Self::Output> {
-        match self.beta_ledger_state {
-            halight::State::Opened(_) => {
-                let eth_htlc = self.finalized_swap.han_params();
-                let data = eth_htlc.into();
-                let amount = self.finalized_swap.alpha_asset.clone();
-                let gas_limit = EtherHtlc::deploy_tx_gas_limit();
-                let chain_id = ChainId::regtest();
-
-                Some(ethereum::DeployContract {
-                    data,
-                    amount,
-                    gas_limit,
-                    chain_id,
-                })
+        match (&self.alpha_ledger_state, &self.beta_ledger_state) {
+            (hbit::State::Funded { .. }, LedgerState::NotDeployed) => {
+                Some(han_deploy_contract(self.finalized_swap.htlc_params()))
             }
             _ => None,
         }
     }
 }
 
-impl RedeemAction for AliceHanEthereumHalightBitcoinState {
-    type Output = lnd::SettleInvoice;
+impl RedeemAction for BobHbitBitcoinHanEthereumState {
+    type Output = SpendOutput;
+
+    fn redeem_action(&self) -> Option<Self::Output> {
+        match (&self.alpha_ledger_state, &self.beta_ledger_state) {
+            (
+                hbit::State::Funded {
+                    htlc_location,
+                    asset,
+                    ..
+                },
+                LedgerState::Redeemed { secret, .. },
+            ) => Some(hbit_redeem(
+                &self.finalized_swap,
+                *htlc_location,
+                *asset,
+                *secret,
+            )),
+            _ => None,
+        }
+    }
+}
+
+impl RefundAction for BobHbitBitcoinHanEthereumState {
+    type Output = ethereum::CallContract;
+
+    fn refund_action(&self) -> Option<Self::Output> {
+        match self.beta_ledger_state {
+            LedgerState::Funded { htlc_location, .. } => Some(han_refund_contract(
+                self.finalized_swap.ethereum_expiry,
+                htlc_location,
+            )),
+            _ => None,
+        }
+    }
+}
+
+impl FundAction for AliceHbitBitcoinHerc20State {
+    type Output = SendToAddress;
+
+    fn fund_action(&self) -> Option<Self::Output> {
+        match self.alpha_ledger_state {
+            hbit::State::None => Some(hbit_fund(&self.finalized_swap)),
+            _ => None,
+        }
+    }
+}
+
+impl RedeemAction for AliceHbitBitcoinHerc20State {
+    type Output = ethereum::CallContract;
 
     fn redeem_action(&self) -> Option<Self::Output> {
         match self.beta_ledger_state {
-            halight::State::Accepted(_) => {
-                let secret = self.finalized_swap.secret.unwrap(); // unwrap ok since only Alice calls this.
-                let chain = Chain::Bitcoin;
-                let network = bitcoin::Network::Regtest;
-                let self_public_key = self.finalized_swap.beta_ledger_redeem_identity;
-
-                Some(lnd::SettleInvoice {
-                    secret,
-                    chain,
-                    network,
-                    self_public_key,
+            herc20::State::Funded { htlc_location, .. } => {
+                self.finalized_swap.secret.map(|secret| {
+                    erc20::redeem_action(
+                        htlc_location,
+                        secret,
+                        self.finalized_swap.ethereum_ledger.chain_id,
+                    )
                 })
             }
             _ => None,
@@ -576,83 +2711,89 @@ impl RedeemAction for AliceHanEthereumHalightBitcoinState {
     }
 }
 
-impl RefundAction for AliceHanEthereumHalightBitcoinState {
-    type Output = ethereum::CallContract;
+impl RefundAction for AliceHbitBitcoinHerc20State {
+    type Output = SpendOutput;
 
     fn refund_action(&self) -> Option<Self::Output> {
+        match self.alpha_ledger_state {
+            hbit::State::Funded {
+                htlc_location,
+                asset,
+                ..
+            }
+            | hbit::State::IncorrectlyFunded {
+                htlc_location,
+                asset,
+                ..
+            } => Some(hbit_refund(&self.finalized_swap, htlc_location, asset)),
+            _ => None,
+        }
+    }
+}
+
+impl DeployAction for BobHbitBitcoinHerc20State {
+    type Output = ethereum::DeployContract;
+
+    fn deploy_action(&self) -> Option<Self::Output> {
         match (&self.alpha_ledger_state, &self.beta_ledger_state) {
-            (LedgerState::Funded { htlc_location, .. }, halight::State::Accepted(_)) => {
-                let to = *htlc_location;
-                let data = None;
-                let gas_limit = EtherHtlc::refund_tx_gas_limit();
-                let chain_id = ChainId::regtest();
-                let min_block_timestamp = Some(self.finalized_swap.alpha_expiry);
-
-                Some(ethereum::CallContract {
-                    to,
-                    data,
-                    gas_limit,
-                    chain_id,
-                    min_block_timestamp,
-                })
+            (hbit::State::Funded { .. }, herc20::State::None) => {
+                Some(erc20::deploy_action(self.finalized_swap.htlc_params()))
             }
             _ => None,
         }
     }
 }
 
-impl FundAction for BobHanEthereumHalightBitcoinState {
-    type Output = lnd::SendPayment;
+impl FundAction for BobHbitBitcoinHerc20State {
+    type Output = ethereum::CallContract;
 
     fn fund_action(&self) -> Option<Self::Output> {
         match (&self.alpha_ledger_state, &self.beta_ledger_state) {
-            (LedgerState::Funded { .. }, halight::State::Opened(_)) => {
-                let to_public_key = self.finalized_swap.beta_ledger_redeem_identity;
-                let amount = self.finalized_swap.beta_asset;
-                let secret_hash = self.finalized_swap.secret_hash;
-                let final_cltv_delta = self.finalized_swap.beta_expiry.into();
-                let chain = Chain::Bitcoin;
-                let network = bitcoin::Network::Regtest;
-                let self_public_key = self.finalized_swap.beta_ledger_refund_identity;
-
-                Some(lnd::SendPayment {
-                    to_public_key,
-                    amount,
-                    secret_hash,
-                    final_cltv_delta,
-                    chain,
-                    network,
-                    self_public_key,
-                })
+            (hbit::State::Funded { .. }, herc20::State::Deployed { htlc_location, .. }) => {
+                Some(erc20::fund_action(
+                    self.finalized_swap.htlc_params(),
+                    self.finalized_swap.ethereum_asset.token_contract,
+                    *htlc_location,
+                ))
             }
             _ => None,
         }
     }
 }
 
-impl RedeemAction for BobHanEthereumHalightBitcoinState {
-    type Output = ethereum::CallContract;
+impl RedeemAction for BobHbitBitcoinHerc20State {
+    type Output = SpendOutput;
 
     fn redeem_action(&self) -> Option<Self::Output> {
         match (&self.alpha_ledger_state, &self.beta_ledger_state) {
             (
-                LedgerState::Funded { htlc_location, .. },
-                halight::State::Settled(Settled { secret }),
-            ) => {
-                let to = *htlc_location;
-                let data = Some(Bytes::from(secret.into_raw_secret().to_vec()));
-                let gas_limit = EtherHtlc::redeem_tx_gas_limit();
-                let chain_id: ChainId = ChainId::regtest();
-                let min_block_timestamp = None;
-
-                Some(ethereum::CallContract {
-                    to,
-                    data,
-                    gas_limit,
-                    chain_id,
-                    min_block_timestamp,
-                })
-            }
+                hbit::State::Funded {
+                    htlc_location,
+                    asset,
+                    ..
+                },
+                herc20::State::Redeemed { secret, .. },
+            ) => Some(hbit_redeem(
+                &self.finalized_swap,
+                *htlc_location,
+                *asset,
+                *secret,
+            )),
+            _ => None,
+        }
+    }
+}
+
+impl RefundAction for BobHbitBitcoinHerc20State {
+    type Output = ethereum::CallContract;
+
+    fn refund_action(&self) -> Option<Self::Output> {
+        match self.beta_ledger_state {
+            herc20::State::Funded { htlc_location, .. } => Some(erc20::refund_action(
+                self.finalized_swap.ethereum_ledger.chain_id,
+                self.finalized_swap.ethereum_expiry,
+                htlc_location,
+            )),
             _ => None,
         }
     }
@@ -667,43 +2808,66 @@ pub async fn action_init(swap_id: LocalSwapId, facade: Facade) -> Result<impl Re
         .map_err(into_rejection)
 }
 
-#[allow(clippy::unit_arg, clippy::let_unit_value, clippy::cognitive_complexity)]
 async fn handle_action_init(
     swap_id: LocalSwapId,
     facade: Facade,
 ) -> anyhow::Result<ActionResponseBody> {
-    let alpha_ledger_state: LedgerState<
-        asset::Ether,
-        htlc_location::Ethereum,
-        transaction::Ethereum,
-    > = facade
-        .alpha_ledger_states
-        .get(&swap_id)
+    let state = load_swap_state(&facade, swap_id)
         .await?
-        .ok_or_else(|| anyhow::anyhow!("alpha ledger state not found for {}", swap_id))?;
+        .ok_or_else(|| anyhow::anyhow!("swap with id {} not found", swap_id))?;
 
-    let beta_ledger_state: halight::State = facade
-        .beta_ledger_states
-        .get(&swap_id)
-        .await?
-        .ok_or_else(|| anyhow::anyhow!("beta ledger state not found for {}", swap_id))?;
+    let maybe_response = match state {
+        SwapState::AliceHanEthereumHalightBitcoin(state) => {
+            state.init_action().map(ActionResponseBody::from)
+        }
+        SwapState::AliceHerc20HalightBitcoin(state) => {
+            state.init_action().map(ActionResponseBody::from)
+        }
+        SwapState::BobHalightBitcoinHanEthereum(state) => {
+            state.init_action().map(ActionResponseBody::from)
+        }
+        SwapState::BobHalightBitcoinHerc20(state) => {
+            state.init_action().map(ActionResponseBody::from)
+        }
+        _ => None,
+    };
+
+    let response = maybe_response.ok_or(LndActionError::NotFound)?;
 
-    let finalized_swap = facade
-        .get_finalized_swap(swap_id)
+    Ok(response)
+}
+
+#[allow(clippy::needless_pass_by_value)]
+pub async fn action_deploy(swap_id: LocalSwapId, facade: Facade) -> Result<impl Reply, Rejection> {
+    handle_action_deploy(swap_id, facade)
         .await
-        .ok_or_else(|| anyhow::anyhow!("swap with id {} not found", swap_id))?;
+        .map(|body| warp::reply::json(&body))
+        .map_err(problem::from_anyhow)
+        .map_err(into_rejection)
+}
 
-    let maybe_response = match finalized_swap.role {
-        Role::Alice => {
-            let state = AliceHanEthereumHalightBitcoinState {
-                alpha_ledger_state,
-                beta_ledger_state,
-                finalized_swap,
-            };
+async fn handle_action_deploy(
+    swap_id: LocalSwapId,
+    facade: Facade,
+) -> anyhow::Result<ActionResponseBody> {
+    let state = load_swap_state(&facade, swap_id)
+        .await?
+        .ok_or_else(|| anyhow::anyhow!("swap with id {} not found", swap_id))?;
 
-            state.init_action().map(ActionResponseBody::from)
+    let maybe_response = match state {
+        SwapState::AliceHerc20HalightBitcoin(state) => {
+            state.deploy_action().map(ActionResponseBody::from)
+        }
+        SwapState::BobHalightBitcoinHerc20(state) => {
+            state.deploy_action().map(ActionResponseBody::from)
         }
-        Role::Bob => None,
+        SwapState::AliceHerc20HbitBitcoin(state) => {
+            state.deploy_action().map(ActionResponseBody::from)
+        }
+        SwapState::BobHbitBitcoinHerc20(state) => {
+            state.deploy_action().map(ActionResponseBody::from)
+        }
+        _ => None,
     };
 
     let response = maybe_response.ok_or(LndActionError::NotFound)?;
@@ -720,51 +2884,59 @@ pub async fn action_fund(swap_id: LocalSwapId, facade: Facade) -> Result<impl Re
         .map_err(into_rejection)
 }
 
-#[allow(clippy::unit_arg, clippy::let_unit_value, clippy::cognitive_complexity)]
 async fn handle_action_fund(
     swap_id: LocalSwapId,
     facade: Facade,
 ) -> anyhow::Result<ActionResponseBody> {
-    let alpha_ledger_state: LedgerState<
-        asset::Ether,
-        htlc_location::Ethereum,
-        transaction::Ethereum,
-    > = facade
-        .alpha_ledger_states
-        .get(&swap_id)
-        .await?
-        .ok_or_else(|| anyhow::anyhow!("alpha ledger state not found for {}", swap_id))?;
-
-    let beta_ledger_state: halight::State = facade
-        .beta_ledger_states
-        .get(&swap_id)
+    let state = load_swap_state(&facade, swap_id)
         .await?
-        .ok_or_else(|| anyhow::anyhow!("beta ledger state not found for {}", swap_id))?;
-
-    let finalized_swap = facade
-        .get_finalized_swap(swap_id)
-        .await
         .ok_or_else(|| anyhow::anyhow!("swap with id {} not found", swap_id))?;
 
-    let maybe_response = match finalized_swap.role {
-        Role::Alice => {
-            let state = AliceHanEthereumHalightBitcoinState {
-                alpha_ledger_state,
-                beta_ledger_state,
-                finalized_swap,
-            };
-
+    let maybe_response = match state {
+        SwapState::AliceHanEthereumHalightBitcoin(state) => {
             state.fund_action().map(ActionResponseBody::from)
         }
-        Role::Bob => {
-            let state = BobHanEthereumHalightBitcoinState {
-                alpha_ledger_state,
-                beta_ledger_state,
-                finalized_swap,
-            };
-
+        SwapState::BobHanEthereumHalightBitcoin(state) => {
+            state.fund_action().map(ActionResponseBody::from)
+        }
+        SwapState::AliceHerc20HalightBitcoin(state) => {
+            state.fund_action().map(ActionResponseBody::from)
+        }
+        SwapState::BobHerc20HalightBitcoin(state) => {
+            state.fund_action().map(ActionResponseBody::from)
+        }
+        SwapState::AliceHalightBitcoinHanEthereum(state) => {
+            state.fund_action().map(ActionResponseBody::from)
+        }
+        SwapState::BobHalightBitcoinHanEthereum(state) => {
+            state.fund_action().map(ActionResponseBody::from)
+        }
+        SwapState::AliceHalightBitcoinHerc20(state) => {
+            state.fund_action().map(ActionResponseBody::from)
+        }
+        SwapState::BobHalightBitcoinHerc20(state) => {
+            state.fund_action().map(ActionResponseBody::from)
+        }
+        SwapState::AliceHanEthereumHbitBitcoin(state) => {
+            state.fund_action().map(ActionResponseBody::from)
+        }
+        SwapState::BobHanEthereumHbitBitcoin(state) => {
+            state.fund_action().map(ActionResponseBody::from)
+        }
+        SwapState::AliceHerc20HbitBitcoin(state) => {
+            state.fund_action().map(ActionResponseBody::from)
+        }
+        SwapState::BobHerc20HbitBitcoin(state) => state.fund_action().map(ActionResponseBody::from),
+        SwapState::AliceHbitBitcoinHanEthereum(state) => {
+            state.fund_action().map(ActionResponseBody::from)
+        }
+        SwapState::BobHbitBitcoinHanEthereum(state) => {
+            state.fund_action().map(ActionResponseBody::from)
+        }
+        SwapState::AliceHbitBitcoinHerc20(state) => {
             state.fund_action().map(ActionResponseBody::from)
         }
+        SwapState::BobHbitBitcoinHerc20(state) => state.fund_action().map(ActionResponseBody::from),
     };
 
     let response = maybe_response.ok_or(LndActionError::NotFound)?;
@@ -773,59 +2945,82 @@ async fn handle_action_fund(
 }
 
 #[allow(clippy::needless_pass_by_value)]
-pub async fn action_redeem(swap_id: LocalSwapId, facade: Facade) -> Result<impl Reply, Rejection> {
-    handle_action_redeem(swap_id, facade)
+pub async fn action_redeem(
+    swap_id: LocalSwapId,
+    query_params: ActionExecutionParameters,
+    facade: Facade,
+) -> Result<impl Reply, Rejection> {
+    handle_action_redeem(swap_id, query_params, facade)
         .await
         .map(|body| warp::reply::json(&body))
         .map_err(problem::from_anyhow)
         .map_err(into_rejection)
 }
 
-#[allow(clippy::unit_arg, clippy::let_unit_value, clippy::cognitive_complexity)]
+/// Spending a Bitcoin HTLC requires the address to send the bitcoin to and
+/// the fee to be given as query parameters.
 async fn handle_action_redeem(
     swap_id: LocalSwapId,
+    query_params: ActionExecutionParameters,
     facade: Facade,
 ) -> anyhow::Result<ActionResponseBody> {
-    let alpha_ledger_state: LedgerState<
-        asset::Ether,
-        htlc_location::Ethereum,
-        transaction::Ethereum,
-    > = facade
-        .alpha_ledger_states
-        .get(&swap_id)
+    let state = load_swap_state(&facade, swap_id)
         .await?
-        .ok_or_else(|| anyhow::anyhow!("alpha ledger state not found for {}", swap_id))?;
-
-    let beta_ledger_state: halight::State = facade
-        .beta_ledger_states
-        .get(&swap_id)
-        .await?
-        .ok_or_else(|| anyhow::anyhow!("beta ledger state not found for {}", swap_id))?;
-
-    let finalized_swap = facade
-        .get_finalized_swap(swap_id)
-        .await
         .ok_or_else(|| anyhow::anyhow!("swap with id {} not found", swap_id))?;
 
-    let maybe_response = match finalized_swap.role {
-        Role::Alice => {
-            let state = AliceHanEthereumHalightBitcoinState {
-                alpha_ledger_state,
-                beta_ledger_state,
-                finalized_swap,
-            };
-
+    let maybe_response = match state {
+        SwapState::AliceHanEthereumHalightBitcoin(state) => {
             state.redeem_action().map(ActionResponseBody::from)
         }
-        Role::Bob => {
-            let state = BobHanEthereumHalightBitcoinState {
-                alpha_ledger_state,
-                beta_ledger_state,
-                finalized_swap,
-            };
-
+        SwapState::BobHanEthereumHalightBitcoin(state) => {
+            state.redeem_action().map(ActionResponseBody::from)
+        }
+        SwapState::AliceHerc20HalightBitcoin(state) => {
+            state.redeem_action().map(ActionResponseBody::from)
+        }
+        SwapState::BobHerc20HalightBitcoin(state) => {
+            state.redeem_action().map(ActionResponseBody::from)
+        }
+        SwapState::AliceHalightBitcoinHanEthereum(state) => {
+            state.redeem_action().map(ActionResponseBody::from)
+        }
+        SwapState::BobHalightBitcoinHanEthereum(state) => {
+            state.redeem_action().map(ActionResponseBody::from)
+        }
+        SwapState::AliceHalightBitcoinHerc20(state) => {
             state.redeem_action().map(ActionResponseBody::from)
         }
+        SwapState::BobHalightBitcoinHerc20(state) => {
+            state.redeem_action().map(ActionResponseBody::from)
+        }
+        SwapState::AliceHanEthereumHbitBitcoin(state) => state
+            .redeem_action()
+            .map(|action| action.into_response_payload(query_params))
+            .transpose()?,
+        SwapState::BobHanEthereumHbitBitcoin(state) => {
+            state.redeem_action().map(ActionResponseBody::from)
+        }
+        SwapState::AliceHerc20HbitBitcoin(state) => state
+            .redeem_action()
+            .map(|action| action.into_response_payload(query_params))
+            .transpose()?,
+        SwapState::BobHerc20HbitBitcoin(state) => {
+            state.redeem_action().map(ActionResponseBody::from)
+        }
+        SwapState::AliceHbitBitcoinHanEthereum(state) => {
+            state.redeem_action().map(ActionResponseBody::from)
+        }
+        SwapState::BobHbitBitcoinHanEthereum(state) => state
+            .redeem_action()
+            .map(|action| action.into_response_payload(query_params))
+            .transpose()?,
+        SwapState::AliceHbitBitcoinHerc20(state) => {
+            state.redeem_action().map(ActionResponseBody::from)
+        }
+        SwapState::BobHbitBitcoinHerc20(state) => state
+            .redeem_action()
+            .map(|action| action.into_response_payload(query_params))
+            .transpose()?,
     };
 
     let response = maybe_response.ok_or(LndActionError::NotFound)?;
@@ -834,48 +3029,68 @@ async fn handle_action_redeem(
 }
 
 #[allow(clippy::needless_pass_by_value)]
-pub async fn action_refund(swap_id: LocalSwapId, facade: Facade) -> Result<impl Reply, Rejection> {
-    handle_action_refund(swap_id, facade)
+pub async fn action_refund(
+    swap_id: LocalSwapId,
+    query_params: ActionExecutionParameters,
+    facade: Facade,
+) -> Result<impl Reply, Rejection> {
+    handle_action_refund(swap_id, query_params, facade)
         .await
         .map(|body| warp::reply::json(&body))
         .map_err(problem::from_anyhow)
         .map_err(into_rejection)
 }
 
-#[allow(clippy::unit_arg, clippy::let_unit_value, clippy::cognitive_complexity)]
+/// Spending a Bitcoin HTLC requires the address to send the bitcoin to and
+/// the fee to be given as query parameters.
 async fn handle_action_refund(
     swap_id: LocalSwapId,
+    query_params: ActionExecutionParameters,
     facade: Facade,
 ) -> anyhow::Result<ActionResponseBody> {
-    let alpha_ledger_state: LedgerState<
-        asset::Ether,
-        htlc_location::Ethereum,
-        transaction::Ethereum,
-    > = facade
-        .alpha_ledger_states
-        .get(&swap_id)
-        .await?
-        .ok_or_else(|| anyhow::anyhow!("alpha ledger state not found for {}", swap_id))?;
-
-    let beta_ledger_state: halight::State = facade
-        .beta_ledger_states
-        .get(&swap_id)
+    let state = load_swap_state(&facade, swap_id)
         .await?
-        .ok_or_else(|| anyhow::anyhow!("beta ledger state not found for {}", swap_id))?;
-
-    let finalized_swap = facade
-        .get_finalized_swap(swap_id)
-        .await
         .ok_or_else(|| anyhow::anyhow!("swap with id {} not found", swap_id))?;
 
-    let maybe_response = match finalized_swap.role {
-        Role::Alice => {
-            let state = AliceHanEthereumHalightBitcoinState {
-                alpha_ledger_state,
-                beta_ledger_state,
-                finalized_swap,
-            };
-
+    let maybe_response = match state {
+        SwapState::AliceHanEthereumHalightBitcoin(state) => {
+            state.refund_action().map(ActionResponseBody::from)
+        }
+        SwapState::AliceHerc20HalightBitcoin(state) => {
+            state.refund_action().map(ActionResponseBody::from)
+        }
+        SwapState::BobHalightBitcoinHanEthereum(state) => {
+            state.refund_action().map(ActionResponseBody::from)
+        }
+        SwapState::BobHalightBitcoinHerc20(state) => {
+            state.refund_action().map(ActionResponseBody::from)
+        }
+        SwapState::AliceHanEthereumHbitBitcoin(state) => {
+            state.refund_action().map(ActionResponseBody::from)
+        }
+        SwapState::BobHanEthereumHbitBitcoin(state) => state
+            .refund_action()
+            .map(|action| action.into_response_payload(query_params))
+            .transpose()?,
+        SwapState::AliceHerc20HbitBitcoin(state) => {
+            state.refund_action().map(ActionResponseBody::from)
+        }
+        SwapState::BobHerc20HbitBitcoin(state) => state
+            .refund_action()
+            .map(|action| action.into_response_payload(query_params))
+            .transpose()?,
+        SwapState::AliceHbitBitcoinHanEthereum(state) => state
+            .refund_action()
+            .map(|action| action.into_response_payload(query_params))
+            .transpose()?,
+        SwapState::BobHbitBitcoinHanEthereum(state) => {
+            state.refund_action().map(ActionResponseBody::from)
+        }
+        SwapState::AliceHbitBitcoinHerc20(state) => state
+            .refund_action()
+            .map(|action| action.into_response_payload(query_params))
+            .transpose()?,
+        SwapState::BobHbitBitcoinHerc20(state) => {
             state.refund_action().map(ActionResponseBody::from)
         }
         _ => None,
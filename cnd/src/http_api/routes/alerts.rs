@@ -0,0 +1,41 @@
+use crate::{expiry_monitor::Alert, swap_protocols::Facade};
+use serde::Serialize;
+use uuid::Uuid;
+use warp::{Rejection, Reply};
+
+#[derive(Serialize, Debug)]
+pub struct AlertsResource {
+    alerts: Vec<SwapAlert>,
+}
+
+#[derive(Serialize, Debug)]
+pub struct SwapAlert {
+    swap_id: Uuid,
+    #[serde(flatten)]
+    alert: Alert,
+}
+
+#[allow(clippy::needless_pass_by_value)]
+pub async fn get_alerts(facade: Facade) -> Result<impl Reply, Rejection> {
+    let mut alerts = facade
+        .expiry_alerts
+        .all()
+        .await
+        .into_iter()
+        .flat_map(|(swap_id, alerts)| {
+            alerts
+                .into_iter()
+                .map(move |alert| SwapAlert { swap_id, alert })
+        })
+        .collect::<Vec<_>>();
+    // The most pressing alerts first, expired HTLCs have no threshold left.
+    // Expiries are not compared, lightning HTLCs expire at a block height.
+    alerts.sort_by_key(|swap_alert| {
+        (
+            swap_alert.alert.threshold_minutes.unwrap_or(0),
+            swap_alert.swap_id,
+        )
+    });
+
+    Ok(warp::reply::json(&AlertsResource { alerts }))
+}
@@ -0,0 +1,251 @@
+use crate::{
+    db::{DetermineTypes, Retrieve},
+    http_api::{
+        routes::handle_get_halight_swap,
+        swap_resource::{build_rfc003_siren_entity, IncludeState, OnFail},
+    },
+    swap_protocols::{rfc003::SwapId, state::Change, Facade, LocalSwapId, Rfc003Facade},
+};
+use futures::{
+    future,
+    stream::{self, BoxStream},
+    Stream, StreamExt,
+};
+use serde::Serialize;
+use std::{collections::HashMap, convert::Infallible};
+use uuid::Uuid;
+use warp::{sse::ServerSentEvent, Rejection, Reply};
+
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, strum_macros::Display)]
+#[serde(rename_all = "lowercase")]
+#[strum(serialize_all = "lowercase")]
+pub enum Protocol {
+    Rfc003,
+    Han,
+    Herc20,
+    Halight,
+    Hbit,
+}
+
+/// The state store a change was recorded in.
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum Origin {
+    Rfc003Communication,
+    Rfc003Ledger(&'static str),
+    SplitProtocol(Protocol),
+}
+
+impl Origin {
+    fn protocol(self) -> Protocol {
+        match self {
+            Origin::Rfc003Communication | Origin::Rfc003Ledger(_) => Protocol::Rfc003,
+            Origin::SplitProtocol(protocol) => protocol,
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+#[serde(untagged)]
+pub(crate) enum Event {
+    Swap {
+        swap_id: Uuid,
+        protocol: Protocol,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        ledger: Option<&'static str>,
+        event: String,
+    },
+    Communication {
+        swap_id: Uuid,
+        state: String,
+    },
+    Actions {
+        swap_id: Uuid,
+        actions: Vec<String>,
+    },
+}
+
+impl Event {
+    fn new(origin: Origin, change: Change) -> Self {
+        let Change { swap_id, event } = change;
+
+        match origin {
+            Origin::Rfc003Communication => Event::Communication {
+                swap_id,
+                state: event,
+            },
+            Origin::Rfc003Ledger(ledger) => Event::Swap {
+                swap_id,
+                protocol: Protocol::Rfc003,
+                ledger: Some(ledger),
+                event,
+            },
+            Origin::SplitProtocol(protocol) => Event::Swap {
+                swap_id,
+                protocol,
+                ledger: None,
+                event,
+            },
+        }
+    }
+
+    fn into_sse(self) -> impl ServerSentEvent {
+        let name = match self {
+            Event::Swap { .. } => "swap_event",
+            Event::Communication { .. } => "communication",
+            Event::Actions { .. } => "actions",
+        };
+
+        (warp::sse::event(name), warp::sse::json(self))
+    }
+}
+
+pub async fn get_events(
+    rfc003_facade: Rfc003Facade,
+    facade: Facade,
+) -> Result<impl Reply, Rejection> {
+    Ok(reply(rfc003_facade, facade, None))
+}
+
+pub async fn get_rfc003_swap_events(
+    swap_id: SwapId,
+    rfc003_facade: Rfc003Facade,
+    facade: Facade,
+) -> Result<impl Reply, Rejection> {
+    Ok(reply(rfc003_facade, facade, Some(swap_id.into())))
+}
+
+pub async fn get_swap_events(
+    swap_id: LocalSwapId,
+    rfc003_facade: Rfc003Facade,
+    facade: Facade,
+) -> Result<impl Reply, Rejection> {
+    Ok(reply(rfc003_facade, facade, Some(swap_id.into())))
+}
+
+fn reply(rfc003_facade: Rfc003Facade, facade: Facade, swap_id: Option<Uuid>) -> impl Reply {
+    let events =
+        events(rfc003_facade, facade, swap_id).map(|event| Ok::<_, Infallible>(event.into_sse()));
+
+    warp::sse::reply(warp::sse::keep_alive().stream(events))
+}
+
+/// Streams the changes recorded by the state stores, restricted to a single
+/// swap if `swap_id` is given.
+///
+/// Every change is followed by the actions of the swap if they differ from
+/// the ones we sent last.
+pub(crate) fn events(
+    rfc003_facade: Rfc003Facade,
+    facade: Facade,
+    swap_id: Option<Uuid>,
+) -> impl Stream<Item = Event> + Send + 'static {
+    let changes: Vec<BoxStream<'static, (Origin, Change)>> = vec![
+        subscribe(
+            rfc003_facade.swap_communication_states.subscribe(),
+            Origin::Rfc003Communication,
+        ),
+        subscribe(
+            rfc003_facade.alpha_ledger_states.subscribe(),
+            Origin::Rfc003Ledger("alpha"),
+        ),
+        subscribe(
+            rfc003_facade.beta_ledger_states.subscribe(),
+            Origin::Rfc003Ledger("beta"),
+        ),
+        subscribe(
+            facade.han_states.subscribe(),
+            Origin::SplitProtocol(Protocol::Han),
+        ),
+        subscribe(
+            facade.herc20_states.subscribe(),
+            Origin::SplitProtocol(Protocol::Herc20),
+        ),
+        subscribe(
+            facade.halight_states.subscribe(),
+            Origin::SplitProtocol(Protocol::Halight),
+        ),
+        subscribe(
+            facade.hbit_states.subscribe(),
+            Origin::SplitProtocol(Protocol::Hbit),
+        ),
+    ];
+    let changes = stream::select_all(changes).filter(move |(_, change)| {
+        future::ready(swap_id.map_or(true, |swap_id| swap_id == change.swap_id))
+    });
+    let sent_actions = HashMap::<Uuid, Vec<String>>::new();
+
+    stream::unfold(
+        (changes, sent_actions, rfc003_facade, facade),
+        |(mut changes, mut sent_actions, rfc003_facade, facade)| async move {
+            let (origin, change) = changes.next().await?;
+            let swap_id = change.swap_id;
+            let mut events = vec![Event::new(origin, change)];
+
+            match action_names(origin.protocol(), swap_id, &rfc003_facade, &facade).await {
+                Ok(actions) if sent_actions.get(&swap_id) != Some(&actions) => {
+                    sent_actions.insert(swap_id, actions.clone());
+                    events.push(Event::Actions { swap_id, actions });
+                }
+                Ok(_) => {}
+                Err(e) => tracing::debug!("failed to get actions of swap {}: {:?}", swap_id, e),
+            }
+
+            Some((
+                stream::iter(events),
+                (changes, sent_actions, rfc003_facade, facade),
+            ))
+        },
+    )
+    .flatten()
+}
+
+fn subscribe(
+    changes: impl Stream<Item = Change> + Send + 'static,
+    origin: Origin,
+) -> BoxStream<'static, (Origin, Change)> {
+    changes.map(move |change| (origin, change)).boxed()
+}
+
+/// The names of the actions that are currently available for the swap.
+async fn action_names(
+    protocol: Protocol,
+    swap_id: Uuid,
+    rfc003_facade: &Rfc003Facade,
+    facade: &Facade,
+) -> anyhow::Result<Vec<String>> {
+    let entity = swap_entity(protocol, swap_id, rfc003_facade, facade).await?;
+
+    Ok(entity
+        .actions
+        .into_iter()
+        .map(|action| action.name)
+        .collect())
+}
+
+/// The siren entity of the swap, as returned by the swap resource.
+async fn swap_entity(
+    protocol: Protocol,
+    swap_id: Uuid,
+    rfc003_facade: &Rfc003Facade,
+    facade: &Facade,
+) -> anyhow::Result<siren::Entity> {
+    match protocol {
+        Protocol::Rfc003 => {
+            let swap_id = SwapId(swap_id);
+            let swap = Retrieve::get(rfc003_facade, &swap_id).await?;
+            let types = rfc003_facade.determine_types(&swap_id).await?;
+
+            build_rfc003_siren_entity(
+                rfc003_facade,
+                swap,
+                types,
+                IncludeState::No,
+                OnFail::NoAction,
+            )
+            .await
+        }
+        Protocol::Han | Protocol::Herc20 | Protocol::Halight | Protocol::Hbit => {
+            handle_get_halight_swap(facade.clone(), LocalSwapId::from(swap_id)).await
+        }
+    }
+}
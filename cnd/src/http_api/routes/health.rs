@@ -0,0 +1,16 @@
+use crate::health::Health;
+use std::sync::Arc;
+use warp::{http::StatusCode, Rejection, Reply};
+
+/// Responds with `503 Service Unavailable` unless cnd is ready to do swaps.
+#[allow(clippy::needless_pass_by_value)]
+pub async fn get_health(health: Arc<Health>) -> Result<impl Reply, Rejection> {
+    let report = health.report().await;
+    let status = if report.ready {
+        StatusCode::OK
+    } else {
+        StatusCode::SERVICE_UNAVAILABLE
+    };
+
+    Ok(warp::reply::with_status(warp::reply::json(&report), status))
+}
@@ -1,14 +1,19 @@
 use crate::{
     asset,
-    db::CreatedSwap,
+    db::{CreatedSwap, Save, Sqlite},
     http_api::{problem, routes::into_rejection, Http},
     identity,
     network::{DialInformation, ListenAddresses},
     swap_protocols::{
-        halight, han, Facade, HanEtherereumHalightBitcoinCreateSwapParams, LocalSwapId,
+        halight, han, hbit, herc20, CreateSwapParams, Facade,
+        HalightBitcoinHanEthereumCreateSwapParams, HalightBitcoinHerc20CreateSwapParams,
+        HanEtherereumHalightBitcoinCreateSwapParams, HanEthereumHbitBitcoinCreateSwapParams,
+        HbitBitcoinHanEthereumCreateSwapParams, HbitBitcoinHerc20CreateSwapParams,
+        Herc20HalightBitcoinCreateSwapParams, Herc20HbitBitcoinCreateSwapParams, LocalSwapId,
         Rfc003Facade, Role,
     },
 };
+use chrono::Utc;
 use http_api_problem::HttpApiProblem;
 use libp2p::{Multiaddr, PeerId};
 use serde::{Deserialize, Serialize};
@@ -78,6 +83,7 @@ pub async fn post_han_ethereum_halight_bitcoin(
         beta: body.beta.into(),
         peer: body.peer.peer_id,
         role: body.role.0,
+        created_at: Utc::now().naive_utc(),
     };
 
     facade
@@ -86,6 +92,13 @@ pub async fn post_han_ethereum_halight_bitcoin(
         .map_err(problem::from_anyhow)
         .map_err(warp::reject::custom)?;
 
+    let swap_params = CreateSwapParams::from(swap_params);
+    facade
+        .record_start_heights(swap_id, &swap_params)
+        .await
+        .map_err(problem::from_anyhow)
+        .map_err(warp::reject::custom)?;
+
     facade
         .initiate_communication(swap_id, swap_params)
         .await
@@ -99,64 +112,299 @@ pub async fn post_han_ethereum_halight_bitcoin(
         .map_err(into_rejection)
 }
 
-// `warp::reply::Json` is used as a return type to please the compiler
-// until proper logic is implemented
 #[allow(clippy::needless_pass_by_value)]
 pub async fn post_herc20_halight_bitcoin(
     body: serde_json::Value,
-    _facade: Facade,
-) -> Result<warp::reply::Json, Rejection> {
-    let _body = Body::<Herc20EthereumErc20, HalightLightningBitcoin>::deserialize(&body)
+    facade: Facade,
+) -> Result<impl Reply, Rejection> {
+    let body = Body::<Herc20EthereumErc20, HalightLightningBitcoin>::deserialize(&body)
         .map_err(anyhow::Error::new)
         .map_err(problem::from_anyhow)
         .map_err(warp::reject::custom)?;
 
-    tracing::error!("Lightning routes are not yet supported");
-    Err(warp::reject::custom(
-        HttpApiProblem::new("Route not yet supported.")
-            .set_status(StatusCode::BAD_REQUEST)
-            .set_detail("This route is not yet supported."),
-    ))
+    let swap_params: Herc20HalightBitcoinCreateSwapParams = body.clone().into();
+
+    let swap_id = LocalSwapId::default();
+    let reply = warp::reply::reply();
+
+    let swap = CreatedSwap::<herc20::CreatedSwap, halight::CreatedSwap> {
+        swap_id,
+        alpha: body.alpha.into(),
+        beta: body.beta.into(),
+        peer: body.peer.peer_id,
+        role: body.role.0,
+        created_at: Utc::now().naive_utc(),
+    };
+
+    facade
+        .save(swap)
+        .await
+        .map_err(problem::from_anyhow)
+        .map_err(warp::reject::custom)?;
+
+    let swap_params = CreateSwapParams::from(swap_params);
+    facade
+        .record_start_heights(swap_id, &swap_params)
+        .await
+        .map_err(problem::from_anyhow)
+        .map_err(warp::reject::custom)?;
+
+    facade
+        .initiate_communication(swap_id, swap_params)
+        .await
+        .map(|_| {
+            warp::reply::with_status(
+                warp::reply::with_header(reply, "Location", format!("/swaps/{}", swap_id)),
+                StatusCode::CREATED,
+            )
+        })
+        .map_err(problem::from_anyhow)
+        .map_err(into_rejection)
 }
 
-// `warp::reply::Json` is used as a return type to please the compiler
-// until proper logic is implemented
 #[allow(clippy::needless_pass_by_value)]
 pub async fn post_halight_bitcoin_han_ether(
     body: serde_json::Value,
-    _facade: Facade,
-) -> Result<warp::reply::Json, Rejection> {
-    let _body = Body::<HalightLightningBitcoin, HanEthereumEther>::deserialize(&body)
+    facade: Facade,
+) -> Result<impl Reply, Rejection> {
+    let body = Body::<HalightLightningBitcoin, HanEthereumEther>::deserialize(&body)
         .map_err(anyhow::Error::new)
         .map_err(problem::from_anyhow)
         .map_err(warp::reject::custom)?;
 
-    tracing::error!("Lightning routes are not yet supported");
-    Err(warp::reject::custom(
-        HttpApiProblem::new("Route not yet supported.")
-            .set_status(StatusCode::BAD_REQUEST)
-            .set_detail("This route is not yet supported."),
-    ))
+    let swap_params: HalightBitcoinHanEthereumCreateSwapParams = body.clone().into();
+
+    let swap_id = LocalSwapId::default();
+    let reply = warp::reply::reply();
+
+    let swap = CreatedSwap::<halight::CreatedSwap, han::CreatedSwap> {
+        swap_id,
+        alpha: body.alpha.into(),
+        beta: body.beta.into(),
+        peer: body.peer.peer_id,
+        role: body.role.0,
+        created_at: Utc::now().naive_utc(),
+    };
+
+    facade
+        .save(swap)
+        .await
+        .map_err(problem::from_anyhow)
+        .map_err(warp::reject::custom)?;
+
+    let swap_params = CreateSwapParams::from(swap_params);
+    facade
+        .record_start_heights(swap_id, &swap_params)
+        .await
+        .map_err(problem::from_anyhow)
+        .map_err(warp::reject::custom)?;
+
+    facade
+        .initiate_communication(swap_id, swap_params)
+        .await
+        .map(|_| {
+            warp::reply::with_status(
+                warp::reply::with_header(reply, "Location", format!("/swaps/{}", swap_id)),
+                StatusCode::CREATED,
+            )
+        })
+        .map_err(problem::from_anyhow)
+        .map_err(into_rejection)
 }
 
-// `warp::reply::Json` is used as a return type to please the compiler
-// until proper logic is implemented
 #[allow(clippy::needless_pass_by_value)]
 pub async fn post_halight_bitcoin_herc20(
     body: serde_json::Value,
-    _facade: Facade,
-) -> Result<warp::reply::Json, Rejection> {
-    let _body = Body::<HalightLightningBitcoin, Herc20EthereumErc20>::deserialize(&body)
+    facade: Facade,
+) -> Result<impl Reply, Rejection> {
+    let body = Body::<HalightLightningBitcoin, Herc20EthereumErc20>::deserialize(&body)
         .map_err(anyhow::Error::new)
         .map_err(problem::from_anyhow)
         .map_err(warp::reject::custom)?;
 
-    tracing::error!("Lightning routes are not yet supported");
-    Err(warp::reject::custom(
-        HttpApiProblem::new("Route not yet supported.")
-            .set_status(StatusCode::BAD_REQUEST)
-            .set_detail("This route is not yet supported."),
-    ))
+    let swap_params: HalightBitcoinHerc20CreateSwapParams = body.clone().into();
+
+    let swap_id = LocalSwapId::default();
+    let reply = warp::reply::reply();
+
+    let swap = CreatedSwap::<halight::CreatedSwap, herc20::CreatedSwap> {
+        swap_id,
+        alpha: body.alpha.into(),
+        beta: body.beta.into(),
+        peer: body.peer.peer_id,
+        role: body.role.0,
+        created_at: Utc::now().naive_utc(),
+    };
+
+    facade
+        .save(swap)
+        .await
+        .map_err(problem::from_anyhow)
+        .map_err(warp::reject::custom)?;
+
+    let swap_params = CreateSwapParams::from(swap_params);
+    facade
+        .record_start_heights(swap_id, &swap_params)
+        .await
+        .map_err(problem::from_anyhow)
+        .map_err(warp::reject::custom)?;
+
+    facade
+        .initiate_communication(swap_id, swap_params)
+        .await
+        .map(|_| {
+            warp::reply::with_status(
+                warp::reply::with_header(reply, "Location", format!("/swaps/{}", swap_id)),
+                StatusCode::CREATED,
+            )
+        })
+        .map_err(problem::from_anyhow)
+        .map_err(into_rejection)
+}
+
+#[allow(clippy::needless_pass_by_value)]
+pub async fn post_han_ethereum_hbit_bitcoin(
+    body: serde_json::Value,
+    facade: Facade,
+) -> Result<impl Reply, Rejection> {
+    let body = Body::<HanEthereumEther, HbitBitcoinBitcoin>::deserialize(&body)
+        .map_err(anyhow::Error::new)
+        .map_err(problem::from_anyhow)
+        .map_err(warp::reject::custom)?;
+
+    let swap = CreatedSwap::<han::CreatedSwap, hbit::CreatedSwap> {
+        swap_id: LocalSwapId::default(),
+        alpha: body.alpha.into(),
+        beta: body.beta.into(),
+        peer: body.peer.peer_id.clone(),
+        role: body.role.0,
+        created_at: Utc::now().naive_utc(),
+    };
+    let swap_params = HanEthereumHbitBitcoinCreateSwapParams {
+        peer: body.peer,
+        ..HanEthereumHbitBitcoinCreateSwapParams::from(swap.clone())
+    };
+
+    create_hbit_swap(facade, swap, swap_params.into()).await
+}
+
+#[allow(clippy::needless_pass_by_value)]
+pub async fn post_herc20_hbit_bitcoin(
+    body: serde_json::Value,
+    facade: Facade,
+) -> Result<impl Reply, Rejection> {
+    let body = Body::<Herc20EthereumErc20, HbitBitcoinBitcoin>::deserialize(&body)
+        .map_err(anyhow::Error::new)
+        .map_err(problem::from_anyhow)
+        .map_err(warp::reject::custom)?;
+
+    let swap = CreatedSwap::<herc20::CreatedSwap, hbit::CreatedSwap> {
+        swap_id: LocalSwapId::default(),
+        alpha: body.alpha.into(),
+        beta: body.beta.into(),
+        peer: body.peer.peer_id.clone(),
+        role: body.role.0,
+        created_at: Utc::now().naive_utc(),
+    };
+    let swap_params = Herc20HbitBitcoinCreateSwapParams {
+        peer: body.peer,
+        ..Herc20HbitBitcoinCreateSwapParams::from(swap.clone())
+    };
+
+    create_hbit_swap(facade, swap, swap_params.into()).await
+}
+
+#[allow(clippy::needless_pass_by_value)]
+pub async fn post_hbit_bitcoin_han_ether(
+    body: serde_json::Value,
+    facade: Facade,
+) -> Result<impl Reply, Rejection> {
+    let body = Body::<HbitBitcoinBitcoin, HanEthereumEther>::deserialize(&body)
+        .map_err(anyhow::Error::new)
+        .map_err(problem::from_anyhow)
+        .map_err(warp::reject::custom)?;
+
+    let swap = CreatedSwap::<hbit::CreatedSwap, han::CreatedSwap> {
+        swap_id: LocalSwapId::default(),
+        alpha: body.alpha.into(),
+        beta: body.beta.into(),
+        peer: body.peer.peer_id.clone(),
+        role: body.role.0,
+        created_at: Utc::now().naive_utc(),
+    };
+    let swap_params = HbitBitcoinHanEthereumCreateSwapParams {
+        peer: body.peer,
+        ..HbitBitcoinHanEthereumCreateSwapParams::from(swap.clone())
+    };
+
+    create_hbit_swap(facade, swap, swap_params.into()).await
+}
+
+#[allow(clippy::needless_pass_by_value)]
+pub async fn post_hbit_bitcoin_herc20(
+    body: serde_json::Value,
+    facade: Facade,
+) -> Result<impl Reply, Rejection> {
+    let body = Body::<HbitBitcoinBitcoin, Herc20EthereumErc20>::deserialize(&body)
+        .map_err(anyhow::Error::new)
+        .map_err(problem::from_anyhow)
+        .map_err(warp::reject::custom)?;
+
+    let swap = CreatedSwap::<hbit::CreatedSwap, herc20::CreatedSwap> {
+        swap_id: LocalSwapId::default(),
+        alpha: body.alpha.into(),
+        beta: body.beta.into(),
+        peer: body.peer.peer_id.clone(),
+        role: body.role.0,
+        created_at: Utc::now().naive_utc(),
+    };
+    let swap_params = HbitBitcoinHerc20CreateSwapParams {
+        peer: body.peer,
+        ..HbitBitcoinHerc20CreateSwapParams::from(swap.clone())
+    };
+
+    create_hbit_swap(facade, swap, swap_params.into()).await
+}
+
+/// Saves the swap before initiating the communication with the counterparty.
+///
+/// The parameters are derived from the saved swap so that a swap resumed after
+/// a restart uses the same start of swap, only the address hint of the peer is
+/// not persisted.
+async fn create_hbit_swap<A, B>(
+    facade: Facade,
+    swap: CreatedSwap<A, B>,
+    swap_params: CreateSwapParams,
+) -> Result<impl Reply, Rejection>
+where
+    Sqlite: Save<CreatedSwap<A, B>>,
+{
+    let swap_id = swap.swap_id;
+    let reply = warp::reply::reply();
+
+    facade
+        .save(swap)
+        .await
+        .map_err(problem::from_anyhow)
+        .map_err(warp::reject::custom)?;
+
+    facade
+        .record_start_heights(swap_id, &swap_params)
+        .await
+        .map_err(problem::from_anyhow)
+        .map_err(warp::reject::custom)?;
+
+    facade
+        .initiate_communication(swap_id, swap_params)
+        .await
+        .map(|_| {
+            warp::reply::with_status(
+                warp::reply::with_header(reply, "Location", format!("/swaps/{}", swap_id)),
+                StatusCode::CREATED,
+            )
+        })
+        .map_err(problem::from_anyhow)
+        .map_err(into_rejection)
 }
 
 #[derive(serde::Deserialize, Clone, Debug)]
@@ -184,6 +432,59 @@ impl From<Body<HanEthereumEther, HalightLightningBitcoin>>
     }
 }
 
+impl From<Body<Herc20EthereumErc20, HalightLightningBitcoin>>
+    for Herc20HalightBitcoinCreateSwapParams
+{
+    fn from(body: Body<Herc20EthereumErc20, HalightLightningBitcoin>) -> Self {
+        Self {
+            role: body.role.0,
+            peer: body.peer,
+            ethereum_identity: body.alpha.identity.into(),
+            ethereum_absolute_expiry: body.alpha.absolute_expiry.into(),
+            ethereum_amount: body.alpha.amount,
+            token_contract: body.alpha.contract_address,
+            lightning_identity: body.beta.identity,
+            lightning_cltv_expiry: body.beta.cltv_expiry.into(),
+            lightning_amount: body.beta.amount.0,
+        }
+    }
+}
+
+impl From<Body<HalightLightningBitcoin, HanEthereumEther>>
+    for HalightBitcoinHanEthereumCreateSwapParams
+{
+    fn from(body: Body<HalightLightningBitcoin, HanEthereumEther>) -> Self {
+        Self {
+            role: body.role.0,
+            peer: body.peer,
+            lightning_identity: body.alpha.identity,
+            lightning_cltv_expiry: body.alpha.cltv_expiry.into(),
+            lightning_amount: body.alpha.amount.0,
+            ethereum_identity: body.beta.identity.into(),
+            ethereum_absolute_expiry: body.beta.absolute_expiry.into(),
+            ethereum_amount: body.beta.amount,
+        }
+    }
+}
+
+impl From<Body<HalightLightningBitcoin, Herc20EthereumErc20>>
+    for HalightBitcoinHerc20CreateSwapParams
+{
+    fn from(body: Body<HalightLightningBitcoin, Herc20EthereumErc20>) -> Self {
+        Self {
+            role: body.role.0,
+            peer: body.peer,
+            lightning_identity: body.alpha.identity,
+            lightning_cltv_expiry: body.alpha.cltv_expiry.into(),
+            lightning_amount: body.alpha.amount.0,
+            ethereum_identity: body.beta.identity.into(),
+            ethereum_absolute_expiry: body.beta.absolute_expiry.into(),
+            ethereum_amount: body.beta.amount,
+            token_contract: body.beta.contract_address,
+        }
+    }
+}
+
 #[derive(serde::Deserialize, Clone, Debug)]
 pub struct HanEthereumEther {
     pub amount: asset::Ether,
@@ -230,3 +531,32 @@ pub struct Herc20EthereumErc20 {
     pub contract_address: identity::Ethereum,
     pub absolute_expiry: u32,
 }
+
+impl From<Herc20EthereumErc20> for herc20::CreatedSwap {
+    fn from(p: Herc20EthereumErc20) -> Self {
+        herc20::CreatedSwap {
+            amount: p.amount,
+            identity: p.identity,
+            chain_id: p.chain_id,
+            contract_address: p.contract_address,
+            absolute_expiry: p.absolute_expiry,
+        }
+    }
+}
+
+#[derive(serde::Deserialize, Clone, Debug)]
+pub struct HbitBitcoinBitcoin {
+    pub amount: Http<asset::Bitcoin>,
+    pub network: Http<bitcoin::Network>,
+    pub absolute_expiry: u32,
+}
+
+impl From<HbitBitcoinBitcoin> for hbit::CreatedSwap {
+    fn from(p: HbitBitcoinBitcoin) -> Self {
+        hbit::CreatedSwap {
+            amount: *p.amount,
+            network: *p.network,
+            absolute_expiry: p.absolute_expiry,
+        }
+    }
+}
@@ -8,8 +8,9 @@ use crate::{
         route_factory::new_action_link,
         routes::rfc003::decline::{to_swap_decline_reason, DeclineBody},
     },
-    init_swap::init_accepted_swap,
+    init_swap::{init_accepted_swap, record_start_heights},
     libp2p_comit_ext::ToHeader,
+    metrics::{self, Direction},
     network::PendingRequestFor,
     seed::Rfc003DeriveSwapSeed,
     swap_protocols::{
@@ -98,6 +99,7 @@ pub async fn handle_action(
                         swap_id
                     )
                 })?;
+                metrics::record_comit_request(Direction::Inbound, "accepted");
 
                 let accepted = LoadAcceptedSwap::<AL, BL, AA, BA, AI, BI>::load_accepted_swap(
                     &dependencies,
@@ -105,6 +107,9 @@ pub async fn handle_action(
                 )
                 .await?;
 
+                record_start_heights::<AL, BL, AA, BA, AH, BH, AT, BT>(&dependencies, swap_id)
+                    .await?;
+
                 init_accepted_swap::<_, _, _, _, AH, BH, _, _, AT, BT>(&dependencies, accepted)
                     .await?;
 
@@ -137,6 +142,7 @@ pub async fn handle_action(
                         swap_id
                     )
                 })?;
+                metrics::record_comit_request(Direction::Inbound, "declined");
 
                 dependencies
                     .insert(swap_id, SwapCommunication::Declined {
@@ -1,14 +1,16 @@
 use crate::{
-    db::{LoadAcceptedSwap, Save, Sqlite, Swap},
+    btsieve::WaitForFinality,
+    db::{LoadAcceptedSwap, Save, Sqlite, Swap, WatcherCheckpoints},
     htlc_location,
     http_api::{HttpAsset, HttpLedger},
     identity,
-    init_swap::init_accepted_swap,
+    init_swap::{init_accepted_swap, record_start_heights},
     network::{DialInformation, SendRequest},
     seed::Rfc003DeriveSwapSeed,
     swap_protocols::{
         rfc003::{
             self,
+            create_swap::StartHeight,
             events::{HtlcDeployed, HtlcFunded, HtlcRedeemed, HtlcRefunded},
             state::Insert,
             Accept, Decline, DeriveIdentities, DeriveSecret, LedgerState, Request, SecretHash,
@@ -56,7 +58,13 @@ where
         + HtlcRedeemed<AL, AA, AH, AI, AT>
         + HtlcRedeemed<BL, BA, BH, BI, BT>
         + HtlcRefunded<AL, AA, AH, AI, AT>
-        + HtlcRefunded<BL, BA, BH, BI, BT>,
+        + HtlcRefunded<BL, BA, BH, BI, BT>
+        + WaitForFinality<AT>
+        + WaitForFinality<BT>
+        + WatcherCheckpoints<AA, AH, AT>
+        + WatcherCheckpoints<BA, BH, BT>
+        + StartHeight<AL>
+        + StartHeight<BL>,
 {
     tracing::trace!("initiating new request: {}", swap_request.swap_id);
 
@@ -95,6 +103,9 @@ where
                     )
                     .await?;
 
+                    record_start_heights::<AL, BL, AA, BA, AH, BH, AT, BT>(&dependencies, id)
+                        .await?;
+
                     init_accepted_swap::<_, _, _, _, AH, BH, _, _, AT, BT>(&dependencies, accepted)
                         .await?;
                 }
@@ -40,6 +40,9 @@ pub struct LedgerState<H, T> {
     pub fund_tx: Option<Http<T>>,
     pub redeem_tx: Option<Http<T>>,
     pub refund_tx: Option<Http<T>>,
+    /// Confirmations of the most recent transaction.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub confirmations: Option<u32>,
 }
 
 #[derive(Debug, Clone, PartialEq, Copy, Serialize)]
@@ -109,6 +112,7 @@ where
                 fund_tx: None,
                 refund_tx: None,
                 redeem_tx: None,
+                confirmations: None,
             },
             Funded {
                 htlc_location,
@@ -122,6 +126,7 @@ where
                 fund_tx: Some(Http(fund_transaction)),
                 refund_tx: None,
                 redeem_tx: None,
+                confirmations: None,
             },
             IncorrectlyFunded {
                 htlc_location,
@@ -135,8 +140,16 @@ where
                 fund_tx: Some(Http(fund_transaction)),
                 redeem_tx: None,
                 refund_tx: None,
+                confirmations: None,
             },
-            Redeemed {
+            RedeemPending {
+                htlc_location,
+                deploy_transaction,
+                fund_transaction,
+                redeem_transaction,
+                ..
+            }
+            | Redeemed {
                 htlc_location,
                 deploy_transaction,
                 fund_transaction,
@@ -149,6 +162,7 @@ where
                 fund_tx: Some(Http(fund_transaction)),
                 redeem_tx: Some(Http(redeem_transaction)),
                 refund_tx: None,
+                confirmations: None,
             },
             Refunded {
                 htlc_location,
@@ -163,6 +177,7 @@ where
                 fund_tx: Some(Http(fund_transaction)),
                 refund_tx: Some(Http(refund_transaction)),
                 redeem_tx: None,
+                confirmations: None,
             },
         }
     }
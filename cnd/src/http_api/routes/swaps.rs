@@ -0,0 +1,406 @@
+use crate::{
+    db::{Cursor, DetermineTypes, ListedSwap, Order, Page, SwapFilter, SwapKind, SwapStatus},
+    http_api::{
+        self, problem, route_factory,
+        routes::{into_rejection, load_swap_state, make_swap_state_entity},
+        swap_resource::{build_rfc003_siren_entity, IncludeState, OnFail},
+    },
+    swap_protocols::{Facade, LocalSwapId, Rfc003Facade, Role},
+};
+use anyhow::anyhow;
+use chrono::{DateTime, Utc};
+use http_api_problem::HttpApiProblem;
+use libp2p::PeerId;
+use serde::{Deserialize, Serialize};
+use std::str::FromStr;
+use warp::{http::StatusCode, Rejection, Reply};
+
+/// The swaps we can store, as (alpha ledger, alpha asset, beta ledger, beta
+/// asset, kind).
+const SUPPORTED_SWAPS: [(LedgerKind, AssetKind, LedgerKind, AssetKind, SwapKind); 12] = [
+    (
+        LedgerKind::Bitcoin,
+        AssetKind::Bitcoin,
+        LedgerKind::Ethereum,
+        AssetKind::Ether,
+        SwapKind::Rfc003BitcoinEther,
+    ),
+    (
+        LedgerKind::Ethereum,
+        AssetKind::Ether,
+        LedgerKind::Bitcoin,
+        AssetKind::Bitcoin,
+        SwapKind::Rfc003EtherBitcoin,
+    ),
+    (
+        LedgerKind::Bitcoin,
+        AssetKind::Bitcoin,
+        LedgerKind::Ethereum,
+        AssetKind::Erc20,
+        SwapKind::Rfc003BitcoinErc20,
+    ),
+    (
+        LedgerKind::Ethereum,
+        AssetKind::Erc20,
+        LedgerKind::Bitcoin,
+        AssetKind::Bitcoin,
+        SwapKind::Rfc003Erc20Bitcoin,
+    ),
+    (
+        LedgerKind::Ethereum,
+        AssetKind::Ether,
+        LedgerKind::Lightning,
+        AssetKind::Bitcoin,
+        SwapKind::HanHalight,
+    ),
+    (
+        LedgerKind::Ethereum,
+        AssetKind::Erc20,
+        LedgerKind::Lightning,
+        AssetKind::Bitcoin,
+        SwapKind::Herc20Halight,
+    ),
+    (
+        LedgerKind::Lightning,
+        AssetKind::Bitcoin,
+        LedgerKind::Ethereum,
+        AssetKind::Ether,
+        SwapKind::HalightHan,
+    ),
+    (
+        LedgerKind::Lightning,
+        AssetKind::Bitcoin,
+        LedgerKind::Ethereum,
+        AssetKind::Erc20,
+        SwapKind::HalightHerc20,
+    ),
+    (
+        LedgerKind::Ethereum,
+        AssetKind::Ether,
+        LedgerKind::Bitcoin,
+        AssetKind::Bitcoin,
+        SwapKind::HanHbit,
+    ),
+    (
+        LedgerKind::Ethereum,
+        AssetKind::Erc20,
+        LedgerKind::Bitcoin,
+        AssetKind::Bitcoin,
+        SwapKind::Herc20Hbit,
+    ),
+    (
+        LedgerKind::Bitcoin,
+        AssetKind::Bitcoin,
+        LedgerKind::Ethereum,
+        AssetKind::Ether,
+        SwapKind::HbitHan,
+    ),
+    (
+        LedgerKind::Bitcoin,
+        AssetKind::Bitcoin,
+        LedgerKind::Ethereum,
+        AssetKind::Erc20,
+        SwapKind::HbitHerc20,
+    ),
+];
+
+#[derive(Clone, Copy, Debug, Deserialize, PartialEq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum LedgerKind {
+    Bitcoin,
+    Ethereum,
+    Lightning,
+}
+
+#[derive(Clone, Copy, Debug, Deserialize, PartialEq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum AssetKind {
+    Bitcoin,
+    Ether,
+    Erc20,
+}
+
+/// Query parameters of `GET /swaps`, all of them are optional.
+#[derive(Clone, Debug, Default, Deserialize, PartialEq, Serialize)]
+pub struct GetSwapsQuery {
+    pub role: Option<String>,
+    pub status: Option<SwapStatus>,
+    pub counterparty: Option<String>,
+    pub alpha_ledger: Option<LedgerKind>,
+    pub alpha_asset: Option<AssetKind>,
+    pub beta_ledger: Option<LedgerKind>,
+    pub beta_asset: Option<AssetKind>,
+    pub created_after: Option<DateTime<Utc>>,
+    pub created_before: Option<DateTime<Utc>>,
+    pub limit: Option<u32>,
+    pub cursor: Option<String>,
+    pub order: Option<Order>,
+}
+
+impl GetSwapsQuery {
+    fn filter(&self) -> anyhow::Result<SwapFilter> {
+        let role = match &self.role {
+            Some(role) => Some(Role::from_str(role).map_err(|_| invalid_parameter("role"))?),
+            None => None,
+        };
+        let counterparty = match &self.counterparty {
+            Some(counterparty) => Some(
+                PeerId::from_str(counterparty).map_err(|_| invalid_parameter("counterparty"))?,
+            ),
+            None => None,
+        };
+
+        Ok(SwapFilter {
+            role,
+            status: self.status,
+            counterparty,
+            kinds: self.kinds(),
+            created_after: self.created_after.map(|time| time.naive_utc()),
+            created_before: self.created_before.map(|time| time.naive_utc()),
+        })
+    }
+
+    fn kinds(&self) -> Option<Vec<SwapKind>> {
+        if self.alpha_ledger.is_none()
+            && self.alpha_asset.is_none()
+            && self.beta_ledger.is_none()
+            && self.beta_asset.is_none()
+        {
+            return None;
+        }
+
+        let ledger_matches =
+            |wanted: Option<LedgerKind>, actual| wanted.map_or(true, |wanted| wanted == actual);
+        let asset_matches =
+            |wanted: Option<AssetKind>, actual| wanted.map_or(true, |wanted| wanted == actual);
+
+        Some(
+            SUPPORTED_SWAPS
+                .iter()
+                .filter(|(alpha_ledger, alpha_asset, beta_ledger, beta_asset, _)| {
+                    ledger_matches(self.alpha_ledger, *alpha_ledger)
+                        && asset_matches(self.alpha_asset, *alpha_asset)
+                        && ledger_matches(self.beta_ledger, *beta_ledger)
+                        && asset_matches(self.beta_asset, *beta_asset)
+                })
+                .map(|(_, _, _, _, kind)| *kind)
+                .collect(),
+        )
+    }
+
+    fn page(&self) -> anyhow::Result<Page> {
+        let cursor = match &self.cursor {
+            Some(cursor) => {
+                Some(Cursor::from_str(cursor).map_err(|_| invalid_parameter("cursor"))?)
+            }
+            None => None,
+        };
+
+        Ok(Page {
+            limit: self.limit,
+            cursor,
+            order: self.order.unwrap_or_default(),
+        })
+    }
+}
+
+fn invalid_parameter(name: &str) -> anyhow::Error {
+    anyhow!(HttpApiProblem::new("Invalid query parameter.")
+        .set_status(StatusCode::BAD_REQUEST)
+        .set_detail(format!("The value of `{}` is invalid.", name)))
+}
+
+#[allow(clippy::needless_pass_by_value)]
+pub async fn get_swaps(
+    query: GetSwapsQuery,
+    rfc003_facade: Rfc003Facade,
+    facade: Facade,
+) -> Result<impl Reply, Rejection> {
+    handle_get_swaps(rfc003_facade, facade, query)
+        .await
+        .map(|swaps| {
+            Ok(warp::reply::with_header(
+                warp::reply::json(&swaps),
+                "content-type",
+                "application/vnd.siren+json",
+            ))
+        })
+        .map_err(problem::from_anyhow)
+        .map_err(into_rejection)
+}
+
+async fn handle_get_swaps(
+    rfc003_facade: Rfc003Facade,
+    facade: Facade,
+    query: GetSwapsQuery,
+) -> anyhow::Result<siren::Entity> {
+    let page = facade
+        .db
+        .list_swaps(&query.filter()?, query.page()?)
+        .await?;
+
+    let mut entity = siren::Entity::default().with_class_member("swaps");
+
+    for swap in page.swaps.into_iter() {
+        let sub_entity = match swap {
+            ListedSwap::Rfc003(swap) => {
+                let types = rfc003_facade.determine_types(&swap.swap_id).await?;
+
+                build_rfc003_siren_entity(
+                    &rfc003_facade,
+                    swap,
+                    types,
+                    IncludeState::No,
+                    OnFail::NoAction,
+                )
+                .await?
+            }
+            // The protocol is taken from the tables the swap is stored in, the
+            // state is only known once the swap is finalized.
+            ListedSwap::SplitProtocol(swap_id, kind) => {
+                match load_swap_state(&facade, swap_id).await? {
+                    Some(state) => {
+                        make_swap_state_entity(&facade, swap_id, kind.protocol(), state).await?
+                    }
+                    None => unfinalized_swap_entity(swap_id, kind)?,
+                }
+            }
+        };
+        entity.push_sub_entity(siren::SubEntity::from_entity(sub_entity, &["item"]));
+    }
+
+    if let Some(cursor) = page.next_cursor {
+        let next = GetSwapsQuery {
+            cursor: Some(cursor.to_string()),
+            ..query
+        };
+        let href = format!(
+            "/{}?{}",
+            http_api::PATH,
+            serde_urlencoded::to_string(&next)?
+        );
+
+        entity = entity.with_link(siren::NavigationalLink::new(&["next"], href));
+    }
+
+    Ok(entity)
+}
+
+/// Not finalized yet, there is nothing to show but the protocol and the link.
+fn unfinalized_swap_entity(swap_id: LocalSwapId, kind: SwapKind) -> anyhow::Result<siren::Entity> {
+    let entity = siren::Entity::default()
+        .with_class_member("swaps")
+        .with_properties(UnfinalizedSwap {
+            protocol: kind.protocol(),
+        })?
+        .with_link(siren::NavigationalLink::new(
+            &["self"],
+            route_factory::swap_path(swap_id),
+        ));
+
+    Ok(entity)
+}
+
+#[derive(Serialize, Debug)]
+struct UnfinalizedSwap {
+    protocol: &'static str,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn no_ledger_or_asset_parameters_match_all_swaps() {
+        let query = GetSwapsQuery::default();
+
+        assert_eq!(query.kinds(), None);
+    }
+
+    #[test]
+    fn ledger_parameter_matches_all_assets_of_the_ledger() {
+        let query = GetSwapsQuery {
+            alpha_ledger: Some(LedgerKind::Ethereum),
+            ..GetSwapsQuery::default()
+        };
+
+        assert_eq!(
+            query.kinds(),
+            Some(vec![
+                SwapKind::Rfc003EtherBitcoin,
+                SwapKind::Rfc003Erc20Bitcoin,
+                SwapKind::HanHalight,
+                SwapKind::Herc20Halight,
+                SwapKind::HanHbit,
+                SwapKind::Herc20Hbit,
+            ])
+        );
+    }
+
+    #[test]
+    fn lightning_parameter_matches_split_protocol_swaps() {
+        let query = GetSwapsQuery {
+            alpha_ledger: Some(LedgerKind::Lightning),
+            beta_asset: Some(AssetKind::Erc20),
+            ..GetSwapsQuery::default()
+        };
+
+        assert_eq!(query.kinds(), Some(vec![SwapKind::HalightHerc20]));
+    }
+
+    #[test]
+    fn unsupported_pair_matches_no_swaps() {
+        let query = GetSwapsQuery {
+            alpha_asset: Some(AssetKind::Ether),
+            beta_asset: Some(AssetKind::Erc20),
+            ..GetSwapsQuery::default()
+        };
+
+        assert_eq!(query.kinds(), Some(vec![]));
+    }
+
+    #[test]
+    fn query_parameters_deserialize() {
+        let query = serde_urlencoded::from_str::<GetSwapsQuery>(
+            "role=Bob&status=not_swapped&alpha_asset=bitcoin&limit=10&cursor=1591000000.000000000-1-42&order=desc",
+        )
+        .unwrap();
+
+        assert_eq!(query, GetSwapsQuery {
+            role: Some(String::from("Bob")),
+            status: Some(SwapStatus::NotSwapped),
+            alpha_asset: Some(AssetKind::Bitcoin),
+            limit: Some(10),
+            cursor: Some(String::from("1591000000.000000000-1-42")),
+            order: Some(Order::Desc),
+            ..GetSwapsQuery::default()
+        });
+    }
+
+    #[test]
+    fn invalid_cursor_is_rejected() {
+        let query = GetSwapsQuery {
+            cursor: Some(String::from("42")),
+            ..GetSwapsQuery::default()
+        };
+
+        assert!(query.page().is_err());
+    }
+
+    #[test]
+    fn unfinalized_swap_is_listed_with_the_protocol_of_its_tables() {
+        let swap_id = LocalSwapId::default();
+
+        let entity = unfinalized_swap_entity(swap_id, SwapKind::HbitHerc20).unwrap();
+        let entity = serde_json::to_value(&entity).unwrap();
+
+        assert_eq!(
+            entity["properties"],
+            serde_json::json!({ "protocol": "hbit-herc20" })
+        );
+        assert_eq!(
+            entity["links"][0]["href"],
+            serde_json::json!(route_factory::swap_path(swap_id))
+        );
+    }
+}
@@ -1,7 +1,9 @@
 #![allow(clippy::type_repetition_in_bounds)]
 
 use crate::{
+    btsieve::Confirmations,
     db::{Swap, SwapTypes},
+    expiry_monitor::Alert,
     http_api::{
         action::rfc003::ToSirenAction,
         route_factory,
@@ -31,6 +33,8 @@ pub struct SwapResource<S> {
     pub parameters: SwapParameters,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub state: Option<S>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub alerts: Vec<Alert>,
 }
 
 #[derive(Debug, Clone, Serialize)]
@@ -115,8 +119,15 @@ pub async fn build_rfc003_siren_entity(
             .ok_or_else(|| anyhow::anyhow!("beta ledger state not found for {}", id))?;
 
         let communication = SwapCommunication::from(swap_communication.clone());
-        let alpha_ledger = LedgerState::from(alpha_ledger_state.clone());
-        let beta_ledger = LedgerState::from(beta_ledger_state.clone());
+        let mut alpha_ledger = LedgerState::from(alpha_ledger_state.clone());
+        let mut beta_ledger = LedgerState::from(beta_ledger_state.clone());
+
+        // Only the state tells the confirmations, don't bother looking them up
+        // otherwise.
+        if let IncludeState::Yes = include_state {
+            alpha_ledger.confirmations = confirmations(dependencies, &alpha_ledger_state).await;
+            beta_ledger.confirmations = confirmations(dependencies, &beta_ledger_state).await;
+        }
         let parameters = SwapParameters::from(swap_communication.request().clone());
 
         let secret_source = dependencies.rfc003_derive_swap_seed(id);
@@ -152,6 +163,7 @@ pub async fn build_rfc003_siren_entity(
                 }),
                 IncludeState::No => None,
             },
+            alerts: dependencies.expiry_alerts.get(id.into()).await,
         };
 
         let entity = siren::Entity::default()
@@ -186,3 +198,22 @@ pub async fn build_rfc003_siren_entity(
         Ok(entity)
     })
 }
+
+/// Looks up the confirmations of the most recent transaction of the ledger.
+async fn confirmations<A, H, T>(
+    dependencies: &Rfc003Facade,
+    ledger_state: &rfc003::LedgerState<A, H, T>,
+) -> Option<u32>
+where
+    Rfc003Facade: Confirmations<T>,
+{
+    let transaction = ledger_state.latest_transaction()?;
+
+    dependencies
+        .confirmations(transaction)
+        .await
+        .unwrap_or_else(|e| {
+            tracing::warn!("failed to look up confirmations: {:?}", e);
+            None
+        })
+}
@@ -0,0 +1,185 @@
+//! Serves the HTTP API over HTTPS.
+//!
+//! Like lnd, we generate a self-signed certificate for `localhost` if neither
+//! the configured certificate nor the key exist. If a client CA is configured,
+//! only clients presenting a certificate signed by it can connect.
+use crate::config::settings::Tls;
+use anyhow::Context;
+use futures::{stream, Stream, StreamExt};
+use std::{
+    fs::{self, File, OpenOptions},
+    io::{self, BufReader, Write},
+    path::Path,
+    sync::Arc,
+    time::Duration,
+};
+use tokio::net::{TcpListener, TcpStream};
+use tokio_rustls::{
+    rustls::{
+        internal::pemfile, AllowAnyAuthenticatedClient, NoClientAuth, RootCertStore, ServerConfig,
+    },
+    server::TlsStream,
+    TlsAcceptor,
+};
+
+/// Number of TLS handshakes we perform at the same time.
+const MAX_CONCURRENT_HANDSHAKES: usize = 64;
+
+/// How long a client has to complete the TLS handshake.
+const HANDSHAKE_TIMEOUT: Duration = Duration::from_secs(10);
+
+pub fn acceptor(tls: &Tls) -> anyhow::Result<TlsAcceptor> {
+    if !tls.cert_path.exists() && !tls.key_path.exists() {
+        generate_self_signed(&tls.cert_path, &tls.key_path)?;
+    }
+
+    let certs = pemfile::certs(&mut open(&tls.cert_path)?)
+        .map_err(|_| anyhow::anyhow!("failed to parse certificate {}", tls.cert_path.display()))?;
+    let key = load_key(&tls.key_path)?;
+
+    let client_verifier = match &tls.client_ca_path {
+        Some(client_ca_path) => {
+            let mut roots = RootCertStore::empty();
+            let (valid, _) = roots
+                .add_pem_file(&mut open(client_ca_path)?)
+                .map_err(|_| {
+                    anyhow::anyhow!("failed to parse client CA {}", client_ca_path.display())
+                })?;
+            anyhow::ensure!(
+                valid > 0,
+                "no certificate found in client CA {}",
+                client_ca_path.display()
+            );
+
+            AllowAnyAuthenticatedClient::new(roots)
+        }
+        None => NoClientAuth::new(),
+    };
+
+    let mut config = ServerConfig::new(client_verifier);
+    config
+        .set_single_cert(certs, key)
+        .context("invalid certificate or key for the HTTP API")?;
+
+    Ok(TlsAcceptor::from(Arc::new(config)))
+}
+
+/// The TLS connections of the clients connecting to `listener`.
+///
+/// Failed handshakes are logged and dropped, an error would stop the server.
+pub fn incoming(
+    listener: TcpListener,
+    acceptor: TlsAcceptor,
+) -> impl Stream<Item = io::Result<TlsStream<TcpStream>>> {
+    stream::unfold(listener, |mut listener| async move {
+        let connection = listener.accept().await;
+        Some((connection, listener))
+    })
+    .filter_map(|connection| async move {
+        match connection {
+            Ok((stream, _)) => Some(stream),
+            Err(e) => {
+                tracing::warn!("failed to accept connection: {:?}", e);
+                None
+            }
+        }
+    })
+    .map(move |stream| tokio::time::timeout(HANDSHAKE_TIMEOUT, acceptor.accept(stream)))
+    .buffer_unordered(MAX_CONCURRENT_HANDSHAKES)
+    .filter_map(|handshake| async move {
+        match handshake {
+            Ok(Ok(stream)) => Some(Ok(stream)),
+            Ok(Err(e)) => {
+                tracing::debug!("TLS handshake failed: {:?}", e);
+                None
+            }
+            Err(_) => {
+                tracing::debug!("TLS handshake timed out");
+                None
+            }
+        }
+    })
+}
+
+fn open(path: &Path) -> anyhow::Result<BufReader<File>> {
+    let file = File::open(path).with_context(|| format!("failed to open {}", path.display()))?;
+
+    Ok(BufReader::new(file))
+}
+
+fn load_key(path: &Path) -> anyhow::Result<tokio_rustls::rustls::PrivateKey> {
+    let parse_error = || anyhow::anyhow!("failed to parse key {}", path.display());
+
+    let mut keys = pemfile::pkcs8_private_keys(&mut open(path)?).map_err(|_| parse_error())?;
+    if keys.is_empty() {
+        keys = pemfile::rsa_private_keys(&mut open(path)?).map_err(|_| parse_error())?;
+    }
+
+    keys.into_iter()
+        .next()
+        .ok_or_else(|| anyhow::anyhow!("no private key found in {}", path.display()))
+}
+
+fn generate_self_signed(cert_path: &Path, key_path: &Path) -> anyhow::Result<()> {
+    let cert = rcgen::generate_simple_self_signed(vec![String::from("localhost")])?;
+
+    for path in &[cert_path, key_path] {
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir)?;
+        }
+    }
+    fs::write(cert_path, cert.serialize_pem()?)?;
+
+    let mut options = OpenOptions::new();
+    options.write(true).create_new(true);
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::OpenOptionsExt;
+        options.mode(0o600);
+    }
+    options
+        .open(key_path)?
+        .write_all(cert.serialize_private_key_pem().as_bytes())?;
+
+    tracing::info!(
+        "No TLS certificate found, created a self-signed one at: {}",
+        cert_path.display()
+    );
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn self_signed_certificate_is_generated_and_loaded() {
+        let dir = tempfile::tempdir().unwrap();
+        let tls = Tls {
+            cert_path: dir.path().join("tls.cert"),
+            key_path: dir.path().join("tls.key"),
+            client_ca_path: None,
+        };
+
+        acceptor(&tls).unwrap();
+        let cert = fs::read(&tls.cert_path).unwrap();
+
+        acceptor(&tls).unwrap();
+        assert_eq!(fs::read(&tls.cert_path).unwrap(), cert);
+    }
+
+    #[test]
+    fn missing_key_is_not_replaced() {
+        let dir = tempfile::tempdir().unwrap();
+        let tls = Tls {
+            cert_path: dir.path().join("tls.cert"),
+            key_path: dir.path().join("tls.key"),
+            client_ca_path: None,
+        };
+        acceptor(&tls).unwrap();
+        fs::remove_file(&tls.key_path).unwrap();
+
+        assert!(acceptor(&tls).is_err());
+    }
+}
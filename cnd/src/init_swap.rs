@@ -1,11 +1,12 @@
 use crate::{
-    db::AcceptedSwap,
+    btsieve::WaitForFinality,
+    db::{AcceptedSwap, Ledger, WatcherCheckpoints},
     swap_protocols::{
         rfc003::{
-            create_swap::{create_watcher, OngoingSwap},
+            create_swap::{create_watcher, OngoingSwap, StartHeight},
             events::{HtlcDeployed, HtlcFunded, HtlcRedeemed, HtlcRefunded},
             state::Insert,
-            Accept, Request, SwapCommunication,
+            Accept, Request, SwapCommunication, SwapId,
         },
         Rfc003Facade,
     },
@@ -25,7 +26,11 @@ where
         + HtlcRedeemed<AL, AA, AH, AI, AT>
         + HtlcRedeemed<BL, BA, BH, BI, BT>
         + HtlcRefunded<AL, AA, AH, AI, AT>
-        + HtlcRefunded<BL, BA, BH, BI, BT>,
+        + HtlcRefunded<BL, BA, BH, BI, BT>
+        + WaitForFinality<AT>
+        + WaitForFinality<BT>
+        + WatcherCheckpoints<AA, AH, AT>
+        + WatcherCheckpoints<BA, BH, BT>,
     AL: Clone + Send + Sync + 'static,
     BL: Clone + Send + Sync + 'static,
     AA: Ord + Clone + Send + Sync + 'static,
@@ -58,6 +63,7 @@ where
             dependencies.clone(),
             dependencies.alpha_ledger_states.clone(),
             id,
+            Ledger::Alpha,
             swap.alpha_htlc_params(),
             accepted_at,
         )
@@ -69,6 +75,7 @@ where
             dependencies.clone(),
             dependencies.beta_ledger_states.clone(),
             id,
+            Ledger::Beta,
             swap.beta_htlc_params(),
             accepted_at,
         )
@@ -77,3 +84,54 @@ where
 
     Ok(())
 }
+
+/// Records the height from which on the ledgers of a freshly accepted swap are
+/// watched.
+///
+/// Watchers of swaps without a start height, for example because the latest
+/// block could not be fetched, fall back to the time the swap was accepted.
+pub async fn record_start_heights<AL, BL, AA, BA, AH, BH, AT, BT>(
+    dependencies: &Rfc003Facade,
+    id: SwapId,
+) -> anyhow::Result<()>
+where
+    Rfc003Facade: StartHeight<AL>
+        + StartHeight<BL>
+        + WatcherCheckpoints<AA, AH, AT>
+        + WatcherCheckpoints<BA, BH, BT>,
+{
+    record_start_height::<AL, AA, AH, AT>(dependencies, id, Ledger::Alpha).await?;
+    record_start_height::<BL, BA, BH, BT>(dependencies, id, Ledger::Beta).await?;
+
+    Ok(())
+}
+
+async fn record_start_height<L, A, H, T>(
+    dependencies: &Rfc003Facade,
+    id: SwapId,
+    ledger: Ledger,
+) -> anyhow::Result<()>
+where
+    Rfc003Facade: StartHeight<L> + WatcherCheckpoints<A, H, T>,
+{
+    let start_height = match StartHeight::<L>::start_height(dependencies).await {
+        Ok(start_height) => start_height,
+        Err(e) => {
+            tracing::warn!(
+                "failed to determine start height on {} ledger of swap {}: {:?}",
+                ledger,
+                id,
+                e
+            );
+            None
+        }
+    };
+
+    let mut checkpoint =
+        WatcherCheckpoints::<A, H, T>::load_watcher_checkpoint(dependencies, &id, ledger).await?;
+    checkpoint.start_height = start_height;
+
+    dependencies
+        .save_watcher_checkpoint(&id, ledger, checkpoint)
+        .await
+}
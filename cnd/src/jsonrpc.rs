@@ -1,10 +1,17 @@
 use anyhow::Context;
 use serde::{de::DeserializeOwned, Serialize};
 
-#[derive(Debug)]
+#[derive(Clone, Debug)]
 pub struct Client {
     inner: reqwest::Client,
     url: reqwest::Url,
+    basic_auth: Option<BasicAuth>,
+}
+
+#[derive(Clone, Debug)]
+struct BasicAuth {
+    username: String,
+    password: String,
 }
 
 #[derive(Debug, thiserror::Error)]
@@ -20,6 +27,17 @@ impl Client {
         Self {
             inner: reqwest::Client::new(),
             url: base_url,
+            basic_auth: None,
+        }
+    }
+
+    /// Authenticates every request with HTTP basic auth, as required by
+    /// bitcoind's JSON-RPC interface.
+    pub fn with_basic_auth(base_url: reqwest::Url, username: String, password: String) -> Self {
+        Self {
+            inner: reqwest::Client::new(),
+            url: base_url,
+            basic_auth: Some(BasicAuth { username, password }),
         }
     }
 
@@ -28,10 +46,12 @@ impl Client {
         Req: Serialize,
         Res: DeserializeOwned,
     {
-        let response = self
-            .inner
-            .post(self.url.clone())
-            .json(&request)
+        let mut request_builder = self.inner.post(self.url.clone()).json(&request);
+        if let Some(BasicAuth { username, password }) = &self.basic_auth {
+            request_builder = request_builder.basic_auth(username, Some(password));
+        }
+
+        let response = request_builder
             .send()
             .await?
             .json::<Response<Res>>()
@@ -39,7 +59,9 @@ impl Client {
 
         match response {
             Response::Success { result } => Ok(result),
-            Response::Error { code, message } => Err(Error::JsonRpc { code, message }),
+            Response::Error {
+                error: ErrorObject { code, message },
+            } => Err(Error::JsonRpc { code, message }),
         }
     }
 }
@@ -67,7 +89,13 @@ impl<T> Request<T> {
 #[serde(untagged)]
 pub enum Response<T> {
     Success { result: T },
-    Error { code: i64, message: String },
+    Error { error: ErrorObject },
+}
+
+#[derive(serde::Deserialize, Debug)]
+pub struct ErrorObject {
+    code: i64,
+    message: String,
 }
 
 pub fn serialize<T>(t: T) -> anyhow::Result<serde_json::Value>
@@ -78,3 +106,26 @@ where
 
     Ok(value)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn deserializes_error_response() {
+        let response =
+            r#"{"result":null,"error":{"code":-5,"message":"Block not found"},"id":"1"}"#;
+
+        let response = serde_json::from_str::<Response<String>>(response).unwrap();
+
+        match response {
+            Response::Error {
+                error: ErrorObject { code, message },
+            } => {
+                assert_eq!(code, -5);
+                assert_eq!(message, "Block not found");
+            }
+            Response::Success { .. } => panic!("expected error response"),
+        }
+    }
+}
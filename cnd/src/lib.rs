@@ -30,15 +30,18 @@ pub mod libp2p_comit_ext;
 pub mod db;
 
 pub mod asset;
+pub mod autopilot;
 pub mod bitcoin;
 pub mod btsieve;
 pub mod comit_api;
 pub mod config;
 pub mod ethereum;
+pub mod expiry_monitor;
 pub mod http_api;
 pub mod init_swap;
 pub mod lightning;
 pub mod load_swaps;
+pub mod metrics;
 #[macro_use]
 pub mod network;
 #[cfg(test)]
@@ -46,11 +49,14 @@ pub mod quickcheck;
 #[macro_use]
 pub mod seed;
 pub mod file_lock;
+pub mod health;
 pub mod jsonrpc;
 #[cfg(test)]
 pub mod spectral_ext;
 pub mod swap_protocols;
+pub mod swap_status;
 pub mod timestamp;
+pub mod webhooks;
 
 use anyhow::Context;
 use std::{
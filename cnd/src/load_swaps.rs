@@ -1,8 +1,18 @@
 #![allow(clippy::type_repetition_in_bounds)]
 use crate::{
-    db::{DetermineTypes, LoadAcceptedSwap, Retrieve},
+    db::{
+        DetermineTypes, FinalizedHbitSwapData, FinalizedSwapData, LoadAcceptedSwap,
+        LoadCreatedSwaps, Retrieve,
+    },
     init_swap::init_accepted_swap,
-    swap_protocols::Rfc003Facade,
+    swap_protocols::{
+        halight, han, hbit, herc20, CreateSwapParams, Facade,
+        HalightBitcoinHanEthereumCreateSwapParams, HalightBitcoinHerc20CreateSwapParams,
+        HanEtherereumHalightBitcoinCreateSwapParams, HanEthereumHbitBitcoinCreateSwapParams,
+        HbitBitcoinHanEthereumCreateSwapParams, HbitBitcoinHerc20CreateSwapParams,
+        Herc20HalightBitcoinCreateSwapParams, Herc20HbitBitcoinCreateSwapParams, LocalSwapId,
+        Rfc003Facade,
+    },
 };
 
 #[allow(clippy::cognitive_complexity)]
@@ -31,3 +41,165 @@ pub async fn load_swaps_from_database(facade: Rfc003Facade) -> anyhow::Result<()
     }
     Ok(())
 }
+
+pub async fn load_split_protocol_swaps_from_database(facade: Facade) -> anyhow::Result<()> {
+    tracing::debug!("loading split protocol swaps from database ...");
+
+    let han_halight_swaps =
+        LoadCreatedSwaps::<han::CreatedSwap, halight::CreatedSwap>::load_created_swaps(&facade.db)
+            .await?
+            .into_iter()
+            .map(|(created, finalized)| {
+                (
+                    created.swap_id,
+                    CreateSwapParams::from(HanEtherereumHalightBitcoinCreateSwapParams::from(
+                        created,
+                    )),
+                    finalized,
+                )
+            });
+    let herc20_halight_swaps =
+        LoadCreatedSwaps::<herc20::CreatedSwap, halight::CreatedSwap>::load_created_swaps(
+            &facade.db,
+        )
+        .await?
+        .into_iter()
+        .map(|(created, finalized)| {
+            (
+                created.swap_id,
+                CreateSwapParams::from(Herc20HalightBitcoinCreateSwapParams::from(created)),
+                finalized,
+            )
+        });
+
+    let halight_han_swaps =
+        LoadCreatedSwaps::<halight::CreatedSwap, han::CreatedSwap>::load_created_swaps(&facade.db)
+            .await?
+            .into_iter()
+            .map(|(created, finalized)| {
+                (
+                    created.swap_id,
+                    CreateSwapParams::from(HalightBitcoinHanEthereumCreateSwapParams::from(
+                        created,
+                    )),
+                    finalized,
+                )
+            });
+    let halight_herc20_swaps =
+        LoadCreatedSwaps::<halight::CreatedSwap, herc20::CreatedSwap>::load_created_swaps(
+            &facade.db,
+        )
+        .await?
+        .into_iter()
+        .map(|(created, finalized)| {
+            (
+                created.swap_id,
+                CreateSwapParams::from(HalightBitcoinHerc20CreateSwapParams::from(created)),
+                finalized,
+            )
+        });
+
+    for (swap_id, swap_params, finalized) in han_halight_swaps
+        .chain(herc20_halight_swaps)
+        .chain(halight_han_swaps)
+        .chain(halight_herc20_swaps)
+    {
+        tracing::debug!("got swap from database: {}", swap_id);
+
+        resume_swap(&facade, swap_id, swap_params, finalized).await;
+    }
+
+    let han_hbit_swaps =
+        LoadCreatedSwaps::<han::CreatedSwap, hbit::CreatedSwap>::load_created_swaps(&facade.db)
+            .await?
+            .into_iter()
+            .map(|(created, finalized)| {
+                (
+                    created.swap_id,
+                    CreateSwapParams::from(HanEthereumHbitBitcoinCreateSwapParams::from(created)),
+                    finalized,
+                )
+            });
+    let herc20_hbit_swaps =
+        LoadCreatedSwaps::<herc20::CreatedSwap, hbit::CreatedSwap>::load_created_swaps(&facade.db)
+            .await?
+            .into_iter()
+            .map(|(created, finalized)| {
+                (
+                    created.swap_id,
+                    CreateSwapParams::from(Herc20HbitBitcoinCreateSwapParams::from(created)),
+                    finalized,
+                )
+            });
+
+    let hbit_han_swaps =
+        LoadCreatedSwaps::<hbit::CreatedSwap, han::CreatedSwap>::load_created_swaps(&facade.db)
+            .await?
+            .into_iter()
+            .map(|(created, finalized)| {
+                (
+                    created.swap_id,
+                    CreateSwapParams::from(HbitBitcoinHanEthereumCreateSwapParams::from(created)),
+                    finalized,
+                )
+            });
+    let hbit_herc20_swaps =
+        LoadCreatedSwaps::<hbit::CreatedSwap, herc20::CreatedSwap>::load_created_swaps(&facade.db)
+            .await?
+            .into_iter()
+            .map(|(created, finalized)| {
+                (
+                    created.swap_id,
+                    CreateSwapParams::from(HbitBitcoinHerc20CreateSwapParams::from(created)),
+                    finalized,
+                )
+            });
+
+    for (swap_id, swap_params, finalized) in han_hbit_swaps
+        .chain(herc20_hbit_swaps)
+        .chain(hbit_han_swaps)
+        .chain(hbit_herc20_swaps)
+    {
+        tracing::debug!("got swap from database: {}", swap_id);
+
+        resume_hbit_swap(&facade, swap_id, swap_params, finalized).await;
+    }
+
+    Ok(())
+}
+
+async fn resume_swap(
+    facade: &Facade,
+    swap_id: LocalSwapId,
+    swap_params: CreateSwapParams,
+    finalized: Option<FinalizedSwapData>,
+) {
+    match finalized {
+        Some(finalized) => facade.resume_finalized_swap(swap_params, finalized).await,
+        None => {
+            if let Err(e) = facade.initiate_communication(swap_id, swap_params).await {
+                tracing::error!("failed to resume swap: {}, continuing ...", e);
+            }
+        }
+    }
+}
+
+async fn resume_hbit_swap(
+    facade: &Facade,
+    swap_id: LocalSwapId,
+    swap_params: CreateSwapParams,
+    finalized: Option<FinalizedHbitSwapData>,
+) {
+    match finalized {
+        Some(finalized) => {
+            facade
+                .resume_finalized_hbit_swap(swap_params, finalized)
+                .await
+        }
+        None => {
+            if let Err(e) = facade.initiate_communication(swap_id, swap_params).await {
+                tracing::error!("failed to resume swap: {}, continuing ...", e);
+            }
+        }
+    }
+}
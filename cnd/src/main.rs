@@ -15,21 +15,25 @@
 use crate::cli::Options;
 use anyhow::Context;
 use cnd::{
+    autopilot::{self, Autopilot},
     btsieve::{
-        bitcoin::{self, BitcoindConnector},
+        bitcoin::{self, BitcoinConnector},
         ethereum::{self, Web3Connector},
     },
     config::{self, validation::validate_blockchain_config, Settings},
     db::Sqlite,
+    expiry_monitor::{self, ExpiryAlerts},
     file_lock::TryLockExclusive,
-    http_api::route_factory,
-    jsonrpc, load_swaps,
+    health::{self, Health},
+    http_api::{auth::Tokens, route_factory, tls},
+    jsonrpc, load_swaps, metrics,
     network::{Swarm, SwarmWorker},
     seed::RootSeed,
     swap_protocols::{
-        halight::States, rfc003, rfc003::SwapCommunicationStates, Facade, LedgerStates,
-        Rfc003Facade, SwapErrorStates,
+        halight::States, hbit, herc20, rfc003, rfc003::SwapCommunicationStates, Facade,
+        LedgerStates, Rfc003Facade, SwapErrorStates,
     },
+    swap_status, webhooks,
 };
 
 use cnd::swap_protocols::halight::LndConnectorParams;
@@ -37,6 +41,7 @@ use rand::rngs::OsRng;
 use std::{process, sync::Arc};
 use structopt::StructOpt;
 use tokio::{net::TcpListener, runtime};
+use tokio_rustls::TlsAcceptor;
 
 mod cli;
 mod trace;
@@ -58,11 +63,28 @@ fn main() -> anyhow::Result<()> {
 
     crate::trace::init_tracing(settings.logging.level)?;
 
+    // Nothing may be written to the data directory before we hold its lock,
+    // another instance might be using it.
+    std::fs::create_dir_all(&settings.data.dir)?;
+    let _locked_datadir = &settings.data.dir.try_lock_exclusive()?;
+
     let database = Sqlite::new_in_dir(&settings.data.dir)?;
 
     let seed = RootSeed::from_dir_or_generate(&settings.data.dir, OsRng)?;
 
-    let _locked_datadir = &settings.data.dir.try_lock_exclusive()?;
+    let api_tokens = if settings.http_api.auth.enabled {
+        Some(Arc::new(Tokens::from_dir_or_generate(
+            &settings.data.dir,
+            OsRng,
+        )?))
+    } else {
+        None
+    };
+
+    let tls_acceptor = match &settings.http_api.tls {
+        Some(config) => Some(tls::acceptor(config)?),
+        None => None,
+    };
 
     let mut runtime = runtime::Builder::new()
         .enable_all()
@@ -71,14 +93,21 @@ fn main() -> anyhow::Result<()> {
         .build()?;
 
     let bitcoin_connector = {
-        let config::Bitcoin { bitcoind, network } = &settings.bitcoin;
-        let connector = BitcoindConnector::new(bitcoind.node_url.clone(), *network)?;
+        let config::Bitcoin {
+            network,
+            min_confirmations,
+            ..
+        } = &settings.bitcoin;
+        let connector = BitcoinConnector::new(&settings.bitcoin)?;
 
         runtime.block_on(async {
             validate_blockchain_config(&connector, *network)
                 .await
                 .or_else::<anyhow::Error, _>(|e| {
-                    let conn_error = e.downcast::<reqwest::Error>()?;
+                    let conn_error = match e.downcast::<reqwest::Error>() {
+                        Ok(conn_error) => conn_error.to_string(),
+                        Err(e) => e.downcast::<jsonrpc::Error>()?.to_string(),
+                    };
                     tracing::warn!("Could not validate Bitcoin node config: {}", conn_error);
 
                     Ok(())
@@ -87,12 +116,30 @@ fn main() -> anyhow::Result<()> {
 
         const BITCOIN_BLOCK_CACHE_CAPACITY: usize = 144;
 
-        Arc::new(bitcoin::Cache::new(connector, BITCOIN_BLOCK_CACHE_CAPACITY))
+        Arc::new(bitcoin::Cache::new(
+            connector,
+            BITCOIN_BLOCK_CACHE_CAPACITY,
+            *min_confirmations,
+        ))
     };
 
     let ethereum_connector = {
-        let config::Ethereum { parity, chain_id } = &settings.ethereum;
-        let connector = Web3Connector::new(parity.node_url.clone());
+        let config::Ethereum {
+            parity,
+            chain_id,
+            min_confirmations,
+            ..
+        } = &settings.ethereum;
+        let connector = match &parity.ws_url {
+            Some(ws_url) => {
+                let (connector, new_heads) =
+                    Web3Connector::with_websocket(parity.node_url.clone(), ws_url.clone());
+                runtime.spawn(new_heads);
+
+                connector
+            }
+            None => Web3Connector::new(parity.node_url.clone()),
+        };
 
         runtime.block_on(async {
             validate_blockchain_config(&connector, *chain_id)
@@ -112,6 +159,7 @@ fn main() -> anyhow::Result<()> {
             connector,
             ETHEREUM_BLOCK_CACHE_CAPACITY,
             ETHEREUM_RECEIPT_CACHE_CAPACITY,
+            *min_confirmations,
         ))
     };
 
@@ -137,56 +185,115 @@ fn main() -> anyhow::Result<()> {
     // Han/HErc20 protocols (A.K.A split protocols)
     let alpha_ledger_states = Arc::new(LedgerStates::default());
     let beta_ledger_states = Arc::new(LedgerStates::default());
+    let herc20_states = Arc::new(herc20::States::default());
+    let hbit_states = Arc::new(hbit::States::default());
 
     // HALight
     let halight_states = Arc::new(States::default());
 
     let swap_error_states = Arc::new(SwapErrorStates::default());
+    let expiry_alerts = Arc::new(ExpiryAlerts::default());
 
     let swarm = Swarm::new(
         &settings,
         seed,
         Arc::clone(&bitcoin_connector),
         Arc::clone(&ethereum_connector),
-        lnd_connector_params,
+        lnd_connector_params.clone(),
         Arc::clone(&swap_communication_states),
         Arc::clone(&rfc003_alpha_ledger_states),
         Arc::clone(&rfc003_beta_ledger_states),
         Arc::clone(&alpha_ledger_states),
         Arc::clone(&beta_ledger_states),
+        Arc::clone(&herc20_states),
         Arc::clone(&halight_states),
+        Arc::clone(&hbit_states),
         &database,
         runtime.handle().clone(),
     )?;
 
     // RCF003 protocol
     let rfc003_facade = Rfc003Facade {
-        bitcoin_connector,
-        ethereum_connector,
+        bitcoin_connector: Arc::clone(&bitcoin_connector),
+        ethereum_connector: Arc::clone(&ethereum_connector),
         alpha_ledger_states: Arc::clone(&rfc003_alpha_ledger_states),
         beta_ledger_states: Arc::clone(&&rfc003_beta_ledger_states),
         swap_communication_states,
         swap_error_states,
+        expiry_alerts: Arc::clone(&expiry_alerts),
         seed,
-        db: database,
+        db: database.clone(),
         swarm: swarm.clone(),
+        bitcoin_start_height_margin: settings.bitcoin.start_height_margin,
+        ethereum_start_height_margin: settings.ethereum.start_height_margin,
     };
 
     // split protocols
     let facade = Facade {
         swarm: swarm.clone(),
-        alpha_ledger_states: Arc::clone(&alpha_ledger_states),
-        beta_ledger_states: Arc::clone(&halight_states),
+        han_states: Arc::clone(&alpha_ledger_states),
+        herc20_states,
+        halight_states: Arc::clone(&halight_states),
+        hbit_states,
+        bitcoin_connector,
+        ethereum_connector,
+        expiry_alerts,
+        db: database.clone(),
+        bitcoin_start_height_margin: settings.bitcoin.start_height_margin,
+        ethereum_start_height_margin: settings.ethereum.start_height_margin,
     };
 
-    let http_api_listener = runtime.block_on(bind_http_api_socket(&settings))?;
-    runtime.block_on(load_swaps::load_swaps_from_database(rfc003_facade.clone()))?;
+    let autopilot = Autopilot::new(&settings, database)?;
 
+    let http_api_listeners = runtime.block_on(bind_http_api_socket(&settings))?;
+    let metrics_listener = match settings.metrics {
+        Some(metrics) => Some(runtime.block_on(TcpListener::bind(metrics.socket))?),
+        None => None,
+    };
+    // Started before the swaps are loaded to pick up the state they resume in.
+    runtime.spawn(swap_status::track(rfc003_facade.clone(), facade.clone()));
+    runtime.block_on(load_swaps::load_swaps_from_database(rfc003_facade.clone()))?;
+    runtime.block_on(load_swaps::load_split_protocol_swaps_from_database(
+        facade.clone(),
+    ))?;
+
+    let health = Arc::new(Health::default());
+    runtime.spawn(health::run(
+        Arc::clone(&health),
+        settings.clone(),
+        rfc003_facade.clone(),
+        lnd_connector_params,
+    ));
+    runtime.spawn(expiry_monitor::run(
+        settings.expiry_monitor.clone(),
+        rfc003_facade.clone(),
+        facade.clone(),
+    ));
+    if !settings.webhooks.is_empty() {
+        runtime.spawn(webhooks::run(
+            settings.webhooks.clone(),
+            rfc003_facade.clone(),
+            facade.clone(),
+        ));
+    }
+    if let Some(autopilot) = autopilot {
+        runtime.spawn(autopilot::run(
+            autopilot,
+            rfc003_facade.clone(),
+            facade.clone(),
+        ));
+    }
+    if let Some(metrics_listener) = metrics_listener {
+        runtime.spawn(metrics::serve(metrics_listener, rfc003_facade.clone()));
+    }
     runtime.spawn(make_http_api_worker(
         settings,
         rfc003_facade,
         facade,
-        http_api_listener,
+        api_tokens,
+        health,
+        tls_acceptor,
+        http_api_listeners,
     ));
     runtime.spawn(make_network_api_worker(swarm));
 
@@ -206,16 +313,101 @@ fn version() {
     println!("{} {} ({})", name, version, short);
 }
 
-/// Binds to the socket for the HTTP API specified in the settings
+/// The sockets the HTTP API is served on.
+struct HttpApiListeners {
+    tcp: Option<TcpListener>,
+    #[cfg(unix)]
+    unix: Option<tokio::net::UnixListener>,
+}
+
+/// Binds to the sockets for the HTTP API specified in the settings
 ///
-/// Fails if we cannot bind to the socket.
+/// Fails if we cannot bind to a socket.
 /// We do this ourselves so we can shut down if this fails and don't just panic
 /// some worker thread in tokio.
-async fn bind_http_api_socket(settings: &Settings) -> anyhow::Result<tokio::net::TcpListener> {
-    let listen_addr = settings.http_api.socket;
-    let listener = TcpListener::bind(listen_addr).await?;
+async fn bind_http_api_socket(settings: &Settings) -> anyhow::Result<HttpApiListeners> {
+    let tcp = match settings.http_api.socket {
+        Some(listen_addr) => Some(TcpListener::bind(listen_addr).await?),
+        None => None,
+    };
 
-    Ok(listener)
+    #[cfg(unix)]
+    let unix = match &settings.http_api.unix_socket {
+        Some(path) => Some(bind_unix_socket(path)?),
+        None => None,
+    };
+    #[cfg(not(unix))]
+    anyhow::ensure!(
+        settings.http_api.unix_socket.is_none(),
+        "Unix sockets are not supported on this platform"
+    );
+
+    Ok(HttpApiListeners {
+        tcp,
+        #[cfg(unix)]
+        unix,
+    })
+}
+
+/// Binds to the Unix socket at `path`, only its owner and group can connect.
+///
+/// The socket is bound inside a directory that only we can access and moved
+/// to `path` once its permissions are restricted, nobody can connect to it in
+/// between.
+#[cfg(unix)]
+fn bind_unix_socket(path: &std::path::Path) -> anyhow::Result<tokio::net::UnixListener> {
+    use std::{
+        fs::{self, DirBuilder, Permissions},
+        os::unix::fs::{DirBuilderExt, FileTypeExt, PermissionsExt},
+    };
+
+    // The socket file of a previous run is not removed when cnd stops.
+    if let Ok(metadata) = fs::symlink_metadata(path) {
+        anyhow::ensure!(
+            metadata.file_type().is_socket(),
+            "{} exists and is not a socket",
+            path.display()
+        );
+        fs::remove_file(path)?;
+    }
+
+    let file_name = path
+        .file_name()
+        .with_context(|| format!("{} does not name a file", path.display()))?;
+    let private_dir = path.with_file_name(format!(".{}.bind", file_name.to_string_lossy()));
+    let private_path = private_dir.join(file_name);
+
+    // Left behind if cnd was stopped while binding.
+    if let Ok(metadata) = fs::symlink_metadata(&private_dir) {
+        anyhow::ensure!(
+            metadata.is_dir(),
+            "{} exists and is not a directory",
+            private_dir.display()
+        );
+        let _ = fs::remove_file(&private_path);
+        fs::remove_dir(&private_dir)
+            .with_context(|| format!("failed to remove {}", private_dir.display()))?;
+    }
+
+    // Fails if the directory was created by someone else in the meantime.
+    DirBuilder::new()
+        .mode(0o700)
+        .create(&private_dir)
+        .with_context(|| format!("failed to create {}", private_dir.display()))?;
+
+    let listener = tokio::net::UnixListener::bind(&private_path)
+        .with_context(|| format!("failed to bind to {}", path.display()))
+        .and_then(|listener| {
+            fs::set_permissions(&private_path, Permissions::from_mode(0o660))?;
+            fs::rename(&private_path, path)?;
+
+            Ok(listener)
+        });
+
+    let _ = fs::remove_file(&private_path);
+    fs::remove_dir(&private_dir)?;
+
+    listener
 }
 
 /// Construct the worker that is going to process HTTP API requests.
@@ -223,23 +415,65 @@ async fn make_http_api_worker(
     settings: Settings,
     rfc003_facade: Rfc003Facade,
     facade: Facade,
-    incoming_requests: tokio::net::TcpListener,
+    api_tokens: Option<Arc<Tokens>>,
+    health: Arc<Health>,
+    tls_acceptor: Option<TlsAcceptor>,
+    listeners: HttpApiListeners,
 ) {
     let routes = route_factory::create(
         rfc003_facade,
         facade,
         &settings.http_api.cors.allowed_origins,
+        api_tokens,
+        health,
     );
 
-    match incoming_requests.local_addr() {
-        Ok(socket) => {
-            tracing::info!("Starting HTTP server on {} ...", socket);
-            warp::serve(routes).serve_incoming(incoming_requests).await;
+    let HttpApiListeners {
+        tcp,
+        #[cfg(unix)]
+        unix,
+    } = listeners;
+
+    let tcp = {
+        let routes = routes.clone();
+
+        async move {
+            let incoming_requests = match tcp {
+                Some(listener) => listener,
+                None => return,
+            };
+
+            match incoming_requests.local_addr() {
+                Ok(socket) => match tls_acceptor {
+                    Some(tls_acceptor) => {
+                        tracing::info!("Starting HTTPS server on {} ...", socket);
+                        warp::serve(routes)
+                            .serve_incoming(tls::incoming(incoming_requests, tls_acceptor))
+                            .await;
+                    }
+                    None => {
+                        tracing::info!("Starting HTTP server on {} ...", socket);
+                        warp::serve(routes).serve_incoming(incoming_requests).await;
+                    }
+                },
+                Err(e) => {
+                    tracing::error!("Cannot start HTTP server because {:?}", e);
+                }
+            }
         }
-        Err(e) => {
-            tracing::error!("Cannot start HTTP server because {:?}", e);
+    };
+
+    #[cfg(unix)]
+    let unix = async move {
+        if let (Some(incoming_requests), Some(path)) = (unix, settings.http_api.unix_socket) {
+            tracing::info!("Starting HTTP server on {} ...", path.display());
+            warp::serve(routes).serve_incoming(incoming_requests).await;
         }
-    }
+    };
+    #[cfg(not(unix))]
+    let unix = futures::future::ready(());
+
+    futures::future::join(tcp, unix).await;
 }
 
 /// Construct the worker that is going to process network (i.e. COMIT)
@@ -283,3 +517,22 @@ fn dump_config(settings: Settings) -> anyhow::Result<()> {
     println!("{}", serialized);
     Ok(())
 }
+
+#[cfg(all(test, unix))]
+mod tests {
+    use super::*;
+    use std::{fs, os::unix::fs::PermissionsExt};
+
+    #[tokio::test]
+    async fn unix_socket_is_only_accessible_by_owner_and_group() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("cnd.sock");
+
+        let _first_run = bind_unix_socket(&path).unwrap();
+        let _listener = bind_unix_socket(&path).unwrap();
+
+        let mode = fs::metadata(&path).unwrap().permissions().mode();
+        assert_eq!(mode & 0o777, 0o660);
+        assert_eq!(fs::read_dir(dir.path()).unwrap().count(), 1);
+    }
+}
@@ -0,0 +1,214 @@
+//! Prometheus metrics, served at `/metrics` if enabled in the settings.
+//!
+//! Most metrics are recorded where things happen. The number of swaps and
+//! connected peers are looked up whenever the metrics are scraped.
+use crate::{network::ComitPeers, swap_protocols::Rfc003Facade};
+use futures::Future;
+use prometheus::{
+    register_histogram_vec, register_int_counter_vec, register_int_gauge, register_int_gauge_vec,
+    Encoder, HistogramVec, IntCounterVec, IntGauge, IntGaugeVec, TextEncoder,
+};
+use std::{
+    convert::TryFrom,
+    time::{Duration, Instant},
+};
+use tokio::net::TcpListener;
+use warp::{http::StatusCode, Filter, Rejection, Reply};
+
+lazy_static::lazy_static! {
+    static ref SWAPS: IntGaugeVec = register_int_gauge_vec!(
+        "cnd_swaps",
+        "Number of swaps by kind and status.",
+        &["kind", "status"]
+    )
+    .expect("metric can be registered");
+    static ref SWAP_PHASE_DURATION: HistogramVec = register_histogram_vec!(
+        "cnd_swap_phase_duration_seconds",
+        "Time a swap spent after an event until the next one.",
+        &["phase"],
+        vec![1.0, 10.0, 60.0, 300.0, 900.0, 3600.0, 14_400.0, 43_200.0, 86_400.0]
+    )
+    .expect("metric can be registered");
+    static ref BTSIEVE_BLOCKS_FETCHED: IntCounterVec = register_int_counter_vec!(
+        "cnd_btsieve_blocks_fetched_total",
+        "Number of blocks btsieve fetched from the node.",
+        &["ledger"]
+    )
+    .expect("metric can be registered");
+    static ref BTSIEVE_CACHE_HITS: IntCounterVec = register_int_counter_vec!(
+        "cnd_btsieve_cache_hits_total",
+        "Number of lookups answered by the btsieve cache.",
+        &["ledger", "cache"]
+    )
+    .expect("metric can be registered");
+    static ref BTSIEVE_CACHE_MISSES: IntCounterVec = register_int_counter_vec!(
+        "cnd_btsieve_cache_misses_total",
+        "Number of lookups the btsieve cache had to forward to the node.",
+        &["ledger", "cache"]
+    )
+    .expect("metric can be registered");
+    static ref NODE_RPC_DURATION: HistogramVec = register_histogram_vec!(
+        "cnd_node_rpc_duration_seconds",
+        "Latency of requests to the blockchain nodes.",
+        &["ledger", "method"]
+    )
+    .expect("metric can be registered");
+    static ref NODE_RPC_ERRORS: IntCounterVec = register_int_counter_vec!(
+        "cnd_node_rpc_errors_total",
+        "Number of failed requests to the blockchain nodes.",
+        &["ledger", "method"]
+    )
+    .expect("metric can be registered");
+    static ref CONNECTED_PEERS: IntGauge = register_int_gauge!(
+        "cnd_connected_peers",
+        "Number of connected COMIT peers."
+    )
+    .expect("metric can be registered");
+    static ref COMIT_REQUESTS: IntCounterVec = register_int_counter_vec!(
+        "cnd_comit_requests_total",
+        "Number of COMIT swap requests by direction and response.",
+        &["direction", "status"]
+    )
+    .expect("metric can be registered");
+    static ref LND_POLLS: IntCounterVec = register_int_counter_vec!(
+        "cnd_lnd_polls_total",
+        "Number of times we asked lnd for the state of a payment or an invoice.",
+        &["resource"]
+    )
+    .expect("metric can be registered");
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Direction {
+    Inbound,
+    Outbound,
+}
+
+impl Direction {
+    fn label(self) -> &'static str {
+        match self {
+            Direction::Inbound => "inbound",
+            Direction::Outbound => "outbound",
+        }
+    }
+}
+
+pub fn observe_swap_phase(phase: &str, duration: Duration) {
+    SWAP_PHASE_DURATION
+        .with_label_values(&[phase])
+        .observe(duration.as_secs_f64());
+}
+
+pub fn record_block_fetched(ledger: &str) {
+    BTSIEVE_BLOCKS_FETCHED.with_label_values(&[ledger]).inc();
+}
+
+pub fn record_cache_hit(ledger: &str, cache: &str) {
+    BTSIEVE_CACHE_HITS.with_label_values(&[ledger, cache]).inc();
+}
+
+pub fn record_cache_miss(ledger: &str, cache: &str) {
+    BTSIEVE_CACHE_MISSES
+        .with_label_values(&[ledger, cache])
+        .inc();
+}
+
+/// Records how long `request` to the node of `ledger` takes and whether it
+/// fails.
+pub async fn observe_node_rpc<F, T, E>(ledger: &str, method: &str, request: F) -> Result<T, E>
+where
+    F: Future<Output = Result<T, E>>,
+{
+    let started = Instant::now();
+    let result = request.await;
+
+    NODE_RPC_DURATION
+        .with_label_values(&[ledger, method])
+        .observe(started.elapsed().as_secs_f64());
+    if result.is_err() {
+        NODE_RPC_ERRORS.with_label_values(&[ledger, method]).inc();
+    }
+
+    result
+}
+
+pub fn record_comit_request(direction: Direction, status: &str) {
+    COMIT_REQUESTS
+        .with_label_values(&[direction.label(), status])
+        .inc();
+}
+
+pub fn record_lnd_poll(resource: &str) {
+    LND_POLLS.with_label_values(&[resource]).inc();
+}
+
+/// Serves the metrics at `/metrics` to the clients connecting to `listener`.
+pub async fn serve(listener: TcpListener, facade: Rfc003Facade) {
+    let route = warp::path!("metrics")
+        .and(warp::get())
+        .and(warp::any().map(move || facade.clone()))
+        .and_then(scrape);
+
+    match listener.local_addr() {
+        Ok(socket) => {
+            tracing::info!("Serving metrics on {} ...", socket);
+            warp::serve(route).serve_incoming(listener).await;
+        }
+        Err(e) => tracing::error!("Cannot serve metrics because {:?}", e),
+    }
+}
+
+async fn scrape(facade: Rfc003Facade) -> Result<impl Reply, Rejection> {
+    match facade.db.count_swaps().await {
+        Ok(counts) => {
+            SWAPS.reset();
+            for ((kind, status), count) in counts {
+                let kind = kind.to_string();
+                let status = status.to_string();
+                SWAPS
+                    .with_label_values(&[kind.as_str(), status.as_str()])
+                    .set(i64::try_from(count).unwrap_or(i64::MAX));
+            }
+        }
+        Err(e) => tracing::warn!("failed to count swaps: {:?}", e),
+    }
+
+    let peers = facade.comit_peers().await.count();
+    CONNECTED_PEERS.set(i64::try_from(peers).unwrap_or(i64::MAX));
+
+    let encoder = TextEncoder::new();
+    let mut buffer = Vec::new();
+    if let Err(e) = encoder.encode(&prometheus::gather(), &mut buffer) {
+        tracing::error!("failed to encode metrics: {:?}", e);
+        return Ok(warp::http::Response::builder()
+            .status(StatusCode::INTERNAL_SERVER_ERROR)
+            .body(Vec::new()));
+    }
+
+    Ok(warp::http::Response::builder()
+        .header("content-type", encoder.format_type())
+        .body(buffer))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn node_rpc_errors_are_counted() {
+        let _ = observe_node_rpc("test", "ok", async { Ok::<_, ()>(()) }).await;
+        let _ = observe_node_rpc("test", "fails", async { Err::<(), _>(()) }).await;
+
+        assert_eq!(NODE_RPC_ERRORS.with_label_values(&["test", "ok"]).get(), 0);
+        assert_eq!(
+            NODE_RPC_ERRORS.with_label_values(&["test", "fails"]).get(),
+            1
+        );
+        assert_eq!(
+            NODE_RPC_DURATION
+                .with_label_values(&["test", "fails"])
+                .get_sample_count(),
+            1
+        );
+    }
+}
@@ -9,37 +9,45 @@ pub mod transport;
 pub use transport::ComitTransport;
 
 use crate::{
-    asset::AssetKind,
+    asset::{self, AssetKind},
     btsieve::{
-        bitcoin::{self, BitcoindConnector},
+        bitcoin::{self, BitcoinConnector},
         ethereum::{self, Web3Connector},
+        StartOfSwap,
     },
     comit_api::LedgerKind,
     config::Settings,
-    db::{Save, Sqlite, Swap},
+    db::{FinalizedHbitSwapData, FinalizedSwapData, Ledger, Save, Sqlite, Swap, SwapStartHeights},
     htlc_location,
     http_api::LedgerNotConfigured,
+    identity,
     libp2p_comit_ext::{FromHeader, ToHeader},
+    metrics::{self, Direction},
     network::comit_ln::ComitLN,
     seed::RootSeed,
     swap_protocols::{
         halight,
         halight::{LndConnectorAsReceiver, LndConnectorAsSender, LndConnectorParams, States},
-        han, ledger,
+        han, hbit, herc20, ledger,
         rfc003::{
             self,
             create_swap::HtlcParams,
             messages::{Decision, DeclineResponseBody, Request, RequestBody, SwapDeclineReason},
             state::Insert,
-            LedgerState, SwapCommunication, SwapCommunicationStates, SwapId,
+            LedgerState, SecretHash, SwapCommunication, SwapCommunicationStates, SwapId,
         },
-        HanEtherereumHalightBitcoinCreateSwapParams, HashFunction, LedgerStates, LocalSwapId, Role,
-        SwapProtocol,
+        CreateSwapParams, HalightBitcoinHanEthereumCreateSwapParams,
+        HalightBitcoinHerc20CreateSwapParams, HanEtherereumHalightBitcoinCreateSwapParams,
+        HanEthereumHbitBitcoinCreateSwapParams, HashFunction,
+        HbitBitcoinHanEthereumCreateSwapParams, HbitBitcoinHerc20CreateSwapParams,
+        Herc20HalightBitcoinCreateSwapParams, Herc20HbitBitcoinCreateSwapParams, LedgerStates,
+        LocalSwapId, Role, SwapProtocol,
     },
     transaction,
 };
 use anyhow::Context;
 use async_trait::async_trait;
+use chrono::{NaiveDateTime, Utc};
 use futures::{
     channel::oneshot::{self, Sender},
     stream::StreamExt,
@@ -82,7 +90,7 @@ impl Swarm {
     pub fn new(
         settings: &Settings,
         seed: RootSeed,
-        bitcoin_connector: Arc<bitcoin::Cache<BitcoindConnector>>,
+        bitcoin_connector: Arc<bitcoin::Cache<BitcoinConnector>>,
         ethereum_connector: Arc<ethereum::Cache<Web3Connector>>,
         lnd_connector_params: Option<LndConnectorParams>,
         swap_communication_states: Arc<SwapCommunicationStates>,
@@ -90,7 +98,9 @@ impl Swarm {
         rfc003_beta_ledger_states: Arc<rfc003::LedgerStates>,
         alpha_ledger_states: Arc<LedgerStates>,
         beta_ledger_states: Arc<LedgerStates>,
+        herc20_states: Arc<herc20::States>,
         halight_states: Arc<States>,
+        hbit_states: Arc<hbit::States>,
         database: &Sqlite,
         task_executor: tokio::runtime::Handle,
     ) -> anyhow::Result<Self> {
@@ -108,7 +118,9 @@ impl Swarm {
             rfc003_beta_ledger_states,
             alpha_ledger_states,
             beta_ledger_states,
+            herc20_states,
             halight_states,
+            hbit_states,
             seed,
             database.clone(),
             task_executor.clone(),
@@ -136,22 +148,68 @@ impl Swarm {
     pub async fn initiate_communication(
         &self,
         id: LocalSwapId,
-        swap_params: HanEtherereumHalightBitcoinCreateSwapParams,
+        swap_params: CreateSwapParams,
     ) -> anyhow::Result<()> {
         let mut guard = self.inner.lock().await;
 
         guard.initiate_communication(id, swap_params)
     }
 
-    pub async fn get_finalized_swap(&self, id: LocalSwapId) -> Option<comit_ln::FinalizedSwap> {
+    pub async fn get_finalized_swap(
+        &self,
+        id: LocalSwapId,
+    ) -> Option<comit_ln::FinalizedSwap<asset::Ether>> {
         let mut guard = self.inner.lock().await;
 
         guard.get_finalized_swap(id)
     }
 
-    // On Bob's side, when an announce message is received execute the required
-    // communication protocols and write the finalized swap to the database.  Then
-    // spawn the same as is done for Alice.
+    pub async fn get_finalized_herc20_swap(
+        &self,
+        id: LocalSwapId,
+    ) -> Option<comit_ln::FinalizedSwap<asset::Erc20>> {
+        let mut guard = self.inner.lock().await;
+
+        guard.get_finalized_herc20_swap(id)
+    }
+
+    pub async fn get_finalized_han_hbit_swap(
+        &self,
+        id: LocalSwapId,
+    ) -> Option<comit_ln::FinalizedHbitSwap<asset::Ether>> {
+        let mut guard = self.inner.lock().await;
+
+        guard.get_finalized_han_hbit_swap(id)
+    }
+
+    pub async fn get_finalized_herc20_hbit_swap(
+        &self,
+        id: LocalSwapId,
+    ) -> Option<comit_ln::FinalizedHbitSwap<asset::Erc20>> {
+        let mut guard = self.inner.lock().await;
+
+        guard.get_finalized_herc20_hbit_swap(id)
+    }
+
+    pub async fn resume_finalized_swap(
+        &self,
+        swap_params: CreateSwapParams,
+        finalized: FinalizedSwapData,
+    ) {
+        let mut guard = self.inner.lock().await;
+
+        guard.resume_finalized_swap(swap_params, finalized)
+    }
+
+    pub async fn resume_finalized_hbit_swap(
+        &self,
+        swap_params: CreateSwapParams,
+        finalized: FinalizedHbitSwapData,
+    ) {
+        let mut guard = self.inner.lock().await;
+
+        guard.resume_finalized_hbit_swap(swap_params, finalized)
+    }
 }
 
 struct TokioExecutor {
@@ -207,7 +265,7 @@ pub struct ComitNode {
 
     // blockchain connectors
     #[behaviour(ignore)]
-    pub bitcoin_connector: Arc<bitcoin::Cache<BitcoindConnector>>,
+    pub bitcoin_connector: Arc<bitcoin::Cache<BitcoinConnector>>,
     #[behaviour(ignore)]
     pub ethereum_connector: Arc<ethereum::Cache<Web3Connector>>,
     #[behaviour(ignore)]
@@ -230,14 +288,19 @@ pub struct ComitNode {
     #[behaviour(ignore)]
     response_channels: Arc<Mutex<HashMap<SwapId, oneshot::Sender<libp2p_comit::frame::Response>>>>,
 
-    // These likely go away once han and herc20 is done.
+    // These likely go away once han and herc20 is done. Han swaps are tracked
+    // in `alpha_ledger_states` regardless of the ledger Ethereum is used on.
     #[behaviour(ignore)]
     pub alpha_ledger_states: Arc<LedgerStates>,
     #[behaviour(ignore)]
     pub beta_ledger_states: Arc<LedgerStates>,
 
+    #[behaviour(ignore)]
+    herc20_states: Arc<herc20::States>,
     #[behaviour(ignore)]
     halight_states: Arc<States>,
+    #[behaviour(ignore)]
+    hbit_states: Arc<hbit::States>,
 }
 
 #[derive(Clone, Debug, PartialEq)]
@@ -275,7 +338,7 @@ pub struct Reason {
 impl ComitNode {
     #[allow(clippy::too_many_arguments)]
     pub fn new(
-        bitcoin_connector: Arc<bitcoin::Cache<BitcoindConnector>>,
+        bitcoin_connector: Arc<bitcoin::Cache<BitcoinConnector>>,
         ethereum_connector: Arc<ethereum::Cache<Web3Connector>>,
         lnd_connector_params: Option<LndConnectorParams>,
         swap_communication_states: Arc<SwapCommunicationStates>,
@@ -283,7 +346,9 @@ impl ComitNode {
         rfc003_beta_ledger_states: Arc<rfc003::LedgerStates>,
         alpha_ledger_states: Arc<LedgerStates>,
         beta_ledger_states: Arc<LedgerStates>,
+        herc20_states: Arc<herc20::States>,
         halight_states: Arc<States>,
+        hbit_states: Arc<hbit::States>,
         seed: RootSeed,
         db: Sqlite,
         task_executor: Handle,
@@ -315,7 +380,9 @@ impl ComitNode {
             response_channels: Arc::new(Mutex::new(HashMap::new())),
             task_executor,
             lnd_connector_params: lnd_connector_params.map(Arc::new),
+            herc20_states,
             halight_states,
+            hbit_states,
         })
     }
 
@@ -332,16 +399,382 @@ impl ComitNode {
     pub fn initiate_communication(
         &mut self,
         id: LocalSwapId,
-        swap_params: HanEtherereumHalightBitcoinCreateSwapParams,
+        swap_params: CreateSwapParams,
     ) -> anyhow::Result<()> {
-        self.supports_halight()?;
+        if swap_params.uses_halight() {
+            self.supports_halight()?;
+        }
         self.comit_ln.initiate_communication(id, swap_params)
     }
 
-    pub fn get_finalized_swap(&mut self, id: LocalSwapId) -> Option<comit_ln::FinalizedSwap> {
+    pub fn get_finalized_swap(
+        &mut self,
+        id: LocalSwapId,
+    ) -> Option<comit_ln::FinalizedSwap<asset::Ether>> {
         self.comit_ln.get_finalized_swap(id)
     }
 
+    pub fn get_finalized_herc20_swap(
+        &mut self,
+        id: LocalSwapId,
+    ) -> Option<comit_ln::FinalizedSwap<asset::Erc20>> {
+        self.comit_ln.get_finalized_herc20_swap(id)
+    }
+
+    pub fn get_finalized_han_hbit_swap(
+        &mut self,
+        id: LocalSwapId,
+    ) -> Option<comit_ln::FinalizedHbitSwap<asset::Ether>> {
+        self.comit_ln.get_finalized_han_hbit_swap(id)
+    }
+
+    pub fn get_finalized_herc20_hbit_swap(
+        &mut self,
+        id: LocalSwapId,
+    ) -> Option<comit_ln::FinalizedHbitSwap<asset::Erc20>> {
+        self.comit_ln.get_finalized_herc20_hbit_swap(id)
+    }
+
+    /// Restores a swap that was finalized before cnd was restarted and
+    /// resumes watching the ledgers for it.
+    pub fn resume_finalized_swap(
+        &mut self,
+        create_swap_params: CreateSwapParams,
+        finalized: FinalizedSwapData,
+    ) {
+        let local_swap_id = finalized.swap_id;
+
+        self.comit_ln.restore_finalized_swap(
+            local_swap_id,
+            create_swap_params.clone(),
+            finalized.clone(),
+        );
+        self.spawn_swap_watchers(local_swap_id, create_swap_params, finalized);
+    }
+
+    /// Same as `resume_finalized_swap` for swaps that use hbit.
+    pub fn resume_finalized_hbit_swap(
+        &mut self,
+        create_swap_params: CreateSwapParams,
+        finalized: FinalizedHbitSwapData,
+    ) {
+        let local_swap_id = finalized.swap_id;
+
+        self.comit_ln.restore_finalized_hbit_swap(
+            local_swap_id,
+            create_swap_params.clone(),
+            finalized.clone(),
+        );
+        self.spawn_hbit_swap_watchers(local_swap_id, create_swap_params, finalized);
+    }
+
+    fn spawn_swap_watchers(
+        &self,
+        local_swap_id: LocalSwapId,
+        create_swap_params: CreateSwapParams,
+        finalized: FinalizedSwapData,
+    ) {
+        let role = create_swap_params.role();
+        let secret_hash = finalized.secret_hash;
+        let start_of_swap = finalized.start_of_swap;
+
+        let lnd_connector_params = match self.lnd_connector_params {
+            None => {
+                tracing::error!("Internal Failure: lnd connectors are not initialised, no action has been taken. This should be unreachable.");
+                return;
+            }
+            Some(ref lnd_connector_params) => (**lnd_connector_params).clone(),
+        };
+
+        // The party funding the Ethereum HTLC receives the Lightning payment, hence it
+        // refunds on Ethereum and adds the hold invoice on Lightning.
+        let funds_ethereum = create_swap_params.funds_ethereum();
+
+        if funds_ethereum {
+            let lnd_connector: LndConnectorAsReceiver = lnd_connector_params.into();
+
+            tokio::task::spawn(
+                halight::new_halight_swap(
+                    local_swap_id,
+                    secret_hash,
+                    self.halight_states.clone(),
+                    lnd_connector,
+                )
+                .instrument(tracing::error_span!(
+                    "lightning_ledger",
+                    swap_id = %local_swap_id,
+                    role = %role
+                )),
+            );
+        } else {
+            let lnd_connector: LndConnectorAsSender = lnd_connector_params.into();
+
+            tokio::task::spawn(
+                halight::new_halight_swap(
+                    local_swap_id,
+                    secret_hash,
+                    self.halight_states.clone(),
+                    lnd_connector,
+                )
+                .instrument(tracing::error_span!(
+                    "lightning_ledger",
+                    swap_id = %local_swap_id,
+                    role = %role
+                )),
+            );
+        }
+
+        self.spawn_ethereum_swap_watcher(
+            local_swap_id,
+            create_swap_params,
+            secret_hash,
+            finalized.counterparty_ethereum_identity,
+            start_of_swap,
+        );
+    }
+
+    fn spawn_hbit_swap_watchers(
+        &self,
+        local_swap_id: LocalSwapId,
+        create_swap_params: CreateSwapParams,
+        finalized: FinalizedHbitSwapData,
+    ) {
+        let role = create_swap_params.role();
+        let secret_hash = finalized.secret_hash;
+        let start_of_swap = finalized.start_of_swap;
+        let counterparty_bitcoin_identity = finalized.counterparty_bitcoin_identity;
+
+        let bitcoin_ledger = match create_swap_params.bitcoin_ledger() {
+            Some(bitcoin_ledger) => bitcoin_ledger,
+            None => {
+                tracing::error!("Internal Failure: swap {} does not use hbit, no action has been taken. This should be unreachable.", local_swap_id);
+                return;
+            }
+        };
+
+        // The party funding the Ethereum HTLC redeems the Bitcoin HTLC.
+        let funds_ethereum = create_swap_params.funds_ethereum();
+        let bitcoin_identity =
+            comit_ln::derive_bitcoin_identity(&self.seed, local_swap_id, funds_ethereum);
+        let (redeem_identity, refund_identity) = if funds_ethereum {
+            (bitcoin_identity, counterparty_bitcoin_identity)
+        } else {
+            (counterparty_bitcoin_identity, bitcoin_identity)
+        };
+
+        let (asset, expiry, network) = match create_swap_params {
+            CreateSwapParams::HanEthereumHbitBitcoin(HanEthereumHbitBitcoinCreateSwapParams {
+                bitcoin_amount,
+                bitcoin_absolute_expiry,
+                bitcoin_network,
+                ..
+            })
+            | CreateSwapParams::Herc20EthereumHbitBitcoin(Herc20HbitBitcoinCreateSwapParams {
+                bitcoin_amount,
+                bitcoin_absolute_expiry,
+                bitcoin_network,
+                ..
+            })
+            | CreateSwapParams::HbitBitcoinHanEthereum(HbitBitcoinHanEthereumCreateSwapParams {
+                bitcoin_amount,
+                bitcoin_absolute_expiry,
+                bitcoin_network,
+                ..
+            })
+            | CreateSwapParams::HbitBitcoinHerc20Ethereum(HbitBitcoinHerc20CreateSwapParams {
+                bitcoin_amount,
+                bitcoin_absolute_expiry,
+                bitcoin_network,
+                ..
+            }) => (bitcoin_amount, bitcoin_absolute_expiry, bitcoin_network),
+            _ => {
+                tracing::error!("Internal Failure: swap {} does not use hbit, no action has been taken. This should be unreachable.", local_swap_id);
+                return;
+            }
+        };
+
+        tokio::task::spawn(
+            {
+                let db = self.db.clone();
+                let connector = self.bitcoin_connector.clone();
+                let states = self.hbit_states.clone();
+
+                async move {
+                    let start_of_swap =
+                        load_start_of_swap(&db, local_swap_id, bitcoin_ledger, start_of_swap).await;
+
+                    hbit::new_hbit_swap(
+                        local_swap_id,
+                        bitcoin_ledger,
+                        connector,
+                        db,
+                        states,
+                        hbit::Params {
+                            network,
+                            asset,
+                            redeem_identity,
+                            refund_identity,
+                            expiry,
+                            start_of_swap,
+                            secret_hash,
+                        },
+                    )
+                    .await
+                }
+            }
+            .instrument(tracing::error_span!(
+                "bitcoin_ledger",
+                swap_id = %local_swap_id,
+                role = %role
+            )),
+        );
+
+        self.spawn_ethereum_swap_watcher(
+            local_swap_id,
+            create_swap_params,
+            secret_hash,
+            finalized.counterparty_ethereum_identity,
+            start_of_swap,
+        );
+    }
+
+    fn spawn_ethereum_swap_watcher(
+        &self,
+        local_swap_id: LocalSwapId,
+        create_swap_params: CreateSwapParams,
+        secret_hash: SecretHash,
+        counterparty_ethereum_identity: identity::Ethereum,
+        start_of_swap: NaiveDateTime,
+    ) {
+        let role = create_swap_params.role();
+        let (redeem_identity, refund_identity) = if create_swap_params.funds_ethereum() {
+            (
+                counterparty_ethereum_identity,
+                create_swap_params.ethereum_identity(),
+            )
+        } else {
+            (
+                create_swap_params.ethereum_identity(),
+                counterparty_ethereum_identity,
+            )
+        };
+        let expiry = create_swap_params.ethereum_absolute_expiry();
+        let ethereum_ledger = create_swap_params.ethereum_ledger();
+        let db = self.db.clone();
+
+        match create_swap_params {
+            CreateSwapParams::HanEthereumHalightBitcoin(
+                HanEtherereumHalightBitcoinCreateSwapParams {
+                    ethereum_amount, ..
+                },
+            )
+            | CreateSwapParams::HalightBitcoinHanEthereum(
+                HalightBitcoinHanEthereumCreateSwapParams {
+                    ethereum_amount, ..
+                },
+            )
+            | CreateSwapParams::HanEthereumHbitBitcoin(HanEthereumHbitBitcoinCreateSwapParams {
+                ethereum_amount,
+                ..
+            })
+            | CreateSwapParams::HbitBitcoinHanEthereum(HbitBitcoinHanEthereumCreateSwapParams {
+                ethereum_amount,
+                ..
+            }) => {
+                let connector = self.ethereum_connector.clone();
+                let states = self.alpha_ledger_states.clone();
+
+                tokio::task::spawn(
+                    async move {
+                        let start_of_swap =
+                            load_start_of_swap(&db, local_swap_id, ethereum_ledger, start_of_swap)
+                                .await;
+
+                        han::new_han_ethereum_ether_swap(
+                            local_swap_id,
+                            ethereum_ledger,
+                            connector,
+                            db,
+                            states,
+                            HtlcParams {
+                                asset: ethereum_amount,
+                                ledger: ledger::Ethereum::default(),
+                                redeem_identity,
+                                refund_identity,
+                                expiry,
+                                secret_hash,
+                            },
+                            start_of_swap,
+                        )
+                        .await
+                    }
+                    .instrument(tracing::error_span!(
+                        "ethereum_ledger",
+                        swap_id = %local_swap_id,
+                        role = %role
+                    )),
+                );
+            }
+            CreateSwapParams::Herc20EthereumHalightBitcoin(
+                Herc20HalightBitcoinCreateSwapParams {
+                    ethereum_amount,
+                    token_contract,
+                    ..
+                },
+            )
+            | CreateSwapParams::HalightBitcoinHerc20Ethereum(
+                HalightBitcoinHerc20CreateSwapParams {
+                    ethereum_amount,
+                    token_contract,
+                    ..
+                },
+            )
+            | CreateSwapParams::Herc20EthereumHbitBitcoin(Herc20HbitBitcoinCreateSwapParams {
+                ethereum_amount,
+                token_contract,
+                ..
+            })
+            | CreateSwapParams::HbitBitcoinHerc20Ethereum(HbitBitcoinHerc20CreateSwapParams {
+                ethereum_amount,
+                token_contract,
+                ..
+            }) => {
+                let connector = self.ethereum_connector.clone();
+                let states = self.herc20_states.clone();
+
+                tokio::task::spawn(
+                    async move {
+                        let start_of_swap =
+                            load_start_of_swap(&db, local_swap_id, ethereum_ledger, start_of_swap)
+                                .await;
+
+                        herc20::new_herc20_swap(
+                            local_swap_id,
+                            ethereum_ledger,
+                            connector,
+                            db,
+                            states,
+                            herc20::Params {
+                                asset: asset::Erc20::new(token_contract, ethereum_amount),
+                                redeem_identity,
+                                refund_identity,
+                                expiry,
+                                start_of_swap,
+                                secret_hash,
+                            },
+                        )
+                        .await
+                    }
+                    .instrument(tracing::error_span!(
+                        "ethereum_ledger",
+                        swap_id = %local_swap_id,
+                        role = %role
+                    )),
+                );
+            }
+        }
+    }
+
     fn supports_halight(&self) -> anyhow::Result<()> {
         match self.lnd_connector_params {
             Some(_) => Ok(()),
@@ -1060,7 +1493,7 @@ impl SendRequest for Swarm {
         }
         .await;
 
-        match result {
+        let response = match result {
             Ok(mut response) => {
                 let decision = response
                     .take_header("decision")
@@ -1114,7 +1547,17 @@ impl SendRequest for Swarm {
                 );
                 Err(RequestError::Connection)
             }
-        }
+        };
+
+        let status = match &response {
+            Ok(Ok(_)) => "accepted",
+            Ok(Err(_)) => "declined",
+            Err(RequestError::InvalidResponse) => "invalid_response",
+            Err(_) => "failed",
+        };
+        metrics::record_comit_request(Direction::Outbound, status);
+
+        response
     }
 }
 
@@ -1145,9 +1588,12 @@ impl libp2p::swarm::NetworkBehaviourEventProcess<BehaviourOutEvent> for ComitNod
                             let mut response_channels = response_channels.lock().await;
                             response_channels.insert(id, channel);
                         }
-                        Err(response) => channel.send(response).unwrap_or_else(|_| {
-                            tracing::debug!("failed to send response through channel")
-                        }),
+                        Err(response) => {
+                            metrics::record_comit_request(Direction::Inbound, "declined");
+                            channel.send(response).unwrap_or_else(|_| {
+                                tracing::debug!("failed to send response through channel")
+                            })
+                        }
                     }
                 });
             }
@@ -1159,6 +1605,32 @@ impl libp2p::swarm::NetworkBehaviourEventProcess<libp2p::mdns::MdnsEvent> for Co
     fn inject_event(&mut self, _event: libp2p::mdns::MdnsEvent) {}
 }
 
+/// Where a split-protocol swap starts on the given ledger.
+///
+/// The height recorded when the swap was created is preferred, the given point
+/// in time is only used for blocks that don't tell their height.
+async fn load_start_of_swap(
+    db: &Sqlite,
+    swap_id: LocalSwapId,
+    ledger: Ledger,
+    timestamp: NaiveDateTime,
+) -> StartOfSwap {
+    let height = match db.load_start_height(&swap_id, ledger).await {
+        Ok(height) => height,
+        Err(e) => {
+            tracing::warn!(
+                "failed to load start height on {} ledger of swap {}: {:?}",
+                ledger,
+                swap_id,
+                e
+            );
+            None
+        }
+    };
+
+    StartOfSwap { timestamp, height }
+}
+
 impl libp2p::swarm::NetworkBehaviourEventProcess<()> for ComitNode {
     fn inject_event(&mut self, _event: ()) {}
 }
@@ -1169,87 +1641,69 @@ impl libp2p::swarm::NetworkBehaviourEventProcess<comit_ln::BehaviourOutEvent> fo
             comit_ln::BehaviourOutEvent::SwapFinalized {
                 local_swap_id,
                 swap_params: create_swap_params,
+                shared_swap_id,
                 secret_hash,
                 ethereum_identity,
+                lightning_identity,
             } => {
-                let role = create_swap_params.role;
-
-                match self.lnd_connector_params {
-                    None => { tracing::error!("Internal Failure: lnd connectors are not initialised, no action has been taken. This should be unreachable.") }
-                    Some(ref lnd_connector_params) => {
-                        match role {
-                            Role::Alice => {
-                                tokio::task::spawn({
-                                    let lnd_connector: LndConnectorAsReceiver = (**lnd_connector_params).clone().into();
-                                    halight::new_halight_swap(local_swap_id, secret_hash, self.halight_states.clone(), lnd_connector)
-                                        .instrument(
-                                            tracing::error_span!("beta_ledger", swap_id = %local_swap_id, role = %role),
-                                        )
-                                });
-
-                                tokio::task::spawn({
-                                    let connector = self.ethereum_connector.clone();
-                                    let alice_ethereum_identity = create_swap_params.ethereum_identity;
-                                    let bob_ethereum_identity = ethereum_identity;
-
-                                    let asset = create_swap_params.ethereum_amount.clone();
-                                    let ledger = ledger::Ethereum::default();
-                                    let expiry = create_swap_params.ethereum_absolute_expiry;
-
-                                    han::new_han_ethereum_ether_swap(
-                                        local_swap_id,
-                                        connector,
-                                        self.alpha_ledger_states.clone(),
-                                        HtlcParams {
-                                            asset,
-                                            ledger,
-                                            redeem_identity: bob_ethereum_identity,
-                                            refund_identity: alice_ethereum_identity.into(),
-                                            expiry,
-                                            secret_hash,
-                                        },
-                                        role,
-                                    )
-                                });
-                            }
-
-                            Role::Bob => {
-                                tokio::task::spawn({
-                                    let lnd_connector: LndConnectorAsSender = (**lnd_connector_params).clone().into();
-                                    self::halight::new_halight_swap(local_swap_id, secret_hash, self.halight_states.clone(), lnd_connector)
-                                        .instrument(
-                                            tracing::error_span!("beta_ledger", swap_id = %local_swap_id, role = %role),
-                                        )
-                                });
-
-                                tokio::task::spawn({
-                                    let connector = self.ethereum_connector.clone();
-                                    let alice_ethereum_identity = ethereum_identity;
-                                    let bob_ethereum_identity = create_swap_params.ethereum_identity;
-
-                                    let asset = create_swap_params.ethereum_amount.clone();
-                                    let ledger = ledger::Ethereum::default();
-                                    let expiry = create_swap_params.ethereum_absolute_expiry;
-
-                                    self::han::new_han_ethereum_ether_swap(
-                                        local_swap_id,
-                                        connector,
-                                        self.alpha_ledger_states.clone(),
-                                        HtlcParams {
-                                            asset,
-                                            ledger,
-                                            redeem_identity: bob_ethereum_identity.into(),
-                                            refund_identity: alice_ethereum_identity,
-                                            expiry,
-                                            secret_hash,
-                                        },
-                                        role,
-                                    )
-                                });
-                            }
+                let finalized = FinalizedSwapData {
+                    swap_id: local_swap_id,
+                    shared_swap_id,
+                    secret_hash,
+                    counterparty_ethereum_identity: ethereum_identity,
+                    counterparty_lightning_identity: lightning_identity,
+                    start_of_swap: Utc::now().naive_local(),
+                };
+
+                tokio::task::spawn({
+                    let db = self.db.clone();
+                    let finalized = finalized.clone();
+
+                    async move {
+                        if let Err(e) = db.save(finalized).await {
+                            tracing::error!("failed to save finalized swap: {:?}", e);
                         }
                     }
-                }
+                });
+
+                self.spawn_swap_watchers(local_swap_id, create_swap_params, finalized);
+            }
+            comit_ln::BehaviourOutEvent::HbitSwapFinalized {
+                local_swap_id,
+                swap_params: create_swap_params,
+                shared_swap_id,
+                secret_hash,
+                ethereum_identity,
+                bitcoin_identity,
+            } => {
+                let start_of_swap = match create_swap_params.start_of_swap() {
+                    Some(start_of_swap) => start_of_swap,
+                    None => {
+                        tracing::error!("Internal Failure: swap {} does not use hbit, no action has been taken. This should be unreachable.", local_swap_id);
+                        return;
+                    }
+                };
+                let finalized = FinalizedHbitSwapData {
+                    swap_id: local_swap_id,
+                    shared_swap_id,
+                    secret_hash,
+                    counterparty_ethereum_identity: ethereum_identity,
+                    counterparty_bitcoin_identity: bitcoin_identity,
+                    start_of_swap,
+                };
+
+                tokio::task::spawn({
+                    let db = self.db.clone();
+                    let finalized = finalized.clone();
+
+                    async move {
+                        if let Err(e) = db.save(finalized).await {
+                            tracing::error!("failed to save finalized swap: {:?}", e);
+                        }
+                    }
+                });
+
+                self.spawn_hbit_swap_watchers(local_swap_id, create_swap_params, finalized);
             }
         }
     }
@@ -1,29 +1,32 @@
 use crate::{
-    asset, identity,
+    asset,
+    db::{FinalizedHbitSwapData, FinalizedSwapData},
+    identity,
     network::{
         oneshot_behaviour,
         protocols::{
             announce,
             announce::{behaviour::Announce, SwapDigest},
-            ethereum_identity, finalize, lightning_identity, secret_hash,
+            bitcoin_identity, ethereum_identity, finalize, lightning_identity, secret_hash,
         },
     },
     seed::{DeriveSwapSeed, RootSeed},
     swap_protocols::{
+        hbit,
         ledger::{ethereum::ChainId, lightning, Ethereum},
-        rfc003::{create_swap::HtlcParams, DeriveSecret, Secret, SecretHash},
-        HanEtherereumHalightBitcoinCreateSwapParams, LocalSwapId, Role, SharedSwapId,
+        rfc003::{create_swap::HtlcParams, DeriveIdentities, DeriveSecret, Secret, SecretHash},
+        AlphaLedger, CreateSwapParams, LocalSwapId, Role, SharedSwapId,
     },
     timestamp::Timestamp,
 };
-use blockchain_contracts::ethereum::rfc003::ether_htlc::EtherHtlc;
-use digest::Digest;
+use bitcoin::secp256k1::SecretKey;
+use blockchain_contracts::bitcoin::rfc003::bitcoin_htlc::BitcoinHtlc;
 use futures::AsyncWriteExt;
 use libp2p::{
     swarm::{
         NetworkBehaviour, NetworkBehaviourAction, NetworkBehaviourEventProcess, PollParameters,
     },
-    NetworkBehaviour,
+    NetworkBehaviour, PeerId,
 };
 use std::{
     collections::{HashMap, VecDeque},
@@ -36,9 +39,19 @@ use std::{
 pub enum BehaviourOutEvent {
     SwapFinalized {
         local_swap_id: LocalSwapId,
-        swap_params: HanEtherereumHalightBitcoinCreateSwapParams,
+        swap_params: CreateSwapParams,
+        shared_swap_id: SharedSwapId,
         secret_hash: SecretHash,
         ethereum_identity: identity::Ethereum,
+        lightning_identity: identity::Lightning,
+    },
+    HbitSwapFinalized {
+        local_swap_id: LocalSwapId,
+        swap_params: CreateSwapParams,
+        shared_swap_id: SharedSwapId,
+        secret_hash: SecretHash,
+        ethereum_identity: identity::Ethereum,
+        bitcoin_identity: identity::Bitcoin,
     },
 }
 
@@ -49,6 +62,7 @@ pub struct ComitLN {
     secret_hash: oneshot_behaviour::Behaviour<secret_hash::Message>,
     ethereum_identity: oneshot_behaviour::Behaviour<ethereum_identity::Message>,
     lightning_identity: oneshot_behaviour::Behaviour<lightning_identity::Message>,
+    bitcoin_identity: oneshot_behaviour::Behaviour<bitcoin_identity::Message>,
     finalize: oneshot_behaviour::Behaviour<finalize::Message>,
 
     #[behaviour(ignore)]
@@ -57,7 +71,7 @@ pub struct ComitLN {
     #[behaviour(ignore)]
     swaps_waiting_for_announcement: HashMap<SwapDigest, LocalSwapId>,
     #[behaviour(ignore)]
-    swaps: HashMap<LocalSwapId, HanEtherereumHalightBitcoinCreateSwapParams>,
+    swaps: HashMap<LocalSwapId, CreateSwapParams>,
     #[behaviour(ignore)]
     swap_ids: HashMap<LocalSwapId, SharedSwapId>,
     #[behaviour(ignore)]
@@ -65,6 +79,8 @@ pub struct ComitLN {
     #[behaviour(ignore)]
     lightning_identities: HashMap<SharedSwapId, identity::Lightning>,
     #[behaviour(ignore)]
+    bitcoin_identities: HashMap<SharedSwapId, identity::Bitcoin>,
+    #[behaviour(ignore)]
     communication_state: HashMap<SharedSwapId, CommunicationState>,
     #[behaviour(ignore)]
     secret_hashes: HashMap<SharedSwapId, SecretHash>,
@@ -77,6 +93,7 @@ pub struct ComitLN {
 struct CommunicationState {
     ethereum_identity_sent: bool,
     lightning_identity_sent: bool,
+    bitcoin_identity_sent: bool,
     received_finalized: bool,
     sent_finalized: bool,
     secret_hash_sent_or_received: bool,
@@ -89,6 +106,7 @@ impl ComitLN {
             secret_hash: Default::default(),
             ethereum_identity: Default::default(),
             lightning_identity: Default::default(),
+            bitcoin_identity: Default::default(),
             finalize: Default::default(),
             events: VecDeque::new(),
             swaps_waiting_for_announcement: Default::default(),
@@ -96,6 +114,7 @@ impl ComitLN {
             swap_ids: Default::default(),
             ethereum_identities: Default::default(),
             lightning_identities: Default::default(),
+            bitcoin_identities: Default::default(),
             communication_state: Default::default(),
             secret_hashes: Default::default(),
             seed,
@@ -105,9 +124,9 @@ impl ComitLN {
     pub fn initiate_communication(
         &mut self,
         id: LocalSwapId,
-        create_swap_params: HanEtherereumHalightBitcoinCreateSwapParams,
+        create_swap_params: CreateSwapParams,
     ) -> anyhow::Result<()> {
-        let digest = create_swap_params.clone().digest();
+        let digest = create_swap_params.digest();
 
         if self.swaps_waiting_for_announcement.contains_key(&digest) {
             anyhow::bail!(SwapExists)
@@ -116,10 +135,10 @@ impl ComitLN {
         self.swaps_waiting_for_announcement
             .insert(digest.clone(), id);
 
-        match create_swap_params.role {
+        match create_swap_params.role() {
             Role::Alice => {
                 self.announce
-                    .start_announce_protocol(digest, create_swap_params.peer);
+                    .start_announce_protocol(digest, create_swap_params.peer());
             }
             Role::Bob => {
                 tracing::info!("Swap waiting for announcement: {}", digest);
@@ -129,13 +148,124 @@ impl ComitLN {
         Ok(())
     }
 
-    pub fn get_finalized_swap(&self, swap_id: LocalSwapId) -> Option<FinalizedSwap> {
+    /// Restores the state of a swap that was finalized before cnd was
+    /// restarted, this makes the swap available via `get_finalized_swap`.
+    pub fn restore_finalized_swap(
+        &mut self,
+        local_swap_id: LocalSwapId,
+        create_swap_params: CreateSwapParams,
+        finalized: FinalizedSwapData,
+    ) {
+        let shared_swap_id = finalized.shared_swap_id;
+
+        self.swaps.insert(local_swap_id, create_swap_params);
+        self.swap_ids.insert(local_swap_id, shared_swap_id);
+        self.ethereum_identities
+            .insert(shared_swap_id, finalized.counterparty_ethereum_identity);
+        self.lightning_identities
+            .insert(shared_swap_id, finalized.counterparty_lightning_identity);
+        self.secret_hashes
+            .insert(shared_swap_id, finalized.secret_hash);
+    }
+
+    /// Same as `restore_finalized_swap` for swaps that use hbit.
+    pub fn restore_finalized_hbit_swap(
+        &mut self,
+        local_swap_id: LocalSwapId,
+        create_swap_params: CreateSwapParams,
+        finalized: FinalizedHbitSwapData,
+    ) {
+        let shared_swap_id = finalized.shared_swap_id;
+
+        self.swaps.insert(local_swap_id, create_swap_params);
+        self.swap_ids.insert(local_swap_id, shared_swap_id);
+        self.ethereum_identities
+            .insert(shared_swap_id, finalized.counterparty_ethereum_identity);
+        self.bitcoin_identities
+            .insert(shared_swap_id, finalized.counterparty_bitcoin_identity);
+        self.secret_hashes
+            .insert(shared_swap_id, finalized.secret_hash);
+    }
+
+    pub fn get_finalized_swap(&self, swap_id: LocalSwapId) -> Option<FinalizedSwap<asset::Ether>> {
+        self.finalized_swap(swap_id, |create_swap_params| match create_swap_params {
+            CreateSwapParams::HanEthereumHalightBitcoin(params) => {
+                Some(params.ethereum_amount.clone())
+            }
+            CreateSwapParams::HalightBitcoinHanEthereum(params) => {
+                Some(params.ethereum_amount.clone())
+            }
+            _ => None,
+        })
+    }
+
+    pub fn get_finalized_herc20_swap(
+        &self,
+        swap_id: LocalSwapId,
+    ) -> Option<FinalizedSwap<asset::Erc20>> {
+        self.finalized_swap(swap_id, |create_swap_params| match create_swap_params {
+            CreateSwapParams::Herc20EthereumHalightBitcoin(params) => Some(asset::Erc20::new(
+                params.token_contract,
+                params.ethereum_amount.clone(),
+            )),
+            CreateSwapParams::HalightBitcoinHerc20Ethereum(params) => Some(asset::Erc20::new(
+                params.token_contract,
+                params.ethereum_amount.clone(),
+            )),
+            _ => None,
+        })
+    }
+
+    pub fn get_finalized_han_hbit_swap(
+        &self,
+        swap_id: LocalSwapId,
+    ) -> Option<FinalizedHbitSwap<asset::Ether>> {
+        self.finalized_hbit_swap(swap_id, |create_swap_params| match create_swap_params {
+            CreateSwapParams::HanEthereumHbitBitcoin(params) => {
+                Some(params.ethereum_amount.clone())
+            }
+            CreateSwapParams::HbitBitcoinHanEthereum(params) => {
+                Some(params.ethereum_amount.clone())
+            }
+            _ => None,
+        })
+    }
+
+    pub fn get_finalized_herc20_hbit_swap(
+        &self,
+        swap_id: LocalSwapId,
+    ) -> Option<FinalizedHbitSwap<asset::Erc20>> {
+        self.finalized_hbit_swap(swap_id, |create_swap_params| match create_swap_params {
+            CreateSwapParams::Herc20EthereumHbitBitcoin(params) => Some(asset::Erc20::new(
+                params.token_contract,
+                params.ethereum_amount.clone(),
+            )),
+            CreateSwapParams::HbitBitcoinHerc20Ethereum(params) => Some(asset::Erc20::new(
+                params.token_contract,
+                params.ethereum_amount.clone(),
+            )),
+            _ => None,
+        })
+    }
+
+    fn finalized_swap<A>(
+        &self,
+        swap_id: LocalSwapId,
+        ethereum_asset: impl FnOnce(&CreateSwapParams) -> Option<A>,
+    ) -> Option<FinalizedSwap<A>> {
         let create_swap_params = match self.swaps.get(&swap_id) {
             Some(body) => body,
             None => return None,
         };
 
-        let secret = match create_swap_params.role {
+        let ethereum_asset = match ethereum_asset(create_swap_params) {
+            Some(ethereum_asset) => ethereum_asset,
+            None => return None,
+        };
+
+        let role = create_swap_params.role();
+
+        let secret = match role {
             Role::Alice => Some(self.seed.derive_swap_seed(swap_id).derive_secret()),
             Role::Bob => None,
         };
@@ -145,56 +275,203 @@ impl ComitLN {
             None => return None,
         };
 
-        let alpha_ledger_redeem_identity = match create_swap_params.role {
-            Role::Alice => match self.ethereum_identities.get(&id).copied() {
-                Some(identity) => identity,
-                None => return None,
-            },
-            Role::Bob => create_swap_params.ethereum_identity.into(),
-        };
-        let alpha_ledger_refund_identity = match create_swap_params.role {
-            Role::Alice => create_swap_params.ethereum_identity.into(),
-            Role::Bob => match self.ethereum_identities.get(&id).copied() {
-                Some(identity) => identity,
-                None => return None,
-            },
+        let counterparty_ethereum_identity = match self.ethereum_identities.get(&id).copied() {
+            Some(identity) => identity,
+            None => return None,
         };
-        let beta_ledger_redeem_identity = match create_swap_params.role {
-            Role::Alice => create_swap_params.lightning_identity,
-            Role::Bob => match self.lightning_identities.get(&id).copied() {
-                Some(identity) => identity,
-                None => return None,
-            },
+        let counterparty_lightning_identity = match self.lightning_identities.get(&id).copied() {
+            Some(identity) => identity,
+            None => return None,
         };
-        let beta_ledger_refund_identity = match create_swap_params.role {
-            Role::Alice => match self.lightning_identities.get(&id).copied() {
-                Some(identity) => identity,
-                None => return None,
-            },
-            Role::Bob => create_swap_params.lightning_identity,
+
+        // The party funding the Ethereum HTLC refunds on Ethereum and redeems on
+        // Lightning, the counterparty does it the other way around.
+        let (
+            ethereum_redeem_identity,
+            ethereum_refund_identity,
+            lightning_redeem_identity,
+            lightning_refund_identity,
+        ) = if create_swap_params.funds_ethereum() {
+            (
+                counterparty_ethereum_identity,
+                create_swap_params.ethereum_identity(),
+                create_swap_params.lightning_identity()?,
+                counterparty_lightning_identity,
+            )
+        } else {
+            (
+                create_swap_params.ethereum_identity(),
+                counterparty_ethereum_identity,
+                counterparty_lightning_identity,
+                create_swap_params.lightning_identity()?,
+            )
         };
 
         Some(FinalizedSwap {
-            alpha_ledger: Ethereum::new(ChainId::regtest()),
-            beta_ledger: lightning::Regtest,
-            alpha_asset: create_swap_params.ethereum_amount.clone(),
-            beta_asset: create_swap_params.lightning_amount,
-            alpha_ledger_redeem_identity,
-            alpha_ledger_refund_identity,
-            beta_ledger_redeem_identity,
-            beta_ledger_refund_identity,
-            alpha_expiry: create_swap_params.ethereum_absolute_expiry,
-            beta_expiry: create_swap_params.lightning_cltv_expiry,
+            alpha_ledger: create_swap_params.alpha_ledger(),
+            ethereum_ledger: Ethereum::new(ChainId::regtest()),
+            lightning_ledger: lightning::Regtest,
+            ethereum_asset,
+            lightning_asset: create_swap_params.lightning_amount()?,
+            ethereum_redeem_identity,
+            ethereum_refund_identity,
+            lightning_redeem_identity,
+            lightning_refund_identity,
+            ethereum_expiry: create_swap_params.ethereum_absolute_expiry(),
+            lightning_expiry: create_swap_params.lightning_cltv_expiry()?,
             swap_id,
             secret,
             secret_hash: match self.secret_hashes.get(&id).copied() {
                 Some(secret_hash) => secret_hash,
                 None => return None,
             },
-            role: create_swap_params.role,
+            role,
+        })
+    }
+
+    /// Same as `finalized_swap` for swaps that use hbit.
+    fn finalized_hbit_swap<A>(
+        &self,
+        swap_id: LocalSwapId,
+        ethereum_asset: impl FnOnce(&CreateSwapParams) -> Option<A>,
+    ) -> Option<FinalizedHbitSwap<A>> {
+        let create_swap_params = self.swaps.get(&swap_id)?;
+        let ethereum_asset = ethereum_asset(create_swap_params)?;
+
+        let role = create_swap_params.role();
+
+        let secret = match role {
+            Role::Alice => Some(self.seed.derive_swap_seed(swap_id).derive_secret()),
+            Role::Bob => None,
+        };
+
+        let id = self.swap_ids.get(&swap_id).copied()?;
+        let counterparty_ethereum_identity = self.ethereum_identities.get(&id).copied()?;
+        let counterparty_bitcoin_identity = self.bitcoin_identities.get(&id).copied()?;
+
+        // The party funding the Ethereum HTLC refunds on Ethereum and redeems on
+        // Bitcoin, the counterparty does it the other way around.
+        let funds_ethereum = create_swap_params.funds_ethereum();
+        let bitcoin_transient_key =
+            derive_bitcoin_transient_key(&self.seed, swap_id, funds_ethereum);
+        let bitcoin_identity =
+            identity::Bitcoin::from_secret_key(&*crate::SECP, &bitcoin_transient_key);
+
+        let (
+            ethereum_redeem_identity,
+            ethereum_refund_identity,
+            bitcoin_redeem_identity,
+            bitcoin_refund_identity,
+        ) = if funds_ethereum {
+            (
+                counterparty_ethereum_identity,
+                create_swap_params.ethereum_identity(),
+                bitcoin_identity,
+                counterparty_bitcoin_identity,
+            )
+        } else {
+            (
+                create_swap_params.ethereum_identity(),
+                counterparty_ethereum_identity,
+                counterparty_bitcoin_identity,
+                bitcoin_identity,
+            )
+        };
+
+        Some(FinalizedHbitSwap {
+            alpha_ledger: create_swap_params.alpha_ledger(),
+            ethereum_ledger: Ethereum::new(ChainId::regtest()),
+            bitcoin_network: create_swap_params.bitcoin_network()?,
+            ethereum_asset,
+            bitcoin_asset: create_swap_params.bitcoin_amount()?,
+            ethereum_redeem_identity,
+            ethereum_refund_identity,
+            bitcoin_redeem_identity,
+            bitcoin_refund_identity,
+            bitcoin_transient_key,
+            ethereum_expiry: create_swap_params.ethereum_absolute_expiry(),
+            bitcoin_expiry: create_swap_params.bitcoin_absolute_expiry()?,
+            swap_id,
+            secret,
+            secret_hash: self.secret_hashes.get(&id).copied()?,
+            role,
         })
     }
 
+    fn local_swap_id(&self, shared_swap_id: SharedSwapId) -> Option<LocalSwapId> {
+        self.swap_ids
+            .iter()
+            .find_map(|(local_swap_id, id)| {
+                if *id == shared_swap_id {
+                    Some(local_swap_id)
+                } else {
+                    None
+                }
+            })
+            .copied()
+    }
+
+    /// Sends our identities for the ledgers used by the swap to the peer.
+    fn send_identities(
+        &mut self,
+        peer: PeerId,
+        local_swap_id: LocalSwapId,
+        shared_swap_id: SharedSwapId,
+    ) {
+        let create_swap_params = self.swaps.get(&local_swap_id).unwrap();
+
+        self.ethereum_identity.send(
+            peer.clone(),
+            ethereum_identity::Message::new(shared_swap_id, create_swap_params.ethereum_identity()),
+        );
+
+        match create_swap_params.lightning_identity() {
+            Some(lightning_identity) => self.lightning_identity.send(
+                peer,
+                lightning_identity::Message::new(shared_swap_id, lightning_identity),
+            ),
+            None => {
+                let bitcoin_identity = derive_bitcoin_identity(
+                    &self.seed,
+                    local_swap_id,
+                    create_swap_params.funds_ethereum(),
+                );
+
+                self.bitcoin_identity.send(
+                    peer,
+                    bitcoin_identity::Message::new(shared_swap_id, bitcoin_identity),
+                )
+            }
+        }
+    }
+
+    /// Returns true once the identities for the ledgers used by the swap and
+    /// the secret hash have been exchanged with the peer.
+    fn is_ready_to_finalize(&self, swap_id: SharedSwapId) -> bool {
+        let state = match self.communication_state.get(&swap_id) {
+            Some(state) => state,
+            None => return false,
+        };
+        let uses_halight = match self
+            .local_swap_id(swap_id)
+            .and_then(|local_swap_id| self.swaps.get(&local_swap_id))
+        {
+            Some(create_swap_params) => create_swap_params.uses_halight(),
+            None => return false,
+        };
+
+        let bitcoin_identities_exchanged = if uses_halight {
+            self.lightning_identities.contains_key(&swap_id) && state.lightning_identity_sent
+        } else {
+            self.bitcoin_identities.contains_key(&swap_id) && state.bitcoin_identity_sent
+        };
+
+        self.ethereum_identities.contains_key(&swap_id)
+            && state.ethereum_identity_sent
+            && bitcoin_identities_exchanged
+            && state.secret_hash_sent_or_received
+    }
+
     fn poll<BIE>(
         &mut self,
         _cx: &mut Context<'_>,
@@ -220,35 +497,121 @@ impl fmt::Display for SwapExists {
     }
 }
 
+/// Derives the identity we use in the Bitcoin HTLC of an hbit swap.
+///
+/// The party funding the Ethereum HTLC redeems the Bitcoin HTLC, the
+/// counterparty refunds it. The identities are transient so that cnd can sign
+/// the spending transactions itself.
+pub fn derive_bitcoin_identity(
+    seed: &RootSeed,
+    swap_id: LocalSwapId,
+    funds_ethereum: bool,
+) -> identity::Bitcoin {
+    let secret_key = derive_bitcoin_transient_key(seed, swap_id, funds_ethereum);
+
+    identity::Bitcoin::from_secret_key(&*crate::SECP, &secret_key)
+}
+
+/// The secret key of the identity returned by `derive_bitcoin_identity`, used
+/// to sign the transaction spending the Bitcoin HTLC.
+fn derive_bitcoin_transient_key(
+    seed: &RootSeed,
+    swap_id: LocalSwapId,
+    funds_ethereum: bool,
+) -> SecretKey {
+    let swap_seed = seed.derive_swap_seed(swap_id);
+
+    if funds_ethereum {
+        swap_seed.derive_redeem_identity()
+    } else {
+        swap_seed.derive_refund_identity()
+    }
+}
+
+/// A swap negotiated through `ComitLN`, Ethereum is used on one ledger and
+/// Lightning on the other, `alpha_ledger` records which one is alpha.
 #[derive(Debug)]
-pub struct FinalizedSwap {
-    pub alpha_ledger: Ethereum,
-    pub beta_ledger: lightning::Regtest,
-    pub alpha_asset: asset::Ether,
-    pub beta_asset: asset::Bitcoin,
-    pub alpha_ledger_refund_identity: identity::Ethereum,
-    pub alpha_ledger_redeem_identity: identity::Ethereum,
-    pub beta_ledger_refund_identity: identity::Lightning,
-    pub beta_ledger_redeem_identity: identity::Lightning,
-    pub alpha_expiry: Timestamp,
-    pub beta_expiry: Timestamp,
+pub struct FinalizedSwap<A> {
+    pub alpha_ledger: AlphaLedger,
+    pub ethereum_ledger: Ethereum,
+    pub lightning_ledger: lightning::Regtest,
+    pub ethereum_asset: A,
+    pub lightning_asset: asset::Bitcoin,
+    pub ethereum_refund_identity: identity::Ethereum,
+    pub ethereum_redeem_identity: identity::Ethereum,
+    pub lightning_refund_identity: identity::Lightning,
+    pub lightning_redeem_identity: identity::Lightning,
+    pub ethereum_expiry: Timestamp,
+    pub lightning_expiry: Timestamp,
     pub swap_id: LocalSwapId,
     pub secret_hash: SecretHash,
     pub secret: Option<Secret>,
     pub role: Role,
 }
 
-impl FinalizedSwap {
-    pub fn han_params(&self) -> EtherHtlc {
+impl<A> FinalizedSwap<A>
+where
+    A: Clone,
+{
+    pub fn htlc_params(&self) -> HtlcParams<Ethereum, A, identity::Ethereum> {
         HtlcParams {
-            asset: self.alpha_asset.clone(),
+            asset: self.ethereum_asset.clone(),
             ledger: Ethereum::new(ChainId::regtest()),
-            redeem_identity: self.alpha_ledger_redeem_identity,
-            refund_identity: self.alpha_ledger_refund_identity,
-            expiry: self.alpha_expiry,
+            redeem_identity: self.ethereum_redeem_identity,
+            refund_identity: self.ethereum_refund_identity,
+            expiry: self.ethereum_expiry,
             secret_hash: self.secret_hash,
         }
-        .into()
+    }
+}
+
+/// A swap negotiated through `ComitLN` that uses hbit, Ethereum is used on one
+/// ledger and the Bitcoin blockchain on the other.
+#[derive(Debug)]
+pub struct FinalizedHbitSwap<A> {
+    pub alpha_ledger: AlphaLedger,
+    pub ethereum_ledger: Ethereum,
+    pub bitcoin_network: bitcoin::Network,
+    pub ethereum_asset: A,
+    pub bitcoin_asset: asset::Bitcoin,
+    pub ethereum_refund_identity: identity::Ethereum,
+    pub ethereum_redeem_identity: identity::Ethereum,
+    pub bitcoin_refund_identity: identity::Bitcoin,
+    pub bitcoin_redeem_identity: identity::Bitcoin,
+    /// The secret key of our identity in the Bitcoin HTLC.
+    pub bitcoin_transient_key: SecretKey,
+    pub ethereum_expiry: Timestamp,
+    pub bitcoin_expiry: Timestamp,
+    pub swap_id: LocalSwapId,
+    pub secret_hash: SecretHash,
+    pub secret: Option<Secret>,
+    pub role: Role,
+}
+
+impl<A> FinalizedHbitSwap<A>
+where
+    A: Clone,
+{
+    pub fn htlc_params(&self) -> HtlcParams<Ethereum, A, identity::Ethereum> {
+        HtlcParams {
+            asset: self.ethereum_asset.clone(),
+            ledger: Ethereum::new(ChainId::regtest()),
+            redeem_identity: self.ethereum_redeem_identity,
+            refund_identity: self.ethereum_refund_identity,
+            expiry: self.ethereum_expiry,
+            secret_hash: self.secret_hash,
+        }
+    }
+}
+
+impl<A> FinalizedHbitSwap<A> {
+    pub fn bitcoin_htlc(&self) -> BitcoinHtlc {
+        hbit::htlc(
+            self.bitcoin_expiry,
+            self.bitcoin_redeem_identity,
+            self.bitcoin_refund_identity,
+            self.secret_hash,
+        )
     }
 }
 
@@ -297,15 +660,7 @@ impl NetworkBehaviourEventProcess<oneshot_behaviour::OutEvent<secret_hash::Messa
             }
         };
 
-        let state = self.communication_state.get(&swap_id).unwrap();
-
-        // check if we are done
-        if self.ethereum_identities.contains_key(&swap_id)
-            && self.lightning_identities.contains_key(&swap_id)
-            && state.lightning_identity_sent
-            && state.ethereum_identity_sent
-            && state.secret_hash_sent_or_received
-        {
+        if self.is_ready_to_finalize(swap_id) {
             self.finalize.send(peer, finalize::Message::new(swap_id));
         }
     }
@@ -325,12 +680,12 @@ impl NetworkBehaviourEventProcess<announce::behaviour::BehaviourOutEvent> for Co
                         // channel.
 
                         let create_swap_params = self.swaps.get(&local_swap_id).unwrap();
-                        if peer != create_swap_params.peer.peer_id {
+                        if peer != create_swap_params.peer().peer_id {
                             tracing::warn!(
                                 "Peer {} announced a swap ({}), but the peer-id {} of the swap awaiting announcement does not match.",
                                 peer,
                                 io.swap_digest,
-                                create_swap_params.peer.peer_id
+                                create_swap_params.peer().peer_id
                             );
                             tokio::task::spawn(async move {
                                 let _ = io.io.close().await;
@@ -357,7 +712,6 @@ impl NetworkBehaviourEventProcess<announce::behaviour::BehaviourOutEvent> for Co
                 if let Some(local_swap_id) =
                     self.swaps_waiting_for_announcement.remove(&io.swap_digest)
                 {
-                    let create_swap_params = self.swaps.get(&local_swap_id).unwrap();
                     let shared_swap_id = SharedSwapId::default();
                     self.swap_ids
                         .insert(local_swap_id.clone(), shared_swap_id.clone());
@@ -371,22 +725,11 @@ impl NetworkBehaviourEventProcess<announce::behaviour::BehaviourOutEvent> for Co
                         .register_addresses(peer.clone(), addresses.clone());
                     self.lightning_identity
                         .register_addresses(peer.clone(), addresses.clone());
+                    self.bitcoin_identity
+                        .register_addresses(peer.clone(), addresses.clone());
                     self.finalize.register_addresses(peer.clone(), addresses);
 
-                    self.ethereum_identity.send(
-                        peer.clone(),
-                        ethereum_identity::Message::new(
-                            shared_swap_id,
-                            create_swap_params.ethereum_identity.into(),
-                        ),
-                    );
-                    self.lightning_identity.send(
-                        peer,
-                        lightning_identity::Message::new(
-                            shared_swap_id,
-                            create_swap_params.lightning_identity,
-                        ),
-                    );
+                    self.send_identities(peer, local_swap_id, shared_swap_id);
 
                     self.communication_state
                         .insert(shared_swap_id, CommunicationState::default());
@@ -411,24 +754,11 @@ impl NetworkBehaviourEventProcess<announce::behaviour::BehaviourOutEvent> for Co
                     .register_addresses(peer.clone(), addresses.clone());
                 self.lightning_identity
                     .register_addresses(peer.clone(), addresses.clone());
+                self.bitcoin_identity
+                    .register_addresses(peer.clone(), addresses.clone());
                 self.finalize.register_addresses(peer.clone(), addresses);
 
-                let create_swap_params = self.swaps.get(&local_swap_id).unwrap();
-
-                self.ethereum_identity.send(
-                    peer.clone(),
-                    ethereum_identity::Message::new(
-                        swap_id,
-                        create_swap_params.ethereum_identity.into(),
-                    ),
-                );
-                self.lightning_identity.send(
-                    peer.clone(),
-                    lightning_identity::Message::new(
-                        swap_id,
-                        create_swap_params.lightning_identity,
-                    ),
-                );
+                self.send_identities(peer.clone(), local_swap_id, swap_id);
 
                 let seed = self.seed.derive_swap_seed(local_swap_id);
                 let secret_hash = seed.derive_secret().hash();
@@ -480,15 +810,7 @@ impl NetworkBehaviourEventProcess<oneshot_behaviour::OutEvent<ethereum_identity:
             }
         };
 
-        let state = self.communication_state.get(&swap_id).unwrap();
-
-        // check if we are done
-        if self.ethereum_identities.contains_key(&swap_id)
-            && self.lightning_identities.contains_key(&swap_id)
-            && state.lightning_identity_sent
-            && state.ethereum_identity_sent
-            && state.secret_hash_sent_or_received
-        {
+        if self.is_ready_to_finalize(swap_id) {
             self.finalize.send(peer, finalize::Message::new(swap_id));
         }
     }
@@ -525,15 +847,42 @@ impl NetworkBehaviourEventProcess<oneshot_behaviour::OutEvent<lightning_identity
             }
         };
 
-        let state = self.communication_state.get(&swap_id).unwrap();
+        if self.is_ready_to_finalize(swap_id) {
+            self.finalize.send(peer, finalize::Message::new(swap_id));
+        }
+    }
+}
+
+impl NetworkBehaviourEventProcess<oneshot_behaviour::OutEvent<bitcoin_identity::Message>>
+    for ComitLN
+{
+    fn inject_event(&mut self, event: oneshot_behaviour::OutEvent<bitcoin_identity::Message>) {
+        let (peer, swap_id) = match event {
+            oneshot_behaviour::OutEvent::Received {
+                peer,
+                message: bitcoin_identity::Message { swap_id, pubkey },
+            } => {
+                self.bitcoin_identities
+                    .insert(swap_id, bitcoin::PublicKey::from_slice(&pubkey).unwrap());
 
-        // check if we are done
-        if self.ethereum_identities.contains_key(&swap_id)
-            && self.lightning_identities.contains_key(&swap_id)
-            && state.lightning_identity_sent
-            && state.ethereum_identity_sent
-            && state.secret_hash_sent_or_received
-        {
+                (peer, swap_id)
+            }
+            oneshot_behaviour::OutEvent::Sent {
+                peer,
+                message: bitcoin_identity::Message { swap_id, .. },
+            } => {
+                let state = self
+                    .communication_state
+                    .get_mut(&swap_id)
+                    .expect("this should exist");
+
+                state.bitcoin_identity_sent = true;
+
+                (peer, swap_id)
+            }
+        };
+
+        if self.is_ready_to_finalize(swap_id) {
             self.finalize.send(peer, finalize::Message::new(swap_id));
         }
     }
@@ -576,20 +925,7 @@ impl NetworkBehaviourEventProcess<oneshot_behaviour::OutEvent<finalize::Message>
             .expect("this should exist");
 
         if state.sent_finalized && state.received_finalized {
-            let local_swap_id = self
-                .swap_ids
-                .iter()
-                .find_map(
-                    |(key, value)| {
-                        if *value == swap_id {
-                            Some(key)
-                        } else {
-                            None
-                        }
-                    },
-                )
-                .copied()
-                .unwrap();
+            let local_swap_id = self.local_swap_id(swap_id).unwrap();
 
             let create_swap_params = self
                 .swaps
@@ -608,12 +944,27 @@ impl NetworkBehaviourEventProcess<oneshot_behaviour::OutEvent<finalize::Message>
             self.swaps_waiting_for_announcement
                 .retain(|_, id| *id != local_swap_id);
 
-            self.events.push_back(BehaviourOutEvent::SwapFinalized {
-                local_swap_id,
-                swap_params: create_swap_params,
-                secret_hash,
-                ethereum_identity,
-            });
+            let event = if create_swap_params.uses_halight() {
+                BehaviourOutEvent::SwapFinalized {
+                    local_swap_id,
+                    swap_params: create_swap_params,
+                    shared_swap_id: swap_id,
+                    secret_hash,
+                    ethereum_identity,
+                    lightning_identity: self.lightning_identities.get(&swap_id).copied().unwrap(),
+                }
+            } else {
+                BehaviourOutEvent::HbitSwapFinalized {
+                    local_swap_id,
+                    swap_params: create_swap_params,
+                    shared_swap_id: swap_id,
+                    secret_hash,
+                    ethereum_identity,
+                    bitcoin_identity: self.bitcoin_identities.get(&swap_id).copied().unwrap(),
+                }
+            };
+
+            self.events.push_back(event);
         }
     }
 }
@@ -625,9 +976,12 @@ mod tests {
         asset::{ethereum::FromWei, Ether},
         lightning,
         network::{test_swarm, DialInformation},
-        swap_protocols::EthereumIdentity,
+        swap_protocols::{
+            EthereumIdentity, HalightBitcoinHanEthereumCreateSwapParams,
+            HanEtherereumHalightBitcoinCreateSwapParams, HanEthereumHbitBitcoinCreateSwapParams,
+        },
     };
-    use digest::Digest;
+    use chrono::Utc;
     use futures::future;
     use libp2p::{multiaddr::Multiaddr, PeerId};
     use rand::thread_rng;
@@ -700,7 +1054,8 @@ mod tests {
                     lnbtc,
                     ethereum_expiry,
                     lightning_expiry,
-                ),
+                )
+                .into(),
             )
             .expect("initiate communication for alice");
         bob_swarm
@@ -712,7 +1067,8 @@ mod tests {
                     lnbtc,
                     ethereum_expiry,
                     lightning_expiry,
-                ),
+                )
+                .into(),
             )
             .expect("initiate communication for bob");
 
@@ -725,18 +1081,134 @@ mod tests {
                 BehaviourOutEvent::SwapFinalized {
                     local_swap_id: _alice_local_swap_id,
                     swap_params: alice_swap_params,
+                    shared_swap_id: alice_shared_swap_id,
                     secret_hash: _alice_secret_hash,
                     ethereum_identity: _alice_eth_id,
+                    lightning_identity: _alice_ln_id,
                 },
                 BehaviourOutEvent::SwapFinalized {
                     local_swap_id: _bob_local_swap_id,
                     swap_params: bob_swap_params,
+                    shared_swap_id: bob_shared_swap_id,
                     secret_hash: _bob_secret_hash,
                     ethereum_identity: _bob_eth_id,
+                    lightning_identity: _bob_ln_id,
                 },
             ) => {
                 assert_eq!(bob_swap_params.digest(), alice_swap_params.digest());
+                assert_eq!(bob_shared_swap_id, alice_shared_swap_id);
             }
+            _ => panic!("expected both swaps to be finalized as halight swaps"),
         }
     }
+
+    #[tokio::test]
+    async fn finalize_hbit_ethereum_swap_success() {
+        // arrange
+        let alice_seed = RootSeed::new_random(thread_rng()).unwrap();
+        let bob_seed = RootSeed::new_random(thread_rng()).unwrap();
+        let (mut alice_swarm, _, alice_peer_id) = test_swarm::new(ComitLN::new(alice_seed));
+        let (mut bob_swarm, bob_addr, bob_peer_id) = test_swarm::new(ComitLN::new(bob_seed));
+
+        let alice_local_swap_id = LocalSwapId::default();
+        let bob_local_swap_id = LocalSwapId::default();
+        let swap_params = |role, peer| HanEthereumHbitBitcoinCreateSwapParams {
+            role,
+            peer,
+            ethereum_identity: EthereumIdentity::from(identity::Ethereum::random()),
+            ethereum_absolute_expiry: Timestamp::from(200),
+            ethereum_amount: Ether::from_wei(9_001_000_000_000_000_000_000u128),
+            bitcoin_absolute_expiry: Timestamp::from(100),
+            bitcoin_amount: asset::Bitcoin::from_sat(42),
+            bitcoin_network: bitcoin::Network::Regtest,
+            start_of_swap: Utc::now().naive_utc(),
+        };
+
+        alice_swarm
+            .initiate_communication(
+                alice_local_swap_id,
+                swap_params(Role::Alice, DialInformation {
+                    peer_id: bob_peer_id,
+                    address_hint: Some(bob_addr),
+                })
+                .into(),
+            )
+            .expect("initiate communication for alice");
+        bob_swarm
+            .initiate_communication(
+                bob_local_swap_id,
+                swap_params(Role::Bob, DialInformation {
+                    peer_id: alice_peer_id,
+                    address_hint: None,
+                })
+                .into(),
+            )
+            .expect("initiate communication for bob");
+
+        // act
+        let (alice_event, bob_event) = future::join(alice_swarm.next(), bob_swarm.next()).await;
+
+        // assert
+        match (alice_event, bob_event) {
+            (
+                BehaviourOutEvent::HbitSwapFinalized {
+                    swap_params: alice_swap_params,
+                    shared_swap_id: alice_shared_swap_id,
+                    bitcoin_identity: bob_bitcoin_identity,
+                    ..
+                },
+                BehaviourOutEvent::HbitSwapFinalized {
+                    swap_params: bob_swap_params,
+                    shared_swap_id: bob_shared_swap_id,
+                    bitcoin_identity: alice_bitcoin_identity,
+                    ..
+                },
+            ) => {
+                assert_eq!(bob_swap_params.digest(), alice_swap_params.digest());
+                assert_eq!(bob_shared_swap_id, alice_shared_swap_id);
+                assert_eq!(
+                    alice_bitcoin_identity,
+                    derive_bitcoin_identity(&alice_seed, alice_local_swap_id, true)
+                );
+                assert_eq!(
+                    bob_bitcoin_identity,
+                    derive_bitcoin_identity(&bob_seed, bob_local_swap_id, false)
+                );
+            }
+            _ => panic!("expected both swaps to be finalized as hbit swaps"),
+        }
+    }
+
+    #[test]
+    fn swaps_in_opposite_directions_have_different_digests() {
+        let bob_peer_id = PeerId::random();
+        let ether = Ether::from_wei(9_001_000_000_000_000_000_000u128);
+        let lnbtc = asset::Bitcoin::from_sat(42);
+        let ethereum_expiry = Timestamp::from(100);
+        let lightning_expiry = Timestamp::from(200);
+
+        let han_halight = make_alice_swap_params(
+            bob_peer_id.clone(),
+            "/ip4/127.0.0.1/tcp/9939".parse().unwrap(),
+            ether.clone(),
+            lnbtc,
+            ethereum_expiry,
+            lightning_expiry,
+        );
+        let halight_han = HalightBitcoinHanEthereumCreateSwapParams {
+            role: Role::Alice,
+            peer: han_halight.peer.clone(),
+            lightning_identity: han_halight.lightning_identity,
+            lightning_cltv_expiry: lightning_expiry,
+            lightning_amount: lnbtc,
+            ethereum_identity: han_halight.ethereum_identity,
+            ethereum_absolute_expiry: ethereum_expiry,
+            ethereum_amount: ether,
+        };
+
+        assert_ne!(
+            CreateSwapParams::from(han_halight).digest(),
+            CreateSwapParams::from(halight_han).digest()
+        );
+    }
 }
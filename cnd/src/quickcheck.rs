@@ -163,6 +163,20 @@ impl Arbitrary for Quickcheck<identity::Bitcoin> {
     }
 }
 
+impl Arbitrary for Quickcheck<identity::Lightning> {
+    fn arbitrary<G: Gen>(g: &mut G) -> Self {
+        let bytes = *Quickcheck::<[u8; 32]>::arbitrary(g);
+        let secret_key =
+            secp256k1::SecretKey::from_slice(&bytes).expect("all bytes are a valid secret key");
+        let public_key = identity::Lightning::from_secret_key(
+            &secp256k1::Secp256k1::signing_only(),
+            &secret_key,
+        );
+
+        Quickcheck(public_key)
+    }
+}
+
 impl Arbitrary for Quickcheck<identity::Ethereum> {
     fn arbitrary<G: Gen>(g: &mut G) -> Self {
         let bytes = *Quickcheck::<[u8; 20]>::arbitrary(g);
@@ -2,11 +2,13 @@ pub mod actions;
 mod facade;
 pub mod halight;
 pub mod han;
+pub mod hbit;
 pub mod herc20;
 pub mod ledger;
 pub mod ledger_states;
 pub mod rfc003;
 mod rfc003_facade;
+mod scan;
 pub mod state;
 mod swap_error_states;
 mod swap_id;
@@ -58,6 +60,15 @@ pub trait InitAction {
     fn init_action(&self) -> Option<Self::Output>;
 }
 
+/// Describes how to get the `deploy` action from the current state.
+///
+/// If `deploy` is not feasible in the current state, this should return `None`.
+pub trait DeployAction {
+    type Output;
+
+    fn deploy_action(&self) -> Option<Self::Output>;
+}
+
 /// Describes how to get the `fund` action from the current state.
 ///
 /// If `fund` is not feasible in the current state, this should return `None`.
@@ -1,11 +1,21 @@
 use crate::{
     asset,
-    db::CreatedSwap,
+    btsieve::{
+        self,
+        bitcoin::BitcoinConnector,
+        ethereum::{self, Web3Connector},
+    },
+    db::{
+        CreatedSwap, FinalizedHbitSwapData, FinalizedSwapData, Ledger, Save, Sqlite,
+        SwapStartHeights,
+    },
+    expiry_monitor::ExpiryAlerts,
     identity,
     network::{comit_ln, protocols::announce::SwapDigest, DialInformation, Swarm},
-    swap_protocols::{halight, LedgerStates, LocalSwapId, Role},
+    swap_protocols::{halight, han, hbit, herc20, LedgerStates, LocalSwapId, Role},
     timestamp::Timestamp,
 };
+use chrono::NaiveDateTime;
 use digest::{Digest, IntoDigestInput};
 use std::sync::Arc;
 
@@ -32,6 +42,677 @@ pub struct HanEtherereumHalightBitcoinCreateSwapParams {
     pub lightning_amount: asset::Bitcoin,
 }
 
+impl From<CreatedSwap<han::CreatedSwap, halight::CreatedSwap>>
+    for HanEtherereumHalightBitcoinCreateSwapParams
+{
+    fn from(created: CreatedSwap<han::CreatedSwap, halight::CreatedSwap>) -> Self {
+        Self {
+            role: created.role,
+            peer: DialInformation {
+                peer_id: created.peer,
+                address_hint: None,
+            },
+            ethereum_identity: created.alpha.identity.into(),
+            ethereum_absolute_expiry: created.alpha.absolute_expiry.into(),
+            ethereum_amount: created.alpha.amount,
+            lightning_identity: created.beta.identity,
+            lightning_cltv_expiry: created.beta.cltv_expiry.into(),
+            lightning_amount: created.beta.amount,
+        }
+    }
+}
+
+/// This represent the information available on a herc20-halight swap before
+/// communication with the other node has started
+#[derive(Clone, Digest, Debug, PartialEq)]
+#[digest(hash = "SwapDigest")]
+pub struct Herc20HalightBitcoinCreateSwapParams {
+    #[digest(ignore)]
+    pub role: Role,
+    #[digest(ignore)]
+    pub peer: DialInformation,
+    #[digest(ignore)]
+    pub ethereum_identity: EthereumIdentity,
+    #[digest(prefix = "2001")]
+    pub ethereum_absolute_expiry: Timestamp,
+    #[digest(prefix = "2002")]
+    pub ethereum_amount: asset::Erc20Quantity,
+    #[digest(prefix = "2003")]
+    pub token_contract: identity::Ethereum,
+    #[digest(ignore)]
+    pub lightning_identity: identity::Lightning,
+    #[digest(prefix = "3001")]
+    pub lightning_cltv_expiry: Timestamp,
+    #[digest(prefix = "3002")]
+    pub lightning_amount: asset::Bitcoin,
+}
+
+impl From<CreatedSwap<herc20::CreatedSwap, halight::CreatedSwap>>
+    for Herc20HalightBitcoinCreateSwapParams
+{
+    fn from(created: CreatedSwap<herc20::CreatedSwap, halight::CreatedSwap>) -> Self {
+        Self {
+            role: created.role,
+            peer: DialInformation {
+                peer_id: created.peer,
+                address_hint: None,
+            },
+            ethereum_identity: created.alpha.identity.into(),
+            ethereum_absolute_expiry: created.alpha.absolute_expiry.into(),
+            ethereum_amount: created.alpha.amount,
+            token_contract: created.alpha.contract_address,
+            lightning_identity: created.beta.identity,
+            lightning_cltv_expiry: created.beta.cltv_expiry.into(),
+            lightning_amount: created.beta.amount,
+        }
+    }
+}
+
+/// This represent the information available on a halight-han swap before
+/// communication with the other node has started.
+///
+/// Lightning is the alpha ledger of this swap, hence the Lightning fields use
+/// the alpha prefixes and the Ethereum fields the beta prefixes. This ensures
+/// the digest differs from the one of the han-halight swap with the same
+/// values.
+#[derive(Clone, Digest, Debug, PartialEq)]
+#[digest(hash = "SwapDigest")]
+pub struct HalightBitcoinHanEthereumCreateSwapParams {
+    #[digest(ignore)]
+    pub role: Role,
+    #[digest(ignore)]
+    pub peer: DialInformation,
+    #[digest(ignore)]
+    pub lightning_identity: identity::Lightning,
+    #[digest(prefix = "2001")]
+    pub lightning_cltv_expiry: Timestamp,
+    #[digest(prefix = "2002")]
+    pub lightning_amount: asset::Bitcoin,
+    #[digest(ignore)]
+    pub ethereum_identity: EthereumIdentity,
+    #[digest(prefix = "3001")]
+    pub ethereum_absolute_expiry: Timestamp,
+    #[digest(prefix = "3002")]
+    pub ethereum_amount: asset::Ether,
+}
+
+impl From<CreatedSwap<halight::CreatedSwap, han::CreatedSwap>>
+    for HalightBitcoinHanEthereumCreateSwapParams
+{
+    fn from(created: CreatedSwap<halight::CreatedSwap, han::CreatedSwap>) -> Self {
+        Self {
+            role: created.role,
+            peer: DialInformation {
+                peer_id: created.peer,
+                address_hint: None,
+            },
+            lightning_identity: created.alpha.identity,
+            lightning_cltv_expiry: created.alpha.cltv_expiry.into(),
+            lightning_amount: created.alpha.amount,
+            ethereum_identity: created.beta.identity.into(),
+            ethereum_absolute_expiry: created.beta.absolute_expiry.into(),
+            ethereum_amount: created.beta.amount,
+        }
+    }
+}
+
+/// This represent the information available on a halight-herc20 swap before
+/// communication with the other node has started.
+///
+/// Lightning is the alpha ledger of this swap, see
+/// `HalightBitcoinHanEthereumCreateSwapParams`.
+#[derive(Clone, Digest, Debug, PartialEq)]
+#[digest(hash = "SwapDigest")]
+pub struct HalightBitcoinHerc20CreateSwapParams {
+    #[digest(ignore)]
+    pub role: Role,
+    #[digest(ignore)]
+    pub peer: DialInformation,
+    #[digest(ignore)]
+    pub lightning_identity: identity::Lightning,
+    #[digest(prefix = "2001")]
+    pub lightning_cltv_expiry: Timestamp,
+    #[digest(prefix = "2002")]
+    pub lightning_amount: asset::Bitcoin,
+    #[digest(ignore)]
+    pub ethereum_identity: EthereumIdentity,
+    #[digest(prefix = "3001")]
+    pub ethereum_absolute_expiry: Timestamp,
+    #[digest(prefix = "3002")]
+    pub ethereum_amount: asset::Erc20Quantity,
+    #[digest(prefix = "3003")]
+    pub token_contract: identity::Ethereum,
+}
+
+impl From<CreatedSwap<halight::CreatedSwap, herc20::CreatedSwap>>
+    for HalightBitcoinHerc20CreateSwapParams
+{
+    fn from(created: CreatedSwap<halight::CreatedSwap, herc20::CreatedSwap>) -> Self {
+        Self {
+            role: created.role,
+            peer: DialInformation {
+                peer_id: created.peer,
+                address_hint: None,
+            },
+            lightning_identity: created.alpha.identity,
+            lightning_cltv_expiry: created.alpha.cltv_expiry.into(),
+            lightning_amount: created.alpha.amount,
+            ethereum_identity: created.beta.identity.into(),
+            ethereum_absolute_expiry: created.beta.absolute_expiry.into(),
+            ethereum_amount: created.beta.amount,
+            token_contract: created.beta.contract_address,
+        }
+    }
+}
+
+/// This represent the information available on a han-hbit swap before
+/// communication with the other node has started.
+///
+/// There is no Bitcoin identity because the keys used in the Bitcoin HTLC are
+/// transient, see `comit_ln::derive_bitcoin_identity`.
+#[derive(Clone, Digest, Debug, PartialEq)]
+#[digest(hash = "SwapDigest")]
+pub struct HanEthereumHbitBitcoinCreateSwapParams {
+    #[digest(ignore)]
+    pub role: Role,
+    #[digest(ignore)]
+    pub peer: DialInformation,
+    #[digest(ignore)]
+    pub ethereum_identity: EthereumIdentity,
+    #[digest(prefix = "2001")]
+    pub ethereum_absolute_expiry: Timestamp,
+    #[digest(prefix = "2002")]
+    pub ethereum_amount: asset::Ether,
+    #[digest(prefix = "3001")]
+    pub bitcoin_absolute_expiry: Timestamp,
+    #[digest(prefix = "3002")]
+    pub bitcoin_amount: asset::Bitcoin,
+    #[digest(prefix = "3003")]
+    pub bitcoin_network: bitcoin::Network,
+    #[digest(ignore)]
+    pub start_of_swap: NaiveDateTime,
+}
+
+impl From<CreatedSwap<han::CreatedSwap, hbit::CreatedSwap>>
+    for HanEthereumHbitBitcoinCreateSwapParams
+{
+    fn from(created: CreatedSwap<han::CreatedSwap, hbit::CreatedSwap>) -> Self {
+        Self {
+            role: created.role,
+            peer: DialInformation {
+                peer_id: created.peer,
+                address_hint: None,
+            },
+            ethereum_identity: created.alpha.identity.into(),
+            ethereum_absolute_expiry: created.alpha.absolute_expiry.into(),
+            ethereum_amount: created.alpha.amount,
+            bitcoin_absolute_expiry: created.beta.absolute_expiry.into(),
+            bitcoin_amount: created.beta.amount,
+            bitcoin_network: created.beta.network,
+            start_of_swap: created.created_at,
+        }
+    }
+}
+
+/// This represent the information available on a herc20-hbit swap before
+/// communication with the other node has started.
+#[derive(Clone, Digest, Debug, PartialEq)]
+#[digest(hash = "SwapDigest")]
+pub struct Herc20HbitBitcoinCreateSwapParams {
+    #[digest(ignore)]
+    pub role: Role,
+    #[digest(ignore)]
+    pub peer: DialInformation,
+    #[digest(ignore)]
+    pub ethereum_identity: EthereumIdentity,
+    #[digest(prefix = "2001")]
+    pub ethereum_absolute_expiry: Timestamp,
+    #[digest(prefix = "2002")]
+    pub ethereum_amount: asset::Erc20Quantity,
+    #[digest(prefix = "2003")]
+    pub token_contract: identity::Ethereum,
+    #[digest(prefix = "3001")]
+    pub bitcoin_absolute_expiry: Timestamp,
+    #[digest(prefix = "3002")]
+    pub bitcoin_amount: asset::Bitcoin,
+    #[digest(prefix = "3003")]
+    pub bitcoin_network: bitcoin::Network,
+    #[digest(ignore)]
+    pub start_of_swap: NaiveDateTime,
+}
+
+impl From<CreatedSwap<herc20::CreatedSwap, hbit::CreatedSwap>>
+    for Herc20HbitBitcoinCreateSwapParams
+{
+    fn from(created: CreatedSwap<herc20::CreatedSwap, hbit::CreatedSwap>) -> Self {
+        Self {
+            role: created.role,
+            peer: DialInformation {
+                peer_id: created.peer,
+                address_hint: None,
+            },
+            ethereum_identity: created.alpha.identity.into(),
+            ethereum_absolute_expiry: created.alpha.absolute_expiry.into(),
+            ethereum_amount: created.alpha.amount,
+            token_contract: created.alpha.contract_address,
+            bitcoin_absolute_expiry: created.beta.absolute_expiry.into(),
+            bitcoin_amount: created.beta.amount,
+            bitcoin_network: created.beta.network,
+            start_of_swap: created.created_at,
+        }
+    }
+}
+
+/// This represent the information available on a hbit-han swap before
+/// communication with the other node has started.
+///
+/// Bitcoin is the alpha ledger of this swap, see
+/// `HalightBitcoinHanEthereumCreateSwapParams`.
+#[derive(Clone, Digest, Debug, PartialEq)]
+#[digest(hash = "SwapDigest")]
+pub struct HbitBitcoinHanEthereumCreateSwapParams {
+    #[digest(ignore)]
+    pub role: Role,
+    #[digest(ignore)]
+    pub peer: DialInformation,
+    #[digest(prefix = "2001")]
+    pub bitcoin_absolute_expiry: Timestamp,
+    #[digest(prefix = "2002")]
+    pub bitcoin_amount: asset::Bitcoin,
+    #[digest(prefix = "2003")]
+    pub bitcoin_network: bitcoin::Network,
+    #[digest(ignore)]
+    pub ethereum_identity: EthereumIdentity,
+    #[digest(prefix = "3001")]
+    pub ethereum_absolute_expiry: Timestamp,
+    #[digest(prefix = "3002")]
+    pub ethereum_amount: asset::Ether,
+    #[digest(ignore)]
+    pub start_of_swap: NaiveDateTime,
+}
+
+impl From<CreatedSwap<hbit::CreatedSwap, han::CreatedSwap>>
+    for HbitBitcoinHanEthereumCreateSwapParams
+{
+    fn from(created: CreatedSwap<hbit::CreatedSwap, han::CreatedSwap>) -> Self {
+        Self {
+            role: created.role,
+            peer: DialInformation {
+                peer_id: created.peer,
+                address_hint: None,
+            },
+            bitcoin_absolute_expiry: created.alpha.absolute_expiry.into(),
+            bitcoin_amount: created.alpha.amount,
+            bitcoin_network: created.alpha.network,
+            ethereum_identity: created.beta.identity.into(),
+            ethereum_absolute_expiry: created.beta.absolute_expiry.into(),
+            ethereum_amount: created.beta.amount,
+            start_of_swap: created.created_at,
+        }
+    }
+}
+
+/// This represent the information available on a hbit-herc20 swap before
+/// communication with the other node has started.
+///
+/// Bitcoin is the alpha ledger of this swap, see
+/// `HalightBitcoinHanEthereumCreateSwapParams`.
+#[derive(Clone, Digest, Debug, PartialEq)]
+#[digest(hash = "SwapDigest")]
+pub struct HbitBitcoinHerc20CreateSwapParams {
+    #[digest(ignore)]
+    pub role: Role,
+    #[digest(ignore)]
+    pub peer: DialInformation,
+    #[digest(prefix = "2001")]
+    pub bitcoin_absolute_expiry: Timestamp,
+    #[digest(prefix = "2002")]
+    pub bitcoin_amount: asset::Bitcoin,
+    #[digest(prefix = "2003")]
+    pub bitcoin_network: bitcoin::Network,
+    #[digest(ignore)]
+    pub ethereum_identity: EthereumIdentity,
+    #[digest(prefix = "3001")]
+    pub ethereum_absolute_expiry: Timestamp,
+    #[digest(prefix = "3002")]
+    pub ethereum_amount: asset::Erc20Quantity,
+    #[digest(prefix = "3003")]
+    pub token_contract: identity::Ethereum,
+    #[digest(ignore)]
+    pub start_of_swap: NaiveDateTime,
+}
+
+impl From<CreatedSwap<hbit::CreatedSwap, herc20::CreatedSwap>>
+    for HbitBitcoinHerc20CreateSwapParams
+{
+    fn from(created: CreatedSwap<hbit::CreatedSwap, herc20::CreatedSwap>) -> Self {
+        Self {
+            role: created.role,
+            peer: DialInformation {
+                peer_id: created.peer,
+                address_hint: None,
+            },
+            bitcoin_absolute_expiry: created.alpha.absolute_expiry.into(),
+            bitcoin_amount: created.alpha.amount,
+            bitcoin_network: created.alpha.network,
+            ethereum_identity: created.beta.identity.into(),
+            ethereum_absolute_expiry: created.beta.absolute_expiry.into(),
+            ethereum_amount: created.beta.amount,
+            token_contract: created.beta.contract_address,
+            start_of_swap: created.created_at,
+        }
+    }
+}
+
+/// The ledger that is used as the alpha ledger of a swap negotiated through
+/// `ComitLN`, the other one is used as the beta ledger.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum AlphaLedger {
+    Ethereum,
+    Lightning,
+    Bitcoin,
+}
+
+/// The parameters of all swaps that can be negotiated with another node
+/// through `ComitLN`.
+#[derive(Clone, Debug, PartialEq)]
+pub enum CreateSwapParams {
+    HanEthereumHalightBitcoin(HanEtherereumHalightBitcoinCreateSwapParams),
+    Herc20EthereumHalightBitcoin(Herc20HalightBitcoinCreateSwapParams),
+    HalightBitcoinHanEthereum(HalightBitcoinHanEthereumCreateSwapParams),
+    HalightBitcoinHerc20Ethereum(HalightBitcoinHerc20CreateSwapParams),
+    HanEthereumHbitBitcoin(HanEthereumHbitBitcoinCreateSwapParams),
+    Herc20EthereumHbitBitcoin(Herc20HbitBitcoinCreateSwapParams),
+    HbitBitcoinHanEthereum(HbitBitcoinHanEthereumCreateSwapParams),
+    HbitBitcoinHerc20Ethereum(HbitBitcoinHerc20CreateSwapParams),
+}
+
+impl CreateSwapParams {
+    pub fn role(&self) -> Role {
+        match self {
+            CreateSwapParams::HanEthereumHalightBitcoin(params) => params.role,
+            CreateSwapParams::Herc20EthereumHalightBitcoin(params) => params.role,
+            CreateSwapParams::HalightBitcoinHanEthereum(params) => params.role,
+            CreateSwapParams::HalightBitcoinHerc20Ethereum(params) => params.role,
+            CreateSwapParams::HanEthereumHbitBitcoin(params) => params.role,
+            CreateSwapParams::Herc20EthereumHbitBitcoin(params) => params.role,
+            CreateSwapParams::HbitBitcoinHanEthereum(params) => params.role,
+            CreateSwapParams::HbitBitcoinHerc20Ethereum(params) => params.role,
+        }
+    }
+
+    pub fn peer(&self) -> DialInformation {
+        match self {
+            CreateSwapParams::HanEthereumHalightBitcoin(params) => params.peer.clone(),
+            CreateSwapParams::Herc20EthereumHalightBitcoin(params) => params.peer.clone(),
+            CreateSwapParams::HalightBitcoinHanEthereum(params) => params.peer.clone(),
+            CreateSwapParams::HalightBitcoinHerc20Ethereum(params) => params.peer.clone(),
+            CreateSwapParams::HanEthereumHbitBitcoin(params) => params.peer.clone(),
+            CreateSwapParams::Herc20EthereumHbitBitcoin(params) => params.peer.clone(),
+            CreateSwapParams::HbitBitcoinHanEthereum(params) => params.peer.clone(),
+            CreateSwapParams::HbitBitcoinHerc20Ethereum(params) => params.peer.clone(),
+        }
+    }
+
+    pub fn alpha_ledger(&self) -> AlphaLedger {
+        match self {
+            CreateSwapParams::HanEthereumHalightBitcoin(_)
+            | CreateSwapParams::Herc20EthereumHalightBitcoin(_)
+            | CreateSwapParams::HanEthereumHbitBitcoin(_)
+            | CreateSwapParams::Herc20EthereumHbitBitcoin(_) => AlphaLedger::Ethereum,
+            CreateSwapParams::HalightBitcoinHanEthereum(_)
+            | CreateSwapParams::HalightBitcoinHerc20Ethereum(_) => AlphaLedger::Lightning,
+            CreateSwapParams::HbitBitcoinHanEthereum(_)
+            | CreateSwapParams::HbitBitcoinHerc20Ethereum(_) => AlphaLedger::Bitcoin,
+        }
+    }
+
+    /// Returns true if the bitcoin are exchanged on Lightning using halight,
+    /// false if they are exchanged on the Bitcoin blockchain using hbit.
+    pub fn uses_halight(&self) -> bool {
+        match self {
+            CreateSwapParams::HanEthereumHalightBitcoin(_)
+            | CreateSwapParams::Herc20EthereumHalightBitcoin(_)
+            | CreateSwapParams::HalightBitcoinHanEthereum(_)
+            | CreateSwapParams::HalightBitcoinHerc20Ethereum(_) => true,
+            CreateSwapParams::HanEthereumHbitBitcoin(_)
+            | CreateSwapParams::Herc20EthereumHbitBitcoin(_)
+            | CreateSwapParams::HbitBitcoinHanEthereum(_)
+            | CreateSwapParams::HbitBitcoinHerc20Ethereum(_) => false,
+        }
+    }
+
+    /// Returns true if we are the party that funds the Ethereum HTLC and
+    /// receives the bitcoin.
+    ///
+    /// Alice funds the alpha ledger and Bob funds the beta ledger, hence this
+    /// depends on our role and the ledger Ethereum is used on.
+    pub fn funds_ethereum(&self) -> bool {
+        match (self.role(), self.alpha_ledger()) {
+            (Role::Alice, AlphaLedger::Ethereum)
+            | (Role::Bob, AlphaLedger::Lightning)
+            | (Role::Bob, AlphaLedger::Bitcoin) => true,
+            (Role::Alice, AlphaLedger::Lightning)
+            | (Role::Alice, AlphaLedger::Bitcoin)
+            | (Role::Bob, AlphaLedger::Ethereum) => false,
+        }
+    }
+
+    pub fn ethereum_identity(&self) -> identity::Ethereum {
+        match self {
+            CreateSwapParams::HanEthereumHalightBitcoin(params) => params.ethereum_identity.into(),
+            CreateSwapParams::Herc20EthereumHalightBitcoin(params) => {
+                params.ethereum_identity.into()
+            }
+            CreateSwapParams::HalightBitcoinHanEthereum(params) => params.ethereum_identity.into(),
+            CreateSwapParams::HalightBitcoinHerc20Ethereum(params) => {
+                params.ethereum_identity.into()
+            }
+            CreateSwapParams::HanEthereumHbitBitcoin(params) => params.ethereum_identity.into(),
+            CreateSwapParams::Herc20EthereumHbitBitcoin(params) => params.ethereum_identity.into(),
+            CreateSwapParams::HbitBitcoinHanEthereum(params) => params.ethereum_identity.into(),
+            CreateSwapParams::HbitBitcoinHerc20Ethereum(params) => params.ethereum_identity.into(),
+        }
+    }
+
+    pub fn ethereum_absolute_expiry(&self) -> Timestamp {
+        match self {
+            CreateSwapParams::HanEthereumHalightBitcoin(params) => params.ethereum_absolute_expiry,
+            CreateSwapParams::Herc20EthereumHalightBitcoin(params) => {
+                params.ethereum_absolute_expiry
+            }
+            CreateSwapParams::HalightBitcoinHanEthereum(params) => params.ethereum_absolute_expiry,
+            CreateSwapParams::HalightBitcoinHerc20Ethereum(params) => {
+                params.ethereum_absolute_expiry
+            }
+            CreateSwapParams::HanEthereumHbitBitcoin(params) => params.ethereum_absolute_expiry,
+            CreateSwapParams::Herc20EthereumHbitBitcoin(params) => params.ethereum_absolute_expiry,
+            CreateSwapParams::HbitBitcoinHanEthereum(params) => params.ethereum_absolute_expiry,
+            CreateSwapParams::HbitBitcoinHerc20Ethereum(params) => params.ethereum_absolute_expiry,
+        }
+    }
+
+    /// Returns `None` for hbit swaps.
+    pub fn lightning_identity(&self) -> Option<identity::Lightning> {
+        match self {
+            CreateSwapParams::HanEthereumHalightBitcoin(params) => Some(params.lightning_identity),
+            CreateSwapParams::Herc20EthereumHalightBitcoin(params) => {
+                Some(params.lightning_identity)
+            }
+            CreateSwapParams::HalightBitcoinHanEthereum(params) => Some(params.lightning_identity),
+            CreateSwapParams::HalightBitcoinHerc20Ethereum(params) => {
+                Some(params.lightning_identity)
+            }
+            _ => None,
+        }
+    }
+
+    /// Returns `None` for hbit swaps.
+    pub fn lightning_cltv_expiry(&self) -> Option<Timestamp> {
+        match self {
+            CreateSwapParams::HanEthereumHalightBitcoin(params) => {
+                Some(params.lightning_cltv_expiry)
+            }
+            CreateSwapParams::Herc20EthereumHalightBitcoin(params) => {
+                Some(params.lightning_cltv_expiry)
+            }
+            CreateSwapParams::HalightBitcoinHanEthereum(params) => {
+                Some(params.lightning_cltv_expiry)
+            }
+            CreateSwapParams::HalightBitcoinHerc20Ethereum(params) => {
+                Some(params.lightning_cltv_expiry)
+            }
+            _ => None,
+        }
+    }
+
+    /// Returns `None` for hbit swaps.
+    pub fn lightning_amount(&self) -> Option<asset::Bitcoin> {
+        match self {
+            CreateSwapParams::HanEthereumHalightBitcoin(params) => Some(params.lightning_amount),
+            CreateSwapParams::Herc20EthereumHalightBitcoin(params) => Some(params.lightning_amount),
+            CreateSwapParams::HalightBitcoinHanEthereum(params) => Some(params.lightning_amount),
+            CreateSwapParams::HalightBitcoinHerc20Ethereum(params) => Some(params.lightning_amount),
+            _ => None,
+        }
+    }
+
+    /// Returns `None` for halight swaps.
+    pub fn bitcoin_amount(&self) -> Option<asset::Bitcoin> {
+        match self {
+            CreateSwapParams::HanEthereumHbitBitcoin(params) => Some(params.bitcoin_amount),
+            CreateSwapParams::Herc20EthereumHbitBitcoin(params) => Some(params.bitcoin_amount),
+            CreateSwapParams::HbitBitcoinHanEthereum(params) => Some(params.bitcoin_amount),
+            CreateSwapParams::HbitBitcoinHerc20Ethereum(params) => Some(params.bitcoin_amount),
+            _ => None,
+        }
+    }
+
+    /// Returns `None` for halight swaps.
+    pub fn bitcoin_absolute_expiry(&self) -> Option<Timestamp> {
+        match self {
+            CreateSwapParams::HanEthereumHbitBitcoin(params) => {
+                Some(params.bitcoin_absolute_expiry)
+            }
+            CreateSwapParams::Herc20EthereumHbitBitcoin(params) => {
+                Some(params.bitcoin_absolute_expiry)
+            }
+            CreateSwapParams::HbitBitcoinHanEthereum(params) => {
+                Some(params.bitcoin_absolute_expiry)
+            }
+            CreateSwapParams::HbitBitcoinHerc20Ethereum(params) => {
+                Some(params.bitcoin_absolute_expiry)
+            }
+            _ => None,
+        }
+    }
+
+    /// Returns `None` for halight swaps.
+    pub fn bitcoin_network(&self) -> Option<bitcoin::Network> {
+        match self {
+            CreateSwapParams::HanEthereumHbitBitcoin(params) => Some(params.bitcoin_network),
+            CreateSwapParams::Herc20EthereumHbitBitcoin(params) => Some(params.bitcoin_network),
+            CreateSwapParams::HbitBitcoinHanEthereum(params) => Some(params.bitcoin_network),
+            CreateSwapParams::HbitBitcoinHerc20Ethereum(params) => Some(params.bitcoin_network),
+            _ => None,
+        }
+    }
+
+    /// The ledger of the swap Ethereum is used on.
+    pub fn ethereum_ledger(&self) -> Ledger {
+        match self.alpha_ledger() {
+            AlphaLedger::Ethereum => Ledger::Alpha,
+            AlphaLedger::Lightning | AlphaLedger::Bitcoin => Ledger::Beta,
+        }
+    }
+
+    /// The ledger of the swap the Bitcoin blockchain is used on.
+    ///
+    /// Returns `None` for halight swaps.
+    pub fn bitcoin_ledger(&self) -> Option<Ledger> {
+        match (self.alpha_ledger(), self.uses_halight()) {
+            (_, true) => None,
+            (AlphaLedger::Bitcoin, false) => Some(Ledger::Alpha),
+            (_, false) => Some(Ledger::Beta),
+        }
+    }
+
+    /// Point in time the swap was created, from which on the Bitcoin
+    /// blockchain is watched.
+    ///
+    /// Returns `None` for halight swaps.
+    pub fn start_of_swap(&self) -> Option<NaiveDateTime> {
+        match self {
+            CreateSwapParams::HanEthereumHbitBitcoin(params) => Some(params.start_of_swap),
+            CreateSwapParams::Herc20EthereumHbitBitcoin(params) => Some(params.start_of_swap),
+            CreateSwapParams::HbitBitcoinHanEthereum(params) => Some(params.start_of_swap),
+            CreateSwapParams::HbitBitcoinHerc20Ethereum(params) => Some(params.start_of_swap),
+            _ => None,
+        }
+    }
+
+    pub fn digest(&self) -> SwapDigest {
+        match self {
+            CreateSwapParams::HanEthereumHalightBitcoin(params) => params.clone().digest(),
+            CreateSwapParams::Herc20EthereumHalightBitcoin(params) => params.clone().digest(),
+            CreateSwapParams::HalightBitcoinHanEthereum(params) => params.clone().digest(),
+            CreateSwapParams::HalightBitcoinHerc20Ethereum(params) => params.clone().digest(),
+            CreateSwapParams::HanEthereumHbitBitcoin(params) => params.clone().digest(),
+            CreateSwapParams::Herc20EthereumHbitBitcoin(params) => params.clone().digest(),
+            CreateSwapParams::HbitBitcoinHanEthereum(params) => params.clone().digest(),
+            CreateSwapParams::HbitBitcoinHerc20Ethereum(params) => params.clone().digest(),
+        }
+    }
+}
+
+impl From<HanEtherereumHalightBitcoinCreateSwapParams> for CreateSwapParams {
+    fn from(params: HanEtherereumHalightBitcoinCreateSwapParams) -> Self {
+        CreateSwapParams::HanEthereumHalightBitcoin(params)
+    }
+}
+
+impl From<Herc20HalightBitcoinCreateSwapParams> for CreateSwapParams {
+    fn from(params: Herc20HalightBitcoinCreateSwapParams) -> Self {
+        CreateSwapParams::Herc20EthereumHalightBitcoin(params)
+    }
+}
+
+impl From<HalightBitcoinHanEthereumCreateSwapParams> for CreateSwapParams {
+    fn from(params: HalightBitcoinHanEthereumCreateSwapParams) -> Self {
+        CreateSwapParams::HalightBitcoinHanEthereum(params)
+    }
+}
+
+impl From<HalightBitcoinHerc20CreateSwapParams> for CreateSwapParams {
+    fn from(params: HalightBitcoinHerc20CreateSwapParams) -> Self {
+        CreateSwapParams::HalightBitcoinHerc20Ethereum(params)
+    }
+}
+
+impl From<HanEthereumHbitBitcoinCreateSwapParams> for CreateSwapParams {
+    fn from(params: HanEthereumHbitBitcoinCreateSwapParams) -> Self {
+        CreateSwapParams::HanEthereumHbitBitcoin(params)
+    }
+}
+
+impl From<Herc20HbitBitcoinCreateSwapParams> for CreateSwapParams {
+    fn from(params: Herc20HbitBitcoinCreateSwapParams) -> Self {
+        CreateSwapParams::Herc20EthereumHbitBitcoin(params)
+    }
+}
+
+impl From<HbitBitcoinHanEthereumCreateSwapParams> for CreateSwapParams {
+    fn from(params: HbitBitcoinHanEthereumCreateSwapParams) -> Self {
+        CreateSwapParams::HbitBitcoinHanEthereum(params)
+    }
+}
+
+impl From<HbitBitcoinHerc20CreateSwapParams> for CreateSwapParams {
+    fn from(params: HbitBitcoinHerc20CreateSwapParams) -> Self {
+        CreateSwapParams::HbitBitcoinHerc20Ethereum(params)
+    }
+}
+
 impl IntoDigestInput for asset::Bitcoin {
     fn into_digest_input(self) -> Vec<u8> {
         self.to_le_bytes().to_vec()
@@ -44,6 +725,24 @@ impl IntoDigestInput for asset::Ether {
     }
 }
 
+impl IntoDigestInput for asset::Erc20Quantity {
+    fn into_digest_input(self) -> Vec<u8> {
+        self.to_bytes()
+    }
+}
+
+impl IntoDigestInput for bitcoin::Network {
+    fn into_digest_input(self) -> Vec<u8> {
+        self.magic().to_le_bytes().to_vec()
+    }
+}
+
+impl IntoDigestInput for identity::Ethereum {
+    fn into_digest_input(self) -> Vec<u8> {
+        <[u8; 20]>::from(self).to_vec()
+    }
+}
+
 #[derive(Clone, Copy, Debug, PartialEq)]
 pub struct EthereumIdentity(identity::Ethereum);
 
@@ -71,25 +770,135 @@ impl IntoDigestInput for Timestamp {
 #[derive(Clone, Debug)]
 pub struct Facade {
     pub swarm: Swarm,
-    // We currently only support Han-HALight, therefor 'alpha' is Ethereum and 'beta' is Lightning.
-    pub alpha_ledger_states: Arc<LedgerStates>,
-    pub beta_ledger_states: Arc<halight::States>,
+    // The states are stored per protocol and not per alpha/beta ledger because
+    // HALight can be used on either side of a swap.
+    pub han_states: Arc<LedgerStates>,
+    pub herc20_states: Arc<herc20::States>,
+    pub halight_states: Arc<halight::States>,
+    pub hbit_states: Arc<hbit::States>,
+    pub bitcoin_connector: Arc<btsieve::bitcoin::Cache<BitcoinConnector>>,
+    pub ethereum_connector: Arc<ethereum::Cache<Web3Connector>>,
+    pub expiry_alerts: Arc<ExpiryAlerts>,
+    pub db: Sqlite,
+    pub bitcoin_start_height_margin: u32,
+    pub ethereum_start_height_margin: u32,
 }
 
 impl Facade {
-    pub async fn save<A, B>(&self, _: CreatedSwap<A, B>) -> anyhow::Result<()> {
+    pub async fn save<A, B>(&self, swap: CreatedSwap<A, B>) -> anyhow::Result<()>
+    where
+        Sqlite: Save<CreatedSwap<A, B>>,
+    {
+        self.db.save(swap).await
+    }
+
+    /// Records the heights from which on the ledgers of a freshly created swap
+    /// are watched, Lightning is not watched block by block.
+    ///
+    /// Watchers of swaps without a start height, for example because the latest
+    /// block could not be fetched, fall back to the time the swap started.
+    pub async fn record_start_heights(
+        &self,
+        id: LocalSwapId,
+        swap_params: &CreateSwapParams,
+    ) -> anyhow::Result<()> {
+        let ethereum_ledger = swap_params.ethereum_ledger();
+        let start_height = btsieve::start_height(
+            self.ethereum_connector.as_ref(),
+            self.ethereum_start_height_margin,
+        )
+        .await;
+        self.record_start_height(id, ethereum_ledger, start_height)
+            .await?;
+
+        if let Some(bitcoin_ledger) = swap_params.bitcoin_ledger() {
+            let start_height = btsieve::start_height(
+                self.bitcoin_connector.as_ref(),
+                self.bitcoin_start_height_margin,
+            )
+            .await;
+            self.record_start_height(id, bitcoin_ledger, start_height)
+                .await?;
+        }
+
         Ok(())
     }
 
+    async fn record_start_height(
+        &self,
+        id: LocalSwapId,
+        ledger: Ledger,
+        start_height: anyhow::Result<Option<u32>>,
+    ) -> anyhow::Result<()> {
+        let start_height = match start_height {
+            Ok(start_height) => start_height,
+            Err(e) => {
+                tracing::warn!(
+                    "failed to determine start height on {} ledger of swap {}: {:?}",
+                    ledger,
+                    id,
+                    e
+                );
+                None
+            }
+        };
+
+        self.db.save_start_height(&id, ledger, start_height).await
+    }
+
     pub async fn initiate_communication(
         &self,
         id: LocalSwapId,
-        swap_params: HanEtherereumHalightBitcoinCreateSwapParams,
+        swap_params: CreateSwapParams,
     ) -> anyhow::Result<()> {
         self.swarm.initiate_communication(id, swap_params).await
     }
 
-    pub async fn get_finalized_swap(&self, id: LocalSwapId) -> Option<comit_ln::FinalizedSwap> {
+    pub async fn get_finalized_swap(
+        &self,
+        id: LocalSwapId,
+    ) -> Option<comit_ln::FinalizedSwap<asset::Ether>> {
         self.swarm.get_finalized_swap(id).await
     }
+
+    pub async fn get_finalized_herc20_swap(
+        &self,
+        id: LocalSwapId,
+    ) -> Option<comit_ln::FinalizedSwap<asset::Erc20>> {
+        self.swarm.get_finalized_herc20_swap(id).await
+    }
+
+    pub async fn get_finalized_han_hbit_swap(
+        &self,
+        id: LocalSwapId,
+    ) -> Option<comit_ln::FinalizedHbitSwap<asset::Ether>> {
+        self.swarm.get_finalized_han_hbit_swap(id).await
+    }
+
+    pub async fn get_finalized_herc20_hbit_swap(
+        &self,
+        id: LocalSwapId,
+    ) -> Option<comit_ln::FinalizedHbitSwap<asset::Erc20>> {
+        self.swarm.get_finalized_herc20_hbit_swap(id).await
+    }
+
+    pub async fn resume_finalized_swap(
+        &self,
+        swap_params: CreateSwapParams,
+        finalized: FinalizedSwapData,
+    ) {
+        self.swarm
+            .resume_finalized_swap(swap_params, finalized)
+            .await
+    }
+
+    pub async fn resume_finalized_hbit_swap(
+        &self,
+        swap_params: CreateSwapParams,
+        finalized: FinalizedHbitSwapData,
+    ) {
+        self.swarm
+            .resume_finalized_hbit_swap(swap_params, finalized)
+            .await
+    }
 }
@@ -3,7 +3,7 @@ use crate::{
     swap_protocols::{
         rfc003::{Secret, SecretHash},
         state,
-        state::Update,
+        state::{Change, Changes, Update},
         LocalSwapId,
     },
 };
@@ -26,7 +26,7 @@ pub use connector::*;
 
 /// Data required to create a swap that involves bitcoin on the lightning
 /// network.
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, PartialEq)]
 pub struct CreatedSwap {
     pub amount: asset::Bitcoin,
     pub identity: identity::Lightning,
@@ -124,7 +124,11 @@ pub enum Event {
 pub struct Opened;
 
 #[derive(Debug, Clone, Copy, PartialEq)]
-pub struct Accepted;
+pub struct Accepted {
+    /// The bitcoin block height at which the payment was accepted, only known
+    /// on the recipient side.
+    pub accept_height: Option<u32>,
+}
 
 #[derive(Debug, Clone, Copy, PartialEq)]
 pub struct Settled {
@@ -135,7 +139,17 @@ pub struct Settled {
 pub struct Cancelled;
 
 #[derive(Default, Debug)]
-pub struct States(Mutex<HashMap<LocalSwapId, State>>);
+pub struct States {
+    states: Mutex<HashMap<LocalSwapId, State>>,
+    changes: Changes,
+}
+
+impl States {
+    /// Subscribes to the events recorded from now on.
+    pub fn subscribe(&self) -> impl Stream<Item = Change> + Send + 'static {
+        self.changes.subscribe()
+    }
+}
 
 impl State {
     pub fn transition_to_opened(&mut self, opened: Opened) {
@@ -173,7 +187,7 @@ impl State {
 #[async_trait::async_trait]
 impl state::Get<State> for States {
     async fn get(&self, key: &LocalSwapId) -> anyhow::Result<Option<State>> {
-        let states = self.0.lock().await;
+        let states = self.states.lock().await;
         let state = states.get(key).copied();
 
         Ok(state)
@@ -183,7 +197,8 @@ impl state::Get<State> for States {
 #[async_trait::async_trait]
 impl state::Update<Event> for States {
     async fn update(&self, key: &LocalSwapId, event: Event) {
-        let mut states = self.0.lock().await;
+        let event_name = event.to_string();
+        let mut states = self.states.lock().await;
         let entry = states.entry(*key);
 
         match (event, entry) {
@@ -207,11 +222,15 @@ impl state::Update<Event> for States {
                     "Received Started event for {} although state is already present",
                     key
                 );
+                return;
             }
             (_, Entry::Vacant(_)) => {
                 tracing::warn!("State not found for {}", key);
+                return;
             }
         }
+
+        self.changes.publish(*key, event_name);
     }
 }
 
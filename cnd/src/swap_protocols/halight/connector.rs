@@ -1,9 +1,12 @@
-use crate::swap_protocols::{
-    halight::{
-        Accepted, Cancelled, Opened, Params, Settled, WaitForAccepted, WaitForCancelled,
-        WaitForOpened, WaitForSettled,
+use crate::{
+    metrics,
+    swap_protocols::{
+        halight::{
+            Accepted, Cancelled, Opened, Params, Settled, WaitForAccepted, WaitForCancelled,
+            WaitForOpened, WaitForSettled,
+        },
+        rfc003::{Secret, SecretHash},
     },
-    rfc003::{Secret, SecretHash},
 };
 use anyhow::{Context, Error};
 use reqwest::{
@@ -51,6 +54,36 @@ struct Invoice {
     pub state: InvoiceState,
     #[serde(deserialize_with = "deserialize_r_preimage")]
     pub r_preimage: Option<[u8; 32]>,
+    #[serde(default)]
+    pub htlcs: Vec<InvoiceHtlc>,
+}
+
+/// Invoice HTLC states.  These mirror the invoice HTLC states used by lnd.
+// ref: https://api.lightning.community/#invoicehtlcstate
+#[derive(Clone, Copy, Debug, Deserialize, PartialEq)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+enum InvoiceHtlcState {
+    Accepted,
+    Settled,
+    Canceled,
+}
+
+#[derive(Clone, Copy, Debug, Deserialize)]
+struct InvoiceHtlc {
+    pub accept_height: u32,
+    pub state: InvoiceHtlcState,
+}
+
+impl Invoice {
+    /// The height at which the first of the HTLCs paying this invoice was
+    /// accepted.
+    fn accept_height(&self) -> Option<u32> {
+        self.htlcs
+            .iter()
+            .filter(|htlc| htlc.state == InvoiceHtlcState::Accepted)
+            .map(|htlc| htlc.accept_height)
+            .min()
+    }
 }
 
 #[derive(Clone, Debug, Deserialize)]
@@ -90,6 +123,16 @@ impl LndConnectorParams {
             macaroon,
         })
     }
+
+    pub fn url(&self) -> &Url {
+        &self.lnd_url
+    }
+
+    /// An HTTP client that authenticates against lnd with the macaroon of
+    /// these params.
+    pub fn client(&self) -> Result<reqwest::Client, Error> {
+        client(&self.certificate, &self.macaroon)
+    }
 }
 
 fn read_file<T>(path: PathBuf) -> anyhow::Result<T>
@@ -159,6 +202,8 @@ impl LndConnectorAsSender {
         secret_hash: SecretHash,
         status: PaymentStatus,
     ) -> Result<Option<Payment>, Error> {
+        metrics::record_lnd_poll("payment");
+
         let response = client(&self.certificate, &self.macaroon)?
             .get(self.payment_url())
             .send()
@@ -197,7 +242,9 @@ impl WaitForAccepted for LndConnectorAsSender {
             tokio::time::delay_for(Duration::from_millis(self.retry_interval_ms)).await;
         }
 
-        Ok(Accepted)
+        Ok(Accepted {
+            accept_height: None,
+        })
     }
 }
 
@@ -282,6 +329,8 @@ impl LndConnectorAsReceiver {
         secret_hash: SecretHash,
         expected_state: InvoiceState,
     ) -> Result<Option<Invoice>, Error> {
+        metrics::record_lnd_poll("invoice");
+
         let response = client(&self.certificate, &self.macaroon)?
             .get(self.invoice_url(secret_hash)?)
             .send()
@@ -354,14 +403,19 @@ impl WaitForAccepted for LndConnectorAsReceiver {
         // Since the sender uses the params to make the payment (as apposed to
         // the invoice) LND guarantees that the params match the invoice when
         // updating the invoice status.
-        while self
-            .find_invoice(params.secret_hash, InvoiceState::Accepted)
-            .await?
-            .is_none()
-        {
-            tokio::time::delay_for(Duration::from_millis(self.retry_interval_ms)).await;
-        }
-        Ok(Accepted)
+        let invoice = loop {
+            match self
+                .find_invoice(params.secret_hash, InvoiceState::Accepted)
+                .await?
+            {
+                Some(invoice) => break invoice,
+                None => tokio::time::delay_for(Duration::from_millis(self.retry_interval_ms)).await,
+            }
+        };
+
+        Ok(Accepted {
+            accept_height: invoice.accept_height(),
+        })
     }
 }
 
@@ -536,4 +590,28 @@ mod tests {
         let invoice = serde_json::from_str::<Invoice>(invoice_json).unwrap();
         assert_that(&invoice.r_preimage).is_none()
     }
+
+    #[test]
+    fn accept_height_of_accepted_invoice_is_the_lowest_accepted_htlc() {
+        let invoice_json = r#"{
+      "r_preimage": null,
+      "value": "10000",
+      "value_msat": "10000000",
+      "expiry": "3600",
+      "cltv_expiry": "350",
+      "amt_paid_sat": "10000",
+      "amt_paid_msat": "10000000",
+      "state": "ACCEPTED",
+      "htlcs": [
+        { "accept_height": 650010, "state": "ACCEPTED" },
+        { "accept_height": 650002, "state": "CANCELED" },
+        { "accept_height": 650005, "state": "ACCEPTED" }
+      ]
+    }"#;
+
+        let invoice = serde_json::from_str::<Invoice>(invoice_json).unwrap();
+        assert_that(&invoice.accept_height())
+            .is_some()
+            .is_equal_to(650005)
+    }
 }
@@ -1,33 +1,40 @@
 use crate::{
     asset,
-    btsieve::ethereum::{Cache, Web3Connector},
+    btsieve::{
+        ethereum::{Cache, Web3Connector},
+        unless_reverted, BlockStatus, ScanProgress, StartOfSwap, WaitForFinality,
+    },
+    db::{Ledger, Sqlite, SwapWatcherCheckpoints},
     htlc_location, identity,
     swap_protocols::{
         han, ledger,
         rfc003::{
             create_swap::{HtlcParams, SwapEvent},
             events::{
-                Deployed, HtlcDeployed, HtlcFunded, HtlcRedeemed, HtlcRefunded, Redeemed, Refunded,
+                Deployed, Funded, HtlcDeployed, HtlcFunded, HtlcRedeemed, HtlcRefunded, Redeemed,
+                Refunded,
             },
             LedgerState,
         },
-        state, LedgerStates, LocalSwapId, Role,
+        scan::scan,
+        state, LedgerStates, LocalSwapId,
     },
     transaction,
 };
-use chrono::{NaiveDateTime, Utc};
-use futures::future::{self, Either};
+use futures::{
+    future::{self, Either},
+    TryFutureExt,
+};
 use genawaiter::{
     sync::{Co, Gen},
     GeneratorState,
 };
 use std::sync::Arc;
-use tracing_futures::Instrument;
 
 /// Htlc Native Ethereum atomic swap protocol.
 
 /// Data required to create a swap that involves Ether.
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, PartialEq)]
 pub struct CreatedSwap {
     pub amount: asset::Ether,
     pub identity: identity::Ethereum,
@@ -37,19 +44,22 @@ pub struct CreatedSwap {
 
 pub async fn new_han_ethereum_ether_swap(
     swap_id: LocalSwapId,
+    ledger: Ledger,
     connector: Arc<Cache<Web3Connector>>,
+    checkpoints: Sqlite,
     ethereum_ledger_state: Arc<LedgerStates>,
     htlc_params: HtlcParams<ledger::Ethereum, asset::Ether, identity::Ethereum>,
-    role: Role,
+    start_of_swap: StartOfSwap,
 ) {
-    han::create_watcher::<_, _, _, _, htlc_location::Ethereum, _, transaction::Ethereum>(
+    han::create_watcher::<_, _, _, _, _, htlc_location::Ethereum, _, transaction::Ethereum>(
         connector.as_ref(),
+        &checkpoints,
         ethereum_ledger_state,
         swap_id,
+        ledger,
         htlc_params,
-        Utc::now().naive_local(),
+        start_of_swap,
     )
-    .instrument(tracing::error_span!("alpha_ledger", swap_id = %swap_id, role = %role))
     .await
 }
 
@@ -64,17 +74,21 @@ pub async fn new_han_ethereum_ether_swap(
 ///
 /// It is highly unlikely for Bob to fund the HTLC now, yet the current
 /// implementation is still waiting for that.
-async fn create_watcher<C, S, L, A, H, I, T>(
+async fn create_watcher<C, D, S, L, A, H, I, T>(
     ethereum_connector: &C,
+    checkpoints: &D,
     ledger_state: Arc<S>,
     swap_id: LocalSwapId,
+    ledger: Ledger,
     htlc_params: HtlcParams<L, A, I>,
-    accepted_at: NaiveDateTime,
+    start_of_swap: StartOfSwap,
 ) where
     C: HtlcFunded<L, A, H, I, T>
         + HtlcDeployed<L, A, H, I, T>
         + HtlcRedeemed<L, A, H, I, T>
-        + HtlcRefunded<L, A, H, I, T>,
+        + HtlcRefunded<L, A, H, I, T>
+        + WaitForFinality<T>,
+    D: SwapWatcherCheckpoints<Deployed<H, T>, Funded<A, T>>,
     S: state::Update<SwapEvent<A, H, T>> + state::Insert<LedgerState<A, H, T>>,
     L: Clone,
     A: Ord + Clone,
@@ -89,8 +103,16 @@ async fn create_watcher<C, S, L, A, H, I, T>(
     // construct a generator that watches alpha and beta ledger concurrently
     let mut generator = Gen::new({
         |co| async {
-            watch_ledger::<C, L, A, H, I, T>(&ethereum_connector, co, htlc_params, accepted_at)
-                .await
+            watch_ledger::<C, D, L, A, H, I, T>(
+                &ethereum_connector,
+                checkpoints,
+                co,
+                swap_id,
+                ledger,
+                htlc_params,
+                start_of_swap,
+            )
+            .await
         }
     });
 
@@ -119,48 +141,157 @@ async fn create_watcher<C, S, L, A, H, I, T>(
 /// Returns a future that waits for events to happen on a ledger.
 ///
 /// Each event is yielded through the controller handle (co) of the coroutine.
-async fn watch_ledger<C, L, A, H, I, T>(
+/// Observed events and the progress of the scan are saved as a checkpoint, a
+/// restarted watcher picks up from there.
+async fn watch_ledger<C, D, L, A, H, I, T>(
     ethereum_connector: &C,
+    checkpoints: &D,
     co: Co<SwapEvent<A, H, T>>,
+    id: LocalSwapId,
+    ledger: Ledger,
     htlc_params: HtlcParams<L, A, I>,
-    start_of_swap: NaiveDateTime,
+    start_of_swap: StartOfSwap,
 ) -> anyhow::Result<()>
 where
     C: HtlcFunded<L, A, H, I, T>
         + HtlcDeployed<L, A, H, I, T>
         + HtlcRedeemed<L, A, H, I, T>
-        + HtlcRefunded<L, A, H, I, T>,
+        + HtlcRefunded<L, A, H, I, T>
+        + WaitForFinality<T>,
+    D: SwapWatcherCheckpoints<Deployed<H, T>, Funded<A, T>>,
+    A: Clone,
+    H: Clone,
     Deployed<H, T>: Clone,
     Redeemed<T>: Clone,
     Refunded<T>: Clone,
+    T: Clone,
 {
-    let deployed = ethereum_connector
-        .htlc_deployed(&htlc_params, start_of_swap)
+    let mut checkpoint = checkpoints
+        .load_swap_watcher_checkpoint(&id, ledger)
         .await?;
-    co.yield_(SwapEvent::Deployed(deployed.clone())).await;
 
-    let funded = ethereum_connector
-        .htlc_funded(&htlc_params, &deployed, start_of_swap)
-        .await?;
-    co.yield_(SwapEvent::Funded(funded)).await;
+    'deployed: loop {
+        let deployed = match checkpoint.deployed.clone() {
+            Some(deployed) => deployed,
+            None => {
+                let progress = ScanProgress::new();
+                let deployed = ethereum_connector.htlc_deployed(
+                    &htlc_params,
+                    progress.start_of_scan(start_of_swap, checkpoint.scanned.clone()),
+                );
+                let deployed = scan(
+                    checkpoints,
+                    &id,
+                    ledger,
+                    &mut checkpoint,
+                    progress,
+                    deployed,
+                )
+                .await?;
 
-    let redeemed = ethereum_connector.htlc_redeemed(&htlc_params, &deployed, start_of_swap);
+                checkpoint.deployed = Some(deployed.clone());
+                checkpoints
+                    .save_swap_watcher_checkpoint(&id, ledger, checkpoint.clone())
+                    .await?;
 
-    let refunded = ethereum_connector.htlc_refunded(&htlc_params, &deployed, start_of_swap);
+                deployed
+            }
+        };
+        co.yield_(SwapEvent::Deployed(deployed.clone())).await;
 
-    match future::try_select(redeemed, refunded).await {
-        Ok(Either::Left((redeemed, _))) => {
-            co.yield_(SwapEvent::Redeemed(redeemed.clone())).await;
-        }
-        Ok(Either::Right((refunded, _))) => {
-            co.yield_(SwapEvent::Refunded(refunded.clone())).await;
-        }
-        Err(either) => {
-            let (error, _other_future) = either.factor_first();
+        'funded: loop {
+            let funded = match checkpoint.funded.clone() {
+                Some(funded) => funded,
+                None => {
+                    let progress = ScanProgress::new();
+                    let funded = ethereum_connector.htlc_funded(
+                        &htlc_params,
+                        &deployed,
+                        progress.start_of_scan(start_of_swap, checkpoint.scanned.clone()),
+                    );
+                    let funded = unless_reverted(ethereum_connector, &deployed.transaction, funded);
+                    let funded =
+                        match scan(checkpoints, &id, ledger, &mut checkpoint, progress, funded)
+                            .await?
+                        {
+                            Some(funded) => funded,
+                            None => {
+                                checkpoint.deployed = None;
+                                checkpoint.scanned = None;
+                                checkpoints
+                                    .save_swap_watcher_checkpoint(&id, ledger, checkpoint.clone())
+                                    .await?;
+
+                                co.yield_(SwapEvent::Reverted).await;
+                                continue 'deployed;
+                            }
+                        };
 
-            return Err(error);
+                    checkpoint.funded = Some(funded.clone());
+                    checkpoints
+                        .save_swap_watcher_checkpoint(&id, ledger, checkpoint.clone())
+                        .await?;
+
+                    funded
+                }
+            };
+            let fund_transaction = funded.transaction().clone();
+            co.yield_(SwapEvent::Funded(funded)).await;
+
+            loop {
+                let progress = ScanProgress::new();
+                let redeemed = ethereum_connector.htlc_redeemed(
+                    &htlc_params,
+                    &deployed,
+                    progress.start_of_scan(start_of_swap, checkpoint.scanned.clone()),
+                );
+                let refunded = ethereum_connector.htlc_refunded(
+                    &htlc_params,
+                    &deployed,
+                    progress.start_of_scan(start_of_swap, checkpoint.scanned.clone()),
+                );
+                let settled = future::try_select(redeemed, refunded)
+                    .map_err(|either| either.factor_first().0);
+                let settled = unless_reverted(ethereum_connector, &fund_transaction, settled);
+
+                let settle_transaction =
+                    match scan(checkpoints, &id, ledger, &mut checkpoint, progress, settled).await?
+                    {
+                        Some(Either::Left((redeemed, _))) => {
+                            co.yield_(SwapEvent::Redeemed(redeemed.clone())).await;
+                            redeemed.transaction
+                        }
+                        Some(Either::Right((refunded, _))) => {
+                            co.yield_(SwapEvent::Refunded(refunded.clone())).await;
+                            refunded.transaction
+                        }
+                        None => {
+                            checkpoint.funded = None;
+                            checkpoint.scanned = None;
+                            checkpoints
+                                .save_swap_watcher_checkpoint(&id, ledger, checkpoint.clone())
+                                .await?;
+
+                            co.yield_(SwapEvent::Reverted).await;
+                            continue 'funded;
+                        }
+                    };
+
+                match ethereum_connector
+                    .wait_for_finality(&settle_transaction)
+                    .await?
+                {
+                    BlockStatus::Confirmed => return Ok(()),
+                    BlockStatus::Reverted => {
+                        checkpoint.scanned = None;
+                        checkpoints
+                            .save_swap_watcher_checkpoint(&id, ledger, checkpoint.clone())
+                            .await?;
+
+                        co.yield_(SwapEvent::Reverted).await
+                    }
+                }
+            }
         }
     }
-
-    Ok(())
 }
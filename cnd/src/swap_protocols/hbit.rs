@@ -0,0 +1,555 @@
+use crate::{
+    asset,
+    btsieve::{
+        unless_reverted, BlockStatus, ScanProgress, StartOfScan, StartOfSwap, WaitForFinality,
+    },
+    db::{Ledger, SwapWatcherCheckpoints},
+    htlc_location, identity,
+    swap_protocols::{
+        rfc003::{Secret, SecretHash},
+        scan::scan,
+        state,
+        state::{Change, Changes, Update},
+        LocalSwapId,
+    },
+    timestamp::Timestamp,
+    transaction,
+};
+use ::bitcoin::{
+    hashes::{hash160, Hash},
+    Address, Network,
+};
+use blockchain_contracts::bitcoin::rfc003::bitcoin_htlc::BitcoinHtlc;
+use futures::{
+    future::{self, Either},
+    Stream, TryFutureExt, TryStreamExt,
+};
+use genawaiter::sync::{Co, Gen};
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::{hash_map::Entry, HashMap},
+    sync::Arc,
+};
+use tokio::sync::Mutex;
+
+mod connector_impls;
+
+pub use connector_impls::*;
+
+/// Htlc Bitcoin atomic swap protocol.
+
+/// Data required to create a swap that involves bitcoin.
+///
+/// There is no identity because the keys used in the HTLC are derived from the
+/// seed once the swap is finalized, see `comit_ln::derive_bitcoin_identity`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct CreatedSwap {
+    pub amount: asset::Bitcoin,
+    pub network: Network,
+    pub absolute_expiry: u32,
+}
+
+/// Creates a new instance of the hbit protocol.
+///
+/// This function delegates to the `new` function for the actual protocol
+/// implementation. Its main purpose is to annotate the protocol instance with
+/// logging information and store the events yielded by the protocol.
+pub async fn new_hbit_swap<C, D>(
+    id: LocalSwapId,
+    ledger: Ledger,
+    connector: Arc<C>,
+    checkpoints: D,
+    state_store: Arc<States>,
+    params: Params,
+) where
+    C: WaitForFunded + WaitForRedeemed + WaitForRefunded + WaitForFinality<transaction::Bitcoin>,
+    D: SwapWatcherCheckpoints<(), Funded>,
+{
+    let mut events = new(connector.as_ref(), &checkpoints, id, ledger, params)
+        .inspect_ok(|event| tracing::info!("yielded event {}", event))
+        .inspect_err(|error| tracing::error!("swap failed with {:?}", error));
+
+    while let Ok(Some(event)) = events.try_next().await {
+        state_store.update(&id, event).await;
+    }
+
+    tracing::info!("swap finished");
+}
+
+/// Resolves when said event has occurred.
+///
+/// Blocks are looked at from the given start of the scan on.
+#[async_trait::async_trait]
+pub trait WaitForFunded {
+    async fn wait_for_funded(
+        &self,
+        params: Params,
+        start_of_scan: StartOfScan,
+    ) -> anyhow::Result<Funded>;
+}
+
+#[async_trait::async_trait]
+pub trait WaitForRedeemed {
+    async fn wait_for_redeemed(
+        &self,
+        params: Params,
+        location: htlc_location::Bitcoin,
+        start_of_scan: StartOfScan,
+    ) -> anyhow::Result<Redeemed>;
+}
+
+#[async_trait::async_trait]
+pub trait WaitForRefunded {
+    async fn wait_for_refunded(
+        &self,
+        params: Params,
+        location: htlc_location::Bitcoin,
+        start_of_scan: StartOfScan,
+    ) -> anyhow::Result<Refunded>;
+}
+
+/// Represents states that a Bitcoin HTLC can be in.
+///
+/// Unlike on Ethereum, deploying and funding a Bitcoin HTLC happens in the
+/// same transaction, hence there is no `Deployed` state.
+#[derive(Debug, Clone)]
+pub enum State {
+    None,
+    Funded {
+        htlc_location: htlc_location::Bitcoin,
+        fund_transaction: transaction::Bitcoin,
+        asset: asset::Bitcoin,
+    },
+    IncorrectlyFunded {
+        htlc_location: htlc_location::Bitcoin,
+        fund_transaction: transaction::Bitcoin,
+        asset: asset::Bitcoin,
+    },
+    Redeemed {
+        htlc_location: htlc_location::Bitcoin,
+        fund_transaction: transaction::Bitcoin,
+        redeem_transaction: transaction::Bitcoin,
+        secret: Secret,
+    },
+    Refunded {
+        htlc_location: htlc_location::Bitcoin,
+        fund_transaction: transaction::Bitcoin,
+        refund_transaction: transaction::Bitcoin,
+    },
+}
+
+/// Represents the events in the hbit protocol.
+#[derive(Debug, Clone, PartialEq, strum_macros::Display)]
+pub enum Event {
+    /// The protocol was started.
+    Started,
+
+    /// The HTLC has been created on the blockchain by sending bitcoin to its
+    /// address.
+    Funded(Funded),
+
+    /// The HTLC output has been spent via the redeem path, bitcoin have been
+    /// sent to the redeemer.
+    Redeemed(Redeemed),
+
+    /// The HTLC output has been spent via the refund path, bitcoin have been
+    /// sent back to the funder.
+    Refunded(Refunded),
+
+    /// The transaction of the previous event was orphaned by a chain
+    /// reorganisation, the HTLC is back in the state before that event.
+    Reverted,
+}
+
+/// Represents the data available at said state.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum Funded {
+    Correctly {
+        transaction: transaction::Bitcoin,
+        location: htlc_location::Bitcoin,
+        asset: asset::Bitcoin,
+    },
+    Incorrectly {
+        transaction: transaction::Bitcoin,
+        location: htlc_location::Bitcoin,
+        asset: asset::Bitcoin,
+    },
+}
+
+impl Funded {
+    pub fn location(&self) -> htlc_location::Bitcoin {
+        match self {
+            Funded::Correctly { location, .. } | Funded::Incorrectly { location, .. } => *location,
+        }
+    }
+
+    pub fn transaction(&self) -> &transaction::Bitcoin {
+        match self {
+            Funded::Correctly { transaction, .. } | Funded::Incorrectly { transaction, .. } => {
+                transaction
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Redeemed {
+    pub transaction: transaction::Bitcoin,
+    pub secret: Secret,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Refunded {
+    pub transaction: transaction::Bitcoin,
+}
+
+/// Keeps the states of each swap, the most recent one being the current state.
+///
+/// Previous states are kept around so we can roll back to them if a
+/// transaction is reverted.
+#[derive(Default, Debug)]
+pub struct States {
+    states: Mutex<HashMap<LocalSwapId, Vec<State>>>,
+    changes: Changes,
+}
+
+impl States {
+    /// Subscribes to the events recorded from now on.
+    pub fn subscribe(&self) -> impl Stream<Item = Change> + Send + 'static {
+        self.changes.subscribe()
+    }
+}
+
+impl State {
+    pub fn transition_to_funded(&mut self, funded: Funded) {
+        match std::mem::replace(self, State::None) {
+            State::None => match funded {
+                Funded::Correctly {
+                    transaction,
+                    location,
+                    asset,
+                } => {
+                    *self = State::Funded {
+                        htlc_location: location,
+                        fund_transaction: transaction,
+                        asset,
+                    }
+                }
+                Funded::Incorrectly {
+                    transaction,
+                    location,
+                    asset,
+                } => {
+                    *self = State::IncorrectlyFunded {
+                        htlc_location: location,
+                        fund_transaction: transaction,
+                        asset,
+                    }
+                }
+            },
+            other => panic!("expected state None, got {:?}", other),
+        }
+    }
+
+    pub fn transition_to_redeemed(&mut self, redeemed: Redeemed) {
+        let Redeemed {
+            transaction,
+            secret,
+        } = redeemed;
+
+        match std::mem::replace(self, State::None) {
+            State::Funded {
+                htlc_location,
+                fund_transaction,
+                ..
+            }
+            | State::IncorrectlyFunded {
+                htlc_location,
+                fund_transaction,
+                ..
+            } => {
+                *self = State::Redeemed {
+                    htlc_location,
+                    fund_transaction,
+                    redeem_transaction: transaction,
+                    secret,
+                }
+            }
+            other => panic!(
+                "expected state Funded or IncorrectlyFunded, got {:?}",
+                other
+            ),
+        }
+    }
+
+    pub fn transition_to_refunded(&mut self, refunded: Refunded) {
+        let Refunded { transaction } = refunded;
+
+        match std::mem::replace(self, State::None) {
+            State::Funded {
+                htlc_location,
+                fund_transaction,
+                ..
+            }
+            | State::IncorrectlyFunded {
+                htlc_location,
+                fund_transaction,
+                ..
+            } => {
+                *self = State::Refunded {
+                    htlc_location,
+                    fund_transaction,
+                    refund_transaction: transaction,
+                }
+            }
+            other => panic!(
+                "expected state Funded or IncorrectlyFunded, got {:?}",
+                other
+            ),
+        }
+    }
+}
+
+#[async_trait::async_trait]
+impl state::Get<State> for States {
+    async fn get(&self, key: &LocalSwapId) -> anyhow::Result<Option<State>> {
+        let states = self.states.lock().await;
+        let state = states.get(key).and_then(|states| states.last()).cloned();
+
+        Ok(state)
+    }
+}
+
+#[async_trait::async_trait]
+impl state::Update<Event> for States {
+    async fn update(&self, key: &LocalSwapId, event: Event) {
+        let event_name = event.to_string();
+        let mut states = self.states.lock().await;
+        let entry = states.entry(*key);
+
+        match (event, entry) {
+            (Event::Started, Entry::Vacant(vacant)) => {
+                vacant.insert(vec![State::None]);
+            }
+            (Event::Funded(funded), Entry::Occupied(mut states)) => {
+                transition(states.get_mut(), |state| state.transition_to_funded(funded))
+            }
+            (Event::Redeemed(redeemed), Entry::Occupied(mut states)) => {
+                transition(states.get_mut(), |state| {
+                    state.transition_to_redeemed(redeemed)
+                })
+            }
+            (Event::Refunded(refunded), Entry::Occupied(mut states)) => {
+                transition(states.get_mut(), |state| {
+                    state.transition_to_refunded(refunded)
+                })
+            }
+            (Event::Reverted, Entry::Occupied(mut states)) => {
+                let states = states.get_mut();
+
+                if states.len() > 1 {
+                    states.pop();
+                } else {
+                    tracing::warn!("No previous state to revert to for {}", key);
+                    return;
+                }
+            }
+            (Event::Started, Entry::Occupied(_)) => {
+                tracing::warn!(
+                    "Received Started event for {} although state is already present",
+                    key
+                );
+                return;
+            }
+            (_, Entry::Vacant(_)) => {
+                tracing::warn!("State not found for {}", key);
+                return;
+            }
+        }
+
+        self.changes.publish(*key, event_name);
+    }
+}
+
+/// Applies the given transition to a copy of the current state and makes the
+/// result the new current state.
+fn transition(states: &mut Vec<State>, apply: impl FnOnce(&mut State)) {
+    let mut state = states.last().cloned().unwrap_or(State::None);
+    apply(&mut state);
+    states.push(state);
+}
+
+/// Creates a new instance of the hbit protocol.
+///
+/// Returns a stream of events happening during the execution.
+pub fn new<'a, C, D>(
+    connector: &'a C,
+    checkpoints: &'a D,
+    id: LocalSwapId,
+    ledger: Ledger,
+    params: Params,
+) -> impl Stream<Item = anyhow::Result<Event>> + 'a
+where
+    C: WaitForFunded + WaitForRedeemed + WaitForRefunded + WaitForFinality<transaction::Bitcoin>,
+    D: SwapWatcherCheckpoints<(), Funded>,
+{
+    Gen::new({
+        |co| async move {
+            if let Err(error) = watch_ledger(connector, checkpoints, id, ledger, params, &co).await
+            {
+                co.yield_(Err(error)).await;
+            }
+        }
+    })
+}
+
+/// Observed events and the progress of the scan are saved as a checkpoint, a
+/// restarted watcher picks up from there instead of looking at every block
+/// since the start of the swap again.
+async fn watch_ledger<C, D, R>(
+    connector: &C,
+    checkpoints: &D,
+    id: LocalSwapId,
+    ledger: Ledger,
+    params: Params,
+    co: &Co<anyhow::Result<Event>, R>,
+) -> anyhow::Result<()>
+where
+    C: WaitForFunded + WaitForRedeemed + WaitForRefunded + WaitForFinality<transaction::Bitcoin>,
+    D: SwapWatcherCheckpoints<(), Funded>,
+{
+    co.yield_(Ok(Event::Started)).await;
+
+    let mut checkpoint = checkpoints
+        .load_swap_watcher_checkpoint(&id, ledger)
+        .await?;
+    let start_of_swap = params.start_of_swap;
+
+    'funded: loop {
+        let funded = match checkpoint.funded.clone() {
+            Some(funded) => funded,
+            None => {
+                let progress = ScanProgress::new();
+                let funded = connector.wait_for_funded(
+                    params,
+                    progress.start_of_scan(start_of_swap, checkpoint.scanned.clone()),
+                );
+                let funded =
+                    scan(checkpoints, &id, ledger, &mut checkpoint, progress, funded).await?;
+
+                checkpoint.funded = Some(funded.clone());
+                checkpoints
+                    .save_swap_watcher_checkpoint(&id, ledger, checkpoint.clone())
+                    .await?;
+
+                funded
+            }
+        };
+        let location = funded.location();
+        let fund_transaction = funded.transaction().clone();
+
+        co.yield_(Ok(Event::Funded(funded))).await;
+
+        loop {
+            let progress = ScanProgress::new();
+            let redeemed = connector.wait_for_redeemed(
+                params,
+                location,
+                progress.start_of_scan(start_of_swap, checkpoint.scanned.clone()),
+            );
+            let refunded = connector.wait_for_refunded(
+                params,
+                location,
+                progress.start_of_scan(start_of_swap, checkpoint.scanned.clone()),
+            );
+            let settled =
+                future::try_select(redeemed, refunded).map_err(|either| either.factor_first().0);
+            let settled = unless_reverted(connector, &fund_transaction, settled);
+
+            let settle_transaction =
+                match scan(checkpoints, &id, ledger, &mut checkpoint, progress, settled).await? {
+                    Some(Either::Left((redeemed, _))) => {
+                        let transaction = redeemed.transaction.clone();
+                        co.yield_(Ok(Event::Redeemed(redeemed))).await;
+                        transaction
+                    }
+                    Some(Either::Right((refunded, _))) => {
+                        let transaction = refunded.transaction.clone();
+                        co.yield_(Ok(Event::Refunded(refunded))).await;
+                        transaction
+                    }
+                    None => {
+                        checkpoint.funded = None;
+                        checkpoint.scanned = None;
+                        checkpoints
+                            .save_swap_watcher_checkpoint(&id, ledger, checkpoint.clone())
+                            .await?;
+
+                        co.yield_(Ok(Event::Reverted)).await;
+                        continue 'funded;
+                    }
+                };
+
+            match connector.wait_for_finality(&settle_transaction).await? {
+                BlockStatus::Confirmed => return Ok(()),
+                BlockStatus::Reverted => {
+                    checkpoint.scanned = None;
+                    checkpoints
+                        .save_swap_watcher_checkpoint(&id, ledger, checkpoint.clone())
+                        .await?;
+
+                    co.yield_(Ok(Event::Reverted)).await
+                }
+            }
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug)]
+pub struct Params {
+    pub network: Network,
+    pub asset: asset::Bitcoin,
+    pub redeem_identity: identity::Bitcoin,
+    pub refund_identity: identity::Bitcoin,
+    pub expiry: Timestamp,
+    pub start_of_swap: StartOfSwap,
+    pub secret_hash: SecretHash,
+}
+
+impl Params {
+    pub fn compute_address(&self) -> Address {
+        BitcoinHtlc::from(*self).compute_address(self.network)
+    }
+}
+
+impl From<Params> for BitcoinHtlc {
+    fn from(params: Params) -> Self {
+        htlc(
+            params.expiry,
+            params.redeem_identity,
+            params.refund_identity,
+            params.secret_hash,
+        )
+    }
+}
+
+/// Builds the HTLC, the point in time the swap started does not affect it.
+pub fn htlc(
+    expiry: Timestamp,
+    redeem_identity: identity::Bitcoin,
+    refund_identity: identity::Bitcoin,
+    secret_hash: SecretHash,
+) -> BitcoinHtlc {
+    let refund_public_key = ::bitcoin::PublicKey::from(refund_identity);
+    let redeem_public_key = ::bitcoin::PublicKey::from(redeem_identity);
+
+    let refund_identity = hash160::Hash::hash(&refund_public_key.key.serialize());
+    let redeem_identity = hash160::Hash::hash(&redeem_public_key.key.serialize());
+
+    BitcoinHtlc::new(
+        expiry.into(),
+        refund_identity,
+        redeem_identity,
+        secret_hash.into_raw(),
+    )
+}
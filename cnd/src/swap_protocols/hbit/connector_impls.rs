@@ -0,0 +1,89 @@
+use crate::{
+    asset,
+    btsieve::{
+        bitcoin::{watch_for_created_outpoint, watch_for_spent_outpoint, BitcoinConnector, Cache},
+        StartOfScan,
+    },
+    htlc_location,
+    swap_protocols::{
+        hbit::{
+            Funded, Params, Redeemed, Refunded, WaitForFunded, WaitForRedeemed, WaitForRefunded,
+        },
+        rfc003::bitcoin::extract_secret,
+    },
+};
+use std::cmp::Ordering;
+use tracing_futures::Instrument;
+
+#[async_trait::async_trait]
+impl WaitForFunded for Cache<BitcoinConnector> {
+    async fn wait_for_funded(
+        &self,
+        params: Params,
+        start_of_scan: StartOfScan,
+    ) -> anyhow::Result<Funded> {
+        let expected_address = params.compute_address();
+
+        let (transaction, location) =
+            watch_for_created_outpoint(self, start_of_scan, expected_address.clone())
+                .instrument(tracing::info_span!("funded", address = %expected_address))
+                .await?;
+
+        let asset = asset::Bitcoin::from_sat(transaction.output[location.vout as usize].value);
+
+        let event = match params.asset.cmp(&asset) {
+            Ordering::Equal => Funded::Correctly {
+                transaction,
+                location,
+                asset,
+            },
+            _ => Funded::Incorrectly {
+                transaction,
+                location,
+                asset,
+            },
+        };
+
+        Ok(event)
+    }
+}
+
+#[async_trait::async_trait]
+impl WaitForRedeemed for Cache<BitcoinConnector> {
+    async fn wait_for_redeemed(
+        &self,
+        params: Params,
+        location: htlc_location::Bitcoin,
+        start_of_scan: StartOfScan,
+    ) -> anyhow::Result<Redeemed> {
+        let (transaction, _) =
+            watch_for_spent_outpoint(self, start_of_scan, location, params.redeem_identity)
+                .instrument(tracing::info_span!("redeemed"))
+                .await?;
+
+        let secret = extract_secret(&transaction, &params.secret_hash)
+            .expect("Redeem transaction must contain secret");
+
+        Ok(Redeemed {
+            transaction,
+            secret,
+        })
+    }
+}
+
+#[async_trait::async_trait]
+impl WaitForRefunded for Cache<BitcoinConnector> {
+    async fn wait_for_refunded(
+        &self,
+        params: Params,
+        location: htlc_location::Bitcoin,
+        start_of_scan: StartOfScan,
+    ) -> anyhow::Result<Refunded> {
+        let (transaction, _) =
+            watch_for_spent_outpoint(self, start_of_scan, location, params.refund_identity)
+                .instrument(tracing::info_span!("refunded"))
+                .await?;
+
+        Ok(Refunded { transaction })
+    }
+}
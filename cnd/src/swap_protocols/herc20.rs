@@ -1,18 +1,29 @@
 use crate::{
-    asset, htlc_location, identity,
+    asset,
+    btsieve::{
+        unless_reverted, BlockStatus, ScanProgress, StartOfScan, StartOfSwap, WaitForFinality,
+    },
+    db::{Ledger, SwapWatcherCheckpoints},
+    htlc_location, identity,
     swap_protocols::{
         rfc003::{Secret, SecretHash},
-        state, LocalSwapId,
+        scan::scan,
+        state,
+        state::{Change, Changes, Update},
+        LocalSwapId,
     },
     transaction,
 };
-use chrono::NaiveDateTime;
 use futures::{
     future::{self, Either},
-    Stream,
+    Stream, TryFutureExt, TryStreamExt,
 };
 use genawaiter::sync::{Co, Gen};
-use std::collections::{hash_map::Entry, HashMap};
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::{hash_map::Entry, HashMap},
+    sync::Arc,
+};
 use tokio::sync::Mutex;
 
 mod connector_impls;
@@ -24,7 +35,7 @@ pub use connector_impls::*;
 /// Htlc ERC20 Token atomic swap protocol.
 
 /// Data required to create a swap that involves an ERC20 token.
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, PartialEq)]
 pub struct CreatedSwap {
     pub amount: asset::Erc20Quantity,
     pub identity: identity::Ethereum,
@@ -33,15 +44,57 @@ pub struct CreatedSwap {
     pub absolute_expiry: u32,
 }
 
+/// Creates a new instance of the herc20 protocol.
+///
+/// This function delegates to the `new` function for the actual protocol
+/// implementation. Its main purpose is to annotate the protocol instance with
+/// logging information and store the events yielded by the protocol.
+pub async fn new_herc20_swap<C, D>(
+    id: LocalSwapId,
+    ledger: Ledger,
+    connector: Arc<C>,
+    checkpoints: D,
+    state_store: Arc<States>,
+    params: Params,
+) where
+    C: WaitForDeployed
+        + WaitForFunded
+        + WaitForRedeemed
+        + WaitForRefunded
+        + WaitForFinality<transaction::Ethereum>,
+    D: SwapWatcherCheckpoints<Deployed, Funded>,
+{
+    let mut events = new(connector.as_ref(), &checkpoints, id, ledger, params)
+        .inspect_ok(|event| tracing::info!("yielded event {}", event))
+        .inspect_err(|error| tracing::error!("swap failed with {:?}", error));
+
+    while let Ok(Some(event)) = events.try_next().await {
+        state_store.update(&id, event).await;
+    }
+
+    tracing::info!("swap finished");
+}
+
 /// Resolves when said event has occurred.
+///
+/// Blocks are looked at from the given start of the scan on.
 #[async_trait::async_trait]
 pub trait WaitForDeployed {
-    async fn wait_for_deployed(&self, params: Params) -> anyhow::Result<Deployed>;
+    async fn wait_for_deployed(
+        &self,
+        params: Params,
+        start_of_scan: StartOfScan,
+    ) -> anyhow::Result<Deployed>;
 }
 
 #[async_trait::async_trait]
 pub trait WaitForFunded {
-    async fn wait_for_funded(&self, params: Params, deployed: Deployed) -> anyhow::Result<Funded>;
+    async fn wait_for_funded(
+        &self,
+        params: Params,
+        deployed: Deployed,
+        start_of_scan: StartOfScan,
+    ) -> anyhow::Result<Funded>;
 }
 
 #[async_trait::async_trait]
@@ -50,6 +103,7 @@ pub trait WaitForRedeemed {
         &self,
         params: Params,
         deployed: Deployed,
+        start_of_scan: StartOfScan,
     ) -> anyhow::Result<Redeemed>;
 }
 
@@ -59,6 +113,7 @@ pub trait WaitForRefunded {
         &self,
         params: Params,
         deployed: Deployed,
+        start_of_scan: StartOfScan,
     ) -> anyhow::Result<Refunded>;
 }
 
@@ -66,10 +121,35 @@ pub trait WaitForRefunded {
 #[derive(Debug, Clone)]
 pub enum State {
     None,
-    Deployed(Deployed),
-    Funded(Funded),
-    Redeemed(Redeemed),
-    Refunded(Refunded),
+    Deployed {
+        htlc_location: htlc_location::Ethereum,
+        deploy_transaction: transaction::Ethereum,
+    },
+    Funded {
+        htlc_location: htlc_location::Ethereum,
+        deploy_transaction: transaction::Ethereum,
+        fund_transaction: transaction::Ethereum,
+        asset: asset::Erc20,
+    },
+    IncorrectlyFunded {
+        htlc_location: htlc_location::Ethereum,
+        deploy_transaction: transaction::Ethereum,
+        fund_transaction: transaction::Ethereum,
+        asset: asset::Erc20,
+    },
+    Redeemed {
+        htlc_location: htlc_location::Ethereum,
+        deploy_transaction: transaction::Ethereum,
+        fund_transaction: transaction::Ethereum,
+        redeem_transaction: transaction::Ethereum,
+        secret: Secret,
+    },
+    Refunded {
+        htlc_location: htlc_location::Ethereum,
+        deploy_transaction: transaction::Ethereum,
+        fund_transaction: transaction::Ethereum,
+        refund_transaction: transaction::Ethereum,
+    },
 }
 
 /// Represents the events in the herc20 protocol.
@@ -91,16 +171,20 @@ pub enum Event {
     /// The HTLC has been destroyed via the refund path, token has been sent
     /// back to funder.
     Refunded(Refunded),
+
+    /// The transaction of the previous event was orphaned by a chain
+    /// reorganisation, the HTLC is back in the state before that event.
+    Reverted,
 }
 
 /// Represents the data available at said state.
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct Deployed {
     pub transaction: transaction::Ethereum,
     pub location: htlc_location::Ethereum,
 }
 
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub enum Funded {
     Correctly {
         transaction: transaction::Ethereum,
@@ -112,6 +196,16 @@ pub enum Funded {
     },
 }
 
+impl Funded {
+    pub fn transaction(&self) -> &transaction::Ethereum {
+        match self {
+            Funded::Correctly { transaction, .. } | Funded::Incorrectly { transaction, .. } => {
+                transaction
+            }
+        }
+    }
+}
+
 #[derive(Debug, Clone, PartialEq)]
 pub struct Redeemed {
     pub transaction: transaction::Ethereum,
@@ -123,35 +217,129 @@ pub struct Refunded {
     pub transaction: transaction::Ethereum,
 }
 
+/// Keeps the states of each swap, the most recent one being the current state.
+///
+/// Previous states are kept around so we can roll back to them if a
+/// transaction is reverted.
 #[derive(Default, Debug)]
-pub struct States(Mutex<HashMap<LocalSwapId, State>>);
+pub struct States {
+    states: Mutex<HashMap<LocalSwapId, Vec<State>>>,
+    changes: Changes,
+}
+
+impl States {
+    /// Subscribes to the events recorded from now on.
+    pub fn subscribe(&self) -> impl Stream<Item = Change> + Send + 'static {
+        self.changes.subscribe()
+    }
+}
 
 impl State {
     pub fn transition_to_deployed(&mut self, deployed: Deployed) {
+        let Deployed {
+            transaction,
+            location,
+        } = deployed;
+
         match std::mem::replace(self, State::None) {
-            State::None => *self = State::Deployed(deployed),
+            State::None => {
+                *self = State::Deployed {
+                    htlc_location: location,
+                    deploy_transaction: transaction,
+                }
+            }
             other => panic!("expected state None, got {:?}", other),
         }
     }
 
     pub fn transition_to_funded(&mut self, funded: Funded) {
         match std::mem::replace(self, State::None) {
-            State::Deployed(_) => *self = State::Funded(funded),
+            State::Deployed {
+                htlc_location,
+                deploy_transaction,
+            } => match funded {
+                Funded::Correctly { transaction, asset } => {
+                    *self = State::Funded {
+                        htlc_location,
+                        deploy_transaction,
+                        fund_transaction: transaction,
+                        asset,
+                    }
+                }
+                Funded::Incorrectly { transaction, asset } => {
+                    *self = State::IncorrectlyFunded {
+                        htlc_location,
+                        deploy_transaction,
+                        fund_transaction: transaction,
+                        asset,
+                    }
+                }
+            },
             other => panic!("expected state Deployed, got {:?}", other),
         }
     }
 
     pub fn transition_to_redeemed(&mut self, redeemed: Redeemed) {
+        let Redeemed {
+            transaction,
+            secret,
+        } = redeemed;
+
         match std::mem::replace(self, State::None) {
-            State::Funded(_) => *self = State::Redeemed(redeemed),
-            other => panic!("expected state Funded, got {:?}", other),
+            State::Funded {
+                htlc_location,
+                deploy_transaction,
+                fund_transaction,
+                ..
+            }
+            | State::IncorrectlyFunded {
+                htlc_location,
+                deploy_transaction,
+                fund_transaction,
+                ..
+            } => {
+                *self = State::Redeemed {
+                    htlc_location,
+                    deploy_transaction,
+                    fund_transaction,
+                    redeem_transaction: transaction,
+                    secret,
+                }
+            }
+            other => panic!(
+                "expected state Funded or IncorrectlyFunded, got {:?}",
+                other
+            ),
         }
     }
 
     pub fn transition_to_refunded(&mut self, refunded: Refunded) {
+        let Refunded { transaction } = refunded;
+
         match std::mem::replace(self, State::None) {
-            State::Funded(_) => *self = State::Refunded(refunded),
-            other => panic!("expected state Funded, got {:?}", other),
+            State::Funded {
+                htlc_location,
+                deploy_transaction,
+                fund_transaction,
+                ..
+            }
+            | State::IncorrectlyFunded {
+                htlc_location,
+                deploy_transaction,
+                fund_transaction,
+                ..
+            } => {
+                *self = State::Refunded {
+                    htlc_location,
+                    deploy_transaction,
+                    fund_transaction,
+                    refund_transaction: transaction,
+                }
+            }
+            other => panic!(
+                "expected state Funded or IncorrectlyFunded, got {:?}",
+                other
+            ),
         }
     }
 }
@@ -159,8 +347,8 @@ impl State {
 #[async_trait::async_trait]
 impl state::Get<State> for States {
     async fn get(&self, key: &LocalSwapId) -> anyhow::Result<Option<State>> {
-        let states = self.0.lock().await;
-        let state = states.get(key).cloned();
+        let states = self.states.lock().await;
+        let state = states.get(key).and_then(|states| states.last()).cloned();
 
         Ok(state)
     }
@@ -169,93 +357,244 @@ impl state::Get<State> for States {
 #[async_trait::async_trait]
 impl state::Update<Event> for States {
     async fn update(&self, key: &LocalSwapId, event: Event) {
-        let mut states = self.0.lock().await;
+        let event_name = event.to_string();
+        let mut states = self.states.lock().await;
         let entry = states.entry(*key);
 
         match (event, entry) {
             (Event::Started, Entry::Vacant(vacant)) => {
-                vacant.insert(State::None);
+                vacant.insert(vec![State::None]);
             }
-            (Event::Deployed(deployed), Entry::Occupied(mut state)) => {
-                state.get_mut().transition_to_deployed(deployed)
+            (Event::Deployed(deployed), Entry::Occupied(mut states)) => {
+                transition(states.get_mut(), |state| {
+                    state.transition_to_deployed(deployed)
+                })
             }
-            (Event::Funded(funded), Entry::Occupied(mut state)) => {
-                state.get_mut().transition_to_funded(funded)
+            (Event::Funded(funded), Entry::Occupied(mut states)) => {
+                transition(states.get_mut(), |state| state.transition_to_funded(funded))
             }
-            (Event::Redeemed(redeemed), Entry::Occupied(mut state)) => {
-                state.get_mut().transition_to_redeemed(redeemed)
+            (Event::Redeemed(redeemed), Entry::Occupied(mut states)) => {
+                transition(states.get_mut(), |state| {
+                    state.transition_to_redeemed(redeemed)
+                })
             }
-            (Event::Refunded(refunded), Entry::Occupied(mut state)) => {
-                state.get_mut().transition_to_refunded(refunded)
+            (Event::Refunded(refunded), Entry::Occupied(mut states)) => {
+                transition(states.get_mut(), |state| {
+                    state.transition_to_refunded(refunded)
+                })
+            }
+            (Event::Reverted, Entry::Occupied(mut states)) => {
+                let states = states.get_mut();
+
+                if states.len() > 1 {
+                    states.pop();
+                } else {
+                    tracing::warn!("No previous state to revert to for {}", key);
+                    return;
+                }
             }
             (Event::Started, Entry::Occupied(_)) => {
                 tracing::warn!(
                     "Received Started event for {} although state is already present",
                     key
                 );
+                return;
             }
             (_, Entry::Vacant(_)) => {
                 tracing::warn!("State not found for {}", key);
+                return;
             }
         }
+
+        self.changes.publish(*key, event_name);
     }
 }
 
+/// Applies the given transition to a copy of the current state and makes the
+/// result the new current state.
+fn transition(states: &mut Vec<State>, apply: impl FnOnce(&mut State)) {
+    let mut state = states.last().cloned().unwrap_or(State::None);
+    apply(&mut state);
+    states.push(state);
+}
+
 /// Creates a new instance of the herc20 protocol.
 ///
 /// Returns a stream of events happening during the execution.
-pub fn new<'a, C>(
+pub fn new<'a, C, D>(
     connector: &'a C,
+    checkpoints: &'a D,
+    id: LocalSwapId,
+    ledger: Ledger,
     params: Params,
 ) -> impl Stream<Item = anyhow::Result<Event>> + 'a
 where
-    C: WaitForDeployed + WaitForFunded + WaitForRedeemed + WaitForRefunded,
+    C: WaitForDeployed
+        + WaitForFunded
+        + WaitForRedeemed
+        + WaitForRefunded
+        + WaitForFinality<transaction::Ethereum>,
+    D: SwapWatcherCheckpoints<Deployed, Funded>,
 {
     Gen::new({
         |co| async move {
-            if let Err(error) = watch_ledger(connector, params, &co).await {
+            if let Err(error) = watch_ledger(connector, checkpoints, id, ledger, params, &co).await
+            {
                 co.yield_(Err(error)).await;
             }
         }
     })
 }
 
-async fn watch_ledger<C, R>(
+/// Observed events and the progress of the scan are saved as a checkpoint, a
+/// restarted watcher picks up from there instead of looking at every block
+/// since the start of the swap again.
+async fn watch_ledger<C, D, R>(
     connector: &C,
+    checkpoints: &D,
+    id: LocalSwapId,
+    ledger: Ledger,
     params: Params,
     co: &Co<anyhow::Result<Event>, R>,
 ) -> anyhow::Result<()>
 where
-    C: WaitForDeployed + WaitForFunded + WaitForRedeemed + WaitForRefunded,
+    C: WaitForDeployed
+        + WaitForFunded
+        + WaitForRedeemed
+        + WaitForRefunded
+        + WaitForFinality<transaction::Ethereum>,
+    D: SwapWatcherCheckpoints<Deployed, Funded>,
 {
     co.yield_(Ok(Event::Started)).await;
 
-    let deployed = connector.wait_for_deployed(params.clone()).await?;
-
-    co.yield_(Ok(Event::Deployed(deployed.clone()))).await;
-
-    let funded = connector
-        .wait_for_funded(params.clone(), deployed.clone())
+    let mut checkpoint = checkpoints
+        .load_swap_watcher_checkpoint(&id, ledger)
         .await?;
-    co.yield_(Ok(Event::Funded(funded))).await;
-
-    let redeemed = connector.wait_for_redeemed(params.clone(), deployed.clone());
-    let refunded = connector.wait_for_refunded(params, deployed);
-
-    match future::try_select(redeemed, refunded).await {
-        Ok(Either::Left((redeemed, _))) => {
-            co.yield_(Ok(Event::Redeemed(redeemed))).await;
-        }
-        Ok(Either::Right((refunded, _))) => {
-            co.yield_(Ok(Event::Refunded(refunded))).await;
-        }
-        Err(either) => {
-            let (error, _other_future) = either.factor_first();
-            return Err(error);
+    let start_of_swap = params.start_of_swap;
+
+    'deployed: loop {
+        let deployed = match checkpoint.deployed.clone() {
+            Some(deployed) => deployed,
+            None => {
+                let progress = ScanProgress::new();
+                let deployed = connector.wait_for_deployed(
+                    params.clone(),
+                    progress.start_of_scan(start_of_swap, checkpoint.scanned.clone()),
+                );
+                let deployed = scan(
+                    checkpoints,
+                    &id,
+                    ledger,
+                    &mut checkpoint,
+                    progress,
+                    deployed,
+                )
+                .await?;
+
+                checkpoint.deployed = Some(deployed.clone());
+                checkpoints
+                    .save_swap_watcher_checkpoint(&id, ledger, checkpoint.clone())
+                    .await?;
+
+                deployed
+            }
+        };
+        co.yield_(Ok(Event::Deployed(deployed.clone()))).await;
+
+        'funded: loop {
+            let funded = match checkpoint.funded.clone() {
+                Some(funded) => funded,
+                None => {
+                    let progress = ScanProgress::new();
+                    let funded = connector.wait_for_funded(
+                        params.clone(),
+                        deployed.clone(),
+                        progress.start_of_scan(start_of_swap, checkpoint.scanned.clone()),
+                    );
+                    let funded = unless_reverted(connector, &deployed.transaction, funded);
+                    let funded =
+                        match scan(checkpoints, &id, ledger, &mut checkpoint, progress, funded)
+                            .await?
+                        {
+                            Some(funded) => funded,
+                            None => {
+                                checkpoint.deployed = None;
+                                checkpoint.scanned = None;
+                                checkpoints
+                                    .save_swap_watcher_checkpoint(&id, ledger, checkpoint.clone())
+                                    .await?;
+
+                                co.yield_(Ok(Event::Reverted)).await;
+                                continue 'deployed;
+                            }
+                        };
+
+                    checkpoint.funded = Some(funded.clone());
+                    checkpoints
+                        .save_swap_watcher_checkpoint(&id, ledger, checkpoint.clone())
+                        .await?;
+
+                    funded
+                }
+            };
+            let fund_transaction = funded.transaction().clone();
+            co.yield_(Ok(Event::Funded(funded))).await;
+
+            loop {
+                let progress = ScanProgress::new();
+                let redeemed = connector.wait_for_redeemed(
+                    params.clone(),
+                    deployed.clone(),
+                    progress.start_of_scan(start_of_swap, checkpoint.scanned.clone()),
+                );
+                let refunded = connector.wait_for_refunded(
+                    params.clone(),
+                    deployed.clone(),
+                    progress.start_of_scan(start_of_swap, checkpoint.scanned.clone()),
+                );
+                let settled = future::try_select(redeemed, refunded)
+                    .map_err(|either| either.factor_first().0);
+                let settled = unless_reverted(connector, &fund_transaction, settled);
+
+                let settle_transaction =
+                    match scan(checkpoints, &id, ledger, &mut checkpoint, progress, settled).await?
+                    {
+                        Some(Either::Left((redeemed, _))) => {
+                            let transaction = redeemed.transaction.clone();
+                            co.yield_(Ok(Event::Redeemed(redeemed))).await;
+                            transaction
+                        }
+                        Some(Either::Right((refunded, _))) => {
+                            let transaction = refunded.transaction.clone();
+                            co.yield_(Ok(Event::Refunded(refunded))).await;
+                            transaction
+                        }
+                        None => {
+                            checkpoint.funded = None;
+                            checkpoint.scanned = None;
+                            checkpoints
+                                .save_swap_watcher_checkpoint(&id, ledger, checkpoint.clone())
+                                .await?;
+
+                            co.yield_(Ok(Event::Reverted)).await;
+                            continue 'funded;
+                        }
+                    };
+
+                match connector.wait_for_finality(&settle_transaction).await? {
+                    BlockStatus::Confirmed => return Ok(()),
+                    BlockStatus::Reverted => {
+                        checkpoint.scanned = None;
+                        checkpoints
+                            .save_swap_watcher_checkpoint(&id, ledger, checkpoint.clone())
+                            .await?;
+
+                        co.yield_(Ok(Event::Reverted)).await
+                    }
+                }
+            }
         }
     }
-
-    Ok(())
 }
 
 #[derive(Clone, Debug)]
@@ -264,7 +603,7 @@ pub struct Params {
     pub redeem_identity: identity::Ethereum,
     pub refund_identity: identity::Ethereum,
     pub expiry: Timestamp,
-    pub start_of_swap: NaiveDateTime,
+    pub start_of_swap: StartOfSwap,
     pub secret_hash: SecretHash,
 }
 
@@ -1,7 +1,10 @@
 use crate::{
     asset::{ethereum::FromWei, Erc20, Erc20Quantity},
-    btsieve::ethereum::{
-        watch_for_contract_creation, watch_for_event, Cache, Event, Topic, Web3Connector,
+    btsieve::{
+        ethereum::{
+            watch_for_contract_creation, watch_for_event, Cache, Event, Topic, Web3Connector,
+        },
+        StartOfScan,
     },
     ethereum::{Hash, U256},
     swap_protocols::{
@@ -23,11 +26,15 @@ lazy_static::lazy_static! {
 
 #[async_trait::async_trait]
 impl WaitForDeployed for Cache<Web3Connector> {
-    async fn wait_for_deployed(&self, params: Params) -> anyhow::Result<Deployed> {
+    async fn wait_for_deployed(
+        &self,
+        params: Params,
+        start_of_scan: StartOfScan,
+    ) -> anyhow::Result<Deployed> {
         let expected_bytecode = params.clone().bytecode();
 
         let (transaction, location) =
-            watch_for_contract_creation(self, params.start_of_swap, &expected_bytecode)
+            watch_for_contract_creation(self, start_of_scan, &expected_bytecode)
                 .instrument(tracing::trace_span!(
                     "deployed",
                     expected_bytecode = %hex::encode(&expected_bytecode.0)
@@ -43,7 +50,12 @@ impl WaitForDeployed for Cache<Web3Connector> {
 
 #[async_trait::async_trait]
 impl WaitForFunded for Cache<Web3Connector> {
-    async fn wait_for_funded(&self, params: Params, deployed: Deployed) -> anyhow::Result<Funded> {
+    async fn wait_for_funded(
+        &self,
+        params: Params,
+        deployed: Deployed,
+        start_of_scan: StartOfScan,
+    ) -> anyhow::Result<Funded> {
         let event = Event {
             address: params.asset.token_contract,
             topics: vec![
@@ -53,7 +65,7 @@ impl WaitForFunded for Cache<Web3Connector> {
             ],
         };
 
-        let (transaction, log) = watch_for_event(self, params.start_of_swap, event)
+        let (transaction, log) = watch_for_event(self, start_of_scan, event)
             .instrument(tracing::trace_span!("funded"))
             .await?;
 
@@ -77,13 +89,14 @@ impl WaitForRedeemed for Cache<Web3Connector> {
         &self,
         params: Params,
         deployed: Deployed,
+        start_of_scan: StartOfScan,
     ) -> anyhow::Result<Redeemed> {
         let event = Event {
             address: deployed.location,
             topics: vec![Some(Topic(*REDEEM_LOG_MSG))],
         };
 
-        let (transaction, log) = watch_for_event(self, params.start_of_swap, event)
+        let (transaction, log) = watch_for_event(self, start_of_scan, event)
             .instrument(tracing::info_span!("redeemed"))
             .await?;
 
@@ -104,13 +117,14 @@ impl WaitForRefunded for Cache<Web3Connector> {
         &self,
         params: Params,
         deployed: Deployed,
+        start_of_scan: StartOfScan,
     ) -> anyhow::Result<Refunded> {
         let event = Event {
             address: deployed.location,
             topics: vec![Some(Topic(*REFUND_LOG_MSG))],
         };
 
-        let (transaction, _) = watch_for_event(self, params.start_of_swap, event)
+        let (transaction, _) = watch_for_event(self, start_of_scan, event)
             .instrument(tracing::info_span!("refunded"))
             .await?;
 
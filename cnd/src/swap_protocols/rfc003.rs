@@ -30,7 +30,7 @@ use ::bitcoin::secp256k1::SecretKey;
 /// Swap request response as received from peer node acting as Bob.
 pub type Response<AI, BI> = Result<Accept<AI, BI>, Decline>;
 
-#[derive(Clone, Debug, PartialEq)]
+#[derive(Clone, Debug, PartialEq, strum_macros::Display)]
 pub enum SwapCommunication<AL, BL, AA, BA, AI, BI> {
     Proposed {
         request: Request<AL, BL, AA, BA, AI, BI>,
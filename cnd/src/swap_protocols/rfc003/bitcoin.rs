@@ -11,7 +11,7 @@ use ::bitcoin::{
 };
 use blockchain_contracts::bitcoin::rfc003::bitcoin_htlc::BitcoinHtlc;
 
-pub use self::htlc_events::*;
+pub use self::{extract_secret::extract_secret, htlc_events::*};
 
 impl<B> From<HtlcParams<B, asset::Bitcoin, identity::Bitcoin>> for BitcoinHtlc
 where
@@ -1,7 +1,11 @@
 use crate::{
     asset,
-    btsieve::bitcoin::{
-        watch_for_created_outpoint, watch_for_spent_outpoint, BitcoindConnector, Cache,
+    btsieve::{
+        bitcoin::{
+            watch_for_created_outpoint, watch_for_spent_outpoint,
+            watch_for_spent_outpoint_in_mempool, BitcoinConnector, Cache,
+        },
+        StartOfScan,
     },
     htlc_location, identity,
     swap_protocols::{
@@ -17,14 +21,13 @@ use crate::{
     },
     transaction,
 };
-use chrono::NaiveDateTime;
 use std::cmp::Ordering;
 use tracing_futures::Instrument;
 
 #[async_trait::async_trait]
 impl<B>
     HtlcFunded<B, asset::Bitcoin, htlc_location::Bitcoin, identity::Bitcoin, transaction::Bitcoin>
-    for Cache<BitcoindConnector>
+    for Cache<BitcoinConnector>
 where
     B: bitcoin::Bitcoin + bitcoin::Network,
 {
@@ -32,7 +35,7 @@ where
         &self,
         htlc_params: &HtlcParams<B, asset::Bitcoin, identity::Bitcoin>,
         htlc_deployment: &Deployed<htlc_location::Bitcoin, transaction::Bitcoin>,
-        _start_of_swap: NaiveDateTime,
+        _start_of_scan: StartOfScan,
     ) -> anyhow::Result<Funded<asset::Bitcoin, transaction::Bitcoin>> {
         let expected_asset = htlc_params.asset;
 
@@ -58,17 +61,17 @@ where
 #[async_trait::async_trait]
 impl<B>
     HtlcDeployed<B, asset::Bitcoin, htlc_location::Bitcoin, identity::Bitcoin, transaction::Bitcoin>
-    for Cache<BitcoindConnector>
+    for Cache<BitcoinConnector>
 where
     B: bitcoin::Bitcoin + bitcoin::Network,
 {
     async fn htlc_deployed(
         &self,
         htlc_params: &HtlcParams<B, asset::Bitcoin, identity::Bitcoin>,
-        start_of_swap: NaiveDateTime,
+        start_of_scan: StartOfScan,
     ) -> anyhow::Result<Deployed<htlc_location::Bitcoin, transaction::Bitcoin>> {
         let (transaction, location) =
-            watch_for_created_outpoint(self, start_of_swap, htlc_params.compute_address())
+            watch_for_created_outpoint(self, start_of_scan, htlc_params.compute_address())
                 .instrument(tracing::info_span!("htlc_deployed"))
                 .await?;
 
@@ -82,7 +85,7 @@ where
 #[async_trait::async_trait]
 impl<B>
     HtlcRedeemed<B, asset::Bitcoin, htlc_location::Bitcoin, identity::Bitcoin, transaction::Bitcoin>
-    for Cache<BitcoindConnector>
+    for Cache<BitcoinConnector>
 where
     B: bitcoin::Bitcoin + bitcoin::Network,
 {
@@ -90,11 +93,11 @@ where
         &self,
         htlc_params: &HtlcParams<B, asset::Bitcoin, identity::Bitcoin>,
         htlc_deployment: &Deployed<htlc_location::Bitcoin, transaction::Bitcoin>,
-        start_of_swap: NaiveDateTime,
+        start_of_scan: StartOfScan,
     ) -> anyhow::Result<Redeemed<transaction::Bitcoin>> {
         let (transaction, _) = watch_for_spent_outpoint(
             self,
-            start_of_swap,
+            start_of_scan,
             htlc_deployment.location,
             htlc_params.redeem_identity,
         )
@@ -109,12 +112,34 @@ where
             secret,
         })
     }
+
+    async fn htlc_redeem_pending(
+        &self,
+        htlc_params: &HtlcParams<B, asset::Bitcoin, identity::Bitcoin>,
+        htlc_deployment: &Deployed<htlc_location::Bitcoin, transaction::Bitcoin>,
+    ) -> anyhow::Result<Redeemed<transaction::Bitcoin>> {
+        let (transaction, _) = watch_for_spent_outpoint_in_mempool(
+            self,
+            htlc_deployment.location,
+            htlc_params.redeem_identity,
+        )
+        .instrument(tracing::info_span!("htlc_redeem_pending"))
+        .await?;
+
+        let secret = extract_secret(&transaction, &htlc_params.secret_hash)
+            .expect("Redeem transaction must contain secret");
+
+        Ok(Redeemed {
+            transaction,
+            secret,
+        })
+    }
 }
 
 #[async_trait::async_trait]
 impl<B>
     HtlcRefunded<B, asset::Bitcoin, htlc_location::Bitcoin, identity::Bitcoin, transaction::Bitcoin>
-    for Cache<BitcoindConnector>
+    for Cache<BitcoinConnector>
 where
     B: bitcoin::Bitcoin + bitcoin::Network,
 {
@@ -122,11 +147,11 @@ where
         &self,
         htlc_params: &HtlcParams<B, asset::Bitcoin, identity::Bitcoin>,
         htlc_deployment: &Deployed<htlc_location::Bitcoin, transaction::Bitcoin>,
-        start_of_swap: NaiveDateTime,
+        start_of_scan: StartOfScan,
     ) -> anyhow::Result<Refunded<transaction::Bitcoin>> {
         let (transaction, _) = watch_for_spent_outpoint(
             self,
-            start_of_swap,
+            start_of_scan,
             htlc_deployment.location,
             htlc_params.refund_identity,
         )
@@ -69,7 +69,8 @@ where
         use self::LedgerState::*;
 
         let mut actions = match (alpha_state, beta_state) {
-            (Funded { htlc_location, .. }, Redeemed { secret, .. }) => {
+            (Funded { htlc_location, .. }, RedeemPending { secret, .. })
+            | (Funded { htlc_location, .. }, Redeemed { secret, .. }) => {
                 vec![Action::Redeem(<(AL, AA)>::make_redeem_action(
                     HtlcParams::new_alpha_params(request, response),
                     htlc_location.clone(),
@@ -158,7 +159,8 @@ where
 
         use self::LedgerState::*;
         let mut actions = match (alpha_state, beta_state) {
-            (Funded { htlc_location, .. }, Redeemed { secret, .. }) => vec![Action::Redeem(
+            (Funded { htlc_location, .. }, RedeemPending { secret, .. })
+            | (Funded { htlc_location, .. }, Redeemed { secret, .. }) => vec![Action::Redeem(
                 erc20::redeem_action(*htlc_location, *secret, request.alpha_ledger.chain_id),
             )],
             (Funded { .. }, NotDeployed) => vec![Action::Fund(<(BL, BA)>::make_fund_action(
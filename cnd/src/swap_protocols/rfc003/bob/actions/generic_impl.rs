@@ -60,7 +60,8 @@ where
 
         use self::LedgerState::*;
         let mut actions = match (alpha_state, beta_state) {
-            (Funded { htlc_location, .. }, Redeemed { secret, .. }) => {
+            (Funded { htlc_location, .. }, RedeemPending { secret, .. })
+            | (Funded { htlc_location, .. }, Redeemed { secret, .. }) => {
                 vec![Action::Redeem(<(AL, AA)>::make_redeem_action(
                     HtlcParams::new_alpha_params(request, response),
                     htlc_location.clone(),
@@ -1,4 +1,6 @@
 use crate::{
+    btsieve::{unless_reverted, BlockStatus, ScanProgress, StartOfSwap, WaitForFinality},
+    db::{Ledger, WatcherCheckpoint, WatcherCheckpoints},
     swap_protocols::{
         rfc003::{
             self,
@@ -13,13 +15,24 @@ use crate::{
     timestamp::Timestamp,
 };
 use chrono::NaiveDateTime;
-use futures::future::{self, Either};
+use futures::{
+    future::{self, Either},
+    Future, TryFutureExt,
+};
 use genawaiter::{
     sync::{Co, Gen},
     GeneratorState,
 };
 use std::sync::Arc;
 
+/// Abstracts over the ability of telling from which height on a ledger has to
+/// be watched for a swap that is accepted now.
+#[async_trait::async_trait]
+pub trait StartHeight<L>: Send + Sync + 'static {
+    /// Returns `None` if the height of the latest block cannot be told.
+    async fn start_height(&self) -> anyhow::Result<Option<u32>>;
+}
+
 /// Returns a future that tracks the swap negotiated from the given request and
 /// accept response on a ledger.
 ///
@@ -35,6 +48,7 @@ pub async fn create_watcher<D, S, L, A, H, I, T>(
     dependencies: D,
     ledger_states: Arc<S>,
     id: SwapId,
+    ledger: Ledger,
     htlc_params: HtlcParams<L, A, I>,
     accepted_at: NaiveDateTime,
 ) where
@@ -42,7 +56,9 @@ pub async fn create_watcher<D, S, L, A, H, I, T>(
         + HtlcFunded<L, A, H, I, T>
         + HtlcDeployed<L, A, H, I, T>
         + HtlcRedeemed<L, A, H, I, T>
-        + HtlcRefunded<L, A, H, I, T>,
+        + HtlcRefunded<L, A, H, I, T>
+        + WaitForFinality<T>
+        + WatcherCheckpoints<A, H, T>,
     S: state::Update<SwapEvent<A, H, T>> + state::Insert<LedgerState<A, H, T>>,
     L: Clone,
     A: Ord + Clone,
@@ -57,7 +73,15 @@ pub async fn create_watcher<D, S, L, A, H, I, T>(
     // construct a generator that watches alpha and beta ledger concurrently
     let mut generator = Gen::new({
         |co| async {
-            watch_ledger::<D, L, A, H, I, T>(&dependencies, co, htlc_params, accepted_at).await
+            watch_ledger::<D, L, A, H, I, T>(
+                &dependencies,
+                co,
+                id,
+                ledger,
+                htlc_params,
+                accepted_at,
+            )
+            .await
         }
     });
 
@@ -87,9 +111,15 @@ pub async fn create_watcher<D, S, L, A, H, I, T>(
 /// Returns a future that waits for events to happen on a ledger.
 ///
 /// Each event is yielded through the controller handle (co) of the coroutine.
+///
+/// Observed events and the progress of the scan are saved as a checkpoint, a
+/// restarted watcher picks up from there instead of looking at every block
+/// since the start of the swap again.
 async fn watch_ledger<D, L, A, H, I, T>(
     dependencies: &D,
     co: Co<SwapEvent<A, H, T>>,
+    id: SwapId,
+    ledger: Ledger,
     htlc_params: HtlcParams<L, A, I>,
     start_of_swap: NaiveDateTime,
 ) -> anyhow::Result<()>
@@ -97,40 +127,219 @@ where
     D: HtlcFunded<L, A, H, I, T>
         + HtlcDeployed<L, A, H, I, T>
         + HtlcRedeemed<L, A, H, I, T>
-        + HtlcRefunded<L, A, H, I, T>,
+        + HtlcRefunded<L, A, H, I, T>
+        + WaitForFinality<T>
+        + WatcherCheckpoints<A, H, T>,
+    A: Clone,
+    H: Clone,
     Deployed<H, T>: Clone,
     Redeemed<T>: Clone,
     Refunded<T>: Clone,
+    T: Clone,
 {
-    let deployed = dependencies
-        .htlc_deployed(&htlc_params, start_of_swap)
-        .await?;
-    co.yield_(SwapEvent::Deployed(deployed.clone())).await;
+    let mut checkpoint = dependencies.load_watcher_checkpoint(&id, ledger).await?;
+    let start_of_swap = StartOfSwap {
+        timestamp: start_of_swap,
+        height: checkpoint.start_height,
+    };
 
-    let funded = dependencies
-        .htlc_funded(&htlc_params, &deployed, start_of_swap)
-        .await?;
-    co.yield_(SwapEvent::Funded(funded)).await;
+    'deployed: loop {
+        let deployed = match checkpoint.deployed.clone() {
+            Some(deployed) => deployed,
+            None => {
+                let progress = ScanProgress::new();
+                let deployed = dependencies.htlc_deployed(
+                    &htlc_params,
+                    progress.start_of_scan(start_of_swap, checkpoint.scanned.clone()),
+                );
+                let deployed = scan(
+                    dependencies,
+                    &id,
+                    ledger,
+                    &mut checkpoint,
+                    progress,
+                    deployed,
+                )
+                .await?;
 
-    let redeemed = dependencies.htlc_redeemed(&htlc_params, &deployed, start_of_swap);
+                checkpoint.deployed = Some(deployed.clone());
+                dependencies
+                    .save_watcher_checkpoint(&id, ledger, checkpoint.clone())
+                    .await?;
 
-    let refunded = dependencies.htlc_refunded(&htlc_params, &deployed, start_of_swap);
+                deployed
+            }
+        };
+        co.yield_(SwapEvent::Deployed(deployed.clone())).await;
 
-    match future::try_select(redeemed, refunded).await {
-        Ok(Either::Left((redeemed, _))) => {
-            co.yield_(SwapEvent::Redeemed(redeemed.clone())).await;
-        }
-        Ok(Either::Right((refunded, _))) => {
-            co.yield_(SwapEvent::Refunded(refunded.clone())).await;
-        }
-        Err(either) => {
-            let (error, _other_future) = either.factor_first();
+        'funded: loop {
+            let funded = match checkpoint.funded.clone() {
+                Some(funded) => funded,
+                None => {
+                    let progress = ScanProgress::new();
+                    let funded = dependencies.htlc_funded(
+                        &htlc_params,
+                        &deployed,
+                        progress.start_of_scan(start_of_swap, checkpoint.scanned.clone()),
+                    );
+                    let funded = unless_reverted(dependencies, &deployed.transaction, funded);
+                    let funded =
+                        match scan(dependencies, &id, ledger, &mut checkpoint, progress, funded)
+                            .await?
+                        {
+                            Some(funded) => funded,
+                            None => {
+                                checkpoint.deployed = None;
+                                checkpoint.scanned = None;
+                                dependencies
+                                    .save_watcher_checkpoint(&id, ledger, checkpoint.clone())
+                                    .await?;
+
+                                co.yield_(SwapEvent::Reverted).await;
+                                continue 'deployed;
+                            }
+                        };
+
+                    checkpoint.funded = Some(funded.clone());
+                    dependencies
+                        .save_watcher_checkpoint(&id, ledger, checkpoint.clone())
+                        .await?;
+
+                    funded
+                }
+            };
+            let fund_transaction = funded.transaction().clone();
+            co.yield_(SwapEvent::Funded(funded)).await;
+
+            let mut redeem_pending_seen = false;
+
+            loop {
+                let progress = ScanProgress::new();
+                let redeemed = dependencies.htlc_redeemed(
+                    &htlc_params,
+                    &deployed,
+                    progress.start_of_scan(start_of_swap, checkpoint.scanned.clone()),
+                );
+                let refunded = dependencies.htlc_refunded(
+                    &htlc_params,
+                    &deployed,
+                    progress.start_of_scan(start_of_swap, checkpoint.scanned.clone()),
+                );
+                let settled = future::try_select(redeemed, refunded)
+                    .map_err(|either| either.factor_first().0);
+
+                let settled = if redeem_pending_seen {
+                    let settled = unless_reverted(dependencies, &fund_transaction, settled);
+                    scan(
+                        dependencies,
+                        &id,
+                        ledger,
+                        &mut checkpoint,
+                        progress,
+                        settled,
+                    )
+                    .await?
+                } else {
+                    let redeem_pending = dependencies.htlc_redeem_pending(&htlc_params, &deployed);
+                    let settled_or_pending = future::try_select(settled, redeem_pending)
+                        .map_err(|either| either.factor_first().0);
+                    let settled_or_pending =
+                        unless_reverted(dependencies, &fund_transaction, settled_or_pending);
 
-            return Err(error);
+                    match scan(
+                        dependencies,
+                        &id,
+                        ledger,
+                        &mut checkpoint,
+                        progress,
+                        settled_or_pending,
+                    )
+                    .await?
+                    {
+                        Some(Either::Left((settled, _))) => Some(settled),
+                        Some(Either::Right((redeemed, _))) => {
+                            co.yield_(SwapEvent::RedeemPending(redeemed)).await;
+                            redeem_pending_seen = true;
+                            continue;
+                        }
+                        None => None,
+                    }
+                };
+
+                let settle_transaction = match settled {
+                    Some(Either::Left((redeemed, _))) => {
+                        co.yield_(SwapEvent::Redeemed(redeemed.clone())).await;
+                        redeemed.transaction
+                    }
+                    Some(Either::Right((refunded, _))) => {
+                        co.yield_(SwapEvent::Refunded(refunded.clone())).await;
+                        refunded.transaction
+                    }
+                    None => {
+                        checkpoint.funded = None;
+                        checkpoint.scanned = None;
+                        dependencies
+                            .save_watcher_checkpoint(&id, ledger, checkpoint.clone())
+                            .await?;
+
+                        // Each reverted event only rolls back a single state.
+                        if redeem_pending_seen {
+                            co.yield_(SwapEvent::Reverted).await;
+                        }
+                        co.yield_(SwapEvent::Reverted).await;
+                        continue 'funded;
+                    }
+                };
+
+                match dependencies.wait_for_finality(&settle_transaction).await? {
+                    BlockStatus::Confirmed => return Ok(()),
+                    BlockStatus::Reverted => {
+                        checkpoint.scanned = None;
+                        dependencies
+                            .save_watcher_checkpoint(&id, ledger, checkpoint.clone())
+                            .await?;
+
+                        co.yield_(SwapEvent::Reverted).await
+                    }
+                }
+            }
         }
     }
+}
+
+/// Waits for the given watchers to find their event, saving the checkpoint
+/// every time all of them scanned further.
+async fn scan<D, A, H, T, F, R>(
+    dependencies: &D,
+    id: &SwapId,
+    ledger: Ledger,
+    checkpoint: &mut WatcherCheckpoint<A, H, T>,
+    mut progress: ScanProgress,
+    watchers: F,
+) -> anyhow::Result<R>
+where
+    D: WatcherCheckpoints<A, H, T>,
+    F: Future<Output = anyhow::Result<R>>,
+    WatcherCheckpoint<A, H, T>: Clone,
+{
+    futures::pin_mut!(watchers);
+
+    loop {
+        let scanned = progress.next_checkpoint();
+        futures::pin_mut!(scanned);
 
-    Ok(())
+        match future::select(watchers.as_mut(), scanned).await {
+            Either::Left((result, _)) => return result,
+            Either::Right((scanned, _)) => {
+                tracing::trace!("scanned up to block {}", scanned.block_hash);
+
+                checkpoint.scanned = Some(scanned);
+                dependencies
+                    .save_watcher_checkpoint(id, ledger, checkpoint.clone())
+                    .await?;
+            }
+        }
+    }
 }
 
 #[derive(Clone, Copy, Debug)]
@@ -253,8 +462,14 @@ where
 pub enum SwapEvent<A, H, T> {
     Deployed(Deployed<H, T>),
     Funded(Funded<A, T>),
+    /// A redeem transaction was seen in the mempool, its secret can be used
+    /// before it is included in a block.
+    RedeemPending(Redeemed<T>),
     Redeemed(Redeemed<T>),
     Refunded(Refunded<T>),
+    /// The transaction of the previous event was orphaned by a chain
+    /// reorganisation, the HTLC is back in the state before that event.
+    Reverted,
 }
 
 #[cfg(test)]
@@ -1,8 +1,11 @@
 use crate::{
     asset,
     asset::{ethereum::FromWei, Erc20, Erc20Quantity, Ether},
-    btsieve::ethereum::{
-        watch_for_contract_creation, watch_for_event, Cache, Event, Topic, Web3Connector,
+    btsieve::{
+        ethereum::{
+            watch_for_contract_creation, watch_for_event, Cache, Event, Topic, Web3Connector,
+        },
+        StartOfScan,
     },
     ethereum::{Hash, U256},
     htlc_location, identity,
@@ -19,7 +22,7 @@ use crate::{
     },
     transaction,
 };
-use chrono::NaiveDateTime;
+use futures::future;
 use std::cmp::Ordering;
 use tracing_futures::Instrument;
 
@@ -44,7 +47,7 @@ impl
         &self,
         htlc_params: &HtlcParams<Ethereum, asset::Ether, identity::Ethereum>,
         deploy_transaction: &Deployed<htlc_location::Ethereum, transaction::Ethereum>,
-        _start_of_swap: NaiveDateTime,
+        _start_of_scan: StartOfScan,
     ) -> anyhow::Result<Funded<asset::Ether, transaction::Ethereum>> {
         let expected_asset = &htlc_params.asset;
 
@@ -78,12 +81,12 @@ impl
     async fn htlc_deployed(
         &self,
         htlc_params: &HtlcParams<Ethereum, asset::Ether, identity::Ethereum>,
-        start_of_swap: NaiveDateTime,
+        start_of_scan: StartOfScan,
     ) -> anyhow::Result<Deployed<htlc_location::Ethereum, transaction::Ethereum>> {
         let expected_bytecode = htlc_params.bytecode();
 
         let (transaction, location) =
-            watch_for_contract_creation(self, start_of_swap, &expected_bytecode)
+            watch_for_contract_creation(self, start_of_scan, &expected_bytecode)
                 .instrument(tracing::trace_span!(
                     "htlc_deployed",
                     expected_bytecode = %hex::encode(&expected_bytecode.0)
@@ -111,14 +114,14 @@ impl
         &self,
         _htlc_params: &HtlcParams<Ethereum, asset::Ether, identity::Ethereum>,
         htlc_deployment: &Deployed<htlc_location::Ethereum, transaction::Ethereum>,
-        start_of_swap: NaiveDateTime,
+        start_of_scan: StartOfScan,
     ) -> anyhow::Result<Redeemed<transaction::Ethereum>> {
         let event = Event {
             address: htlc_deployment.location,
             topics: vec![Some(Topic(*REDEEM_LOG_MSG))],
         };
 
-        let (transaction, log) = watch_for_event(self, start_of_swap, event)
+        let (transaction, log) = watch_for_event(self, start_of_scan, event)
             .instrument(tracing::trace_span!(
                 "htlc_redeemed",
                 htlc = format_args!("{:x}", htlc_deployment.location),
@@ -135,6 +138,15 @@ impl
             secret,
         })
     }
+
+    async fn htlc_redeem_pending(
+        &self,
+        _htlc_params: &HtlcParams<Ethereum, asset::Ether, identity::Ethereum>,
+        _htlc_deployment: &Deployed<htlc_location::Ethereum, transaction::Ethereum>,
+    ) -> anyhow::Result<Redeemed<transaction::Ethereum>> {
+        // We only learn about Ethereum redeems through logs of mined transactions.
+        future::pending().await
+    }
 }
 
 #[async_trait::async_trait]
@@ -151,14 +163,14 @@ impl
         &self,
         _htlc_params: &HtlcParams<Ethereum, asset::Ether, identity::Ethereum>,
         htlc_deployment: &Deployed<htlc_location::Ethereum, transaction::Ethereum>,
-        start_of_swap: NaiveDateTime,
+        start_of_scan: StartOfScan,
     ) -> anyhow::Result<Refunded<transaction::Ethereum>> {
         let event = Event {
             address: htlc_deployment.location,
             topics: vec![Some(Topic(*REFUND_LOG_MSG))],
         };
 
-        let (transaction, _) = watch_for_event(self, start_of_swap, event)
+        let (transaction, _) = watch_for_event(self, start_of_scan, event)
             .instrument(tracing::trace_span!(
                 "htlc_refunded",
                 htlc = format_args!("{:x}", htlc_deployment.location),
@@ -184,7 +196,7 @@ impl
         &self,
         htlc_params: &HtlcParams<Ethereum, asset::Erc20, identity::Ethereum>,
         htlc_deployment: &Deployed<htlc_location::Ethereum, transaction::Ethereum>,
-        start_of_swap: NaiveDateTime,
+        start_of_scan: StartOfScan,
     ) -> anyhow::Result<Funded<asset::Erc20, transaction::Ethereum>> {
         let event = Event {
             address: htlc_params.asset.token_contract,
@@ -195,7 +207,7 @@ impl
             ],
         };
 
-        let (transaction, log) = watch_for_event(self, start_of_swap, event)
+        let (transaction, log) = watch_for_event(self, start_of_scan, event)
             .instrument(tracing::trace_span!("htlc_funded"))
             .await?;
 
@@ -226,12 +238,12 @@ impl
     async fn htlc_deployed(
         &self,
         htlc_params: &HtlcParams<Ethereum, asset::Erc20, identity::Ethereum>,
-        start_of_swap: NaiveDateTime,
+        start_of_scan: StartOfScan,
     ) -> anyhow::Result<Deployed<htlc_location::Ethereum, transaction::Ethereum>> {
         let expected_bytecode = htlc_params.clone().bytecode();
 
         let (transaction, location) =
-            watch_for_contract_creation(self, start_of_swap, &expected_bytecode)
+            watch_for_contract_creation(self, start_of_scan, &expected_bytecode)
                 .instrument(tracing::trace_span!(
                     "htlc_deployed",
                     expected_bytecode = %hex::encode(&expected_bytecode.0)
@@ -259,14 +271,14 @@ impl
         &self,
         _htlc_params: &HtlcParams<Ethereum, Erc20, identity::Ethereum>,
         htlc_deployment: &Deployed<htlc_location::Ethereum, transaction::Ethereum>,
-        start_of_swap: NaiveDateTime,
+        start_of_scan: StartOfScan,
     ) -> anyhow::Result<Redeemed<transaction::Ethereum>> {
         let event = Event {
             address: htlc_deployment.location,
             topics: vec![Some(Topic(*REDEEM_LOG_MSG))],
         };
 
-        let (transaction, log) = watch_for_event(self, start_of_swap, event)
+        let (transaction, log) = watch_for_event(self, start_of_scan, event)
             .instrument(tracing::info_span!("htlc_redeemed"))
             .await?;
 
@@ -279,6 +291,15 @@ impl
             secret,
         })
     }
+
+    async fn htlc_redeem_pending(
+        &self,
+        _htlc_params: &HtlcParams<Ethereum, Erc20, identity::Ethereum>,
+        _htlc_deployment: &Deployed<htlc_location::Ethereum, transaction::Ethereum>,
+    ) -> anyhow::Result<Redeemed<transaction::Ethereum>> {
+        // We only learn about Ethereum redeems through logs of mined transactions.
+        future::pending().await
+    }
 }
 
 #[async_trait::async_trait]
@@ -295,14 +316,14 @@ impl
         &self,
         _htlc_params: &HtlcParams<Ethereum, Erc20, identity::Ethereum>,
         htlc_deployment: &Deployed<htlc_location::Ethereum, transaction::Ethereum>,
-        start_of_swap: NaiveDateTime,
+        start_of_scan: StartOfScan,
     ) -> anyhow::Result<Refunded<transaction::Ethereum>> {
         let event = Event {
             address: htlc_deployment.location,
             topics: vec![Some(Topic(*REFUND_LOG_MSG))],
         };
 
-        let (transaction, _) = watch_for_event(self, start_of_swap, event)
+        let (transaction, _) = watch_for_event(self, start_of_scan, event)
             .instrument(tracing::info_span!("htlc_refunded"))
             .await?;
 
@@ -1,5 +1,7 @@
-use crate::swap_protocols::rfc003::{create_swap::HtlcParams, Secret};
-use chrono::NaiveDateTime;
+use crate::{
+    btsieve::StartOfScan,
+    swap_protocols::rfc003::{create_swap::HtlcParams, Secret},
+};
 use serde::{Deserialize, Serialize};
 
 #[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
@@ -8,6 +10,16 @@ pub enum Funded<A, T> {
     Incorrectly { asset: A, transaction: T },
 }
 
+impl<A, T> Funded<A, T> {
+    pub fn transaction(&self) -> &T {
+        match self {
+            Funded::Correctly { transaction, .. } | Funded::Incorrectly { transaction, .. } => {
+                transaction
+            }
+        }
+    }
+}
+
 #[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
 pub struct Redeemed<T> {
     pub transaction: T,
@@ -31,7 +43,7 @@ pub trait HtlcFunded<L, A, H, I, T>: Send + Sync + Sized + 'static {
         &self,
         htlc_params: &HtlcParams<L, A, I>,
         htlc_deployment: &Deployed<H, T>,
-        start_of_swap: NaiveDateTime,
+        start_of_scan: StartOfScan,
     ) -> anyhow::Result<Funded<A, T>>;
 }
 
@@ -40,7 +52,7 @@ pub trait HtlcDeployed<L, A, H, I, T>: Send + Sync + Sized + 'static {
     async fn htlc_deployed(
         &self,
         htlc_params: &HtlcParams<L, A, I>,
-        start_of_swap: NaiveDateTime,
+        start_of_scan: StartOfScan,
     ) -> anyhow::Result<Deployed<H, T>>;
 }
 
@@ -50,7 +62,15 @@ pub trait HtlcRedeemed<L, A, H, I, T>: Send + Sync + Sized + 'static {
         &self,
         htlc_params: &HtlcParams<L, A, I>,
         htlc_deployment: &Deployed<H, T>,
-        start_of_swap: NaiveDateTime,
+        start_of_scan: StartOfScan,
+    ) -> anyhow::Result<Redeemed<T>>;
+
+    /// Resolves as soon as a redeem transaction is seen before it is included
+    /// in a block. Never resolves on ledgers where this is not supported.
+    async fn htlc_redeem_pending(
+        &self,
+        htlc_params: &HtlcParams<L, A, I>,
+        htlc_deployment: &Deployed<H, T>,
     ) -> anyhow::Result<Redeemed<T>>;
 }
 
@@ -60,6 +80,6 @@ pub trait HtlcRefunded<L, A, H, I, T>: Send + Sync + Sized + 'static {
         &self,
         htlc_params: &HtlcParams<L, A, I>,
         htlc_deployment: &Deployed<H, T>,
-        start_of_swap: NaiveDateTime,
+        start_of_scan: StartOfScan,
     ) -> anyhow::Result<Refunded<T>>;
 }
@@ -29,6 +29,15 @@ pub enum LedgerState<A, H, T> {
         fund_transaction: T,
         asset: A,
     },
+    /// A redeem transaction has been seen but is not yet confirmed.
+    RedeemPending {
+        htlc_location: H,
+        deploy_transaction: T,
+        fund_transaction: T,
+        redeem_transaction: T,
+        asset: A,
+        secret: Secret,
+    },
     Redeemed {
         htlc_location: H,
         deploy_transaction: T,
@@ -91,6 +100,32 @@ impl<A, H, T> LedgerState<A, H, T> {
         }
     }
 
+    pub fn transition_to_redeem_pending(&mut self, redeemed: Redeemed<T>) {
+        let Redeemed {
+            transaction,
+            secret,
+        } = redeemed;
+
+        match std::mem::replace(self, LedgerState::NotDeployed) {
+            LedgerState::Funded {
+                deploy_transaction,
+                htlc_location,
+                asset,
+                fund_transaction,
+            } => {
+                *self = LedgerState::RedeemPending {
+                    deploy_transaction,
+                    htlc_location,
+                    fund_transaction,
+                    redeem_transaction: transaction,
+                    asset,
+                    secret,
+                }
+            }
+            other => panic!("expected state Funded, got {}", HtlcState::from(other)),
+        }
+    }
+
     pub fn transition_to_redeemed(&mut self, redeemed: Redeemed<T>) {
         let Redeemed {
             transaction,
@@ -103,6 +138,13 @@ impl<A, H, T> LedgerState<A, H, T> {
                 htlc_location,
                 asset,
                 fund_transaction,
+            }
+            | LedgerState::RedeemPending {
+                deploy_transaction,
+                htlc_location,
+                asset,
+                fund_transaction,
+                ..
             } => {
                 *self = LedgerState::Redeemed {
                     deploy_transaction,
@@ -113,7 +155,10 @@ impl<A, H, T> LedgerState<A, H, T> {
                     secret,
                 }
             }
-            other => panic!("expected state Funded, got {}", HtlcState::from(other)),
+            other => panic!(
+                "expected state Funded or RedeemPending, got {}",
+                HtlcState::from(other)
+            ),
         }
     }
 
@@ -132,6 +177,13 @@ impl<A, H, T> LedgerState<A, H, T> {
                 htlc_location,
                 asset,
                 fund_transaction,
+            }
+            | LedgerState::RedeemPending {
+                deploy_transaction,
+                htlc_location,
+                asset,
+                fund_transaction,
+                ..
             } => {
                 *self = LedgerState::Refunded {
                     deploy_transaction,
@@ -142,11 +194,36 @@ impl<A, H, T> LedgerState<A, H, T> {
                 }
             }
             other => panic!(
-                "expected state Funded or IncorrectlyFunded, got {}",
+                "expected state Funded, IncorrectlyFunded or RedeemPending, got {}",
                 HtlcState::from(other)
             ),
         }
     }
+
+    /// The most recent transaction that touched the HTLC.
+    pub fn latest_transaction(&self) -> Option<&T> {
+        match self {
+            LedgerState::NotDeployed => None,
+            LedgerState::Deployed {
+                deploy_transaction, ..
+            } => Some(deploy_transaction),
+            LedgerState::Funded {
+                fund_transaction, ..
+            }
+            | LedgerState::IncorrectlyFunded {
+                fund_transaction, ..
+            } => Some(fund_transaction),
+            LedgerState::RedeemPending {
+                redeem_transaction, ..
+            }
+            | LedgerState::Redeemed {
+                redeem_transaction, ..
+            } => Some(redeem_transaction),
+            LedgerState::Refunded {
+                refund_transaction, ..
+            } => Some(refund_transaction),
+        }
+    }
 }
 
 impl Default for HtlcState {
@@ -158,13 +235,14 @@ impl Default for HtlcState {
 #[cfg(test)]
 impl quickcheck::Arbitrary for HtlcState {
     fn arbitrary<G: quickcheck::Gen>(g: &mut G) -> Self {
-        match g.next_u32() % 6 {
+        match g.next_u32() % 7 {
             0 => HtlcState::NotDeployed,
             1 => HtlcState::Deployed,
             2 => HtlcState::Funded,
             3 => HtlcState::IncorrectlyFunded,
-            4 => HtlcState::Redeemed,
-            5 => HtlcState::Refunded,
+            4 => HtlcState::RedeemPending,
+            5 => HtlcState::Redeemed,
+            6 => HtlcState::Refunded,
             _ => unreachable!(),
         }
     }
@@ -1,15 +1,30 @@
-use crate::swap_protocols::rfc003::{
-    create_swap::SwapEvent,
-    state::{Get, Insert, Update},
-    LedgerState, SwapId,
+use crate::swap_protocols::{
+    rfc003::{
+        create_swap::SwapEvent,
+        state::{Get, Insert, Update},
+        LedgerState, SwapId,
+    },
+    state::{Change, Changes},
 };
 use async_trait::async_trait;
+use futures::Stream;
 use std::{any::Any, collections::HashMap};
 use tokio::sync::Mutex;
 
 #[derive(Default, Debug)]
 pub struct LedgerStates {
-    states: Mutex<HashMap<SwapId, Box<dyn Any + Send>>>,
+    /// The states of each swap, the most recent one being the current state.
+    /// Previous states are kept around so we can roll back to them if a
+    /// transaction is reverted.
+    states: Mutex<HashMap<SwapId, Vec<Box<dyn Any + Send>>>>,
+    changes: Changes,
+}
+
+impl LedgerStates {
+    /// Subscribes to the events recorded from now on.
+    pub fn subscribe(&self) -> impl Stream<Item = Change> + Send + 'static {
+        self.changes.subscribe()
+    }
 }
 
 #[async_trait]
@@ -19,7 +34,7 @@ where
 {
     async fn insert(&self, key: SwapId, value: S) {
         let mut states = self.states.lock().await;
-        states.insert(key, Box::new(value));
+        states.insert(key, vec![Box::new(value)]);
     }
 }
 
@@ -30,7 +45,7 @@ where
 {
     async fn get(&self, key: &SwapId) -> anyhow::Result<Option<S>> {
         let states = self.states.lock().await;
-        match states.get(key) {
+        match states.get(key).and_then(|states| states.last()) {
             Some(state) => match state.downcast_ref::<S>() {
                 Some(state) => Ok(Some(state.clone())),
                 None => Err(anyhow::anyhow!("invalid type")),
@@ -43,18 +58,26 @@ where
 #[async_trait]
 impl<A, H, T> Update<SwapEvent<A, H, T>> for LedgerStates
 where
-    LedgerState<A, H, T>: 'static,
+    LedgerState<A, H, T>: Clone + 'static,
     A: Send,
     H: Send,
     T: Send,
 {
     async fn update(&self, key: &SwapId, event: SwapEvent<A, H, T>) {
+        let event_name = event.to_string();
         let mut states = self.states.lock().await;
-        let ledger_state = match states
-            .get_mut(key)
-            .and_then(|state| state.downcast_mut::<LedgerState<A, H, T>>())
+        let history = match states.get_mut(key) {
+            Some(history) => history,
+            None => {
+                tracing::warn!("Value not found for key {}", key);
+                return;
+            }
+        };
+        let mut ledger_state = match history
+            .last()
+            .and_then(|state| state.downcast_ref::<LedgerState<A, H, T>>())
         {
-            Some(state) => state,
+            Some(state) => state.clone(),
             None => {
                 tracing::warn!("Value not found for key {}", key);
                 return;
@@ -64,13 +87,28 @@ where
         match event {
             SwapEvent::Deployed(deployed) => ledger_state.transition_to_deployed(deployed),
             SwapEvent::Funded(funded) => ledger_state.transition_to_funded(funded),
+            SwapEvent::RedeemPending(redeemed) => {
+                ledger_state.transition_to_redeem_pending(redeemed)
+            }
             SwapEvent::Redeemed(redeemed) => {
                 // what if redeemed.secret.hash() != secret_hash in request ??
 
                 ledger_state.transition_to_redeemed(redeemed);
             }
             SwapEvent::Refunded(refunded) => ledger_state.transition_to_refunded(refunded),
+            SwapEvent::Reverted => {
+                if history.len() > 1 {
+                    history.pop();
+                    self.changes.publish(*key, event_name);
+                } else {
+                    tracing::warn!("No previous state to revert to for key {}", key);
+                }
+                return;
+            }
         }
+
+        history.push(Box::new(ledger_state));
+        self.changes.publish(*key, event_name);
     }
 }
 
@@ -1,24 +1,39 @@
-use crate::swap_protocols::rfc003::{
-    state::{Get, Insert},
-    SwapId,
+use crate::swap_protocols::{
+    rfc003::{
+        state::{Get, Insert},
+        SwapCommunication, SwapId,
+    },
+    state::{Change, Changes},
 };
 use async_trait::async_trait;
+use futures::Stream;
 use std::{any::Any, clone::Clone, collections::HashMap};
 use tokio::sync::Mutex;
 
 #[derive(Default, Debug)]
 pub struct SwapCommunicationStates {
     states: Mutex<HashMap<SwapId, Box<dyn Any + Send>>>,
+    changes: Changes,
+}
+
+impl SwapCommunicationStates {
+    /// Subscribes to the communication states recorded from now on.
+    pub fn subscribe(&self) -> impl Stream<Item = Change> + Send + 'static {
+        self.changes.subscribe()
+    }
 }
 
 #[async_trait]
-impl<S> Insert<S> for SwapCommunicationStates
+impl<AL, BL, AA, BA, AI, BI> Insert<SwapCommunication<AL, BL, AA, BA, AI, BI>>
+    for SwapCommunicationStates
 where
-    S: Send + 'static,
+    SwapCommunication<AL, BL, AA, BA, AI, BI>: Send + 'static,
 {
-    async fn insert(&self, key: SwapId, value: S) {
+    async fn insert(&self, key: SwapId, value: SwapCommunication<AL, BL, AA, BA, AI, BI>) {
+        let state_name = value.to_string();
         let mut states = self.states.lock().await;
         states.insert(key, Box::new(value));
+        self.changes.publish(key, state_name);
     }
 }
 
@@ -2,10 +2,15 @@ use crate::{
     asset,
     btsieve::{
         self,
-        bitcoin::BitcoindConnector,
+        bitcoin::BitcoinConnector,
         ethereum::{self, Web3Connector},
+        start_height, BlockStatus, Confirmations, StartOfScan, WaitForFinality,
     },
-    db::{AcceptedSwap, DetermineTypes, LoadAcceptedSwap, Retrieve, Save, Sqlite, Swap, SwapTypes},
+    db::{
+        AcceptedSwap, DetermineTypes, Ledger, LoadAcceptedSwap, Retrieve, Save, Sqlite, Swap,
+        SwapStatus, SwapTypes, WatcherCheckpoint, WatcherCheckpoints,
+    },
+    expiry_monitor::ExpiryAlerts,
     htlc_location, identity,
     network::{
         ComitPeers, DialInformation, ListenAddresses, LocalPeerId, PendingRequestFor, RequestError,
@@ -16,7 +21,7 @@ use crate::{
         ledger::{bitcoin, Ethereum},
         rfc003::{
             self,
-            create_swap::HtlcParams,
+            create_swap::{HtlcParams, StartHeight},
             events::{
                 Deployed, Funded, HtlcDeployed, HtlcFunded, HtlcRedeemed, HtlcRefunded, Redeemed,
                 Refunded,
@@ -28,7 +33,6 @@ use crate::{
     transaction,
 };
 use async_trait::async_trait;
-use chrono::NaiveDateTime;
 use futures::channel::oneshot::Sender;
 use impl_template::impl_template;
 use libp2p::{Multiaddr, PeerId};
@@ -48,15 +52,18 @@ use std::{convert::TryInto, fmt::Debug, sync::Arc};
 #[delegate(Retrieve, target = "db")]
 #[delegate(DetermineTypes, target = "db")]
 pub struct Rfc003Facade {
-    pub bitcoin_connector: Arc<btsieve::bitcoin::Cache<BitcoindConnector>>,
+    pub bitcoin_connector: Arc<btsieve::bitcoin::Cache<BitcoinConnector>>,
     pub ethereum_connector: Arc<ethereum::Cache<Web3Connector>>,
     pub alpha_ledger_states: Arc<LedgerStates>,
     pub beta_ledger_states: Arc<LedgerStates>,
     pub swap_communication_states: Arc<SwapCommunicationStates>,
     pub swap_error_states: Arc<SwapErrorStates>,
+    pub expiry_alerts: Arc<ExpiryAlerts>,
     pub seed: RootSeed,
     pub swarm: Swarm,
     pub db: Sqlite,
+    pub bitcoin_start_height_margin: u32,
+    pub ethereum_start_height_margin: u32,
 }
 
 #[async_trait]
@@ -87,7 +94,11 @@ where
 #[async_trait]
 impl InsertFailedSwap for Rfc003Facade {
     async fn insert_failed_swap(&self, id: &SwapId) {
-        self.swap_error_states.insert_failed_swap(&id).await
+        self.swap_error_states.insert_failed_swap(&id).await;
+
+        if let Err(e) = self.db.update_swap_status(id, SwapStatus::Failed).await {
+            tracing::error!("failed to record status of failed swap {}: {:?}", id, e);
+        }
     }
 }
 
@@ -132,6 +143,97 @@ where
     }
 }
 
+#[async_trait]
+impl<A, H, T> WatcherCheckpoints<A, H, T> for Rfc003Facade
+where
+    A: Send + 'static,
+    H: Send + 'static,
+    T: Send + 'static,
+    Sqlite: WatcherCheckpoints<A, H, T>,
+{
+    async fn load_watcher_checkpoint(
+        &self,
+        swap_id: &SwapId,
+        ledger: Ledger,
+    ) -> anyhow::Result<WatcherCheckpoint<A, H, T>> {
+        self.db.load_watcher_checkpoint(swap_id, ledger).await
+    }
+
+    async fn save_watcher_checkpoint(
+        &self,
+        swap_id: &SwapId,
+        ledger: Ledger,
+        checkpoint: WatcherCheckpoint<A, H, T>,
+    ) -> anyhow::Result<()> {
+        self.db
+            .save_watcher_checkpoint(swap_id, ledger, checkpoint)
+            .await
+    }
+}
+
+#[async_trait]
+impl WaitForFinality<transaction::Bitcoin> for Rfc003Facade {
+    async fn wait_for_finality(
+        &self,
+        transaction: &transaction::Bitcoin,
+    ) -> anyhow::Result<BlockStatus> {
+        self.bitcoin_connector.wait_for_finality(transaction).await
+    }
+}
+
+#[async_trait]
+impl WaitForFinality<transaction::Ethereum> for Rfc003Facade {
+    async fn wait_for_finality(
+        &self,
+        transaction: &transaction::Ethereum,
+    ) -> anyhow::Result<BlockStatus> {
+        self.ethereum_connector.wait_for_finality(transaction).await
+    }
+}
+
+#[async_trait]
+impl Confirmations<transaction::Bitcoin> for Rfc003Facade {
+    async fn confirmations(
+        &self,
+        transaction: &transaction::Bitcoin,
+    ) -> anyhow::Result<Option<u32>> {
+        self.bitcoin_connector.confirmations(transaction).await
+    }
+}
+
+#[async_trait]
+impl Confirmations<transaction::Ethereum> for Rfc003Facade {
+    async fn confirmations(
+        &self,
+        transaction: &transaction::Ethereum,
+    ) -> anyhow::Result<Option<u32>> {
+        self.ethereum_connector.confirmations(transaction).await
+    }
+}
+
+#[impl_template]
+#[async_trait::async_trait]
+impl StartHeight<((bitcoin::Mainnet, bitcoin::Testnet, bitcoin::Regtest))> for Rfc003Facade {
+    async fn start_height(&self) -> anyhow::Result<Option<u32>> {
+        start_height(
+            self.bitcoin_connector.as_ref(),
+            self.bitcoin_start_height_margin,
+        )
+        .await
+    }
+}
+
+#[async_trait::async_trait]
+impl StartHeight<Ethereum> for Rfc003Facade {
+    async fn start_height(&self) -> anyhow::Result<Option<u32>> {
+        start_height(
+            self.ethereum_connector.as_ref(),
+            self.ethereum_start_height_margin,
+        )
+        .await
+    }
+}
+
 #[impl_template]
 #[async_trait::async_trait]
 impl
@@ -147,10 +249,10 @@ impl
         &self,
         htlc_params: &HtlcParams<__TYPE0__, asset::Bitcoin, identity::Bitcoin>,
         htlc_deployment: &Deployed<htlc_location::Bitcoin, transaction::Bitcoin>,
-        start_of_swap: NaiveDateTime,
+        start_of_scan: StartOfScan,
     ) -> anyhow::Result<Funded<asset::Bitcoin, transaction::Bitcoin>> {
         self.bitcoin_connector
-            .htlc_funded(htlc_params, htlc_deployment, start_of_swap)
+            .htlc_funded(htlc_params, htlc_deployment, start_of_scan)
             .await
     }
 }
@@ -169,10 +271,10 @@ impl
     async fn htlc_deployed(
         &self,
         htlc_params: &HtlcParams<__TYPE0__, asset::Bitcoin, identity::Bitcoin>,
-        start_of_swap: NaiveDateTime,
+        start_of_scan: StartOfScan,
     ) -> anyhow::Result<Deployed<htlc_location::Bitcoin, transaction::Bitcoin>> {
         self.bitcoin_connector
-            .htlc_deployed(htlc_params, start_of_swap)
+            .htlc_deployed(htlc_params, start_of_scan)
             .await
     }
 }
@@ -192,10 +294,20 @@ impl
         &self,
         htlc_params: &HtlcParams<__TYPE0__, asset::Bitcoin, identity::Bitcoin>,
         htlc_deployment: &Deployed<htlc_location::Bitcoin, transaction::Bitcoin>,
-        start_of_swap: NaiveDateTime,
+        start_of_scan: StartOfScan,
+    ) -> anyhow::Result<Redeemed<transaction::Bitcoin>> {
+        self.bitcoin_connector
+            .htlc_redeemed(htlc_params, htlc_deployment, start_of_scan)
+            .await
+    }
+
+    async fn htlc_redeem_pending(
+        &self,
+        htlc_params: &HtlcParams<__TYPE0__, asset::Bitcoin, identity::Bitcoin>,
+        htlc_deployment: &Deployed<htlc_location::Bitcoin, transaction::Bitcoin>,
     ) -> anyhow::Result<Redeemed<transaction::Bitcoin>> {
         self.bitcoin_connector
-            .htlc_redeemed(htlc_params, htlc_deployment, start_of_swap)
+            .htlc_redeem_pending(htlc_params, htlc_deployment)
             .await
     }
 }
@@ -215,10 +327,10 @@ impl
         &self,
         htlc_params: &HtlcParams<__TYPE0__, asset::Bitcoin, identity::Bitcoin>,
         htlc_deployment: &Deployed<htlc_location::Bitcoin, transaction::Bitcoin>,
-        start_of_swap: NaiveDateTime,
+        start_of_scan: StartOfScan,
     ) -> anyhow::Result<Refunded<transaction::Bitcoin>> {
         self.bitcoin_connector
-            .htlc_refunded(htlc_params, htlc_deployment, start_of_swap)
+            .htlc_refunded(htlc_params, htlc_deployment, start_of_scan)
             .await
     }
 }
@@ -238,10 +350,10 @@ impl
         &self,
         htlc_params: &HtlcParams<Ethereum, __TYPE0__, identity::Ethereum>,
         htlc_deployment: &Deployed<htlc_location::Ethereum, transaction::Ethereum>,
-        start_of_swap: NaiveDateTime,
+        start_of_scan: StartOfScan,
     ) -> anyhow::Result<Funded<__TYPE0__, transaction::Ethereum>> {
         self.ethereum_connector
-            .htlc_funded(htlc_params, htlc_deployment, start_of_swap)
+            .htlc_funded(htlc_params, htlc_deployment, start_of_scan)
             .await
     }
 }
@@ -260,10 +372,10 @@ impl
     async fn htlc_deployed(
         &self,
         htlc_params: &HtlcParams<Ethereum, __TYPE0__, identity::Ethereum>,
-        start_of_swap: NaiveDateTime,
+        start_of_scan: StartOfScan,
     ) -> anyhow::Result<Deployed<htlc_location::Ethereum, transaction::Ethereum>> {
         self.ethereum_connector
-            .htlc_deployed(htlc_params, start_of_swap)
+            .htlc_deployed(htlc_params, start_of_scan)
             .await
     }
 }
@@ -283,10 +395,20 @@ impl
         &self,
         htlc_params: &HtlcParams<Ethereum, __TYPE0__, identity::Ethereum>,
         htlc_deployment: &Deployed<htlc_location::Ethereum, transaction::Ethereum>,
-        start_of_swap: NaiveDateTime,
+        start_of_scan: StartOfScan,
+    ) -> anyhow::Result<Redeemed<transaction::Ethereum>> {
+        self.ethereum_connector
+            .htlc_redeemed(htlc_params, htlc_deployment, start_of_scan)
+            .await
+    }
+
+    async fn htlc_redeem_pending(
+        &self,
+        htlc_params: &HtlcParams<Ethereum, __TYPE0__, identity::Ethereum>,
+        htlc_deployment: &Deployed<htlc_location::Ethereum, transaction::Ethereum>,
     ) -> anyhow::Result<Redeemed<transaction::Ethereum>> {
         self.ethereum_connector
-            .htlc_redeemed(htlc_params, htlc_deployment, start_of_swap)
+            .htlc_redeem_pending(htlc_params, htlc_deployment)
             .await
     }
 }
@@ -306,10 +428,10 @@ impl
         &self,
         htlc_params: &HtlcParams<Ethereum, __TYPE0__, identity::Ethereum>,
         htlc_deployment: &Deployed<htlc_location::Ethereum, transaction::Ethereum>,
-        start_of_swap: NaiveDateTime,
+        start_of_scan: StartOfScan,
     ) -> anyhow::Result<Refunded<transaction::Ethereum>> {
         self.ethereum_connector
-            .htlc_refunded(htlc_params, htlc_deployment, start_of_swap)
+            .htlc_refunded(htlc_params, htlc_deployment, start_of_scan)
             .await
     }
 }
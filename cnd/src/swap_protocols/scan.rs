@@ -0,0 +1,47 @@
+use crate::{
+    btsieve::ScanProgress,
+    db::{Ledger, SwapWatcherCheckpoint, SwapWatcherCheckpoints},
+    swap_protocols::LocalSwapId,
+};
+use futures::{
+    future::{self, Either},
+    Future,
+};
+
+/// Waits for the given watchers to find their event, saving the checkpoint
+/// every time all of them scanned further.
+///
+/// This is the counterpart of the rfc003 watchers' scan for swaps that use the
+/// split protocols.
+pub async fn scan<C, D, F, W, R>(
+    checkpoints: &C,
+    id: &LocalSwapId,
+    ledger: Ledger,
+    checkpoint: &mut SwapWatcherCheckpoint<D, F>,
+    mut progress: ScanProgress,
+    watchers: W,
+) -> anyhow::Result<R>
+where
+    C: SwapWatcherCheckpoints<D, F>,
+    W: Future<Output = anyhow::Result<R>>,
+    SwapWatcherCheckpoint<D, F>: Clone,
+{
+    futures::pin_mut!(watchers);
+
+    loop {
+        let scanned = progress.next_checkpoint();
+        futures::pin_mut!(scanned);
+
+        match future::select(watchers.as_mut(), scanned).await {
+            Either::Left((result, _)) => return result,
+            Either::Right((scanned, _)) => {
+                tracing::trace!("scanned up to block {}", scanned.block_hash);
+
+                checkpoint.scanned = Some(scanned);
+                checkpoints
+                    .save_swap_watcher_checkpoint(id, ledger, checkpoint.clone())
+                    .await?;
+            }
+        }
+    }
+}
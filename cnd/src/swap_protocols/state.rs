@@ -1,5 +1,8 @@
 use crate::swap_protocols::LocalSwapId;
 use async_trait::async_trait;
+use futures::{stream, Stream};
+use tokio::sync::broadcast::{self, RecvError};
+use uuid::Uuid;
 
 #[async_trait]
 pub trait Insert<S>: Send + Sync + 'static {
@@ -15,3 +18,76 @@ pub trait Get<S>: Send + Sync + 'static {
 pub trait Update<E>: Send + Sync + 'static {
     async fn update(&self, key: &LocalSwapId, update: E);
 }
+
+/// Number of changes a subscriber can fall behind before it misses some.
+const CHANGES_CAPACITY: usize = 256;
+
+/// A change recorded by one of the state stores.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Change {
+    pub swap_id: Uuid,
+    /// Name of the event that was recorded, e.g. `Funded`.
+    pub event: String,
+}
+
+/// Broadcasts the changes recorded by a state store to everybody who
+/// subscribed to them.
+#[derive(Debug)]
+pub struct Changes(broadcast::Sender<Change>);
+
+impl Default for Changes {
+    fn default() -> Self {
+        let (sender, _) = broadcast::channel(CHANGES_CAPACITY);
+
+        Self(sender)
+    }
+}
+
+impl Changes {
+    pub fn publish(&self, swap_id: impl Into<Uuid>, event: impl ToString) {
+        // Not having any subscribers is fine
+        let _ = self.0.send(Change {
+            swap_id: swap_id.into(),
+            event: event.to_string(),
+        });
+    }
+
+    /// Changes published from now on.
+    pub fn subscribe(&self) -> impl Stream<Item = Change> + Send + 'static {
+        stream::unfold(self.0.subscribe(), |mut receiver| async move {
+            loop {
+                match receiver.recv().await {
+                    Ok(change) => return Some((change, receiver)),
+                    Err(RecvError::Lagged(missed)) => {
+                        tracing::warn!("subscriber missed {} state changes", missed)
+                    }
+                    Err(RecvError::Closed) => return None,
+                }
+            }
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use futures::StreamExt;
+
+    #[tokio::test]
+    async fn subscriber_receives_changes_published_after_subscribing() {
+        let changes = Changes::default();
+        let swap_id = LocalSwapId::default();
+
+        changes.publish(swap_id, "Started");
+        let subscription = changes.subscribe();
+        changes.publish(swap_id, "Funded");
+        drop(changes);
+
+        let received = subscription.collect::<Vec<_>>().await;
+
+        assert_eq!(received, vec![Change {
+            swap_id: swap_id.into(),
+            event: String::from("Funded"),
+        }]);
+    }
+}
@@ -0,0 +1,155 @@
+//! Records the status of swaps in the database.
+//!
+//! The status is derived from the in-memory state of a swap. Keeping a copy
+//! of it in the database allows us to filter the swaps by status in SQL.
+use crate::{
+    db::{DetermineTypes, SwapStatus},
+    http_api::{
+        self,
+        routes::{load_swap_state, rfc003::SwapCommunicationState},
+    },
+    metrics,
+    swap_protocols::{
+        rfc003::{ledger_state::HtlcState, state::Get, LedgerState, SwapCommunication, SwapId},
+        Facade, LocalSwapId, Rfc003Facade,
+    },
+};
+use futures::{stream, Future, StreamExt};
+use std::{collections::HashMap, time::Instant};
+
+/// A swap whose state changed.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+enum Changed {
+    Rfc003(SwapId),
+    SplitProtocol(LocalSwapId),
+}
+
+/// The phase every swap in progress is in, named after the event that started
+/// it, and since when.
+#[derive(Debug, Default)]
+struct SwapPhases(HashMap<Changed, (String, Instant)>);
+
+impl SwapPhases {
+    fn enter(&mut self, swap: Changed, phase: String) {
+        if let Some((previous, since)) = self.0.insert(swap, (phase, Instant::now())) {
+            metrics::observe_swap_phase(&previous, since.elapsed());
+        }
+    }
+
+    fn finish(&mut self, swap: Changed) {
+        self.0.remove(&swap);
+    }
+}
+
+impl From<http_api::SwapStatus> for SwapStatus {
+    fn from(status: http_api::SwapStatus) -> Self {
+        match status {
+            http_api::SwapStatus::InProgress => SwapStatus::InProgress,
+            http_api::SwapStatus::Swapped => SwapStatus::Swapped,
+            http_api::SwapStatus::NotSwapped => SwapStatus::NotSwapped,
+            http_api::SwapStatus::InternalFailure => SwapStatus::Failed,
+        }
+    }
+}
+
+/// Updates the status of a swap in the database whenever its state changes and
+/// records how long swaps spend in each phase.
+///
+/// We subscribe to the state stores before returning so that no change is
+/// missed, even if the returned future is only polled later on.
+pub fn track(rfc003_facade: Rfc003Facade, facade: Facade) -> impl Future<Output = ()> + Send {
+    let rfc003_changes = stream::select_all(vec![
+        rfc003_facade.swap_communication_states.subscribe().boxed(),
+        rfc003_facade.alpha_ledger_states.subscribe().boxed(),
+        rfc003_facade.beta_ledger_states.subscribe().boxed(),
+    ])
+    .map(|change| (Changed::Rfc003(SwapId(change.swap_id)), change.event));
+    let split_protocol_changes = stream::select_all(vec![
+        facade.han_states.subscribe().boxed(),
+        facade.herc20_states.subscribe().boxed(),
+        facade.halight_states.subscribe().boxed(),
+        facade.hbit_states.subscribe().boxed(),
+    ])
+    .map(|change| {
+        (
+            Changed::SplitProtocol(LocalSwapId::from(change.swap_id)),
+            change.event,
+        )
+    });
+    let mut changes = stream::select(rfc003_changes, split_protocol_changes);
+
+    async move {
+        let mut phases = SwapPhases::default();
+
+        while let Some((changed, event)) = changes.next().await {
+            phases.enter(changed, event);
+
+            let status = match changed {
+                Changed::Rfc003(swap_id) => status(&rfc003_facade, swap_id).await,
+                Changed::SplitProtocol(swap_id) => load_swap_state(&facade, swap_id)
+                    .await
+                    .map(|state| state.map(|state| state.status())),
+            };
+
+            let result = match status {
+                Ok(Some(status)) => {
+                    if status != SwapStatus::InProgress {
+                        phases.finish(changed);
+                    }
+
+                    match changed {
+                        Changed::Rfc003(swap_id) => {
+                            rfc003_facade.db.update_swap_status(&swap_id, status).await
+                        }
+                        Changed::SplitProtocol(swap_id) => {
+                            facade.db.update_local_swap_status(swap_id, status).await
+                        }
+                    }
+                }
+                Ok(None) => Ok(()),
+                Err(e) => Err(e),
+            };
+
+            if let Err(e) = result {
+                tracing::warn!("failed to update status of swap {:?}: {:?}", changed, e);
+            }
+        }
+    }
+}
+
+/// The status of an rfc003 swap, `None` if we don't know about it.
+#[allow(clippy::cognitive_complexity)]
+pub(crate) async fn status(
+    facade: &Rfc003Facade,
+    swap_id: SwapId,
+) -> anyhow::Result<Option<SwapStatus>> {
+    if facade.swap_error_states.has_failed(&swap_id).await {
+        return Ok(Some(SwapStatus::Failed));
+    }
+
+    let types = facade.determine_types(&swap_id).await?;
+
+    with_swap_types!(types, {
+        let swap_communication: Option<SwapCommunication<AL, BL, AA, BA, AI, BI>> =
+            facade.get(&swap_id).await?;
+        let communication = match swap_communication {
+            Some(SwapCommunication::Proposed { .. }) => SwapCommunicationState::Sent,
+            Some(SwapCommunication::Accepted { .. }) => SwapCommunicationState::Accepted,
+            Some(SwapCommunication::Declined { .. }) => SwapCommunicationState::Declined,
+            None => return Ok(None),
+        };
+
+        let alpha_ledger_state: Option<LedgerState<AA, AH, AT>> =
+            facade.alpha_ledger_states.get(&swap_id).await?;
+        let beta_ledger_state: Option<LedgerState<BA, BH, BT>> =
+            facade.beta_ledger_states.get(&swap_id).await?;
+
+        let status = http_api::SwapStatus::new(
+            communication,
+            alpha_ledger_state.map_or(HtlcState::NotDeployed, HtlcState::from),
+            beta_ledger_state.map_or(HtlcState::NotDeployed, HtlcState::from),
+        );
+
+        Ok(Some(SwapStatus::from(status)))
+    })
+}
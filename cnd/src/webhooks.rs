@@ -0,0 +1,362 @@
+//! Notifies the configured webhooks about swap events.
+//!
+//! Every event that is relevant to an application driving cnd is turned into
+//! a JSON notification and queued in the database for each webhook. The
+//! notifications are POSTed with an HMAC-SHA256 signature of the body in the
+//! `X-Cnd-Signature` header. Failed deliveries are retried with exponential
+//! backoff until they are accepted or `MAX_ATTEMPTS` is reached.
+use crate::{
+    config::Webhook,
+    db::{DetermineTypes, Sqlite, SwapStatus, WebhookDeliveries, WebhookDelivery},
+    http_api::{
+        route_factory,
+        routes::{
+            events::{events, Event, Protocol},
+            load_swap_state,
+        },
+    },
+    swap_protocols::{rfc003::SwapId, Facade, LocalSwapId, Rfc003Facade, Role},
+    swap_status,
+};
+use bitcoin::hashes::{
+    hmac::{Hmac, HmacEngine},
+    sha256, Hash, HashEngine,
+};
+use chrono::{NaiveDateTime, Utc};
+use futures::{future, StreamExt};
+use reqwest::header::CONTENT_TYPE;
+use serde::Serialize;
+use std::{collections::HashMap, convert::TryFrom, time::Duration};
+use uuid::Uuid;
+
+/// How often we look for deliveries that are due.
+const POLL_INTERVAL: Duration = Duration::from_secs(5);
+
+/// How long we wait for a webhook to respond.
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Delay before the first retry, doubled with every failed attempt.
+const INITIAL_BACKOFF_SECS: i64 = 10;
+const MAX_BACKOFF_SECS: i64 = 60 * 60;
+
+/// Number of attempts after which a delivery is dropped.
+const MAX_ATTEMPTS: i32 = 10;
+
+pub const SIGNATURE_HEADER: &str = "X-Cnd-Signature";
+
+#[derive(Debug, Serialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+enum Notification {
+    /// The counterparty sent us an rfc003 swap request, it can be accepted
+    /// or declined through the actions of the swap resource.
+    SwapRequest {
+        swap_id: Uuid,
+        swap: String,
+    },
+    LedgerEvent {
+        swap_id: Uuid,
+        protocol: Protocol,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        ledger: Option<&'static str>,
+        event: String,
+    },
+    SwapFinished {
+        swap_id: Uuid,
+        status: &'static str,
+    },
+}
+
+impl Notification {
+    /// Tells the notification apart from the other ones of its swap.
+    ///
+    /// Ledger events are numbered by how often they happened for the swap, the
+    /// same event happens again if its transaction is orphaned and mined
+    /// anew.
+    fn key(&self, occurrences: &mut HashMap<(Uuid, String), u32>) -> (Uuid, String) {
+        match self {
+            Notification::SwapRequest { swap_id, .. } => (*swap_id, String::from("swap_request")),
+            Notification::LedgerEvent {
+                swap_id,
+                protocol,
+                ledger,
+                event,
+            } => {
+                let event = match ledger {
+                    Some(ledger) => format!("{} {}", ledger, event),
+                    None => format!("{} {}", protocol, event),
+                };
+                let occurrence = occurrences.entry((*swap_id, event.clone())).or_insert(0);
+                *occurrence += 1;
+
+                (*swap_id, format!("{} {}", event, occurrence))
+            }
+            Notification::SwapFinished { swap_id, .. } => (*swap_id, String::from("swap_finished")),
+        }
+    }
+}
+
+pub async fn run(webhooks: Vec<Webhook>, rfc003_facade: Rfc003Facade, facade: Facade) {
+    let db = facade.db.clone();
+
+    future::join(
+        dispatch(webhooks.clone(), rfc003_facade, facade),
+        deliver(webhooks, db),
+    )
+    .await;
+}
+
+/// Queues a notification for every webhook whenever a swap event happens.
+///
+/// Events are replayed when cnd restarts, every notification is only queued
+/// the first time its event happened.
+async fn dispatch(webhooks: Vec<Webhook>, rfc003_facade: Rfc003Facade, facade: Facade) {
+    let mut events = events(rfc003_facade.clone(), facade.clone(), None).boxed();
+    let urls = webhooks
+        .iter()
+        .map(|webhook| webhook.url.clone())
+        .collect::<Vec<_>>();
+    let mut occurrences = HashMap::new();
+
+    while let Some(event) = events.next().await {
+        let notifications = match notifications(event, &rfc003_facade, &facade).await {
+            Ok(notifications) => notifications,
+            Err(e) => {
+                tracing::warn!("failed to build webhook notification: {:?}", e);
+                continue;
+            }
+        };
+
+        for notification in notifications {
+            let payload = match serde_json::to_string(&notification) {
+                Ok(payload) => payload,
+                Err(e) => {
+                    tracing::error!("failed to serialize webhook notification: {:?}", e);
+                    continue;
+                }
+            };
+            let (swap_id, event) = notification.key(&mut occurrences);
+
+            if let Err(e) = facade
+                .db
+                .enqueue_swap_event_deliveries(swap_id, event.clone(), urls.clone(), payload, now())
+                .await
+            {
+                tracing::error!(
+                    "failed to queue notification about {} of swap {}: {:?}",
+                    event,
+                    swap_id,
+                    e
+                );
+            }
+        }
+    }
+}
+
+/// Returns the notifications for the given event.
+///
+/// A swap finished notification is built for every event of a finished swap,
+/// it is only queued once though.
+async fn notifications(
+    event: Event,
+    rfc003_facade: &Rfc003Facade,
+    facade: &Facade,
+) -> anyhow::Result<Vec<Notification>> {
+    match event {
+        Event::Communication { swap_id, state } if state == "Proposed" => {
+            let types = rfc003_facade.determine_types(&SwapId(swap_id)).await?;
+            if types.role != Role::Bob {
+                return Ok(Vec::new());
+            }
+
+            Ok(vec![Notification::SwapRequest {
+                swap_id,
+                swap: route_factory::rfc003_swap_path(SwapId(swap_id)),
+            }])
+        }
+        Event::Swap {
+            swap_id,
+            protocol,
+            ledger,
+            event,
+        } => {
+            let mut notifications = vec![Notification::LedgerEvent {
+                swap_id,
+                protocol,
+                ledger,
+                event,
+            }];
+
+            let status = match protocol {
+                Protocol::Rfc003 => swap_status::status(rfc003_facade, SwapId(swap_id)).await?,
+                Protocol::Han | Protocol::Herc20 | Protocol::Halight | Protocol::Hbit => {
+                    load_swap_state(facade, LocalSwapId::from(swap_id))
+                        .await?
+                        .map(|state| state.status())
+                }
+            };
+
+            match status {
+                Some(SwapStatus::Swapped) => notifications.push(Notification::SwapFinished {
+                    swap_id,
+                    status: "SWAPPED",
+                }),
+                Some(SwapStatus::NotSwapped) => notifications.push(Notification::SwapFinished {
+                    swap_id,
+                    status: "NOT_SWAPPED",
+                }),
+                Some(SwapStatus::InProgress) | Some(SwapStatus::Failed) | None => {}
+            }
+
+            Ok(notifications)
+        }
+        Event::Communication { .. } | Event::Actions { .. } => Ok(Vec::new()),
+    }
+}
+
+/// Periodically POSTs the deliveries that are due.
+async fn deliver(webhooks: Vec<Webhook>, db: Sqlite) {
+    let client = reqwest::Client::new();
+
+    loop {
+        match db.due_webhook_deliveries(now()).await {
+            Ok(deliveries) => {
+                for delivery in deliveries {
+                    if let Err(e) = attempt(&client, &webhooks, &db, delivery).await {
+                        tracing::error!("failed to update webhook delivery: {:?}", e);
+                    }
+                }
+            }
+            Err(e) => tracing::error!("failed to load webhook deliveries: {:?}", e),
+        }
+
+        tokio::time::delay_for(POLL_INTERVAL).await;
+    }
+}
+
+async fn attempt(
+    client: &reqwest::Client,
+    webhooks: &[Webhook],
+    db: &Sqlite,
+    delivery: WebhookDelivery,
+) -> anyhow::Result<()> {
+    let webhook = match webhooks.iter().find(|webhook| webhook.url == *delivery.url) {
+        Some(webhook) => webhook,
+        None => {
+            tracing::warn!(
+                "dropping notification for {} which is no longer configured",
+                *delivery.url
+            );
+            return db.remove_webhook_delivery(delivery.id).await;
+        }
+    };
+
+    let e = match post(client, webhook, &delivery.payload).await {
+        Ok(()) => return db.remove_webhook_delivery(delivery.id).await,
+        Err(e) => e,
+    };
+
+    let attempts = delivery.attempts + 1;
+    if attempts >= MAX_ATTEMPTS {
+        tracing::warn!(
+            "giving up on notification for {} after {} attempts: {:?}",
+            webhook.url,
+            attempts,
+            e
+        );
+        return db.remove_webhook_delivery(delivery.id).await;
+    }
+
+    tracing::debug!(
+        "failed to notify {}, attempt {}: {:?}",
+        webhook.url,
+        attempts,
+        e
+    );
+    db.reschedule_webhook_delivery(delivery.id, attempts, now() + backoff(attempts))
+        .await
+}
+
+async fn post(client: &reqwest::Client, webhook: &Webhook, payload: &str) -> anyhow::Result<()> {
+    client
+        .post(webhook.url.clone())
+        .timeout(REQUEST_TIMEOUT)
+        .header(CONTENT_TYPE, "application/json")
+        .header(
+            SIGNATURE_HEADER,
+            signature(webhook.secret.expose(), payload),
+        )
+        .body(payload.to_owned())
+        .send()
+        .await?
+        .error_for_status()?;
+
+    Ok(())
+}
+
+/// Hex encoded HMAC-SHA256 of the payload, prefixed with the hash function.
+fn signature(secret: &str, payload: &str) -> String {
+    let mut engine = HmacEngine::<sha256::Hash>::new(secret.as_bytes());
+    engine.input(payload.as_bytes());
+    let hmac = Hmac::<sha256::Hash>::from_engine(engine);
+
+    format!("sha256={}", hex::encode(&hmac[..]))
+}
+
+/// Time to wait before the next attempt after `attempts` failed ones.
+fn backoff(attempts: i32) -> chrono::Duration {
+    let exponent = u32::try_from(attempts - 1).unwrap_or(0);
+    let seconds = 2i64
+        .checked_pow(exponent)
+        .and_then(|factor| factor.checked_mul(INITIAL_BACKOFF_SECS))
+        .map_or(MAX_BACKOFF_SECS, |seconds| seconds.min(MAX_BACKOFF_SECS));
+
+    chrono::Duration::seconds(seconds)
+}
+
+fn now() -> NaiveDateTime {
+    Utc::now().naive_utc()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn signature_matches_rfc4231_test_case() {
+        let signature = signature("Jefe", "what do ya want for nothing?");
+
+        assert_eq!(
+            signature,
+            "sha256=5bdcc146bf60754e6a042426089575c75a003f089d2739839dec58b964ec3843"
+        );
+    }
+
+    #[test]
+    fn backoff_doubles_until_capped() {
+        assert_eq!(backoff(1), chrono::Duration::seconds(10));
+        assert_eq!(backoff(2), chrono::Duration::seconds(20));
+        assert_eq!(backoff(3), chrono::Duration::seconds(40));
+        assert_eq!(backoff(9), chrono::Duration::seconds(2560));
+        assert_eq!(backoff(10), chrono::Duration::hours(1));
+        assert_eq!(backoff(100), chrono::Duration::hours(1));
+    }
+
+    #[test]
+    fn notifications_are_tagged_with_their_type() {
+        let notification = Notification::SwapFinished {
+            swap_id: Uuid::nil(),
+            status: "SWAPPED",
+        };
+
+        let json = serde_json::to_value(&notification).unwrap();
+
+        assert_eq!(
+            json,
+            serde_json::json!({
+                "type": "swap_finished",
+                "swap_id": "00000000-0000-0000-0000-000000000000",
+                "status": "SWAPPED",
+            })
+        );
+    }
+}
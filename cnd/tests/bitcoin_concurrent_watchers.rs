@@ -0,0 +1,53 @@
+pub mod bitcoin_helper;
+
+use bitcoin::Address;
+use bitcoin_helper::BitcoinConnectorMock;
+use chrono::NaiveDateTime;
+use cnd::btsieve::bitcoin::watch_for_created_outpoint;
+use std::str::FromStr;
+
+#[tokio::test]
+async fn concurrent_watchers_share_a_single_block_scan() {
+    let connector = BitcoinConnectorMock::new(
+        vec![
+            include_hex!("./test_data/bitcoin/find_transaction_missed_previous_latest_block/block1.hex"),
+            include_hex!("./test_data/bitcoin/find_transaction_missed_previous_latest_block/block3.hex"),
+        ],
+        vec![
+            include_hex!("./test_data/bitcoin/find_transaction_missed_previous_latest_block/block1.hex"),
+            include_hex!(
+                "./test_data/bitcoin/find_transaction_missed_previous_latest_block/block2_with_transaction.hex"
+            ),
+            include_hex!("./test_data/bitcoin/find_transaction_missed_previous_latest_block/block3.hex"),
+        ],
+    );
+
+    let block1: bitcoin::Block = include_hex!(
+        "./test_data/bitcoin/find_transaction_missed_previous_latest_block/block1.hex"
+    );
+    let start_of_swap = NaiveDateTime::from_timestamp((block1.header.time as i64) + 1, 0);
+    let address = Address::from_str(
+        include_str!("test_data/bitcoin/find_transaction_missed_previous_latest_block/address")
+            .trim(),
+    )
+    .unwrap();
+
+    // Both watchers would compete for the latest blocks of the mock if each of
+    // them polled the connector on its own.
+    let (first, second) = futures::future::join(
+        watch_for_created_outpoint(&connector, start_of_swap, address.clone()),
+        watch_for_created_outpoint(&connector, start_of_swap, address),
+    )
+    .await;
+
+    let expected_transaction: bitcoin::Transaction = include_hex!(
+        "./test_data/bitcoin/find_transaction_missed_previous_latest_block/transaction.hex"
+    );
+    assert_eq!(first.unwrap().0, expected_transaction);
+    assert_eq!(second.unwrap().0, expected_transaction);
+
+    // Both latest blocks were fetched once and the block in between was looked
+    // up once for both watchers.
+    assert_eq!(connector.latest_block_calls(), 2);
+    assert_eq!(connector.block_by_hash_calls(), 1);
+}
@@ -0,0 +1,97 @@
+pub mod bitcoin_helper;
+
+use bitcoin::Address;
+use bitcoin_helper::BitcoinConnectorMock;
+use chrono::NaiveDateTime;
+use cnd::btsieve::bitcoin::{confirmations, watch_for_created_outpoint};
+use std::str::FromStr;
+
+fn connector() -> BitcoinConnectorMock {
+    BitcoinConnectorMock::new(
+        vec![
+            include_hex!("./test_data/bitcoin/find_transaction_missed_previous_latest_block/block1.hex"),
+            include_hex!(
+                "./test_data/bitcoin/find_transaction_missed_previous_latest_block/block2_with_transaction.hex"
+            ),
+            include_hex!("./test_data/bitcoin/find_transaction_missed_previous_latest_block/block3.hex"),
+        ],
+        vec![
+            include_hex!("./test_data/bitcoin/find_transaction_missed_previous_latest_block/block1.hex"),
+            include_hex!(
+                "./test_data/bitcoin/find_transaction_missed_previous_latest_block/block2_with_transaction.hex"
+            ),
+            include_hex!("./test_data/bitcoin/find_transaction_missed_previous_latest_block/block3.hex"),
+        ],
+    )
+}
+
+fn start_of_swap() -> NaiveDateTime {
+    let block1: bitcoin::Block = include_hex!(
+        "./test_data/bitcoin/find_transaction_missed_previous_latest_block/block1.hex"
+    );
+
+    NaiveDateTime::from_timestamp((block1.header.time as i64) + 1, 0)
+}
+
+fn address() -> Address {
+    Address::from_str(
+        include_str!("test_data/bitcoin/find_transaction_missed_previous_latest_block/address")
+            .trim(),
+    )
+    .unwrap()
+}
+
+#[tokio::test]
+async fn find_transaction_once_it_has_enough_confirmations() {
+    let connector = connector().with_min_confirmations(2);
+
+    let (transaction, _out_point) =
+        watch_for_created_outpoint(&connector, start_of_swap(), address())
+            .await
+            .unwrap();
+
+    assert_eq!(
+        transaction,
+        include_hex!(
+            "./test_data/bitcoin/find_transaction_missed_previous_latest_block/transaction.hex"
+        )
+    );
+}
+
+#[tokio::test]
+async fn do_not_find_transaction_without_enough_confirmations() {
+    // The transaction only ever gets two confirmations before the mock runs out
+    // of blocks.
+    let connector = connector().with_min_confirmations(3);
+
+    let result = watch_for_created_outpoint(&connector, start_of_swap(), address()).await;
+
+    assert!(result.is_err());
+}
+
+#[tokio::test]
+async fn count_confirmations_from_the_latest_block_of_the_block_stream() {
+    let connector = BitcoinConnectorMock::new(
+        vec![include_hex!(
+            "./test_data/bitcoin/find_transaction_missed_previous_latest_block/block3.hex"
+        )],
+        vec![
+            include_hex!("./test_data/bitcoin/find_transaction_missed_previous_latest_block/block1.hex"),
+            include_hex!(
+                "./test_data/bitcoin/find_transaction_missed_previous_latest_block/block2_with_transaction.hex"
+            ),
+            include_hex!("./test_data/bitcoin/find_transaction_missed_previous_latest_block/block3.hex"),
+        ],
+    );
+    let transaction: bitcoin::Transaction = include_hex!(
+        "./test_data/bitcoin/find_transaction_missed_previous_latest_block/transaction.hex"
+    );
+
+    let first = confirmations(&connector, &transaction).await.unwrap();
+    let second = confirmations(&connector, &transaction).await.unwrap();
+
+    assert_eq!(first, Some(2));
+    assert_eq!(second, Some(2));
+    // The latest block is only fetched once and then shared.
+    assert_eq!(connector.latest_block_calls(), 1);
+}
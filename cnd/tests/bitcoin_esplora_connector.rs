@@ -0,0 +1,125 @@
+pub mod bitcoin_helper;
+
+use bitcoin::{
+    blockdata::constants::genesis_block, consensus::serialize, Address, BitcoinHash, Block, Network,
+};
+use chrono::NaiveDateTime;
+use cnd::{
+    btsieve::{
+        bitcoin::{watch_for_created_outpoint, Cache, EsploraConnector},
+        BlockByHash, LatestBlock,
+    },
+    config::validation::FetchNetworkId,
+};
+use reqwest::Url;
+use std::{collections::HashMap, str::FromStr, sync::Arc};
+use warp::Filter;
+
+fn blocks() -> Vec<Block> {
+    vec![
+        include_hex!("./test_data/bitcoin/find_transaction_missed_previous_latest_block/block1.hex"),
+        include_hex!(
+            "./test_data/bitcoin/find_transaction_missed_previous_latest_block/block2_with_transaction.hex"
+        ),
+        include_hex!("./test_data/bitcoin/find_transaction_missed_previous_latest_block/block3.hex"),
+    ]
+}
+
+/// Serves the given blocks through the subset of the Esplora HTTP API that we
+/// are using. The last block is the tip of the chain.
+fn serve_esplora(blocks: Vec<Block>) -> Url {
+    let tip = blocks
+        .last()
+        .expect("at least one block")
+        .bitcoin_hash()
+        .to_string();
+    let genesis = genesis_block(Network::Regtest).bitcoin_hash().to_string();
+    let raw_blocks = Arc::new(
+        blocks
+            .iter()
+            .map(|block| (block.bitcoin_hash().to_string(), serialize(block)))
+            .collect::<HashMap<_, _>>(),
+    );
+
+    let tip_hash = warp::path!("blocks" / "tip" / "hash").map(move || tip.clone());
+    let block_height = warp::path!("block-height" / u32).and_then(move |height| {
+        let genesis = genesis.clone();
+        async move {
+            match height {
+                0 => Ok(genesis),
+                _ => Err(warp::reject::not_found()),
+            }
+        }
+    });
+    let raw_block = warp::path!("block" / String / "raw").and_then(move |hash: String| {
+        let raw_blocks = raw_blocks.clone();
+        async move {
+            raw_blocks
+                .get(&hash)
+                .cloned()
+                .ok_or_else(warp::reject::not_found)
+        }
+    });
+
+    let routes = warp::get().and(tip_hash.or(block_height).or(raw_block));
+    let (address, server) = warp::serve(routes).bind_ephemeral(([127, 0, 0, 1], 0));
+    tokio::spawn(server);
+
+    format!("http://{}", address).parse().unwrap()
+}
+
+#[tokio::test]
+async fn latest_block_is_the_tip_of_the_chain() {
+    let url = serve_esplora(blocks());
+    let connector = EsploraConnector::new(url);
+
+    let block = connector.latest_block().await.unwrap();
+
+    assert_eq!(block, blocks()[2]);
+}
+
+#[tokio::test]
+async fn fetches_block_by_hash() {
+    let url = serve_esplora(blocks());
+    let connector = EsploraConnector::new(url);
+
+    let block = connector
+        .block_by_hash(blocks()[1].bitcoin_hash())
+        .await
+        .unwrap();
+
+    assert_eq!(block, blocks()[1]);
+}
+
+#[tokio::test]
+async fn detects_network_from_genesis_block() {
+    let url = serve_esplora(blocks());
+    let connector = EsploraConnector::new(url);
+
+    let network = connector.network_id().await.unwrap();
+
+    assert_eq!(network, Network::Regtest);
+}
+
+#[tokio::test]
+async fn find_transaction_through_esplora() {
+    let url = serve_esplora(blocks());
+    let connector = Cache::new(EsploraConnector::new(url), 144, 1);
+    let start_of_swap = NaiveDateTime::from_timestamp((blocks()[0].header.time as i64) + 1, 0);
+    let address = Address::from_str(
+        include_str!("test_data/bitcoin/find_transaction_missed_previous_latest_block/address")
+            .trim(),
+    )
+    .unwrap();
+
+    let (transaction, _out_point) = watch_for_created_outpoint(&connector, start_of_swap, address)
+        .await
+        .unwrap();
+
+    assert_eq!(
+        transaction,
+        include_hex!(
+            "./test_data/bitcoin/find_transaction_missed_previous_latest_block/transaction.hex"
+        )
+    );
+}
@@ -1,30 +1,66 @@
 use anyhow::Context;
 use async_trait::async_trait;
 use bitcoin::{util::hash::BitcoinHash, BlockHash};
-use cnd::btsieve::{BlockByHash, LatestBlock};
+use cnd::btsieve::{
+    BlockByHash, BlockStream, LatestBlock, LatestBlocks, RelevantBlocks, Sieve, StartOfScan,
+    SubscribeLatestBlocks,
+};
 use futures::{stream::BoxStream, StreamExt};
-use std::{collections::HashMap, time::Duration};
+use std::{
+    collections::HashMap,
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc,
+    },
+    time::Duration,
+};
 use tokio::{stream, sync::Mutex, time::throttle};
 
+#[derive(Clone)]
 pub struct BitcoinConnectorMock {
-    all_blocks: HashMap<BlockHash, bitcoin::Block>,
-    latest_blocks: Mutex<BoxStream<'static, bitcoin::Block>>,
+    all_blocks: Arc<HashMap<BlockHash, bitcoin::Block>>,
+    latest_blocks: Arc<Mutex<BoxStream<'static, bitcoin::Block>>>,
+    block_stream: BlockStream<bitcoin::Block>,
+    latest_block_calls: Arc<AtomicUsize>,
+    block_by_hash_calls: Arc<AtomicUsize>,
 }
 
 impl BitcoinConnectorMock {
     pub fn new(latest_blocks: Vec<bitcoin::Block>, all_blocks: Vec<bitcoin::Block>) -> Self {
         BitcoinConnectorMock {
-            all_blocks: all_blocks
-                .into_iter()
-                .fold(HashMap::new(), |mut hm, block| {
-                    hm.insert(block.bitcoin_hash(), block);
-                    hm
-                }),
-            latest_blocks: Mutex::new(
-                throttle(Duration::from_secs(1), stream::iter(latest_blocks)).boxed(),
+            all_blocks: Arc::new(
+                all_blocks
+                    .into_iter()
+                    .fold(HashMap::new(), |mut hm, block| {
+                        hm.insert(block.bitcoin_hash(), block);
+                        hm
+                    }),
             ),
+            latest_blocks: Arc::new(Mutex::new(
+                throttle(Duration::from_secs(1), stream::iter(latest_blocks)).boxed(),
+            )),
+            block_stream: BlockStream::default(),
+            latest_block_calls: Arc::new(AtomicUsize::new(0)),
+            block_by_hash_calls: Arc::new(AtomicUsize::new(0)),
+        }
+    }
+
+    pub fn with_min_confirmations(self, min_confirmations: u32) -> Self {
+        Self {
+            block_stream: BlockStream::new(min_confirmations),
+            ..self
         }
     }
+
+    /// How often `latest_block` was called so far.
+    pub fn latest_block_calls(&self) -> usize {
+        self.latest_block_calls.load(Ordering::SeqCst)
+    }
+
+    /// How often `block_by_hash` was called so far.
+    pub fn block_by_hash_calls(&self) -> usize {
+        self.block_by_hash_calls.load(Ordering::SeqCst)
+    }
 }
 
 #[derive(Debug, thiserror::Error)]
@@ -36,6 +72,8 @@ impl LatestBlock for BitcoinConnectorMock {
     type Block = bitcoin::Block;
 
     async fn latest_block(&self) -> anyhow::Result<Self::Block> {
+        self.latest_block_calls.fetch_add(1, Ordering::SeqCst);
+
         let block = self
             .latest_blocks
             .lock()
@@ -48,12 +86,30 @@ impl LatestBlock for BitcoinConnectorMock {
     }
 }
 
+impl SubscribeLatestBlocks for BitcoinConnectorMock {
+    type Block = bitcoin::Block;
+
+    fn subscribe_latest_blocks(&self) -> LatestBlocks<Self::Block> {
+        self.block_stream.subscribe(self)
+    }
+
+    fn subscribe_relevant_blocks(
+        &self,
+        start_of_scan: StartOfScan,
+        sieve: Sieve<Self::Block>,
+    ) -> RelevantBlocks<Self::Block> {
+        self.block_stream.watch(self, start_of_scan, sieve)
+    }
+}
+
 #[async_trait]
 impl BlockByHash for BitcoinConnectorMock {
     type Block = bitcoin::Block;
     type BlockHash = bitcoin::BlockHash;
 
     async fn block_by_hash(&self, block_hash: Self::BlockHash) -> anyhow::Result<Self::Block> {
+        self.block_by_hash_calls.fetch_add(1, Ordering::SeqCst);
+
         self.all_blocks
             .get(&block_hash)
             .cloned()
@@ -0,0 +1,17 @@
+use bitcoin::{util::hash::BitcoinHash, Block, BlockHeader, Transaction};
+
+/// Creates a block on top of the given one, allowing tests to build a chain
+/// that competes with the blocks we have test data for.
+///
+/// Blocks created this way are not valid as far as proof of work goes, which
+/// does not matter to the connector mock.
+pub fn block_on_top_of(parent: &Block, txdata: Vec<Transaction>) -> Block {
+    Block {
+        header: BlockHeader {
+            prev_blockhash: parent.bitcoin_hash(),
+            time: parent.header.time + 1,
+            ..parent.header
+        },
+        txdata,
+    }
+}
@@ -0,0 +1,79 @@
+pub mod bitcoin_helper;
+
+use bitcoin::Address;
+use bitcoin_helper::{block_on_top_of, BitcoinConnectorMock};
+use chrono::offset::Utc;
+use cnd::btsieve::{
+    bitcoin::{wait_for_finality, watch_for_created_outpoint},
+    BlockStatus,
+};
+use std::str::FromStr;
+
+#[tokio::test]
+async fn find_transaction_again_after_its_block_was_orphaned() {
+    let block1: bitcoin::Block = include_hex!(
+        "./test_data/bitcoin/find_transaction_if_blockchain_reorganisation/block1.hex"
+    );
+    let block2_with_transaction: bitcoin::Block = include_hex!(
+        "./test_data/bitcoin/find_transaction_if_blockchain_reorganisation/block2_with_transaction.hex"
+    );
+    let block1b_stale: bitcoin::Block = include_hex!(
+        "./test_data/bitcoin/find_transaction_if_blockchain_reorganisation/block1b_stale.hex"
+    );
+    let transaction: bitcoin::Transaction = include_hex!(
+        "./test_data/bitcoin/find_transaction_if_blockchain_reorganisation/transaction.hex"
+    );
+
+    // The competing chain includes the transaction one block later.
+    let block2b_with_transaction = block_on_top_of(&block1b_stale, vec![transaction.clone()]);
+    let block3b = block_on_top_of(&block2b_with_transaction, vec![]);
+
+    let blocks = vec![
+        block1,
+        block2_with_transaction,
+        block1b_stale,
+        block2b_with_transaction,
+        block3b,
+    ];
+    let connector = BitcoinConnectorMock::new(blocks.clone(), blocks).with_min_confirmations(2);
+
+    let start_of_swap = Utc::now().naive_local();
+    let (found_transaction, _out_point) = watch_for_created_outpoint(
+        &connector,
+        start_of_swap,
+        Address::from_str(
+            include_str!("test_data/bitcoin/find_transaction_if_blockchain_reorganisation/address")
+                .trim(),
+        )
+        .unwrap(),
+    )
+    .await
+    .unwrap();
+
+    assert_eq!(found_transaction, transaction);
+}
+
+#[tokio::test]
+async fn report_transaction_as_reverted_if_its_block_is_orphaned() {
+    let block1: bitcoin::Block = include_hex!(
+        "./test_data/bitcoin/find_transaction_if_blockchain_reorganisation/block1.hex"
+    );
+    let block2_with_transaction: bitcoin::Block = include_hex!(
+        "./test_data/bitcoin/find_transaction_if_blockchain_reorganisation/block2_with_transaction.hex"
+    );
+    let block1b_stale: bitcoin::Block = include_hex!(
+        "./test_data/bitcoin/find_transaction_if_blockchain_reorganisation/block1b_stale.hex"
+    );
+    let transaction: bitcoin::Transaction = include_hex!(
+        "./test_data/bitcoin/find_transaction_if_blockchain_reorganisation/transaction.hex"
+    );
+
+    let connector = BitcoinConnectorMock::new(
+        vec![block2_with_transaction.clone(), block1b_stale.clone()],
+        vec![block1, block2_with_transaction, block1b_stale],
+    );
+
+    let status = wait_for_finality(&connector, &transaction).await.unwrap();
+
+    assert_eq!(status, BlockStatus::Reverted);
+}
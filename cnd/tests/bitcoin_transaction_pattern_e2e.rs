@@ -1,7 +1,7 @@
 use bitcoin::{Amount, Network};
 use bitcoincore_rpc::RpcApi;
 use chrono::offset::Utc;
-use cnd::btsieve::bitcoin::{watch_for_created_outpoint, BitcoindConnector};
+use cnd::btsieve::bitcoin::{watch_for_created_outpoint, BitcoindConnector, Cache};
 use images::coblox_bitcoincore::BitcoinCore;
 use reqwest::Url;
 use std::time::Duration;
@@ -23,7 +23,11 @@ async fn bitcoin_transaction_pattern_e2e_test() {
     url.set_port(Some(container.get_host_port(18443).unwrap() as u16))
         .unwrap();
 
-    let connector = BitcoindConnector::new(url, Network::Regtest).unwrap();
+    let connector = Cache::new(
+        BitcoindConnector::new(url, Network::Regtest).unwrap(),
+        100,
+        1,
+    );
 
     let target_address = client.get_new_address(None, None).unwrap();
 
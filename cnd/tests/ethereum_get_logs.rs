@@ -0,0 +1,110 @@
+pub mod ethereum_helper;
+
+use chrono::NaiveDateTime;
+use cnd::{
+    btsieve::{
+        ethereum::{watch_for_event, Event, Topic},
+        StartOfSwap,
+    },
+    ethereum::{Address, Block, Bytes, Hash, Log, Transaction},
+};
+use ethereum_helper::EthereumConnectorMock;
+
+fn htlc_address() -> Address {
+    "49a1897a4b65ab6e87fa4fbbf7bf148ffbcccdae".parse().unwrap()
+}
+
+fn redeem_topic() -> Hash {
+    "b8cac300e37f03ad332e581dea21b2f0b84eaaadc184a295fef71e81f44a7413"
+        .parse()
+        .unwrap()
+}
+
+#[tokio::test]
+async fn find_transaction_through_its_log() {
+    let want_transaction: Transaction = include_json_test_data!(
+        "./test_data/ethereum/find_transaction_missed_previous_latest_block/transaction.json"
+    );
+    let block1: Block = include_json_test_data!(
+        "./test_data/ethereum/find_transaction_missed_previous_latest_block/block1.json"
+    );
+    let block2_with_transaction: Block = include_json_test_data!(
+        "./test_data/ethereum/find_transaction_missed_previous_latest_block/block2_with_transaction.json"
+    );
+    let block3: Block = include_json_test_data!(
+        "./test_data/ethereum/find_transaction_missed_previous_latest_block/block3.json"
+    );
+
+    let want_log = Log {
+        address: htlc_address(),
+        topics: vec![redeem_topic()],
+        data: Bytes(vec![]),
+        block_hash: block2_with_transaction.hash,
+        transaction_hash: Some(want_transaction.hash),
+    };
+    let other_contract_log = Log {
+        address: Address::from([1u8; 20]),
+        ..want_log.clone()
+    };
+
+    let start_of_swap = NaiveDateTime::from_timestamp(block1.timestamp.as_u32() as i64 + 1, 0);
+    let latest_blocks = vec![block1, block2_with_transaction, block3];
+    let connector = EthereumConnectorMock::new(latest_blocks.clone(), latest_blocks, vec![])
+        .with_logs(vec![other_contract_log, want_log.clone()]);
+
+    let event = Event {
+        address: htlc_address(),
+        topics: vec![Some(Topic(redeem_topic()))],
+    };
+    let (transaction, log) = watch_for_event(&connector, start_of_swap, event)
+        .await
+        .unwrap();
+
+    assert_eq!(transaction, want_transaction);
+    assert_eq!(log, want_log);
+}
+
+#[tokio::test]
+async fn start_querying_logs_at_the_recorded_start_height() {
+    let want_transaction: Transaction = include_json_test_data!(
+        "./test_data/ethereum/find_transaction_missed_previous_latest_block/transaction.json"
+    );
+    let block1: Block = include_json_test_data!(
+        "./test_data/ethereum/find_transaction_missed_previous_latest_block/block1.json"
+    );
+    let block2_with_transaction: Block = include_json_test_data!(
+        "./test_data/ethereum/find_transaction_missed_previous_latest_block/block2_with_transaction.json"
+    );
+    let block3: Block = include_json_test_data!(
+        "./test_data/ethereum/find_transaction_missed_previous_latest_block/block3.json"
+    );
+
+    let want_log = Log {
+        address: htlc_address(),
+        topics: vec![redeem_topic()],
+        data: Bytes(vec![]),
+        block_hash: block2_with_transaction.hash,
+        transaction_hash: Some(want_transaction.hash),
+    };
+
+    // The timestamp predates all blocks the connector knows, walking back to it
+    // would fail.
+    let start_of_swap = StartOfSwap {
+        timestamp: NaiveDateTime::from_timestamp(0, 0),
+        height: block2_with_transaction.number.map(|number| number.as_u32()),
+    };
+    let latest_blocks = vec![block1, block2_with_transaction, block3];
+    let connector = EthereumConnectorMock::new(latest_blocks.clone(), latest_blocks, vec![])
+        .with_logs(vec![want_log.clone()]);
+
+    let event = Event {
+        address: htlc_address(),
+        topics: vec![Some(Topic(redeem_topic()))],
+    };
+    let (transaction, log) = watch_for_event(&connector, start_of_swap, event)
+        .await
+        .unwrap();
+
+    assert_eq!(transaction, want_transaction);
+    assert_eq!(log, want_log);
+}
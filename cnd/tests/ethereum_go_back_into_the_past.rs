@@ -50,7 +50,8 @@ async fn find_transaction_go_back_into_the_past() {
 
     let (got_transaction, got_receipt) =
         matching_transaction_and_receipt(&connector, start_of_swap, {
-            |transaction| transaction.to == want_transaction.to
+            let to = want_transaction.to;
+            move |transaction| transaction.to == to
         })
         .await
         .expect("failed to get the transaction and receipt");
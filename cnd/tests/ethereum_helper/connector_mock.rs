@@ -1,17 +1,24 @@
 use anyhow::Context;
 use async_trait::async_trait;
 use cnd::{
-    btsieve::{ethereum::ReceiptByHash, BlockByHash, LatestBlock},
-    ethereum::{Block, Hash, TransactionReceipt},
+    btsieve::{
+        ethereum::{Event, GetLogs, ReceiptByHash},
+        BlockByHash, BlockStream, LatestBlock, LatestBlocks, RelevantBlocks, Sieve, StartOfScan,
+        SubscribeLatestBlocks,
+    },
+    ethereum::{Block, Hash, Log, TransactionReceipt, U256},
 };
 use futures::{stream::BoxStream, StreamExt};
-use std::{collections::HashMap, time::Duration};
+use std::{collections::HashMap, sync::Arc, time::Duration};
 use tokio::{stream, sync::Mutex, time::throttle};
 
+#[derive(Clone)]
 pub struct EthereumConnectorMock {
-    all_blocks: HashMap<Hash, Block>,
-    receipts: HashMap<Hash, TransactionReceipt>,
-    latest_blocks: Mutex<BoxStream<'static, Block>>,
+    all_blocks: Arc<HashMap<Hash, Block>>,
+    receipts: Arc<HashMap<Hash, TransactionReceipt>>,
+    logs: Arc<Vec<Log>>,
+    latest_blocks: Arc<Mutex<BoxStream<'static, Block>>>,
+    block_stream: BlockStream<Block>,
 }
 
 impl EthereumConnectorMock {
@@ -28,11 +35,29 @@ impl EthereumConnectorMock {
             });
 
         EthereumConnectorMock {
-            all_blocks,
-            receipts: receipts.into_iter().collect(),
-            latest_blocks: Mutex::new(
+            all_blocks: Arc::new(all_blocks),
+            receipts: Arc::new(receipts.into_iter().collect()),
+            logs: Arc::new(Vec::new()),
+            latest_blocks: Arc::new(Mutex::new(
                 throttle(Duration::from_secs(1), stream::iter(latest_blocks)).boxed(),
-            ),
+            )),
+            block_stream: BlockStream::default(),
+        }
+    }
+
+    /// The logs returned by `get_logs`, each must reference one of the blocks
+    /// of this blockchain.
+    pub fn with_logs(self, logs: Vec<Log>) -> Self {
+        Self {
+            logs: Arc::new(logs),
+            ..self
+        }
+    }
+
+    pub fn with_min_confirmations(self, min_confirmations: u32) -> Self {
+        Self {
+            block_stream: BlockStream::new(min_confirmations),
+            ..self
         }
     }
 }
@@ -58,6 +83,22 @@ impl LatestBlock for EthereumConnectorMock {
     }
 }
 
+impl SubscribeLatestBlocks for EthereumConnectorMock {
+    type Block = Block;
+
+    fn subscribe_latest_blocks(&self) -> LatestBlocks<Self::Block> {
+        self.block_stream.subscribe(self)
+    }
+
+    fn subscribe_relevant_blocks(
+        &self,
+        start_of_scan: StartOfScan,
+        sieve: Sieve<Self::Block>,
+    ) -> RelevantBlocks<Self::Block> {
+        self.block_stream.watch(self, start_of_scan, sieve)
+    }
+}
+
 #[async_trait]
 impl BlockByHash for EthereumConnectorMock {
     type Block = Block;
@@ -80,3 +121,28 @@ impl ReceiptByHash for EthereumConnectorMock {
             .with_context(|| format!("could not find block with hash {}", transaction_hash))
     }
 }
+
+#[async_trait]
+impl GetLogs for EthereumConnectorMock {
+    async fn get_logs(
+        &self,
+        event: &Event,
+        from_block: U256,
+        to_block: U256,
+    ) -> anyhow::Result<Vec<Log>> {
+        let logs = self
+            .logs
+            .iter()
+            .filter(|log| log.address == event.address)
+            .filter(|log| {
+                log.block_hash
+                    .and_then(|block_hash| self.all_blocks.get(&block_hash))
+                    .and_then(|block| block.number)
+                    .map_or(false, |number| from_block <= number && number <= to_block)
+            })
+            .cloned()
+            .collect();
+
+        Ok(logs)
+    }
+}
@@ -0,0 +1,21 @@
+use cnd::ethereum::{Block, Hash, Transaction};
+
+/// Creates a block on top of the given one, allowing tests to build a chain
+/// that competes with the blocks we have test data for.
+pub fn block_on_top_of(parent: &Block, transactions: Vec<Transaction>) -> Block {
+    let parent_hash = parent.hash.expect("test blocks have a hash");
+
+    // Any hash that is different from the ones in the test data will do.
+    let mut hash: [u8; 32] = parent_hash.into();
+    hash[0] ^= 0xff;
+    hash[31] = hash[31].wrapping_add(1);
+
+    Block {
+        hash: Some(Hash::from(hash)),
+        number: parent.number.map(|number| number + 1),
+        parent_hash,
+        timestamp: parent.timestamp + 1,
+        transactions,
+        ..parent.clone()
+    }
+}
@@ -47,7 +47,8 @@ async fn find_transaction_missed_previous_latest_block_single_block_gap() {
 
     let (got_transaction, got_receipt) =
         matching_transaction_and_receipt(&connector, start_of_swap, {
-            |transaction| transaction.to == want_transaction.to
+            let to = want_transaction.to;
+            move |transaction| transaction.to == to
         })
         .await
         .expect("failed to get the transaction and receipt");
@@ -101,7 +102,8 @@ async fn find_transaction_missed_previous_latest_block_two_block_gap() {
 
     let (got_transaction, got_receipt) =
         matching_transaction_and_receipt(&connector, start_of_swap, {
-            |transaction| transaction.to == want_transaction.to
+            let to = want_transaction.to;
+            move |transaction| transaction.to == to
         })
         .await
         .expect("failed to get the transaction and receipt");
@@ -0,0 +1,91 @@
+pub mod ethereum_helper;
+
+use chrono::NaiveDateTime;
+use cnd::{
+    btsieve::{
+        ethereum::{matching_transaction_and_receipt, wait_for_finality},
+        BlockStatus,
+    },
+    ethereum::{Block, Transaction, TransactionReceipt},
+};
+use ethereum_helper::{block_on_top_of, EthereumConnectorMock};
+
+#[tokio::test]
+async fn find_transaction_again_after_its_block_was_orphaned() {
+    let want_transaction: Transaction = include_json_test_data!(
+        "./test_data/ethereum/find_transaction_missed_previous_latest_block/transaction.json"
+    );
+    let want_receipt: TransactionReceipt = include_json_test_data!(
+        "./test_data/ethereum/find_transaction_missed_previous_latest_block/receipt.json"
+    );
+    let block1: Block = include_json_test_data!(
+        "./test_data/ethereum/find_transaction_missed_previous_latest_block/block1.json"
+    );
+    let block2: Block = include_json_test_data!(
+        "./test_data/ethereum/find_transaction_missed_previous_latest_block/block2.json"
+    );
+    let block3_with_transaction: Block = include_json_test_data!(
+        "./test_data/ethereum/find_transaction_missed_previous_latest_block/block3_with_transaction.json"
+    );
+
+    // The competing chain includes the transaction one block later.
+    let block3b = block_on_top_of(&block2, vec![]);
+    let block4b_with_transaction = block_on_top_of(&block3b, vec![want_transaction.clone()]);
+    let block5b = block_on_top_of(&block4b_with_transaction, vec![]);
+
+    let start_of_swap = NaiveDateTime::from_timestamp(block2.timestamp.as_u32() as i64, 0);
+    let latest_blocks = vec![
+        block2,
+        block3_with_transaction,
+        block3b,
+        block4b_with_transaction,
+        block5b,
+    ];
+    let all_blocks = latest_blocks
+        .iter()
+        .cloned()
+        .chain(std::iter::once(block1))
+        .collect();
+    let connector = EthereumConnectorMock::new(latest_blocks, all_blocks, vec![(
+        want_transaction.hash,
+        want_receipt.clone(),
+    )])
+    .with_min_confirmations(2);
+
+    let (got_transaction, got_receipt) =
+        matching_transaction_and_receipt(&connector, start_of_swap, {
+            let to = want_transaction.to;
+            move |transaction| transaction.to == to
+        })
+        .await
+        .expect("failed to get the transaction and receipt");
+
+    assert_eq!(
+        (got_transaction, got_receipt),
+        (want_transaction, want_receipt)
+    );
+}
+
+#[tokio::test]
+async fn report_transaction_as_reverted_if_its_block_is_orphaned() {
+    let transaction: Transaction = include_json_test_data!(
+        "./test_data/ethereum/find_transaction_missed_previous_latest_block/transaction.json"
+    );
+    let block2: Block = include_json_test_data!(
+        "./test_data/ethereum/find_transaction_missed_previous_latest_block/block2.json"
+    );
+    let block3_with_transaction: Block = include_json_test_data!(
+        "./test_data/ethereum/find_transaction_missed_previous_latest_block/block3_with_transaction.json"
+    );
+    let block3b = block_on_top_of(&block2, vec![]);
+
+    let connector = EthereumConnectorMock::new(
+        vec![block3_with_transaction.clone(), block3b.clone()],
+        vec![block2, block3_with_transaction, block3b],
+        vec![],
+    );
+
+    let status = wait_for_finality(&connector, &transaction).await.unwrap();
+
+    assert_eq!(status, BlockStatus::Reverted);
+}
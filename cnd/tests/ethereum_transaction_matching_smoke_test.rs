@@ -1,6 +1,6 @@
 use chrono::offset::Utc;
 use cnd::{
-    btsieve::ethereum::{matching_transaction_and_receipt, Web3Connector},
+    btsieve::ethereum::{matching_transaction_and_receipt, Cache, Web3Connector},
     ethereum::{Address, U256},
     jsonrpc,
 };
@@ -23,7 +23,7 @@ async fn ethereum_transaction_matching_smoke_test() {
     let url = connection_url(&container).unwrap();
 
     let client = jsonrpc::Client::new(url.clone());
-    let connector = Web3Connector::new(url);
+    let connector = Cache::new(Web3Connector::new(url), 100, 100, 1);
 
     let accounts: Vec<Address> = client
         .send(jsonrpc::Request::new("eth_accounts", Vec::<u32>::new()))
@@ -55,7 +55,7 @@ async fn ethereum_transaction_matching_smoke_test() {
 
     let (matched_transaction, _receipt) = tokio::time::timeout(
         Duration::from_secs(5),
-        matching_transaction_and_receipt(&connector, start_of_swap, |transaction| {
+        matching_transaction_and_receipt(&connector, start_of_swap, move |transaction| {
             transaction.to == Some(target_address)
         }),
     )